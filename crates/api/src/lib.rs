@@ -20,30 +20,57 @@
 
 // Re-export the main Workbook and Worksheet types from controller/api
 pub use logisheets_controller::api::{
-    BlockSortOrder, CellInfo, CellRefRange, CfRuleInfo, DependentCell, FillRange, ReproducibleCell,
-    SaveFileResult, SheetCoordinate, SheetDimension, Workbook, Worksheet,
+    BlockSortOrder, CellInfo, CellRefRange, CellStyleInfo, CfRuleInfo, DataTableInfo,
+    DependentCell, FillRange, GoalSeekResult, ReproducibleCell, SaveFileResult, ScenarioInfo,
+    SheetCoordinate, SheetDimension, ThemeInfo, Workbook, Worksheet,
+};
+
+// Re-export the fill options (fill-handle types and the Series dialog)
+pub use logisheets_controller::api::{DateUnit, FillType, SeriesIn, SeriesOptions, SeriesType};
+
+// Re-export the clipboard (copy, cut and paste-special, TSV and HTML)
+pub use logisheets_controller::api::{
+    ClipboardCell, ClipboardContents, ClipboardData, PasteContent, PasteOperation, PasteOptions,
+};
+
+// Re-export find and replace
+pub use logisheets_controller::api::{
+    FindFormat, FindLookIn, FindMode, FindOptions, FindOrder, FindRange, FindScope, FoundCell,
+    ReplacePlan, ReplaceResult,
+};
+
+// Re-export the input locale (how typed cell input is read)
+pub use logisheets_controller::{DateOrder, InputLocale, InputLocaleInfo};
+
+// Re-export the Excel-compatibility check (recalculate and diff against the
+// values Excel cached in the file)
+pub use logisheets_controller::api::{
+    CompatMismatch, CompatOptions, CompatReport, check_excel_compat,
 };
 
 // Re-export display types
 pub use logisheets_controller::controller::display::{
     BlockCellInfo, BlockDataRow, BlockDisplayInfo, BlockField, BlockInfo, BlockSchema,
-    BlockSchemaRandomEntry, BlockSchemaType, CellCoordinate, CellCoordinateWithSheet,
-    CellImageInfo, CellPosition, ChartInfo, ChartSeriesInfo, ColInfo, DisplayWindow,
-    DisplayWindowRequest, DisplayWindowWithStartPoint, LinkInfo, RowInfo, ShadowCellInfo,
-    SheetInfo, TempCellChange, TempStatusDiff,
+    BlockSchemaRandomEntry, BlockSchemaType, BranchInfo, CellChange, CellCoordinate,
+    CellCoordinateWithSheet, CellImageInfo, CellPosition, ChangeEntry, ChartInfo, ChartSeriesInfo,
+    ColInfo, DisplayWindow, DisplayWindowRequest, DisplayWindowWithStartPoint, LinkInfo,
+    MergeConflict, MergeResult, RowInfo, ShadowCellInfo, SheetInfo, TempCellChange, TempStatusDiff,
 };
 
 // Re-export edit actions
 pub use logisheets_controller::edit_action::{
-    ActionEffect, Alignment, AsyncFuncResult, BindFormSchema, BindRandomSchema, BlockInput,
-    BlockLineNameFieldUpdate, BlockLineStyleUpdate, CellClear, CellFormatBrush, CellInput,
-    CellStyleUpdate, CreateAppendix, CreateBlock, CreateDiyCell, CreateSheet, DeleteCellImage,
-    DeleteCols, DeleteColsInBlock, DeleteRows, DeleteRowsInBlock, DeleteSheet, EditAction,
-    EditPayload, EphemeralCellInput, HorizontalAlignment, InsertCols, InsertColsInBlock,
-    InsertRows, InsertRowsInBlock, LineFormatBrush, LineStyleUpdate, MergeCells, MoveBlock,
-    PayloadsAction, RemoveBlock, ReproduceCells, ResizeBlock, SetCellImage, SetColWidth,
-    SetRowHeight, SetSheetColor, SetSheetVisible, SheetCellId, SheetRename, SplitMergedCells,
-    StatusCode, StyleUpdateType, UpsertFieldRenderInfo, VerticalAlignment,
+    ActionEffect, Alignment, ApplyBuiltinTheme, ApplyCellStyle, AsyncFuncResult, AuthorInput,
+    BindFormSchema, BindRandomSchema, BlockInput, BlockLineNameFieldUpdate, BlockLineStyleUpdate,
+    CellClear, CellFormatBrush, CellInput, CellStyleUpdate, CreateAppendix, CreateBlock,
+    CreateCellStyle, CreateDiyCell, CreateSheet, DeleteCellImage, DeleteCellStyle, DeleteCells,
+    DeleteCols, DeleteColsInBlock, DeleteRows, DeleteRowsInBlock, DeleteSheet, DuplicateSheet,
+    EditAction, EditPayload, EphemeralCellInput, FieldKind, FieldType, HorizontalAlignment,
+    ImportBlockRecords, InsertCells, InsertCols, InsertColsInBlock, InsertRows, InsertRowsInBlock,
+    LineFormatBrush, LineStyleUpdate, MergeCells, ModifyCellStyle, MoveBlock, MoveRange, MoveSheet,
+    PayloadsAction, RecordFormat, RemoveBlock, ReproduceCells, ResizeBlock, SetCellImage,
+    SetCellStyles, SetColWidth, SetRowHeight, SetSheetColor, SetSheetVisible, SetTheme,
+    SetThemeColor, SetThemeFonts, SheetCellId, SheetRename, SplitMergedCells, StatusCode,
+    StyleUpdateType, ThemeColorSlot, UpsertFieldRenderInfo, VerticalAlignment,
 };
 
 // Re-export style types
@@ -65,6 +92,13 @@ pub use logisheets_controller::{Appendix, AppendixWithCell, Comment, MergeCell,
 
 // Re-export FormulaDisplayInfo and lex functions
 pub use logisheets_controller::{FormulaDisplayInfo, lex_and_fmt, lex_success};
+pub use logisheets_controller::{FormulaToken, FormulaTokenKind, TokenReference, tokenize};
+
+// Re-export the function registry and formula check types
+pub use logisheets_controller::{
+    ArgCount, ArgType, FormulaCheckResult, FormulaDiagnostic, FormulaDiagnosticKind, FunctionArg,
+    FunctionCategory, FunctionSignature, get_function_signature,
+};
 
 // Re-export async calculation types
 pub use logisheets_base::async_func::{AsyncCalcResult, AsyncErr, Task};
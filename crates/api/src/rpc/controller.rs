@@ -1,8 +1,11 @@
+use crate::ReplacePlan;
 use crate::{
     ActionEffect, AppData, BasicError, BlockDataRow, BlockField, BlockId, BlockSortOrder,
-    CellCoordinateWithSheet, CellInfo, ColId, DisplayWindow, EditAction, Error, ErrorMessage,
-    FormulaDisplayInfo, PayloadsAction, RowId, RowInfo, SaveFileResult, ShadowCellInfo,
-    SheetCellId, SheetId, SheetInfo, TempStatusDiff, Workbook, lex_and_fmt, lex_success,
+    CellCoordinateWithSheet, CellInfo, CellStyleInfo, ColId, DisplayWindow, EditAction, Error,
+    ErrorMessage, FindOptions, FormulaCheckResult, FormulaDisplayInfo, FormulaToken, FoundCell,
+    FunctionSignature, InputLocale, InputLocaleInfo, PayloadsAction, RowId, RowInfo,
+    SaveFileResult, ShadowCellInfo, SheetCellId, SheetId, SheetInfo, TempStatusDiff, ThemeInfo,
+    Workbook, get_function_signature, lex_and_fmt, lex_success, tokenize,
 };
 
 use super::{Manager, Transaction};
@@ -109,6 +112,85 @@ pub fn toggle_status(mgr: &mut Manager, id: usize, use_temp: bool) {
     wb.toggle_status(use_temp);
 }
 
+pub fn get_custom_lists(mgr: &Manager, id: usize) -> Vec<Vec<String>> {
+    let wb = mgr.get_workbook(&id).unwrap();
+    wb.get_custom_lists().to_vec()
+}
+
+pub fn set_custom_lists(mgr: &mut Manager, id: usize, lists: Vec<Vec<String>>) {
+    let wb = mgr.get_mut_workbook(&id).unwrap();
+    wb.set_custom_lists(lists);
+}
+
+pub fn get_input_locale(mgr: &Manager, id: usize) -> InputLocaleInfo {
+    let wb = mgr.get_workbook(&id).unwrap();
+    InputLocaleInfo::from(wb.get_input_locale())
+}
+
+pub fn set_input_locale(
+    mgr: &mut Manager,
+    id: usize,
+    locale: InputLocaleInfo,
+) -> Result<(), ErrorMessage> {
+    let locale = InputLocale::try_from(locale).map_err(Error::PayloadError)?;
+    let wb = mgr.get_mut_workbook(&id).unwrap();
+    wb.set_input_locale(locale);
+    Ok(())
+}
+
+pub fn get_cell_styles(mgr: &Manager, id: usize) -> Vec<CellStyleInfo> {
+    let wb = mgr.get_workbook(&id).unwrap();
+    wb.get_cell_styles()
+}
+
+pub fn get_theme(mgr: &Manager, id: usize) -> Option<ThemeInfo> {
+    let wb = mgr.get_workbook(&id).unwrap();
+    wb.get_theme()
+}
+
+pub fn get_builtin_themes(mgr: &Manager, id: usize) -> Vec<ThemeInfo> {
+    let wb = mgr.get_workbook(&id).unwrap();
+    wb.get_builtin_themes()
+}
+
+pub fn find_all(
+    mgr: &Manager,
+    id: usize,
+    options: &FindOptions,
+) -> Result<Vec<FoundCell>, ErrorMessage> {
+    let wb = mgr.get_workbook(&id).unwrap();
+    wb.find_all(options).map_err(ErrorMessage::from)
+}
+
+/// The next match after `(row, col)` of sheet `sheet_idx`, wrapping around.
+pub fn find_next(
+    mgr: &Manager,
+    id: usize,
+    options: &FindOptions,
+    sheet_idx: usize,
+    row: usize,
+    col: usize,
+) -> Result<Option<FoundCell>, ErrorMessage> {
+    let wb = mgr.get_workbook(&id).unwrap();
+    wb.find_next(options, sheet_idx, row, col)
+        .map_err(ErrorMessage::from)
+}
+
+/// The payloads that replace the matches, or only the one in `cell`, and
+/// the computed matches they leave alone. The caller sends the payloads as
+/// one transaction.
+pub fn replace_payloads(
+    mgr: &Manager,
+    id: usize,
+    options: &FindOptions,
+    replacement: &str,
+    cell: Option<&FoundCell>,
+) -> Result<ReplacePlan, ErrorMessage> {
+    let wb = mgr.get_workbook(&id).unwrap();
+    wb.replace_payloads(options, replacement, cell)
+        .map_err(ErrorMessage::from)
+}
+
 pub fn batch_get_cell_info_by_id(
     mgr: &mut Manager,
     id: usize,
@@ -168,7 +250,7 @@ pub fn get_row_info(
     Ok(ws.get_row_info(row_idx).unwrap_or(RowInfo::default(row_idx)))
 }
 
-pub fn check_formula(mgr: &Manager, id: usize, f: String) -> bool {
+pub fn check_formula(mgr: &Manager, id: usize, f: String) -> FormulaCheckResult {
     let wb = mgr.get_workbook(&id).unwrap();
     wb.check_formula(f)
 }
@@ -398,3 +480,15 @@ pub fn get_display_units_of_formula(f: &str) -> Result<FormulaDisplayInfo, Error
     lex_and_fmt(f)
         .ok_or_else(|| ErrorMessage::from(Error::from(BasicError::InvalidFormula(f.to_string()))))
 }
+
+/// Classified tokens with byte spans. Unlike the display units, this works
+/// on formulas that do not parse yet, so it can highlight while typing.
+pub fn get_formula_tokens(f: &str) -> Vec<FormulaToken> {
+    tokenize(f)
+}
+
+pub fn get_function_signature_by_name(name: &str) -> Result<FunctionSignature, ErrorMessage> {
+    get_function_signature(name).ok_or_else(|| {
+        ErrorMessage::from(Error::from(BasicError::UnknownFunction(name.to_string())))
+    })
+}
@@ -1,14 +1,19 @@
 use gents_derives::{Interface, TS};
 
 use crate::BlockId;
+use crate::ReplacePlan;
 use crate::{
     ActionEffect, AppData, AppendixWithCell, BlockDataRow, BlockField, BlockInfo, BlockSortOrder,
     CellCoordinateWithSheet, CellImageInfo, CellInfo, CellInput, CellPosition, CellRefRange,
-    CfRuleInfo, ChartInfo, ColId, Comment, DependentCell, DisplayWindow,
-    DisplayWindowWithStartPoint, EditPayload, ErrorMessage, FormulaDisplayInfo, LinkInfo,
-    MergeCell, ReproducibleCell, RowId, RowInfo, SaveFileResult, ShadowCellInfo, SheetCellId,
-    SheetCoordinate, SheetDimension, SheetId, SheetInfo, Style, TempStatusDiff, Value,
+    CellStyleInfo, CfRuleInfo, ChartInfo, ClipboardContents, ClipboardData, ColId, Comment,
+    DependentCell, DisplayWindow, DisplayWindowWithStartPoint, EditPayload, ErrorMessage, FillType,
+    FindOptions, FormulaCheckResult, FormulaDisplayInfo, FormulaToken, FoundCell,
+    FunctionSignature, InputLocaleInfo, LinkInfo, MergeCell, PasteOptions, ReproducibleCell, RowId,
+    RowInfo, SaveFileResult, SeriesOptions, ShadowCellInfo, SheetCellId, SheetCoordinate,
+    SheetDimension, SheetId, SheetInfo, Style, TempStatusDiff, ThemeInfo, Value,
 };
+use crate::{AuthorInput, BranchInfo, CellChange, ChangeEntry, MergeResult};
+use crate::{DataTableInfo, GoalSeekResult, ScenarioInfo};
 
 // ============================================================================
 // Params structs - all derive TS for TypeScript generation
@@ -29,9 +34,26 @@ pub enum Message {
     GetValue(GetCellParams),
     GetFormula(GetCellParams),
     GetStyle(GetCellParams),
+    GetCellStyleName(GetCellParams),
     GetCells(GetCellsParams),
     GetCellsExceptWindow(GetCellsExceptWindowParams),
     PredictFill(PredictFillParams),
+    CopyRange(CopyRangeParams),
+    ReadClipboard(ReadClipboardParams),
+    PastePayloads(PastePayloadsParams),
+    PredictFillPayloads(PredictFillPayloadsParams),
+    FillSeries(FillSeriesParams),
+    GetCustomLists,
+    SetCustomLists(SetCustomListsParams),
+    GetInputLocale,
+    SetInputLocale(SetInputLocaleParams),
+    GetCellStyles,
+    GetTheme,
+    GetBuiltinThemes,
+    PredictFlashFill(PredictFlashFillParams),
+    FindAll(FindAllParams),
+    FindNext(FindNextParams),
+    ReplacePayloads(ReplacePayloadsParams),
     GetReproducibleCells(GetReproducibleCellsParams),
     GetReproducibleCell(GetReproducibleCellParams),
     GetCellPosition(GetCellPositionParams),
@@ -69,9 +91,11 @@ pub enum Message {
     GetNextVisibleCell(GetNextVisibleCellParams),
     GetDataBoundary(GetDataBoundaryParams),
     GetDisplayUnitsOfFormula(GetDisplayUnitsOfFormulaParams),
+    GetFormulaTokens(GetFormulaTokensParams),
     GetRowInfo(GetRowInfoParams),
     GetAvailableBlockId(GetAvailableBlockIdParams),
     CheckFormula(CheckFormulaParams),
+    GetFunctionSignature(GetFunctionSignatureParams),
 
     GetBlockInfo(GetBlockInfoParams),
     GetCellInfos(GetCellInfosParams),
@@ -103,6 +127,23 @@ pub enum Message {
     SaveCheckpoint(SaveCheckpointParams),
     DeleteCheckpoint(DeleteCheckpointParams),
     ListCheckpoints,
+
+    SetAudit(SetAuditParams),
+    SetAuditAuthor(SetAuditAuthorParams),
+    GetCellHistory(GetCellHistoryParams),
+    GetChangeFeed(GetChangeFeedParams),
+    ClearAuditLog,
+
+    ForkBranch(BranchParams),
+    SwitchBranch(BranchParams),
+    DeleteBranch(BranchParams),
+    ListBranches,
+    CompareBranches(CompareBranchesParams),
+    MergeBranch(MergeBranchParams),
+
+    GetDataTables(GetDataTablesParams),
+    GoalSeek(GoalSeekParams),
+    GetScenarios(GetScenariosParams),
 }
 
 #[derive(Debug, Clone, TS)]
@@ -251,6 +292,119 @@ pub struct PredictFillParams {
     pub dst_end_col: usize,
 }
 
+#[derive(Debug, Clone, TS)]
+#[ts(file_name = "rpc_copy_range_params.ts", rename_all = "camelCase")]
+pub struct CopyRangeParams {
+    pub sheet_idx: usize,
+    pub start_row: usize,
+    pub start_col: usize,
+    pub end_row: usize,
+    pub end_col: usize,
+    pub cut: bool,
+}
+
+#[derive(Debug, Clone, TS)]
+#[ts(file_name = "rpc_read_clipboard_params.ts", rename_all = "camelCase")]
+pub struct ReadClipboardParams {
+    pub sheet_idx: usize,
+    pub row: usize,
+    pub col: usize,
+    /// The `text/plain` item, tab-separated.
+    pub tsv: Option<String>,
+    /// The `text/html` item.
+    pub html: Option<String>,
+}
+
+#[derive(Debug, Clone, TS)]
+#[ts(file_name = "rpc_paste_payloads_params.ts", rename_all = "camelCase")]
+pub struct PastePayloadsParams {
+    pub data: ClipboardData,
+    pub sheet_idx: usize,
+    pub row: usize,
+    pub col: usize,
+    pub options: PasteOptions,
+}
+
+#[derive(Debug, Clone, TS)]
+#[ts(
+    file_name = "rpc_predict_fill_payloads_params.ts",
+    rename_all = "camelCase"
+)]
+pub struct PredictFillPayloadsParams {
+    pub sheet_idx: usize,
+    pub src_start_row: usize,
+    pub src_start_col: usize,
+    pub src_end_row: usize,
+    pub src_end_col: usize,
+    pub dst_start_row: usize,
+    pub dst_start_col: usize,
+    pub dst_end_row: usize,
+    pub dst_end_col: usize,
+    pub fill_type: FillType,
+}
+
+#[derive(Debug, Clone, TS)]
+#[ts(file_name = "rpc_fill_series_params.ts", rename_all = "camelCase")]
+pub struct FillSeriesParams {
+    pub sheet_idx: usize,
+    pub start_row: usize,
+    pub start_col: usize,
+    pub end_row: usize,
+    pub end_col: usize,
+    pub options: SeriesOptions,
+}
+
+#[derive(Debug, Clone, TS)]
+#[ts(file_name = "rpc_set_custom_lists_params.ts", rename_all = "camelCase")]
+pub struct SetCustomListsParams {
+    pub lists: Vec<Vec<String>>,
+}
+
+#[derive(Debug, Clone, TS)]
+#[ts(file_name = "rpc_set_input_locale_params.ts", rename_all = "camelCase")]
+pub struct SetInputLocaleParams {
+    pub locale: InputLocaleInfo,
+}
+
+#[derive(Debug, Clone, TS)]
+#[ts(
+    file_name = "rpc_predict_flash_fill_params.ts",
+    rename_all = "camelCase"
+)]
+pub struct PredictFlashFillParams {
+    pub sheet_idx: usize,
+    pub start_row: usize,
+    pub start_col: usize,
+    pub end_row: usize,
+    pub end_col: usize,
+    pub target_col: usize,
+}
+
+#[derive(Debug, Clone, TS)]
+#[ts(file_name = "rpc_find_all_params.ts", rename_all = "camelCase")]
+pub struct FindAllParams {
+    pub options: FindOptions,
+}
+
+#[derive(Debug, Clone, TS)]
+#[ts(file_name = "rpc_find_next_params.ts", rename_all = "camelCase")]
+pub struct FindNextParams {
+    pub options: FindOptions,
+    /// The search starts after this cell.
+    pub sheet_idx: usize,
+    pub row: usize,
+    pub col: usize,
+}
+
+#[derive(Debug, Clone, TS)]
+#[ts(file_name = "rpc_replace_payloads_params.ts", rename_all = "camelCase")]
+pub struct ReplacePayloadsParams {
+    pub options: FindOptions,
+    pub replacement: String,
+    /// Replace only in this cell; every match when omitted.
+    pub cell: Option<FoundCell>,
+}
+
 #[derive(Debug, Clone, TS)]
 #[ts(
     file_name = "rpc_get_cells_except_window_params.ts",
@@ -610,6 +764,15 @@ pub struct GetDisplayUnitsOfFormulaParams {
     pub formula: String,
 }
 
+#[derive(Debug, Clone, TS)]
+#[ts(
+    file_name = "rpc_get_formula_tokens_params.ts",
+    rename_all = "camelCase"
+)]
+pub struct GetFormulaTokensParams {
+    pub formula: String,
+}
+
 #[derive(Debug, Clone, TS)]
 #[ts(file_name = "rpc_get_row_info_params.ts", rename_all = "camelCase")]
 pub struct GetRowInfoParams {
@@ -632,6 +795,15 @@ pub struct CheckFormulaParams {
     pub formula: String,
 }
 
+#[derive(Debug, Clone, TS)]
+#[ts(
+    file_name = "rpc_get_function_signature_params.ts",
+    rename_all = "camelCase"
+)]
+pub struct GetFunctionSignatureParams {
+    pub name: String,
+}
+
 #[derive(Debug, Clone, TS)]
 #[ts(file_name = "rpc_check_bind_block_params.ts", rename_all = "camelCase")]
 pub struct CheckBindBlockParams {
@@ -666,6 +838,11 @@ pub struct SaveCheckpointParams {
     /// Optional human-readable description, echoed back by
     /// `ListCheckpoints` for the UI/agent.
     pub description: Option<String>,
+    /// Host-supplied timestamp, stored as given.
+    pub created_at: Option<String>,
+    pub author: Option<String>,
+    /// Keep the checkpoint in the file when the workbook is saved.
+    pub persist: Option<bool>,
 }
 
 #[derive(Debug, Clone, TS)]
@@ -682,6 +859,9 @@ pub struct DeleteCheckpointParams {
 pub struct CheckpointMetaDto {
     pub label: String,
     pub description: Option<String>,
+    pub created_at: Option<String>,
+    pub author: Option<String>,
+    pub persist: bool,
 }
 
 impl From<crate::CheckpointMeta> for CheckpointMetaDto {
@@ -689,10 +869,91 @@ impl From<crate::CheckpointMeta> for CheckpointMetaDto {
         Self {
             label: m.label,
             description: m.description,
+            created_at: m.created_at,
+            author: m.author,
+            persist: m.persist,
         }
     }
 }
 
+#[derive(Debug, Clone, TS)]
+#[ts(file_name = "rpc_set_audit_params.ts", rename_all = "camelCase")]
+pub struct SetAuditParams {
+    pub enabled: bool,
+    /// Write the log into the file when the workbook is saved.
+    pub persist: Option<bool>,
+}
+
+#[derive(Debug, Clone, TS)]
+#[ts(file_name = "rpc_set_audit_author_params.ts", rename_all = "camelCase")]
+pub struct SetAuditAuthorParams {
+    pub author: AuthorInput,
+    /// Host-supplied timestamp, stored as given.
+    pub timestamp: String,
+}
+
+#[derive(Debug, Clone, TS)]
+#[ts(file_name = "rpc_get_cell_history_params.ts", rename_all = "camelCase")]
+pub struct GetCellHistoryParams {
+    pub sheet_idx: usize,
+    pub row: usize,
+    pub col: usize,
+}
+
+#[derive(Debug, Clone, TS)]
+#[ts(file_name = "rpc_get_change_feed_params.ts", rename_all = "camelCase")]
+pub struct GetChangeFeedParams {
+    /// Skip the entries before this sequence number.
+    pub since: Option<usize>,
+}
+
+#[derive(Debug, Clone, TS)]
+#[ts(file_name = "rpc_branch_params.ts", rename_all = "camelCase")]
+pub struct BranchParams {
+    /// `main` names main.
+    pub name: String,
+}
+
+#[derive(Debug, Clone, TS)]
+#[ts(file_name = "rpc_compare_branches_params.ts", rename_all = "camelCase")]
+pub struct CompareBranchesParams {
+    pub from: String,
+    pub to: String,
+}
+
+#[derive(Debug, Clone, TS)]
+#[ts(file_name = "rpc_merge_branch_params.ts", rename_all = "camelCase")]
+pub struct MergeBranchParams {
+    pub name: String,
+    /// Merge even when main changed the same cells; the branch wins.
+    pub force: Option<bool>,
+}
+
+#[derive(Debug, Clone, TS)]
+#[ts(file_name = "rpc_get_data_tables_params.ts", rename_all = "camelCase")]
+pub struct GetDataTablesParams {
+    pub sheet_idx: usize,
+}
+
+#[derive(Debug, Clone, TS)]
+#[ts(file_name = "rpc_get_scenarios_params.ts", rename_all = "camelCase")]
+pub struct GetScenariosParams {
+    pub sheet_idx: usize,
+}
+
+#[derive(Debug, Clone, TS)]
+#[ts(file_name = "rpc_goal_seek_params.ts", rename_all = "camelCase")]
+pub struct GoalSeekParams {
+    pub sheet_idx: usize,
+    /// The formula cell to bring to `goal`.
+    pub target_row: usize,
+    pub target_col: usize,
+    pub goal: f64,
+    /// The value cell Goal Seek changes.
+    pub changing_row: usize,
+    pub changing_col: usize,
+}
+
 #[derive(Debug, Clone, TS)]
 #[ts(
     file_name = "rpc_get_fully_covered_blocks_params.ts",
@@ -714,6 +975,9 @@ pub struct Transaction {
     pub temp: bool,
 }
 
+/// The custom fill lists, each as its items in order.
+pub type CustomLists = Vec<Vec<String>>;
+
 #[derive(Debug)]
 // ============================================================================
 // WorkbookMethods Interface - generates TypeScript interface
@@ -775,6 +1039,8 @@ pub struct WorkbookMethods {
     pub get_formula:
         fn(params: GetCellParams, book_id: Option<usize>) -> Result<String, ErrorMessage>,
     pub get_style: fn(params: GetCellParams, book_id: Option<usize>) -> Result<Style, ErrorMessage>,
+    pub get_cell_style_name:
+        fn(params: GetCellParams, book_id: Option<usize>) -> Result<Option<String>, ErrorMessage>,
     pub get_cells_except_window: fn(
         params: GetCellsExceptWindowParams,
         book_id: Option<usize>,
@@ -783,6 +1049,49 @@ pub struct WorkbookMethods {
         params: PredictFillParams,
         book_id: Option<usize>,
     ) -> Result<Vec<CellInput>, ErrorMessage>,
+    pub copy_range: fn(
+        params: CopyRangeParams,
+        book_id: Option<usize>,
+    ) -> Result<ClipboardContents, ErrorMessage>,
+    pub read_clipboard: fn(
+        params: ReadClipboardParams,
+        book_id: Option<usize>,
+    ) -> Result<ClipboardData, ErrorMessage>,
+    pub paste_payloads: fn(
+        params: PastePayloadsParams,
+        book_id: Option<usize>,
+    ) -> Result<Vec<EditPayload>, ErrorMessage>,
+    pub predict_fill_payloads: fn(
+        params: PredictFillPayloadsParams,
+        book_id: Option<usize>,
+    ) -> Result<Vec<EditPayload>, ErrorMessage>,
+    pub fill_series: fn(
+        params: FillSeriesParams,
+        book_id: Option<usize>,
+    ) -> Result<Vec<CellInput>, ErrorMessage>,
+    pub get_custom_lists: fn(book_id: Option<usize>) -> Result<CustomLists, ErrorMessage>,
+    pub set_custom_lists:
+        fn(params: SetCustomListsParams, book_id: Option<usize>) -> Result<(), ErrorMessage>,
+    pub get_input_locale: fn(book_id: Option<usize>) -> Result<InputLocaleInfo, ErrorMessage>,
+    pub set_input_locale:
+        fn(params: SetInputLocaleParams, book_id: Option<usize>) -> Result<(), ErrorMessage>,
+    pub get_cell_styles: fn(book_id: Option<usize>) -> Result<Vec<CellStyleInfo>, ErrorMessage>,
+    pub get_theme: fn(book_id: Option<usize>) -> Result<Option<ThemeInfo>, ErrorMessage>,
+    pub get_builtin_themes: fn(book_id: Option<usize>) -> Result<Vec<ThemeInfo>, ErrorMessage>,
+    pub predict_flash_fill: fn(
+        params: PredictFlashFillParams,
+        book_id: Option<usize>,
+    ) -> Result<Vec<CellInput>, ErrorMessage>,
+    pub find_all:
+        fn(params: FindAllParams, book_id: Option<usize>) -> Result<Vec<FoundCell>, ErrorMessage>,
+    pub find_next: fn(
+        params: FindNextParams,
+        book_id: Option<usize>,
+    ) -> Result<Option<FoundCell>, ErrorMessage>,
+    pub replace_payloads: fn(
+        params: ReplacePayloadsParams,
+        book_id: Option<usize>,
+    ) -> Result<ReplacePlan, ErrorMessage>,
     pub get_cell_position: fn(
         params: GetCellPositionParams,
         book_id: Option<usize>,
@@ -855,6 +1164,42 @@ pub struct WorkbookMethods {
         fn(params: DeleteCheckpointParams, book_id: Option<usize>) -> Result<bool, ErrorMessage>,
     pub list_checkpoints:
         fn(book_id: Option<usize>) -> Result<Vec<CheckpointMetaDto>, ErrorMessage>,
+    pub set_audit: fn(params: SetAuditParams, book_id: Option<usize>) -> Result<(), ErrorMessage>,
+    pub set_audit_author:
+        fn(params: SetAuditAuthorParams, book_id: Option<usize>) -> Result<(), ErrorMessage>,
+    pub get_cell_history: fn(
+        params: GetCellHistoryParams,
+        book_id: Option<usize>,
+    ) -> Result<Vec<ChangeEntry>, ErrorMessage>,
+    pub get_change_feed: fn(
+        params: GetChangeFeedParams,
+        book_id: Option<usize>,
+    ) -> Result<Vec<ChangeEntry>, ErrorMessage>,
+    pub clear_audit_log: fn(book_id: Option<usize>) -> Result<(), ErrorMessage>,
+    pub fork_branch: fn(params: BranchParams, book_id: Option<usize>) -> Result<(), ErrorMessage>,
+    pub switch_branch: fn(params: BranchParams, book_id: Option<usize>) -> Result<(), ErrorMessage>,
+    pub delete_branch:
+        fn(params: BranchParams, book_id: Option<usize>) -> Result<bool, ErrorMessage>,
+    pub list_branches: fn(book_id: Option<usize>) -> Result<Vec<BranchInfo>, ErrorMessage>,
+    pub compare_branches: fn(
+        params: CompareBranchesParams,
+        book_id: Option<usize>,
+    ) -> Result<Vec<CellChange>, ErrorMessage>,
+    pub merge_branch:
+        fn(params: MergeBranchParams, book_id: Option<usize>) -> Result<MergeResult, ErrorMessage>,
+
+    // What-if analysis. Data tables and scenarios are edited through
+    // `handle_transaction`; Goal Seek leaves its answer in the temp status.
+    pub get_data_tables: fn(
+        params: GetDataTablesParams,
+        book_id: Option<usize>,
+    ) -> Result<Vec<DataTableInfo>, ErrorMessage>,
+    pub goal_seek:
+        fn(params: GoalSeekParams, book_id: Option<usize>) -> Result<GoalSeekResult, ErrorMessage>,
+    pub get_scenarios: fn(
+        params: GetScenariosParams,
+        book_id: Option<usize>,
+    ) -> Result<Vec<ScenarioInfo>, ErrorMessage>,
     pub get_diy_cell_id_with_block_id: fn(
         params: GetDiyCellIdWithBlockIdParams,
         book_id: Option<usize>,
@@ -929,6 +1274,10 @@ pub struct WorkbookMethods {
         params: GetDisplayUnitsOfFormulaParams,
         book_id: Option<usize>,
     ) -> Result<FormulaDisplayInfo, ErrorMessage>,
+    pub get_formula_tokens: fn(
+        params: GetFormulaTokensParams,
+        book_id: Option<usize>,
+    ) -> Result<Vec<FormulaToken>, ErrorMessage>,
     pub calc_condition:
         fn(params: CalcConditionParams, book_id: Option<usize>) -> Result<bool, ErrorMessage>,
     pub get_cell_id_by_block_ref: fn(
@@ -940,8 +1289,14 @@ pub struct WorkbookMethods {
         book_id: Option<usize>,
     ) -> Result<Vec<BlockDataRow>, ErrorMessage>,
     pub get_temp_status_changes: fn(book_id: Option<usize>) -> Result<TempStatusDiff, ErrorMessage>,
-    pub check_formula:
-        fn(params: CheckFormulaParams, book_id: Option<usize>) -> Result<bool, ErrorMessage>,
+    pub check_formula: fn(
+        params: CheckFormulaParams,
+        book_id: Option<usize>,
+    ) -> Result<FormulaCheckResult, ErrorMessage>,
+    pub get_function_signature: fn(
+        params: GetFunctionSignatureParams,
+        book_id: Option<usize>,
+    ) -> Result<FunctionSignature, ErrorMessage>,
 
     // Row info
     pub get_row_info:
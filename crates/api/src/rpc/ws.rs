@@ -1,11 +1,14 @@
 use crate::{
     AppendixWithCell, BasicError, BlockId, BlockInfo, CellCoordinate, CellImageInfo, CellInfo,
-    CellInput, CellPosition, CellRefRange, CfRuleInfo, ChartInfo, ColInfo, Comment, DependentCell,
-    DisplayWindow, DisplayWindowWithStartPoint, DiyCellId, Error, ErrorMessage, FillRange,
-    LinkInfo, MergeCell, ReproducibleCell, SheetCoordinate, SheetId, Style, Value,
+    CellInput, CellPosition, CellRefRange, CfRuleInfo, ChartInfo, ClipboardContents,
+    ClipboardData, ColInfo, Comment, DependentCell, DisplayWindow, DisplayWindowWithStartPoint,
+    DiyCellId, EditPayload, Error, ErrorMessage, FillRange, FillType, LinkInfo, MergeCell,
+    PasteOptions, ReproducibleCell, SeriesOptions, SheetCoordinate, SheetId, Style, Value,
 };
 
 use super::{Direction, Manager};
+use crate::{BranchInfo, CellChange, ChangeEntry, MergeResult};
+use crate::{DataTableInfo, GoalSeekResult, ScenarioInfo};
 
 // ============================================================================
 // Transport-agnostic worksheet logic. See the note in `controller.rs`: these
@@ -185,6 +188,18 @@ pub fn get_style(
     ws.get_style(row_idx, col_idx).map_err(ErrorMessage::from)
 }
 
+pub fn get_cell_style_name(
+    mgr: &Manager,
+    id: usize,
+    sheet_idx: usize,
+    row_idx: usize,
+    col_idx: usize,
+) -> Result<Option<String>, ErrorMessage> {
+    let wb = mgr.get_workbook(&id).unwrap();
+    let ws = wb.get_sheet_by_idx(sheet_idx).map_err(ErrorMessage::from)?;
+    ws.get_cell_style_name(row_idx, col_idx).map_err(ErrorMessage::from)
+}
+
 pub fn get_display_window(
     mgr: &Manager,
     id: usize,
@@ -389,6 +404,149 @@ pub fn predict_fill(
         .map_err(ErrorMessage::from)
 }
 
+/// Copy (or cut) a block, in the internal form and as TSV and HTML for the
+/// system clipboard.
+#[allow(clippy::too_many_arguments)]
+pub fn copy_range(
+    mgr: &Manager,
+    id: usize,
+    sheet_idx: usize,
+    start_row: usize,
+    start_col: usize,
+    end_row: usize,
+    end_col: usize,
+    cut: bool,
+) -> Result<ClipboardContents, ErrorMessage> {
+    let wb = mgr.get_workbook(&id).unwrap();
+    let data = if cut {
+        wb.cut_range(sheet_idx, start_row, start_col, end_row, end_col)
+    } else {
+        wb.copy_range(sheet_idx, start_row, start_col, end_row, end_col)
+    }
+    .map_err(ErrorMessage::from)?;
+    Ok(wb.clipboard_contents(data))
+}
+
+/// Read what another application put on the system clipboard, anchored at
+/// the paste cell. HTML is preferred; the text is the fallback.
+pub fn read_clipboard(
+    mgr: &Manager,
+    id: usize,
+    sheet_idx: usize,
+    row: usize,
+    col: usize,
+    tsv: Option<&str>,
+    html: Option<&str>,
+) -> Result<ClipboardData, ErrorMessage> {
+    let wb = mgr.get_workbook(&id).unwrap();
+    let from_html = html.map(|html| wb.clipboard_from_html(html, sheet_idx, row, col));
+    match (from_html, tsv) {
+        (Some(Ok(data)), _) => Ok(data),
+        (_, Some(tsv)) => wb
+            .clipboard_from_tsv(tsv, sheet_idx, row, col)
+            .map_err(ErrorMessage::from),
+        (Some(Err(e)), None) => Err(ErrorMessage::from(e)),
+        (None, None) => Err(ErrorMessage::from(Error::PayloadError(
+            "the clipboard is empty".to_string(),
+        ))),
+    }
+}
+
+/// The payloads that paste `data` at `(row, col)`. The caller sends them as
+/// one transaction.
+pub fn paste_payloads(
+    mgr: &Manager,
+    id: usize,
+    data: &ClipboardData,
+    sheet_idx: usize,
+    row: usize,
+    col: usize,
+    options: &PasteOptions,
+) -> Result<Vec<EditPayload>, ErrorMessage> {
+    let wb = mgr.get_workbook(&id).unwrap();
+    wb.paste_payloads(data, sheet_idx, row, col, options)
+        .map_err(ErrorMessage::from)
+}
+
+/// Fill-handle drag with a fill type: contents and formats as payloads.
+#[allow(clippy::too_many_arguments)]
+pub fn predict_fill_payloads(
+    mgr: &Manager,
+    id: usize,
+    sheet_idx: usize,
+    src_start_row: usize,
+    src_start_col: usize,
+    src_end_row: usize,
+    src_end_col: usize,
+    dst_start_row: usize,
+    dst_start_col: usize,
+    dst_end_row: usize,
+    dst_end_col: usize,
+    fill_type: FillType,
+) -> Result<Vec<EditPayload>, ErrorMessage> {
+    let wb = mgr.get_workbook(&id).unwrap();
+    let src = FillRange {
+        start_row: src_start_row,
+        start_col: src_start_col,
+        end_row: src_end_row,
+        end_col: src_end_col,
+    };
+    let dst = FillRange {
+        start_row: dst_start_row,
+        start_col: dst_start_col,
+        end_row: dst_end_row,
+        end_col: dst_end_col,
+    };
+    wb.predict_fill_payloads(sheet_idx, src, dst, fill_type)
+        .map_err(ErrorMessage::from)
+}
+
+/// The Series dialog.
+#[allow(clippy::too_many_arguments)]
+pub fn fill_series(
+    mgr: &Manager,
+    id: usize,
+    sheet_idx: usize,
+    start_row: usize,
+    start_col: usize,
+    end_row: usize,
+    end_col: usize,
+    options: &SeriesOptions,
+) -> Result<Vec<CellInput>, ErrorMessage> {
+    let wb = mgr.get_workbook(&id).unwrap();
+    let range = FillRange {
+        start_row,
+        start_col,
+        end_row,
+        end_col,
+    };
+    wb.fill_series(sheet_idx, range, options)
+        .map_err(ErrorMessage::from)
+}
+
+/// Flash Fill: predict `target_col` from the example rows of the block.
+#[allow(clippy::too_many_arguments)]
+pub fn predict_flash_fill(
+    mgr: &Manager,
+    id: usize,
+    sheet_idx: usize,
+    start_row: usize,
+    start_col: usize,
+    end_row: usize,
+    end_col: usize,
+    target_col: usize,
+) -> Result<Vec<CellInput>, ErrorMessage> {
+    let wb = mgr.get_workbook(&id).unwrap();
+    let range = FillRange {
+        start_row,
+        start_col,
+        end_row,
+        end_col,
+    };
+    wb.predict_flash_fill(sheet_idx, range, target_col)
+        .map_err(ErrorMessage::from)
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn get_cell_infos_except_window(
     mgr: &Manager,
@@ -443,11 +601,16 @@ pub fn get_all_blocks(
 pub fn save_checkpoint(
     mgr: &mut Manager,
     id: usize,
-    label: String,
-    description: Option<String>,
+    params: super::SaveCheckpointParams,
 ) -> usize {
     let wb = mgr.get_mut_workbook(&id).unwrap();
-    wb.save_checkpoint(label, description)
+    wb.save_checkpoint_with_meta(crate::CheckpointMeta {
+        label: params.label,
+        description: params.description,
+        created_at: params.created_at,
+        author: params.author,
+        persist: params.persist.unwrap_or(false),
+    })
 }
 
 pub fn delete_checkpoint(mgr: &mut Manager, id: usize, label: String) -> bool {
@@ -458,10 +621,115 @@ pub fn delete_checkpoint(mgr: &mut Manager, id: usize, label: String) -> bool {
 pub fn list_checkpoints(mgr: &Manager, id: usize) -> Vec<super::CheckpointMetaDto> {
     let wb = mgr.get_workbook(&id).unwrap();
     // Convert CheckpointMeta to the RPC DTO (drops the Status payload —
-    // the manager's `list()` already only returns label + description).
+    // the manager's `list()` already only returns the metadata).
     wb.list_checkpoints().into_iter().map(Into::into).collect()
 }
 
+pub fn set_audit(mgr: &mut Manager, id: usize, params: super::SetAuditParams) {
+    let wb = mgr.get_mut_workbook(&id).unwrap();
+    wb.set_audit(params.enabled, params.persist.unwrap_or(false))
+}
+
+pub fn set_audit_author(mgr: &mut Manager, id: usize, params: super::SetAuditAuthorParams) {
+    let wb = mgr.get_mut_workbook(&id).unwrap();
+    wb.set_audit_author(params.author, params.timestamp)
+}
+
+pub fn get_cell_history(
+    mgr: &Manager,
+    id: usize,
+    sheet_idx: usize,
+    row: usize,
+    col: usize,
+) -> Result<Vec<ChangeEntry>, ErrorMessage> {
+    let wb = mgr.get_workbook(&id).unwrap();
+    wb.get_cell_history(sheet_idx, row, col)
+        .map_err(ErrorMessage::from)
+}
+
+pub fn get_change_feed(mgr: &Manager, id: usize, since: Option<usize>) -> Vec<ChangeEntry> {
+    let wb = mgr.get_workbook(&id).unwrap();
+    wb.get_change_feed(since.unwrap_or(0))
+}
+
+pub fn clear_audit_log(mgr: &mut Manager, id: usize) {
+    let wb = mgr.get_mut_workbook(&id).unwrap();
+    wb.clear_audit_log()
+}
+
+pub fn fork_branch(mgr: &mut Manager, id: usize, name: String) -> Result<(), ErrorMessage> {
+    let wb = mgr.get_mut_workbook(&id).unwrap();
+    wb.fork_branch(name).map_err(ErrorMessage::from)
+}
+
+pub fn switch_branch(mgr: &mut Manager, id: usize, name: &str) -> Result<(), ErrorMessage> {
+    let wb = mgr.get_mut_workbook(&id).unwrap();
+    wb.switch_branch(name).map_err(ErrorMessage::from)
+}
+
+pub fn delete_branch(mgr: &mut Manager, id: usize, name: &str) -> Result<bool, ErrorMessage> {
+    let wb = mgr.get_mut_workbook(&id).unwrap();
+    wb.delete_branch(name).map_err(ErrorMessage::from)
+}
+
+pub fn list_branches(mgr: &Manager, id: usize) -> Vec<BranchInfo> {
+    let wb = mgr.get_workbook(&id).unwrap();
+    wb.list_branches()
+}
+
+pub fn compare_branches(
+    mgr: &Manager,
+    id: usize,
+    from: &str,
+    to: &str,
+) -> Result<Vec<CellChange>, ErrorMessage> {
+    let wb = mgr.get_workbook(&id).unwrap();
+    wb.compare_branches(from, to).map_err(ErrorMessage::from)
+}
+
+pub fn merge_branch(
+    mgr: &mut Manager,
+    id: usize,
+    name: &str,
+    force: bool,
+) -> Result<MergeResult, ErrorMessage> {
+    let wb = mgr.get_mut_workbook(&id).unwrap();
+    wb.merge_branch(name, force).map_err(ErrorMessage::from)
+}
+
+pub fn get_data_tables(mgr: &Manager, id: usize, sheet_idx: usize) -> Vec<DataTableInfo> {
+    let wb = mgr.get_workbook(&id).unwrap();
+    let Ok(ws) = wb.get_sheet_by_idx(sheet_idx) else {
+        return Vec::new();
+    };
+    ws.get_data_tables()
+}
+
+pub fn get_scenarios(mgr: &Manager, id: usize, sheet_idx: usize) -> Vec<ScenarioInfo> {
+    let wb = mgr.get_workbook(&id).unwrap();
+    let Ok(ws) = wb.get_sheet_by_idx(sheet_idx) else {
+        return Vec::new();
+    };
+    ws.get_scenarios()
+}
+
+pub fn goal_seek(
+    mgr: &mut Manager,
+    id: usize,
+    params: super::GoalSeekParams,
+) -> Result<GoalSeekResult, ErrorMessage> {
+    let wb = mgr.get_mut_workbook(&id).unwrap();
+    wb.goal_seek(
+        params.sheet_idx,
+        params.target_row,
+        params.target_col,
+        params.goal,
+        params.changing_row,
+        params.changing_col,
+    )
+    .map_err(ErrorMessage::from)
+}
+
 pub fn get_reproducible_cell(
     mgr: &Manager,
     id: usize,
@@ -0,0 +1,100 @@
+//! Recalculate xlsx files and report every formula whose result differs from
+//! the value Excel cached in the file.
+//!
+//! ```sh
+//! cargo run -p buildtools --bin excel-compat -- [--tolerance 1e-9] a.xlsx b.xlsx ...
+//! ```
+//!
+//! Exits with 1 if any file has a mismatch or can not be loaded.
+
+use std::process::ExitCode;
+
+use logisheets_rs::{CompatOptions, Value, check_excel_compat};
+
+fn main() -> ExitCode {
+    let mut options = CompatOptions::default();
+    let mut files = Vec::new();
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--tolerance" {
+            match args.next().and_then(|t| t.parse::<f64>().ok()) {
+                Some(t) => options.tolerance = t,
+                None => {
+                    eprintln!("--tolerance expects a number");
+                    return ExitCode::from(2);
+                }
+            }
+        } else {
+            files.push(arg);
+        }
+    }
+    if files.is_empty() {
+        eprintln!("usage: excel-compat [--tolerance <number>] <file.xlsx>...");
+        return ExitCode::from(2);
+    }
+
+    let mut failed = false;
+    for file in files {
+        let buf = match std::fs::read(&file) {
+            Ok(buf) => buf,
+            Err(e) => {
+                eprintln!("{}: {}", file, e);
+                failed = true;
+                continue;
+            }
+        };
+        let report = match check_excel_compat(&buf, &options) {
+            Ok(report) => report,
+            Err(e) => {
+                eprintln!("{}: failed to load: {}", file, e);
+                failed = true;
+                continue;
+            }
+        };
+        for m in report.mismatches.iter() {
+            println!(
+                "{}: {}!{}: ={}\n    excel:      {}\n    logisheets: {}",
+                file,
+                m.sheet,
+                cell_name(m.row, m.col),
+                m.formula,
+                show(&m.excel),
+                show(&m.logisheets),
+            );
+        }
+        println!(
+            "{}: {} checked, {} skipped, {} mismatched",
+            file,
+            report.checked,
+            report.skipped,
+            report.mismatches.len()
+        );
+        failed |= !report.is_compatible();
+    }
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn cell_name(row: usize, col: usize) -> String {
+    let mut col = col + 1;
+    let mut letters = Vec::new();
+    while col > 0 {
+        let rem = (col - 1) % 26;
+        letters.push((b'A' + rem as u8) as char);
+        col = (col - 1) / 26;
+    }
+    letters.iter().rev().collect::<String>() + &(row + 1).to_string()
+}
+
+fn show(v: &Value) -> String {
+    match v {
+        Value::Str(s) => format!("{:?}", s),
+        Value::Bool(b) => b.to_string().to_uppercase(),
+        Value::Number(n) => n.to_string(),
+        Value::Error(e) => e.clone(),
+        Value::Empty => String::from("(empty)"),
+    }
+}
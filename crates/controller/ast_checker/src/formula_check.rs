@@ -0,0 +1,303 @@
+use gents_derives::TS;
+use logisheets_lexer::{Rule, lex};
+use logisheets_parser::check_syntax;
+use pest::iterators::Pair;
+
+use crate::arg_count_check;
+use crate::registry::{ArgType, FunctionSignature, get_function_signature};
+
+#[derive(Debug, Clone, TS)]
+#[ts(file_name = "formula_check_result.ts", rename_all = "camelCase")]
+pub struct FormulaCheckResult {
+    pub valid: bool,
+    pub diagnostics: Vec<FormulaDiagnostic>,
+}
+
+/// A problem found in a formula. `start` and `end` are byte offsets into
+/// the checked text.
+#[derive(Debug, Clone, PartialEq, Eq, TS)]
+#[ts(file_name = "formula_diagnostic.ts", rename_all = "camelCase")]
+pub struct FormulaDiagnostic {
+    pub start: usize,
+    pub end: usize,
+    pub kind: FormulaDiagnosticKind,
+    pub message: String,
+    /// What would have been accepted at `start`. Only filled for syntax
+    /// errors.
+    pub expected: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, TS)]
+#[ts(file_name = "formula_diagnostic_kind.ts", rename_all = "camelCase")]
+pub enum FormulaDiagnosticKind {
+    /// A built-in function is called with a wrong number of arguments.
+    ArgCount,
+    /// A literal argument can not be converted to the type the parameter
+    /// expects, like `SUM("abc")` or `COUNTIF(1, 2)`.
+    ArgType,
+    /// The formula can not be parsed.
+    Syntax,
+    /// A reference points outside the grid, like `A0` or `XFE1`.
+    BadReference,
+    /// The function is neither built in nor provided by the host.
+    UnknownFunction,
+    /// The sheet a reference points to does not exist in this workbook.
+    UnknownSheet,
+}
+
+/// What the checker needs to know about the workbook the formula lives in.
+pub trait FormulaCheckContext {
+    /// Whether `name` can be called, besides the functions in the registry.
+    fn is_known_function(&self, name: &str) -> bool;
+    fn is_known_sheet(&self, name: &str) -> bool;
+}
+
+const MAX_ROW: usize = 1_048_576;
+const MAX_COL: usize = 16_384;
+
+/// Check a formula (without the leading `=`) against the function
+/// registry and the workbook. Calls to functions that are not in the
+/// registry only get their names checked.
+pub fn check_formula<C: FormulaCheckContext>(f: &str, ctx: &C) -> FormulaCheckResult {
+    let diagnostics = match lex(f) {
+        Some(pair) => {
+            let mut diagnostics = Vec::new();
+            check_pair(pair, ctx, &mut diagnostics);
+            diagnostics
+        }
+        None => match check_syntax(f) {
+            Ok(_) => vec![],
+            Err(e) => vec![FormulaDiagnostic {
+                start: e.start,
+                end: e.end,
+                kind: FormulaDiagnosticKind::Syntax,
+                message: e.message,
+                expected: e.expected,
+            }],
+        },
+    };
+    FormulaCheckResult {
+        valid: diagnostics.is_empty(),
+        diagnostics,
+    }
+}
+
+fn check_pair<C: FormulaCheckContext>(
+    pair: Pair<Rule>,
+    ctx: &C,
+    diagnostics: &mut Vec<FormulaDiagnostic>,
+) {
+    match pair.as_rule() {
+        Rule::function_call => check_function_call(pair.clone(), ctx, diagnostics),
+        Rule::work_sheet_prefix => return check_sheet_prefix(pair, ctx, diagnostics),
+        Rule::a1_column => {
+            let col = pair
+                .as_str()
+                .trim_start_matches('$')
+                .chars()
+                .try_fold(0usize, |acc, c| {
+                    let d = c.to_ascii_uppercase() as usize - 'A' as usize + 1;
+                    acc.checked_mul(26).and_then(|v| v.checked_add(d))
+                });
+            if col.is_none_or(|c| c > MAX_COL) {
+                diagnostics.push(bad_reference(&pair, "Column", "XFD"));
+            }
+        }
+        Rule::a1_row => {
+            let row = pair.as_str().trim_start_matches('$').parse::<usize>();
+            if row.map_or(true, |r| r == 0 || r > MAX_ROW) {
+                diagnostics.push(bad_reference(&pair, "Row", "1048576"));
+            }
+        }
+        _ => {}
+    }
+    pair.into_inner()
+        .for_each(|inner| check_pair(inner, ctx, diagnostics));
+}
+
+fn bad_reference(pair: &Pair<Rule>, what: &str, last: &str) -> FormulaDiagnostic {
+    let span = pair.as_span();
+    FormulaDiagnostic {
+        start: span.start(),
+        end: span.end(),
+        kind: FormulaDiagnosticKind::BadReference,
+        message: format!(
+            "{} {} is outside the sheet, the last one is {}",
+            what,
+            pair.as_str(),
+            last
+        ),
+        expected: vec![],
+    }
+}
+
+fn check_sheet_prefix<C: FormulaCheckContext>(
+    pair: Pair<Rule>,
+    ctx: &C,
+    diagnostics: &mut Vec<FormulaDiagnostic>,
+) {
+    let names: Vec<Pair<Rule>> = pair.into_inner().flatten().collect();
+    // Sheets of other workbooks can not be checked.
+    if names.iter().any(|p| {
+        matches!(
+            p.as_rule(),
+            Rule::workbook_name | Rule::workbook_name_special
+        )
+    }) {
+        return;
+    }
+    names
+        .into_iter()
+        .filter(|p| matches!(p.as_rule(), Rule::sheet_name | Rule::sheet_name_special))
+        .for_each(|p| {
+            let name = p.as_str().replace("''", "'");
+            if !ctx.is_known_sheet(&name) {
+                let span = p.as_span();
+                diagnostics.push(FormulaDiagnostic {
+                    start: span.start(),
+                    end: span.end(),
+                    kind: FormulaDiagnosticKind::UnknownSheet,
+                    message: format!("There is no sheet named {}", name),
+                    expected: vec![],
+                });
+            }
+        });
+}
+
+fn check_function_call<C: FormulaCheckContext>(
+    pair: Pair<Rule>,
+    ctx: &C,
+    diagnostics: &mut Vec<FormulaDiagnostic>,
+) {
+    let span = pair.as_span();
+    let mut inner = pair.into_inner();
+    let name_pair = inner.next().unwrap();
+    let name = name_pair.as_str();
+    let Some(sign) = get_function_signature(name) else {
+        if !ctx.is_known_function(name) {
+            let span = name_pair.as_span();
+            diagnostics.push(FormulaDiagnostic {
+                start: span.start(),
+                end: span.end(),
+                kind: FormulaDiagnosticKind::UnknownFunction,
+                message: format!("Unknown function {}", name),
+                expected: vec![],
+            });
+        }
+        return;
+    };
+    let args: Vec<Pair<Rule>> = inner.collect();
+    if !arg_count_check(&sign.arg_count, args.len().min(u8::MAX as usize) as u8) {
+        diagnostics.push(FormulaDiagnostic {
+            start: span.start(),
+            end: span.end(),
+            kind: FormulaDiagnosticKind::ArgCount,
+            message: format!(
+                "{} expects {}, but got {}",
+                sign.name,
+                describe_arg_count(sign),
+                args.len()
+            ),
+            expected: vec![],
+        });
+        return;
+    }
+    for (idx, arg) in args.iter().enumerate() {
+        let Some(def) = sign.arg_at(idx) else {
+            continue;
+        };
+        let Some(literal) = literal_of(arg) else {
+            continue;
+        };
+        let expected = match (def.arg_type, literal) {
+            (ArgType::Reference, _) => Some("a reference"),
+            (ArgType::Number, Literal::Text(s)) if !text_is_number(s) => Some("a number"),
+            (ArgType::Logical, Literal::Text(s))
+                if !s.eq_ignore_ascii_case("TRUE") && !s.eq_ignore_ascii_case("FALSE") =>
+            {
+                Some("a logical value")
+            }
+            _ => None,
+        };
+        if let Some(expected) = expected {
+            let span = arg.as_span();
+            diagnostics.push(FormulaDiagnostic {
+                start: span.start(),
+                end: span.end(),
+                kind: FormulaDiagnosticKind::ArgType,
+                message: format!(
+                    "Argument {} of {} ({}) expects {}",
+                    idx + 1,
+                    sign.name,
+                    def.name,
+                    expected
+                ),
+                expected: vec![],
+            });
+        }
+    }
+}
+
+enum Literal<'a> {
+    Number,
+    Text(&'a str),
+    Logical,
+    Array,
+}
+
+/// The constant an argument is made of, or `None` if its value is only
+/// known at calculation time.
+fn literal_of<'a>(arg: &Pair<'a, Rule>) -> Option<Literal<'a>> {
+    if arg.as_rule() != Rule::expression {
+        return None;
+    }
+    let inner: Vec<Pair<Rule>> = arg.clone().into_inner().collect();
+    let constant = match inner.as_slice() {
+        [c] => c,
+        [op, c] if op.as_rule() == Rule::prefix_op && c.as_rule() == Rule::numerical_constant => c,
+        _ => return None,
+    };
+    match constant.as_rule() {
+        Rule::numerical_constant => Some(Literal::Number),
+        Rule::string_constant => {
+            let s = constant.as_str();
+            Some(Literal::Text(&s[1..s.len() - 1]))
+        }
+        Rule::logical_constant => Some(Literal::Logical),
+        Rule::array_constant => Some(Literal::Array),
+        _ => None,
+    }
+}
+
+/// Whether a text literal converts to a number where one is expected, as
+/// `"2.5"` and `"50%"` do and `"1e"` does not.
+fn text_is_number(s: &str) -> bool {
+    let s = s.trim();
+    let s = s.strip_suffix('%').unwrap_or(s).trim_end();
+    !s.is_empty() && s.parse::<f64>().is_ok_and(f64::is_finite)
+}
+
+fn describe_arg_count(sign: &FunctionSignature) -> String {
+    let rule = &sign.arg_count;
+    let plural = |n: u8| {
+        if n == 1 {
+            format!("{} argument", n)
+        } else {
+            format!("{} arguments", n)
+        }
+    };
+    let mut result = match (rule.eq, rule.ge, rule.le) {
+        (Some(n), _, _) => format!("exactly {}", plural(n)),
+        (None, Some(lo), Some(hi)) => format!("{} to {} arguments", lo, hi),
+        (None, Some(lo), None) => format!("at least {}", plural(lo)),
+        (None, None, Some(hi)) => format!("at most {}", plural(hi)),
+        (None, None, None) => String::from("any number of arguments"),
+    };
+    if rule.odd.unwrap_or(false) {
+        result.push_str(", an odd count");
+    }
+    if rule.even.unwrap_or(false) {
+        result.push_str(", an even count");
+    }
+    result
+}
@@ -1,84 +1,22 @@
-use std::collections::HashMap;
+#[macro_use]
+extern crate lazy_static;
 
-use logisheets_base::FuncId;
-use logisheets_parser::ast::{self, Node};
+use gents_derives::TS;
 
-pub struct AstChecker {
-    registry: HashMap<FuncId, FuncSignature>,
-}
-
-impl AstChecker {
-    pub fn func_check(&self, node: &ast::Node) -> Result<(), FuncCheckError> {
-        match &node.pure {
-            ast::PureNode::Func(f) => match &f.op {
-                ast::Operator::Function(id) => {
-                    if let Some(sign) = self.registry.get(id) {
-                        let args = &f.args;
-                        args_check(sign, args)
-                    } else {
-                        Ok(())
-                    }
-                }
-                _ => Ok(()),
-            },
-            ast::PureNode::Value(_) => Ok(()),
-            ast::PureNode::Reference(_) => Ok(()),
-            // BlockRef arity is enforced by the parser (it refuses to emit
-            // the variant if args don't match), so it bypasses the
-            // FuncSignature registry.
-            ast::PureNode::BlockRef(_) => Ok(()),
-            // A literal matrix: nothing to check, it contains no calls.
-            ast::PureNode::ArrayConstant(_) => Ok(()),
-        }
-    }
-}
-
-pub struct ArgDefIter<'a> {
-    data: &'a Vec<ArgDef>,
-    idx: usize,
-    has_repeated: Option<usize>,
-}
+mod formula_check;
+mod registry;
 
-impl<'a> From<&'a Vec<ArgDef>> for ArgDefIter<'a> {
-    fn from(data: &'a Vec<ArgDef>) -> Self {
-        ArgDefIter {
-            data,
-            idx: 0,
-            has_repeated: None,
-        }
-    }
-}
-
-impl<'a> Iterator for ArgDefIter<'a> {
-    type Item = &'a ArgDef;
+pub use formula_check::{
+    FormulaCheckContext, FormulaCheckResult, FormulaDiagnostic, FormulaDiagnosticKind,
+    check_formula,
+};
+pub use registry::{
+    ArgType, FunctionArg, FunctionCategory, FunctionSignature, get_all_function_signatures,
+    get_function_signature,
+};
 
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.idx < self.data.len() {
-            let arg = self.data.get(self.idx).unwrap();
-            if arg.start_repeated.unwrap_or(false) {
-                self.has_repeated = Some(self.idx);
-            }
-            self.idx += 1;
-            Some(arg)
-        } else {
-            if let Some(repeated) = self.has_repeated {
-                self.idx = repeated + 1;
-                Some(self.data.get(repeated).unwrap())
-            } else {
-                None
-            }
-        }
-    }
-}
-
-#[derive(Debug)]
-pub struct FuncSignature {
-    pub id: FuncId,
-    pub arg_count: ArgCount,
-    pub args: Vec<ArgDef>,
-}
-
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, TS)]
+#[ts(file_name = "arg_count.ts", rename_all = "camelCase")]
 pub struct ArgCount {
     pub le: Option<u8>,
     pub ge: Option<u8>,
@@ -116,75 +54,6 @@ fn arg_count_check(count_rule: &ArgCount, count: u8) -> bool {
     true
 }
 
-#[derive(Debug, Clone)]
-pub struct ArgDef {
-    pub arg_name: String,
-    pub ref_only: Option<bool>,
-    pub start_repeated: Option<bool>,
-}
-
-impl ArgDef {
-    pub fn unexpected_arg() -> Self {
-        ArgDef {
-            arg_name: String::from("Unexpected"),
-            ref_only: None,
-            start_repeated: None,
-        }
-    }
-}
-
-#[derive(Debug)]
-pub struct FuncCheckError {
-    pub id: FuncId,
-    pub break_arg_count: Option<ArgCount>,
-    pub break_args: Option<(usize, ArgDef)>,
-}
-
-fn args_check(sign: &FuncSignature, args: &Vec<Node>) -> Result<(), FuncCheckError> {
-    let count_rule = &sign.arg_count;
-    let count = args.len() as u8;
-    if !arg_count_check(count_rule, count) {
-        return Err(FuncCheckError {
-            id: sign.id,
-            break_args: None,
-            break_arg_count: Some(count_rule.clone()),
-        });
-    }
-    let mut args_iter = args.iter();
-    let mut arg_rule_iter = ArgDefIter::from(&sign.args);
-    let mut idx = 0usize;
-    while let Some(arg) = args_iter.next() {
-        let rule = arg_rule_iter.next();
-        if rule.is_none() {
-            return Err(FuncCheckError {
-                id: sign.id,
-                break_arg_count: None,
-                break_args: Some((idx, ArgDef::unexpected_arg())),
-            });
-        }
-        let arg_def = rule.unwrap();
-        if !arg_def_check(arg_def, arg) {
-            return Err(FuncCheckError {
-                id: sign.id,
-                break_arg_count: None,
-                break_args: Some((idx, arg_def.clone())),
-            });
-        }
-        idx += 1;
-    }
-    todo!()
-}
-
-fn arg_def_check(arg_def: &ArgDef, arg: &Node) -> bool {
-    if arg_def.ref_only.unwrap_or(false) {
-        match &arg.pure {
-            ast::PureNode::Value(_) | ast::PureNode::ArrayConstant(_) => return false,
-            _ => {}
-        };
-    }
-    true
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -247,36 +116,124 @@ mod tests {
     }
 
     #[test]
-    fn arg_def_iter_check() {
-        let arg_def1 = ArgDef {
-            arg_name: String::from("arg1"),
-            ref_only: None,
-            start_repeated: None,
-        };
-        let arg_def2 = ArgDef {
-            arg_name: String::from("arg2"),
-            ref_only: None,
-            start_repeated: None,
+    fn funcs_json_matches_formula_editor() {
+        // gen-builtin-functions.mjs writes both copies; an edit to one alone
+        // would let the editor and the checker disagree. The crate can be
+        // built outside the monorepo, where there is no editor to compare.
+        let Ok(editor) = std::fs::read_to_string(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../../../packages/formula-editor/src/lib/builtin-functions.json"
+        )) else {
+            return;
         };
-        let arg_def3 = ArgDef {
-            arg_name: String::from("arg3"),
-            ref_only: None,
-            start_repeated: Some(true),
-        };
-        let arg_def4 = ArgDef {
-            arg_name: String::from("arg4"),
-            ref_only: None,
-            start_repeated: None,
-        };
-        let arg_defs = vec![arg_def1, arg_def2, arg_def3, arg_def4];
-        let mut iter = ArgDefIter::from(&arg_defs);
-        assert_eq!(iter.next().unwrap().arg_name, "arg1");
-        assert_eq!(iter.next().unwrap().arg_name, "arg2");
-        assert_eq!(iter.next().unwrap().arg_name, "arg3");
-        assert_eq!(iter.next().unwrap().arg_name, "arg4");
-        assert_eq!(iter.next().unwrap().arg_name, "arg3");
-        assert_eq!(iter.next().unwrap().arg_name, "arg4");
-        assert_eq!(iter.next().unwrap().arg_name, "arg3");
-        assert_eq!(iter.next().unwrap().arg_name, "arg4");
+        assert!(editor == include_str!("../funcs.json"));
+    }
+
+    #[test]
+    fn registry_lookup() {
+        let sumifs = get_function_signature("sumifs").unwrap();
+        assert_eq!(sumifs.category, FunctionCategory::Math);
+        assert!(!sumifs.volatile);
+        assert_eq!(sumifs.args[0].arg_type, ArgType::Reference);
+        assert!(!sumifs.args[0].repeated);
+        assert!(sumifs.args[1].repeated);
+        assert_eq!(sumifs.arg_at(3).unwrap().name, "criteria_range");
+        assert_eq!(sumifs.arg_at(4).unwrap().name, "criteria");
+
+        let left = get_function_signature("LEFT").unwrap();
+        assert!(!left.args[0].optional);
+        assert!(left.args[1].optional);
+        assert!(left.arg_at(2).is_none());
+
+        assert!(get_function_signature("RAND").unwrap().volatile);
+        assert!(get_function_signature("_xlfn.GAMMA.DIST").is_some());
+        assert!(get_function_signature("NOT_A_FUNCTION").is_none());
+    }
+
+    struct TestContext;
+
+    impl FormulaCheckContext for TestContext {
+        fn is_known_function(&self, name: &str) -> bool {
+            name.eq_ignore_ascii_case("MYFUNC")
+        }
+
+        fn is_known_sheet(&self, name: &str) -> bool {
+            name == "Sheet1" || name == "It's"
+        }
+    }
+
+    fn check_formula(f: &str) -> FormulaCheckResult {
+        super::check_formula(f, &TestContext)
+    }
+
+    #[test]
+    fn check_formula_diagnostics() {
+        assert!(check_formula("SUM(1, A1:B2, \"3\")").valid);
+        assert!(check_formula("myfunc(1, 2, 3)").valid);
+
+        let r = check_formula("1+ABS(1, 2)");
+        assert!(!r.valid);
+        assert_eq!(r.diagnostics.len(), 1);
+        let d = &r.diagnostics[0];
+        assert_eq!(d.kind, FormulaDiagnosticKind::ArgCount);
+        assert_eq!((d.start, d.end), (2, 11));
+        assert_eq!(d.message, "ABS expects exactly 1 argument, but got 2");
+
+        let r = check_formula("SUMIFS(A1:A3, B1:B3)");
+        assert_eq!(r.diagnostics[0].kind, FormulaDiagnosticKind::ArgCount);
+
+        let r = check_formula("SUM(1, \"abc\") + COUNTIF(-1, 2)");
+        assert_eq!(r.diagnostics.len(), 2);
+        assert_eq!(r.diagnostics[0].kind, FormulaDiagnosticKind::ArgType);
+        assert_eq!((r.diagnostics[0].start, r.diagnostics[0].end), (7, 12));
+        assert_eq!(
+            r.diagnostics[1].message,
+            "Argument 1 of COUNTIF (range) expects a reference"
+        );
+
+        assert!(check_formula("ABS(\" 2.5 \") + ABS(\"50%\")").valid);
+        let r = check_formula("ABS(\"1e\") + ABS(\"R2D2\")");
+        assert_eq!(r.diagnostics.len(), 2);
+        assert_eq!(
+            r.diagnostics[1].message,
+            "Argument 1 of ABS (number) expects a number"
+        );
+
+        let r = check_formula("IFERROR(OR(\"yes\"), 0)");
+        assert_eq!(r.diagnostics.len(), 1);
+        assert_eq!(r.diagnostics[0].kind, FormulaDiagnosticKind::ArgType);
+
+        let r = check_formula("SUM(1");
+        assert_eq!(r.diagnostics[0].kind, FormulaDiagnosticKind::Syntax);
+        assert_eq!((r.diagnostics[0].start, r.diagnostics[0].end), (3, 4));
+        assert_eq!(r.diagnostics[0].expected, vec!["')'"]);
+    }
+
+    #[test]
+    fn check_formula_names_and_references() {
+        let r = check_formula("UNKNOWN(1) + SUM(1)");
+        assert_eq!(r.diagnostics.len(), 1);
+        let d = &r.diagnostics[0];
+        assert_eq!(d.kind, FormulaDiagnosticKind::UnknownFunction);
+        assert_eq!((d.start, d.end), (0, 7));
+
+        assert!(check_formula("Sheet1!A1 + 'It''s'!B2 + [Book]Other!A1").valid);
+        let r = check_formula("SUM(Sheet2!A1:B2)");
+        assert_eq!(r.diagnostics.len(), 1);
+        let d = &r.diagnostics[0];
+        assert_eq!(d.kind, FormulaDiagnosticKind::UnknownSheet);
+        assert_eq!((d.start, d.end), (4, 10));
+        assert_eq!(d.message, "There is no sheet named Sheet2");
+
+        let r = check_formula("A0 + $XFE$1 + XFD1048576 + A1048577");
+        let kinds: Vec<_> = r.diagnostics.iter().map(|d| (d.kind, d.start)).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (FormulaDiagnosticKind::BadReference, 1),
+                (FormulaDiagnosticKind::BadReference, 5),
+                (FormulaDiagnosticKind::BadReference, 28),
+            ]
+        );
     }
 }
@@ -0,0 +1,170 @@
+use std::collections::HashMap;
+
+use gents_derives::TS;
+use serde::Deserialize;
+
+use crate::ArgCount;
+
+/// Metadata of a built-in function, served to hosts for autocomplete and
+/// argument tooltips, and used by `check_formula` to validate calls.
+#[derive(Debug, Clone, TS)]
+#[ts(file_name = "function_signature.ts", rename_all = "camelCase")]
+pub struct FunctionSignature {
+    pub name: String,
+    pub description: String,
+    pub category: FunctionCategory,
+    /// Recalculated on every edit, like `RAND` or `TODAY`.
+    pub volatile: bool,
+    pub arg_count: ArgCount,
+    pub args: Vec<FunctionArg>,
+}
+
+impl FunctionSignature {
+    /// The parameter the `idx`-th argument binds to. Past the declared
+    /// list, the parameters from the first repeated one onwards cycle, so
+    /// `SUMIFS(sum_range, criteria_range, criteria, ...)` keeps pairing up.
+    pub fn arg_at(&self, idx: usize) -> Option<&FunctionArg> {
+        if let Some(arg) = self.args.get(idx) {
+            return Some(arg);
+        }
+        let start = self.args.iter().position(|a| a.repeated)?;
+        let group = self.args.len() - start;
+        self.args.get(start + (idx - self.args.len()) % group)
+    }
+}
+
+#[derive(Debug, Clone, TS)]
+#[ts(file_name = "function_arg.ts", rename_all = "camelCase")]
+pub struct FunctionArg {
+    pub name: String,
+    pub description: Option<String>,
+    pub arg_type: ArgType,
+    /// The call is complete without this argument.
+    pub optional: bool,
+    /// This argument belongs to the group that can be repeated.
+    pub repeated: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, TS)]
+#[ts(file_name = "arg_type.ts", rename_all = "camelCase")]
+pub enum ArgType {
+    Any,
+    Number,
+    Text,
+    Logical,
+    Reference,
+    Array,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, TS)]
+#[ts(file_name = "function_category.ts", rename_all = "camelCase")]
+pub enum FunctionCategory {
+    Math,
+    Statistical,
+    Text,
+    Logical,
+    Lookup,
+    Date,
+    Financial,
+    Engineering,
+    Information,
+}
+
+/// Look up a built-in function by name, case-insensitively. The `_xlfn.`
+/// prefix newer functions carry in files is ignored.
+pub fn get_function_signature(name: &str) -> Option<&'static FunctionSignature> {
+    let name = name.to_uppercase();
+    let name = name.strip_prefix("_XLFN.").unwrap_or(&name);
+    REGISTRY.get(name)
+}
+
+/// Every built-in function with metadata, sorted by name.
+pub fn get_all_function_signatures() -> Vec<&'static FunctionSignature> {
+    let mut result: Vec<_> = REGISTRY.values().collect();
+    result.sort_by(|a, b| a.name.cmp(&b.name));
+    result
+}
+
+lazy_static! {
+    static ref REGISTRY: HashMap<String, FunctionSignature> = load_registry();
+}
+
+// Generated by packages/formula-editor/scripts/gen-builtin-functions.mjs
+// from resources/funcs.
+const FUNCS_JSON: &str = include_str!("../funcs.json");
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawFunc {
+    name: String,
+    description: String,
+    category: FunctionCategory,
+    #[serde(default)]
+    volatile: bool,
+    arg_count: RawArgCount,
+    #[serde(default)]
+    args: Vec<RawArg>,
+}
+
+#[derive(Deserialize)]
+struct RawArgCount {
+    le: Option<u8>,
+    ge: Option<u8>,
+    eq: Option<u8>,
+    odd: Option<bool>,
+    even: Option<bool>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawArg {
+    arg_name: String,
+    arg_type: Option<ArgType>,
+    #[serde(default)]
+    ref_only: bool,
+    #[serde(default)]
+    start_repeated: bool,
+    description: Option<String>,
+}
+
+fn load_registry() -> HashMap<String, FunctionSignature> {
+    let raw: Vec<RawFunc> = serde_json::from_str(FUNCS_JSON).expect("invalid funcs.json");
+    raw.into_iter()
+        .map(|f| {
+            let arg_count = ArgCount {
+                le: f.arg_count.le,
+                ge: f.arg_count.ge,
+                eq: f.arg_count.eq,
+                odd: f.arg_count.odd,
+                even: f.arg_count.even,
+            };
+            let required = arg_count.eq.or(arg_count.ge).unwrap_or(0) as usize;
+            let repeated_from = f.args.iter().position(|a| a.start_repeated);
+            let args = f
+                .args
+                .into_iter()
+                .enumerate()
+                .map(|(idx, a)| FunctionArg {
+                    name: a.arg_name,
+                    description: a.description,
+                    arg_type: if a.ref_only {
+                        ArgType::Reference
+                    } else {
+                        a.arg_type.unwrap_or(ArgType::Any)
+                    },
+                    optional: idx >= required,
+                    repeated: repeated_from.is_some_and(|r| idx >= r),
+                })
+                .collect();
+            let sign = FunctionSignature {
+                name: f.name,
+                description: f.description,
+                category: f.category,
+                volatile: f.volatile,
+                arg_count,
+                args,
+            };
+            (sign.name.clone(), sign)
+        })
+        .collect()
+}
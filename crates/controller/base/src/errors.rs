@@ -78,6 +78,8 @@ pub enum BasicError {
 
     #[error("invalid formula: {0}")]
     InvalidFormula(String),
+    #[error("unknown function: {0}")]
+    UnknownFunction(String),
     #[error("bind block size mismatch: {0}, {1}, {2}")]
     BindBlockSizeMismatch(BlockId, usize, usize),
     #[error("sheet id not found: {0}")]
@@ -86,6 +88,12 @@ pub enum BasicError {
     NoAppendix,
     #[error("checkpoint not found: {0}")]
     CheckpointNotFound(String),
+    #[error("branch not found: {0}")]
+    BranchNotFound(String),
+    #[error("branch already exists: {0}")]
+    BranchExists(String),
+    #[error("branch {0} cannot be merged: {1}")]
+    BranchUnmergeable(String, String),
     #[error("incomplete row col length: {0}, {1}")]
     IncompleteRowColLength(usize, usize),
     #[error("referencing ephemeral cell")]
@@ -223,6 +223,21 @@ impl Error {
             _ => Error::Unspecified,
         }
     }
+
+    /// The error a user can type into a cell, like `#N/A`. Errors only a
+    /// calculation produces are not read back.
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "#DIV/0!" => Some(Error::Div0),
+            "#N/A" => Some(Error::NA),
+            "#NAME?" => Some(Error::Name),
+            "#NULL!" => Some(Error::Null),
+            "#NUM!" => Some(Error::Num),
+            "#REF!" => Some(Error::Ref),
+            "#VALUE!" => Some(Error::Value),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -38,6 +38,13 @@ pub trait NameFetcherTrait {
         None
     }
 
+    /// Whether `@` (implicit intersection) should be written the way OOXML
+    /// stores it, `_xlfn.SINGLE(...)`. Only the file saver says yes: the
+    /// formula bar shows the operator Excel's UI shows.
+    fn implicit_intersection_as_function(&self) -> bool {
+        false
+    }
+
     /// Resolve a `BLOCKREF` target to the concrete cell it names, so unparse can
     /// print an ordinary `A1` reference instead of the `BLOCKREF(...)` call.
     ///
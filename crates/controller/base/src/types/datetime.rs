@@ -133,12 +133,22 @@ impl Time {
     }
 }
 
+/// The serial of 9999-12-31, the last date of the 1900 date system.
+pub const MAX_SERIAL_NUM_1900: u32 = 2_958_465;
+
 pub fn get_serial_num_by_date_1900(year: u32, month: u32, day: u32) -> Option<u32> {
     let year = if year < 1900 { year + 1900 } else { year };
     let (year, month) = {
         let (y, m) = (year + month / 12, month % 12);
         if m == 0 { (y - 1, 12) } else { (y, m) }
     };
+    if year == 1900 && month <= 2 {
+        // 60 represents the date 1900/2/29, though this date does not exist.
+        // This was a mistake made by Microsoft Excel, and January and
+        // February 1900 are counted as if it did.
+        let r = (month - 1) * 31 + day;
+        return if r < 1 { None } else { Some(r) };
+    }
     let init_date = NaiveDate::from_ymd_opt(year as i32, month, 1).unwrap();
     let duration = Duration::days((day - 1) as i64);
     let new_date = init_date + duration;
@@ -146,10 +156,7 @@ pub fn get_serial_num_by_date_1900(year: u32, month: u32, day: u32) -> Option<u3
     let r = duration.num_days();
     if r < 1 {
         None
-    } else if r <= 60 {
-        // 60 represents the date 1900/2/29, though this date does not exist.
-        // This was a mistake made by Microsoft Excel and we make a special
-        // treatment here.
+    } else if r < 60 {
         Some(r as u32)
     } else {
         Some(1 + r as u32)
@@ -248,6 +255,10 @@ mod tests {
         assert_eq!(r, Some(1));
         let r = get_serial_num_by_date_1900(1900, 2, 29);
         assert_eq!(r, Some(60));
+        let r = get_serial_num_by_date_1900(1900, 3, 1);
+        assert_eq!(r, Some(61));
+        let r = get_serial_num_by_date_1900(1900, 1, 61);
+        assert_eq!(r, Some(61));
         let r = get_serial_num_by_date_1900(1900, 133, 1);
         assert_eq!(r, Some(4019));
         let r = get_serial_num_by_date_1900(2000, 133, 690);
@@ -2,17 +2,17 @@ use pest::Parser;
 use pest_derive::Parser;
 use tracing::error;
 
+mod tokenizer;
+
+pub use tokenizer::{FormulaToken, FormulaTokenKind, TokenReference, tokenize};
+
 #[derive(Parser)]
 #[grammar = "grammar.pest"]
 pub struct FormulaParser;
 
 pub fn lex(s: &str) -> Option<pest::iterators::Pair<'_, Rule>> {
-    let result = FormulaParser::parse(Rule::start, s);
-    match result {
-        Ok(mut r) => {
-            let tokens = r.next().unwrap();
-            Some(tokens)
-        }
+    match try_lex(s) {
+        Ok(tokens) => Some(tokens),
         Err(e) => {
             error!("parse formula failed: {}\nMeet error: {}", s, e);
             None
@@ -20,6 +20,13 @@ pub fn lex(s: &str) -> Option<pest::iterators::Pair<'_, Rule>> {
     }
 }
 
+/// Like `lex`, but hands back the pest error, which carries the failing
+/// position and the rules that were expected there.
+pub fn try_lex(s: &str) -> Result<pest::iterators::Pair<'_, Rule>, Box<pest::error::Error<Rule>>> {
+    let mut r = FormulaParser::parse(Rule::start, s).map_err(Box::new)?;
+    Ok(r.next().unwrap())
+}
+
 #[cfg(test)]
 mod tests {
     use super::lex;
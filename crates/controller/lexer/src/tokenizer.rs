@@ -0,0 +1,413 @@
+use std::collections::HashMap;
+
+use gents_derives::TS;
+
+/// A classified piece of formula text, for syntax highlighting. `start` and
+/// `end` are byte offsets.
+#[derive(Debug, Clone, PartialEq, Eq, TS)]
+#[ts(file_name = "formula_token.ts", rename_all = "camelCase")]
+pub struct FormulaToken {
+    pub kind: FormulaTokenKind,
+    pub start: usize,
+    pub end: usize,
+    /// Set on `Reference` tokens.
+    pub reference: Option<TokenReference>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, TS)]
+#[ts(file_name = "formula_token_kind.ts", rename_all = "camelCase")]
+pub enum FormulaTokenKind {
+    Number,
+    String,
+    Boolean,
+    Error,
+    Reference,
+    Function,
+    Name,
+    Operator,
+    /// `,` between arguments or array items, `;` between array rows.
+    Separator,
+    OpenParen,
+    CloseParen,
+    OpenBrace,
+    CloseBrace,
+    Whitespace,
+    /// Text that can not start any token, like a stray `!`.
+    Unknown,
+}
+
+/// What a reference token points at. Rows and columns are 0-based; a whole
+/// column range has no rows and a whole row range has no columns.
+#[derive(Debug, Clone, PartialEq, Eq, Default, TS)]
+#[ts(file_name = "token_reference.ts", rename_all = "camelCase")]
+pub struct TokenReference {
+    pub workbook: Option<String>,
+    pub sheet1: Option<String>,
+    pub sheet2: Option<String>,
+    pub row1: Option<usize>,
+    pub col1: Option<usize>,
+    pub row2: Option<usize>,
+    pub col2: Option<usize>,
+    /// References spelled the same, ignoring `$` and case, share a color
+    /// index. Indices are handed out in order of first appearance.
+    pub color: usize,
+}
+
+const ERROR_CONSTANTS: [&str; 11] = [
+    "#DIV/0!",
+    "#N/A",
+    "#NAME?",
+    "#NULL!",
+    "#NUM!",
+    "#REF!",
+    "#VALUE!",
+    "#GETTING_DATA",
+    "#PLACEHOLDER",
+    "#KEY",
+    "#FIELD",
+];
+
+/// Split a formula (without the leading `=`) into classified tokens. Unlike
+/// `lex`, this never fails: incomplete input like `SUM(A1,` still produces
+/// tokens, so an editor can highlight while the user types.
+pub fn tokenize(s: &str) -> Vec<FormulaToken> {
+    let bytes = s.as_bytes();
+    let mut tokens = Vec::new();
+    let mut colors: HashMap<String, usize> = HashMap::new();
+    let mut i = 0;
+    while i < s.len() {
+        let c = s[i..].chars().next().unwrap();
+        let (kind, end, reference) = if c.is_whitespace() {
+            let end = scan_while(s, i, char::is_whitespace);
+            (FormulaTokenKind::Whitespace, end, None)
+        } else if c == '"' {
+            (FormulaTokenKind::String, scan_string(s, i), None)
+        } else if let Some((end, reference)) = scan_reference(s, i) {
+            (FormulaTokenKind::Reference, end, Some(reference))
+        } else {
+            match c {
+                '(' => (FormulaTokenKind::OpenParen, i + 1, None),
+                ')' => (FormulaTokenKind::CloseParen, i + 1, None),
+                '{' => (FormulaTokenKind::OpenBrace, i + 1, None),
+                '}' => (FormulaTokenKind::CloseBrace, i + 1, None),
+                ',' | ';' => (FormulaTokenKind::Separator, i + 1, None),
+                '#' => match ERROR_CONSTANTS.iter().find(|e| s[i..].starts_with(*e)) {
+                    Some(&"#FIELD") => (FormulaTokenKind::Function, i + 6, None),
+                    Some(e) => (FormulaTokenKind::Error, i + e.len(), None),
+                    None => (FormulaTokenKind::Unknown, i + 1, None),
+                },
+                '<' if matches!(bytes.get(i + 1), Some(b'>') | Some(b'=')) => {
+                    (FormulaTokenKind::Operator, i + 2, None)
+                }
+                '>' if bytes.get(i + 1) == Some(&b'=') => (FormulaTokenKind::Operator, i + 2, None),
+                '+' | '-' | '*' | '/' | '^' | '&' | '=' | '<' | '>' | '%' | '@' | ':' => {
+                    (FormulaTokenKind::Operator, i + 1, None)
+                }
+                '0'..='9' | '.' => (FormulaTokenKind::Number, scan_number(s, i), None),
+                c if c.is_alphabetic() || c == '_' || c == '\\' => {
+                    let end = scan_while(s, i, is_name_char);
+                    let word = &s[i..end];
+                    let kind = if bytes.get(end) == Some(&b'(') {
+                        FormulaTokenKind::Function
+                    } else if word.eq_ignore_ascii_case("TRUE")
+                        || word.eq_ignore_ascii_case("FALSE")
+                    {
+                        FormulaTokenKind::Boolean
+                    } else {
+                        FormulaTokenKind::Name
+                    };
+                    (kind, end, None)
+                }
+                c => (FormulaTokenKind::Unknown, i + c.len_utf8(), None),
+            }
+        };
+        let reference = reference.map(|mut r: TokenReference| {
+            let key = s[i..end].replace('$', "").to_uppercase();
+            let next = colors.len();
+            r.color = *colors.entry(key).or_insert(next);
+            r
+        });
+        tokens.push(FormulaToken {
+            kind,
+            start: i,
+            end,
+            reference,
+        });
+        i = end;
+    }
+    tokens
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '.' || c == '\\'
+}
+
+fn scan_while(s: &str, start: usize, f: impl Fn(char) -> bool) -> usize {
+    s[start..]
+        .char_indices()
+        .find(|(_, c)| !f(*c))
+        .map_or(s.len(), |(idx, _)| start + idx)
+}
+
+/// `start` is at the opening quote. An unterminated string runs to the end.
+fn scan_string(s: &str, start: usize) -> usize {
+    let bytes = s.as_bytes();
+    let mut i = start + 1;
+    while i < bytes.len() {
+        if bytes[i] == b'"' {
+            if bytes.get(i + 1) == Some(&b'"') {
+                i += 2;
+                continue;
+            }
+            return i + 1;
+        }
+        i += 1;
+    }
+    s.len()
+}
+
+fn scan_number(s: &str, start: usize) -> usize {
+    let bytes = s.as_bytes();
+    let mut i = scan_while(s, start, |c| c.is_ascii_digit() || c == '.');
+    if matches!(bytes.get(i), Some(b'e') | Some(b'E')) {
+        let mut j = i + 1;
+        if matches!(bytes.get(j), Some(b'+') | Some(b'-')) {
+            j += 1;
+        }
+        if bytes.get(j).is_some_and(u8::is_ascii_digit) {
+            i = scan_while(s, j, |c| c.is_ascii_digit());
+        }
+    }
+    i
+}
+
+/// Scan a reference starting at `start`, sheet prefix included:
+/// `A1`, `$A$1:B2`, `A:C`, `1:3`, `Sheet1!A1`, `'My sheet'!A1`,
+/// `Sheet1:Sheet3!A1` or `[Book]Sheet1!A1`.
+fn scan_reference(s: &str, start: usize) -> Option<(usize, TokenReference)> {
+    let mut reference = TokenReference::default();
+    let body = match scan_sheet_prefix(s, start, &mut reference) {
+        Some(end) => end,
+        None => start,
+    };
+    let (end, first) = scan_ref_part(s, body)?;
+    let mut end = end;
+    let mut second = None;
+    if s.as_bytes().get(end) == Some(&b':')
+        && let Some((e, part)) = scan_ref_part(s, end + 1)
+    {
+        end = e;
+        second = Some(part);
+    }
+    let next = s[end..].chars().next();
+    if next.is_some_and(|c| is_name_char(c) || c == '(' || c == '!') {
+        return None;
+    }
+    match (first, second) {
+        (RefPart::Cell(r1, c1), None) => {
+            reference.row1 = Some(r1);
+            reference.col1 = Some(c1);
+        }
+        (RefPart::Cell(r1, c1), Some(RefPart::Cell(r2, c2))) => {
+            reference.row1 = Some(r1);
+            reference.col1 = Some(c1);
+            reference.row2 = Some(r2);
+            reference.col2 = Some(c2);
+        }
+        (RefPart::Col(c1), Some(RefPart::Col(c2))) => {
+            reference.col1 = Some(c1);
+            reference.col2 = Some(c2);
+        }
+        (RefPart::Row(r1), Some(RefPart::Row(r2))) => {
+            reference.row1 = Some(r1);
+            reference.row2 = Some(r2);
+        }
+        _ => return None,
+    }
+    Some((end, reference))
+}
+
+enum RefPart {
+    Cell(usize, usize),
+    Col(usize),
+    Row(usize),
+}
+
+/// One side of a reference: `$A$1`, `$A` or `$1`.
+fn scan_ref_part(s: &str, start: usize) -> Option<(usize, RefPart)> {
+    let bytes = s.as_bytes();
+    let mut i = start;
+    if bytes.get(i) == Some(&b'$') {
+        i += 1;
+    }
+    let col_end = scan_while(s, i, |c| c.is_ascii_alphabetic());
+    let col = &s[i..col_end];
+    if col.len() > 3 {
+        return None;
+    }
+    i = col_end;
+    let row_start = if bytes.get(i) == Some(&b'$') && !col.is_empty() {
+        i + 1
+    } else {
+        i
+    };
+    let row_end = scan_while(s, row_start, |c| c.is_ascii_digit());
+    let row = &s[row_start..row_end];
+    let row = if row.is_empty() {
+        None
+    } else {
+        Some(row.parse::<usize>().ok()?.checked_sub(1)?)
+    };
+    match (col.is_empty(), row) {
+        (false, Some(r)) => Some((row_end, RefPart::Cell(r, column_index(col)))),
+        (false, None) if row_start == col_end => Some((col_end, RefPart::Col(column_index(col)))),
+        (true, Some(r)) => Some((row_end, RefPart::Row(r))),
+        _ => None,
+    }
+}
+
+fn column_index(label: &str) -> usize {
+    label.chars().fold(0, |acc, c| {
+        acc * 26 + (c.to_ascii_uppercase() as usize - 'A' as usize + 1)
+    }) - 1
+}
+
+/// Scan `Sheet1!`, `'My sheet'!`, `Sheet1:Sheet3!` or `[Book]Sheet1!` and
+/// return where the reference body starts.
+fn scan_sheet_prefix(s: &str, start: usize, reference: &mut TokenReference) -> Option<usize> {
+    let bytes = s.as_bytes();
+    let (names, end) = if bytes.get(start) == Some(&b'\'') {
+        let mut i = start + 1;
+        let mut name = String::new();
+        loop {
+            let c = s[i..].chars().next()?;
+            if c == '\'' {
+                if bytes.get(i + 1) == Some(&b'\'') {
+                    name.push('\'');
+                    i += 2;
+                    continue;
+                }
+                break;
+            }
+            name.push(c);
+            i += c.len_utf8();
+        }
+        (name, i + 1)
+    } else {
+        let is_sheet_char = |c: char| !c.is_whitespace() && !"+-*/^&=<>%@,;(){}\"'\\?!".contains(c);
+        let end = scan_while(s, start, is_sheet_char);
+        (s[start..end].to_string(), end)
+    };
+    if bytes.get(end) != Some(&b'!') || names.is_empty() {
+        return None;
+    }
+    let mut names = names.as_str();
+    if let Some(rest) = names.strip_prefix('[') {
+        let (book, sheets) = rest.split_once(']')?;
+        reference.workbook = Some(book.to_string());
+        names = sheets;
+    }
+    match names.split_once(':') {
+        Some((sheet1, sheet2)) => {
+            reference.sheet1 = Some(sheet1.to_string());
+            reference.sheet2 = Some(sheet2.to_string());
+        }
+        None => reference.sheet1 = Some(names.to_string()),
+    }
+    Some(end + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(s: &str) -> Vec<(FormulaTokenKind, &str)> {
+        tokenize(s)
+            .into_iter()
+            .map(|t| (t.kind, &s[t.start..t.end]))
+            .collect()
+    }
+
+    #[test]
+    fn classify_tokens() {
+        use FormulaTokenKind::*;
+        assert_eq!(
+            kinds("SUM(A1:B2, 1.5e3)&\"a\"\"b\""),
+            vec![
+                (Function, "SUM"),
+                (OpenParen, "("),
+                (Reference, "A1:B2"),
+                (Separator, ","),
+                (Whitespace, " "),
+                (Number, "1.5e3"),
+                (CloseParen, ")"),
+                (Operator, "&"),
+                (String, "\"a\"\"b\""),
+            ]
+        );
+        assert_eq!(
+            kinds("LOG10(x)>=TRUE"),
+            vec![
+                (Function, "LOG10"),
+                (OpenParen, "("),
+                (Name, "x"),
+                (CloseParen, ")"),
+                (Operator, ">="),
+                (Boolean, "TRUE"),
+            ]
+        );
+        assert_eq!(
+            kinds("{1,2;3,4}+#N/A"),
+            vec![
+                (OpenBrace, "{"),
+                (Number, "1"),
+                (Separator, ","),
+                (Number, "2"),
+                (Separator, ";"),
+                (Number, "3"),
+                (Separator, ","),
+                (Number, "4"),
+                (CloseBrace, "}"),
+                (Operator, "+"),
+                (Error, "#N/A"),
+            ]
+        );
+        // Incomplete input still tokenizes.
+        assert_eq!(
+            kinds("IF(A1,\"ab"),
+            vec![
+                (Function, "IF"),
+                (OpenParen, "("),
+                (Reference, "A1"),
+                (Separator, ","),
+                (String, "\"ab"),
+            ]
+        );
+    }
+
+    #[test]
+    fn references() {
+        let tokens = tokenize("'My ''q'''!$B$2+Sheet1:Sheet3!A:C+[Book]S!3:4+b2+$B2");
+        let refs: Vec<&TokenReference> =
+            tokens.iter().filter_map(|t| t.reference.as_ref()).collect();
+        assert_eq!(refs.len(), 5);
+        assert_eq!(refs[0].sheet1.as_deref(), Some("My 'q'"));
+        assert_eq!((refs[0].row1, refs[0].col1), (Some(1), Some(1)));
+        assert_eq!(refs[1].sheet2.as_deref(), Some("Sheet3"));
+        assert_eq!(
+            (refs[1].col1, refs[1].col2, refs[1].row1),
+            (Some(0), Some(2), None)
+        );
+        assert_eq!(refs[2].workbook.as_deref(), Some("Book"));
+        assert_eq!(
+            (refs[2].row1, refs[2].row2, refs[2].col1),
+            (Some(2), Some(3), None)
+        );
+        // Spelled the same ignoring `$` and case: same color.
+        assert_eq!(refs[0].color, 0);
+        assert_eq!(refs[1].color, 1);
+        assert_eq!(refs[2].color, 2);
+        assert_eq!(refs[3].color, 3);
+        assert_eq!(refs[4].color, 3);
+    }
+}
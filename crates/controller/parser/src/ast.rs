@@ -96,6 +96,11 @@ pub enum InfixOperator {
 pub enum PrefixOperator {
     Minus,
     Plus,
+    /// `@`: legacy implicit intersection. A range used where a single value
+    /// is expected collapses to the cell sharing the formula's row (for a
+    /// column-shaped range) or column (for a row-shaped one). Stored in
+    /// OOXML as `_xlfn.SINGLE(...)`.
+    ImplicitIntersection,
 }
 
 #[derive(Debug, Clone)]
@@ -21,3 +21,15 @@ pub enum ParseError {
     #[error("a range spanning two different blocks is not supported")]
     CrossBlockRange,
 }
+
+/// Why a formula does not parse. `start` and `end` are byte offsets into
+/// the formula text, and `expected` names what would have been accepted
+/// at `start`.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("{message}")]
+pub struct SyntaxError {
+    pub start: usize,
+    pub end: usize,
+    pub expected: Vec<String>,
+    pub message: String,
+}
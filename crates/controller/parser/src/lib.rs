@@ -3,10 +3,13 @@ mod climber;
 pub mod context;
 pub mod errors;
 mod reference;
+mod syntax;
 #[cfg(test)]
 mod test_utils;
 pub mod unparse;
 
+pub use syntax::check_syntax;
+
 #[macro_use]
 extern crate lazy_static;
 use crate::climber::{Assoc, Climber, ClimberBuilder, Operator};
@@ -240,6 +243,7 @@ impl Parser {
                         let op = match pair.as_str().trim() {
                             "-" => ast::Operator::Prefix(ast::PrefixOperator::Minus),
                             "+" => ast::Operator::Prefix(ast::PrefixOperator::Plus),
+                            "@" => ast::Operator::Prefix(ast::PrefixOperator::ImplicitIntersection),
                             _ => ast::Operator::Prefix(ast::PrefixOperator::Minus),
                         };
                         let args = vec![rhs];
@@ -338,8 +342,27 @@ impl Parser {
                 }
             }
             Rule::expression_bracket => {
-                let rule = pair.into_inner().next().unwrap();
-                self.parse_from_pair(rule, curr_sheet, context, true)
+                let members = pair
+                    .into_inner()
+                    .filter(|p| p.as_rule() == Rule::expression)
+                    .collect::<Vec<_>>();
+                if members.len() == 1 {
+                    let rule = members.into_iter().next().unwrap();
+                    return self.parse_from_pair(rule, curr_sheet, context, true);
+                }
+                // `(A1:A5,C1:C5)`: a reference union, the same node a
+                // parenthesized union produces in argument position.
+                let args = members
+                    .into_iter()
+                    .map(|p| self.parse_from_pair(p, curr_sheet, context, false))
+                    .collect();
+                ast::Node {
+                    pure: ast::PureNode::Func(ast::Func {
+                        op: ast::Operator::Comma,
+                        args,
+                    }),
+                    bracket: false,
+                }
             }
             Rule::function_call => {
                 let pure = self.build_func_call(pair, curr_sheet, context);
@@ -414,6 +437,15 @@ impl Parser {
                     return node;
                 }
             }
+            // How OOXML spells the `@` operator. Folding it into the prefix
+            // form keeps one representation in the AST, whichever way the
+            // formula arrived.
+            "_XLFN.SINGLE" if args.len() == 1 => {
+                return ast::PureNode::Func(ast::Func {
+                    op: ast::Operator::Prefix(ast::PrefixOperator::ImplicitIntersection),
+                    args,
+                });
+            }
             _ => {}
         }
 
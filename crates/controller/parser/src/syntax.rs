@@ -0,0 +1,208 @@
+use logisheets_lexer::{FormulaToken, FormulaTokenKind, Rule, tokenize, try_lex};
+use pest::error::{ErrorVariant, InputLocation};
+
+use crate::errors::SyntaxError;
+
+/// Check that a formula (without the leading `=`) parses. If it does not,
+/// explain where and why: unbalanced parentheses and unterminated strings
+/// are reported at the bracket or quote that causes them, anything else at
+/// the first token the grammar can not accept.
+pub fn check_syntax(f: &str) -> Result<(), SyntaxError> {
+    let err = match try_lex(f) {
+        Ok(_) => return Ok(()),
+        Err(e) => e,
+    };
+    if f.trim().is_empty() {
+        return Err(SyntaxError {
+            start: 0,
+            end: f.len(),
+            expected: vec![String::from("an expression")],
+            message: String::from("The formula is empty"),
+        });
+    }
+    let tokens = tokenize(f);
+    check_balance(f, &tokens)?;
+
+    let pos = match err.location {
+        InputLocation::Pos(p) => p,
+        InputLocation::Span((s, _)) => s,
+    };
+    let expected = match &err.variant {
+        ErrorVariant::ParsingError { positives, .. } => describe_rules(positives),
+        ErrorVariant::CustomError { .. } => vec![],
+    };
+    let wanted = if expected.is_empty() {
+        String::new()
+    } else {
+        format!(", expected {}", expected.join(" or "))
+    };
+    let token = tokens
+        .iter()
+        .find(|t| t.start <= pos && pos < t.end && t.kind != FormulaTokenKind::Whitespace);
+    let error = match token {
+        Some(t) => SyntaxError {
+            start: t.start,
+            end: t.end,
+            message: format!("Unexpected '{}'{}", &f[t.start..t.end], wanted),
+            expected,
+        },
+        None => SyntaxError {
+            start: pos,
+            end: f.len(),
+            message: format!("The formula ends unexpectedly{}", wanted),
+            expected,
+        },
+    };
+    Err(error)
+}
+
+fn check_balance(f: &str, tokens: &[FormulaToken]) -> Result<(), SyntaxError> {
+    let mut open: Vec<&FormulaToken> = Vec::new();
+    for t in tokens {
+        match t.kind {
+            FormulaTokenKind::String => {
+                let s = &f[t.start..t.end];
+                if s.len() == 1 || !s.ends_with('"') {
+                    return Err(SyntaxError {
+                        start: t.start,
+                        end: t.end,
+                        expected: vec![String::from("'\"'")],
+                        message: String::from("The text is missing its closing quotation mark"),
+                    });
+                }
+            }
+            FormulaTokenKind::OpenParen | FormulaTokenKind::OpenBrace => open.push(t),
+            FormulaTokenKind::CloseParen | FormulaTokenKind::CloseBrace => {
+                let want = if t.kind == FormulaTokenKind::CloseParen {
+                    FormulaTokenKind::OpenParen
+                } else {
+                    FormulaTokenKind::OpenBrace
+                };
+                match open.pop() {
+                    Some(o) if o.kind == want => {}
+                    Some(o) => return Err(unclosed(o)),
+                    None => {
+                        return Err(SyntaxError {
+                            start: t.start,
+                            end: t.end,
+                            expected: vec![],
+                            message: format!(
+                                "'{}' has no matching opening bracket",
+                                &f[t.start..t.end]
+                            ),
+                        });
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    match open.pop() {
+        Some(o) => Err(unclosed(o)),
+        None => Ok(()),
+    }
+}
+
+fn unclosed(open: &FormulaToken) -> SyntaxError {
+    let (close, what) = if open.kind == FormulaTokenKind::OpenParen {
+        ("')'", "parenthesis")
+    } else {
+        ("'}'", "brace")
+    };
+    SyntaxError {
+        start: open.start,
+        end: open.end,
+        expected: vec![String::from(close)],
+        message: format!("This {} is never closed", what),
+    }
+}
+
+fn describe_rules(rules: &[Rule]) -> Vec<String> {
+    let mut result: Vec<String> = Vec::new();
+    for rule in rules {
+        let desc = match rule {
+            Rule::EOI => "the end of the formula",
+            Rule::exponent_part => continue,
+            Rule::comma => "','",
+            Rule::space_op
+            | Rule::colon_op
+            | Rule::multiply_op
+            | Rule::div_op
+            | Rule::plus_op
+            | Rule::minus_op
+            | Rule::concat_op
+            | Rule::eq_op
+            | Rule::neq_op
+            | Rule::gt_op
+            | Rule::ge_op
+            | Rule::lt_op
+            | Rule::le_op
+            | Rule::exp_op
+            | Rule::percent_op => "an operator",
+            Rule::cell_reference
+            | Rule::a1_reference
+            | Rule::a1_column
+            | Rule::r1c1_reference
+            | Rule::sheet_name => "a reference",
+            Rule::name_characters => "a name",
+            _ => "an expression",
+        };
+        if !result.iter().any(|r| r == desc) {
+            result.push(desc.to_string());
+        }
+    }
+    // References and names are expressions too.
+    if result.iter().any(|r| r == "an expression") {
+        result.retain(|r| r != "a reference" && r != "a name");
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::check_syntax;
+
+    fn err(f: &str) -> (usize, usize, String) {
+        let e = check_syntax(f).unwrap_err();
+        (e.start, e.end, e.message)
+    }
+
+    #[test]
+    fn syntax_errors_have_spans() {
+        assert!(check_syntax("SUM(A1, 2)").is_ok());
+        assert_eq!(err("SUM((1+2)").0, 3);
+        assert_eq!(err("SUM((1+2)").2, "This parenthesis is never closed");
+        assert_eq!(
+            err("1+2)"),
+            (3, 4, String::from("')' has no matching opening bracket"))
+        );
+        assert_eq!(
+            err("{1,2"),
+            (0, 1, String::from("This brace is never closed"))
+        );
+        assert_eq!(
+            err("\"abc"),
+            (
+                0,
+                4,
+                String::from("The text is missing its closing quotation mark")
+            )
+        );
+        assert_eq!(err("  ").2, "The formula is empty");
+
+        let e = check_syntax("1+").unwrap_err();
+        assert_eq!((e.start, e.end), (2, 2));
+        assert_eq!(e.expected, vec!["an expression"]);
+        assert_eq!(
+            e.message,
+            "The formula ends unexpectedly, expected an expression"
+        );
+
+        let e = check_syntax("1 +* 2").unwrap_err();
+        assert_eq!((e.start, e.end), (3, 4));
+        assert_eq!(e.message, "Unexpected '*', expected an expression");
+
+        let e = check_syntax("Sheet1!").unwrap_err();
+        assert_eq!(e.expected, vec!["a reference", "a name"]);
+    }
+}
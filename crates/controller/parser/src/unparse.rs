@@ -222,6 +222,13 @@ impl Stringify for Func {
                         });
                 Ok(format!("{}({})", func_name, args_str))
             }
+            // The intersection operator is the space itself, so it takes no
+            // padding of its own.
+            Operator::Infix(InfixOperator::Space) => Ok(format!(
+                "{} {}",
+                args.first().unwrap().unparse(fetcher, curr_sheet, shift)?,
+                args.get(1).unwrap().unparse(fetcher, curr_sheet, shift)?,
+            )),
             Operator::Infix(op) => {
                 let r = format!(
                     "{} {} {}",
@@ -236,6 +243,14 @@ impl Stringify for Func {
                 args.get(0).unwrap().unparse(fetcher, curr_sheet, shift)?,
                 op.unparse(fetcher, curr_sheet, shift)?,
             )),
+            Operator::Prefix(PrefixOperator::ImplicitIntersection)
+                if fetcher.implicit_intersection_as_function() =>
+            {
+                Ok(format!(
+                    "_xlfn.SINGLE({})",
+                    args.first().unwrap().unparse(fetcher, curr_sheet, shift)?,
+                ))
+            }
             Operator::Prefix(op) => Ok(format!(
                 "{}{}",
                 op.unparse(fetcher, curr_sheet, shift)?,
@@ -564,6 +579,7 @@ impl Stringify for PrefixOperator {
         let s = match self {
             PrefixOperator::Minus => String::from("-"),
             PrefixOperator::Plus => String::from("+"),
+            PrefixOperator::ImplicitIntersection => String::from("@"),
         };
         Ok(s)
     }
@@ -622,6 +638,34 @@ mod tests {
         assert_eq!(a, "(1 + 2) ^ 3");
     }
 
+    #[test]
+    fn reference_operators_roundtrip() {
+        let parser = Parser {};
+        let mut id_fetcher = TestIdFetcher {};
+        let mut vertex_fetcher = TestVertexFetcher {};
+        // The test fetcher cannot resolve ranges, so constants stand in for
+        // the references these operators normally take.
+        let cases = [
+            ("SUM((1,2) 3)", "SUM((1, 2) 3)"),
+            ("(1,2)", "(1, 2)"),
+            ("@1", "@1"),
+            // The OOXML spelling folds into the operator.
+            ("_xlfn.SINGLE(2)+1", "@2 + 1"),
+        ];
+        for (input, expected) in cases {
+            let node = {
+                let mut context = Context {
+                    book_name: "book",
+                    id_fetcher: &mut id_fetcher,
+                    vertex_fetcher: &mut vertex_fetcher,
+                };
+                parser.parse(input, 1, &mut context).unwrap()
+            };
+            let a = unparse(&node, &mut id_fetcher, 0).unwrap();
+            assert_eq!(a, expected);
+        }
+    }
+
     #[test]
     fn brakcet_test() {
         let parser = Parser {};
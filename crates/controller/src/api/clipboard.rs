@@ -0,0 +1,863 @@
+//! Clipboard copy, cut and paste-special.
+//!
+//! [`Workbook::copy_range`] snapshots a block into a [`ClipboardData`]: the
+//! values, the formula text as written at the source, the full style of every
+//! cell, the column widths and the merges. The payload is self-contained and
+//! serializable, so the host may keep it between copy and paste, or paste it
+//! into another sheet.
+//!
+//! [`Workbook::paste_payloads`] turns a clipboard into ordinary edit payloads;
+//! [`Workbook::paste`] applies them as one undoable `PayloadsAction`, so a
+//! paste is a single undo step whatever it touches.
+//!
+//! ## Reference shifting
+//!
+//! The clipboard does not hold a live source, so formulas are shifted on
+//! their text: each reference token found by the lexer has its relative
+//! components moved by the distance between source and target cell. A
+//! reference pushed off the grid becomes `#REF!`, as in Excel. Cut-paste
+//! does not shift at all: it is a `MoveRange` of the live source cells, so
+//! references into the cut block follow them everywhere in the workbook and
+//! references out of it stay put.
+
+use gents_derives::TS;
+use logisheets_base::CellId;
+use logisheets_lexer::{TokenReference, tokenize};
+
+use crate::edit_action::{
+    ActionEffect, CellInput, EditAction, EditPayload, MergeCells, MoveRange, PayloadsAction,
+    SetCellStyles, SetColWidth,
+};
+use crate::errors::Error;
+use crate::navigator::sheet_nav::{MAX_COL_CNT, MAX_ROW_CNT};
+use crate::style_manager::RawStyle;
+use crate::{MergeCell, Value};
+
+use super::{Workbook, Worksheet};
+
+/// A copied block of cells.
+#[derive(Debug, Clone, TS)]
+#[ts(file_name = "clipboard_data.ts", rename_all = "camelCase")]
+pub struct ClipboardData {
+    pub sheet_idx: usize,
+    pub start_row: usize,
+    pub start_col: usize,
+    pub row_count: usize,
+    pub col_count: usize,
+    /// Row-major, `row_count * col_count` cells.
+    pub cells: Vec<ClipboardCell>,
+    /// The width of each copied column, `None` where it has the default.
+    pub col_widths: Vec<Option<f64>>,
+    /// Merged ranges inside the block, relative to its top-left cell.
+    pub merges: Vec<MergeCell>,
+    /// Set by `cut_range`: pasting moves the cells instead of copying them.
+    pub cut: bool,
+}
+
+#[derive(Debug, Clone, TS)]
+#[ts(file_name = "clipboard_cell.ts", rename_all = "camelCase")]
+pub struct ClipboardCell {
+    /// The value, the last computed one for a formula.
+    pub value: Value,
+    /// The formula, without `=`, as it reads at the source cell.
+    pub formula: Option<String>,
+    pub style: RawStyle,
+}
+
+impl ClipboardCell {
+    fn is_blank(&self) -> bool {
+        self.formula.is_none() && matches!(self.value, Value::Empty)
+    }
+}
+
+/// What part of the copied cells a paste writes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, TS)]
+#[ts(file_name = "paste_content.ts", rename_all = "camelCase")]
+pub enum PasteContent {
+    /// Contents, formats and merges.
+    #[default]
+    All,
+    /// Computed values; formulas are not pasted.
+    Values,
+    /// Formulas, and the values of cells without one.
+    Formulas,
+    /// Formats and merges.
+    Formats,
+    /// Only the widths of the copied columns.
+    ColumnWidths,
+}
+
+/// Arithmetic that combines pasted numbers with the destination.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, TS)]
+#[ts(file_name = "paste_operation.ts", rename_all = "camelCase")]
+pub enum PasteOperation {
+    #[default]
+    None,
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+}
+
+impl PasteOperation {
+    fn symbol(self) -> &'static str {
+        match self {
+            PasteOperation::None => "",
+            PasteOperation::Add => "+",
+            PasteOperation::Subtract => "-",
+            PasteOperation::Multiply => "*",
+            PasteOperation::Divide => "/",
+        }
+    }
+}
+
+/// Paste-special options. The default is a plain paste.
+#[derive(Debug, Clone, Default, TS)]
+#[ts(file_name = "paste_options.ts", rename_all = "camelCase")]
+pub struct PasteOptions {
+    pub content: PasteContent,
+    pub operation: PasteOperation,
+    /// Leave the destination alone where the copied cell is blank.
+    pub skip_blanks: bool,
+    /// Paste rows as columns.
+    pub transpose: bool,
+}
+
+impl Workbook {
+    /// Copy the block `start_row..=end_row` x `start_col..=end_col`.
+    pub fn copy_range(
+        &self,
+        sheet_idx: usize,
+        start_row: usize,
+        start_col: usize,
+        end_row: usize,
+        end_col: usize,
+    ) -> crate::errors::Result<ClipboardData> {
+        if end_row < start_row || end_col < start_col {
+            return Err(Error::PayloadError("empty copy range".to_string()));
+        }
+        let ws = self.get_sheet_by_idx(sheet_idx)?;
+        let mut cells = Vec::with_capacity((end_row - start_row + 1) * (end_col - start_col + 1));
+        for row in start_row..=end_row {
+            for col in start_col..=end_col {
+                let cell_id = ws.cell_id(row, col)?;
+                let formula = if ws.has_formula(&cell_id) {
+                    Some(ws.get_formula_by_id(&cell_id)?)
+                } else {
+                    None
+                };
+                cells.push(ClipboardCell {
+                    value: ws.get_value_by_id(&cell_id)?,
+                    formula,
+                    style: ws.get_raw_style_by_id(&cell_id)?,
+                });
+            }
+        }
+        let col_widths = (start_col..=end_col)
+            .map(|col| {
+                let status = &ws.controller.status;
+                let col_id = status.navigator.fetch_col_id(&ws.sheet_id, col).ok()?;
+                let info = status
+                    .container
+                    .data
+                    .get(&ws.sheet_id)?
+                    .col_info
+                    .get_col_info(col_id)?;
+                info.width.filter(|_| info.custom_width)
+            })
+            .collect();
+        // Only merges wholly inside the block travel with it.
+        let merges = ws
+            .get_merged_cells(start_row, start_col, end_row, end_col)
+            .into_iter()
+            .filter(|m| {
+                m.start_row >= start_row
+                    && m.start_col >= start_col
+                    && m.end_row <= end_row
+                    && m.end_col <= end_col
+            })
+            .map(|m| MergeCell {
+                start_row: m.start_row - start_row,
+                start_col: m.start_col - start_col,
+                end_row: m.end_row - start_row,
+                end_col: m.end_col - start_col,
+            })
+            .collect();
+        Ok(ClipboardData {
+            sheet_idx,
+            start_row,
+            start_col,
+            row_count: end_row - start_row + 1,
+            col_count: end_col - start_col + 1,
+            cells,
+            col_widths,
+            merges,
+            cut: false,
+        })
+    }
+
+    /// Like [`copy_range`](Self::copy_range), but pasting the result moves
+    /// the cells. Nothing changes until the paste.
+    pub fn cut_range(
+        &self,
+        sheet_idx: usize,
+        start_row: usize,
+        start_col: usize,
+        end_row: usize,
+        end_col: usize,
+    ) -> crate::errors::Result<ClipboardData> {
+        let mut data = self.copy_range(sheet_idx, start_row, start_col, end_row, end_col)?;
+        data.cut = true;
+        Ok(data)
+    }
+
+    /// The payloads that paste `data` with its top-left cell at `(row, col)`.
+    /// They belong in one transaction; [`paste`](Self::paste) does that.
+    ///
+    /// A cut is always pasted whole and `options` are ignored for it, as in
+    /// Excel.
+    pub fn paste_payloads(
+        &self,
+        data: &ClipboardData,
+        sheet_idx: usize,
+        row: usize,
+        col: usize,
+        options: &PasteOptions,
+    ) -> crate::errors::Result<Vec<EditPayload>> {
+        if data.cells.len() != data.row_count * data.col_count {
+            return Err(Error::PayloadError(
+                "clipboard cells do not match its size".to_string(),
+            ));
+        }
+        let (rows, cols) = if options.transpose && !data.cut {
+            (data.col_count, data.row_count)
+        } else {
+            (data.row_count, data.col_count)
+        };
+        if row + rows > MAX_ROW_CNT as usize || col + cols > MAX_COL_CNT as usize {
+            return Err(Error::PayloadError(
+                "paste area is outside the sheet".to_string(),
+            ));
+        }
+        if data.cut {
+            self.move_payloads(data, sheet_idx, row, col)
+        } else {
+            self.copy_payloads(data, sheet_idx, row, col, options)
+        }
+    }
+
+    /// Paste `data` at `(row, col)` as a single undoable transaction.
+    pub fn paste(
+        &mut self,
+        data: &ClipboardData,
+        sheet_idx: usize,
+        row: usize,
+        col: usize,
+        options: &PasteOptions,
+    ) -> crate::errors::Result<ActionEffect> {
+        let payloads = self.paste_payloads(data, sheet_idx, row, col, options)?;
+        Ok(self.handle_action(EditAction::Payloads(PayloadsAction {
+            payloads,
+            undoable: true,
+            init: false,
+        })))
+    }
+
+    fn copy_payloads(
+        &self,
+        data: &ClipboardData,
+        sheet_idx: usize,
+        row: usize,
+        col: usize,
+        options: &PasteOptions,
+    ) -> crate::errors::Result<Vec<EditPayload>> {
+        let ws = self.get_sheet_by_idx(sheet_idx)?;
+        let mut payloads = Vec::new();
+        if options.content == PasteContent::ColumnWidths {
+            for (i, width) in data.col_widths.iter().enumerate() {
+                if let Some(width) = width {
+                    payloads.push(
+                        SetColWidth {
+                            sheet_idx,
+                            col: col + i,
+                            width: *width,
+                        }
+                        .into(),
+                    );
+                }
+            }
+            return Ok(payloads);
+        }
+        let writes_content = matches!(
+            options.content,
+            PasteContent::All | PasteContent::Values | PasteContent::Formulas
+        );
+        let writes_format = matches!(options.content, PasteContent::All | PasteContent::Formats);
+        let cols = if options.transpose {
+            data.row_count
+        } else {
+            data.col_count
+        };
+        let mut styles = vec![None; data.cells.len()];
+        for r in 0..data.row_count {
+            for c in 0..data.col_count {
+                let cell = &data.cells[r * data.col_count + c];
+                if options.skip_blanks && cell.is_blank() {
+                    continue;
+                }
+                let (dst_row, dst_col) = if options.transpose {
+                    (row + c, col + r)
+                } else {
+                    (row + r, col + c)
+                };
+                if writes_content {
+                    let drow = dst_row as i64 - (data.start_row + r) as i64;
+                    let dcol = dst_col as i64 - (data.start_col + c) as i64;
+                    let formula = match (&cell.formula, options.content) {
+                        (Some(f), PasteContent::All | PasteContent::Formulas) => {
+                            Some(shift_formula(f, drow, dcol))
+                        }
+                        _ => None,
+                    };
+                    let content = match options.operation {
+                        PasteOperation::None => Some(match formula {
+                            Some(f) => format!("={}", f),
+                            None => value_to_input(&cell.value),
+                        }),
+                        op => combine(&ws, dst_row, dst_col, cell, formula, op)?,
+                    };
+                    if let Some(content) = content {
+                        payloads.push(
+                            CellInput {
+                                sheet_idx,
+                                row: dst_row,
+                                col: dst_col,
+                                content,
+                                skip_recognition: Some(true),
+                            }
+                            .into(),
+                        );
+                    }
+                }
+                if writes_format {
+                    styles[(dst_row - row) * cols + dst_col - col] = Some(cell.style.clone());
+                }
+            }
+        }
+        if writes_format {
+            payloads.push(
+                SetCellStyles {
+                    sheet_idx,
+                    start_row: row,
+                    start_col: col,
+                    col_count: cols,
+                    styles,
+                }
+                .into(),
+            );
+            for m in &data.merges {
+                let (start_row, start_col, end_row, end_col) = if options.transpose {
+                    (m.start_col, m.start_row, m.end_col, m.end_row)
+                } else {
+                    (m.start_row, m.start_col, m.end_row, m.end_col)
+                };
+                payloads.push(
+                    MergeCells {
+                        sheet_idx,
+                        start_row: row + start_row,
+                        start_col: col + start_col,
+                        end_row: row + end_row,
+                        end_col: col + end_col,
+                    }
+                    .into(),
+                );
+            }
+        }
+        Ok(payloads)
+    }
+
+    fn move_payloads(
+        &self,
+        data: &ClipboardData,
+        sheet_idx: usize,
+        row: usize,
+        col: usize,
+    ) -> crate::errors::Result<Vec<EditPayload>> {
+        Ok(vec![
+            MoveRange {
+                sheet_idx: data.sheet_idx,
+                start_row: data.start_row,
+                start_col: data.start_col,
+                end_row: data.start_row + data.row_count - 1,
+                end_col: data.start_col + data.col_count - 1,
+                dest_sheet_idx: sheet_idx,
+                dest_row: row,
+                dest_col: col,
+            }
+            .into(),
+        ])
+    }
+}
+
+impl<'a> Worksheet<'a> {
+    fn cell_id(&self, row: usize, col: usize) -> crate::errors::Result<CellId> {
+        Ok(self
+            .controller
+            .status
+            .navigator
+            .fetch_cell_id(&self.sheet_id, row, col)?)
+    }
+}
+
+/// The input that combines a pasted cell with the destination under `op`, or
+/// `None` to leave the destination as it is. Numbers are combined directly;
+/// when either side is a formula the result is a formula, `=(dst)+(src)`.
+/// Blank copied cells change nothing and text is pasted over the destination,
+/// as in Excel.
+fn combine(
+    ws: &Worksheet,
+    row: usize,
+    col: usize,
+    cell: &ClipboardCell,
+    formula: Option<String>,
+    op: PasteOperation,
+) -> crate::errors::Result<Option<String>> {
+    let src = match (formula, &cell.value) {
+        (Some(f), _) => f,
+        (None, Value::Empty) => return Ok(None),
+        (None, Value::Number(n)) => format_number(*n),
+        (None, v) => return Ok(Some(value_to_input(v))),
+    };
+    let cell_id = ws.cell_id(row, col)?;
+    let dst = if ws.has_formula(&cell_id) {
+        ws.get_formula_by_id(&cell_id)?
+    } else {
+        match ws.get_value_by_id(&cell_id)? {
+            Value::Number(n) => format_number(n),
+            Value::Empty => "0".to_string(),
+            _ => return Ok(None),
+        }
+    };
+    if let (Ok(a), Ok(b)) = (dst.parse::<f64>(), src.parse::<f64>()) {
+        let v = match op {
+            PasteOperation::Add => a + b,
+            PasteOperation::Subtract => a - b,
+            PasteOperation::Multiply => a * b,
+            PasteOperation::Divide if b == 0. => return Ok(Some("=#DIV/0!".to_string())),
+            PasteOperation::Divide => a / b,
+            PasteOperation::None => b,
+        };
+        return Ok(Some(format_number(v)));
+    }
+    Ok(Some(format!("=({}){}({})", dst, op.symbol(), src)))
+}
+
+/// The input that stores `v` back as it is. Text that would be read as
+/// something else is written behind a `'`.
+fn value_to_input(v: &Value) -> String {
+    match v {
+        Value::Str(s) => {
+            let ambiguous = s.starts_with('=')
+                || s.starts_with('\'')
+                || s.trim() != s
+                || s.parse::<f64>().is_ok()
+                || s.eq_ignore_ascii_case("TRUE")
+                || s.eq_ignore_ascii_case("FALSE");
+            if ambiguous {
+                format!("'{}", s)
+            } else {
+                s.clone()
+            }
+        }
+        Value::Bool(b) => if *b { "TRUE" } else { "FALSE" }.to_string(),
+        Value::Number(n) => format_number(*n),
+        Value::Error(s) => s.clone(),
+        Value::Empty => String::new(),
+    }
+}
+
+fn format_number(n: f64) -> String {
+    format!("{}", n)
+}
+
+/// Shift the relative components of every reference in `formula` by `drow`
+/// rows and `dcol` columns. A reference that leaves the grid becomes `#REF!`.
+pub(crate) fn shift_formula(formula: &str, drow: i64, dcol: i64) -> String {
+    rewrite_references(formula, |_, text| {
+        let (prefix, mut parts) = split_reference(text)?;
+        for part in parts.iter_mut() {
+            if !part.shift(drow, dcol, false) {
+                return Some("#REF!".to_string());
+            }
+        }
+        Some(format!("{}{}", prefix, join_parts(&parts)))
+    })
+}
+
+/// Replace every reference token of `formula` for which `f` returns a new
+/// spelling. `f` gets the token's reference and its text.
+pub(super) fn rewrite_references<F>(formula: &str, mut f: F) -> String
+where
+    F: FnMut(&TokenReference, &str) -> Option<String>,
+{
+    let mut out = String::with_capacity(formula.len());
+    let mut last = 0;
+    for token in tokenize(formula) {
+        let Some(reference) = &token.reference else {
+            continue;
+        };
+        if let Some(text) = f(reference, &formula[token.start..token.end]) {
+            out.push_str(&formula[last..token.start]);
+            out.push_str(&text);
+            last = token.end;
+        }
+    }
+    out.push_str(&formula[last..]);
+    out
+}
+
+/// One side of an A1 reference, such as `$A$1`, `B` or `$3`. Each
+/// coordinate carries whether it is absolute.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct RefPart {
+    pub(super) col: Option<(usize, bool)>,
+    pub(super) row: Option<(usize, bool)>,
+}
+
+impl RefPart {
+    fn parse(s: &str) -> Option<RefPart> {
+        let (col_abs, s) = match s.strip_prefix('$') {
+            Some(rest) => (true, rest),
+            None => (false, s),
+        };
+        let letters = s.bytes().take_while(u8::is_ascii_alphabetic).count();
+        let (letters, s) = s.split_at(letters);
+        let (row_abs, digits) = match s.strip_prefix('$') {
+            Some(rest) => (true, rest),
+            None => (false, s),
+        };
+        if !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        let col = if letters.is_empty() {
+            None
+        } else {
+            let label = letters.to_ascii_uppercase();
+            Some((logisheets_base::column_label_to_index(&label), col_abs))
+        };
+        let row = if digits.is_empty() {
+            None
+        } else {
+            let abs = if letters.is_empty() { col_abs } else { row_abs };
+            Some((digits.parse::<usize>().ok()?.checked_sub(1)?, abs))
+        };
+        if col.is_none() && row.is_none() {
+            return None;
+        }
+        Some(RefPart { col, row })
+    }
+
+    /// Move the relative coordinates, and the absolute ones too when
+    /// `absolute` is set. Returns `false` when the result leaves the grid.
+    fn shift(&mut self, drow: i64, dcol: i64, absolute: bool) -> bool {
+        fn step(coord: &mut Option<(usize, bool)>, d: i64, max: u32, absolute: bool) -> bool {
+            match coord {
+                Some((v, abs)) if absolute || !*abs => {
+                    let moved = *v as i64 + d;
+                    if moved < 0 || moved >= max as i64 {
+                        return false;
+                    }
+                    *v = moved as usize;
+                    true
+                }
+                _ => true,
+            }
+        }
+        step(&mut self.row, drow, MAX_ROW_CNT, absolute)
+            && step(&mut self.col, dcol, MAX_COL_CNT, absolute)
+    }
+
+    pub(super) fn write(&self, out: &mut String) {
+        if let Some((col, abs)) = self.col {
+            if abs {
+                out.push('$');
+            }
+            out.push_str(&logisheets_base::index_to_column_label(col));
+        }
+        if let Some((row, abs)) = self.row {
+            if abs {
+                out.push('$');
+            }
+            out.push_str(&(row + 1).to_string());
+        }
+    }
+}
+
+/// Split a reference token into its sheet prefix, `!` included, and its
+/// one or two parts.
+pub(super) fn split_reference(text: &str) -> Option<(&str, Vec<RefPart>)> {
+    let body = text.rfind('!').map_or(0, |i| i + 1);
+    let (prefix, body) = text.split_at(body);
+    let parts = body
+        .split(':')
+        .map(RefPart::parse)
+        .collect::<Option<Vec<_>>>()?;
+    Some((prefix, parts))
+}
+
+fn join_parts(parts: &[RefPart]) -> String {
+    let mut out = String::new();
+    for (i, part) in parts.iter().enumerate() {
+        if i > 0 {
+            out.push(':');
+        }
+        part.write(&mut out);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PasteContent, PasteOperation, PasteOptions, shift_formula};
+    use crate::edit_action::{
+        CellInput, CellStyleUpdate, CreateSheet, EditAction, EditPayload, PayloadsAction,
+        SetColWidth, StyleUpdateType,
+    };
+    use crate::{Value, Workbook};
+
+    fn apply(wb: &mut Workbook, payload: EditPayload) {
+        wb.handle_action(EditAction::Payloads(PayloadsAction {
+            payloads: vec![payload],
+            undoable: true,
+            init: false,
+        }));
+    }
+
+    fn input(wb: &mut Workbook, row: usize, col: usize, content: &str) {
+        apply(
+            wb,
+            EditPayload::CellInput(CellInput {
+                sheet_idx: 0,
+                row,
+                col,
+                content: content.to_string(),
+                ..Default::default()
+            }),
+        );
+    }
+
+    fn value(wb: &Workbook, row: usize, col: usize) -> Value {
+        wb.get_sheet_by_idx(0).unwrap().get_value(row, col).unwrap()
+    }
+
+    fn num(wb: &Workbook, row: usize, col: usize) -> f64 {
+        match value(wb, row, col) {
+            Value::Number(n) => n,
+            v => panic!("expected a number, got {:?}", v),
+        }
+    }
+
+    fn text(wb: &Workbook, row: usize, col: usize) -> String {
+        match value(wb, row, col) {
+            Value::Str(s) => s,
+            v => panic!("expected text, got {:?}", v),
+        }
+    }
+
+    fn formula(wb: &Workbook, sheet_idx: usize, row: usize, col: usize) -> String {
+        wb.get_sheet_by_idx(sheet_idx)
+            .unwrap()
+            .get_formula(row, col)
+            .unwrap()
+    }
+
+    fn paste(
+        wb: &mut Workbook,
+        data: &super::ClipboardData,
+        row: usize,
+        col: usize,
+        o: PasteOptions,
+    ) {
+        wb.paste(data, 0, row, col, &o).unwrap();
+    }
+
+    #[test]
+    fn shift_references() {
+        assert_eq!(shift_formula("A1 + $B$2 + C$3", 1, 2), "C2 + $B$2 + E$3");
+        assert_eq!(
+            shift_formula("SUM(Sheet2!A1:B2)", 2, 0),
+            "SUM(Sheet2!A3:B4)"
+        );
+        assert_eq!(shift_formula("$A1:A$1", 1, 1), "$A2:B$1");
+        assert_eq!(shift_formula("\"A1\" & A:A", 0, 1), "\"A1\" & B:B");
+        assert_eq!(shift_formula("A1 * 2", -1, 0), "#REF! * 2");
+    }
+
+    #[test]
+    fn paste_shifts_formulas() {
+        let mut wb = Workbook::default();
+        input(&mut wb, 0, 0, "1");
+        input(&mut wb, 1, 0, "2");
+        input(&mut wb, 0, 1, "=A1+$A$2");
+        let data = wb.copy_range(0, 0, 1, 0, 1).unwrap();
+        paste(&mut wb, &data, 1, 1, PasteOptions::default());
+        assert_eq!(formula(&wb, 0, 1, 1), "A2 + $A$2");
+        assert_eq!(num(&wb, 1, 1), 4.);
+    }
+
+    #[test]
+    fn paste_is_one_undo_step() {
+        let mut wb = Workbook::default();
+        input(&mut wb, 0, 0, "1");
+        input(&mut wb, 1, 0, "2");
+        input(&mut wb, 0, 3, "old");
+        let data = wb.copy_range(0, 0, 0, 1, 0).unwrap();
+        paste(&mut wb, &data, 0, 3, PasteOptions::default());
+        assert_eq!(num(&wb, 1, 3), 2.);
+        assert!(wb.undo());
+        assert_eq!(text(&wb, 0, 3), "old");
+        assert!(matches!(value(&wb, 1, 3), Value::Empty));
+    }
+
+    #[test]
+    fn paste_values_keeps_text() {
+        let mut wb = Workbook::default();
+        input(&mut wb, 0, 0, "3");
+        input(&mut wb, 0, 1, "=A1*2");
+        input(&mut wb, 0, 2, "'001");
+        let data = wb.copy_range(0, 0, 1, 0, 2).unwrap();
+        let options = PasteOptions {
+            content: PasteContent::Values,
+            ..Default::default()
+        };
+        paste(&mut wb, &data, 2, 1, options);
+        assert_eq!(num(&wb, 2, 1), 6.);
+        assert_eq!(formula(&wb, 0, 2, 1), "");
+        assert_eq!(text(&wb, 2, 2), "001");
+    }
+
+    #[test]
+    fn paste_transpose_and_skip_blanks() {
+        let mut wb = Workbook::default();
+        input(&mut wb, 0, 0, "1");
+        input(&mut wb, 0, 2, "3");
+        input(&mut wb, 1, 4, "9");
+        let data = wb.copy_range(0, 0, 0, 0, 2).unwrap();
+        let options = PasteOptions {
+            transpose: true,
+            skip_blanks: true,
+            ..Default::default()
+        };
+        paste(&mut wb, &data, 0, 4, options);
+        assert_eq!(num(&wb, 0, 4), 1.);
+        assert_eq!(num(&wb, 1, 4), 9.);
+        assert_eq!(num(&wb, 2, 4), 3.);
+    }
+
+    #[test]
+    fn paste_operations() {
+        let mut wb = Workbook::default();
+        input(&mut wb, 0, 0, "2");
+        input(&mut wb, 0, 1, "10");
+        input(&mut wb, 0, 2, "=B1+1");
+        input(&mut wb, 0, 3, "text");
+        let data = wb.copy_range(0, 0, 0, 0, 0).unwrap();
+        let op = |operation| PasteOptions {
+            operation,
+            ..Default::default()
+        };
+        paste(&mut wb, &data, 0, 1, op(PasteOperation::Multiply));
+        assert_eq!(num(&wb, 0, 1), 20.);
+        paste(&mut wb, &data, 0, 2, op(PasteOperation::Subtract));
+        assert_eq!(formula(&wb, 0, 0, 2), "(B1 + 1) - 2");
+        assert_eq!(num(&wb, 0, 2), 19.);
+        paste(&mut wb, &data, 0, 3, op(PasteOperation::Add));
+        assert_eq!(text(&wb, 0, 3), "text");
+    }
+
+    #[test]
+    fn paste_formats_and_column_widths() {
+        let mut wb = Workbook::default();
+        input(&mut wb, 0, 0, "1");
+        input(&mut wb, 0, 1, "keep");
+        apply(
+            &mut wb,
+            EditPayload::CellStyleUpdate(CellStyleUpdate {
+                sheet_idx: 0,
+                row: 0,
+                col: 0,
+                ty: StyleUpdateType {
+                    set_font_bold: Some(true),
+                    ..Default::default()
+                },
+            }),
+        );
+        apply(
+            &mut wb,
+            EditPayload::SetColWidth(SetColWidth {
+                sheet_idx: 0,
+                col: 0,
+                width: 30.,
+            }),
+        );
+        let data = wb.copy_range(0, 0, 0, 0, 0).unwrap();
+        let formats = PasteOptions {
+            content: PasteContent::Formats,
+            ..Default::default()
+        };
+        paste(&mut wb, &data, 0, 1, formats);
+        let ws = wb.get_sheet_by_idx(0).unwrap();
+        assert!(ws.get_style(0, 1).unwrap().font.bold);
+        assert_eq!(text(&wb, 0, 1), "keep");
+        let widths = PasteOptions {
+            content: PasteContent::ColumnWidths,
+            ..Default::default()
+        };
+        paste(&mut wb, &data, 5, 2, widths);
+        let ws = wb.get_sheet_by_idx(0).unwrap();
+        assert_eq!(ws.get_col_info(2).unwrap().width, 30.);
+        assert!(matches!(value(&wb, 5, 2), Value::Empty));
+    }
+
+    #[test]
+    fn cut_paste_moves_references() {
+        let mut wb = Workbook::default();
+        input(&mut wb, 0, 0, "5");
+        input(&mut wb, 0, 1, "=A1*2");
+        input(&mut wb, 0, 2, "=SUM(A1:A3)");
+        input(&mut wb, 1, 0, "=$B$1+1");
+        let data = wb.cut_range(0, 0, 0, 1, 0).unwrap();
+        paste(&mut wb, &data, 4, 3, PasteOptions::default());
+        assert!(matches!(value(&wb, 0, 0), Value::Empty));
+        assert_eq!(num(&wb, 4, 3), 5.);
+        // Into the block: follows it. Partly inside: stays.
+        assert_eq!(formula(&wb, 0, 0, 1), "D5 * 2");
+        assert_eq!(formula(&wb, 0, 0, 2), "SUM(A1:A3)");
+        // The moved formula keeps pointing where it did.
+        assert_eq!(formula(&wb, 0, 5, 3), "$B$1 + 1");
+        assert_eq!(num(&wb, 0, 1), 10.);
+        assert!(wb.undo());
+        assert_eq!(formula(&wb, 0, 0, 1), "A1 * 2");
+        assert_eq!(num(&wb, 0, 0), 5.);
+    }
+
+    #[test]
+    fn cut_paste_to_another_sheet() {
+        let mut wb = Workbook::default();
+        apply(
+            &mut wb,
+            EditPayload::CreateSheet(CreateSheet {
+                idx: 1,
+                new_name: "Data2".to_string(),
+            }),
+        );
+        input(&mut wb, 0, 0, "5");
+        input(&mut wb, 1, 0, "=A1+C1");
+        input(&mut wb, 0, 1, "=A1*2");
+        let data = wb.cut_range(0, 0, 0, 1, 0).unwrap();
+        wb.paste(&data, 1, 0, 0, &PasteOptions::default()).unwrap();
+        assert_eq!(formula(&wb, 0, 0, 1), "Data2!A1 * 2");
+        assert_eq!(formula(&wb, 1, 1, 0), "A1 + Sheet1!C1");
+        assert_eq!(num(&wb, 0, 1), 10.);
+    }
+}
@@ -0,0 +1,138 @@
+//! Excel-compatibility check.
+//!
+//! An xlsx file keeps the value Excel last computed for every formula. Load
+//! the file, recalculate everything ourselves and compare against those
+//! cached values: every disagreement is a formula whose semantics differ
+//! from Excel's, found without anyone writing the expected results down.
+//!
+//! Cells that can not be compared are skipped and counted: formulas the
+//! file carries no value for (files written by tools that do not compute),
+//! and volatile formulas like `NOW()` or `RAND()`, whose cached value is
+//! stale by definition. A cell is volatile when the recalculation schedules
+//! it for the next round again.
+
+use logisheets_base::CellId;
+use logisheets_workbook::prelude::read;
+
+use crate::Workbook;
+use crate::controller::display::Value;
+use crate::errors::Result;
+use crate::file_loader::load_file;
+
+#[derive(Debug, Clone, Copy)]
+pub struct CompatOptions {
+    /// Numbers agree when they differ by at most this much, relative to the
+    /// larger magnitude (or absolutely, below 1).
+    pub tolerance: f64,
+}
+
+impl Default for CompatOptions {
+    fn default() -> Self {
+        CompatOptions { tolerance: 1e-9 }
+    }
+}
+
+/// A formula cell whose recalculated value differs from Excel's.
+#[derive(Debug, Clone)]
+pub struct CompatMismatch {
+    pub sheet: String,
+    pub row: usize,
+    pub col: usize,
+    pub formula: String,
+    pub excel: Value,
+    pub logisheets: Value,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CompatReport {
+    /// Formula cells compared against a cached value.
+    pub checked: usize,
+    /// Formula cells left out: no cached value, or volatile.
+    pub skipped: usize,
+    /// Ordered by sheet, then row, then column.
+    pub mismatches: Vec<CompatMismatch>,
+}
+
+impl CompatReport {
+    pub fn is_compatible(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+}
+
+/// Load an xlsx file, recalculate every formula and report the cells where
+/// the result differs from the value Excel cached in the file.
+pub fn check_excel_compat(buf: &[u8], options: &CompatOptions) -> Result<CompatReport> {
+    // Opening a workbook computes the formulas the file has no value for,
+    // so find them on a bare load first.
+    let uncomputed = load_file(read(buf)?, String::new())
+        .status
+        .dirty_cells_next_round;
+    let mut wb = Workbook::from_file(buf, String::from("compat"))?;
+    let mut report = CompatReport::default();
+
+    // (sheet idx, row, col, sheet name, sheet id, cell id, cached value)
+    let mut cells = Vec::new();
+    for (sheet_idx, info) in wb.get_all_sheet_info().into_iter().enumerate() {
+        let ws = wb.get_sheet_by_id(info.id)?;
+        for (sheet_id, cell_id) in wb.status().formula_manager.formulas.keys() {
+            if *sheet_id != info.id || !matches!(cell_id, CellId::NormalCell(_)) {
+                continue;
+            }
+            if uncomputed.contains(&(*sheet_id, *cell_id)) {
+                report.skipped += 1;
+                continue;
+            }
+            let cached = ws.get_value_by_id(cell_id)?;
+            let coordinate = ws.get_cell_coordinate_by_id(cell_id)?;
+            cells.push((
+                sheet_idx,
+                coordinate.y,
+                coordinate.x,
+                info.name.clone(),
+                info.id,
+                *cell_id,
+                cached,
+            ));
+        }
+    }
+    cells.sort_by_key(|(sheet_idx, row, col, ..)| (*sheet_idx, *row, *col));
+
+    wb.recalc_all();
+    let volatile = &wb.status().dirty_cells_next_round;
+
+    for (_, row, col, sheet, sheet_id, cell_id, excel) in cells {
+        if volatile.contains(&(sheet_id, cell_id)) {
+            report.skipped += 1;
+            continue;
+        }
+        let ws = wb.get_sheet_by_id(sheet_id)?;
+        let logisheets = ws.get_value_by_id(&cell_id)?;
+        report.checked += 1;
+        if same_value(&excel, &logisheets, options.tolerance) {
+            continue;
+        }
+        report.mismatches.push(CompatMismatch {
+            sheet,
+            row,
+            col,
+            formula: ws.get_formula(row, col)?,
+            excel,
+            logisheets,
+        });
+    }
+    Ok(report)
+}
+
+fn same_value(excel: &Value, ours: &Value, tolerance: f64) -> bool {
+    match (excel, ours) {
+        (Value::Number(a), Value::Number(b)) => {
+            let scale = a.abs().max(b.abs()).max(1.);
+            (a - b).abs() <= tolerance * scale
+        }
+        (Value::Str(a), Value::Str(b)) => a == b,
+        (Value::Bool(a), Value::Bool(b)) => a == b,
+        (Value::Error(a), Value::Error(b)) => a == b,
+        (Value::Empty, Value::Empty) => true,
+        _ => false,
+    }
+}
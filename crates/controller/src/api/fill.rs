@@ -1,4 +1,4 @@
-//! Autofill prediction (the "fill handle").
+//! Autofill prediction (the "fill handle") and the Series dialog.
 //!
 //! This is a **pure, read-only query**: given a source range and the
 //! target range a user dragged the fill handle over, it computes the
@@ -17,31 +17,53 @@
 //! supplies geometry (which cells, which direction) and dispatches the
 //! transaction.
 //!
-//! ## Abstraction note (future copy/paste)
+//! ## Relation to copy/paste
 //!
-//! Copy/paste is "fill without sequence inference": the same
-//! [`Worksheet::get_formula_with_shift_by_id`] primitive translates a
-//! formula from one cell to another. When copy/paste lands it should
-//! share that primitive rather than reimplementing reference shifting.
+//! Copy/paste is "fill without sequence inference", but a clipboard has
+//! to outlive its source cells, so `api/clipboard.rs` shifts the formula
+//! text it captured instead of an AST. Fill always has a live source and
+//! keeps using [`Worksheet::get_formula_with_shift_by_id`].
 //!
-//! ## MVP scope
+//! ## Rules
 //!
-//! Three rules, covering the common cases:
-//!   1. **Single-value / periodic copy** — repeat the source block.
-//!   2. **Formula relative shift** — translate references per target.
-//!   3. **Arithmetic series** — linear extrapolation for numeric sources.
+//! Each line (a column of a vertical drag, a row of a horizontal one) is
+//! filled on its own, by the first rule its source cells match:
+//!   1. **Formula relative shift** — translate references per target.
+//!   2. **Dates** — numbers shown with a date format. A single date steps
+//!      by a day. Dates on the same day of the month step by their distance
+//!      in months (or years), other dates by their distance in days.
+//!   3. **Arithmetic series** — linear extrapolation from two or more
+//!      numbers. A single number is copied.
+//!   4. **Custom lists** — items of one list (weekdays, months, quarters or
+//!      one of the workbook's own lists) walk the list at the source's
+//!      stride, wrapping around, in the case they were typed in.
+//!   5. **Text with a number** — `Item 1`, `Item 3` gives `Item 5`; the
+//!      text around the number and its zero padding are kept.
+//!   6. **Periodic copy** — anything else repeats the source block. A list
+//!      item or a text number in it moves on by one each time the block
+//!      repeats.
 //!
-//! Date stepping, text+number suffix increment and richer pattern
-//! detection are deliberately left for later iterations.
+//! A [`FillType`] overrides the inference the way Excel's AutoFill options
+//! do, and picks whether contents, formats or both are filled.
+//! [`Workbook::fill_series`] is the Series dialog: an explicit step, and
+//! optionally a stop value, from the first cell of each line.
 
+use chrono::{Datelike, Duration, Months, NaiveDate};
 use logisheets_base::CellId;
+use logisheets_base::datetime::{
+    MAX_SERIAL_NUM_1900, get_date_by_serial_num_1900, get_serial_num_by_date_1900,
+};
 use logisheets_parser::unparse::CellShift;
 
 use crate::Workbook;
 use crate::api::worksheet::Worksheet;
 use crate::controller::display::Value;
-use crate::edit_action::CellInput;
+use crate::edit_action::{CellInput, EditPayload, SetCellStyles};
 use crate::errors::{Error, Result};
+use crate::navigator::sheet_nav::{MAX_COL_CNT, MAX_ROW_CNT};
+use crate::style_manager::RawStyle;
+
+use super::types::{DateUnit, FillType, SeriesIn, SeriesOptions, SeriesType};
 
 /// A rectangular block of cells, in row/col indices (0-based, inclusive).
 #[derive(Debug, Clone, Copy)]
@@ -52,6 +74,38 @@ pub struct FillRange {
     pub end_col: usize,
 }
 
+/// The lists every workbook fills from, after its own.
+const BUILTIN_LISTS: [&[&str]; 5] = [
+    &["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"],
+    &[
+        "Sunday",
+        "Monday",
+        "Tuesday",
+        "Wednesday",
+        "Thursday",
+        "Friday",
+        "Saturday",
+    ],
+    &[
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ],
+    &[
+        "January",
+        "February",
+        "March",
+        "April",
+        "May",
+        "June",
+        "July",
+        "August",
+        "September",
+        "October",
+        "November",
+        "December",
+    ],
+    &["Q1", "Q2", "Q3", "Q4"],
+];
+
 /// The axis the fill extends along. Inferred from the geometry of the
 /// source and target ranges.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -64,11 +118,57 @@ enum Axis {
 enum SrcCell {
     Formula(CellId),
     Number(f64),
-    /// Raw input string for non-numeric, non-formula cells (text, bool,
-    /// error, empty). Copied verbatim.
+    /// A number shown with a date format: a serial in the 1900 date system.
+    Date(f64),
+    Text(String),
+    /// Raw input string for booleans, errors and blanks. Copied verbatim.
     Raw(String),
 }
 
+/// How one line is filled, decided once from its source cells. Series
+/// values are functions of the offset from the first source cell.
+enum Rule {
+    /// Repeat the source block. List items and text numbers in it move on
+    /// by `cycle_step` each time the block repeats.
+    Repeat {
+        cycle_step: i64,
+    },
+    Linear {
+        v0: f64,
+        step: f64,
+    },
+    Growth {
+        v0: f64,
+        ratio: f64,
+    },
+    Dates {
+        v0: f64,
+        unit: DateUnit,
+        step: i64,
+    },
+    /// `sample` is the first source item, whose case the output follows.
+    List {
+        list: Vec<String>,
+        start: usize,
+        step: i64,
+        sample: String,
+    },
+    Numbered {
+        text: NumberedText,
+        v0: i64,
+        step: i64,
+    },
+}
+
+/// Text with a leading or trailing integer, such as `Item 1` or `1st`.
+struct NumberedText {
+    prefix: String,
+    suffix: String,
+    /// Digits to zero-pad to; 0 when the number was not padded.
+    width: usize,
+    value: i64,
+}
+
 impl Workbook {
     /// Predict the contents of a fill-handle drag.
     ///
@@ -82,16 +182,54 @@ impl Workbook {
         dst: FillRange,
     ) -> Result<Vec<CellInput>> {
         let ws = self.get_sheet_by_idx(sheet_idx)?;
-        let cells = ws.predict_fill(src, dst)?;
-        Ok(cells
-            .into_iter()
-            .map(|(row, col, content)| CellInput {
-                sheet_idx,
-                row,
-                col,
-                content,
-            })
-            .collect())
+        let cells = ws.predict_fill(src, dst, FillType::Default)?;
+        Ok(to_inputs(sheet_idx, cells))
+    }
+
+    /// Predict a fill-handle drag as edit payloads: the contents as
+    /// [`CellInput`]s, then the source formats repeated over `dst`, as
+    /// `fill_type` asks.
+    pub fn predict_fill_payloads(
+        &self,
+        sheet_idx: usize,
+        src: FillRange,
+        dst: FillRange,
+        fill_type: FillType,
+    ) -> Result<Vec<EditPayload>> {
+        let ws = self.get_sheet_by_idx(sheet_idx)?;
+        let mut payloads = Vec::new();
+        if fill_type != FillType::Formats {
+            let cells = ws.predict_fill(src, dst, fill_type)?;
+            payloads.extend(to_inputs(sheet_idx, cells).into_iter().map(Into::into));
+        }
+        if fill_type != FillType::Values {
+            let styles = ws.fill_styles(src, dst)?;
+            payloads.push(
+                SetCellStyles {
+                    sheet_idx,
+                    start_row: dst.start_row,
+                    start_col: dst.start_col,
+                    col_count: dst.end_col - dst.start_col + 1,
+                    styles: styles.into_iter().map(Some).collect(),
+                }
+                .into(),
+            );
+        }
+        Ok(payloads)
+    }
+
+    /// The Series dialog: fill `range` line by line from the first cell of
+    /// each line. Lines whose first cell is not a number are left alone,
+    /// except by [`SeriesType::AutoFill`].
+    pub fn fill_series(
+        &self,
+        sheet_idx: usize,
+        range: FillRange,
+        options: &SeriesOptions,
+    ) -> Result<Vec<CellInput>> {
+        let ws = self.get_sheet_by_idx(sheet_idx)?;
+        let cells = ws.fill_series(range, options)?;
+        Ok(to_inputs(sheet_idx, cells))
     }
 }
 
@@ -102,54 +240,157 @@ impl<'a> Worksheet<'a> {
         &self,
         src: FillRange,
         dst: FillRange,
+        fill_type: FillType,
     ) -> Result<Vec<(usize, usize, String)>> {
         let axis = infer_axis(src, dst)?;
+        // Each column (row) is an independent series along the rows
+        // (columns).
+        let (lines, src_positions, targets) = match axis {
+            Axis::Vertical => (
+                src.start_col..=src.end_col,
+                (src.start_row..=src.end_row).collect::<Vec<_>>(),
+                (dst.start_row..=dst.end_row).collect::<Vec<_>>(),
+            ),
+            Axis::Horizontal => (
+                src.start_row..=src.end_row,
+                (src.start_col..=src.end_col).collect(),
+                (dst.start_col..=dst.end_col).collect(),
+            ),
+        };
         let mut out = Vec::new();
-        match axis {
-            // Each column is an independent series along the rows.
-            Axis::Vertical => {
-                let src_positions: Vec<usize> = (src.start_row..=src.end_row).collect();
-                let targets: Vec<usize> = (dst.start_row..=dst.end_row).collect();
-                for col in src.start_col..=src.end_col {
-                    let src_cells = self.collect_line(&src_positions, |&row| (row, col))?;
-                    self.fill_line(
-                        &src_positions,
-                        &src_cells,
-                        &targets,
-                        axis,
-                        |pos, content| out.push((pos, col, content)),
-                    )?;
+        for line in lines {
+            let src_cells = self.collect_line(axis, line, &src_positions)?;
+            self.fill_line(
+                &src_positions,
+                &src_cells,
+                &targets,
+                axis,
+                fill_type,
+                |pos, content| {
+                    let (row, col) = axis.cell(line, pos);
+                    out.push((row, col, content))
+                },
+            )?;
+        }
+        Ok(out)
+    }
+
+    /// The style of the source cell each target repeats, row by row over
+    /// `dst`.
+    fn fill_styles(&self, src: FillRange, dst: FillRange) -> Result<Vec<RawStyle>> {
+        let axis = infer_axis(src, dst)?;
+        let mut styles = Vec::new();
+        for row in dst.start_row..=dst.end_row {
+            for col in dst.start_col..=dst.end_col {
+                let (src_row, src_col) = match axis {
+                    Axis::Vertical => (periodic(row, src.start_row, src.end_row), col),
+                    Axis::Horizontal => (row, periodic(col, src.start_col, src.end_col)),
+                };
+                let cell_id = self.controller.status.navigator.fetch_cell_id(
+                    &self.sheet_id,
+                    src_row,
+                    src_col,
+                )?;
+                styles.push(self.get_raw_style_by_id(&cell_id)?);
+            }
+        }
+        Ok(styles)
+    }
+
+    /// Core of the Series dialog. Returns `(row, col, content)` for every
+    /// cell filled.
+    pub(crate) fn fill_series(
+        &self,
+        range: FillRange,
+        options: &SeriesOptions,
+    ) -> Result<Vec<(usize, usize, String)>> {
+        let (axis, lines, positions, limit) = match options.series_in {
+            SeriesIn::Columns => (
+                Axis::Vertical,
+                range.start_col..=range.end_col,
+                (range.start_row..=range.end_row).collect::<Vec<_>>(),
+                MAX_ROW_CNT as usize - 1,
+            ),
+            SeriesIn::Rows => (
+                Axis::Horizontal,
+                range.start_row..=range.end_row,
+                (range.start_col..=range.end_col).collect(),
+                MAX_COL_CNT as usize - 1,
+            ),
+        };
+        let mut out = Vec::new();
+        for line in lines {
+            let cells = self.collect_line(axis, line, &positions)?;
+            if options.series_type == SeriesType::AutoFill {
+                let n = cells
+                    .iter()
+                    .take_while(|c| !matches!(c, SrcCell::Raw(s) if s.is_empty()))
+                    .count();
+                if n == 0 || n == cells.len() {
+                    continue;
                 }
+                self.fill_line(
+                    &positions[..n],
+                    &cells[..n],
+                    &positions[n..],
+                    axis,
+                    FillType::Default,
+                    |pos, content| {
+                        let (row, col) = axis.cell(line, pos);
+                        out.push((row, col, content))
+                    },
+                )?;
+                continue;
             }
-            // Each row is an independent series along the columns.
-            Axis::Horizontal => {
-                let src_positions: Vec<usize> = (src.start_col..=src.end_col).collect();
-                let targets: Vec<usize> = (dst.start_col..=dst.end_col).collect();
-                for row in src.start_row..=src.end_row {
-                    let src_cells = self.collect_line(&src_positions, |&col| (row, col))?;
-                    self.fill_line(
-                        &src_positions,
-                        &src_cells,
-                        &targets,
-                        axis,
-                        |pos, content| out.push((row, pos, content)),
-                    )?;
+            let v0 = match cells[0] {
+                SrcCell::Number(v) | SrcCell::Date(v) => v,
+                _ => continue,
+            };
+            let first = positions[0];
+            let last = match options.stop {
+                Some(_) if positions.len() == 1 => limit,
+                _ => positions[positions.len() - 1],
+            };
+            let mut prev = v0;
+            for (k, pos) in (first + 1..=last).enumerate() {
+                let k = k as i64 + 1;
+                let value = match options.series_type {
+                    SeriesType::Linear => Some(v0 + options.step * k as f64),
+                    SeriesType::Growth => Some(v0 * options.step.powi(k as i32)),
+                    SeriesType::Date => {
+                        date_series_value(v0, options.date_unit, options.step as i64, k)
+                    }
+                    SeriesType::AutoFill => unreachable!("handled above"),
+                };
+                let Some(value) = value.filter(|v| v.is_finite()) else {
+                    break;
+                };
+                if let Some(stop) = options.stop {
+                    // Past the stop in the direction the series runs; a
+                    // series that stands still would never get there.
+                    let past = if value >= prev {
+                        value > stop
+                    } else {
+                        value < stop
+                    };
+                    if past || value == prev {
+                        break;
+                    }
                 }
+                prev = value;
+                let (row, col) = axis.cell(line, pos);
+                out.push((row, col, format_number(value)));
             }
         }
         Ok(out)
     }
 
     /// Classify each source cell on one line (row or column).
-    fn collect_line(
-        &self,
-        positions: &[usize],
-        to_rc: impl Fn(&usize) -> (usize, usize),
-    ) -> Result<Vec<SrcCell>> {
+    fn collect_line(&self, axis: Axis, line: usize, positions: &[usize]) -> Result<Vec<SrcCell>> {
         positions
             .iter()
-            .map(|p| {
-                let (row, col) = to_rc(p);
+            .map(|&p| {
+                let (row, col) = axis.cell(line, p);
                 let cell_id =
                     self.controller
                         .status
@@ -159,7 +400,15 @@ impl<'a> Worksheet<'a> {
                     Ok(SrcCell::Formula(cell_id))
                 } else {
                     Ok(match self.get_value_by_id(&cell_id)? {
-                        Value::Number(n) => SrcCell::Number(n),
+                        Value::Number(n) => {
+                            let style = self.get_raw_style_by_id(&cell_id)?;
+                            if ssf_rs::is_date(&style.formatter) {
+                                SrcCell::Date(n)
+                            } else {
+                                SrcCell::Number(n)
+                            }
+                        }
+                        Value::Str(s) => SrcCell::Text(s),
                         v => SrcCell::Raw(value_to_input(&v)),
                     })
                 }
@@ -175,6 +424,7 @@ impl<'a> Worksheet<'a> {
         src_cells: &[SrcCell],
         targets: &[usize],
         axis: Axis,
+        fill_type: FillType,
         mut emit: impl FnMut(usize, String),
     ) -> Result<()> {
         let n = src_cells.len();
@@ -182,34 +432,185 @@ impl<'a> Worksheet<'a> {
             return Ok(());
         }
         let first = src_positions[0] as i64;
-
-        let all_formula = src_cells.iter().all(|c| matches!(c, SrcCell::Formula(_)));
-        let arithmetic = (n >= 2).then(|| numeric_series(src_cells)).flatten();
+        let rule = self.line_rule(src_cells, fill_type);
 
         for &t in targets {
-            let content = if let Some((v0, step)) = arithmetic {
-                // Arithmetic series: linear extrapolation from the anchor.
-                let value = v0 + step * ((t as i64 - first) as f64);
-                format_number(value)
-            } else if all_formula {
-                let idx = (t as i64 - first).rem_euclid(n as i64) as usize;
-                self.shifted_formula(src_positions[idx], src_cells[idx].cell_id(), t, axis)?
-            } else {
-                // Periodic copy of the source block.
-                let idx = (t as i64 - first).rem_euclid(n as i64) as usize;
-                match &src_cells[idx] {
-                    SrcCell::Formula(id) => {
-                        self.shifted_formula(src_positions[idx], *id, t, axis)?
+            let k = t as i64 - first;
+            let content = match &rule {
+                Rule::Repeat { cycle_step } => {
+                    // Periodic copy of the source block.
+                    let idx = k.rem_euclid(n as i64) as usize;
+                    match &src_cells[idx] {
+                        SrcCell::Formula(id) => {
+                            self.shifted_formula(src_positions[idx], *id, t, axis)?
+                        }
+                        SrcCell::Number(num) | SrcCell::Date(num) => format_number(*num),
+                        SrcCell::Text(s) => self.step_text(s, k.div_euclid(n as i64) * cycle_step),
+                        SrcCell::Raw(s) => s.clone(),
                     }
-                    SrcCell::Number(num) => format_number(*num),
-                    SrcCell::Raw(s) => s.clone(),
                 }
+                Rule::Linear { v0, step } => format_number(v0 + step * k as f64),
+                Rule::Growth { v0, ratio } => format_number(v0 * ratio.powi(k as i32)),
+                Rule::Dates { v0, unit, step } => match date_series_value(*v0, *unit, *step, k) {
+                    Some(v) => format_number(v),
+                    None => "#NUM!".to_string(),
+                },
+                Rule::List {
+                    list,
+                    start,
+                    step,
+                    sample,
+                } => {
+                    let idx = (*start as i64 + step * k).rem_euclid(list.len() as i64);
+                    match_case(sample, &list[idx as usize])
+                }
+                Rule::Numbered { text, v0, step } => text.with(v0 + step * k),
             };
             emit(t, content);
         }
         Ok(())
     }
 
+    /// Decide how a line is filled from its source cells.
+    fn line_rule(&self, src_cells: &[SrcCell], fill_type: FillType) -> Rule {
+        let n = src_cells.len();
+        let all_formula = src_cells.iter().all(|c| matches!(c, SrcCell::Formula(_)));
+        if all_formula || fill_type == FillType::Copy {
+            return Rule::Repeat { cycle_step: 0 };
+        }
+        let nums: Option<Vec<f64>> = src_cells
+            .iter()
+            .map(|c| match c {
+                SrcCell::Number(n) | SrcCell::Date(n) => Some(*n),
+                _ => None,
+            })
+            .collect();
+        if let Some(nums) = nums {
+            let all_dates = src_cells.iter().all(|c| matches!(c, SrcCell::Date(_)));
+            let unit = match fill_type {
+                FillType::Days => Some(DateUnit::Day),
+                FillType::Weekdays => Some(DateUnit::Weekday),
+                FillType::Months => Some(DateUnit::Month),
+                FillType::Years => Some(DateUnit::Year),
+                _ => None,
+            };
+            return if let Some(unit) = unit {
+                let step = if n == 1 {
+                    1
+                } else {
+                    date_distance(nums[0], nums[1], unit).unwrap_or(0)
+                };
+                Rule::Dates {
+                    v0: nums[0],
+                    unit,
+                    step,
+                }
+            } else if fill_type == FillType::Growth {
+                geometric_series(&nums)
+                    .map(|(v0, ratio)| Rule::Growth { v0, ratio })
+                    .unwrap_or(Rule::Repeat { cycle_step: 0 })
+            } else if n == 1 && all_dates {
+                Rule::Dates {
+                    v0: nums[0],
+                    unit: DateUnit::Day,
+                    step: 1,
+                }
+            } else if n == 1 && fill_type == FillType::Series {
+                Rule::Linear {
+                    v0: nums[0],
+                    step: 1.0,
+                }
+            } else if n == 1 {
+                Rule::Repeat { cycle_step: 0 }
+            } else if let Some((unit, step)) = (all_dates && fill_type != FillType::Series)
+                .then(|| monthly_step(&nums))
+                .flatten()
+            {
+                Rule::Dates {
+                    v0: nums[0],
+                    unit,
+                    step,
+                }
+            } else {
+                // Step from the endpoints (source cells are contiguous
+                // along the axis).
+                Rule::Linear {
+                    v0: nums[0],
+                    step: (nums[n - 1] - nums[0]) / (n as f64 - 1.0),
+                }
+            };
+        }
+        let texts: Option<Vec<&str>> = src_cells
+            .iter()
+            .map(|c| match c {
+                SrcCell::Text(s) => Some(s.as_str()),
+                _ => None,
+            })
+            .collect();
+        if let Some(texts) = texts {
+            if let Some(rule) = self.list_rule(&texts) {
+                return rule;
+            }
+            if let Some(rule) = numbered_rule(&texts) {
+                return rule;
+            }
+        }
+        Rule::Repeat { cycle_step: 1 }
+    }
+
+    /// The workbook's lists, then the built-in ones.
+    fn fill_lists(&self) -> Vec<Vec<&str>> {
+        self.controller
+            .settings
+            .custom_lists
+            .iter()
+            .map(|l| l.iter().map(String::as_str).collect())
+            .chain(BUILTIN_LISTS.iter().map(|l| l.to_vec()))
+            .collect()
+    }
+
+    /// Walk the first list holding every source item at a constant stride.
+    fn list_rule(&self, texts: &[&str]) -> Option<Rule> {
+        self.fill_lists().into_iter().find_map(|list| {
+            let positions: Vec<usize> = texts
+                .iter()
+                .map(|t| list_position(&list, t))
+                .collect::<Option<_>>()?;
+            let len = list.len() as i64;
+            let step = match positions.as_slice() {
+                [_] => 1,
+                [p0, p1, ..] => (*p1 as i64 - *p0 as i64).rem_euclid(len),
+                [] => return None,
+            };
+            let steady = positions
+                .windows(2)
+                .all(|w| (w[1] as i64 - w[0] as i64).rem_euclid(len) == step);
+            steady.then(|| Rule::List {
+                list: list.iter().map(|s| s.to_string()).collect(),
+                start: positions[0],
+                step,
+                sample: texts[0].to_string(),
+            })
+        })
+    }
+
+    /// `text` moved on by `by` places: along its list, or its number.
+    fn step_text(&self, text: &str, by: i64) -> String {
+        if by == 0 {
+            return text.to_string();
+        }
+        for list in self.fill_lists() {
+            if let Some(p) = list_position(&list, text) {
+                let idx = (p as i64 + by).rem_euclid(list.len() as i64);
+                return match_case(text, list[idx as usize]);
+            }
+        }
+        match NumberedText::parse(text) {
+            Some(t) => t.with(t.value + by),
+            None => text.to_string(),
+        }
+    }
+
     /// Translate the formula at `src_pos` to target position `t`,
     /// shifting relative references along `axis`. Returns a `=`-prefixed
     /// formula string ready for [`CellInput`].
@@ -230,13 +631,115 @@ impl<'a> Worksheet<'a> {
     }
 }
 
-impl SrcCell {
-    fn cell_id(&self) -> CellId {
+impl Axis {
+    /// The `(row, col)` of position `pos` on line `line`.
+    fn cell(self, line: usize, pos: usize) -> (usize, usize) {
         match self {
-            SrcCell::Formula(id) => *id,
-            // Only ever called when the line is all-formula.
-            _ => unreachable!("cell_id called on non-formula source cell"),
+            Axis::Vertical => (pos, line),
+            Axis::Horizontal => (line, pos),
+        }
+    }
+}
+
+impl NumberedText {
+    fn parse(s: &str) -> Option<Self> {
+        let digits = |c: char| c.is_ascii_digit();
+        let (prefix, number, suffix) = if s.ends_with(digits) {
+            let start = s.trim_end_matches(digits).len();
+            (&s[..start], &s[start..], "")
+        } else if s.starts_with(digits) {
+            let end = s.len() - s.trim_start_matches(digits).len();
+            ("", &s[..end], &s[end..])
+        } else {
+            return None;
+        };
+        // Longer digit runs are identifiers rather than counters.
+        if number.len() > 15 {
+            return None;
+        }
+        let width = if number.len() > 1 && number.starts_with('0') {
+            number.len()
+        } else {
+            0
+        };
+        Some(NumberedText {
+            prefix: prefix.to_string(),
+            suffix: suffix.to_string(),
+            width,
+            value: number.parse().ok()?,
+        })
+    }
+
+    /// The text with its number replaced. Counting down past zero counts
+    /// back up, as in Excel.
+    fn with(&self, value: i64) -> String {
+        format!(
+            "{}{:0width$}{}",
+            self.prefix,
+            value.unsigned_abs(),
+            self.suffix,
+            width = self.width
+        )
+    }
+}
+
+/// `Item 1`, `Item 3`: the same text around numbers in a steady stride.
+fn numbered_rule(texts: &[&str]) -> Option<Rule> {
+    let parsed: Vec<NumberedText> = texts
+        .iter()
+        .map(|t| NumberedText::parse(t))
+        .collect::<Option<_>>()?;
+    let first = &parsed[0];
+    if parsed
+        .iter()
+        .any(|t| t.prefix != first.prefix || t.suffix != first.suffix)
+    {
+        return None;
+    }
+    let n = parsed.len() as i64;
+    let step = if n == 1 {
+        1
+    } else {
+        let delta = parsed[parsed.len() - 1].value - first.value;
+        if delta % (n - 1) != 0 {
+            return None;
+        }
+        delta / (n - 1)
+    };
+    let v0 = first.value;
+    Some(Rule::Numbered {
+        text: parsed.into_iter().next()?,
+        v0,
+        step,
+    })
+}
+
+fn list_position(list: &[&str], text: &str) -> Option<usize> {
+    let text = text.to_lowercase();
+    list.iter().position(|item| item.to_lowercase() == text)
+}
+
+/// `item` in the case `sample` was typed in: upper, lower or capitalized.
+/// Mixed case keeps the list's own spelling.
+fn match_case(sample: &str, item: &str) -> String {
+    let mut chars = sample.chars().filter(|c| c.is_alphabetic());
+    let first_upper = chars.next().is_some_and(char::is_uppercase);
+    let rest: Vec<char> = chars.collect();
+    if first_upper && rest.iter().all(|c| c.is_uppercase()) && !rest.is_empty() {
+        item.to_uppercase()
+    } else if !first_upper && rest.iter().all(|c| c.is_lowercase()) {
+        item.to_lowercase()
+    } else if first_upper && rest.iter().all(|c| c.is_lowercase()) {
+        let mut chars = item.chars();
+        match chars.next() {
+            Some(c) => c
+                .to_uppercase()
+                .chain(chars.flat_map(char::to_lowercase))
+                .collect(),
+            None => String::new(),
         }
+    } else {
+        item.to_string()
     }
 }
 
@@ -256,26 +759,137 @@ fn infer_axis(src: FillRange, dst: FillRange) -> Result<Axis> {
     }
 }
 
-/// If every source cell is a number and they form an arithmetic series,
-/// return `(anchor_value, step)` where `step` is the per-position delta
-/// measured against the first source cell. `None` otherwise.
-fn numeric_series(src_cells: &[SrcCell]) -> Option<(f64, f64)> {
-    let nums: Option<Vec<f64>> = src_cells
-        .iter()
-        .map(|c| match c {
-            SrcCell::Number(n) => Some(*n),
-            _ => None,
-        })
-        .collect();
-    let nums = nums?;
+/// The source position `t` repeats in the block `start..=end`.
+fn periodic(t: usize, start: usize, end: usize) -> usize {
+    let len = (end - start + 1) as i64;
+    start + (t as i64 - start as i64).rem_euclid(len) as usize
+}
+
+/// `(first, ratio)` of the geometric series through the endpoints. `None`
+/// unless the endpoints share a sign.
+fn geometric_series(nums: &[f64]) -> Option<(f64, f64)> {
     let n = nums.len();
     if n < 2 {
+        return Some((nums[0], 1.0));
+    }
+    let q = nums[n - 1] / nums[0];
+    (q.is_finite() && q > 0.0).then_some((nums[0], q.powf(1.0 / (n as f64 - 1.0))))
+}
+
+/// Dates on the same day of the month, a steady number of months apart:
+/// a month series, or a year series when the month never changes.
+fn monthly_step(serials: &[f64]) -> Option<(DateUnit, i64)> {
+    let dates: Vec<NaiveDate> = serials
+        .iter()
+        .map(|s| serial_to_date(*s))
+        .collect::<Option<_>>()?;
+    if dates.iter().any(|d| d.day() != dates[0].day()) {
         return None;
     }
-    // Step from the endpoints; treat any non-decreasing index spacing as 1
-    // (source cells are contiguous along the axis).
-    let step = (nums[n - 1] - nums[0]) / (n as f64 - 1.0);
-    Some((nums[0], step))
+    let months = month_index(&dates[1]) - month_index(&dates[0]);
+    let steady = dates
+        .windows(2)
+        .all(|w| month_index(&w[1]) - month_index(&w[0]) == months);
+    if !steady || months == 0 {
+        None
+    } else if months % 12 == 0 {
+        Some((DateUnit::Year, months / 12))
+    } else {
+        Some((DateUnit::Month, months))
+    }
+}
+
+/// How many `unit`s the date `b` is after `a`.
+fn date_distance(a: f64, b: f64, unit: DateUnit) -> Option<i64> {
+    if unit == DateUnit::Day {
+        return Some((b.floor() - a.floor()) as i64);
+    }
+    let (a, b) = (serial_to_date(a)?, serial_to_date(b)?);
+    Some(match unit {
+        DateUnit::Day => unreachable!("handled above"),
+        DateUnit::Weekday => weekday_index(b) - weekday_index(a),
+        DateUnit::Month => month_index(&b) - month_index(&a),
+        DateUnit::Year => (b.year() - a.year()) as i64,
+    })
+}
+
+/// The serial `k` steps of `step` `unit`s after `v0`, keeping its time of
+/// day. `None` when the date leaves the calendar.
+fn date_series_value(v0: f64, unit: DateUnit, step: i64, k: i64) -> Option<f64> {
+    let by = step.checked_mul(k)?;
+    if unit == DateUnit::Day {
+        return Some(v0 + by as f64);
+    }
+    let date = serial_to_date(v0)?;
+    let moved = match unit {
+        DateUnit::Day => unreachable!("handled above"),
+        DateUnit::Weekday => weekday_date(weekday_index(date).checked_add(by)?),
+        DateUnit::Month => add_months(date, by)?,
+        DateUnit::Year => add_months(date, by.checked_mul(12)?)?,
+    };
+    Some(date_to_serial(moved)? + v0.fract())
+}
+
+/// Months run past the end of a shorter month end on its last day, as
+/// `EDATE` does.
+fn add_months(date: NaiveDate, months: i64) -> Option<NaiveDate> {
+    let m = Months::new(u32::try_from(months.unsigned_abs()).ok()?);
+    if months >= 0 {
+        date.checked_add_months(m)
+    } else {
+        date.checked_sub_months(m)
+    }
+}
+
+fn month_index(date: &NaiveDate) -> i64 {
+    date.year() as i64 * 12 + date.month0() as i64
+}
+
+/// Monday 1900-01-01, from which weekdays are counted.
+fn weekday_epoch() -> NaiveDate {
+    NaiveDate::from_ymd_opt(1900, 1, 1).unwrap()
+}
+
+/// Weekdays since the epoch. A weekend counts as the Monday after it.
+fn weekday_index(date: NaiveDate) -> i64 {
+    let days = (date - weekday_epoch()).num_days();
+    days.div_euclid(7) * 5 + days.rem_euclid(7).min(5)
+}
+
+fn weekday_date(index: i64) -> NaiveDate {
+    let days = index.div_euclid(5) * 7 + index.rem_euclid(5);
+    weekday_epoch() + Duration::days(days)
+}
+
+/// The date of a serial in the 1900 date system. The non-existent
+/// 1900-02-29 is not a date to step from.
+fn serial_to_date(serial: f64) -> Option<NaiveDate> {
+    let days = serial.floor();
+    if !(1.0..=MAX_SERIAL_NUM_1900 as f64).contains(&days) {
+        return None;
+    }
+    let date = get_date_by_serial_num_1900(days as u32);
+    NaiveDate::from_ymd_opt(date.year as i32, date.month.into(), date.day.into())
+}
+
+fn date_to_serial(date: NaiveDate) -> Option<f64> {
+    let year = u32::try_from(date.year()).ok().filter(|y| *y >= 1900)?;
+    get_serial_num_by_date_1900(year, date.month(), date.day())
+        .filter(|s| *s <= MAX_SERIAL_NUM_1900)
+        .map(f64::from)
+}
+
+fn to_inputs(sheet_idx: usize, cells: Vec<(usize, usize, String)>) -> Vec<CellInput> {
+    cells
+        .into_iter()
+        .map(|(row, col, content)| CellInput {
+            sheet_idx,
+            row,
+            col,
+            content,
+            ..Default::default()
+        })
+        .collect()
 }
 
 /// Render the raw input string the fill should write for a copied value.
@@ -295,7 +909,7 @@ fn format_number(n: f64) -> String {
 
 #[cfg(test)]
 mod tests {
-    use super::FillRange;
+    use super::{DateUnit, FillRange, FillType, SeriesIn, SeriesOptions, SeriesType};
     use crate::Workbook;
     use crate::edit_action::{CellInput, EditAction, EditPayload, PayloadsAction};
 
@@ -306,6 +920,7 @@ mod tests {
                 row,
                 col,
                 content: content.to_string(),
+                ..Default::default()
             })],
             undoable: true,
             init: false,
@@ -395,4 +1010,180 @@ mod tests {
         let contents: Vec<_> = res.iter().map(|c| (c.col, c.content.clone())).collect();
         assert_eq!(contents, vec![(2, "30".to_string()), (3, "40".to_string())]);
     }
+
+    fn contents(wb: &Workbook, src: FillRange, dst: FillRange, fill_type: FillType) -> Vec<String> {
+        wb.predict_fill_payloads(0, src, dst, fill_type)
+            .unwrap()
+            .into_iter()
+            .filter_map(|p| match p {
+                EditPayload::CellInput(c) => Some(c.content),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn fill_dates() {
+        let mut wb = Workbook::default();
+        // 2024-01-31 is serial 45322.
+        input(&mut wb, 0, 0, "1/31/2024");
+        let res = contents(&wb, rng(0, 0, 0, 0), rng(1, 0, 2, 0), FillType::Default);
+        assert_eq!(res, vec!["45323", "45324"]);
+        // Month ends clamp to the shorter month: 2024-02-29, 2024-03-31.
+        let res = contents(&wb, rng(0, 0, 0, 0), rng(1, 0, 2, 0), FillType::Months);
+        assert_eq!(res, vec!["45351", "45382"]);
+        // 2024-01-15, 2024-02-15 step by a month: 03-15, 04-15.
+        input(&mut wb, 0, 1, "1/15/2024");
+        input(&mut wb, 1, 1, "2/15/2024");
+        let res = contents(&wb, rng(0, 1, 1, 1), rng(2, 1, 3, 1), FillType::Default);
+        assert_eq!(res, vec!["45366", "45397"]);
+        // Friday 2024-01-05 skips the weekend.
+        input(&mut wb, 0, 2, "1/5/2024");
+        let res = contents(&wb, rng(0, 2, 0, 2), rng(1, 2, 2, 2), FillType::Weekdays);
+        assert_eq!(res, vec!["45299", "45300"]);
+    }
+
+    #[test]
+    fn fill_text_with_number() {
+        let mut wb = Workbook::default();
+        input(&mut wb, 0, 0, "Item 1");
+        input(&mut wb, 1, 0, "Item 3");
+        input(&mut wb, 0, 1, "Room 009");
+        input(&mut wb, 0, 2, "1st");
+        let res = contents(&wb, rng(0, 0, 1, 0), rng(2, 0, 3, 0), FillType::Default);
+        assert_eq!(res, vec!["Item 5", "Item 7"]);
+        let res = contents(&wb, rng(0, 1, 0, 1), rng(1, 1, 1, 1), FillType::Default);
+        assert_eq!(res, vec!["Room 010"]);
+        let res = contents(&wb, rng(0, 2, 0, 2), rng(1, 2, 1, 2), FillType::Default);
+        assert_eq!(res, vec!["2st"]);
+        let res = contents(&wb, rng(0, 1, 0, 1), rng(1, 1, 1, 1), FillType::Copy);
+        assert_eq!(res, vec!["Room 009"]);
+    }
+
+    #[test]
+    fn fill_custom_lists() {
+        let mut wb = Workbook::default();
+        input(&mut wb, 0, 0, "Sat");
+        input(&mut wb, 0, 1, "JAN");
+        input(&mut wb, 1, 1, "MAR");
+        input(&mut wb, 0, 2, "Q3");
+        input(&mut wb, 0, 3, "east");
+        let res = contents(&wb, rng(0, 0, 0, 0), rng(1, 0, 2, 0), FillType::Default);
+        assert_eq!(res, vec!["Sun", "Mon"]);
+        let res = contents(&wb, rng(0, 1, 1, 1), rng(2, 1, 3, 1), FillType::Default);
+        assert_eq!(res, vec!["MAY", "JUL"]);
+        let res = contents(&wb, rng(0, 2, 0, 2), rng(1, 2, 2, 2), FillType::Default);
+        assert_eq!(res, vec!["Q4", "Q1"]);
+        let res = contents(&wb, rng(0, 3, 0, 3), rng(1, 3, 1, 3), FillType::Default);
+        assert_eq!(res, vec!["east"]);
+        wb.set_custom_lists(vec![
+            ["North", "East", "South", "West"]
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+        ]);
+        let res = contents(&wb, rng(0, 3, 0, 3), rng(1, 3, 2, 3), FillType::Default);
+        assert_eq!(res, vec!["south", "west"]);
+    }
+
+    #[test]
+    fn fill_periodic_block_steps_each_cycle() {
+        let mut wb = Workbook::default();
+        input(&mut wb, 0, 0, "a1");
+        input(&mut wb, 1, 0, "x");
+        let res = contents(&wb, rng(0, 0, 1, 0), rng(2, 0, 5, 0), FillType::Default);
+        assert_eq!(res, vec!["a2", "x", "a3", "x"]);
+    }
+
+    #[test]
+    fn fill_growth_and_series() {
+        let mut wb = Workbook::default();
+        input(&mut wb, 0, 0, "2");
+        input(&mut wb, 1, 0, "4");
+        input(&mut wb, 0, 1, "5");
+        let res = contents(&wb, rng(0, 0, 1, 0), rng(2, 0, 3, 0), FillType::Growth);
+        assert_eq!(res, vec!["8", "16"]);
+        let res = contents(&wb, rng(0, 1, 0, 1), rng(1, 1, 2, 1), FillType::Series);
+        assert_eq!(res, vec!["6", "7"]);
+    }
+
+    #[test]
+    fn fill_formats_and_values() {
+        let mut wb = Workbook::default();
+        input(&mut wb, 0, 0, "1/31/2024");
+        let payloads = wb
+            .predict_fill_payloads(0, rng(0, 0, 0, 0), rng(1, 0, 2, 0), FillType::Formats)
+            .unwrap();
+        assert_eq!(payloads.len(), 1);
+        match &payloads[0] {
+            EditPayload::SetCellStyles(s) => {
+                assert_eq!((s.start_row, s.col_count, s.styles.len()), (1, 1, 2));
+                let formatter = &s.styles[0].as_ref().unwrap().formatter;
+                assert!(ssf_rs::is_date(formatter));
+            }
+            _ => panic!("expected styles"),
+        }
+        let payloads = wb
+            .predict_fill_payloads(0, rng(0, 0, 0, 0), rng(1, 0, 2, 0), FillType::Values)
+            .unwrap();
+        assert!(
+            payloads
+                .iter()
+                .all(|p| matches!(p, EditPayload::CellInput(_)))
+        );
+    }
+
+    #[test]
+    fn fill_series_dialog() {
+        let mut wb = Workbook::default();
+        input(&mut wb, 0, 0, "1");
+        input(&mut wb, 0, 1, "1");
+        input(&mut wb, 0, 2, "1/31/2024");
+        let options = |series_type, step, stop| SeriesOptions {
+            series_in: SeriesIn::Columns,
+            series_type,
+            date_unit: DateUnit::Month,
+            step,
+            stop,
+        };
+        let values = |res: Vec<crate::edit_action::CellInput>| {
+            res.into_iter()
+                .map(|c| (c.row, c.content))
+                .collect::<Vec<_>>()
+        };
+        // A single cell extends until the stop value.
+        let res = wb
+            .fill_series(
+                0,
+                rng(0, 0, 0, 0),
+                &options(SeriesType::Linear, 3.0, Some(10.0)),
+            )
+            .unwrap();
+        assert_eq!(
+            values(res),
+            vec![
+                (1, "4".to_string()),
+                (2, "7".to_string()),
+                (3, "10".to_string())
+            ]
+        );
+        let res = wb
+            .fill_series(0, rng(0, 1, 3, 1), &options(SeriesType::Growth, 2.0, None))
+            .unwrap();
+        assert_eq!(
+            values(res),
+            vec![
+                (1, "2".to_string()),
+                (2, "4".to_string()),
+                (3, "8".to_string())
+            ]
+        );
+        let res = wb
+            .fill_series(0, rng(0, 2, 2, 2), &options(SeriesType::Date, 1.0, None))
+            .unwrap();
+        assert_eq!(
+            values(res),
+            vec![(1, "45351".to_string()), (2, "45382".to_string())]
+        );
+    }
 }
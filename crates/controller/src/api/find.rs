@@ -0,0 +1,733 @@
+//! Find and replace.
+//!
+//! [`Workbook::find_all`] lists the cells whose text matches a
+//! [`FindOptions`], in the order Excel's Find All shows them: sheet by
+//! sheet, then by rows or by columns. [`Workbook::find_next`] steps through
+//! the same list from a cell, wrapping around at the end.
+//!
+//! ## What is matched
+//!
+//! Looking in values matches the text a cell shows, formatted, so a formula
+//! is found by its result. Looking in formulas matches what was typed: the
+//! formula with its `=`, as the formula bar shows it (`=A1 * 2`), or the
+//! constant. A number was typed as its number format shows it when that
+//! reads back as the same number, so a date is matched as `1/2/2026` and
+//! not as its serial. The query is plain text, an Excel wildcard pattern
+//! (`*`, `?`, `~` to escape) or a regular expression; all three are
+//! compiled into one regex by the code `REGEXTEST` and friends use. An
+//! empty query matches every cell the format filter accepts, blanks
+//! included, which finds cells by format alone.
+//!
+//! ## Replacing
+//!
+//! A replacement rewrites what was typed and goes through ordinary cell
+//! input, so numbers stay numbers. It never turns text into a formula, and
+//! a formula whose new text does not check fails the whole replacement
+//! before anything is written. Looking in values only constants are
+//! rewritten: a computed value cannot be edited, and the formulas found
+//! are handed back as skipped. All the cells go in one undoable
+//! `PayloadsAction`.
+
+use std::collections::HashSet;
+
+use gents_derives::TS;
+use logisheets_base::CellId;
+use logisheets_workbook::prelude::StPatternType;
+use regex::Regex;
+
+use crate::Value;
+use crate::calc_engine::calculator::regex_funcs;
+use crate::controller::style::{Color, Fill, Style};
+use crate::edit_action::{ActionEffect, CellInput, EditAction, EditPayload, PayloadsAction};
+use crate::errors::Error;
+use crate::input_recognition::recognize;
+use crate::settings::InputLocale;
+use crate::sqref::col_to_letters;
+
+use super::worksheet::format_display_text;
+use super::{Workbook, Worksheet};
+
+/// Which text of a cell is searched.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, TS)]
+#[ts(file_name = "find_look_in.ts", rename_all = "camelCase")]
+pub enum FindLookIn {
+    /// The text the cell shows.
+    #[default]
+    Values,
+    /// The formula, or the constant as typed.
+    Formulas,
+}
+
+/// How the query is read.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, TS)]
+#[ts(file_name = "find_mode.ts", rename_all = "camelCase")]
+pub enum FindMode {
+    #[default]
+    Text,
+    /// `*` is any run of characters, `?` any one, `~` escapes either.
+    Wildcard,
+    Regex,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, TS)]
+#[ts(file_name = "find_order.ts", rename_all = "camelCase")]
+pub enum FindOrder {
+    /// Left to right along a row, then down.
+    #[default]
+    Rows,
+    /// Top to bottom along a column, then right.
+    Columns,
+}
+
+#[derive(Debug, Clone, TS)]
+#[ts(file_name = "find_range.ts", rename_all = "camelCase")]
+pub struct FindRange {
+    pub sheet_idx: usize,
+    pub start_row: usize,
+    pub start_col: usize,
+    pub end_row: usize,
+    pub end_col: usize,
+}
+
+#[derive(Debug, Clone, Default, TS)]
+#[ts(file_name = "find_scope.ts", tag = "type", rename_all = "camelCase")]
+pub enum FindScope {
+    #[default]
+    Workbook,
+    /// One sheet, by index.
+    Sheet(usize),
+    Range(FindRange),
+}
+
+/// Match cells by format as well. Each field set must agree with the
+/// cell's style; colors are `RRGGBB`.
+#[derive(Debug, Clone, Default, TS)]
+#[ts(file_name = "find_format.ts", rename_all = "camelCase")]
+pub struct FindFormat {
+    pub bold: Option<bool>,
+    pub italic: Option<bool>,
+    pub font_color: Option<String>,
+    /// The color of a solid fill.
+    pub fill_color: Option<String>,
+    pub number_format: Option<String>,
+    /// The name of the cell style the cell uses.
+    pub cell_style: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, TS)]
+#[ts(file_name = "find_options.ts", rename_all = "camelCase")]
+pub struct FindOptions {
+    pub query: String,
+    pub look_in: FindLookIn,
+    pub mode: FindMode,
+    pub match_case: bool,
+    /// The query must match the whole text, not a part of it.
+    pub whole_cell: bool,
+    pub scope: FindScope,
+    pub order: FindOrder,
+    pub format: Option<FindFormat>,
+}
+
+/// A cell that matched.
+#[derive(Debug, Clone, TS)]
+#[ts(file_name = "found_cell.ts", rename_all = "camelCase")]
+pub struct FoundCell {
+    pub sheet_idx: usize,
+    pub row: usize,
+    pub col: usize,
+    /// The text the cell shows.
+    pub value: String,
+    /// The formula, with `=`.
+    pub formula: Option<String>,
+}
+
+/// What replacing would write, and the matches it has to leave alone.
+#[derive(Debug, Clone, Default, TS)]
+#[ts(file_name = "replace_plan.ts", rename_all = "camelCase")]
+pub struct ReplacePlan {
+    pub payloads: Vec<EditPayload>,
+    /// Cells that matched by the value a formula computes, which can not be
+    /// rewritten.
+    pub skipped: Vec<FoundCell>,
+}
+
+/// What replacing did.
+#[derive(Debug, Clone)]
+pub struct ReplaceResult {
+    pub effect: ActionEffect,
+    /// See [`ReplacePlan::skipped`].
+    pub skipped: Vec<FoundCell>,
+}
+
+/// A found cell and what was typed into it.
+struct Hit {
+    cell: FoundCell,
+    input: String,
+}
+
+struct Matcher {
+    /// `None` for an empty query, which matches anything.
+    regex: Option<Regex>,
+}
+
+impl Matcher {
+    fn new(options: &FindOptions) -> crate::errors::Result<Self> {
+        if options.query.is_empty() {
+            return Ok(Matcher { regex: None });
+        }
+        let pattern = match options.mode {
+            FindMode::Text => regex::escape(&options.query),
+            FindMode::Wildcard => wildcard_to_regex(&options.query),
+            FindMode::Regex => options.query.clone(),
+        };
+        let pattern = if options.whole_cell {
+            format!("^(?:{})$", pattern)
+        } else {
+            pattern
+        };
+        let regex = regex_funcs::build_regex(&pattern, !options.match_case)
+            .map_err(|e| Error::PayloadError(format!("invalid search pattern: {}", e)))?;
+        Ok(Matcher { regex: Some(regex) })
+    }
+
+    fn is_match(&self, text: &str) -> bool {
+        match &self.regex {
+            Some(regex) => regex.is_match(text),
+            None => true,
+        }
+    }
+
+    /// Replace every match in `text`. `$1`-style groups are expanded only
+    /// for a regex query.
+    fn replace(&self, text: &str, replacement: &str, mode: FindMode) -> Option<String> {
+        let regex = self.regex.as_ref()?;
+        match mode {
+            FindMode::Regex => regex_funcs::replace_matches(regex, text, replacement, 0),
+            _ => regex_funcs::replace_matches(regex, text, &replacement.replace('$', "$$"), 0),
+        }
+    }
+}
+
+fn wildcard_to_regex(pattern: &str) -> String {
+    let mut result = String::new();
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' => result.push_str(".*"),
+            '?' => result.push('.'),
+            '~' => match chars.next() {
+                Some(next) => result.push_str(&regex::escape(&next.to_string())),
+                None => result.push_str(&regex::escape("~")),
+            },
+            _ => result.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    result
+}
+
+impl FindFormat {
+    fn matches(&self, style: &Style, cell_style: Option<&str>) -> bool {
+        let same_color = |want: &Option<String>, got: Option<&Color>| match want {
+            None => true,
+            Some(want) => got
+                .and_then(color_rgb)
+                .is_some_and(|got| got.eq_ignore_ascii_case(want.trim_start_matches('#'))),
+        };
+        let fill = match &style.fill {
+            Fill::PatternFill(p) if p.pattern_type == Some(StPatternType::Solid) => {
+                p.fg_color.as_ref()
+            }
+            _ => None,
+        };
+        self.bold.is_none_or(|b| b == style.font.bold)
+            && self.italic.is_none_or(|i| i == style.font.italic)
+            && same_color(&self.font_color, style.font.color.as_ref())
+            && same_color(&self.fill_color, fill)
+            && self
+                .number_format
+                .as_ref()
+                .is_none_or(|f| *f == style.formatter)
+            && self
+                .cell_style
+                .as_ref()
+                .is_none_or(|s| Some(s.as_str()) == cell_style)
+    }
+}
+
+fn color_rgb(color: &Color) -> Option<String> {
+    let channel = |c: Option<f64>| c.map(|v| v.round().clamp(0., 255.) as u8);
+    Some(format!(
+        "{:02X}{:02X}{:02X}",
+        channel(color.red)?,
+        channel(color.green)?,
+        channel(color.blue)?
+    ))
+}
+
+impl Workbook {
+    /// Every cell in scope that matches, in search order.
+    pub fn find_all(&self, options: &FindOptions) -> crate::errors::Result<Vec<FoundCell>> {
+        Ok(self
+            .find_hits(options)?
+            .into_iter()
+            .map(|h| h.cell)
+            .collect())
+    }
+
+    /// The first match after `(row, col)` of sheet `sheet_idx` in search
+    /// order, wrapping around to the start of the scope. The cell itself is
+    /// found last, so repeated calls cycle through the matches.
+    pub fn find_next(
+        &self,
+        options: &FindOptions,
+        sheet_idx: usize,
+        row: usize,
+        col: usize,
+    ) -> crate::errors::Result<Option<FoundCell>> {
+        let key = |s: usize, r: usize, c: usize| match options.order {
+            FindOrder::Rows => (s, r, c),
+            FindOrder::Columns => (s, c, r),
+        };
+        let from = key(sheet_idx, row, col);
+        let found = self.find_all(options)?;
+        let next = found
+            .iter()
+            .position(|f| key(f.sheet_idx, f.row, f.col) > from)
+            .unwrap_or(0);
+        Ok(found.into_iter().nth(next))
+    }
+
+    /// The cell inputs that replace the matches with `replacement`, all of
+    /// them or only the one in `cell`. They belong in one transaction;
+    /// [`replace`](Self::replace) and [`replace_all`](Self::replace_all) do
+    /// that.
+    pub fn replace_payloads(
+        &self,
+        options: &FindOptions,
+        replacement: &str,
+        cell: Option<&FoundCell>,
+    ) -> crate::errors::Result<ReplacePlan> {
+        if options.query.is_empty() {
+            return Err(Error::PayloadError("nothing to replace".to_string()));
+        }
+        let matcher = Matcher::new(options)?;
+        let mut plan = ReplacePlan::default();
+        for hit in self.find_hits(options)? {
+            let FoundCell {
+                sheet_idx,
+                row,
+                col,
+                ref formula,
+                ..
+            } = hit.cell;
+            if cell.is_some_and(|c| (c.sheet_idx, c.row, c.col) != (sheet_idx, row, col)) {
+                continue;
+            }
+            if formula.is_some() && options.look_in == FindLookIn::Values {
+                plan.skipped.push(hit.cell);
+                continue;
+            }
+            let Some(content) = matcher.replace(&hit.input, replacement, options.mode) else {
+                continue;
+            };
+            if content == hit.input {
+                continue;
+            }
+            let content = if formula.is_none() && content.starts_with('=') {
+                format!("'{}", content)
+            } else if formula.is_some()
+                && content.starts_with('=')
+                && !self.check_formula(content.clone()).valid
+            {
+                return Err(Error::PayloadError(format!(
+                    "replacing in {}!{}{} gives an invalid formula: {}",
+                    self.get_sheet_name_by_idx(sheet_idx)?,
+                    col_to_letters(col),
+                    row + 1,
+                    content
+                )));
+            } else {
+                content
+            };
+            plan.payloads.push(
+                CellInput {
+                    sheet_idx,
+                    row,
+                    col,
+                    content,
+                    ..Default::default()
+                }
+                .into(),
+            );
+        }
+        Ok(plan)
+    }
+
+    /// Replace the match in `cell` as a single undoable transaction.
+    pub fn replace(
+        &mut self,
+        options: &FindOptions,
+        replacement: &str,
+        cell: &FoundCell,
+    ) -> crate::errors::Result<ReplaceResult> {
+        let plan = self.replace_payloads(options, replacement, Some(cell))?;
+        Ok(self.apply_replace(plan))
+    }
+
+    /// Replace every match as a single undoable transaction.
+    pub fn replace_all(
+        &mut self,
+        options: &FindOptions,
+        replacement: &str,
+    ) -> crate::errors::Result<ReplaceResult> {
+        let plan = self.replace_payloads(options, replacement, None)?;
+        Ok(self.apply_replace(plan))
+    }
+
+    fn apply_replace(&mut self, plan: ReplacePlan) -> ReplaceResult {
+        let effect = self.handle_action(EditAction::Payloads(PayloadsAction {
+            payloads: plan.payloads,
+            undoable: true,
+            init: false,
+        }));
+        ReplaceResult {
+            effect,
+            skipped: plan.skipped,
+        }
+    }
+
+    fn find_hits(&self, options: &FindOptions) -> crate::errors::Result<Vec<Hit>> {
+        let matcher = Matcher::new(options)?;
+        let sheets = match &options.scope {
+            FindScope::Workbook => (0..self.get_sheet_count()).collect(),
+            FindScope::Sheet(idx) => vec![*idx],
+            FindScope::Range(range) => vec![range.sheet_idx],
+        };
+        let mut hits = Vec::new();
+        for sheet_idx in sheets {
+            let ws = self.get_sheet_by_idx(sheet_idx)?;
+            let mut cells = ws.stored_cells();
+            if let FindScope::Range(r) = &options.scope {
+                cells.retain(|(row, col, _)| {
+                    (r.start_row..=r.end_row).contains(row)
+                        && (r.start_col..=r.end_col).contains(col)
+                });
+            }
+            match options.order {
+                FindOrder::Rows => cells.sort_by_key(|(row, col, _)| (*row, *col)),
+                FindOrder::Columns => cells.sort_by_key(|(row, col, _)| (*col, *row)),
+            }
+            for (row, col, cell_id) in cells {
+                let value = ws.get_value_by_id(&cell_id)?;
+                let formula = if ws.has_formula(&cell_id) {
+                    Some(format!("={}", ws.get_formula_by_id(&cell_id)?))
+                } else {
+                    None
+                };
+                if !options.query.is_empty() && formula.is_none() && matches!(value, Value::Empty) {
+                    continue;
+                }
+                let style = ws.get_style_by_id(&cell_id)?;
+                let (shown, _) = format_display_text(&value, &style.formatter, None);
+                let input = formula.clone().unwrap_or_else(|| {
+                    typed_text(&value, &style.formatter, self.get_input_locale())
+                });
+                let is_match = match options.look_in {
+                    FindLookIn::Values => matcher.is_match(&shown),
+                    FindLookIn::Formulas => matcher.is_match(&input),
+                };
+                if !is_match {
+                    continue;
+                }
+                if let Some(format) = &options.format {
+                    let style_id = ws.get_applicable_raw_style_id(&cell_id);
+                    let cell_style = ws
+                        .controller
+                        .status
+                        .style_manager
+                        .get_named_style_name(style_id);
+                    if !format.matches(&style, cell_style) {
+                        continue;
+                    }
+                }
+                hits.push(Hit {
+                    cell: FoundCell {
+                        sheet_idx,
+                        row,
+                        col,
+                        value: shown,
+                        formula,
+                    },
+                    input,
+                });
+            }
+        }
+        Ok(hits)
+    }
+}
+
+impl<'a> Worksheet<'a> {
+    /// The cells this sheet stores, values and formulas, with their
+    /// positions. Ephemeral cells have none and are left out.
+    fn stored_cells(&self) -> Vec<(usize, usize, CellId)> {
+        let status = &self.controller.status;
+        let mut ids = HashSet::new();
+        if let Some(sc) = status.container.get_sheet_container(self.sheet_id) {
+            ids.extend(sc.cells.keys().copied());
+        }
+        ids.extend(
+            status
+                .formula_manager
+                .formulas
+                .keys()
+                .filter(|(sheet_id, _)| *sheet_id == self.sheet_id)
+                .map(|(_, cell_id)| *cell_id),
+        );
+        ids.into_iter()
+            .filter_map(|id| {
+                let (row, col) = status.navigator.fetch_cell_idx(&self.sheet_id, &id).ok()?;
+                Some((row, col, id))
+            })
+            .collect()
+    }
+}
+
+/// A constant as it would be typed, without the `'` that keeps text text.
+/// A number is typed as `formatter` shows it, or else as General shows it,
+/// when input recognition reads that back as the same number.
+fn typed_text(value: &Value, formatter: &str, locale: &InputLocale) -> String {
+    match value {
+        Value::Str(s) => s.clone(),
+        Value::Bool(b) => if *b { "TRUE" } else { "FALSE" }.to_string(),
+        Value::Number(n) => [formatter, "General"]
+            .into_iter()
+            .map(|f| format_display_text(value, f, None).0)
+            .find(|text| recognize(text, locale).is_some_and(|r| r.value == *n))
+            .unwrap_or_else(|| n.to_string()),
+        Value::Error(e) => e.clone(),
+        Value::Empty => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FindFormat, FindLookIn, FindMode, FindOptions, FindOrder, FindScope};
+    use crate::edit_action::{
+        CellInput, CellStyleUpdate, CreateSheet, EditAction, EditPayload, PayloadsAction,
+        StyleUpdateType,
+    };
+    use crate::{Value, Workbook};
+
+    fn apply(wb: &mut Workbook, payloads: Vec<EditPayload>) {
+        wb.handle_action(EditAction::Payloads(PayloadsAction {
+            payloads,
+            undoable: true,
+            init: false,
+        }));
+    }
+
+    fn input(wb: &mut Workbook, sheet_idx: usize, row: usize, col: usize, content: &str) {
+        apply(
+            wb,
+            vec![
+                CellInput {
+                    sheet_idx,
+                    row,
+                    col,
+                    content: content.to_string(),
+                    ..Default::default()
+                }
+                .into(),
+            ],
+        );
+    }
+
+    fn positions(wb: &Workbook, options: &FindOptions) -> Vec<(usize, usize, usize)> {
+        wb.find_all(options)
+            .unwrap()
+            .into_iter()
+            .map(|f| (f.sheet_idx, f.row, f.col))
+            .collect()
+    }
+
+    fn query(q: &str) -> FindOptions {
+        FindOptions {
+            query: q.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn finds_across_sheets_in_order() {
+        let mut wb = Workbook::default();
+        apply(
+            &mut wb,
+            vec![
+                CreateSheet {
+                    idx: 1,
+                    new_name: "Two".to_string(),
+                }
+                .into(),
+            ],
+        );
+        input(&mut wb, 0, 0, 1, "apple pie");
+        input(&mut wb, 0, 1, 0, "Apple");
+        input(&mut wb, 1, 0, 0, "pineapple");
+        input(&mut wb, 0, 2, 2, "pear");
+
+        let all = query("apple");
+        assert_eq!(positions(&wb, &all), vec![(0, 0, 1), (0, 1, 0), (1, 0, 0)]);
+        let by_cols = FindOptions {
+            order: FindOrder::Columns,
+            ..all.clone()
+        };
+        assert_eq!(
+            positions(&wb, &by_cols),
+            vec![(0, 1, 0), (0, 0, 1), (1, 0, 0)]
+        );
+        let cased = FindOptions {
+            match_case: true,
+            whole_cell: true,
+            ..query("Apple")
+        };
+        assert_eq!(positions(&wb, &cased), vec![(0, 1, 0)]);
+        let sheet = FindOptions {
+            scope: FindScope::Sheet(1),
+            ..all.clone()
+        };
+        assert_eq!(positions(&wb, &sheet), vec![(1, 0, 0)]);
+
+        let next = wb.find_next(&all, 0, 1, 0).unwrap().unwrap();
+        assert_eq!((next.sheet_idx, next.row, next.col), (1, 0, 0));
+        let wrapped = wb.find_next(&all, 1, 0, 0).unwrap().unwrap();
+        assert_eq!((wrapped.sheet_idx, wrapped.row, wrapped.col), (0, 0, 1));
+    }
+
+    #[test]
+    fn wildcards_regex_and_look_in() {
+        let mut wb = Workbook::default();
+        input(&mut wb, 0, 0, 0, "a*b");
+        input(&mut wb, 0, 1, 0, "axxb");
+        input(&mut wb, 0, 2, 0, "=SUM(1,2)");
+
+        let wildcard = |q: &str| FindOptions {
+            mode: FindMode::Wildcard,
+            whole_cell: true,
+            ..query(q)
+        };
+        assert_eq!(positions(&wb, &wildcard("a*b")), vec![(0, 0, 0), (0, 1, 0)]);
+        assert_eq!(positions(&wb, &wildcard("a~*b")), vec![(0, 0, 0)]);
+        assert_eq!(positions(&wb, &wildcard("a??b")), vec![(0, 1, 0)]);
+        let regex = FindOptions {
+            mode: FindMode::Regex,
+            ..query("^a.{2}b$")
+        };
+        assert_eq!(positions(&wb, &regex), vec![(0, 1, 0)]);
+        let bad = FindOptions {
+            mode: FindMode::Regex,
+            ..query("(")
+        };
+        assert!(wb.find_all(&bad).is_err());
+
+        assert_eq!(positions(&wb, &query("3")), vec![(0, 2, 0)]);
+        let formulas = FindOptions {
+            look_in: FindLookIn::Formulas,
+            ..query("sum")
+        };
+        assert_eq!(positions(&wb, &formulas), vec![(0, 2, 0)]);
+        assert!(positions(&wb, &query("sum")).is_empty());
+    }
+
+    #[test]
+    fn finds_by_format() {
+        let mut wb = Workbook::default();
+        input(&mut wb, 0, 0, 0, "x");
+        input(&mut wb, 0, 1, 0, "x");
+        apply(
+            &mut wb,
+            vec![
+                CellStyleUpdate {
+                    sheet_idx: 0,
+                    row: 1,
+                    col: 0,
+                    ty: StyleUpdateType {
+                        set_font_bold: Some(true),
+                        ..Default::default()
+                    },
+                }
+                .into(),
+            ],
+        );
+        let bold = FindOptions {
+            format: Some(FindFormat {
+                bold: Some(true),
+                ..Default::default()
+            }),
+            ..query("x")
+        };
+        assert_eq!(positions(&wb, &bold), vec![(0, 1, 0)]);
+    }
+
+    #[test]
+    fn replace_all_is_one_undo_step_and_keeps_formulas_valid() {
+        let mut wb = Workbook::default();
+        input(&mut wb, 0, 0, 0, "5");
+        input(&mut wb, 0, 1, 0, "=A1*2");
+        input(&mut wb, 0, 2, 0, "cat and cat");
+        let formulas = |q: &str| FindOptions {
+            look_in: FindLookIn::Formulas,
+            ..query(q)
+        };
+
+        wb.replace_all(&formulas("A1"), "A3").unwrap();
+        wb.replace_all(&formulas("cat"), "dog").unwrap();
+        let ws = wb.get_sheet_by_idx(0).unwrap();
+        assert_eq!(ws.get_formula(1, 0).unwrap(), "A3 * 2");
+        assert_eq!(ws.get_cell_info(2, 0).unwrap().display_text, "dog and dog");
+
+        // A formula is found as the formula bar shows it.
+        assert_eq!(positions(&wb, &formulas("* 2")), vec![(0, 1, 0)]);
+        assert!(positions(&wb, &formulas("*2")).is_empty());
+
+        // A replacement that breaks a formula changes nothing.
+        assert!(wb.replace_all(&formulas("* 2"), "*(").is_err());
+        let ws = wb.get_sheet_by_idx(0).unwrap();
+        assert_eq!(ws.get_formula(1, 0).unwrap(), "A3 * 2");
+
+        // Looking in values, the computed `10` of A2 is not rewritten but
+        // handed back.
+        input(&mut wb, 0, 2, 0, "10");
+        input(&mut wb, 0, 1, 0, "=A1*2");
+        let result = wb.replace_all(&query("10"), "11").unwrap();
+        let skipped: Vec<_> = result.skipped.iter().map(|f| (f.row, f.col)).collect();
+        assert_eq!(skipped, vec![(1, 0)]);
+        let ws = wb.get_sheet_by_idx(0).unwrap();
+        assert_eq!(ws.get_formula(1, 0).unwrap(), "A1 * 2");
+        assert_eq!(ws.get_cell_info(2, 0).unwrap().display_text, "11");
+
+        assert!(wb.undo());
+        let ws = wb.get_sheet_by_idx(0).unwrap();
+        assert_eq!(ws.get_cell_info(2, 0).unwrap().display_text, "10");
+    }
+
+    #[test]
+    fn numbers_are_typed_as_their_format_shows_them() {
+        let mut wb = Workbook::default();
+        input(&mut wb, 0, 0, 0, "2026-01-02");
+        input(&mut wb, 0, 1, 0, "12%");
+        input(&mut wb, 0, 2, 0, "0.1");
+        let formulas = |q: &str| FindOptions {
+            look_in: FindLookIn::Formulas,
+            ..query(q)
+        };
+        assert_eq!(positions(&wb, &formulas("2026")), vec![(0, 0, 0)]);
+        assert!(positions(&wb, &formulas("46024")).is_empty());
+        assert_eq!(positions(&wb, &formulas("%")), vec![(0, 1, 0)]);
+
+        // The replaced date is typed back in, and read as a date again.
+        wb.replace_all(&formulas("2026"), "2027").unwrap();
+        let ws = wb.get_sheet_by_idx(0).unwrap();
+        assert_eq!(ws.get_value(0, 0).unwrap(), Value::Number(46389.));
+        assert_eq!(positions(&wb, &formulas("0.1")), vec![(0, 2, 0)]);
+    }
+}
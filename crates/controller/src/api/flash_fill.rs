@@ -0,0 +1,499 @@
+//! Flash Fill: fill a column from a few typed examples.
+//!
+//! Like [`predict_fill`](Workbook::predict_fill) this is a **pure,
+//! read-only query**. The rows of a block whose target cell is filled in
+//! are examples: the other cells of the row are the inputs, the target is
+//! the output. A small string program is inferred from them and run on the
+//! rows whose target is blank, and the predictions come back as
+//! [`CellInput`]s for the caller to commit as one transaction.
+//!
+//! ## Programs
+//!
+//! A program concatenates atoms. An atom is a constant, or a piece of an
+//! input cell's text with a case change applied:
+//!   - the whole text,
+//!   - a word (a run of letters and digits), counted from either end,
+//!   - a field between occurrences of a delimiter such as `@` or `,`,
+//!   - the first letter of a word, for initials,
+//!   - a fixed number of leading or trailing characters.
+//!
+//! ## Search
+//!
+//! Candidate atoms are matched against the first example's output, which
+//! gives a graph of the ways to spell it. The graph is searched cheapest
+//! program first, and a partial program is dropped as soon as it does not
+//! spell a prefix of every other example. Extractions are cheaper than
+//! constants, so the program that generalizes is found before one that
+//! hard-codes the first example.
+
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashSet};
+
+use crate::Workbook;
+use crate::api::fill::FillRange;
+use crate::api::worksheet::{Worksheet, format_display_text};
+use crate::edit_action::CellInput;
+use crate::errors::{Error, Result};
+
+/// Search states expanded before giving up on the examples.
+const SEARCH_LIMIT: usize = 200_000;
+
+/// The longest run of leading or trailing characters tried as an atom.
+const MAX_AFFIX: usize = 8;
+
+/// The longest constant tried as one atom. Longer ones are spelled by
+/// consecutive constants, so a long example does not get a constant for
+/// every stretch of its output.
+const MAX_CONST: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Case {
+    Keep,
+    Lower,
+    Upper,
+    /// The first letter of each word upper case, the rest lower case.
+    Title,
+}
+
+/// Which piece of an input's text an atom takes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Piece {
+    Whole,
+    /// The word at `index`, from the end when negative (-1 is the last).
+    Word(i64),
+    /// The first letter of a word.
+    Initial(i64),
+    /// The field at `index` between occurrences of the delimiter.
+    Field(char, i64),
+    Prefix(usize),
+    Suffix(usize),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Atom {
+    Const(Vec<char>),
+    Extract {
+        input: usize,
+        piece: Piece,
+        case: Case,
+    },
+}
+
+/// A search state: the length of the first example's output spelled so
+/// far, the lengths spelled of the other examples, and how.
+struct State {
+    cost: f64,
+    pos: usize,
+    others: Vec<usize>,
+    program: Vec<Atom>,
+}
+
+impl Workbook {
+    /// Predict a Flash Fill of column `target_col` over the rows of `range`.
+    ///
+    /// The columns of `range` other than `target_col` are the inputs. Rows
+    /// with a value in `target_col` are the examples; the others get a
+    /// prediction, unless their inputs are blank or the program does not
+    /// apply to them. Fails when there is no example or no program fits.
+    pub fn predict_flash_fill(
+        &self,
+        sheet_idx: usize,
+        range: FillRange,
+        target_col: usize,
+    ) -> Result<Vec<CellInput>> {
+        let ws = self.get_sheet_by_idx(sheet_idx)?;
+        let cells = ws.predict_flash_fill(range, target_col)?;
+        Ok(cells
+            .into_iter()
+            .map(|(row, col, content)| CellInput {
+                sheet_idx,
+                row,
+                col,
+                content,
+                skip_recognition: Some(true),
+            })
+            .collect())
+    }
+}
+
+impl<'a> Worksheet<'a> {
+    pub(crate) fn predict_flash_fill(
+        &self,
+        range: FillRange,
+        target_col: usize,
+    ) -> Result<Vec<(usize, usize, String)>> {
+        let input_cols: Vec<usize> = (range.start_col..=range.end_col)
+            .filter(|c| *c != target_col)
+            .collect();
+        let mut examples = Vec::new();
+        let mut blanks = Vec::new();
+        for row in range.start_row..=range.end_row {
+            let inputs = input_cols
+                .iter()
+                .map(|col| self.display_chars(row, *col))
+                .collect::<Result<Vec<_>>>()?;
+            if inputs.iter().all(|i| i.is_empty()) {
+                continue;
+            }
+            let output = self.display_chars(row, target_col)?;
+            if output.is_empty() {
+                blanks.push((row, inputs));
+            } else {
+                examples.push((inputs, output));
+            }
+        }
+        if examples.is_empty() {
+            return Err(Error::PayloadError(
+                "flash fill needs at least one example".to_string(),
+            ));
+        }
+        let program = synthesize(&examples).ok_or_else(|| {
+            Error::PayloadError("flash fill found no pattern matching the examples".to_string())
+        })?;
+        Ok(blanks
+            .into_iter()
+            .filter_map(|(row, inputs)| {
+                let out = run(&program, &inputs)?;
+                Some((row, target_col, out.into_iter().collect()))
+            })
+            .collect())
+    }
+
+    /// The text a cell shows.
+    fn display_chars(&self, row: usize, col: usize) -> Result<Vec<char>> {
+        let cell_id = self
+            .controller
+            .status
+            .navigator
+            .fetch_cell_id(&self.sheet_id, row, col)?;
+        let value = self.get_value_by_id(&cell_id)?;
+        let style = self.get_raw_style_by_id(&cell_id)?;
+        let (text, _) = format_display_text(&value, &style.formatter, None);
+        Ok(text.chars().collect())
+    }
+}
+
+impl PartialEq for State {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for State {}
+
+impl PartialOrd for State {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for State {
+    // Reversed, so the max-heap pops the cheapest state.
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.cost.total_cmp(&self.cost)
+    }
+}
+
+/// The cheapest program that spells every example's output from its
+/// inputs.
+fn synthesize(examples: &[(Vec<Vec<char>>, Vec<char>)]) -> Option<Vec<Atom>> {
+    let (inputs0, output0) = &examples[0];
+    let edges = edges(inputs0, output0);
+    let mut heap = BinaryHeap::new();
+    let mut seen = HashSet::new();
+    heap.push(State {
+        cost: 0.0,
+        pos: 0,
+        others: vec![0; examples.len() - 1],
+        program: Vec::new(),
+    });
+    let mut expanded = 0;
+    while let Some(state) = heap.pop() {
+        let others_done = examples[1..]
+            .iter()
+            .zip(&state.others)
+            .all(|((_, out), p)| *p == out.len());
+        if state.pos == output0.len() && others_done {
+            return Some(state.program);
+        }
+        if !seen.insert((state.pos, state.others.clone())) {
+            continue;
+        }
+        expanded += 1;
+        if expanded > SEARCH_LIMIT {
+            return None;
+        }
+        for (end, atom, cost) in &edges[state.pos] {
+            // The atom must spell the next stretch of every other example.
+            let others: Option<Vec<usize>> = examples[1..]
+                .iter()
+                .zip(&state.others)
+                .map(|((inputs, out), p)| {
+                    let s = eval(atom, inputs)?;
+                    out[*p..].starts_with(&s).then_some(p + s.len())
+                })
+                .collect();
+            let Some(others) = others else {
+                continue;
+            };
+            let mut program = state.program.clone();
+            program.push(atom.clone());
+            heap.push(State {
+                cost: state.cost + cost,
+                pos: *end,
+                others,
+                program,
+            });
+        }
+    }
+    None
+}
+
+/// For each position of `output`, the atoms that spell a stretch of it
+/// starting there: `(end, atom, cost)`.
+fn edges(inputs: &[Vec<char>], output: &[char]) -> Vec<Vec<(usize, Atom, f64)>> {
+    let mut edges = vec![Vec::new(); output.len() + 1];
+    for (input, text) in inputs.iter().enumerate() {
+        for (piece, piece_cost) in pieces(text) {
+            for case in [Case::Keep, Case::Lower, Case::Upper, Case::Title] {
+                let atom = Atom::Extract {
+                    input,
+                    piece: piece.clone(),
+                    case,
+                };
+                let Some(s) = eval(&atom, inputs) else {
+                    continue;
+                };
+                if s.is_empty() {
+                    continue;
+                }
+                // A case change that changes nothing is the same atom.
+                if case != Case::Keep && Some(&s) == extract(text, &piece).as_ref() {
+                    continue;
+                }
+                let cost = piece_cost + if case == Case::Keep { 0.0 } else { 0.5 };
+                for start in 0..output.len() {
+                    if output[start..].starts_with(&s) {
+                        edges[start].push((start + s.len(), atom.clone(), cost));
+                    }
+                }
+            }
+        }
+    }
+    for (start, from) in edges.iter_mut().enumerate() {
+        for end in start + 1..=output.len().min(start + MAX_CONST) {
+            let c = output[start..end].to_vec();
+            // Letters and digits usually come from an input, punctuation
+            // and spaces are usually typed.
+            let cost = 1.0
+                + c.iter()
+                    .map(|ch| if ch.is_alphanumeric() { 5.0 } else { 0.2 })
+                    .sum::<f64>();
+            from.push((end, Atom::Const(c), cost));
+        }
+    }
+    edges
+}
+
+/// The pieces worth trying on `text`, with their cost. Indices near the
+/// ends are cheaper, the first word cheapest, then the last.
+fn pieces(text: &[char]) -> Vec<(Piece, f64)> {
+    let index_cost = |i: i64| 0.01 * if i >= 0 { i * 2 } else { -i * 2 - 1 } as f64;
+    let words = words(text).len() as i64;
+    let mut pieces = vec![(Piece::Whole, 1.0)];
+    for i in 0..words {
+        for idx in [i, i - words] {
+            pieces.push((Piece::Word(idx), 1.0 + index_cost(idx)));
+            pieces.push((Piece::Initial(idx), 1.5 + index_cost(idx)));
+        }
+    }
+    let mut delims: Vec<char> = text
+        .iter()
+        .copied()
+        .filter(|c| !c.is_alphanumeric())
+        .collect();
+    delims.sort_unstable();
+    delims.dedup();
+    for d in delims {
+        let fields = text.split(|c| *c == d).count() as i64;
+        for i in 0..fields {
+            for idx in [i, i - fields] {
+                pieces.push((Piece::Field(d, idx), 1.2 + index_cost(idx)));
+            }
+        }
+    }
+    for n in 1..=MAX_AFFIX.min(text.len()) {
+        pieces.push((Piece::Prefix(n), 3.0));
+        pieces.push((Piece::Suffix(n), 3.0));
+    }
+    pieces
+}
+
+/// The program's output for one row, or `None` if a piece it needs is
+/// missing from the inputs.
+fn run(program: &[Atom], inputs: &[Vec<char>]) -> Option<Vec<char>> {
+    let mut out = Vec::new();
+    for atom in program {
+        out.extend(eval(atom, inputs)?);
+    }
+    Some(out)
+}
+
+fn eval(atom: &Atom, inputs: &[Vec<char>]) -> Option<Vec<char>> {
+    match atom {
+        Atom::Const(c) => Some(c.clone()),
+        Atom::Extract { input, piece, case } => {
+            let s = extract(inputs.get(*input)?, piece)?;
+            Some(apply_case(&s, *case))
+        }
+    }
+}
+
+fn extract(text: &[char], piece: &Piece) -> Option<Vec<char>> {
+    match piece {
+        Piece::Whole => Some(text.to_vec()),
+        Piece::Word(i) => nth(&words(text), *i).map(|w| w.to_vec()),
+        Piece::Initial(i) => nth(&words(text), *i).map(|w| w[..1].to_vec()),
+        Piece::Field(d, i) => {
+            let fields: Vec<&[char]> = text.split(|c| c == d).collect();
+            // Without the delimiter there is nothing to split.
+            if fields.len() < 2 {
+                return None;
+            }
+            nth(&fields, *i).map(|f| f.to_vec())
+        }
+        Piece::Prefix(n) => (text.len() >= *n).then(|| text[..*n].to_vec()),
+        Piece::Suffix(n) => (text.len() >= *n).then(|| text[text.len() - n..].to_vec()),
+    }
+}
+
+/// Runs of letters and digits.
+fn words(text: &[char]) -> Vec<&[char]> {
+    text.split(|c| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect()
+}
+
+fn nth<'a>(items: &[&'a [char]], i: i64) -> Option<&'a [char]> {
+    let idx = if i >= 0 { i } else { items.len() as i64 + i };
+    usize::try_from(idx)
+        .ok()
+        .and_then(|i| items.get(i).copied())
+}
+
+fn apply_case(s: &[char], case: Case) -> Vec<char> {
+    match case {
+        Case::Keep => s.to_vec(),
+        Case::Lower => s.iter().flat_map(|c| c.to_lowercase()).collect(),
+        Case::Upper => s.iter().flat_map(|c| c.to_uppercase()).collect(),
+        Case::Title => {
+            let mut out = Vec::with_capacity(s.len());
+            let mut word_start = true;
+            for c in s {
+                if word_start {
+                    out.extend(c.to_uppercase());
+                } else {
+                    out.extend(c.to_lowercase());
+                }
+                word_start = !c.is_alphanumeric();
+            }
+            out
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Workbook;
+    use crate::api::FillRange;
+    use crate::edit_action::{CellInput, EditAction, EditPayload, PayloadsAction};
+
+    fn input(wb: &mut Workbook, row: usize, col: usize, content: &str) {
+        wb.handle_action(EditAction::Payloads(PayloadsAction {
+            payloads: vec![EditPayload::CellInput(CellInput {
+                sheet_idx: 0,
+                row,
+                col,
+                content: content.to_string(),
+                ..Default::default()
+            })],
+            undoable: true,
+            init: false,
+        }));
+    }
+
+    /// Column A holds the inputs, column B the `examples` from the top.
+    fn flash_fill(inputs: &[&str], examples: &[&str]) -> Vec<String> {
+        let mut wb = Workbook::default();
+        for (row, s) in inputs.iter().enumerate() {
+            input(&mut wb, row, 0, s);
+        }
+        for (row, s) in examples.iter().enumerate() {
+            input(&mut wb, row, 1, s);
+        }
+        let range = FillRange {
+            start_row: 0,
+            start_col: 0,
+            end_row: inputs.len() - 1,
+            end_col: 1,
+        };
+        wb.predict_flash_fill(0, range, 1)
+            .unwrap()
+            .into_iter()
+            .map(|c| c.content)
+            .collect()
+    }
+
+    #[test]
+    fn first_names() {
+        let res = flash_fill(&["John Smith", "Mary Ann Jones", "Wei Zhang"], &["John"]);
+        assert_eq!(res, vec!["Mary", "Wei"]);
+    }
+
+    #[test]
+    fn reorder_with_constants() {
+        let res = flash_fill(&["John Smith", "Mary Jones", "Wei Zhang"], &["Smith, J."]);
+        assert_eq!(res, vec!["Jones, M.", "Zhang, W."]);
+    }
+
+    #[test]
+    fn fields_and_case() {
+        let res = flash_fill(
+            &["john.smith@example.com", "mary.jones@example.org"],
+            &["JOHN.SMITH"],
+        );
+        assert_eq!(res, vec!["MARY.JONES"]);
+    }
+
+    #[test]
+    fn second_example_disambiguates() {
+        // From the first example alone, "Ann" could be the second or the
+        // last word.
+        let res = flash_fill(&["Mary Ann", "Lee Wong Kai", "Jo Max"], &["Ann", "Wong"]);
+        assert_eq!(res, vec!["Max"]);
+    }
+
+    #[test]
+    fn long_example() {
+        let tail = " - ".to_string() + "abc-123 ".repeat(300).trim_end();
+        let res = flash_fill(&["John Smith", "Wei Zhang"], &[&format!("Smith{}", tail)]);
+        assert_eq!(res, vec![format!("Zhang{}", tail)]);
+    }
+
+    #[test]
+    fn no_pattern() {
+        let mut wb = Workbook::default();
+        input(&mut wb, 0, 0, "abc");
+        input(&mut wb, 0, 1, "xyz");
+        input(&mut wb, 1, 0, "def");
+        input(&mut wb, 1, 1, "uvw");
+        input(&mut wb, 2, 0, "ghi");
+        let range = FillRange {
+            start_row: 0,
+            start_col: 0,
+            end_row: 2,
+            end_col: 1,
+        };
+        assert!(wb.predict_flash_fill(0, range, 1).is_err());
+    }
+}
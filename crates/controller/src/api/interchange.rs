@@ -0,0 +1,1592 @@
+//! External clipboard formats: `text/plain` as tab-separated values and
+//! `text/html` table fragments, as Excel and Google Sheets write and read
+//! them.
+//!
+//! Both map onto [`ClipboardData`], so a paste from another application goes
+//! through [`Workbook::paste_payloads`] like an internal one, and the WASM and
+//! Tauri hosts only move strings between the system clipboard and the engine.
+//!
+//! ## TSV
+//!
+//! One line per row, cells separated by tabs, each cell as it is displayed.
+//! A cell holding a tab, a line break or a `"` is quoted, with quotes
+//! doubled. On the way in every cell is read the way a typed entry is: `=`
+//! starts a formula, and numbers, dates and percentages are recognized with
+//! the workbook's input locale, their number format kept.
+//!
+//! ## HTML
+//!
+//! A `<table>` with the display text in each cell, and the rest in the
+//! attributes both spreadsheets understand:
+//!
+//! - the value: Excel's `x:num`, `x:bool`, `x:str` and `x:err`, and Google's
+//!   `data-sheets-value`;
+//! - the formula: Google's `data-sheets-formula`, in R1C1 so it does not
+//!   depend on where it lands, and Excel's `x:fmla`;
+//! - the number format: Excel's `mso-number-format` and Google's
+//!   `data-sheets-numberformat`;
+//! - fonts, fill colour and alignment as CSS, inline or in `<style>` classes;
+//! - merges as `rowspan`/`colspan`, and column widths as `<col width>`.
+//!
+//! ## Anchoring
+//!
+//! Text from outside has no source position. The parsers take the cell the
+//! text will be pasted at and pretend it was copied from there: R1C1 formulas
+//! are resolved against it, and A1 formulas paste as written.
+
+use std::collections::{HashMap, HashSet};
+
+use gents_derives::TS;
+use logisheets_workbook::prelude::{
+    CtCellAlignment, CtColor, CtFill, CtFontName, CtFontSize, CtPatternFill, CtUnderlineProperty,
+    StHorizontalAlignment, StPatternType, StUnderlineValues, StVerticalAlignment,
+};
+
+use crate::controller::style::StyleConverter;
+use crate::errors::Error;
+use crate::input_recognition::recognize;
+use crate::navigator::sheet_nav::{MAX_COL_CNT, MAX_ROW_CNT};
+use crate::style_manager::RawStyle;
+use crate::{MergeCell, Value};
+
+use super::Workbook;
+use super::clipboard::{
+    ClipboardCell, ClipboardData, RefPart, rewrite_references, split_reference,
+};
+use super::worksheet::format_display_text;
+
+const ERRORS: [&str; 7] = [
+    "#NULL!", "#DIV/0!", "#VALUE!", "#REF!", "#NAME?", "#NUM!", "#N/A",
+];
+
+/// The most cells a pasted HTML table may cover, spans included. Each one
+/// becomes a `ClipboardCell`, so a single huge `rowspan` would otherwise
+/// allocate the whole sheet.
+const MAX_HTML_CELLS: usize = 100_000;
+
+/// Excel's default column, in pixels. Columns of this width are written
+/// without one.
+const DEFAULT_COL_PX: f64 = 64.;
+
+/// A copy in every format the host puts on the system clipboard.
+#[derive(Debug, Clone, TS)]
+#[ts(file_name = "clipboard_contents.ts", rename_all = "camelCase")]
+pub struct ClipboardContents {
+    /// Kept by the host for pastes inside the workbook.
+    pub data: ClipboardData,
+    /// For `text/plain`.
+    pub tsv: String,
+    /// For `text/html`.
+    pub html: String,
+}
+
+impl Workbook {
+    /// `data` together with its TSV and HTML forms.
+    pub fn clipboard_contents(&self, data: ClipboardData) -> ClipboardContents {
+        ClipboardContents {
+            tsv: self.clipboard_to_tsv(&data),
+            html: self.clipboard_to_html(&data),
+            data,
+        }
+    }
+
+    /// `data` as tab-separated display text.
+    pub fn clipboard_to_tsv(&self, data: &ClipboardData) -> String {
+        let mut out = String::new();
+        for r in 0..data.row_count {
+            for c in 0..data.col_count {
+                if c > 0 {
+                    out.push('\t');
+                }
+                let cell = &data.cells[r * data.col_count + c];
+                let (text, _) = format_display_text(&cell.value, &cell.style.formatter, None);
+                if text.contains(['\t', '\n', '\r', '"']) {
+                    out.push('"');
+                    out.push_str(&text.replace('"', "\"\""));
+                    out.push('"');
+                } else {
+                    out.push_str(&text);
+                }
+            }
+            out.push_str("\r\n");
+        }
+        out
+    }
+
+    /// `data` as an HTML table that Excel and Google Sheets paste with its
+    /// values, formulas, formats and merges.
+    pub fn clipboard_to_html(&self, data: &ClipboardData) -> String {
+        let mut covered = HashSet::new();
+        let mut spans = HashMap::new();
+        for m in &data.merges {
+            for r in m.start_row..=m.end_row {
+                for c in m.start_col..=m.end_col {
+                    covered.insert((r, c));
+                }
+            }
+            covered.remove(&(m.start_row, m.start_col));
+            spans.insert(
+                (m.start_row, m.start_col),
+                (m.end_row - m.start_row + 1, m.end_col - m.start_col + 1),
+            );
+        }
+        let mut out = String::from(
+            "<meta charset=\"utf-8\"><table xmlns:x=\"urn:schemas-microsoft-com:office:excel\" \
+             style=\"border-collapse:collapse\">",
+        );
+        out.push_str("<colgroup>");
+        for width in &data.col_widths {
+            match width {
+                Some(w) => out.push_str(&format!("<col width=\"{}\">", (w * 7. + 5.).round())),
+                None => out.push_str("<col>"),
+            }
+        }
+        out.push_str("</colgroup><tbody>");
+        for r in 0..data.row_count {
+            out.push_str("<tr>");
+            for c in 0..data.col_count {
+                if covered.contains(&(r, c)) {
+                    continue;
+                }
+                let cell = &data.cells[r * data.col_count + c];
+                out.push_str("<td");
+                if let Some((rows, cols)) = spans.get(&(r, c)) {
+                    if *rows > 1 {
+                        out.push_str(&format!(" rowspan=\"{}\"", rows));
+                    }
+                    if *cols > 1 {
+                        out.push_str(&format!(" colspan=\"{}\"", cols));
+                    }
+                }
+                let css = self.style_css(&cell.style);
+                if !css.is_empty() {
+                    push_attr(&mut out, "style", &css);
+                }
+                match &cell.value {
+                    Value::Number(n) => {
+                        push_attr(&mut out, "x:num", &n.to_string());
+                        let v = serde_json::json!({"1": 3, "3": n});
+                        push_attr(&mut out, "data-sheets-value", &v.to_string());
+                    }
+                    Value::Bool(b) => {
+                        push_attr(&mut out, "x:bool", if *b { "TRUE" } else { "FALSE" });
+                        let v = serde_json::json!({"1": 4, "4": *b as u8});
+                        push_attr(&mut out, "data-sheets-value", &v.to_string());
+                    }
+                    Value::Str(s) => {
+                        push_attr(&mut out, "x:str", s);
+                        let v = serde_json::json!({"1": 2, "2": s});
+                        push_attr(&mut out, "data-sheets-value", &v.to_string());
+                    }
+                    Value::Error(e) => push_attr(&mut out, "x:err", e),
+                    Value::Empty => {}
+                }
+                if let Some(f) = &cell.formula {
+                    let (row, col) = (data.start_row + r, data.start_col + c);
+                    push_attr(&mut out, "x:fmla", &format!("={}", f));
+                    push_attr(
+                        &mut out,
+                        "data-sheets-formula",
+                        &format!("={}", a1_to_r1c1(f, row, col)),
+                    );
+                }
+                out.push('>');
+                let (text, _) = format_display_text(&cell.value, &cell.style.formatter, None);
+                out.push_str(&escape_html(&text).replace('\n', "<br>"));
+                out.push_str("</td>");
+            }
+            out.push_str("</tr>");
+        }
+        out.push_str("</tbody></table>");
+        out
+    }
+
+    /// Read tab-separated text as if it were copied from `(row, col)` of the
+    /// sheet at `sheet_idx`.
+    pub fn clipboard_from_tsv(
+        &self,
+        text: &str,
+        sheet_idx: usize,
+        row: usize,
+        col: usize,
+    ) -> crate::errors::Result<ClipboardData> {
+        let rows = split_tsv(text);
+        let col_count = rows.iter().map(Vec::len).max().unwrap_or(0);
+        if col_count == 0 {
+            return Err(Error::PayloadError(
+                "no cells in clipboard text".to_string(),
+            ));
+        }
+        let base = self.default_style();
+        let mut cells = Vec::with_capacity(rows.len() * col_count);
+        for fields in &rows {
+            for c in 0..col_count {
+                let text = fields.get(c).map_or("", String::as_str);
+                cells.push(self.read_text(text, &base));
+            }
+        }
+        Ok(ClipboardData {
+            sheet_idx,
+            start_row: row,
+            start_col: col,
+            row_count: rows.len(),
+            col_count,
+            cells,
+            col_widths: vec![None; col_count],
+            merges: vec![],
+            cut: false,
+        })
+    }
+
+    /// Read the first table of an HTML fragment as if it were copied from
+    /// `(row, col)` of the sheet at `sheet_idx`.
+    pub fn clipboard_from_html(
+        &self,
+        html: &str,
+        sheet_idx: usize,
+        row: usize,
+        col: usize,
+    ) -> crate::errors::Result<ClipboardData> {
+        let table = HtmlTable::parse(html)?;
+        let row_count = table
+            .cells
+            .iter()
+            .map(|c| c.row + c.rowspan)
+            .max()
+            .unwrap_or(0);
+        let col_count = table
+            .cells
+            .iter()
+            .map(|c| c.col + c.colspan)
+            .max()
+            .unwrap_or(0);
+        if row_count == 0 || col_count == 0 {
+            return Err(Error::PayloadError(
+                "no table in clipboard HTML".to_string(),
+            ));
+        }
+        let base = self.default_style();
+        let mut cells = vec![
+            ClipboardCell {
+                value: Value::Empty,
+                formula: None,
+                style: base.clone(),
+            };
+            row_count * col_count
+        ];
+        let mut merges = Vec::new();
+        for cell in &table.cells {
+            let decls = table.declarations(cell);
+            cells[cell.row * col_count + cell.col] =
+                self.read_html_cell(cell, &decls, &base, row + cell.row, col + cell.col);
+            if cell.rowspan > 1 || cell.colspan > 1 {
+                merges.push(MergeCell {
+                    start_row: cell.row,
+                    start_col: cell.col,
+                    end_row: cell.row + cell.rowspan - 1,
+                    end_col: cell.col + cell.colspan - 1,
+                });
+            }
+        }
+        let mut col_widths = table.col_widths;
+        col_widths.resize(col_count, None);
+        Ok(ClipboardData {
+            sheet_idx,
+            start_row: row,
+            start_col: col,
+            row_count,
+            col_count,
+            cells,
+            col_widths,
+            merges,
+            cut: false,
+        })
+    }
+
+    fn default_style(&self) -> RawStyle {
+        self.status().style_manager.get_style(0)
+    }
+
+    /// A cell read from text, the way a typed entry is.
+    fn read_text(&self, text: &str, base: &RawStyle) -> ClipboardCell {
+        let mut style = base.clone();
+        let (value, formula) = if text.is_empty() {
+            (Value::Empty, None)
+        } else if let Some(f) = text.strip_prefix('=').filter(|f| !f.is_empty()) {
+            (Value::Empty, Some(f.to_string()))
+        } else if text.eq_ignore_ascii_case("TRUE") || text.eq_ignore_ascii_case("FALSE") {
+            (Value::Bool(text.eq_ignore_ascii_case("TRUE")), None)
+        } else if ERRORS.contains(&text) {
+            (Value::Error(text.to_string()), None)
+        } else if let Some(r) = recognize(text, self.get_input_locale()) {
+            if let Some(format) = r.format {
+                style.formatter = format;
+            }
+            (Value::Number(r.value), None)
+        } else {
+            (Value::Str(text.to_string()), None)
+        };
+        ClipboardCell {
+            value,
+            formula,
+            style,
+        }
+    }
+
+    fn read_html_cell(
+        &self,
+        cell: &HtmlCell,
+        decls: &[(String, String)],
+        base: &RawStyle,
+        row: usize,
+        col: usize,
+    ) -> ClipboardCell {
+        let attr = |name: &str| {
+            cell.attrs
+                .iter()
+                .find(|(k, _)| k == name)
+                .map(|(_, v)| v.as_str())
+        };
+        let text = cell.text.as_str();
+        let typed = if let Some(v) = attr("data-sheets-value").and_then(google_value) {
+            Some(v)
+        } else if let Some(n) = attr("x:num") {
+            let n = n
+                .trim()
+                .parse::<f64>()
+                .ok()
+                .or_else(|| recognize(text, self.get_input_locale()).map(|r| r.value));
+            n.map(Value::Number)
+        } else if let Some(b) = attr("x:bool") {
+            let b = if b.is_empty() { text } else { b };
+            Some(Value::Bool(b.eq_ignore_ascii_case("TRUE") || b == "1"))
+        } else if let Some(e) = attr("x:err") {
+            Some(Value::Error(
+                if e.is_empty() { text } else { e }.to_string(),
+            ))
+        } else {
+            attr("x:str").map(|s| Value::Str(if s.is_empty() { text } else { s }.to_string()))
+        };
+        let formula = attr("data-sheets-formula")
+            .map(|f| r1c1_to_a1(f.strip_prefix('=').unwrap_or(f), row, col))
+            .or_else(|| attr("x:fmla").map(|f| f.strip_prefix('=').unwrap_or(f).to_string()));
+        let mut result = match typed {
+            Some(value) => ClipboardCell {
+                value,
+                formula: None,
+                style: base.clone(),
+            },
+            None => self.read_text(text, base),
+        };
+        if formula.is_some() {
+            if result.formula.is_some() {
+                result.value = Value::Empty;
+            }
+            result.formula = formula;
+        }
+        if let Some(format) = apply_css(&mut result.style, decls) {
+            result.style.formatter = format;
+        }
+        let google_format = attr("data-sheets-numberformat")
+            .and_then(|s| serde_json::from_str::<serde_json::Value>(s).ok())
+            .and_then(|v| v.get("2")?.as_str().map(str::to_string));
+        if let Some(format) = google_format {
+            result.style.formatter = format;
+        }
+        result
+    }
+
+    /// The CSS declarations that show `style`.
+    fn style_css(&self, style: &RawStyle) -> String {
+        let converter = StyleConverter {
+            theme_manager: self.theme(),
+        };
+        let hex = |c: &CtColor| {
+            let c = converter.convert_color_pub(c.clone());
+            match (c.red, c.green, c.blue) {
+                (Some(r), Some(g), Some(b)) => {
+                    Some(format!("#{:02x}{:02x}{:02x}", r as u8, g as u8, b as u8))
+                }
+                _ => None,
+            }
+        };
+        let mut css = String::new();
+        let font = &style.font;
+        if font.bold {
+            css.push_str("font-weight:bold;");
+        }
+        if font.italic {
+            css.push_str("font-style:italic;");
+        }
+        let underline = font
+            .underline
+            .as_ref()
+            .is_some_and(|u| u.val != StUnderlineValues::None);
+        match (underline, font.strike) {
+            (true, true) => css.push_str("text-decoration:underline line-through;"),
+            (true, false) => css.push_str("text-decoration:underline;"),
+            (false, true) => css.push_str("text-decoration:line-through;"),
+            (false, false) => {}
+        }
+        let color = font.color.as_ref().filter(|c| c.auto != Some(true));
+        if let Some(hex) = color.and_then(hex) {
+            css.push_str(&format!("color:{};", hex));
+        }
+        if let Some(sz) = &font.sz {
+            css.push_str(&format!("font-size:{}pt;", sz.val));
+        }
+        if let Some(name) = &font.name {
+            css.push_str(&format!("font-family:'{}';", name.val.replace('\'', "\\'")));
+        }
+        let fill = match &style.fill {
+            CtFill::PatternFill(CtPatternFill {
+                fg_color: Some(color),
+                pattern_type: Some(StPatternType::Solid),
+                ..
+            }) => hex(color),
+            _ => None,
+        };
+        if let Some(hex) = fill {
+            css.push_str(&format!("background:{};", hex));
+        }
+        if let Some(alignment) = &style.alignment {
+            let horizontal = match alignment.horizontal {
+                Some(StHorizontalAlignment::Left) => Some("left"),
+                Some(StHorizontalAlignment::Center | StHorizontalAlignment::CenterContinuous) => {
+                    Some("center")
+                }
+                Some(StHorizontalAlignment::Right) => Some("right"),
+                Some(StHorizontalAlignment::Justify | StHorizontalAlignment::Distributed) => {
+                    Some("justify")
+                }
+                _ => None,
+            };
+            if let Some(h) = horizontal {
+                css.push_str(&format!("text-align:{};", h));
+            }
+            let vertical = match alignment.vertical {
+                Some(StVerticalAlignment::Top) => Some("top"),
+                Some(StVerticalAlignment::Center) => Some("middle"),
+                Some(StVerticalAlignment::Bottom) => Some("bottom"),
+                _ => None,
+            };
+            if let Some(v) = vertical {
+                css.push_str(&format!("vertical-align:{};", v));
+            }
+        }
+        if !style.formatter.is_empty() && style.formatter != "General" {
+            let escaped = style.formatter.replace('\\', "\\\\").replace('"', "\\22 ");
+            css.push_str(&format!("mso-number-format:\"{}\";", escaped));
+        }
+        css
+    }
+}
+
+/// Split tab-separated text into rows of fields, honouring quotes. A final
+/// line break does not start another row.
+fn split_tsv(text: &str) -> Vec<Vec<String>> {
+    split_delimited(text, '\t')
+}
+
+/// Split text into rows of fields separated by `sep`, honouring quotes. CSV
+/// is read with `','`.
+pub(crate) fn split_delimited(text: &str, sep: char) -> Vec<Vec<String>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut chars = text.chars().peekable();
+    let mut at_field_start = true;
+    let mut quoted = false;
+    while let Some(ch) = chars.next() {
+        if quoted {
+            if ch == '"' {
+                if chars.peek() == Some(&'"') {
+                    chars.next();
+                    field.push('"');
+                } else {
+                    quoted = false;
+                }
+            } else {
+                field.push(ch);
+            }
+            continue;
+        }
+        match ch {
+            '"' if at_field_start => {
+                quoted = true;
+                at_field_start = false;
+            }
+            _ if ch == sep => {
+                row.push(std::mem::take(&mut field));
+                at_field_start = true;
+            }
+            '\r' | '\n' => {
+                if ch == '\r' && chars.peek() == Some(&'\n') {
+                    chars.next();
+                }
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+                at_field_start = true;
+            }
+            _ => {
+                field.push(ch);
+                at_field_start = false;
+            }
+        }
+    }
+    if !at_field_start || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+    rows
+}
+
+/// The value in Google's `data-sheets-value`: key `1` holds the type, and
+/// the key it names holds the value.
+fn google_value(json: &str) -> Option<Value> {
+    let v: serde_json::Value = serde_json::from_str(json).ok()?;
+    match v.get("1")?.as_u64()? {
+        2 => Some(Value::Str(v.get("2")?.as_str()?.to_string())),
+        3 => Some(Value::Number(v.get("3")?.as_f64()?)),
+        4 => {
+            let b = v.get("4")?;
+            Some(Value::Bool(b.as_bool().or(b.as_u64().map(|n| n != 0))?))
+        }
+        _ => None,
+    }
+}
+
+/// Apply CSS declarations to `style`. Returns the number format given by
+/// `mso-number-format`, if any.
+fn apply_css(style: &mut RawStyle, decls: &[(String, String)]) -> Option<String> {
+    let mut format = None;
+    for (name, value) in decls {
+        let v = value.to_ascii_lowercase();
+        match name.as_str() {
+            "font-weight" => {
+                style.font.bold =
+                    v == "bold" || v == "bolder" || v.parse::<u32>().is_ok_and(|w| w >= 600);
+            }
+            "font-style" => style.font.italic = v == "italic" || v == "oblique",
+            "text-decoration" | "text-decoration-line" => {
+                style.font.underline = v.contains("underline").then_some(CtUnderlineProperty {
+                    val: StUnderlineValues::Single,
+                });
+                style.font.strike = v.contains("line-through");
+            }
+            "color" => {
+                if let Some(rgb) = css_color(&v) {
+                    style.font.color = Some(rgb_color(rgb));
+                }
+            }
+            "background" | "background-color" => {
+                if let Some(rgb) = v.split_whitespace().find_map(css_color) {
+                    style.fill = CtFill::PatternFill(CtPatternFill {
+                        fg_color: Some(rgb_color(rgb)),
+                        bg_color: None,
+                        pattern_type: Some(StPatternType::Solid),
+                    });
+                }
+            }
+            "font-size" => {
+                if let Some(pt) = css_points(&v) {
+                    style.font.sz = Some(CtFontSize { val: pt });
+                }
+            }
+            "font-family" => {
+                let family = value.split(',').next().unwrap_or("");
+                let family = family.trim().trim_matches(|c| c == '"' || c == '\'');
+                if !family.is_empty() {
+                    style.font.name = Some(CtFontName {
+                        val: family.to_string(),
+                    });
+                }
+            }
+            "text-align" => {
+                let horizontal = match v.as_str() {
+                    "left" => StHorizontalAlignment::Left,
+                    "center" => StHorizontalAlignment::Center,
+                    "right" => StHorizontalAlignment::Right,
+                    "justify" => StHorizontalAlignment::Justify,
+                    _ => continue,
+                };
+                style
+                    .alignment
+                    .get_or_insert_with(CtCellAlignment::default)
+                    .horizontal = Some(horizontal);
+            }
+            "vertical-align" => {
+                let vertical = match v.as_str() {
+                    "top" => StVerticalAlignment::Top,
+                    "middle" => StVerticalAlignment::Center,
+                    "bottom" => StVerticalAlignment::Bottom,
+                    _ => continue,
+                };
+                style
+                    .alignment
+                    .get_or_insert_with(CtCellAlignment::default)
+                    .vertical = Some(vertical);
+            }
+            "mso-number-format" => format = excel_number_format(value),
+            _ => {}
+        }
+    }
+    format
+}
+
+/// The number format in an `mso-number-format` value: a CSS string, or one
+/// of Excel's names for the built-in formats.
+fn excel_number_format(value: &str) -> Option<String> {
+    let format = css_unescape(value.trim().trim_matches(|c| c == '"' || c == '\''));
+    let format = match format.as_str() {
+        "" | "General" => return None,
+        "Percent" => "0.00%",
+        "Fixed" => "0.00",
+        "Standard" => "#,##0.00",
+        "Scientific" => "0.00E+00",
+        "Short Date" => "m/d/yyyy",
+        "Medium Date" => "d-mmm-yy",
+        "Short Time" => "h:mm",
+        "Medium Time" => "h:mm AM/PM",
+        "Long Time" => "h:mm:ss AM/PM",
+        f => f,
+    };
+    Some(format.to_string())
+}
+
+/// Resolve CSS escapes: `\22 ` is a code point in hex, `\.` the character
+/// itself.
+fn css_unescape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars().peekable();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            out.push(ch);
+            continue;
+        }
+        let mut hex = String::new();
+        while hex.len() < 6 && chars.peek().is_some_and(char::is_ascii_hexdigit) {
+            hex.push(chars.next().unwrap());
+        }
+        if hex.is_empty() {
+            if let Some(next) = chars.next() {
+                out.push(next);
+            }
+            continue;
+        }
+        if chars.peek() == Some(&' ') {
+            chars.next();
+        }
+        if let Some(c) = u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32) {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// A CSS colour as `RRGGBB`. `None` for transparent and the system colours.
+fn css_color(v: &str) -> Option<u32> {
+    let v = v.trim();
+    if let Some(hex) = v.strip_prefix('#') {
+        return match hex.len() {
+            3 => {
+                let n = u32::from_str_radix(hex, 16).ok()?;
+                let (r, g, b) = ((n >> 8) & 0xf, (n >> 4) & 0xf, n & 0xf);
+                Some(((r * 17) << 16) | ((g * 17) << 8) | (b * 17))
+            }
+            6 => u32::from_str_radix(hex, 16).ok(),
+            _ => None,
+        };
+    }
+    if let Some(args) = v
+        .strip_prefix("rgb(")
+        .or_else(|| v.strip_prefix("rgba("))
+        .and_then(|s| s.strip_suffix(')'))
+    {
+        let parts: Vec<&str> = args.split(',').map(str::trim).collect();
+        if parts.len() == 4 && parts[3].parse::<f64>().ok()? == 0. {
+            return None;
+        }
+        let channel = |s: &str| s.parse::<f64>().ok().map(|n| n.clamp(0., 255.) as u32);
+        return Some(
+            (channel(parts.first()?)? << 16)
+                | (channel(parts.get(1)?)? << 8)
+                | channel(parts.get(2)?)?,
+        );
+    }
+    let rgb = match v {
+        "black" => 0x000000,
+        "white" => 0xffffff,
+        "red" => 0xff0000,
+        "lime" => 0x00ff00,
+        "green" => 0x008000,
+        "blue" => 0x0000ff,
+        "yellow" => 0xffff00,
+        "cyan" | "aqua" => 0x00ffff,
+        "magenta" | "fuchsia" => 0xff00ff,
+        "gray" | "grey" => 0x808080,
+        "silver" => 0xc0c0c0,
+        "maroon" => 0x800000,
+        "olive" => 0x808000,
+        "navy" => 0x000080,
+        "purple" => 0x800080,
+        "teal" => 0x008080,
+        "orange" => 0xffa500,
+        _ => return None,
+    };
+    Some(rgb)
+}
+
+fn rgb_color(rgb: u32) -> CtColor {
+    CtColor {
+        auto: None,
+        indexed: None,
+        rgb: Some(format!("FF{:06X}", rgb)),
+        theme: None,
+        tint: 0.,
+    }
+}
+
+/// A CSS length in points. Pixels are 3/4 of a point.
+fn css_points(v: &str) -> Option<f64> {
+    if let Some(pt) = v.strip_suffix("pt") {
+        pt.trim().parse().ok()
+    } else if let Some(px) = v.strip_suffix("px") {
+        px.trim().parse::<f64>().ok().map(|px| px * 0.75)
+    } else {
+        None
+    }
+}
+
+/// Split `a:b;c:"d;e"` into its declarations, leaving quoted `;` and `:`
+/// alone. Names are lowercased; values are trimmed but keep their quotes.
+fn parse_declarations(s: &str) -> Vec<(String, String)> {
+    let mut decls = Vec::new();
+    for decl in split_unquoted(s, ';') {
+        let mut halves = split_unquoted(decl, ':').into_iter();
+        let (Some(name), Some(_)) = (halves.next(), halves.next()) else {
+            continue;
+        };
+        let value = decl[name.len() + 1..].trim();
+        let value = value
+            .strip_suffix("!important")
+            .map_or(value, str::trim_end);
+        decls.push((name.trim().to_ascii_lowercase(), value.to_string()));
+    }
+    decls
+}
+
+/// Split `s` on `sep` outside quotes and outside backslash escapes.
+fn split_unquoted(s: &str, sep: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let mut quote = None;
+    let mut escaped = false;
+    for (i, ch) in s.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if ch == '\\' {
+            escaped = true;
+        } else if let Some(q) = quote {
+            if ch == q {
+                quote = None;
+            }
+        } else if ch == '"' || ch == '\'' {
+            quote = Some(ch);
+        } else if ch == sep {
+            parts.push(&s[start..i]);
+            start = i + ch.len_utf8();
+        }
+    }
+    parts.push(&s[start..]);
+    parts
+}
+
+/// The cells of the first table in an HTML fragment.
+struct HtmlTable {
+    cells: Vec<HtmlCell>,
+    col_widths: Vec<Option<f64>>,
+    /// Declarations for every `td`, and per class, from `<style>`.
+    td_css: Vec<(String, String)>,
+    class_css: HashMap<String, Vec<(String, String)>>,
+}
+
+struct HtmlCell {
+    row: usize,
+    col: usize,
+    rowspan: usize,
+    colspan: usize,
+    attrs: Vec<(String, String)>,
+    text: String,
+}
+
+/// Stands in for `<br>` while whitespace is collapsed.
+const LINE_BREAK: char = '\u{2028}';
+
+impl HtmlTable {
+    /// Fails if the table covers more than `MAX_HTML_CELLS` cells.
+    fn parse(html: &str) -> Result<HtmlTable, Error> {
+        let mut table = HtmlTable {
+            cells: vec![],
+            col_widths: vec![],
+            td_css: vec![],
+            class_css: HashMap::new(),
+        };
+        let mut depth = 0;
+        let mut row: Option<usize> = None;
+        let (mut row_count, mut col_count) = (0, 0);
+        let mut occupied = HashSet::new();
+        let mut current: Option<HtmlCell> = None;
+        for token in html_tokens(html) {
+            let tag = match token {
+                HtmlToken::Style(css) => {
+                    table.add_style_sheet(&css);
+                    continue;
+                }
+                HtmlToken::Text(text) => {
+                    if let Some(cell) = current.as_mut() {
+                        cell.text.push_str(&text);
+                    }
+                    continue;
+                }
+                HtmlToken::Tag(tag) => tag,
+            };
+            if tag.name == "table" {
+                if tag.closing {
+                    depth -= 1;
+                    if depth == 0 {
+                        break;
+                    }
+                } else {
+                    depth += 1;
+                }
+                continue;
+            }
+            if depth != 1 {
+                continue;
+            }
+            match (tag.name.as_str(), tag.closing) {
+                ("tr", false) => {
+                    table.finish(&mut current);
+                    row = Some(row.map_or(0, |r| r + 1));
+                }
+                ("td" | "th", false) => {
+                    table.finish(&mut current);
+                    let r = *row.get_or_insert(0);
+                    let mut c = 0;
+                    while occupied.contains(&(r, c)) {
+                        c += 1;
+                    }
+                    let span = |name: &str, max: u32| {
+                        tag.attr(name)
+                            .and_then(|s| s.trim().parse::<usize>().ok())
+                            .unwrap_or(1)
+                            .clamp(1, max as usize)
+                    };
+                    let rowspan = span("rowspan", MAX_ROW_CNT);
+                    let colspan = span("colspan", MAX_COL_CNT);
+                    row_count = usize::max(row_count, r + rowspan);
+                    col_count = usize::max(col_count, c + colspan);
+                    if row_count.saturating_mul(col_count) > MAX_HTML_CELLS {
+                        return Err(Error::PayloadError(format!(
+                            "clipboard HTML table is larger than {} cells",
+                            MAX_HTML_CELLS
+                        )));
+                    }
+                    for dr in 0..rowspan {
+                        for dc in 0..colspan {
+                            occupied.insert((r + dr, c + dc));
+                        }
+                    }
+                    current = Some(HtmlCell {
+                        row: r,
+                        col: c,
+                        rowspan,
+                        colspan,
+                        attrs: tag.attrs,
+                        text: String::new(),
+                    });
+                }
+                ("td" | "th" | "tr", true) => table.finish(&mut current),
+                ("br", false) => {
+                    if let Some(cell) = current.as_mut() {
+                        cell.text.push(LINE_BREAK);
+                    }
+                }
+                ("col", false) => {
+                    let span = tag
+                        .attr("span")
+                        .and_then(|s| s.trim().parse::<usize>().ok())
+                        .unwrap_or(1)
+                        .clamp(1, MAX_COL_CNT as usize);
+                    let px = tag
+                        .attr("width")
+                        .and_then(|w| w.trim().trim_end_matches("px").parse::<f64>().ok())
+                        .or_else(|| {
+                            let style = parse_declarations(tag.attr("style")?);
+                            let (_, w) = style.iter().rev().find(|(k, _)| k == "width")?;
+                            css_points(w).map(|pt| pt / 0.75)
+                        });
+                    let width = px
+                        .filter(|px| *px != DEFAULT_COL_PX)
+                        .map(|px| (((px - 5.) / 7.).max(0.) * 100.).round() / 100.);
+                    table.col_widths.extend(std::iter::repeat_n(width, span));
+                }
+                _ => {}
+            }
+        }
+        table.finish(&mut current);
+        Ok(table)
+    }
+
+    fn finish(&mut self, current: &mut Option<HtmlCell>) {
+        let Some(mut cell) = current.take() else {
+            return;
+        };
+        let mut text = String::with_capacity(cell.text.len());
+        for word in cell.text.split_ascii_whitespace() {
+            if !text.is_empty() {
+                text.push(' ');
+            }
+            text.push_str(word);
+        }
+        cell.text = text
+            .split(LINE_BREAK)
+            .map(|line| line.trim_matches(' '))
+            .collect::<Vec<_>>()
+            .join("\n")
+            .replace('\u{a0}', " ");
+        self.cells.push(cell);
+    }
+
+    /// Collect the rules for `td` and for classes from a style sheet.
+    fn add_style_sheet(&mut self, css: &str) {
+        let mut css = css.replace("<!--", "").replace("-->", "");
+        while let Some(start) = css.find("/*") {
+            let end = css[start..].find("*/").map_or(css.len(), |i| start + i + 2);
+            css.replace_range(start..end, "");
+        }
+        let mut rest = css.as_str();
+        while let Some(open) = rest.find('{') {
+            let selectors = &rest[..open];
+            let body = &rest[open + 1..];
+            let close = body.find('}').unwrap_or(body.len());
+            let decls = parse_declarations(&body[..close]);
+            rest = body.get(close + 1..).unwrap_or("");
+            for selector in selectors.split(',') {
+                let selector = selector.trim().to_ascii_lowercase();
+                let class = selector
+                    .strip_prefix("td.")
+                    .or_else(|| selector.strip_prefix("th."))
+                    .or_else(|| selector.strip_prefix('.'));
+                match class {
+                    Some(class) => self
+                        .class_css
+                        .entry(class.to_string())
+                        .or_default()
+                        .extend(decls.iter().cloned()),
+                    None if selector == "td" || selector == "th" => {
+                        self.td_css.extend(decls.iter().cloned())
+                    }
+                    None => {}
+                }
+            }
+        }
+    }
+
+    /// The declarations that apply to `cell`, later ones winning.
+    fn declarations(&self, cell: &HtmlCell) -> Vec<(String, String)> {
+        let mut decls = self.td_css.clone();
+        let attr = |name: &str| {
+            cell.attrs
+                .iter()
+                .find(|(k, _)| k == name)
+                .map(|(_, v)| v.as_str())
+        };
+        for class in attr("class").unwrap_or("").split_ascii_whitespace() {
+            if let Some(rules) = self.class_css.get(&class.to_ascii_lowercase()) {
+                decls.extend(rules.iter().cloned());
+            }
+        }
+        if let Some(style) = attr("style") {
+            decls.extend(parse_declarations(style));
+        }
+        decls
+    }
+}
+
+enum HtmlToken {
+    Tag(HtmlTag),
+    Text(String),
+    /// The contents of a `<style>` element.
+    Style(String),
+}
+
+struct HtmlTag {
+    /// Lowercased.
+    name: String,
+    closing: bool,
+    /// Names lowercased, values with entities decoded.
+    attrs: Vec<(String, String)>,
+}
+
+impl HtmlTag {
+    fn attr(&self, name: &str) -> Option<&str> {
+        self.attrs
+            .iter()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// A forgiving tokenizer: enough HTML for what spreadsheets and browsers put
+/// on the clipboard. Comments, doctypes and scripts are dropped.
+fn html_tokens(html: &str) -> Vec<HtmlToken> {
+    let mut tokens = Vec::new();
+    let mut rest = html;
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix("<!--") {
+            rest = after.find("-->").map_or("", |i| &after[i + 3..]);
+            continue;
+        }
+        let is_tag = rest.starts_with('<')
+            && rest[1..]
+                .starts_with(|c: char| c.is_ascii_alphabetic() || matches!(c, '/' | '!' | '?'));
+        if !is_tag {
+            let first = rest.chars().next().map_or(1, char::len_utf8);
+            let end = rest[first..].find('<').map_or(rest.len(), |i| i + first);
+            tokens.push(HtmlToken::Text(decode_entities(&rest[..end])));
+            rest = &rest[end..];
+            continue;
+        }
+        let end = tag_end(rest);
+        let tag = parse_tag(&rest[1..end]);
+        rest = rest.get(end + 1..).unwrap_or("");
+        let Some(tag) = tag else {
+            continue;
+        };
+        if !tag.closing && (tag.name == "style" || tag.name == "script") {
+            let close = find_ignore_case(rest, &format!("</{}", tag.name)).unwrap_or(rest.len());
+            if tag.name == "style" {
+                tokens.push(HtmlToken::Style(rest[..close].to_string()));
+            }
+            rest = &rest[close..];
+            continue;
+        }
+        tokens.push(HtmlToken::Tag(tag));
+    }
+    tokens
+}
+
+/// The index of the `>` that ends the tag at the start of `s`, skipping
+/// quoted attribute values.
+fn tag_end(s: &str) -> usize {
+    let mut quote = None;
+    let mut after_eq = false;
+    for (i, ch) in s.char_indices() {
+        match quote {
+            Some(q) if ch == q => quote = None,
+            Some(_) => {}
+            None if ch == '>' => return i,
+            None if (ch == '"' || ch == '\'') && after_eq => quote = Some(ch),
+            None => {}
+        }
+        if !ch.is_whitespace() {
+            after_eq = ch == '=';
+        }
+    }
+    s.len()
+}
+
+/// Parse the inside of `<...>`. `None` for doctypes and processing
+/// instructions.
+fn parse_tag(inner: &str) -> Option<HtmlTag> {
+    if inner.starts_with(['!', '?']) {
+        return None;
+    }
+    let (closing, inner) = match inner.strip_prefix('/') {
+        Some(rest) => (true, rest),
+        None => (false, inner),
+    };
+    let name_len = inner
+        .find(|c: char| c.is_whitespace() || c == '/')
+        .unwrap_or(inner.len());
+    let name = inner[..name_len].to_ascii_lowercase();
+    let mut attrs = Vec::new();
+    let mut rest = &inner[name_len..];
+    loop {
+        rest = rest.trim_start_matches(|c: char| c.is_whitespace() || c == '/');
+        if rest.is_empty() {
+            break;
+        }
+        let key_len = rest
+            .find(|c: char| c.is_whitespace() || c == '=' || c == '/')
+            .unwrap_or(rest.len());
+        let key = rest[..key_len].to_ascii_lowercase();
+        rest = rest[key_len..].trim_start();
+        let value = match rest.strip_prefix('=') {
+            Some(after) => {
+                let after = after.trim_start();
+                match after.chars().next() {
+                    Some(q @ ('"' | '\'')) => {
+                        let body = &after[1..];
+                        let end = body.find(q).unwrap_or(body.len());
+                        rest = body.get(end + 1..).unwrap_or("");
+                        &body[..end]
+                    }
+                    _ => {
+                        let end = after.find(char::is_whitespace).unwrap_or(after.len());
+                        rest = &after[end..];
+                        &after[..end]
+                    }
+                }
+            }
+            None => "",
+        };
+        attrs.push((key, decode_entities(value)));
+    }
+    Some(HtmlTag {
+        name,
+        closing,
+        attrs,
+    })
+}
+
+fn find_ignore_case(haystack: &str, needle: &str) -> Option<usize> {
+    haystack
+        .to_ascii_lowercase()
+        .find(&needle.to_ascii_lowercase())
+}
+
+/// Decode character references. `&nbsp;` becomes U+00A0 so that it survives
+/// whitespace collapsing.
+fn decode_entities(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let decoded = rest[1..].find(';').filter(|&i| i <= 10).and_then(|i| {
+            let name = &rest[1..i + 1];
+            let ch = match name {
+                "amp" => '&',
+                "lt" => '<',
+                "gt" => '>',
+                "quot" => '"',
+                "apos" => '\'',
+                "nbsp" => '\u{a0}',
+                _ => {
+                    let code =
+                        if let Some(hex) = name.strip_prefix("#x").or(name.strip_prefix("#X")) {
+                            u32::from_str_radix(hex, 16).ok()?
+                        } else {
+                            name.strip_prefix('#')?.parse().ok()?
+                        };
+                    char::from_u32(code)?
+                }
+            };
+            Some((ch, i + 2))
+        });
+        match decoded {
+            Some((ch, len)) => {
+                out.push(ch);
+                rest = &rest[len..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn push_attr(out: &mut String, name: &str, value: &str) {
+    out.push(' ');
+    out.push_str(name);
+    out.push_str("=\"");
+    out.push_str(&escape_html(value));
+    out.push('"');
+}
+
+/// `formula`, sitting at `(row, col)`, with its references in R1C1.
+fn a1_to_r1c1(formula: &str, row: usize, col: usize) -> String {
+    fn axis(out: &mut String, letter: char, coord: Option<(usize, bool)>, home: usize) {
+        match coord {
+            Some((v, true)) => out.push_str(&format!("{}{}", letter, v + 1)),
+            Some((v, false)) => out.push_str(&format!("{}[{}]", letter, v as i64 - home as i64)),
+            None => {}
+        }
+    }
+    rewrite_references(formula, |_, text| {
+        let (prefix, parts) = split_reference(text)?;
+        let mut out = prefix.to_string();
+        for (i, part) in parts.iter().enumerate() {
+            if i > 0 {
+                out.push(':');
+            }
+            axis(&mut out, 'R', part.row, row);
+            axis(&mut out, 'C', part.col, col);
+        }
+        Some(out)
+    })
+}
+
+/// `formula`, sitting at `(row, col)`, with its R1C1 references in A1. A
+/// reference that falls off the grid becomes `#REF!`.
+fn r1c1_to_a1(formula: &str, row: usize, col: usize) -> String {
+    let mut out = String::with_capacity(formula.len());
+    let mut prev = None;
+    let mut i = 0;
+    while i < formula.len() {
+        let rest = &formula[i..];
+        let ch = rest.chars().next().unwrap_or_default();
+        if ch == '"' || ch == '\'' {
+            let end = quoted_end(rest, ch);
+            out.push_str(&rest[..end]);
+            prev = Some(ch);
+            i += end;
+            continue;
+        }
+        let boundary =
+            !prev.is_some_and(|p: char| p.is_alphanumeric() || matches!(p, '_' | '.' | '$'));
+        if boundary
+            && matches!(ch, 'R' | 'r' | 'C' | 'c')
+            && let Some((part, len)) = r1c1_reference(rest, row, col)
+        {
+            let next = rest[len..].chars().next();
+            if !next.is_some_and(|n| n.is_alphanumeric() || matches!(n, '_' | '.' | '(' | '[')) {
+                match part {
+                    Some(part) => part.write(&mut out),
+                    None => out.push_str("#REF!"),
+                }
+                prev = rest[..len].chars().last();
+                i += len;
+                continue;
+            }
+        }
+        out.push(ch);
+        prev = Some(ch);
+        i += ch.len_utf8();
+    }
+    out
+}
+
+/// The length of the quoted run at the start of `s`, closing quote
+/// included; doubled quotes stay inside.
+fn quoted_end(s: &str, quote: char) -> usize {
+    let mut chars = s.char_indices().skip(1).peekable();
+    while let Some((i, ch)) = chars.next() {
+        if ch == quote {
+            if chars.peek().is_some_and(|(_, c)| *c == quote) {
+                chars.next();
+            } else {
+                return i + 1;
+            }
+        }
+    }
+    s.len()
+}
+
+/// Read `R2C[-1]`, `RC`, `C[3]` and the like at the start of `s`. Returns
+/// the part, `None` if it is off the grid, and the length read.
+fn r1c1_reference(s: &str, row: usize, col: usize) -> Option<(Option<RefPart>, usize)> {
+    let (r, row_len) =
+        r1c1_axis(s, b'R', row, MAX_ROW_CNT).map_or((None, 0), |(c, l)| (Some(c), l));
+    let (c, col_len) =
+        r1c1_axis(&s[row_len..], b'C', col, MAX_COL_CNT).map_or((None, 0), |(c, l)| (Some(c), l));
+    if row_len + col_len == 0 {
+        return None;
+    }
+    let part = match (r, c) {
+        (Some(None), _) | (_, Some(None)) => None,
+        (r, c) => Some(RefPart {
+            row: r.flatten(),
+            col: c.flatten(),
+        }),
+    };
+    Some((part, row_len + col_len))
+}
+
+/// Read one axis, `R`, `R3` or `R[-1]`, at the start of `s`.
+fn r1c1_axis(s: &str, letter: u8, home: usize, max: u32) -> Option<(Option<(usize, bool)>, usize)> {
+    let bytes = s.as_bytes();
+    if bytes.first()?.to_ascii_uppercase() != letter {
+        return None;
+    }
+    if bytes.get(1) == Some(&b'[') {
+        let close = s.find(']')?;
+        let offset: i64 = s[2..close].parse().ok()?;
+        let v = home as i64 + offset;
+        let coord = (0..max as i64).contains(&v).then_some((v as usize, false));
+        return Some((coord, close + 1));
+    }
+    let digits = bytes[1..].iter().take_while(|b| b.is_ascii_digit()).count();
+    if digits == 0 {
+        return Some((Some((home, false)), 1));
+    }
+    let n: usize = s[1..1 + digits].parse().ok()?;
+    let coord = (1..=max as usize).contains(&n).then(|| (n - 1, true));
+    Some((coord, 1 + digits))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{a1_to_r1c1, r1c1_to_a1, split_tsv};
+    use crate::edit_action::{
+        CellInput, EditAction, EditPayload, MergeCells, PayloadsAction, SetColWidth,
+    };
+    use crate::{PasteOptions, Value, Workbook};
+
+    fn apply(wb: &mut Workbook, payloads: Vec<EditPayload>) {
+        wb.handle_action(EditAction::Payloads(PayloadsAction {
+            payloads,
+            undoable: true,
+            init: false,
+        }));
+    }
+
+    fn input(row: usize, col: usize, content: &str) -> EditPayload {
+        CellInput {
+            sheet_idx: 0,
+            row,
+            col,
+            content: content.to_string(),
+            ..Default::default()
+        }
+        .into()
+    }
+
+    fn num(wb: &Workbook, row: usize, col: usize) -> f64 {
+        let ws = wb.get_sheet_by_idx(0).unwrap();
+        match ws.get_value(row, col).unwrap() {
+            Value::Number(n) => n,
+            v => panic!("expected a number, got {:?}", v),
+        }
+    }
+
+    fn text(wb: &Workbook, row: usize, col: usize) -> String {
+        let ws = wb.get_sheet_by_idx(0).unwrap();
+        match ws.get_value(row, col).unwrap() {
+            Value::Str(s) => s,
+            v => panic!("expected text, got {:?}", v),
+        }
+    }
+
+    #[test]
+    fn tsv_quoting() {
+        let rows = split_tsv("a\t\"b\tc\"\r\n\"say \"\"hi\"\"\"\t\"x\ny\"\n");
+        assert_eq!(
+            rows,
+            vec![
+                vec!["a".to_string(), "b\tc".to_string()],
+                vec!["say \"hi\"".to_string(), "x\ny".to_string()],
+            ]
+        );
+        assert_eq!(split_tsv("1\t\t3"), vec![vec!["1", "", "3"]]);
+    }
+
+    #[test]
+    fn tsv_round_trip() {
+        let mut wb = Workbook::default();
+        apply(
+            &mut wb,
+            vec![
+                input(0, 0, "12.5%"),
+                input(0, 1, "tab\there"),
+                input(1, 0, "TRUE"),
+                input(1, 1, "=A1*2"),
+            ],
+        );
+        let data = wb.copy_range(0, 0, 0, 1, 1).unwrap();
+        let tsv = wb.clipboard_to_tsv(&data);
+        assert_eq!(tsv, "12.50%\t\"tab\there\"\r\nTRUE\t0.25\r\n");
+
+        let parsed = wb.clipboard_from_tsv(&tsv, 0, 4, 0).unwrap();
+        assert_eq!((parsed.row_count, parsed.col_count), (2, 2));
+        assert_eq!(parsed.cells[0].style.formatter, "0.00%");
+        let payloads = wb
+            .paste_payloads(&parsed, 0, 4, 0, &PasteOptions::default())
+            .unwrap();
+        apply(&mut wb, payloads);
+        assert_eq!(num(&wb, 4, 0), 0.125);
+        assert_eq!(text(&wb, 4, 1), "tab\there");
+        assert_eq!(num(&wb, 5, 1), 0.25);
+    }
+
+    #[test]
+    fn tsv_formulas_paste_as_written() {
+        let mut wb = Workbook::default();
+        apply(&mut wb, vec![input(0, 0, "3")]);
+        let parsed = wb.clipboard_from_tsv("=A1+1\r\n", 0, 2, 2).unwrap();
+        let payloads = wb
+            .paste_payloads(&parsed, 0, 2, 2, &PasteOptions::default())
+            .unwrap();
+        apply(&mut wb, payloads);
+        assert_eq!(num(&wb, 2, 2), 4.);
+    }
+
+    #[test]
+    fn r1c1_conversion() {
+        assert_eq!(r1c1_to_a1("R[0]C[-1]*2", 0, 1), "A1*2");
+        assert_eq!(r1c1_to_a1("SUM(R1C1:R[2]C)", 4, 2), "SUM($A$1:C7)");
+        assert_eq!(
+            r1c1_to_a1("ROUND(RC[1],2)&\"RC\"", 0, 0),
+            "ROUND(B1,2)&\"RC\""
+        );
+        assert_eq!(r1c1_to_a1("'R1'!R[-1]C", 0, 0), "'R1'!#REF!");
+        assert_eq!(
+            a1_to_r1c1("SUM($A$1:C7)+Sheet2!B1", 4, 2),
+            "SUM(R1C1:R[2]C[0])+Sheet2!R[-4]C[-1]"
+        );
+        assert_eq!(r1c1_to_a1(&a1_to_r1c1("A$1*$B2", 3, 3), 3, 3), "A$1*$B2");
+    }
+
+    #[test]
+    fn html_round_trip() {
+        let mut wb = Workbook::default();
+        apply(
+            &mut wb,
+            vec![
+                input(0, 0, "1,234.50"),
+                input(0, 1, "<b> & \"q\""),
+                input(1, 0, "=A1*2"),
+                MergeCells {
+                    sheet_idx: 0,
+                    start_row: 1,
+                    start_col: 0,
+                    end_row: 1,
+                    end_col: 1,
+                }
+                .into(),
+                SetColWidth {
+                    sheet_idx: 0,
+                    col: 1,
+                    width: 20.,
+                }
+                .into(),
+            ],
+        );
+        let data = wb.copy_range(0, 0, 0, 1, 1).unwrap();
+        let html = wb.clipboard_to_html(&data);
+        assert!(html.contains("x:num=\"1234.5\""));
+        assert!(html.contains("mso-number-format:&quot;#,##0.00&quot;"));
+        assert!(html.contains("colspan=\"2\""));
+        assert!(html.contains("data-sheets-formula=\"=R[-1]C[0] * 2\""));
+        assert!(html.contains("&lt;b&gt; &amp; &quot;q&quot;"));
+
+        let parsed = wb.clipboard_from_html(&html, 0, 5, 3).unwrap();
+        assert_eq!((parsed.row_count, parsed.col_count), (2, 2));
+        assert_eq!(parsed.cells[0].style.formatter, "#,##0.00");
+        assert_eq!(parsed.cells[2].formula.as_deref(), Some("D6 * 2"));
+        assert_eq!(parsed.merges.len(), 1);
+        assert_eq!(parsed.col_widths, vec![None, Some(20.)]);
+        let payloads = wb
+            .paste_payloads(&parsed, 0, 5, 3, &PasteOptions::default())
+            .unwrap();
+        apply(&mut wb, payloads);
+        assert_eq!(num(&wb, 5, 3), 1234.5);
+        assert_eq!(text(&wb, 5, 4), "<b> & \"q\"");
+        assert_eq!(num(&wb, 6, 3), 2469.);
+        let merged = wb.get_sheet_by_idx(0).unwrap().get_merged_cells(5, 3, 6, 4);
+        assert_eq!(merged.len(), 1);
+    }
+
+    #[test]
+    fn html_from_excel() {
+        let html = r#"<html xmlns:x="urn:schemas-microsoft-com:office:excel">
+<head><style><!--
+td {padding-top:1px; color:windowtext; font-size:11.0pt;}
+.xl65 {mso-number-format:"0\.0%"; font-weight:700;}
+.xl66 {background:yellow; mso-pattern:black none; text-align:center;}
+--></style></head><body>
+<!--StartFragment-->
+<table border=0 cellpadding=0 cellspacing=0 width=128>
+ <col width=64 span=2>
+ <tr height=20>
+  <td height=20 class=xl65 align=right x:num="0.25">25.0%</td>
+  <td class=xl66 rowspan=2>two&nbsp;
+  lines<br>here</td>
+ </tr>
+ <tr><td x:bool="TRUE">TRUE</td></tr>
+</table>
+<!--EndFragment--></body></html>"#;
+        let wb = Workbook::default();
+        let data = wb.clipboard_from_html(html, 0, 0, 0).unwrap();
+        assert_eq!((data.row_count, data.col_count), (2, 2));
+        let first = &data.cells[0];
+        assert!(matches!(first.value, Value::Number(n) if n == 0.25));
+        assert_eq!(first.style.formatter, "0.0%");
+        assert!(first.style.font.bold);
+        assert_eq!(first.style.font.sz.as_ref().unwrap().val, 11.);
+        let second = &data.cells[1];
+        assert!(matches!(&second.value, Value::Str(s) if s == "two  lines\nhere"));
+        assert_eq!(second.style.font.color, data.cells[3].style.font.color);
+        assert!(second.style.alignment.is_some());
+        assert!(matches!(data.cells[2].value, Value::Bool(true)));
+        assert_eq!(data.merges.len(), 1);
+        assert_eq!(data.col_widths, vec![None, None]);
+    }
+
+    #[test]
+    fn html_with_non_ascii_text() {
+        let html = "<table><tr><td>中文</td><td>€5</td><td>x</td></tr></table>";
+        let wb = Workbook::default();
+        let data = wb.clipboard_from_html(html, 0, 0, 0).unwrap();
+        assert_eq!((data.row_count, data.col_count), (1, 3));
+        assert!(matches!(&data.cells[0].value, Value::Str(s) if s == "中文"));
+        assert!(matches!(&data.cells[1].value, Value::Str(s) if s == "€5"));
+    }
+
+    #[test]
+    fn html_table_too_large() {
+        let wb = Workbook::default();
+        let html = "<table><tr><td rowspan=1000000 colspan=1000>x</td></tr></table>";
+        assert!(wb.clipboard_from_html(html, 0, 0, 0).is_err());
+        let html = "<table><tr><td rowspan=100000>x</td><td colspan=2>y</td></tr></table>";
+        assert!(wb.clipboard_from_html(html, 0, 0, 0).is_err());
+    }
+
+    #[test]
+    fn html_from_google_sheets() {
+        let html = concat!(
+            "<google-sheets-html-origin><style type=\"text/css\"><!--td {border: 1px solid #cccccc;}--></style>",
+            "<table dir=\"ltr\"><colgroup><col width=\"100\"/><col width=\"100\"/></colgroup><tbody><tr>",
+            "<td style=\"color:#ff0000;font-style:italic;\" data-sheets-value=\"{&quot;1&quot;:3,&quot;3&quot;:1.5}\" ",
+            "data-sheets-numberformat=\"{&quot;1&quot;:2,&quot;2&quot;:&quot;0.000&quot;,&quot;3&quot;:1}\">1.500</td>",
+            "<td data-sheets-value=\"{&quot;1&quot;:3,&quot;3&quot;:3}\" data-sheets-formula=\"=R[0]C[-1]*2\">3</td>",
+            "</tr><tr><td data-sheets-value=\"{&quot;1&quot;:2,&quot;2&quot;:&quot;007&quot;}\">007</td></tr></tbody></table>",
+        );
+        let mut wb = Workbook::default();
+        let data = wb.clipboard_from_html(html, 0, 0, 4).unwrap();
+        assert_eq!(data.cells[0].style.formatter, "0.000");
+        assert!(data.cells[0].style.font.italic);
+        assert_eq!(
+            data.cells[0]
+                .style
+                .font
+                .color
+                .as_ref()
+                .unwrap()
+                .rgb
+                .as_deref(),
+            Some("FFFF0000")
+        );
+        assert_eq!(data.cells[1].formula.as_deref(), Some("E1*2"));
+        assert_eq!(data.col_widths, vec![Some(13.57), Some(13.57)]);
+        let payloads = wb
+            .paste_payloads(&data, 0, 0, 4, &PasteOptions::default())
+            .unwrap();
+        apply(&mut wb, payloads);
+        assert_eq!(num(&wb, 0, 5), 3.);
+        assert_eq!(text(&wb, 1, 4), "007");
+    }
+}
@@ -5,7 +5,12 @@ pub use crate::{
     errors::{Error, ErrorMessage, Result},
 };
 mod cell_positioner;
+mod clipboard;
+mod compat;
 mod fill;
+mod find;
+mod flash_fill;
+pub(crate) mod interchange;
 mod sort_block;
 mod types;
 mod workbook;
@@ -13,9 +18,17 @@ mod worksheet;
 
 #[cfg(test)]
 mod test;
+pub use clipboard::{ClipboardCell, ClipboardData, PasteContent, PasteOperation, PasteOptions};
+pub use compat::{CompatMismatch, CompatOptions, CompatReport, check_excel_compat};
 pub use fill::FillRange;
+pub use find::{
+    FindFormat, FindLookIn, FindMode, FindOptions, FindOrder, FindRange, FindScope, FoundCell,
+    ReplacePlan, ReplaceResult,
+};
+pub use interchange::ClipboardContents;
 pub use logisheets_base::BlockId;
 pub use sort_block::BlockSortOrder;
 pub use types::*;
 pub use workbook::Workbook;
+pub(crate) use workbook::read_display_value;
 pub use worksheet::Worksheet;
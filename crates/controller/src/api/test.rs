@@ -1,8 +1,10 @@
 use crate::edit_action::{
-    AddComment, AuthorInput, CellInput, CommentMention, CreateBlock, CreateChart,
-    CreateChartSeries, CreateDiyCell, DeleteCellImage, DeleteChart, DeleteComment, EditComment,
-    EditPayload, LineStyleUpdate, ModifyPolicy, MoveChart, PayloadsAction, RemoveDiyCell,
-    ResolveComment, SetCellImage, SheetRename, StyleUpdateType, UpdateChart, WorkbookUpdateType,
+    AddComment, ApplyCellStyle, AuthorInput, CellInput, CellStyleUpdate, CommentMention,
+    CreateBlock, CreateCellStyle, CreateChart, CreateChartSeries, CreateDiyCell, DeleteCellImage,
+    DeleteCellStyle, DeleteChart, DeleteComment, EditComment, EditPayload, LineStyleUpdate,
+    ModifyCellStyle, ModifyPolicy, MoveChart, PayloadsAction, RemoveDiyCell, ResolveComment,
+    SetCellImage, SetThemeColor, SetThemeFonts, SheetRename, StyleUpdateType, ThemeColorSlot,
+    UpdateChart, WorkbookUpdateType,
 };
 
 #[test]
@@ -18,6 +20,7 @@ fn cross_sheet_range_unparses_after_calc() {
             row: 0,
             col: 25,
             content: "=VLOOKUP(O8,Sheet2!A5:B20,2,FALSE)".to_string(),
+            ..Default::default()
         })],
         undoable: true,
         init: false,
@@ -86,6 +89,7 @@ fn create_chart_from_scratch() {
                 row: *r,
                 col: *c,
                 content: v.to_string(),
+                ..Default::default()
             })
         })
         .collect();
@@ -209,6 +213,7 @@ fn chart_reflects_live_data() {
             row: 1,
             col: 1,
             content: "100".to_string(),
+            ..Default::default()
         })],
         undoable: true,
         init: false,
@@ -385,12 +390,14 @@ fn data_validation_flags_invalid_cell() {
                 row: 0,
                 col: 0,
                 content: "Apple".to_string(),
+                ..Default::default()
             }),
             EditPayload::CellInput(CellInput {
                 sheet_idx: 0,
                 row: 1,
                 col: 0,
                 content: "Zebra".to_string(),
+                ..Default::default()
             }),
         ],
         undoable: false,
@@ -830,6 +837,249 @@ fn get_col_style() {
     assert_eq!(style.formatter, "0.00");
 }
 
+#[test]
+fn named_cell_styles_survive_save_load() {
+    // builtin_style.xlsx carries 49 named styles (Chinese Excel names).
+    // Previously `<cellStyles>` was dropped on save and every cell-style xf
+    // was deduplicated away on load.
+    use logisheets_workbook::prelude::Wb;
+    let buf = std::fs::read("../../tests/builtin_style.xlsx").unwrap();
+    let wb = Workbook::from_file(&buf, "builtin_style".to_string()).unwrap();
+    let styles = wb.get_cell_styles();
+    assert_eq!(styles.len(), 49);
+    assert_eq!(styles[0].name, "常规");
+    assert_eq!(styles[0].builtin_id, Some(0));
+
+    let saved = wb.save().unwrap();
+    let doc = Wb::from_file(&saved).unwrap();
+    let ct = doc.xl.styles.1;
+    let cell_styles = ct.cell_styles.unwrap();
+    assert_eq!(cell_styles.cell_styles.len(), 49);
+    assert_eq!(ct.cell_style_xfs.unwrap().xfs.len(), 49);
+
+    let reloaded = Workbook::from_file(&saved, "reloaded".to_string()).unwrap();
+    let names = |wb: &Workbook| {
+        wb.get_cell_styles()
+            .into_iter()
+            .map(|s| s.name)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(names(&reloaded), names(&wb));
+}
+
+#[test]
+fn named_cell_style_edits_follow_linked_cells() {
+    let mut wb = Workbook::default();
+    let exec = |wb: &mut Workbook, payload: EditPayload| {
+        wb.handle_action(EditAction::Payloads(PayloadsAction {
+            payloads: vec![payload],
+            undoable: true,
+            init: false,
+        }))
+    };
+    exec(
+        &mut wb,
+        CreateCellStyle {
+            name: "Emphasis".to_string(),
+            ty: StyleUpdateType {
+                set_font_bold: Some(true),
+                ..Default::default()
+            },
+        }
+        .into(),
+    );
+    exec(
+        &mut wb,
+        ApplyCellStyle {
+            sheet_idx: 0,
+            start_row: 0,
+            start_col: 0,
+            end_row: 0,
+            end_col: 1,
+            name: "emphasis".to_string(),
+        }
+        .into(),
+    );
+    // B1 sets its own number format, which the style must not override.
+    exec(
+        &mut wb,
+        CellStyleUpdate {
+            sheet_idx: 0,
+            row: 0,
+            col: 1,
+            ty: StyleUpdateType {
+                set_num_fmt: Some("0.00".to_string()),
+                ..Default::default()
+            },
+        }
+        .into(),
+    );
+    {
+        let ws = wb.get_sheet_by_idx(0).unwrap();
+        assert_eq!(
+            ws.get_cell_style_name(0, 0).unwrap().as_deref(),
+            Some("Emphasis")
+        );
+        assert_eq!(
+            ws.get_cell_style_name(0, 1).unwrap().as_deref(),
+            Some("Emphasis")
+        );
+        assert_eq!(
+            ws.get_cell_style_name(1, 0).unwrap().as_deref(),
+            Some("Normal")
+        );
+        assert!(ws.get_style(0, 0).unwrap().font.bold);
+    }
+
+    exec(
+        &mut wb,
+        ModifyCellStyle {
+            name: "Emphasis".to_string(),
+            ty: StyleUpdateType {
+                set_font_italic: Some(true),
+                set_num_fmt: Some("0%".to_string()),
+                ..Default::default()
+            },
+        }
+        .into(),
+    );
+    {
+        let ws = wb.get_sheet_by_idx(0).unwrap();
+        let a1 = ws.get_style(0, 0).unwrap();
+        assert!(a1.font.bold && a1.font.italic);
+        assert_eq!(a1.formatter, "0%");
+        let b1 = ws.get_style(0, 1).unwrap();
+        assert!(b1.font.italic);
+        assert_eq!(b1.formatter, "0.00");
+    }
+
+    exec(
+        &mut wb,
+        DeleteCellStyle {
+            name: "Emphasis".to_string(),
+        }
+        .into(),
+    );
+    {
+        let ws = wb.get_sheet_by_idx(0).unwrap();
+        assert_eq!(
+            ws.get_cell_style_name(0, 0).unwrap().as_deref(),
+            Some("Normal")
+        );
+        assert!(ws.get_style(0, 0).unwrap().font.italic);
+    }
+    assert_eq!(wb.get_cell_styles().len(), 1);
+
+    // Normal can't be deleted.
+    let effect = exec(
+        &mut wb,
+        DeleteCellStyle {
+            name: "Normal".to_string(),
+        }
+        .into(),
+    );
+    assert!(matches!(
+        effect.status,
+        crate::edit_action::StatusCode::Err(_)
+    ));
+
+    assert!(wb.undo());
+    let ws = wb.get_sheet_by_idx(0).unwrap();
+    assert_eq!(
+        ws.get_cell_style_name(0, 0).unwrap().as_deref(),
+        Some("Emphasis")
+    );
+}
+
+#[test]
+fn theme_color_edits_re_resolve_undo_and_save() {
+    // Named style 2 of builtin_style.xlsx, "20% - Accent 3", fills with
+    // theme colour accent3 (9BBB59) lightened.
+    use crate::controller::style::Fill;
+    let fill_of = |wb: &Workbook| match &wb.get_cell_styles()[2].style.fill {
+        Fill::PatternFill(pf) => {
+            let c = pf.fg_color.clone().unwrap();
+            (c.red, c.green, c.blue)
+        }
+        Fill::GradientFill(_) => panic!("expected a pattern fill"),
+    };
+    let buf = std::fs::read("../../tests/builtin_style.xlsx").unwrap();
+    let mut wb = Workbook::from_file(&buf, "builtin_style".to_string()).unwrap();
+    let before = fill_of(&wb);
+
+    wb.handle_action(EditAction::Payloads(PayloadsAction {
+        payloads: vec![
+            SetThemeColor {
+                slot: ThemeColorSlot::Accent3,
+                color: "C00000".to_string(),
+            }
+            .into(),
+        ],
+        undoable: true,
+        init: false,
+    }));
+    let after = fill_of(&wb);
+    assert_ne!(after, before);
+    assert_eq!(wb.get_theme().unwrap().colors[6], "C00000");
+
+    let saved = wb.save().unwrap();
+    let reloaded = Workbook::from_file(&saved, "reloaded".to_string()).unwrap();
+    assert_eq!(reloaded.get_theme().unwrap().colors[6], "C00000");
+    assert_eq!(fill_of(&reloaded), after);
+
+    assert!(wb.undo());
+    assert_eq!(fill_of(&wb), before);
+    assert_eq!(wb.get_theme().unwrap().colors[6], "9BBB59");
+}
+
+#[test]
+fn theme_font_edits_rename_scheme_fonts() {
+    // openpyxl-dcf.xlsx's only font, that of its "Normal" style, is
+    // Calibri: the theme's minor font.
+    let buf = std::fs::read("../../tests/openpyxl-dcf.xlsx").unwrap();
+    let mut wb = Workbook::from_file(&buf, "dcf".to_string()).unwrap();
+    let normal_font = |wb: &Workbook| wb.get_cell_styles()[0].style.font.name.clone().unwrap().val;
+    let b1_font = |wb: &Workbook| {
+        let ws = wb.get_sheet_by_idx(0).unwrap();
+        ws.get_style(0, 1).unwrap().font.name.unwrap().val
+    };
+    assert_eq!(normal_font(&wb), "Calibri");
+    // B1 picks Calibri by name, so it leaves the theme's fonts.
+    wb.handle_action(EditAction::Payloads(PayloadsAction {
+        payloads: vec![
+            CellStyleUpdate {
+                sheet_idx: 0,
+                row: 0,
+                col: 1,
+                ty: StyleUpdateType {
+                    set_font_name: Some("Calibri".to_string()),
+                    ..Default::default()
+                },
+            }
+            .into(),
+        ],
+        undoable: true,
+        init: false,
+    }));
+    wb.handle_action(EditAction::Payloads(PayloadsAction {
+        payloads: vec![
+            SetThemeFonts {
+                major: None,
+                minor: Some("Arial".to_string()),
+            }
+            .into(),
+        ],
+        undoable: true,
+        init: false,
+    }));
+    assert_eq!(normal_font(&wb), "Arial");
+    assert_eq!(b1_font(&wb), "Calibri");
+    assert_eq!(wb.get_theme().unwrap().minor_font, "Arial");
+
+    assert!(wb.undo());
+    assert_eq!(normal_font(&wb), "Calibri");
+}
+
 #[test]
 fn overwrite_formula_with_plain_value() {
     use crate::controller::display::Value;
@@ -844,6 +1094,7 @@ fn overwrite_formula_with_plain_value() {
             row: 0,
             col: 0,
             content: "=1+1".to_string(),
+            ..Default::default()
         })],
         undoable: true,
         init: false,
@@ -861,6 +1112,7 @@ fn overwrite_formula_with_plain_value() {
             row: 0,
             col: 0,
             content: "5".to_string(),
+            ..Default::default()
         })],
         undoable: true,
         init: false,
@@ -961,6 +1213,7 @@ fn table_converts_to_block_on_load() {
                 row: r,
                 col: c,
                 content: val.to_string(),
+                ..Default::default()
             }));
         }
     }
@@ -1070,18 +1323,21 @@ fn range_link_redirects_to_block_and_tracks_growth() {
                 field_formulas: vec![],
                 validation_formulas: vec![],
                 editability_formulas: vec![],
+                field_types: None,
             }),
             EditPayload::CellInput(CellInput {
                 sheet_idx: 0,
                 row: 0,
                 col: 3,
                 content: "10".to_string(),
+                ..Default::default()
             }),
             EditPayload::CellInput(CellInput {
                 sheet_idx: 0,
                 row: 1,
                 col: 3,
                 content: "20".to_string(),
+                ..Default::default()
             }),
         ],
         undoable: false,
@@ -1098,6 +1354,7 @@ fn range_link_redirects_to_block_and_tracks_growth() {
             row: 0,
             col: 4,
             content: "=SUM(A1:A2)".to_string(),
+            ..Default::default()
         })],
         undoable: false,
         init: false,
@@ -1120,6 +1377,7 @@ fn range_link_redirects_to_block_and_tracks_growth() {
             row: 0,
             col: 3,
             content: "100".to_string(),
+            ..Default::default()
         })],
         undoable: false,
         init: false,
@@ -1151,6 +1409,7 @@ fn range_link_redirects_to_block_and_tracks_growth() {
             row: 1,
             col: 3,
             content: "5".to_string(),
+            ..Default::default()
         })],
         undoable: false,
         init: false,
@@ -1206,18 +1465,21 @@ fn clearing_field_rule_purges_stale_shadow_value() {
                 field_formulas: vec![],
                 validation_formulas: vec![Some("#PLACEHOLDER>100".into())],
                 editability_formulas: vec![Some("#PLACEHOLDER>100".into())],
+                field_types: None,
             }),
             EditPayload::CellInput(CellInput {
                 sheet_idx: 0,
                 row: 0,
                 col: 3,
                 content: "10".to_string(),
+                ..Default::default()
             }),
             EditPayload::CellInput(CellInput {
                 sheet_idx: 0,
                 row: 1,
                 col: 3,
                 content: "20".to_string(),
+                ..Default::default()
             }),
         ],
         undoable: false,
@@ -1299,18 +1561,21 @@ fn create_link_payload_redirects_existing_formula() {
                 row: 0,
                 col: 0,
                 content: "1".to_string(),
+                ..Default::default()
             }),
             EditPayload::CellInput(CellInput {
                 sheet_idx: 0,
                 row: 1,
                 col: 0,
                 content: "2".to_string(),
+                ..Default::default()
             }),
             EditPayload::CellInput(CellInput {
                 sheet_idx: 0,
                 row: 0,
                 col: 4,
                 content: "=SUM(A1:A2)".to_string(),
+                ..Default::default()
             }),
             EditPayload::CreateBlock(CreateBlock {
                 sheet_idx: 0,
@@ -1334,18 +1599,21 @@ fn create_link_payload_redirects_existing_formula() {
                 field_formulas: vec![],
                 validation_formulas: vec![],
                 editability_formulas: vec![],
+                field_types: None,
             }),
             EditPayload::CellInput(CellInput {
                 sheet_idx: 0,
                 row: 0,
                 col: 3,
                 content: "10".to_string(),
+                ..Default::default()
             }),
             EditPayload::CellInput(CellInput {
                 sheet_idx: 0,
                 row: 1,
                 col: 3,
                 content: "20".to_string(),
+                ..Default::default()
             }),
         ],
         undoable: false,
@@ -1399,6 +1667,7 @@ fn create_link_payload_redirects_existing_formula() {
                 row: 1,
                 col: 3,
                 content: "5".to_string(),
+                ..Default::default()
             }),
         ],
         undoable: false,
@@ -1446,6 +1715,7 @@ fn linked_range_size_mismatch_reads_block_both_orders() {
                 row: i,
                 col: 3,
                 content: v.to_string(),
+                ..Default::default()
             }));
         }
         for (i, v) in [1, 2, 3, 4].iter().enumerate() {
@@ -1454,6 +1724,7 @@ fn linked_range_size_mismatch_reads_block_both_orders() {
                 row: i,
                 col: 0,
                 content: v.to_string(),
+                ..Default::default()
             }));
         }
         let formula = EditPayload::CellInput(CellInput {
@@ -1461,6 +1732,7 @@ fn linked_range_size_mismatch_reads_block_both_orders() {
             row: 0,
             col: 6,
             content: "=SUM(A1:A4)".to_string(),
+            ..Default::default()
         });
         let link = EditPayload::CreateLink(CreateLink {
             sheet_idx: 0,
@@ -1527,6 +1799,7 @@ fn linked_multicol_subcolumn_reference_reads_block_column() {
             row: i,
             col: 3,
             content: v.to_string(),
+            ..Default::default()
         }));
     }
     for (i, v) in [1, 3, 4, 5, 6, 7].iter().enumerate() {
@@ -1535,6 +1808,7 @@ fn linked_multicol_subcolumn_reference_reads_block_column() {
             row: i,
             col: 4,
             content: v.to_string(),
+            ..Default::default()
         }));
     }
     // Literal 1,2,3,4 in A1:B4 (source), then link A1:B4 (2 cols) -> block.
@@ -1544,12 +1818,14 @@ fn linked_multicol_subcolumn_reference_reads_block_column() {
             row: i,
             col: 0,
             content: (v * 100).to_string(),
+            ..Default::default()
         }));
         payloads.push(EditPayload::CellInput(CellInput {
             sheet_idx: 0,
             row: i,
             col: 1,
             content: v.to_string(),
+            ..Default::default()
         }));
     }
     // =SUM(B1:B4): only the 2nd column of the linked A1:B4 range.
@@ -1558,6 +1834,7 @@ fn linked_multicol_subcolumn_reference_reads_block_column() {
         row: 0,
         col: 6,
         content: "=SUM(B1:B4)".to_string(),
+        ..Default::default()
     }));
     payloads.push(EditPayload::CreateLink(CreateLink {
         sheet_idx: 0,
@@ -1631,24 +1908,28 @@ fn cross_sheet_linked_column_tracks_block_and_survives_save_load() {
                 field_formulas: vec![],
                 validation_formulas: vec![],
                 editability_formulas: vec![],
+                field_types: None,
             }),
             EditPayload::CellInput(CellInput {
                 sheet_idx: 1,
                 row: 0,
                 col: 3,
                 content: "10".into(),
+                ..Default::default()
             }),
             EditPayload::CellInput(CellInput {
                 sheet_idx: 1,
                 row: 1,
                 col: 3,
                 content: "20".into(),
+                ..Default::default()
             }),
             EditPayload::CellInput(CellInput {
                 sheet_idx: 0,
                 row: 0,
                 col: 6,
                 content: "=SUM(A1:A2)".into(),
+                ..Default::default()
             }),
             EditPayload::CreateLink(CreateLink {
                 sheet_idx: 0,
@@ -1689,6 +1970,7 @@ fn cross_sheet_linked_column_tracks_block_and_survives_save_load() {
                 row: 2,
                 col: 3,
                 content: "7".into(),
+                ..Default::default()
             }),
         ],
         undoable: false,
@@ -1706,6 +1988,7 @@ fn cross_sheet_linked_column_tracks_block_and_survives_save_load() {
             row: 2,
             col: 3,
             content: "100".into(),
+            ..Default::default()
         })],
         undoable: false,
         init: false,
@@ -1766,24 +2049,28 @@ fn link_survives_save_load() {
                 field_formulas: vec![],
                 validation_formulas: vec![],
                 editability_formulas: vec![],
+                field_types: None,
             }),
             EditPayload::CellInput(CellInput {
                 sheet_idx: 0,
                 row: 0,
                 col: 3,
                 content: "10".into(),
+                ..Default::default()
             }),
             EditPayload::CellInput(CellInput {
                 sheet_idx: 0,
                 row: 1,
                 col: 3,
                 content: "20".into(),
+                ..Default::default()
             }),
             EditPayload::CellInput(CellInput {
                 sheet_idx: 0,
                 row: 0,
                 col: 5,
                 content: "=SUM(A1:A2)".into(),
+                ..Default::default()
             }),
             EditPayload::CreateLink(CreateLink {
                 sheet_idx: 0,
@@ -1832,6 +2119,7 @@ fn link_survives_save_load() {
                 row: 2,
                 col: 3,
                 content: "7".into(),
+                ..Default::default()
             }),
         ],
         undoable: false,
@@ -1879,24 +2167,28 @@ fn linked_column_tracks_tail_append() {
                 field_formulas: vec![],
                 validation_formulas: vec![],
                 editability_formulas: vec![],
+                field_types: None,
             }),
             EditPayload::CellInput(CellInput {
                 sheet_idx: 0,
                 row: 0,
                 col: 3,
                 content: "10".into(),
+                ..Default::default()
             }),
             EditPayload::CellInput(CellInput {
                 sheet_idx: 0,
                 row: 1,
                 col: 3,
                 content: "20".into(),
+                ..Default::default()
             }),
             EditPayload::CellInput(CellInput {
                 sheet_idx: 0,
                 row: 0,
                 col: 5,
                 content: "=SUM(A1:A2)".into(),
+                ..Default::default()
             }),
             EditPayload::CreateLink(CreateLink {
                 sheet_idx: 0,
@@ -1932,6 +2224,7 @@ fn linked_column_tracks_tail_append() {
                 row: 2,
                 col: 3,
                 content: "7".into(),
+                ..Default::default()
             }),
         ],
         undoable: false,
@@ -1950,6 +2243,7 @@ fn linked_column_tracks_tail_append() {
             row: 2,
             col: 3,
             content: "100".into(),
+            ..Default::default()
         })],
         undoable: false,
         init: false,
@@ -1992,6 +2286,7 @@ fn linked_record_rejects_non_column_references() {
                 row: i,
                 col: 4,
                 content: v.to_string(),
+                ..Default::default()
             }));
         }
         for (i, v) in [1, 2, 3, 4].iter().enumerate() {
@@ -2000,12 +2295,14 @@ fn linked_record_rejects_non_column_references() {
                 row: i,
                 col: 0,
                 content: (v * 10).to_string(),
+                ..Default::default()
             }));
             payloads.push(EditPayload::CellInput(CellInput {
                 sheet_idx: 0,
                 row: i,
                 col: 1,
                 content: v.to_string(),
+                ..Default::default()
             }));
         }
         let f = EditPayload::CellInput(CellInput {
@@ -2013,6 +2310,7 @@ fn linked_record_rejects_non_column_references() {
             row: 0,
             col: 6,
             content: formula.to_string(),
+            ..Default::default()
         });
         let link = EditPayload::CreateLink(CreateLink {
             sheet_idx: 0,
@@ -2089,12 +2387,14 @@ fn get_links_reports_linked_source_range_coords() {
                 row: 0,
                 col: 0,
                 content: "1".to_string(),
+                ..Default::default()
             }),
             EditPayload::CellInput(CellInput {
                 sheet_idx: 0,
                 row: 1,
                 col: 0,
                 content: "2".to_string(),
+                ..Default::default()
             }),
             EditPayload::CreateBlock(CreateBlock {
                 sheet_idx: 0,
@@ -2133,12 +2433,44 @@ fn get_links_reports_linked_source_range_coords() {
 
 #[test]
 fn test_check_formula() {
+    use crate::FormulaDiagnosticKind;
+
     let wb = Workbook::new();
     let r = wb.check_formula("=1+1".to_string());
-    assert!(r);
+    assert!(r.valid);
 
     let r = wb.check_formula("=SUM(1)+".to_string());
-    assert!(!r);
+    assert!(!r.valid);
+
+    // Diagnostic positions count from the start of the text as given.
+    let r = wb.check_formula(" =ROUND(1)".to_string());
+    assert!(!r.valid);
+    assert_eq!(r.diagnostics.len(), 1);
+    assert_eq!((r.diagnostics[0].start, r.diagnostics[0].end), (2, 10));
+
+    let r = wb.check_formula("=SUM(1".to_string());
+    assert_eq!(r.diagnostics[0].kind, FormulaDiagnosticKind::Syntax);
+    assert_eq!((r.diagnostics[0].start, r.diagnostics[0].end), (4, 5));
+
+    let r = wb.check_formula("  SUM(1)".to_string());
+    assert!(!r.valid);
+    assert_eq!(r.diagnostics[0].kind, FormulaDiagnosticKind::Syntax);
+    assert_eq!(r.diagnostics[0].start, 2);
+    assert_eq!(r.diagnostics[0].expected, vec!["=".to_string()]);
+
+    assert!(
+        wb.check_formula("=sheet1!A1+_xlfn.STDEV.S(1, 2)".to_string())
+            .valid
+    );
+    let r = wb.check_formula("=NOSUCHSHEET!A1+NOSUCHFUNC(1)".to_string());
+    let kinds: Vec<_> = r.diagnostics.iter().map(|d| d.kind).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            FormulaDiagnosticKind::UnknownSheet,
+            FormulaDiagnosticKind::UnknownFunction
+        ]
+    );
 }
 
 #[test]
@@ -2388,6 +2720,7 @@ fn dependency_tracking_precedents_and_dependents() {
             row,
             col,
             content: content.to_string(),
+            ..Default::default()
         })
     };
     wb.handle_action(EditAction::Payloads(PayloadsAction {
@@ -2498,6 +2831,7 @@ fn sort_block_by_field_end_to_end() {
                 field_formulas: vec![],
                 validation_formulas: vec![],
                 editability_formulas: vec![],
+                field_types: None,
             }),
         ];
         for (r, (name, age)) in records.iter().enumerate() {
@@ -2506,12 +2840,14 @@ fn sort_block_by_field_end_to_end() {
                 row: r,
                 col: 0,
                 content: name.to_string(),
+                ..Default::default()
             }));
             payloads.push(EditPayload::CellInput(CellInput {
                 sheet_idx: 0,
                 row: r,
                 col: 1,
                 content: age.to_string(),
+                ..Default::default()
             }));
         }
         // Seed as the init baseline so an undo of a later (undoable) sort
@@ -2708,6 +3044,7 @@ fn sort_block_grown_by_insert_rows() {
                 field_formulas: vec![],
                 validation_formulas: vec![],
                 editability_formulas: vec![],
+                field_types: None,
             }),
             // Add two rows (interior insert at index 1), like clicking "add row".
             EditPayload::InsertRowsInBlock(InsertRowsInBlock {
@@ -2722,18 +3059,21 @@ fn sort_block_grown_by_insert_rows() {
                 row: 0,
                 col: 0,
                 content: "Charlie".into(),
+                ..Default::default()
             }),
             EditPayload::CellInput(CellInput {
                 sheet_idx: 0,
                 row: 1,
                 col: 0,
                 content: "Alice".into(),
+                ..Default::default()
             }),
             EditPayload::CellInput(CellInput {
                 sheet_idx: 0,
                 row: 2,
                 col: 0,
                 content: "Bob".into(),
+                ..Default::default()
             }),
         ],
         undoable: false,
@@ -2796,6 +3136,7 @@ fn sort_block_reference_follows_moved_cell() {
             row,
             col,
             content: content.to_string(),
+            ..Default::default()
         })
     };
 
@@ -2825,6 +3166,7 @@ fn sort_block_reference_follows_moved_cell() {
                 field_formulas: vec![],
                 validation_formulas: vec![],
                 editability_formulas: vec![],
+                field_types: None,
             }),
             cell(0, 0, "Charlie"),
             cell(0, 1, "30"),
@@ -2902,6 +3244,7 @@ fn formulatext_returns_source_formula_and_na() {
             row,
             col,
             content: content.to_string(),
+            ..Default::default()
         })
     };
     let r = wb.handle_action(EditAction::Payloads(PayloadsAction {
@@ -3345,12 +3688,14 @@ fn conditional_formatting_rule_evaluates_on_load() {
                 row: 0,
                 col: 0,
                 content: "150".to_string(),
+                ..Default::default()
             }),
             EditPayload::CellInput(CellInput {
                 sheet_idx: 0,
                 row: 1,
                 col: 0,
                 content: "50".to_string(),
+                ..Default::default()
             }),
         ],
         undoable: false,
@@ -3424,6 +3769,7 @@ fn conditional_formatting_resyncs_after_edits() {
                 row,
                 col: 0,
                 content: content.to_string(),
+                ..Default::default()
             })],
             undoable: true,
             init: false,
@@ -3524,12 +3870,14 @@ fn conditional_format_reaches_cell_info_merged() {
                 row: 0,
                 col: 0,
                 content: "150".to_string(),
+                ..Default::default()
             }),
             EditPayload::CellInput(CellInput {
                 sheet_idx: 0,
                 row: 1,
                 col: 0,
                 content: "50".to_string(),
+                ..Default::default()
             }),
             EditPayload::CellStyleUpdate(crate::edit_action::CellStyleUpdate {
                 sheet_idx: 0,
@@ -3663,6 +4011,7 @@ fn conditional_formatting_follows_undo_and_redo() {
             row: 0,
             col: 0,
             content: "500".to_string(),
+            ..Default::default()
         })],
         undoable: true,
         init: false,
@@ -3751,12 +4100,14 @@ fn create_conditional_formatting_rule_end_to_end() {
                 row: 0,
                 col: 0,
                 content: "50".to_string(),
+                ..Default::default()
             }),
             EditPayload::CellInput(CellInput {
                 sheet_idx: 0,
                 row: 1,
                 col: 0,
                 content: "500".to_string(),
+                ..Default::default()
             }),
         ],
         undoable: false,
@@ -4081,6 +4432,7 @@ fn empty_app_data_round_trips() {
             row: 0,
             col: 0,
             content: "=1+1".to_string(),
+            ..Default::default()
         })],
         undoable: true,
         init: false,
@@ -4169,24 +4521,28 @@ fn block_schema_key_entries_report_record_row() {
                 field_formulas: vec![],
                 validation_formulas: vec![],
                 editability_formulas: vec![],
+                field_types: None,
             }),
             EditPayload::CellInput(CellInput {
                 sheet_idx: 0,
                 row: 0,
                 col: 0,
                 content: "2024".to_string(),
+                ..Default::default()
             }),
             EditPayload::CellInput(CellInput {
                 sheet_idx: 0,
                 row: 1,
                 col: 0,
                 content: "2025".to_string(),
+                ..Default::default()
             }),
             EditPayload::CellInput(CellInput {
                 sheet_idx: 0,
                 row: 2,
                 col: 0,
                 content: "2026".to_string(),
+                ..Default::default()
             }),
         ],
         undoable: false,
@@ -4237,6 +4593,7 @@ fn block_schema_key_entries_report_record_row() {
                 row: 1,
                 col: 0,
                 content: "2024H2".to_string(),
+                ..Default::default()
             }),
         ],
         undoable: false,
@@ -4292,12 +4649,14 @@ fn range_straddling_a_block_boundary_does_not_panic() {
                 field_formulas: vec![],
                 validation_formulas: vec![],
                 editability_formulas: vec![],
+                field_types: None,
             }),
             EditPayload::CellInput(CellInput {
                 sheet_idx: 0,
                 row: 0,
                 col: 1,
                 content: "4".to_string(),
+                ..Default::default()
             }),
         ],
         undoable: false,
@@ -4311,6 +4670,7 @@ fn range_straddling_a_block_boundary_does_not_panic() {
             row: 0,
             col: 4,
             content: "=SUM(B1:B10)*2".to_string(),
+            ..Default::default()
         })],
         undoable: false,
         init: false,
@@ -4323,6 +4683,7 @@ fn range_straddling_a_block_boundary_does_not_panic() {
             row: 5,
             col: 0,
             content: "=1+1".to_string(),
+            ..Default::default()
         })],
         undoable: false,
         init: false,
@@ -4340,3 +4701,1573 @@ fn range_straddling_a_block_boundary_does_not_panic() {
         .expect("reloading must not panic on the rejected reference");
     assert_eq!(reloaded.get_sheet_count(), 1);
 }
+
+#[test]
+fn cell_input_follows_the_input_locale() {
+    let mut wb = Workbook::default();
+    wb.set_input_locale(crate::InputLocale {
+        date_order: crate::DateOrder::Dmy,
+        date_separator: '.',
+        decimal_separator: ',',
+        thousands_separator: '.',
+        currency_symbol: "€".to_string(),
+    });
+    let input = |row, content: &str, skip_recognition| {
+        EditPayload::CellInput(CellInput {
+            sheet_idx: 0,
+            row,
+            col: 0,
+            content: content.to_string(),
+            skip_recognition,
+        })
+    };
+    wb.handle_action(EditAction::Payloads(PayloadsAction {
+        payloads: vec![
+            input(0, "1.3.2024", None),
+            input(1, "12,50 €", None),
+            input(2, "1.3.2024", Some(true)),
+        ],
+        undoable: true,
+        init: false,
+    }));
+    let ws = wb.get_sheet_by_idx(0).unwrap();
+    assert!(matches!(ws.get_value(0, 0).unwrap(), crate::Value::Number(n) if n == 45352.));
+    assert_eq!(ws.get_style(0, 0).unwrap().formatter, "d\\.m\\.yyyy");
+    assert!(matches!(ws.get_value(1, 0).unwrap(), crate::Value::Number(n) if n == 12.5));
+    assert_eq!(ws.get_style(1, 0).unwrap().formatter, "#,##0.00 \"€\"");
+    assert!(matches!(ws.get_value(2, 0).unwrap(), crate::Value::Str(s) if s == "1.3.2024"));
+    assert_eq!(ws.get_style(2, 0).unwrap().formatter, "General");
+}
+
+#[test]
+fn input_locale_is_saved_with_the_workbook() {
+    let mut wb = Workbook::default();
+    let mut info = crate::InputLocaleInfo::from(wb.get_input_locale());
+    info.date_order = crate::DateOrder::Dmy;
+    info.decimal_separator = ",".to_string();
+    assert!(crate::InputLocale::try_from(info.clone()).is_err());
+    info.thousands_separator = " ".to_string();
+    wb.set_input_locale(crate::InputLocale::try_from(info).unwrap());
+
+    let bytes = wb.save().unwrap();
+    let reloaded = Workbook::from_file(&bytes, "saved".to_string()).unwrap();
+    assert_eq!(reloaded.get_input_locale(), wb.get_input_locale());
+
+    // The default locale is left out of the file.
+    let bytes = Workbook::default().save().unwrap();
+    let reloaded = Workbook::from_file(&bytes, "saved".to_string()).unwrap();
+    assert_eq!(reloaded.get_input_locale(), &crate::InputLocale::default());
+}
+
+#[test]
+fn cell_info_carries_display_text() {
+    let mut wb = Workbook::default();
+    let input = |row, col, content: &str| {
+        EditPayload::CellInput(CellInput {
+            sheet_idx: 0,
+            row,
+            col,
+            content: content.to_string(),
+            ..Default::default()
+        })
+    };
+    let num_fmt = |row, col, fmt: &str| {
+        EditPayload::CellStyleUpdate(crate::edit_action::CellStyleUpdate {
+            sheet_idx: 0,
+            row,
+            col,
+            ty: StyleUpdateType {
+                set_num_fmt: Some(fmt.to_string()),
+                ..Default::default()
+            },
+        })
+    };
+    wb.handle_action(EditAction::Payloads(PayloadsAction {
+        payloads: vec![
+            input(0, 0, "1234567"),
+            num_fmt(0, 0, "#,##0.00"),
+            input(1, 0, "-5"),
+            num_fmt(1, 0, "0.0;[Red]-0.0"),
+            input(2, 0, "3.14159265"),
+            input(3, 0, "a long piece of text"),
+            input(4, 0, "1234567"),
+            num_fmt(4, 0, "#,##0.00"),
+            EditPayload::MergeCells(crate::edit_action::MergeCells {
+                sheet_idx: 0,
+                start_row: 4,
+                start_col: 0,
+                end_row: 4,
+                end_col: 1,
+            }),
+        ],
+        undoable: true,
+        init: false,
+    }));
+    let ws = wb.get_sheet_by_idx(0).unwrap();
+    let info = |row| ws.get_cell_info(row, 0).unwrap();
+
+    assert_eq!(info(0).display_text, "########");
+    assert_eq!(info(1).display_text, "-5.0");
+    let red = info(1).display_color.unwrap();
+    assert_eq!(
+        (red.red, red.green, red.blue),
+        (Some(255.), Some(0.), Some(0.))
+    );
+    assert_eq!(info(2).display_text, "3.141593");
+    assert!(info(2).display_color.is_none());
+    assert_eq!(info(3).display_text, "a long piece of text");
+    assert_eq!(info(4).display_text, "1,234,567.00");
+
+    let window = ws.get_display_window(0, 0, 1, 0).unwrap();
+    assert_eq!(window.cells[0].display_text, "########");
+}
+
+fn sheet_names(wb: &Workbook) -> Vec<String> {
+    wb.get_all_sheet_info()
+        .into_iter()
+        .map(|s| s.name)
+        .collect()
+}
+
+#[test]
+fn move_sheet_reorders_tabs_and_re_evaluates_3d_refs() {
+    use crate::controller::display::Value;
+    use crate::edit_action::{CreateSheet, MoveSheet};
+
+    let mut wb = Workbook::default();
+    let input = |sheet_idx, content: &str| {
+        EditPayload::CellInput(CellInput {
+            sheet_idx,
+            row: 0,
+            col: 0,
+            content: content.to_string(),
+            ..Default::default()
+        })
+    };
+    let create = |idx, name: &str| {
+        EditPayload::CreateSheet(CreateSheet {
+            idx,
+            new_name: name.to_string(),
+        })
+    };
+    wb.handle_action(EditAction::Payloads(PayloadsAction {
+        payloads: vec![
+            create(1, "B"),
+            create(2, "C"),
+            create(3, "D"),
+            input(0, "1"),
+            input(1, "10"),
+            input(2, "100"),
+            input(3, "=SUM(Sheet1:B!A1)"),
+        ],
+        undoable: true,
+        init: false,
+    }));
+    let sum = |wb: &Workbook| match wb.get_sheet_by_idx(3).unwrap().get_value(0, 0).unwrap() {
+        Value::Number(n) => n,
+        v => panic!("unexpected {:?}", v),
+    };
+    assert_eq!(sum(&wb), 11.0);
+
+    // Sheet1, C, B, D: C now sits inside the span.
+    let result = wb.handle_action(EditAction::Payloads(PayloadsAction {
+        payloads: vec![EditPayload::MoveSheet(MoveSheet { from: 2, to: 1 })],
+        undoable: true,
+        init: false,
+    }));
+    assert!(result.version > 0);
+    assert_eq!(sheet_names(&wb), vec!["Sheet1", "C", "B", "D"]);
+    assert_eq!(sum(&wb), 111.0);
+
+    assert!(wb.undo());
+    assert_eq!(sheet_names(&wb), vec!["Sheet1", "B", "C", "D"]);
+    assert_eq!(sum(&wb), 11.0);
+
+    // B, Sheet1, C, D: the end sheet now comes before the start sheet.
+    wb.handle_action(EditAction::Payloads(PayloadsAction {
+        payloads: vec![EditPayload::MoveSheet(MoveSheet { from: 1, to: 0 })],
+        undoable: true,
+        init: false,
+    }));
+    assert_eq!(sheet_names(&wb), vec!["B", "Sheet1", "C", "D"]);
+    assert_eq!(sum(&wb), 11.0);
+
+    let result = wb.handle_action(EditAction::Payloads(PayloadsAction {
+        payloads: vec![EditPayload::MoveSheet(MoveSheet { from: 0, to: 4 })],
+        undoable: true,
+        init: false,
+    }));
+    assert_eq!(result.version, 0);
+}
+
+#[test]
+fn duplicate_sheet_copies_content_and_repoints_formulas() {
+    use crate::controller::display::Value;
+    use crate::edit_action::{CreateSheet, DuplicateSheet, MergeCells};
+
+    let mut wb = Workbook::default();
+    let input = |row, col, content: &str| {
+        EditPayload::CellInput(CellInput {
+            sheet_idx: 0,
+            row,
+            col,
+            content: content.to_string(),
+            ..Default::default()
+        })
+    };
+    wb.handle_action(EditAction::Payloads(PayloadsAction {
+        payloads: vec![
+            EditPayload::CreateSheet(CreateSheet {
+                idx: 1,
+                new_name: "Other".to_string(),
+            }),
+            input(0, 0, "2"),
+            input(1, 0, "=A1 * 3"),
+            input(2, 0, "=Other!A1 + 1"),
+            input(3, 0, "=Sheet1!A1 + SUM(Sheet1!A1:A2)"),
+            EditPayload::CellStyleUpdate(CellStyleUpdate {
+                sheet_idx: 0,
+                row: 0,
+                col: 0,
+                ty: StyleUpdateType {
+                    set_num_fmt: Some("0.00".to_string()),
+                    ..Default::default()
+                },
+            }),
+            EditPayload::MergeCells(MergeCells {
+                sheet_idx: 0,
+                start_row: 4,
+                start_col: 0,
+                end_row: 4,
+                end_col: 2,
+            }),
+            EditPayload::AddComment(AddComment {
+                sheet_idx: 0,
+                row: 0,
+                col: 0,
+                comment_id: "{root}".to_string(),
+                parent_id: None,
+                author: enterprise_author("Alice", "alice@corp.com"),
+                dt: "2026-07-03T10:00:00Z".to_string(),
+                content: "source".to_string(),
+                mentions: vec![],
+            }),
+        ],
+        undoable: true,
+        init: false,
+    }));
+
+    let result = wb.handle_action(EditAction::Payloads(PayloadsAction {
+        payloads: vec![EditPayload::DuplicateSheet(DuplicateSheet {
+            idx: 0,
+            new_idx: 1,
+            new_name: "Copy".to_string(),
+        })],
+        undoable: true,
+        init: false,
+    }));
+    assert!(result.version > 0);
+    assert_eq!(sheet_names(&wb), vec!["Sheet1", "Copy", "Other"]);
+
+    // Editing the copy leaves the source alone: its formula reads the copy.
+    wb.handle_action(EditAction::Payloads(PayloadsAction {
+        payloads: vec![EditPayload::CellInput(CellInput {
+            sheet_idx: 1,
+            row: 0,
+            col: 0,
+            content: "5".to_string(),
+            ..Default::default()
+        })],
+        undoable: true,
+        init: false,
+    }));
+    let num = |idx, row| match wb.get_sheet_by_idx(idx).unwrap().get_value(row, 0).unwrap() {
+        Value::Number(n) => n,
+        v => panic!("unexpected {:?}", v),
+    };
+    assert_eq!(num(0, 1), 6.0);
+    assert_eq!(num(1, 1), 15.0);
+    assert_eq!(num(1, 2), 1.0);
+    // References the source makes to itself by name follow it to the copy.
+    assert_eq!(num(0, 3), 10.0);
+    assert_eq!(num(1, 3), 25.0);
+
+    let copy = wb.get_sheet_by_idx(1).unwrap();
+    assert_eq!(copy.get_formula(2, 0).unwrap(), "Other!A1 + 1");
+    assert_eq!(copy.get_style(0, 0).unwrap().formatter, "0.00");
+    assert_eq!(copy.get_merged_cells(0, 0, 10, 10).len(), 1);
+    let comments = copy.get_comments();
+    assert_eq!(comments.len(), 1);
+    assert_eq!(comments[0].notes[0].content, "source");
+    assert_ne!(comments[0].notes[0].id, "{root}");
+
+    assert!(wb.undo());
+    assert!(wb.undo());
+    assert_eq!(sheet_names(&wb), vec!["Sheet1", "Other"]);
+}
+
+#[test]
+fn insert_and_delete_cells_shift_content_and_references() {
+    use crate::controller::display::Value;
+    use crate::edit_action::{DeleteCells, InsertCells};
+
+    let mut wb = Workbook::default();
+    let input = |row, col, content: &str| {
+        EditPayload::CellInput(CellInput {
+            sheet_idx: 0,
+            row,
+            col,
+            content: content.to_string(),
+            ..Default::default()
+        })
+    };
+    wb.handle_action(EditAction::Payloads(PayloadsAction {
+        payloads: vec![
+            input(0, 1, "1"),
+            input(1, 1, "2"),
+            input(2, 1, "3"),
+            input(0, 2, "9"),
+            input(0, 4, "=B3 * 10"),
+            input(1, 4, "=SUM(B1:B3)"),
+            input(2, 4, "=SUM(A1:C3)"),
+        ],
+        undoable: true,
+        init: false,
+    }));
+    let value =
+        |wb: &Workbook, row, col| wb.get_sheet_by_idx(0).unwrap().get_value(row, col).unwrap();
+    let formula = |wb: &Workbook, row, col| {
+        wb.get_sheet_by_idx(0)
+            .unwrap()
+            .get_formula(row, col)
+            .unwrap()
+    };
+
+    // B2:B3 shift down by two; B1 and column C stay.
+    let result = wb.handle_action(EditAction::Payloads(PayloadsAction {
+        payloads: vec![EditPayload::InsertCells(InsertCells {
+            sheet_idx: 0,
+            start_row: 1,
+            start_col: 1,
+            end_row: 2,
+            end_col: 1,
+            shift_right: false,
+        })],
+        undoable: true,
+        init: false,
+    }));
+    assert!(result.version > 0);
+    assert!(matches!(value(&wb, 1, 1), Value::Empty));
+    assert!(matches!(value(&wb, 3, 1), Value::Number(n) if n == 2.0));
+    assert!(matches!(value(&wb, 4, 1), Value::Number(n) if n == 3.0));
+    assert!(matches!(value(&wb, 0, 2), Value::Number(n) if n == 9.0));
+    assert_eq!(formula(&wb, 0, 4), "B5 * 10");
+    // The insertion falls inside B1:B3, which grows to keep its cells.
+    assert_eq!(formula(&wb, 1, 4), "SUM(B1:B5)");
+    assert!(matches!(value(&wb, 1, 4), Value::Number(n) if n == 6.0));
+    // ... and picks up what is entered in the cells it now covers.
+    wb.handle_action(EditAction::Payloads(PayloadsAction {
+        payloads: vec![input(2, 1, "4")],
+        undoable: true,
+        init: false,
+    }));
+    assert!(matches!(value(&wb, 1, 4), Value::Number(n) if n == 10.0));
+    assert!(wb.undo());
+    // A1:C3 only partly lies in the shifted column: it keeps its extent and
+    // loses the cells pushed out of it.
+    assert_eq!(formula(&wb, 2, 4), "SUM(A1:C3)");
+    assert!(matches!(value(&wb, 2, 4), Value::Number(n) if n == 10.0));
+
+    assert!(wb.undo());
+    assert!(matches!(value(&wb, 2, 1), Value::Number(n) if n == 3.0));
+    assert_eq!(formula(&wb, 0, 4), "B3 * 10");
+    assert!(matches!(value(&wb, 2, 4), Value::Number(n) if n == 15.0));
+
+    // Deleting B3 pulls nothing up but leaves B3 * 10 without its cell.
+    wb.handle_action(EditAction::Payloads(PayloadsAction {
+        payloads: vec![EditPayload::DeleteCells(DeleteCells {
+            sheet_idx: 0,
+            start_row: 2,
+            start_col: 1,
+            end_row: 2,
+            end_col: 1,
+            shift_left: false,
+        })],
+        undoable: true,
+        init: false,
+    }));
+    assert!(matches!(value(&wb, 2, 1), Value::Empty));
+    assert!(matches!(value(&wb, 0, 4), Value::Error(e) if e == "#REF!"));
+    assert_eq!(formula(&wb, 1, 4), "SUM(B1:B2)");
+    assert!(matches!(value(&wb, 1, 4), Value::Number(n) if n == 3.0));
+}
+
+#[test]
+fn shifting_cells_moves_merges_and_refuses_to_split_them() {
+    use crate::edit_action::{DeleteCells, InsertCells, MergeCells};
+
+    let mut wb = Workbook::default();
+    wb.handle_action(EditAction::Payloads(PayloadsAction {
+        payloads: vec![EditPayload::MergeCells(MergeCells {
+            sheet_idx: 0,
+            start_row: 2,
+            start_col: 1,
+            end_row: 3,
+            end_col: 2,
+        })],
+        undoable: true,
+        init: false,
+    }));
+
+    // B2:C2 shift down, over both of the merge's columns.
+    let result = wb.handle_action(EditAction::Payloads(PayloadsAction {
+        payloads: vec![EditPayload::InsertCells(InsertCells {
+            sheet_idx: 0,
+            start_row: 1,
+            start_col: 1,
+            end_row: 1,
+            end_col: 2,
+            shift_right: false,
+        })],
+        undoable: true,
+        init: false,
+    }));
+    assert!(result.version > 0);
+    let merges = wb
+        .get_sheet_by_idx(0)
+        .unwrap()
+        .get_merged_cells(0, 0, 10, 10);
+    assert_eq!(merges.len(), 1);
+    assert_eq!((merges[0].start_row, merges[0].end_row), (3, 4));
+
+    // Only column B: the merge would be torn apart.
+    let result = wb.handle_action(EditAction::Payloads(PayloadsAction {
+        payloads: vec![EditPayload::DeleteCells(DeleteCells {
+            sheet_idx: 0,
+            start_row: 0,
+            start_col: 1,
+            end_row: 0,
+            end_col: 1,
+            shift_left: false,
+        })],
+        undoable: true,
+        init: false,
+    }));
+    assert_eq!(result.version, 0);
+    let merges = wb
+        .get_sheet_by_idx(0)
+        .unwrap()
+        .get_merged_cells(0, 0, 10, 10);
+    assert_eq!((merges[0].start_row, merges[0].end_row), (3, 4));
+}
+
+#[test]
+fn moving_a_range_carries_content_and_rewrites_references() {
+    use crate::controller::display::Value;
+    use crate::edit_action::{CreateSheet, MergeCells, MoveRange};
+
+    let mut wb = Workbook::default();
+    let input = |row, col, content: &str| {
+        EditPayload::CellInput(CellInput {
+            sheet_idx: 0,
+            row,
+            col,
+            content: content.to_string(),
+            ..Default::default()
+        })
+    };
+    wb.handle_action(EditAction::Payloads(PayloadsAction {
+        payloads: vec![
+            EditPayload::CreateSheet(CreateSheet {
+                idx: 1,
+                new_name: "Data".into(),
+            }),
+            input(0, 0, "1"),
+            input(1, 0, "2"),
+            input(0, 1, "=A1 + A2"),
+            input(0, 4, "7"),
+            input(1, 4, "5"),
+            input(0, 2, "=SUM(A1:A2)"),
+            input(1, 2, "=E1 * 2"),
+            input(2, 2, "=SUM(A2:A3)"),
+        ],
+        undoable: true,
+        init: false,
+    }));
+    let value = |wb: &Workbook, sheet, row, col| {
+        wb.get_sheet_by_idx(sheet)
+            .unwrap()
+            .get_value(row, col)
+            .unwrap()
+    };
+    let formula = |wb: &Workbook, sheet, row, col| {
+        wb.get_sheet_by_idx(sheet)
+            .unwrap()
+            .get_formula(row, col)
+            .unwrap()
+    };
+    let move_range = |dest_sheet_idx, dest_row, dest_col| {
+        EditAction::Payloads(PayloadsAction {
+            payloads: vec![EditPayload::MoveRange(MoveRange {
+                sheet_idx: 0,
+                start_row: 0,
+                start_col: 0,
+                end_row: 1,
+                end_col: 1,
+                dest_sheet_idx,
+                dest_row,
+                dest_col,
+            })],
+            undoable: true,
+            init: false,
+        })
+    };
+
+    // A1:B2 onto E1:F2, overwriting E1 and E2.
+    let result = wb.handle_action(move_range(0, 0, 4));
+    assert!(result.version > 0);
+    assert!(matches!(value(&wb, 0, 0, 0), Value::Empty));
+    assert!(matches!(value(&wb, 0, 0, 4), Value::Number(n) if n == 1.0));
+    assert!(matches!(value(&wb, 0, 1, 4), Value::Number(n) if n == 2.0));
+    assert_eq!(formula(&wb, 0, 0, 5), "E1 + E2");
+    assert!(matches!(value(&wb, 0, 0, 5), Value::Number(n) if n == 3.0));
+    assert_eq!(formula(&wb, 0, 0, 2), "SUM(E1:E2)");
+    assert!(matches!(value(&wb, 0, 0, 2), Value::Number(n) if n == 3.0));
+    assert_eq!(formula(&wb, 0, 1, 2), "#REF! * 2");
+    assert!(matches!(value(&wb, 0, 1, 2), Value::Error(e) if e == "#REF!"));
+    // A2:A3 only overlaps the move: it stays and loses the moved value.
+    assert_eq!(formula(&wb, 0, 2, 2), "SUM(A2:A3)");
+    assert!(matches!(value(&wb, 0, 2, 2), Value::Number(n) if n == 0.0));
+
+    assert!(wb.undo());
+    assert!(matches!(value(&wb, 0, 0, 0), Value::Number(n) if n == 1.0));
+    assert_eq!(formula(&wb, 0, 1, 2), "E1 * 2");
+    assert!(matches!(value(&wb, 0, 1, 2), Value::Number(n) if n == 14.0));
+
+    // Onto B2 of another sheet.
+    let result = wb.handle_action(move_range(1, 1, 1));
+    assert!(result.version > 0);
+    assert!(matches!(value(&wb, 1, 1, 1), Value::Number(n) if n == 1.0));
+    assert_eq!(formula(&wb, 1, 1, 2), "B2 + B3");
+    assert!(matches!(value(&wb, 1, 1, 2), Value::Number(n) if n == 3.0));
+    assert_eq!(formula(&wb, 0, 0, 2), "SUM(Data!B2:B3)");
+    assert!(matches!(value(&wb, 0, 0, 2), Value::Number(n) if n == 3.0));
+    assert!(wb.undo());
+
+    // A merge across the edge of the moved cells would be split.
+    wb.handle_action(EditAction::Payloads(PayloadsAction {
+        payloads: vec![EditPayload::MergeCells(MergeCells {
+            sheet_idx: 0,
+            start_row: 1,
+            start_col: 1,
+            end_row: 2,
+            end_col: 1,
+        })],
+        undoable: true,
+        init: false,
+    }));
+    let result = wb.handle_action(move_range(0, 0, 4));
+    assert_eq!(result.version, 0);
+    assert!(matches!(value(&wb, 0, 0, 0), Value::Number(n) if n == 1.0));
+}
+
+#[test]
+fn typed_block_fields_refuse_bad_input_and_export_typed_values() {
+    use crate::controller::display::Value;
+    use crate::edit_action::{BindFormSchema, BlockInput, FieldKind, FieldType};
+
+    let mut wb = Workbook::default();
+    let ty = |kind| FieldType {
+        kind,
+        required: false,
+        min: None,
+        max: None,
+        options: vec![],
+        pattern: None,
+        max_length: None,
+        ref_name: None,
+    };
+    let block = |id, master_col, fields: Vec<&str>, field_types| {
+        vec![
+            EditPayload::CreateBlock(CreateBlock {
+                sheet_idx: 0,
+                id,
+                master_row: 0,
+                master_col,
+                row_cnt: 2,
+                col_cnt: fields.len() + 1,
+                owner: None,
+                modify_policy: None,
+            }),
+            EditPayload::BindFormSchema(BindFormSchema {
+                ref_name: if id == 1 { "materials" } else { "orders" }.into(),
+                sheet_idx: 0,
+                block_id: id,
+                field_from: 1,
+                key_idx: 0,
+                render_ids: fields.iter().map(|f| f.to_string()).collect(),
+                fields: fields.iter().map(|f| f.to_string()).collect(),
+                row: true,
+                field_formulas: vec![],
+                validation_formulas: vec![],
+                editability_formulas: vec![],
+                field_types: Some(field_types),
+            }),
+        ]
+    };
+    let input = |block_id, row, col, input: &str| {
+        EditAction::Payloads(PayloadsAction {
+            payloads: vec![EditPayload::BlockInput(BlockInput {
+                sheet_idx: 0,
+                block_id,
+                row,
+                col,
+                input: input.to_string(),
+            })],
+            undoable: true,
+            init: false,
+        })
+    };
+
+    let mut payloads = block(
+        1,
+        0,
+        vec!["kind"],
+        vec![Some(FieldType {
+            options: vec!["steel".into(), "wood".into()],
+            ..ty(FieldKind::Enum)
+        })],
+    );
+    payloads.extend(block(
+        2,
+        3,
+        vec!["qty", "due", "paid", "material", "code"],
+        vec![
+            Some(FieldType {
+                required: true,
+                min: Some(1.0),
+                ..ty(FieldKind::Integer)
+            }),
+            Some(ty(FieldKind::Date)),
+            Some(ty(FieldKind::Boolean)),
+            Some(FieldType {
+                ref_name: Some("materials".into()),
+                ..ty(FieldKind::Reference)
+            }),
+            Some(FieldType {
+                pattern: Some("[0-9]+".into()),
+                ..ty(FieldKind::Text)
+            }),
+        ],
+    ));
+    wb.handle_action(EditAction::Payloads(PayloadsAction {
+        payloads,
+        undoable: true,
+        init: false,
+    }));
+    for action in [
+        input(1, 0, 0, "m1"),
+        input(1, 0, 1, "steel"),
+        input(2, 0, 0, "o1"),
+        input(2, 0, 1, "3"),
+        input(2, 0, 2, "2024-03-01"),
+        input(2, 0, 3, "true"),
+        input(2, 0, 4, "m1"),
+        input(2, 0, 5, "007"),
+    ] {
+        assert!(wb.handle_action(action).version > 0);
+    }
+    for action in [
+        input(1, 0, 1, "glass"),
+        input(2, 0, 1, "0"),
+        input(2, 0, 1, "2.5"),
+        input(2, 0, 1, ""),
+        input(2, 0, 2, "soon"),
+        input(2, 0, 3, "yes"),
+        input(2, 0, 4, "m2"),
+        input(2, 0, 5, "7a"),
+    ] {
+        assert_eq!(wb.handle_action(action).version, 0);
+    }
+
+    let rows = wb.export_block_data("orders", None, None).unwrap();
+    let row = &rows[0].cells;
+    assert!(matches!(row[0], Value::Number(n) if n == 3.0));
+    assert!(matches!(&row[1], Value::Str(s) if s == "2024-03-01"));
+    assert!(matches!(row[2], Value::Bool(true)));
+    assert!(matches!(&row[3], Value::Str(s) if s == "m1"));
+    assert!(matches!(&row[4], Value::Str(s) if s == "007"));
+
+    // The types are saved with the schema.
+    let bytes = wb.save().unwrap();
+    let mut wb = Workbook::from_file(&bytes, "reloaded".to_string()).unwrap();
+    assert_eq!(wb.handle_action(input(2, 1, 1, "0")).version, 0);
+    assert_eq!(wb.handle_action(input(1, 1, 1, "glass")).version, 0);
+    assert!(wb.handle_action(input(1, 1, 1, "wood")).version > 0);
+}
+
+#[test]
+fn importing_records_fills_grows_and_upserts_a_block() {
+    use crate::controller::display::Value;
+    use crate::edit_action::{
+        BindFormSchema, FieldKind, FieldType, ImportBlockRecords, RecordFormat,
+    };
+
+    let mut wb = Workbook::default();
+    wb.handle_action(EditAction::Payloads(PayloadsAction {
+        payloads: vec![
+            EditPayload::CreateBlock(CreateBlock {
+                sheet_idx: 0,
+                id: 1,
+                master_row: 0,
+                master_col: 0,
+                row_cnt: 2,
+                col_cnt: 3,
+                owner: None,
+                modify_policy: None,
+            }),
+            EditPayload::BindFormSchema(BindFormSchema {
+                ref_name: "items".into(),
+                sheet_idx: 0,
+                block_id: 1,
+                field_from: 0,
+                key_idx: 0,
+                fields: vec!["id".into(), "name".into(), "qty".into()],
+                render_ids: vec!["id".into(), "name".into(), "qty".into()],
+                row: true,
+                field_formulas: vec![],
+                validation_formulas: vec![],
+                editability_formulas: vec![],
+                field_types: Some(vec![
+                    None,
+                    None,
+                    Some(FieldType {
+                        kind: FieldKind::Integer,
+                        required: true,
+                        min: None,
+                        max: None,
+                        options: vec![],
+                        pattern: None,
+                        max_length: None,
+                        ref_name: None,
+                    }),
+                ]),
+            }),
+        ],
+        undoable: true,
+        init: false,
+    }));
+    let import = |format, data: &str, upsert| {
+        EditAction::Payloads(PayloadsAction {
+            payloads: vec![EditPayload::ImportBlockRecords(ImportBlockRecords {
+                sheet_idx: 0,
+                block_id: 1,
+                format,
+                data: data.to_string(),
+                key_field: None,
+                upsert,
+            })],
+            undoable: true,
+            init: false,
+        })
+    };
+    let rows = |wb: &Workbook| {
+        wb.export_block_data("items", None, None)
+            .unwrap()
+            .into_iter()
+            .map(|r| {
+                r.cells
+                    .into_iter()
+                    .map(|v| match v {
+                        Value::Str(s) => s,
+                        Value::Number(n) => n.to_string(),
+                        _ => String::new(),
+                    })
+                    .collect::<Vec<_>>()
+                    .join(",")
+            })
+            .collect::<Vec<_>>()
+    };
+
+    // Three records into two empty lines: the block grows by one.
+    let version = wb.handle_action(import(
+        RecordFormat::Json,
+        r#"[{"id": "a", "name": "bolt", "qty": 10},
+            {"id": "b", "name": "nut", "qty": 20},
+            {"id": "c", "name": "pin", "qty": 30}]"#,
+        false,
+    ));
+    assert!(version.version > 0);
+    assert_eq!(rows(&wb), vec!["a,bolt,10", "b,nut,20", "c,pin,30"]);
+
+    // Upsert updates the known key and appends the new one.
+    let effect = wb.handle_action(import(RecordFormat::Csv, "id,qty\nb,25\nd,40\n", true));
+    assert_eq!(effect.version, version.version + 1);
+    assert_eq!(
+        rows(&wb),
+        vec!["a,bolt,10", "b,nut,25", "c,pin,30", "d,,40"]
+    );
+
+    // Blank and null fields of an update leave the line's values alone.
+    let effect = wb.handle_action(import(
+        RecordFormat::Json,
+        r#"[{"id": "a", "name": null, "qty": ""}, {"id": "c", "name": "peg"}]"#,
+        true,
+    ));
+    assert_eq!(effect.version, version.version + 2);
+    assert_eq!(
+        rows(&wb),
+        vec!["a,bolt,10", "b,nut,25", "c,peg,30", "d,,40"]
+    );
+    assert!(wb.undo());
+
+    // A record that does not fit refuses the whole import.
+    for (format, data) in [
+        (RecordFormat::Csv, "id,qty\ne,50\nf,many\n"),
+        (RecordFormat::Csv, "id,name\ne,washer\n"),
+        (
+            RecordFormat::Json,
+            r#"[{"id": "e", "colour": "red", "qty": 1}]"#,
+        ),
+    ] {
+        assert_eq!(wb.handle_action(import(format, data, true)).version, 0);
+    }
+    assert_eq!(rows(&wb).len(), 4);
+
+    // One import is one undo step.
+    assert!(wb.undo());
+    assert_eq!(rows(&wb), vec!["a,bolt,10", "b,nut,20", "c,pin,30"]);
+}
+
+#[test]
+fn persisted_checkpoints_survive_save_and_load() {
+    use crate::checkpoint_manager::CheckpointMeta;
+    use crate::controller::display::Value;
+    use crate::edit_action::{CreateSheet, RestoreCheckpoint, SheetRename};
+
+    let mut wb = Workbook::default();
+    let input = |sheet_idx, row, col, content: &str| {
+        EditPayload::CellInput(CellInput {
+            sheet_idx,
+            row,
+            col,
+            content: content.to_string(),
+            ..Default::default()
+        })
+    };
+    wb.handle_action(EditAction::Payloads(PayloadsAction {
+        payloads: vec![
+            input(0, 0, 0, "1"),
+            input(0, 1, 0, "'007"),
+            input(0, 2, 0, "=A1*10"),
+            input(0, 3, 0, "TRUE"),
+            input(0, 5, 0, "#N/A"),
+            EditPayload::CellStyleUpdate(CellStyleUpdate {
+                sheet_idx: 0,
+                row: 0,
+                col: 0,
+                ty: StyleUpdateType {
+                    set_num_fmt: Some("0.00".to_string()),
+                    ..Default::default()
+                },
+            }),
+        ],
+        undoable: true,
+        init: false,
+    }));
+    wb.save_checkpoint_with_meta(CheckpointMeta {
+        label: "draft".into(),
+        description: Some("before the rework".into()),
+        created_at: Some("2026-01-02T03:04:05Z".into()),
+        author: Some("Ana".into()),
+        persist: true,
+    });
+    wb.save_checkpoint("scratch".into(), None);
+
+    wb.handle_action(EditAction::Payloads(PayloadsAction {
+        payloads: vec![
+            input(0, 0, 0, "5"),
+            input(0, 1, 0, "x"),
+            input(0, 4, 0, "new"),
+            input(0, 5, 0, "0"),
+            EditPayload::CreateSheet(CreateSheet {
+                idx: 1,
+                new_name: "Extra".into(),
+            }),
+            EditPayload::SheetRename(SheetRename {
+                old_name: None,
+                idx: Some(0),
+                new_name: "Main".into(),
+            }),
+        ],
+        undoable: true,
+        init: false,
+    }));
+
+    let bytes = wb.save().unwrap();
+    let wb = Workbook::from_file(&bytes, "reloaded".to_string()).unwrap();
+    let listed = wb.list_checkpoints();
+    assert_eq!(listed.len(), 1, "only persisted checkpoints are saved");
+    assert_eq!(listed[0].label, "draft");
+    assert_eq!(listed[0].description.as_deref(), Some("before the rework"));
+    assert_eq!(
+        listed[0].created_at.as_deref(),
+        Some("2026-01-02T03:04:05Z")
+    );
+    assert_eq!(listed[0].author.as_deref(), Some("Ana"));
+    assert!(listed[0].persist);
+
+    // Saving again rewrites the checkpoint against the new file without
+    // having to rebuild it.
+    let bytes = wb.save().unwrap();
+    let mut wb = Workbook::from_file(&bytes, "again".to_string()).unwrap();
+    assert_eq!(wb.list_checkpoints().len(), 1);
+
+    let effect = wb.handle_action(EditAction::Payloads(PayloadsAction {
+        payloads: vec![EditPayload::RestoreCheckpoint(RestoreCheckpoint {
+            label: "draft".into(),
+        })],
+        undoable: true,
+        init: false,
+    }));
+    assert!(effect.version > 0);
+    let sheets = wb.get_all_sheet_info();
+    assert_eq!(sheets.len(), 1);
+    assert_eq!(sheets[0].name, "Sheet1");
+    let ws = wb.get_sheet_by_idx(0).unwrap();
+    assert!(matches!(ws.get_value(0, 0).unwrap(), Value::Number(n) if n == 1.0));
+    assert!(matches!(ws.get_value(1, 0).unwrap(), Value::Str(s) if s == "007"));
+    assert!(matches!(ws.get_value(2, 0).unwrap(), Value::Number(n) if n == 10.0));
+    assert_eq!(ws.get_formula(2, 0).unwrap(), "A1 * 10");
+    assert!(matches!(ws.get_value(3, 0).unwrap(), Value::Bool(true)));
+    assert!(matches!(ws.get_value(4, 0).unwrap(), Value::Empty));
+    // A typed-in error comes back as the error, not as a formula.
+    assert!(matches!(ws.get_value(5, 0).unwrap(), Value::Error(e) if e == "#N/A"));
+    assert_eq!(ws.get_formula(5, 0).unwrap(), "");
+    // The renamed sheet is renamed back rather than replaced, so what the
+    // checkpoint does not record, such as styles, stays.
+    assert_eq!(ws.get_style(0, 0).unwrap().formatter, "0.00");
+
+    // The restore is undoable like any other.
+    wb.undo();
+    let ws = wb.get_sheet_by_idx(0).unwrap();
+    assert!(matches!(ws.get_value(0, 0).unwrap(), Value::Number(n) if n == 5.0));
+}
+
+#[test]
+fn audit_log_records_cell_history_and_survives_reload() {
+    use crate::controller::display::Value;
+
+    let mut wb = Workbook::default();
+    let input = |row, col, content: &str| {
+        EditAction::Payloads(PayloadsAction {
+            payloads: vec![EditPayload::CellInput(CellInput {
+                sheet_idx: 0,
+                row,
+                col,
+                content: content.to_string(),
+                ..Default::default()
+            })],
+            undoable: true,
+            init: false,
+        })
+    };
+    // Nothing is recorded until the log is switched on.
+    wb.handle_action(input(0, 0, "1"));
+    assert!(wb.get_change_feed(0).is_empty());
+
+    wb.set_audit(true, true);
+    wb.set_audit_author(author("Ana"), "2026-03-01T09:00:00Z".into());
+    wb.handle_action(input(0, 1, "=A1*2"));
+    wb.set_audit_author(author("Bo"), "2026-03-01T10:00:00Z".into());
+    wb.handle_action(input(0, 0, "5"));
+    wb.undo();
+
+    let feed = wb.get_change_feed(0);
+    assert_eq!(feed.len(), 3);
+    assert_eq!(feed[0].payloads, vec!["CellInput"]);
+    assert_eq!(feed[1].author.as_ref().unwrap().display_name, "Bo");
+    assert_eq!(feed[1].timestamp.as_deref(), Some("2026-03-01T10:00:00Z"));
+    // The edited cell and its dependent, which only recalculated.
+    assert_eq!(feed[1].changes.len(), 2);
+    let dependent = &feed[1].changes[1];
+    assert_eq!((dependent.row, dependent.col), (0, 1));
+    assert!(dependent.recalculated);
+    assert_eq!(dependent.old_value, Value::Number(2.0));
+    assert_eq!(dependent.new_value, Value::Number(10.0));
+    assert_eq!(feed[2].payloads, vec!["Undo"]);
+    assert!(feed[2].changes.is_empty());
+    assert_eq!(wb.get_change_feed(2).len(), 1);
+
+    let history = wb.get_cell_history(0, 0, 1).unwrap();
+    assert_eq!(history.len(), 2);
+    let written = &history[0].changes[0];
+    assert_eq!(history[0].author.as_ref().unwrap().display_name, "Ana");
+    assert_eq!(written.old_formula, "");
+    assert_eq!(written.new_formula, "A1 * 2");
+    assert!(!written.recalculated);
+
+    // A row inserted above moves the cell; its history follows it through
+    // save and load.
+    wb.handle_action(EditAction::Payloads(PayloadsAction {
+        payloads: vec![EditPayload::InsertRows(crate::edit_action::InsertRows {
+            sheet_idx: 0,
+            start: 0,
+            count: 1,
+        })],
+        undoable: true,
+        init: false,
+    }));
+    let bytes = wb.save().unwrap();
+    let wb = Workbook::from_file(&bytes, "reloaded".to_string()).unwrap();
+    assert_eq!(wb.get_change_feed(0).len(), 4);
+    let history = wb.get_cell_history(0, 1, 1).unwrap();
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[0].author.as_ref().unwrap().display_name, "Ana");
+    assert_eq!(history[0].changes[0].new_formula, "A1 * 2");
+    assert_eq!(history[1].changes[0].new_value, Value::Number(10.0));
+    assert!(wb.get_cell_history(0, 0, 1).unwrap().is_empty());
+}
+
+// Shared by the what-if tests below.
+fn run(wb: &mut Workbook, payload: EditPayload) -> crate::edit_action::StatusCode {
+    let effect = wb.handle_action(EditAction::Payloads(PayloadsAction {
+        payloads: vec![payload],
+        undoable: true,
+        init: false,
+    }));
+    effect.status
+}
+
+fn input(
+    wb: &mut Workbook,
+    sheet_idx: usize,
+    row: usize,
+    col: usize,
+    content: &str,
+) -> crate::edit_action::StatusCode {
+    let payload = EditPayload::CellInput(CellInput {
+        sheet_idx,
+        row,
+        col,
+        content: content.to_string(),
+        ..Default::default()
+    });
+    run(wb, payload)
+}
+
+fn value(
+    wb: &Workbook,
+    sheet_idx: usize,
+    row: usize,
+    col: usize,
+) -> crate::controller::display::Value {
+    wb.get_sheet_by_idx(sheet_idx)
+        .unwrap()
+        .get_value(row, col)
+        .unwrap()
+}
+
+#[test]
+fn what_if_branches_compare_and_merge() {
+    use crate::controller::display::Value;
+
+    let mut wb = Workbook::default();
+    input(&mut wb, 0, 0, 0, "1");
+    input(&mut wb, 0, 0, 1, "=A1*2");
+    wb.fork_branch("low".into()).unwrap();
+    wb.fork_branch("high".into()).unwrap();
+    assert!(wb.fork_branch("main".into()).is_err());
+    assert!(wb.switch_branch("nope").is_err());
+
+    wb.switch_branch("high").unwrap();
+    input(&mut wb, 0, 0, 0, "10");
+    assert_eq!(value(&wb, 0, 0, 1), Value::Number(20.0));
+    wb.switch_branch("low").unwrap();
+    assert_eq!(value(&wb, 0, 0, 1), Value::Number(2.0));
+    input(&mut wb, 0, 0, 0, "0.5");
+    input(&mut wb, 0, 0, 2, "x");
+    input(&mut wb, 0, 5, 5, "dropped");
+    wb.undo();
+    let branches = wb.list_branches();
+    let names: Vec<_> = branches.iter().map(|b| b.name.as_str()).collect();
+    assert_eq!(names, vec!["main", "high", "low"]);
+    assert!(branches[2].current);
+    assert_eq!(branches[2].edits, 2);
+
+    let diff = wb.compare_branches("low", "high").unwrap();
+    let cells: Vec<_> = diff.iter().map(|c| (c.row, c.col)).collect();
+    assert_eq!(cells, vec![(0, 0), (0, 1), (0, 2)]);
+    assert_eq!(diff[0].old_value, Value::Number(0.5));
+    assert!(diff[1].recalculated);
+    assert_eq!(diff[2].new_value, Value::Empty);
+
+    // Main moved on elsewhere: no conflict, and the merge keeps both.
+    wb.switch_branch("main").unwrap();
+    assert_eq!(value(&wb, 0, 0, 0), Value::Number(1.0));
+    input(&mut wb, 0, 3, 3, "7");
+    let merged = wb.merge_branch("high", false).unwrap();
+    assert!(merged.merged && merged.conflicts.is_empty());
+    assert_eq!(value(&wb, 0, 0, 1), Value::Number(20.0));
+    assert_eq!(value(&wb, 0, 3, 3), Value::Number(7.0));
+    assert_eq!(wb.list_branches().len(), 2);
+
+    // Main and `low` both changed A1 since the fork.
+    let refused = wb.merge_branch("low", false).unwrap();
+    assert!(!refused.merged);
+    assert_eq!(refused.conflicts.len(), 1);
+    let conflict = &refused.conflicts[0];
+    assert_eq!((conflict.row, conflict.col), (0, 0));
+    assert_eq!(conflict.base_value, Value::Number(1.0));
+    assert_eq!(conflict.main_value, Value::Number(10.0));
+    assert_eq!(conflict.branch_value, Value::Number(0.5));
+    assert_eq!(value(&wb, 0, 0, 0), Value::Number(10.0));
+
+    let forced = wb.merge_branch("low", true).unwrap();
+    assert!(forced.merged);
+    assert_eq!(value(&wb, 0, 0, 0), Value::Number(0.5));
+    assert!(matches!(value(&wb, 0, 0, 2), Value::Str(s) if s == "x"));
+    assert_eq!(value(&wb, 0, 5, 5), Value::Empty);
+    wb.undo();
+    assert_eq!(value(&wb, 0, 0, 0), Value::Number(10.0));
+    assert_eq!(wb.current_branch(), "main");
+}
+
+#[test]
+fn merging_a_branch_follows_main_sheets_and_lines() {
+    use crate::controller::display::Value;
+    use crate::edit_action::{CreateSheet, InsertRows};
+
+    fn create(wb: &mut Workbook, idx: usize, new_name: &str) {
+        let payload = EditPayload::CreateSheet(CreateSheet {
+            idx,
+            new_name: new_name.to_string(),
+        });
+        run(wb, payload);
+    }
+
+    let mut wb = Workbook::default();
+    input(&mut wb, 0, 0, 0, "1");
+    wb.fork_branch("notes".into()).unwrap();
+    wb.switch_branch("notes").unwrap();
+    input(&mut wb, 0, 0, 1, "=A1+1");
+    create(&mut wb, 1, "Notes");
+    input(&mut wb, 1, 0, 0, "'note");
+
+    // Main puts a sheet in front of the one the branch edits and inserts
+    // rows there: the branch's edits still go to its own sheets.
+    wb.switch_branch("main").unwrap();
+    create(&mut wb, 0, "Front");
+    input(&mut wb, 0, 0, 0, "'front");
+    let payload = EditPayload::InsertRows(InsertRows {
+        sheet_idx: 0,
+        start: 0,
+        count: 1,
+    });
+    run(&mut wb, payload);
+    let merged = wb.merge_branch("notes", false).unwrap();
+    assert!(merged.merged && merged.conflicts.is_empty());
+    let names: Vec<_> = wb
+        .get_all_sheet_info()
+        .into_iter()
+        .map(|s| s.name)
+        .collect();
+    assert_eq!(names, vec!["Front", "Sheet1", "Notes"]);
+    assert!(matches!(value(&wb, 0, 1, 0), Value::Str(s) if s == "front"));
+    assert_eq!(value(&wb, 1, 0, 1), Value::Number(2.0));
+    assert!(matches!(value(&wb, 2, 0, 0), Value::Str(s) if s == "note"));
+
+    // Replaying the branch by position would miss the cells main moved down,
+    // so the merge is refused.
+    wb.fork_branch("rows".into()).unwrap();
+    wb.switch_branch("rows").unwrap();
+    input(&mut wb, 1, 0, 0, "5");
+    wb.switch_branch("main").unwrap();
+    let payload = EditPayload::InsertRows(InsertRows {
+        sheet_idx: 1,
+        start: 0,
+        count: 1,
+    });
+    run(&mut wb, payload);
+    let err = wb.merge_branch("rows", true).unwrap_err();
+    assert!(err.to_string().contains("rows or columns in sheet Sheet1"));
+    assert_eq!(value(&wb, 1, 1, 0), Value::Number(1.0));
+
+    // Rows the branch inserts move cells without changing them, so main's
+    // edit to one of them is no conflict and survives the merge.
+    wb.delete_branch("rows").unwrap();
+    wb.fork_branch("shift".into()).unwrap();
+    wb.switch_branch("shift").unwrap();
+    let payload = EditPayload::InsertRows(InsertRows {
+        sheet_idx: 1,
+        start: 0,
+        count: 1,
+    });
+    run(&mut wb, payload);
+    wb.switch_branch("main").unwrap();
+    input(&mut wb, 1, 1, 0, "3");
+    let merged = wb.merge_branch("shift", false).unwrap();
+    assert!(merged.merged && merged.conflicts.is_empty());
+    assert_eq!(value(&wb, 1, 2, 0), Value::Number(3.0));
+    assert_eq!(value(&wb, 1, 2, 1), Value::Number(4.0));
+
+    // An edit that is not recorded is not dropped silently either.
+    wb.fork_branch("quiet".into()).unwrap();
+    wb.switch_branch("quiet").unwrap();
+    wb.handle_action(EditAction::Payloads(PayloadsAction {
+        payloads: vec![EditPayload::CellInput(CellInput {
+            sheet_idx: 0,
+            row: 4,
+            col: 4,
+            content: "1".to_string(),
+            ..Default::default()
+        })],
+        undoable: false,
+        init: false,
+    }));
+    wb.switch_branch("main").unwrap();
+    assert!(wb.merge_branch("quiet", true).is_err());
+}
+
+#[test]
+fn data_tables_fill_in_and_survive_reload() {
+    use crate::controller::display::Value;
+    use crate::edit_action::{CreateDataTable, InputCell, RemoveDataTable, StatusCode};
+
+    let mut wb = Workbook::default();
+    input(&mut wb, 0, 0, 3, "1");
+    input(&mut wb, 0, 0, 4, "10");
+    // Column input D1: the values run down A3:A5, the formula sits in B2.
+    input(&mut wb, 0, 1, 1, "=D1*2+G1");
+    for (row, v) in [(2, "1"), (3, "2"), (4, "3")] {
+        input(&mut wb, 0, row, 0, v);
+    }
+    let one_var = CreateDataTable {
+        sheet_idx: 0,
+        start_row: 2,
+        start_col: 1,
+        end_row: 4,
+        end_col: 1,
+        row_input: None,
+        col_input: Some(InputCell { row: 0, col: 3 }),
+    };
+    assert!(matches!(run(&mut wb, one_var.into()), StatusCode::Ok(_)));
+    // Both inputs: D1 along G8:H8, E1 down F9:F10, the formula in F8.
+    input(&mut wb, 0, 7, 5, "=D1*E1");
+    input(&mut wb, 0, 7, 6, "2");
+    input(&mut wb, 0, 7, 7, "3");
+    input(&mut wb, 0, 8, 5, "5");
+    input(&mut wb, 0, 9, 5, "7");
+    let two_var = CreateDataTable {
+        sheet_idx: 0,
+        start_row: 8,
+        start_col: 6,
+        end_row: 9,
+        end_col: 7,
+        row_input: Some(InputCell { row: 0, col: 3 }),
+        col_input: Some(InputCell { row: 0, col: 4 }),
+    };
+    assert!(matches!(run(&mut wb, two_var.into()), StatusCode::Ok(_)));
+
+    let check = |wb: &Workbook| {
+        assert_eq!(value(wb, 0, 2, 1), Value::Number(2.0));
+        assert_eq!(value(wb, 0, 4, 1), Value::Number(6.0));
+        assert_eq!(value(wb, 0, 8, 6), Value::Number(10.0));
+        assert_eq!(value(wb, 0, 9, 7), Value::Number(21.0));
+        // The substitutions never reach the input cells.
+        assert_eq!(value(wb, 0, 0, 3), Value::Number(1.0));
+        assert_eq!(value(wb, 0, 1, 1), Value::Number(2.0));
+    };
+    check(&wb);
+    let ws = wb.get_sheet_by_idx(0).unwrap();
+    assert_eq!(ws.get_formula(3, 1).unwrap(), "TABLE(,D1)");
+    assert_eq!(ws.get_formula(8, 7).unwrap(), "TABLE(D1,E1)");
+    assert_eq!(ws.get_data_tables().len(), 2);
+
+    assert!(matches!(input(&mut wb, 0, 3, 1, "9"), StatusCode::Err(_)));
+    input(&mut wb, 0, 3, 0, "5");
+    assert_eq!(value(&wb, 0, 3, 1), Value::Number(10.0));
+    wb.undo();
+    check(&wb);
+
+    let bytes = wb.save().unwrap();
+    let mut wb = Workbook::from_file(&bytes, "reloaded".to_string()).unwrap();
+    check(&wb);
+    let tables = wb.get_sheet_by_idx(0).unwrap().get_data_tables();
+    assert_eq!(tables.len(), 2);
+    input(&mut wb, 0, 9, 5, "100");
+    assert_eq!(value(&wb, 0, 9, 7), Value::Number(300.0));
+    // G1 is no input, but the formula reads it.
+    input(&mut wb, 0, 0, 6, "1");
+    assert_eq!(value(&wb, 0, 4, 1), Value::Number(7.0));
+    assert_eq!(value(&wb, 0, 9, 7), Value::Number(300.0));
+
+    let table_id = tables[0].table_id;
+    let remove = RemoveDataTable {
+        sheet_idx: 0,
+        table_id,
+    };
+    assert!(matches!(run(&mut wb, remove.into()), StatusCode::Ok(_)));
+    assert!(matches!(input(&mut wb, 0, 3, 1, "9"), StatusCode::Ok(_)));
+    assert_eq!(wb.get_sheet_by_idx(0).unwrap().get_data_tables().len(), 1);
+}
+
+#[test]
+fn data_tables_keep_their_inputs_through_save_and_load() {
+    use crate::controller::display::Value;
+    use crate::edit_action::{CreateDataTable, InputCell};
+    use logisheets_workbook::prelude::{StCellFormulaType, Wb, write};
+
+    // A table's top-left cell with its row and column inputs.
+    type Inputs = (usize, usize, Option<(usize, usize)>, Option<(usize, usize)>);
+    // A `<f t="dataTable">`: its ref, dt2D, dtr, r1 and r2.
+    type Formula = (String, bool, bool, Option<String>, Option<String>);
+
+    fn inputs(wb: &Workbook) -> Vec<Inputs> {
+        let at = |c: Option<InputCell>| c.map(|c| (c.row, c.col));
+        let mut tables = wb.get_sheet_by_idx(0).unwrap().get_data_tables();
+        tables.sort_by_key(|t| (t.start_row, t.start_col));
+        tables
+            .into_iter()
+            .map(|t| (t.start_row, t.start_col, at(t.row_input), at(t.col_input)))
+            .collect()
+    }
+    fn data_table_formulas(raw: &Wb) -> Vec<Formula> {
+        let mut formulas = raw
+            .xl
+            .worksheets
+            .values()
+            .flat_map(|ws| ws.worksheet_part.sheet_data.rows.iter())
+            .flat_map(|row| row.cells.iter())
+            .filter_map(|c| c.f.as_ref())
+            .filter(|f| f.t == StCellFormulaType::DataTable)
+            .map(|f| {
+                let reference = f.reference.clone().unwrap_or_default();
+                (reference, f.dt_2d, f.dtr, f.r1.clone(), f.r2.clone())
+            })
+            .collect::<Vec<_>>();
+        formulas.sort();
+        formulas
+    }
+
+    let mut wb = Workbook::default();
+    input(&mut wb, 0, 0, 3, "1");
+    input(&mut wb, 0, 0, 4, "10");
+    // Column input D1: values down A3:A5, the formula in B2.
+    input(&mut wb, 0, 1, 1, "=D1*2");
+    for (row, v) in [(2, "1"), (3, "2"), (4, "3")] {
+        input(&mut wb, 0, row, 0, v);
+    }
+    // Row input E1: values along I3:J3, the formula in H4.
+    input(&mut wb, 0, 3, 7, "=E1+1");
+    input(&mut wb, 0, 2, 8, "4");
+    input(&mut wb, 0, 2, 9, "5");
+    // Both: D1 along G8:H8, E1 down F9:F10, the formula in F8.
+    input(&mut wb, 0, 7, 5, "=D1*E1");
+    input(&mut wb, 0, 7, 6, "2");
+    input(&mut wb, 0, 7, 7, "3");
+    input(&mut wb, 0, 8, 5, "5");
+    input(&mut wb, 0, 9, 5, "7");
+    for (start, end, row_input, col_input) in [
+        ((2, 1), (4, 1), None, Some((0, 3))),
+        ((3, 8), (3, 9), Some((0, 4)), None),
+        ((8, 6), (9, 7), Some((0, 3)), Some((0, 4))),
+    ] {
+        let cell = |(row, col): (usize, usize)| InputCell { row, col };
+        let payload = CreateDataTable {
+            sheet_idx: 0,
+            start_row: start.0,
+            start_col: start.1,
+            end_row: end.0,
+            end_col: end.1,
+            row_input: row_input.map(cell),
+            col_input: col_input.map(cell),
+        };
+        wb.handle_action(EditAction::Payloads(PayloadsAction {
+            payloads: vec![payload.into()],
+            undoable: true,
+            init: false,
+        }));
+    }
+    let expected = inputs(&wb);
+    assert_eq!(expected.len(), 3);
+    assert_eq!(value(&wb, 0, 3, 9), Value::Number(6.0));
+
+    // A one-input table names its input in `r1` and says with `dtr` whether
+    // it is the row input; with both, `r1` is the row input and `dtr` says so.
+    let bytes = wb.save().unwrap();
+    let mut raw = Wb::from_file(&bytes).unwrap();
+    let d1 = Some("D1".to_string());
+    let e1 = Some("E1".to_string());
+    assert_eq!(
+        data_table_formulas(&raw),
+        vec![
+            ("B3:B5".to_string(), false, false, d1.clone(), None),
+            ("G9:H10".to_string(), true, true, d1.clone(), e1.clone()),
+            ("I4:J4".to_string(), false, true, e1.clone(), None),
+        ]
+    );
+    let reloaded = Workbook::from_file(&bytes, "reloaded".to_string()).unwrap();
+    assert_eq!(inputs(&reloaded), expected);
+    assert_eq!(value(&reloaded, 0, 4, 1), Value::Number(6.0));
+    assert_eq!(value(&reloaded, 0, 3, 9), Value::Number(6.0));
+    assert_eq!(value(&reloaded, 0, 9, 7), Value::Number(21.0));
+
+    // A two-input table whose `dtr` is clear has its column input in `r1`.
+    for ws in raw.xl.worksheets.values_mut() {
+        for cell in ws
+            .worksheet_part
+            .sheet_data
+            .rows
+            .iter_mut()
+            .flat_map(|r| r.cells.iter_mut())
+        {
+            if let Some(f) = cell.f.as_mut()
+                && f.dt_2d
+            {
+                f.dtr = false;
+                std::mem::swap(&mut f.r1, &mut f.r2);
+            }
+        }
+    }
+    let bytes = write(raw).unwrap();
+    let swapped = Workbook::from_file(&bytes, "swapped".to_string()).unwrap();
+    assert_eq!(inputs(&swapped), expected);
+}
+
+#[test]
+fn goal_seek_waits_in_the_temp_status() {
+    use crate::controller::display::Value;
+
+    fn number(wb: &Workbook, row: usize, col: usize) -> f64 {
+        match value(wb, 0, row, col) {
+            Value::Number(n) => n,
+            v => panic!("not a number: {:?}", v),
+        }
+    }
+
+    let mut wb = Workbook::default();
+    input(&mut wb, 0, 0, 0, "1");
+    input(&mut wb, 0, 0, 1, "=A1*A1+1");
+    assert!(wb.goal_seek(0, 0, 0, 10.0, 0, 1).is_err());
+
+    let result = wb.goal_seek(0, 0, 1, 10.0, 0, 0).unwrap();
+    assert!(result.found);
+    assert!((result.input_value - 3.0).abs() < 1e-6);
+    assert!((number(&wb, 0, 1) - 10.0).abs() < 1e-6);
+    wb.clean_temp_status();
+    assert_eq!(number(&wb, 0, 0), 1.0);
+
+    // A kink Newton's method can't follow falls back to bisection.
+    input(&mut wb, 0, 0, 2, "=IF(A1<4,A1,A1*10)");
+    let result = wb.goal_seek(0, 0, 2, 50.0, 0, 0).unwrap();
+    assert!(result.found);
+    assert!((result.input_value - 5.0).abs() < 1e-6);
+    wb.commit_temp_status();
+    assert!((number(&wb, 0, 0) - 5.0).abs() < 1e-6);
+
+    let result = wb.goal_seek(0, 0, 1, -5.0, 0, 0).unwrap();
+    assert!(!result.found);
+    assert!((result.input_value - 5.0).abs() < 1e-6);
+}
+
+#[test]
+fn scenarios_show_summarize_and_survive_reload() {
+    use crate::controller::display::Value;
+    use crate::edit_action::{
+        AddScenario, CreateScenarioSummary, DeleteScenario, EditScenario, InputCell, InsertRows,
+        ScenarioValue, ShowScenario, StatusCode,
+    };
+
+    fn ok(status: StatusCode) -> bool {
+        matches!(status, StatusCode::Ok(_))
+    }
+    fn add(name: &str, price: &str, qty: &str) -> EditPayload {
+        let cell = |row, value: &str| ScenarioValue {
+            row,
+            col: 1,
+            value: value.to_string(),
+        };
+        AddScenario {
+            sheet_idx: 0,
+            name: name.to_string(),
+            cells: vec![cell(0, price), cell(1, qty)],
+            comment: None,
+        }
+        .into()
+    }
+    fn show(name: &str) -> EditPayload {
+        ShowScenario {
+            sheet_idx: 0,
+            name: name.to_string(),
+        }
+        .into()
+    }
+
+    let mut wb = Workbook::default();
+    input(&mut wb, 0, 0, 1, "10");
+    input(&mut wb, 0, 1, 1, "5");
+    input(&mut wb, 0, 2, 1, "=B1*B2");
+    assert!(ok(run(&mut wb, add("Best", "12", "8"))));
+    assert!(ok(run(&mut wb, add("Worst", "8", "3"))));
+    assert!(!ok(run(&mut wb, add("Best", "1", "1"))));
+    assert!(ok(run(&mut wb, add("Spare", "1", "1"))));
+    let delete = DeleteScenario {
+        sheet_idx: 0,
+        name: "Spare".to_string(),
+    };
+    assert!(ok(run(&mut wb, delete.into())));
+    let rename = EditScenario {
+        sheet_idx: 0,
+        name: "Worst".to_string(),
+        new_name: Some("Low".to_string()),
+        cells: None,
+        comment: Some("slow quarter".to_string()),
+    };
+    assert!(ok(run(&mut wb, rename.into())));
+
+    assert!(ok(run(&mut wb, show("Best"))));
+    assert_eq!(value(&wb, 0, 2, 1), Value::Number(96.0));
+    assert!(wb.get_sheet_by_idx(0).unwrap().get_scenarios()[0].shown);
+    wb.undo();
+    assert_eq!(value(&wb, 0, 0, 1), Value::Number(10.0));
+    assert_eq!(value(&wb, 0, 2, 1), Value::Number(50.0));
+
+    let summary = CreateScenarioSummary {
+        sheet_idx: 0,
+        result_cells: vec![InputCell { row: 2, col: 1 }],
+        new_idx: 1,
+        new_name: "Scenario Summary".to_string(),
+    };
+    assert!(ok(run(&mut wb, summary.into())));
+    let text = |s: &str| Value::Str(s.to_string());
+    assert_eq!(value(&wb, 1, 1, 3), text("Best"));
+    assert_eq!(value(&wb, 1, 1, 4), text("Low"));
+    assert_eq!(value(&wb, 1, 3, 1), text("$B$1"));
+    assert_eq!(value(&wb, 1, 3, 3), Value::Number(12.0));
+    assert_eq!(value(&wb, 1, 5, 0), text("Result Cells:"));
+    assert_eq!(value(&wb, 1, 6, 1), text("$B$3"));
+    assert_eq!(value(&wb, 1, 6, 2), Value::Number(50.0));
+    assert_eq!(value(&wb, 1, 6, 3), Value::Number(96.0));
+    assert_eq!(value(&wb, 1, 6, 4), Value::Number(24.0));
+    // The scenarios were shown on a scratch copy only.
+    assert_eq!(value(&wb, 0, 2, 1), Value::Number(50.0));
+
+    run(
+        &mut wb,
+        EditPayload::InsertRows(InsertRows {
+            sheet_idx: 0,
+            start: 0,
+            count: 1,
+        }),
+    );
+    let bytes = wb.save().unwrap();
+    let mut wb = Workbook::from_file(&bytes, "reloaded".to_string()).unwrap();
+    let scenarios = wb.get_sheet_by_idx(0).unwrap().get_scenarios();
+    assert_eq!(scenarios.len(), 2);
+    assert_eq!(scenarios[1].name, "Low");
+    assert_eq!(scenarios[1].comment.as_deref(), Some("slow quarter"));
+    assert_eq!(scenarios[1].cells[0].row, 1);
+    assert!(ok(run(&mut wb, show("Low"))));
+    assert_eq!(value(&wb, 0, 3, 1), Value::Number(24.0));
+}
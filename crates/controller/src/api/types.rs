@@ -25,6 +25,13 @@ pub struct CellInfo {
     /// The conditional formatting currently in effect for this cell, or `None`
     /// when no rule matches it. See [`ConditionalFormat`].
     pub conditional_format: Option<ConditionalFormat>,
+    /// `value` as the grid shows it: rendered with the number format in
+    /// effect (the conditional format's, when one applies) and fitted to the
+    /// cell's width. Numbers that do not fit read `###`.
+    pub display_text: String,
+    /// The colour of a `[Red]` or `[Color10]` number-format section. When
+    /// set, it replaces the font colour.
+    pub display_color: Option<Color>,
 }
 
 /// What conditional formatting does to one cell, already resolved: the caller
@@ -151,3 +158,150 @@ pub struct CfRuleInfo {
     /// a preview swatch without the caller parsing a dxf.
     pub preview: Option<Style>,
 }
+
+/// A named cell style ("Normal", "Good", "Heading 1", ...) as a style
+/// gallery would list it.
+#[derive(Debug, Clone, TS)]
+#[ts(file_name = "cell_style_info.ts", rename_all = "camelCase")]
+pub struct CellStyleInfo {
+    pub name: String,
+    /// Set for Excel's built-in styles; 0 is "Normal".
+    pub builtin_id: Option<u32>,
+    pub style: Style,
+}
+
+/// A theme as a theme picker or editor shows it.
+#[derive(Debug, Clone, TS)]
+#[ts(file_name = "theme_info.ts", rename_all = "camelCase")]
+pub struct ThemeInfo {
+    pub name: String,
+    /// `RRGGBB`, in `clrScheme` order: dk1, lt1, dk2, lt2, accent1..6,
+    /// hlink, folHlink. The order of `ThemeColorSlot`.
+    pub colors: Vec<String>,
+    pub major_font: String,
+    pub minor_font: String,
+}
+
+/// A data table as `Worksheet::get_data_tables` lists it. Positions are
+/// 0-based and read from the table's current anchors.
+#[derive(Debug, Clone, TS)]
+#[ts(file_name = "data_table_info.ts", rename_all = "camelCase")]
+pub struct DataTableInfo {
+    /// Session-scoped; re-minted on load, so never persist it.
+    pub table_id: u32,
+    pub start_row: usize,
+    pub start_col: usize,
+    pub end_row: usize,
+    pub end_col: usize,
+    pub row_input: Option<crate::edit_action::InputCell>,
+    pub col_input: Option<crate::edit_action::InputCell>,
+    /// What the result cells show as their formula, e.g. `TABLE(B1,A2)`.
+    pub formula: String,
+}
+
+/// A scenario as `Worksheet::get_scenarios` lists it.
+#[derive(Debug, Clone, TS)]
+#[ts(file_name = "scenario_info.ts", rename_all = "camelCase")]
+pub struct ScenarioInfo {
+    pub name: String,
+    /// The changing cells at their current positions, each with the value
+    /// showing the scenario types into it.
+    pub cells: Vec<crate::edit_action::ScenarioValue>,
+    pub comment: Option<String>,
+    pub user: Option<String>,
+    pub locked: bool,
+    pub hidden: bool,
+    /// Whether this is the scenario last shown.
+    pub shown: bool,
+}
+
+/// The outcome of `Workbook::goal_seek`.
+#[derive(Debug, Clone, TS)]
+#[ts(file_name = "goal_seek_result.ts", rename_all = "camelCase")]
+pub struct GoalSeekResult {
+    /// Whether a value was found that brings the target to the goal.
+    pub found: bool,
+    /// The value put into the changing cell. When nothing was found, the
+    /// value it already had.
+    pub input_value: f64,
+    /// What the target cell shows with that value in place.
+    pub target_value: Value,
+}
+
+/// How a fill treats its source, after Excel's AutoFill options.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, TS)]
+#[ts(file_name = "fill_type.ts", rename_all = "camelCase")]
+pub enum FillType {
+    /// Infer a series from the source; contents and formats.
+    #[default]
+    Default,
+    /// Repeat the source block without inferring a series.
+    Copy,
+    /// A linear series. A single number steps by 1, a single date by a day.
+    Series,
+    /// Only the formats.
+    Formats,
+    /// The contents of [`FillType::Default`], without the formats.
+    Values,
+    /// Step dates by days.
+    Days,
+    /// Step dates by weekdays, skipping Saturdays and Sundays.
+    Weekdays,
+    /// Step dates by months.
+    Months,
+    /// Step dates by years.
+    Years,
+    /// A geometric series.
+    Growth,
+}
+
+/// Whether a series runs along rows or down columns.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, TS)]
+#[ts(file_name = "series_in.ts", rename_all = "camelCase")]
+pub enum SeriesIn {
+    Rows,
+    #[default]
+    Columns,
+}
+
+/// How the Series dialog computes each value from the previous one.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, TS)]
+#[ts(file_name = "series_type.ts", rename_all = "camelCase")]
+pub enum SeriesType {
+    /// Add the step.
+    #[default]
+    Linear,
+    /// Multiply by the step.
+    Growth,
+    /// Move the date on by `step` date units.
+    Date,
+    /// Fill from the leading non-blank cells as the fill handle would.
+    /// Step and stop are ignored.
+    AutoFill,
+}
+
+/// The unit of a date series.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, TS)]
+#[ts(file_name = "date_unit.ts", rename_all = "camelCase")]
+pub enum DateUnit {
+    #[default]
+    Day,
+    /// A day, skipping Saturdays and Sundays.
+    Weekday,
+    Month,
+    Year,
+}
+
+/// The Series dialog. Each line of the range starts from its first cell.
+#[derive(Debug, Clone, TS)]
+#[ts(file_name = "series_options.ts", rename_all = "camelCase")]
+pub struct SeriesOptions {
+    pub series_in: SeriesIn,
+    pub series_type: SeriesType,
+    /// The unit of a [`SeriesType::Date`] series.
+    pub date_unit: DateUnit,
+    pub step: f64,
+    /// The series ends before passing this value. A range that is a single
+    /// cell along the series is extended until it does.
+    pub stop: Option<f64>,
+}
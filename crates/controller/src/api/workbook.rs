@@ -1,34 +1,63 @@
-use std::collections::{BTreeSet, HashMap};
+use std::collections::{BTreeSet, HashMap, HashSet};
 
 use super::{cell_positioner::CellPositioner, worksheet::Worksheet};
 use crate::{
-    CellInfo, Controller,
+    CellInfo, CellStyleInfo, Controller, GoalSeekResult, ThemeInfo,
+    block_manager::schema_manager::field_type::typed_value,
+    calc_engine::calculator::is_builtin_function,
     controller::{
         FormulaFormat,
         display::{
-            BlockDataRow, BlockField, BlockInfo, CellCoordinateWithSheet, CellPosition,
-            ShadowCellInfo, SheetInfo, TempCellChange, TempStatusDiff, Value as DisplayValue,
+            BlockDataRow, BlockField, BlockInfo, BranchInfo, CellChange, CellCoordinateWithSheet,
+            CellPosition, ChangeEntry, MergeResult, ShadowCellInfo, SheetInfo, TempCellChange,
+            TempStatusDiff, Value as DisplayValue,
         },
         status::Status,
+        style::StyleConverter,
     },
-    edit_action::{ActionEffect, PayloadsAction, SheetCellId, StatusCode},
+    edit_action::{ActionEffect, AuthorInput, PayloadsAction, SheetCellId, StatusCode},
     lock::{Locked, locked_write, new_locked},
+    settings::InputLocale,
+    theme_manager::{BUILTIN_THEMES, ThemeManager, builtin_theme},
 };
 use crate::{
     edit_action::{EditAction, EphemeralCellInput},
     errors::{Error, Result},
 };
+use logisheets_astchecker::{
+    FormulaCheckContext, FormulaCheckResult, FormulaDiagnostic, FormulaDiagnosticKind,
+};
 use logisheets_base::{
     BlockCellId, BlockId, CellId, ColId, FuncId, RowId, SheetId, TextId,
     async_func::{AsyncCalcResult, Task},
     errors::BasicError,
 };
-use logisheets_lexer::lex;
 use logisheets_parser::ast;
 use logisheets_workbook::logisheets::AppData;
 
 const CALC_CONDITION_EPHEMERAL_ID: u64 = 225715;
 
+struct WorkbookCheckContext<'a> {
+    sheets: HashSet<String>,
+    async_funcs: &'a HashSet<String>,
+}
+
+impl FormulaCheckContext for WorkbookCheckContext<'_> {
+    fn is_known_function(&self, name: &str) -> bool {
+        let upper = name.to_uppercase();
+        is_builtin_function(&upper)
+            || self.async_funcs.contains(&upper)
+            || matches!(
+                upper.as_str(),
+                "BLOCKREF" | "BLOCKREFS" | "BLOCKREFB" | "BLOCKREFSB" | "_XLFN.SINGLE"
+            )
+    }
+
+    fn is_known_sheet(&self, name: &str) -> bool {
+        self.sheets.contains(&name.to_lowercase())
+    }
+}
+
 pub(crate) type CellPositionerDefault = CellPositioner<1000>;
 
 pub struct Workbook {
@@ -60,6 +89,11 @@ impl Workbook {
         &self.controller.status
     }
 
+    /// The theme, for sibling api modules that resolve theme colours.
+    pub(crate) fn theme(&self) -> &crate::theme_manager::ThemeManager {
+        &self.controller.status.theme_manager
+    }
+
     /// Execute the `EditAction`
     pub fn handle_action(&mut self, action: EditAction) -> ActionEffect {
         // Undo/Redo report an empty change list (they swap a whole status
@@ -447,6 +481,67 @@ impl Workbook {
         Ok(wb)
     }
 
+    /// Recalculate every formula, ignoring the cached values a loaded file
+    /// came with.
+    pub fn recalc_all(&mut self) {
+        self.controller.recalc_all();
+    }
+
+    /// The locale cell input is read with: date order, separators and
+    /// currency symbol.
+    pub fn get_input_locale(&self) -> &InputLocale {
+        &self.controller.settings.input_locale
+    }
+
+    pub fn set_input_locale(&mut self, locale: InputLocale) {
+        self.controller.settings.input_locale = locale;
+    }
+
+    /// The workbook's own fill lists, tried before the built-in weekdays,
+    /// months and quarters.
+    pub fn get_custom_lists(&self) -> &[Vec<String>] {
+        &self.controller.settings.custom_lists
+    }
+
+    pub fn set_custom_lists(&mut self, lists: Vec<Vec<String>>) {
+        self.controller.settings.custom_lists = lists;
+    }
+
+    /// The workbook theme, or `None` for a workbook without one, which
+    /// renders with the Office theme.
+    pub fn get_theme(&self) -> Option<ThemeInfo> {
+        theme_info(&self.controller.status.theme_manager)
+    }
+
+    /// The built-in Office themes `ApplyBuiltinTheme` accepts.
+    pub fn get_builtin_themes(&self) -> Vec<ThemeInfo> {
+        BUILTIN_THEMES
+            .iter()
+            .filter_map(|t| theme_info(&ThemeManager::from(builtin_theme(t.name)?)))
+            .collect()
+    }
+
+    /// The named cell styles, in file order.
+    pub fn get_cell_styles(&self) -> Vec<CellStyleInfo> {
+        let style_manager = &self.controller.status.style_manager;
+        let converter = StyleConverter {
+            theme_manager: &self.controller.status.theme_manager,
+        };
+        style_manager
+            .named_styles
+            .styles()
+            .iter()
+            .filter_map(|s| {
+                let raw = style_manager.get_named_style(&s.name)?;
+                Some(CellStyleInfo {
+                    name: s.name.clone(),
+                    builtin_id: s.builtin_id,
+                    style: converter.convert_style(raw),
+                })
+            })
+            .collect()
+    }
+
     #[inline]
     pub fn get_app_data(&self) -> Vec<AppData> {
         self.controller.app_data.clone()
@@ -550,18 +645,145 @@ impl Workbook {
         self.controller.checkpoint_manager.len()
     }
 
+    /// Like `save_checkpoint`, with a timestamp and author to list it by.
+    /// With `persist` set, the checkpoint is written into the file on
+    /// save and can be restored after the workbook is loaded again. Such a
+    /// restore only brings back cell values and formulas, and the sheets
+    /// with their names and order; styles, merges, defined names and the
+    /// rest are not kept in the file and stay as the workbook has them.
+    pub fn save_checkpoint_with_meta(
+        &mut self,
+        meta: crate::checkpoint_manager::CheckpointMeta,
+    ) -> usize {
+        self.controller
+            .checkpoint_manager
+            .save_with_meta(meta, self.controller.status.clone());
+        self.controller.checkpoint_manager.len()
+    }
+
     /// Drop a named checkpoint. Returns `true` if it existed.
     pub fn delete_checkpoint(&mut self, label: &str) -> bool {
         self.controller.checkpoint_manager.delete(label)
     }
 
-    /// Enumerate all checkpoints, newest first. Returns just labels
-    /// and descriptions — the bulky `Status` snapshot stays inside the
-    /// manager.
+    /// Enumerate all checkpoints, newest first. Returns just their
+    /// metadata — the bulky `Status` snapshot stays inside the manager,
+    /// and a checkpoint loaded from the file is not rebuilt to list it.
     pub fn list_checkpoints(&self) -> Vec<crate::checkpoint_manager::CheckpointMeta> {
         self.controller.checkpoint_manager.list()
     }
 
+    // ----- Audit log ----------------------------------------------------
+
+    /// Start or stop recording committed changes. With `persist`, the log
+    /// is written into the file on save. A file that carries a log turns
+    /// recording on when loaded.
+    pub fn set_audit(&mut self, enabled: bool, persist: bool) {
+        self.controller.audit_manager.set_enabled(enabled, persist)
+    }
+
+    /// The author and timestamp the following commits are recorded with.
+    /// The host owns the clock, so it restamps as time moves on.
+    pub fn set_audit_author(&mut self, author: AuthorInput, timestamp: String) {
+        self.controller.audit_manager.stamp(author, timestamp)
+    }
+
+    /// Every recorded change of the cell, oldest first.
+    pub fn get_cell_history(
+        &self,
+        sheet_idx: usize,
+        row: usize,
+        col: usize,
+    ) -> Result<Vec<ChangeEntry>> {
+        let status = &self.controller.status;
+        let sheet_id = status
+            .sheet_info_manager
+            .get_sheet_id(sheet_idx)
+            .ok_or(BasicError::SheetIdxExceed(sheet_idx))?;
+        let cell_id = status.navigator.fetch_cell_id(&sheet_id, row, col)?;
+        Ok(self
+            .controller
+            .audit_manager
+            .cell_history(sheet_id, cell_id))
+    }
+
+    /// The change feed from entry `since` on. Hosts poll with the `seq`
+    /// after the last entry they have seen.
+    pub fn get_change_feed(&self, since: usize) -> Vec<ChangeEntry> {
+        self.controller.audit_manager.feed(since)
+    }
+
+    pub fn clear_audit_log(&mut self) {
+        self.controller.audit_manager.clear()
+    }
+
+    // ----- What-if branches --------------------------------------------
+
+    /// Fork main into a new branch named `name`. Main is `main`; names
+    /// are unique.
+    pub fn fork_branch(&mut self, name: String) -> Result<()> {
+        self.controller.fork_branch(name)
+    }
+
+    /// Show branch `name`, or `main`. Edits, undo and redo apply to the
+    /// branch shown.
+    pub fn switch_branch(&mut self, name: &str) -> Result<()> {
+        self.controller.switch_branch(name)
+    }
+
+    /// Drop a branch. Returns `true` if it existed.
+    pub fn delete_branch(&mut self, name: &str) -> Result<bool> {
+        self.controller.delete_branch(name)
+    }
+
+    pub fn current_branch(&self) -> String {
+        self.controller.branch_manager.current().to_string()
+    }
+
+    /// `main` and every branch.
+    pub fn list_branches(&self) -> Vec<BranchInfo> {
+        self.controller.branch_manager.list()
+    }
+
+    /// The cells whose value or formula differ between two branches (either
+    /// may be `main`), old being `from`.
+    pub fn compare_branches(&self, from: &str, to: &str) -> Result<Vec<CellChange>> {
+        let shown = self.controller.committed_status();
+        Ok(self.controller.branch_manager.compare(from, to, shown)?)
+    }
+
+    /// Merge a branch into main. With conflicting edits nothing happens
+    /// unless `force` is set; the result lists the conflicts either way.
+    /// Fails, forced or not, when main has since inserted or deleted rows
+    /// or columns in a sheet the branch edits, or when the branch was
+    /// changed by an edit that was not undoable.
+    pub fn merge_branch(&mut self, name: &str, force: bool) -> Result<MergeResult> {
+        self.controller.merge_branch(name, force)
+    }
+
+    /// Goal Seek on sheet `sheet_idx`: find the value of the changing cell
+    /// that makes the target cell show `goal`. The value found is shown in
+    /// the temp status; `commit_temp_status` keeps it and
+    /// `clean_temp_status` takes it back.
+    pub fn goal_seek(
+        &mut self,
+        sheet_idx: usize,
+        target_row: usize,
+        target_col: usize,
+        goal: f64,
+        changing_row: usize,
+        changing_col: usize,
+    ) -> Result<GoalSeekResult> {
+        self.controller.goal_seek(
+            sheet_idx,
+            target_row,
+            target_col,
+            goal,
+            changing_row,
+            changing_col,
+        )
+    }
+
     #[inline]
     pub fn handle_async_calc_results(
         &mut self,
@@ -758,17 +980,41 @@ impl Workbook {
         self.controller.revision()
     }
 
-    /// To see if the formula is valid.
-    pub fn check_formula(&self, f: String) -> bool {
-        if f.is_empty() {
-            return false;
-        }
-        let f = f.trim();
-        if !f.starts_with('=') {
-            return false;
-        }
-        let tokens = lex(&f[1..]);
-        tokens.is_some()
+    /// To see if the formula is valid. Besides syntax errors, calls to
+    /// built-in functions are checked for their argument count and for
+    /// literal arguments of the wrong type, and references for sheets that
+    /// do not exist or addresses outside the grid. Diagnostic positions are
+    /// byte offsets into `f`.
+    pub fn check_formula(&self, f: String) -> FormulaCheckResult {
+        let trimmed = f.trim_start();
+        let Some(body) = trimmed.strip_prefix('=') else {
+            let start = f.len() - trimmed.len();
+            return FormulaCheckResult {
+                valid: false,
+                diagnostics: vec![FormulaDiagnostic {
+                    start,
+                    end: start,
+                    kind: FormulaDiagnosticKind::Syntax,
+                    message: "a formula starts with =".to_string(),
+                    expected: vec!["=".to_string()],
+                }],
+            };
+        };
+        let offset = f.len() - body.len();
+        let ctx = WorkbookCheckContext {
+            sheets: self
+                .get_all_sheet_info()
+                .into_iter()
+                .map(|s| s.name.to_lowercase())
+                .collect(),
+            async_funcs: &self.controller.settings.async_funcs,
+        };
+        let mut result = logisheets_astchecker::check_formula(body.trim_end(), &ctx);
+        result.diagnostics.iter_mut().for_each(|d| {
+            d.start += offset;
+            d.end += offset;
+        });
+        result
     }
 
     pub fn get_all_block_fields(&self) -> Result<Vec<BlockField>> {
@@ -1075,6 +1321,10 @@ impl Workbook {
     /// Columns follow the same order as `get_all_fields(ref_name)` (filtered),
     /// so the host can pair each column with that field list and the key index
     /// it already holds — no metadata is duplicated here.
+    ///
+    /// Values of typed fields come out as their type: dates as `yyyy-mm-dd`
+    /// strings, text, enum and reference fields as strings, numbers as
+    /// numbers.
     pub fn export_block_data(
         &self,
         ref_name: &str,
@@ -1098,6 +1348,11 @@ impl Workbook {
             None => all_fields,
         };
 
+        let field_types = fields
+            .iter()
+            .map(|f| schema.get_field_type(ref_name, f))
+            .collect::<Vec<_>>();
+
         let (sheet_id, key_cell_ids) = schema
             .get_all_key_cell_ids(ref_name, &bp_fetcher)
             .ok_or_else(unknown)?;
@@ -1119,11 +1374,15 @@ impl Workbook {
                 }
             }
             let mut row = Vec::with_capacity(fields.len());
-            for field in &fields {
+            for (field, field_type) in fields.iter().zip(field_types.iter()) {
                 let value = match schema.partially_resolve(ref_name, key_cell, field) {
                     Some(bcid) => read_display_value(status, sheet_id, &CellId::BlockCell(bcid)),
                     None => DisplayValue::Empty,
                 };
+                let value = match field_type {
+                    Some(ty) => typed_value(ty, value),
+                    None => value,
+                };
                 row.push(value);
             }
             rows.push(BlockDataRow { cells: row });
@@ -1237,7 +1496,11 @@ impl Workbook {
 /// branch and the fork (main) branch are both `Status` instances — this
 /// helper lets `get_temp_status_changes` resolve old/new values without
 /// going through `Worksheet` (which is tied to the live status only).
-fn read_display_value(status: &Status, sheet_id: SheetId, cell_id: &CellId) -> DisplayValue {
+pub(crate) fn read_display_value(
+    status: &Status,
+    sheet_id: SheetId,
+    cell_id: &CellId,
+) -> DisplayValue {
     let Some(cell) = status.container.get_cell(sheet_id, cell_id) else {
         return DisplayValue::Empty;
     };
@@ -1298,3 +1561,16 @@ fn is_conditional_formatting_payload(p: &crate::edit_action::EditPayload) -> boo
             | EditPayload::DeleteConditionalFormattingRule(_)
     )
 }
+
+fn theme_info(theme: &ThemeManager) -> Option<ThemeInfo> {
+    use logisheets_workbook::prelude::StFontScheme;
+    let font = |scheme| theme.get_font(&scheme).unwrap_or_default().to_string();
+    Some(ThemeInfo {
+        name: theme.get_name()?.to_string(),
+        colors: (0..12)
+            .map(|i| theme.get_color(i).get(2..).unwrap_or_default().to_string())
+            .collect(),
+        major_font: font(StFontScheme::Major),
+        minor_font: font(StFontScheme::Minor),
+    })
+}
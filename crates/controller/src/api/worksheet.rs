@@ -25,8 +25,9 @@ use crate::{
     connectors::NameFetcher,
     controller::{
         display::{get_default_col_width, get_default_row_height},
-        style::StyleConverter,
+        style::{Color, StyleConverter, from_hex_str},
     },
+    utils::turn_indexed_color_to_rgb,
 };
 use logisheets_base::PersonId;
 use logisheets_base::errors::BasicError;
@@ -189,6 +190,9 @@ impl<'a> Worksheet<'a> {
     }
 
     pub(crate) fn get_formula_by_id(&self, cell_id: &CellId) -> Result<String> {
+        if let Some(f) = self.data_table_formula(cell_id) {
+            return Ok(f);
+        }
         self.get_formula_with_shift_by_id(cell_id, unparse::CellShift::ZERO)
     }
 
@@ -281,11 +285,20 @@ impl<'a> Worksheet<'a> {
                 diy_cell_id,
                 validation_shadow: None,
                 conditional_format: None,
+                display_text: String::new(),
+                display_color: None,
             });
         }
         let formula = self.get_formula_by_id(cell_id)?;
         let value = self.get_value_by_id(cell_id)?;
         let style = self.get_style_by_id(cell_id)?;
+        let conditional_format = self.get_conditional_format(cell_id);
+        let formatter = match &conditional_format {
+            Some(cf) => &cf.style.formatter,
+            None => &style.formatter,
+        };
+        let (display_text, display_color) =
+            format_display_text(&value, formatter, self.get_display_width(cell_id));
         Ok(CellInfo {
             value,
             formula,
@@ -293,10 +306,41 @@ impl<'a> Worksheet<'a> {
             block_id,
             diy_cell_id,
             validation_shadow: self.get_validation_shadow(cell_id),
-            conditional_format: self.get_conditional_format(cell_id),
+            conditional_format,
+            display_text,
+            display_color,
         })
     }
 
+    /// How many characters fit in the cell: the width of its column, or of
+    /// all the visible merged columns when the cell starts a merge.
+    fn get_display_width(&self, cell_id: &CellId) -> Option<usize> {
+        let status = &self.controller.status;
+        let (_, col) = status
+            .navigator
+            .fetch_cell_idx(&self.sheet_id, cell_id)
+            .ok()?;
+        let end_col = match cell_id {
+            CellId::NormalCell(start) => status
+                .cell_attachment_manager
+                .merge_cells
+                .get_merge_cell(&self.sheet_id, start)
+                .and_then(|(_, end)| {
+                    status
+                        .navigator
+                        .fetch_normal_cell_idx(&self.sheet_id, &end)
+                        .ok()
+                })
+                .map_or(col, |(_, end_col)| end_col),
+            _ => col,
+        };
+        let width = (col..=end_col)
+            .filter(|c| !self.is_col_hidden(*c))
+            .map(|c| self.get_col_width(c).unwrap_or(0.))
+            .sum::<f64>();
+        Some(width as usize)
+    }
+
     /// The conditional formatting in effect for `cell_id`: decode the match
     /// bitmask from the cell's `ConditionalFormat` shadow, merge the matching
     /// rules' dxfs onto the cell's own style in priority order, and pick up the
@@ -334,7 +378,7 @@ impl<'a> Worksheet<'a> {
                     spec: rule_to_spec(&r.rule, dxfs),
                     preview: r.rule.dxf_id.and_then(|id| dxfs.get(id)).map(|dxf| {
                         let converter = StyleConverter {
-                            theme_manager: &self.controller.settings.theme,
+                            theme_manager: &self.controller.status.theme_manager,
                         };
                         let base = status.style_manager.get_style(0);
                         converter
@@ -347,6 +391,92 @@ impl<'a> Worksheet<'a> {
         out
     }
 
+    /// The data tables on this sheet. A table whose corner cells were
+    /// deleted is left out.
+    pub fn get_data_tables(&self) -> Vec<crate::DataTableInfo> {
+        use crate::data_table_manager::resolve::layout;
+        use crate::edit_action::InputCell;
+
+        let status = &self.controller.status;
+        let input = |p: Option<(usize, usize)>| p.map(|(row, col)| InputCell { row, col });
+        status
+            .data_table_manager
+            .tables_of_sheet(self.sheet_id)
+            .filter_map(|t| {
+                let l = layout(&status.navigator, t)?;
+                Some(crate::DataTableInfo {
+                    table_id: t.id,
+                    start_row: l.start.0,
+                    start_col: l.start.1,
+                    end_row: l.end.0,
+                    end_col: l.end.1,
+                    row_input: input(l.row_input),
+                    col_input: input(l.col_input),
+                    formula: l.formula(),
+                })
+            })
+            .collect()
+    }
+
+    /// The scenarios on this sheet, in the order they were added.
+    pub fn get_scenarios(&self) -> Vec<crate::ScenarioInfo> {
+        use crate::edit_action::ScenarioValue;
+
+        let status = &self.controller.status;
+        let Some(sheet) = status.scenario_manager.get_sheet(self.sheet_id) else {
+            return vec![];
+        };
+        sheet
+            .scenarios
+            .iter()
+            .map(|s| crate::ScenarioInfo {
+                name: s.name.clone(),
+                cells: s
+                    .cells
+                    .iter()
+                    .filter_map(|c| {
+                        let (row, col) = status
+                            .navigator
+                            .fetch_cell_idx(&self.sheet_id, &c.cell_id)
+                            .ok()?;
+                        Some(ScenarioValue {
+                            row,
+                            col,
+                            value: c.value.clone(),
+                        })
+                    })
+                    .collect(),
+                comment: s.comment.clone(),
+                user: s.user.clone(),
+                locked: s.locked,
+                hidden: s.hidden,
+                shown: sheet.shown.as_deref() == Some(s.name.as_str()),
+            })
+            .collect()
+    }
+
+    /// `TABLE(...)` when `cell_id` is a data table result.
+    fn data_table_formula(&self, cell_id: &CellId) -> Option<String> {
+        use crate::data_table_manager::resolve::table_at;
+
+        let status = &self.controller.status;
+        if status.data_table_manager.is_empty() {
+            return None;
+        }
+        let (row, col) = status
+            .navigator
+            .fetch_cell_idx(&self.sheet_id, cell_id)
+            .ok()?;
+        let (_, l) = table_at(
+            &status.navigator,
+            &status.data_table_manager,
+            self.sheet_id,
+            row,
+            col,
+        )?;
+        Some(l.formula())
+    }
+
     fn get_conditional_format(&self, cell_id: &CellId) -> Option<crate::ConditionalFormat> {
         use crate::conditional_formatting_manager::query::{
             color_scale_at, data_bar_at, icon_at, matched_rules, rules_for_cell,
@@ -428,7 +558,7 @@ impl<'a> Worksheet<'a> {
                     data_bar = data_bar_at(v.rule, scale).map(|(color, fraction, show_value)| {
                         crate::CfDataBar {
                             color: StyleConverter {
-                                theme_manager: &self.controller.settings.theme,
+                                theme_manager: &self.controller.status.theme_manager,
                             }
                             .convert_color_pub(color),
                             fraction,
@@ -451,7 +581,7 @@ impl<'a> Worksheet<'a> {
         }
 
         let style_converter = StyleConverter {
-            theme_manager: &self.controller.settings.theme,
+            theme_manager: &self.controller.status.theme_manager,
         };
         Some(crate::ConditionalFormat {
             style: style_converter.convert_style(raw),
@@ -538,7 +668,7 @@ impl<'a> Worksheet<'a> {
                     "folHlink" => 11,
                     _ => return None,
                 };
-                let c = self.controller.settings.theme.get_color(idx);
+                let c = self.controller.status.theme_manager.get_color(idx);
                 if c.is_empty() { None } else { Some(c) }
             }
         }
@@ -1332,7 +1462,7 @@ impl<'a> Worksheet<'a> {
         0
     }
 
-    fn get_raw_style_by_id(&self, cell_id: &CellId) -> Result<RawStyle> {
+    pub(crate) fn get_raw_style_by_id(&self, cell_id: &CellId) -> Result<RawStyle> {
         let style_id = self.get_applicable_raw_style_id(cell_id);
         let raw_style = self.controller.status.style_manager.get_style(style_id);
         Ok(raw_style)
@@ -1341,7 +1471,7 @@ impl<'a> Worksheet<'a> {
     pub(crate) fn get_style_by_id(&self, cell_id: &CellId) -> Result<Style> {
         let raw_style = self.get_raw_style_by_id(cell_id)?;
         let style_converter = StyleConverter {
-            theme_manager: &self.controller.settings.theme,
+            theme_manager: &self.controller.status.theme_manager,
         };
         Ok(style_converter.convert_style(raw_style))
     }
@@ -1355,6 +1485,22 @@ impl<'a> Worksheet<'a> {
         self.get_style_by_id(&cell_id)
     }
 
+    /// The named cell style the cell is linked to, if any.
+    pub fn get_cell_style_name(&self, row: usize, col: usize) -> Result<Option<String>> {
+        let cell_id = self
+            .controller
+            .status
+            .navigator
+            .fetch_cell_id(&self.sheet_id, row, col)?;
+        let style_id = self.get_applicable_raw_style_id(&cell_id);
+        Ok(self
+            .controller
+            .status
+            .style_manager
+            .get_named_style_name(style_id)
+            .map(String::from))
+    }
+
     pub fn get_all_merged_cells(&self) -> Vec<MergeCell> {
         let merges = self
             .controller
@@ -1648,6 +1794,7 @@ impl<'a> Worksheet<'a> {
                                             value_formula: e.value_formula.clone(),
                                             validation_formula: e.validation_formula.clone(),
                                             editability_formula: e.editability_formula.clone(),
+                                            field_type: e.field_type.clone(),
                                         }
                                     })
                                     .collect::<Vec<_>>();
@@ -1685,6 +1832,7 @@ impl<'a> Worksheet<'a> {
                                             value_formula: e.value_formula.clone(),
                                             validation_formula: e.validation_formula.clone(),
                                             editability_formula: e.editability_formula.clone(),
+                                            field_type: e.field_type.clone(),
                                         }
                                     })
                                     .collect::<Vec<_>>();
@@ -1755,7 +1903,7 @@ impl<'a> Worksheet<'a> {
                                     let raw_style =
                                         self.controller.status.style_manager.get_style(sid);
                                     let style_converter = StyleConverter {
-                                        theme_manager: &self.controller.settings.theme,
+                                        theme_manager: &self.controller.status.theme_manager,
                                     };
                                     style_converter.convert_style(raw_style)
                                 });
@@ -2432,6 +2580,7 @@ impl<'a> Worksheet<'a> {
                                     value_formula: e.value_formula.clone(),
                                     validation_formula: e.validation_formula.clone(),
                                     editability_formula: e.editability_formula.clone(),
+                                    field_type: e.field_type.clone(),
                                 }
                             })
                             .collect::<Vec<_>>();
@@ -2469,6 +2618,7 @@ impl<'a> Worksheet<'a> {
                                     value_formula: e.value_formula.clone(),
                                     validation_formula: e.validation_formula.clone(),
                                     editability_formula: e.editability_formula.clone(),
+                                    field_type: e.field_type.clone(),
                                 }
                             })
                             .collect::<Vec<_>>();
@@ -2531,7 +2681,7 @@ impl<'a> Worksheet<'a> {
                         let style = info.style.map(|sid| {
                             let raw_style = self.controller.status.style_manager.get_style(sid);
                             let style_converter = StyleConverter {
-                                theme_manager: &self.controller.settings.theme,
+                                theme_manager: &self.controller.status.theme_manager,
                             };
                             style_converter.convert_style(raw_style)
                         });
@@ -2726,6 +2876,34 @@ fn build_comment_note(comments: &InternalComments, note: &InternalNote) -> Comme
     }
 }
 
+/// Render `value` with `formatter` the way a grid cell shows it. Booleans and
+/// errors ignore the number format; a format `ssf_rs` rejects falls back to
+/// General.
+pub(crate) fn format_display_text(
+    value: &Value,
+    formatter: &str,
+    width: Option<usize>,
+) -> (String, Option<Color>) {
+    let v = match value {
+        Value::Empty => return (String::new(), None),
+        Value::Bool(b) => return (String::from(if *b { "TRUE" } else { "FALSE" }), None),
+        Value::Error(e) => return (e.clone(), None),
+        Value::Number(n) => ssf_rs::Value::Num(*n),
+        Value::Str(s) => ssf_rs::Value::Text(s.clone()),
+    };
+    let cell = ssf_rs::format_cell(formatter, &v, width, false)
+        .or_else(|_| ssf_rs::format_cell("General", &v, width, false));
+    match cell {
+        Ok(cell) => {
+            let color = cell
+                .color
+                .map(|n| from_hex_str(turn_indexed_color_to_rgb(n as u32 + 7), 0.));
+            (cell.text, color)
+        }
+        Err(_) => (String::new(), None),
+    }
+}
+
 #[cfg(test)]
 mod boundary_tests {
     use super::boundary_1d;
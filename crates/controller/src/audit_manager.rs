@@ -0,0 +1,342 @@
+//! Per-cell change history.
+//!
+//! Off by default. Once enabled, every committed transaction becomes an
+//! entry of the log: who made it, when, which payloads it ran, and each
+//! cell whose value or formula it changed, old and new. Undo and redo are
+//! entries too, without cells.
+//!
+//! The host owns identity and clock. It stamps the log with an author (the
+//! same identity model comment authors use) and a timestamp, and every
+//! commit is recorded with the latest stamp.
+//!
+//! The log lives beside `VersionManager`, not inside `Status`: undoing an
+//! edit must not erase the record that it happened. It is written into the
+//! file only when asked to.
+
+use std::collections::HashMap;
+
+use logisheets_base::{CellId, PersonId, SheetId};
+use logisheets_workbook::logisheets::{AuditChangeXml, AuditEntryXml, AuditPersonXml, AuditXml};
+
+use crate::api::read_display_value;
+use crate::cell_attachments::comment::{PersonInput, PersonManager};
+use crate::controller::display::{CellChange, ChangeEntry, CommentPerson, Value};
+use crate::controller::status::Status;
+use crate::edit_action::{AuthorInput, EditPayload};
+
+#[derive(Debug, Clone)]
+struct AuditChange {
+    /// The cell the change belongs to, `None` once the cell is gone
+    /// (after a reload that found it deleted).
+    cell: Option<(SheetId, CellId)>,
+    change: CellChange,
+}
+
+#[derive(Debug, Clone)]
+struct AuditEntry {
+    author: Option<PersonId>,
+    timestamp: Option<String>,
+    payloads: Vec<String>,
+    changes: Vec<AuditChange>,
+}
+
+#[derive(Debug, Default)]
+pub struct AuditManager {
+    enabled: bool,
+    /// Write the log into the file on save.
+    persist: bool,
+    author: Option<PersonId>,
+    timestamp: Option<String>,
+    persons: PersonManager,
+    entries: Vec<AuditEntry>,
+}
+
+impl AuditManager {
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Start or stop recording. Stopping keeps what was recorded.
+    pub fn set_enabled(&mut self, enabled: bool, persist: bool) {
+        self.enabled = enabled;
+        self.persist = persist;
+    }
+
+    /// The author and timestamp the following commits are recorded with.
+    pub fn stamp(&mut self, author: AuthorInput, timestamp: String) {
+        let person = self.persons.get_or_register(PersonInput {
+            display_name: author.display_name,
+            user_id: author.user_id,
+            provider_id: author.provider_id,
+        });
+        self.author = Some(person);
+        self.timestamp = Some(timestamp);
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Record a committed transaction that turned `old` into `new`. `cells`
+    /// are the cells it may have changed; those whose value and formula
+    /// are both unchanged are left out.
+    pub fn record<'a, I>(&mut self, payloads: Vec<String>, old: &Status, new: &Status, cells: I)
+    where
+        I: IntoIterator<Item = &'a (SheetId, CellId)>,
+    {
+        let mut changes = vec![];
+        for (sheet_id, cell_id) in cells {
+            if matches!(cell_id, CellId::EphemeralCell(_)) {
+                continue;
+            }
+            let old_value = read_display_value(old, *sheet_id, cell_id);
+            let new_value = read_display_value(new, *sheet_id, cell_id);
+            let old_formula = old.formula_text(*sheet_id, cell_id);
+            let new_formula = new.formula_text(*sheet_id, cell_id);
+            if old_value == new_value && old_formula == new_formula {
+                continue;
+            }
+            let Some((status, (row, col))) = [new, old].into_iter().find_map(|s| {
+                s.navigator
+                    .fetch_cell_idx(sheet_id, cell_id)
+                    .ok()
+                    .map(|pos| (s, pos))
+            }) else {
+                continue;
+            };
+            let recalculated = !new_formula.is_empty() && old_formula == new_formula;
+            changes.push(AuditChange {
+                cell: Some((*sheet_id, *cell_id)),
+                change: CellChange {
+                    sheet_name: status
+                        .sheet_id_manager
+                        .get_string(sheet_id)
+                        .unwrap_or_default(),
+                    row,
+                    col,
+                    old_value,
+                    new_value,
+                    old_formula,
+                    new_formula,
+                    recalculated,
+                },
+            });
+        }
+        changes.sort_by(|a, b| {
+            let (a, b) = (&a.change, &b.change);
+            (&a.sheet_name, a.row, a.col).cmp(&(&b.sheet_name, b.row, b.col))
+        });
+        self.entries.push(AuditEntry {
+            author: self.author,
+            timestamp: self.timestamp.clone(),
+            payloads,
+            changes,
+        });
+    }
+
+    /// Undo and redo swap whole states, so they are logged without cells.
+    pub fn record_undo_redo(&mut self, name: &str) {
+        self.entries.push(AuditEntry {
+            author: self.author,
+            timestamp: self.timestamp.clone(),
+            payloads: vec![name.to_string()],
+            changes: vec![],
+        });
+    }
+
+    /// The entries from `since` on, oldest first.
+    pub fn feed(&self, since: usize) -> Vec<ChangeEntry> {
+        self.entries
+            .iter()
+            .enumerate()
+            .skip(since)
+            .map(|(seq, e)| self.to_change_entry(seq, e, e.changes.iter()))
+            .collect()
+    }
+
+    /// The entries that changed the cell, oldest first, each with just
+    /// that cell's change.
+    pub fn cell_history(&self, sheet_id: SheetId, cell_id: CellId) -> Vec<ChangeEntry> {
+        let cell = Some((sheet_id, cell_id));
+        self.entries
+            .iter()
+            .enumerate()
+            .filter_map(|(seq, e)| {
+                let change = e.changes.iter().find(|c| c.cell == cell)?;
+                Some(self.to_change_entry(seq, e, std::iter::once(change)))
+            })
+            .collect()
+    }
+
+    fn to_change_entry<'a, I>(&self, seq: usize, entry: &AuditEntry, changes: I) -> ChangeEntry
+    where
+        I: Iterator<Item = &'a AuditChange>,
+    {
+        ChangeEntry {
+            seq,
+            author: entry
+                .author
+                .and_then(|id| self.persons.get(&id))
+                .map(|p| CommentPerson {
+                    display_name: p.display_name.clone(),
+                    user_id: p.user_id.clone(),
+                    provider_id: p.provider_id.clone(),
+                }),
+            timestamp: entry.timestamp.clone(),
+            payloads: entry.payloads.clone(),
+            changes: changes.map(|c| c.change.clone()).collect(),
+        }
+    }
+
+    /// The log as it goes into the file, or `None` when it is not to be
+    /// kept there. Positions are those the cells have in `current`.
+    pub fn to_xml(&self, current: &Status) -> Option<AuditXml> {
+        if !self.persist || self.entries.is_empty() {
+            return None;
+        }
+        let persons = self
+            .persons
+            .iter()
+            .map(|(_, p)| AuditPersonXml {
+                guid: p.guid.clone(),
+                display_name: p.display_name.clone(),
+                user_id: p.user_id.clone(),
+                provider_id: p.provider_id.clone(),
+            })
+            .collect();
+        let entries = self
+            .entries
+            .iter()
+            .map(|e| AuditEntryXml {
+                author: e
+                    .author
+                    .and_then(|id| self.persons.get(&id))
+                    .map(|p| p.guid.clone()),
+                timestamp: e.timestamp.clone(),
+                payloads: Some(e.payloads.join(" ")).filter(|p| !p.is_empty()),
+                changes: e
+                    .changes
+                    .iter()
+                    .map(|c| change_to_xml(c, current))
+                    .collect(),
+            })
+            .collect();
+        Some(AuditXml { persons, entries })
+    }
+
+    /// Take the log read from a file. `status` is the workbook loaded from
+    /// it, where the recorded cells are looked up again.
+    pub fn load(&mut self, audit: AuditXml, status: &Status) {
+        let mut guids = HashMap::new();
+        for p in audit.persons {
+            let id = self.persons.register_with_guid(
+                p.guid.clone(),
+                PersonInput {
+                    display_name: p.display_name,
+                    user_id: p.user_id,
+                    provider_id: p.provider_id,
+                },
+            );
+            guids.insert(p.guid, id);
+        }
+        self.entries = audit
+            .entries
+            .into_iter()
+            .map(|e| AuditEntry {
+                author: e.author.and_then(|g| guids.get(&g).copied()),
+                timestamp: e.timestamp,
+                payloads: e
+                    .payloads
+                    .map(|p| p.split(' ').map(String::from).collect())
+                    .unwrap_or_default(),
+                changes: e
+                    .changes
+                    .into_iter()
+                    .map(|c| change_from_xml(c, status))
+                    .collect(),
+            })
+            .collect();
+        self.enabled = true;
+        self.persist = true;
+    }
+}
+
+/// The variant name of each payload, `CellInput` for a cell input.
+pub fn payload_names(payloads: &[EditPayload]) -> Vec<String> {
+    payloads.iter().map(|p| p.name().to_string()).collect()
+}
+
+fn change_to_xml(c: &AuditChange, current: &Status) -> AuditChangeXml {
+    let change = &c.change;
+    let now = c.cell.and_then(|(sheet_id, cell_id)| {
+        let pos = current.navigator.fetch_cell_idx(&sheet_id, &cell_id).ok()?;
+        let name = current.sheet_id_manager.get_string(&sheet_id)?;
+        Some((name, pos))
+    });
+    let live = now.is_some();
+    let (sheet, (row, col)) = now.unwrap_or((change.sheet_name.clone(), (change.row, change.col)));
+    let (old_type, old_value) = value_to_xml(&change.old_value);
+    let (new_type, new_value) = value_to_xml(&change.new_value);
+    AuditChangeXml {
+        sheet,
+        row,
+        col,
+        live: if live { None } else { Some(false) },
+        old_type,
+        old_value,
+        new_type,
+        new_value,
+        old_formula: Some(change.old_formula.clone()).filter(|f| !f.is_empty()),
+        new_formula: Some(change.new_formula.clone()).filter(|f| !f.is_empty()),
+        recalculated: change.recalculated.then_some(true),
+    }
+}
+
+fn change_from_xml(c: AuditChangeXml, status: &Status) -> AuditChange {
+    let cell = if c.live == Some(false) {
+        None
+    } else {
+        status.sheet_id_manager.has(&c.sheet).and_then(|sheet_id| {
+            let cell_id = status
+                .navigator
+                .fetch_cell_id(&sheet_id, c.row, c.col)
+                .ok()?;
+            Some((sheet_id, cell_id))
+        })
+    };
+    AuditChange {
+        cell,
+        change: CellChange {
+            sheet_name: c.sheet,
+            row: c.row,
+            col: c.col,
+            old_value: value_from_xml(c.old_type, c.old_value),
+            new_value: value_from_xml(c.new_type, c.new_value),
+            old_formula: c.old_formula.unwrap_or_default(),
+            new_formula: c.new_formula.unwrap_or_default(),
+            recalculated: c.recalculated.unwrap_or(false),
+        },
+    }
+}
+
+fn value_to_xml(v: &Value) -> (Option<String>, Option<String>) {
+    let (t, v) = match v {
+        Value::Empty => return (None, None),
+        Value::Str(s) => ("s", s.clone()),
+        Value::Number(n) => ("n", n.to_string()),
+        Value::Bool(b) => ("b", if *b { "1" } else { "0" }.to_string()),
+        Value::Error(e) => ("e", e.clone()),
+    };
+    (Some(t.to_string()), Some(v))
+}
+
+fn value_from_xml(t: Option<String>, v: Option<String>) -> Value {
+    let v = v.unwrap_or_default();
+    match t.as_deref() {
+        Some("s") => Value::Str(v),
+        Some("n") => v.parse().map(Value::Number).unwrap_or(Value::Empty),
+        Some("b") => Value::Bool(v == "1"),
+        Some("e") => Value::Error(v),
+        _ => Value::Empty,
+    }
+}
@@ -6,6 +6,7 @@ use crate::{
     Error,
     block_manager::schema_manager::{
         ctx::BlockSchemaCtx,
+        field_type::check_field_type,
         manager::SchemaManager,
         schema::{ColSchema, FieldEntry, RandomSchema, RowSchema, Schema, SchemaTrait},
     },
@@ -163,6 +164,27 @@ impl BlockSchemaExecutor {
                     "editability_formulas",
                 )?;
 
+                let field_types = p.field_types.unwrap_or_default();
+                if !field_types.is_empty() && field_types.len() != p.fields.len() {
+                    return Err(Error::PayloadError(format!(
+                        "BindFormSchema: field type vec length mismatch (fields={}, types={})",
+                        p.fields.len(),
+                        field_types.len()
+                    )));
+                }
+                let mut patterns = Vec::with_capacity(field_types.len());
+                for (i, ty) in field_types.iter().enumerate() {
+                    let pattern = match ty {
+                        Some(ty) => check_field_type(ty).map_err(|e| {
+                            Error::PayloadError(format!("field_types[{}]: {}", i, e))
+                        })?,
+                        None => None,
+                    };
+                    patterns.push(pattern);
+                }
+                let mut type_iter = field_types.into_iter();
+                let mut pattern_iter = patterns.into_iter();
+
                 let mut value_iter = normalize_formula_vec(field_formulas).into_iter();
                 let mut validation_iter = normalize_formula_vec(validation_formulas).into_iter();
                 let mut editability_iter = normalize_formula_vec(editability_formulas).into_iter();
@@ -191,7 +213,9 @@ impl BlockSchemaExecutor {
                     let entry = FieldEntry::new(id, render_id)
                         .with_value_formula(value_iter.next().flatten())
                         .with_validation_formula(validation_iter.next().flatten())
-                        .with_editability_formula(editability_iter.next().flatten());
+                        .with_editability_formula(editability_iter.next().flatten())
+                        .with_field_type(type_iter.next().flatten())
+                        .with_pattern(pattern_iter.next().flatten());
                     fields.push((field, entry));
                 }
                 let schema = if p.row {
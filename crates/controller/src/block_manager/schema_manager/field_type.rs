@@ -0,0 +1,284 @@
+//! Typed form-schema fields.
+//!
+//! A field bound with a [`FieldType`] only takes input of that type: the
+//! container executor reads each `BlockInput` through [`parse_input`] and
+//! refuses the payload when it does not fit. Formulas (`=…`) are not
+//! checked, their results are whatever they compute. `export_block_data`
+//! goes through [`typed_value`] so a host gets dates as `yyyy-mm-dd` and text
+//! fields as strings even when the cell holds a number.
+
+use chrono::{Datelike, NaiveDate};
+use logisheets_base::datetime::{
+    MAX_SERIAL_NUM_1900, get_date_by_serial_num_1900, get_serial_num_by_date_1900,
+};
+use logisheets_base::{CellValue, TextId};
+use regex::Regex;
+
+use crate::controller::display::Value;
+use crate::edit_action::{FieldKind, FieldType};
+
+/// A `BlockInput` read as a value of its field's type.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TypedInput {
+    Blank,
+    Number(f64),
+    Boolean(bool),
+    Text(String),
+}
+
+impl TypedInput {
+    pub fn into_cell_value<F>(self, text_id_fetcher: &mut F) -> CellValue
+    where
+        F: FnMut(&str) -> TextId,
+    {
+        match self {
+            TypedInput::Blank => CellValue::Blank,
+            TypedInput::Number(n) => CellValue::Number(n),
+            TypedInput::Boolean(b) => CellValue::Boolean(b),
+            TypedInput::Text(t) => CellValue::String(text_id_fetcher(&t)),
+        }
+    }
+}
+
+/// Check that a field type can be enforced at all. Run when the schema is
+/// bound, so a bad pattern fails there rather than on every input. Returns
+/// the compiled pattern of a text field, which the schema keeps for
+/// [`parse_input`].
+pub fn check_field_type(ty: &FieldType) -> Result<Option<Regex>, String> {
+    if let (Some(min), Some(max)) = (ty.min, ty.max)
+        && min > max
+    {
+        return Err(format!("min {} is greater than max {}", min, max));
+    }
+    match ty.kind {
+        FieldKind::Enum if ty.options.is_empty() => Err("an enum needs options".to_string()),
+        FieldKind::Reference if ty.ref_name.is_none() => {
+            Err("a reference needs the ref name of its block".to_string())
+        }
+        FieldKind::Text => ty.pattern.as_deref().map(anchored).transpose(),
+        _ => Ok(None),
+    }
+}
+
+/// Read `input` as a value of type `ty`, whose pattern was compiled by
+/// [`check_field_type`]. Whether a reference names an existing key is left
+/// to the caller, which can see the other block.
+pub fn parse_input(
+    ty: &FieldType,
+    pattern: Option<&Regex>,
+    input: &str,
+) -> Result<TypedInput, String> {
+    let trimmed = input.trim();
+    if trimmed.is_empty() {
+        return if ty.required {
+            Err("a value is required".to_string())
+        } else {
+            Ok(TypedInput::Blank)
+        };
+    }
+    match ty.kind {
+        FieldKind::Number => {
+            let n = parse_number(trimmed)?;
+            check_bounds(ty, n, trimmed)?;
+            Ok(TypedInput::Number(n))
+        }
+        FieldKind::Integer => {
+            let n = parse_number(trimmed)?;
+            if n.fract() != 0.0 {
+                return Err(format!("{:?} is not an integer", trimmed));
+            }
+            check_bounds(ty, n, trimmed)?;
+            Ok(TypedInput::Number(n))
+        }
+        FieldKind::Date => {
+            let serial = match NaiveDate::parse_from_str(trimmed, "%Y-%m-%d") {
+                Ok(date) => u32::try_from(date.year())
+                    .ok()
+                    .filter(|y| *y >= 1900)
+                    .and_then(|y| get_serial_num_by_date_1900(y, date.month(), date.day()))
+                    .map(f64::from)
+                    .ok_or_else(|| format!("{:?} is before 1900", trimmed))?,
+                Err(_) => parse_number(trimmed)
+                    .map_err(|_| format!("{:?} is not a yyyy-mm-dd date", trimmed))?,
+            };
+            check_bounds(ty, serial, trimmed)?;
+            Ok(TypedInput::Number(serial))
+        }
+        FieldKind::Boolean => {
+            if trimmed.eq_ignore_ascii_case("TRUE") {
+                Ok(TypedInput::Boolean(true))
+            } else if trimmed.eq_ignore_ascii_case("FALSE") {
+                Ok(TypedInput::Boolean(false))
+            } else {
+                Err(format!("{:?} is not TRUE or FALSE", trimmed))
+            }
+        }
+        FieldKind::Enum => {
+            if ty.options.iter().any(|o| o == trimmed) {
+                Ok(TypedInput::Text(trimmed.to_string()))
+            } else {
+                Err(format!("{:?} is not one of {:?}", trimmed, ty.options))
+            }
+        }
+        FieldKind::Text => {
+            if let Some(max) = ty.max_length
+                && input.chars().count() > max
+            {
+                return Err(format!("{:?} is longer than {} characters", input, max));
+            }
+            if let Some(p) = pattern
+                && !p.is_match(input)
+            {
+                let source = ty.pattern.as_deref().unwrap_or_default();
+                return Err(format!("{:?} does not match {:?}", input, source));
+            }
+            Ok(TypedInput::Text(input.to_string()))
+        }
+        FieldKind::Reference => Ok(TypedInput::Text(trimmed.to_string())),
+    }
+}
+
+/// The value of a cell in a field of type `ty`, as `export_block_data`
+/// hands it out. Values that do not fit the type (a formula's result, or
+/// data entered before the type was bound) are left as they are.
+pub fn typed_value(ty: &FieldType, value: Value) -> Value {
+    match (ty.kind, value) {
+        (FieldKind::Date, Value::Number(n))
+            if (1.0..=MAX_SERIAL_NUM_1900 as f64).contains(&n.floor()) =>
+        {
+            let date = get_date_by_serial_num_1900(n.floor() as u32);
+            Value::Str(format!(
+                "{:04}-{:02}-{:02}",
+                date.year, date.month, date.day
+            ))
+        }
+        (FieldKind::Text | FieldKind::Enum | FieldKind::Reference, Value::Number(n)) => {
+            Value::Str(n.to_string())
+        }
+        (FieldKind::Text | FieldKind::Enum | FieldKind::Reference, Value::Bool(b)) => {
+            Value::Str(if b { "TRUE" } else { "FALSE" }.to_string())
+        }
+        (FieldKind::Number | FieldKind::Integer, Value::Str(s)) => match s.trim().parse() {
+            Ok(n) => Value::Number(n),
+            Err(_) => Value::Str(s),
+        },
+        (_, value) => value,
+    }
+}
+
+fn parse_number(s: &str) -> Result<f64, String> {
+    s.parse::<f64>()
+        .ok()
+        .filter(|n| n.is_finite())
+        .ok_or_else(|| format!("{:?} is not a number", s))
+}
+
+fn check_bounds(ty: &FieldType, n: f64, input: &str) -> Result<(), String> {
+    if ty.min.is_some_and(|min| n < min) || ty.max.is_some_and(|max| n > max) {
+        return Err(format!(
+            "{:?} is out of range [{}, {}]",
+            input,
+            ty.min.map_or("-".to_string(), |m| m.to_string()),
+            ty.max.map_or("-".to_string(), |m| m.to_string()),
+        ));
+    }
+    Ok(())
+}
+
+/// The pattern must match the whole text.
+fn anchored(pattern: &str) -> Result<Regex, String> {
+    Regex::new(&format!("^(?:{})$", pattern))
+        .map_err(|e| format!("invalid pattern {:?}: {}", pattern, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ty(kind: FieldKind) -> FieldType {
+        FieldType {
+            kind,
+            required: false,
+            min: None,
+            max: None,
+            options: vec![],
+            pattern: None,
+            max_length: None,
+            ref_name: None,
+        }
+    }
+
+    fn parse(ty: &FieldType, input: &str) -> Result<TypedInput, String> {
+        parse_input(ty, check_field_type(ty).unwrap().as_ref(), input)
+    }
+
+    #[test]
+    fn numbers_and_bounds() {
+        let t = FieldType {
+            min: Some(0.0),
+            max: Some(10.0),
+            ..ty(FieldKind::Integer)
+        };
+        assert_eq!(parse(&t, " 7 "), Ok(TypedInput::Number(7.0)));
+        assert!(parse(&t, "7.5").is_err());
+        assert!(parse(&t, "11").is_err());
+        assert!(parse(&t, "abc").is_err());
+        assert_eq!(parse(&t, ""), Ok(TypedInput::Blank));
+        let required = FieldType {
+            required: true,
+            ..t
+        };
+        assert!(parse(&required, "  ").is_err());
+    }
+
+    #[test]
+    fn dates_round_trip_through_serials() {
+        let t = ty(FieldKind::Date);
+        assert_eq!(parse(&t, "2024-03-01"), Ok(TypedInput::Number(45352.0)));
+        assert_eq!(parse(&t, "45352"), Ok(TypedInput::Number(45352.0)));
+        assert!(parse(&t, "March 1st").is_err());
+        assert!(matches!(
+            typed_value(&t, Value::Number(45352.0)),
+            Value::Str(s) if s == "2024-03-01"
+        ));
+    }
+
+    #[test]
+    fn text_enum_and_boolean() {
+        let t = FieldType {
+            pattern: Some("[A-Z]{3}-\\d+".to_string()),
+            max_length: Some(8),
+            ..ty(FieldKind::Text)
+        };
+        assert_eq!(
+            parse(&t, "ABC-12"),
+            Ok(TypedInput::Text("ABC-12".to_string()))
+        );
+        assert!(parse(&t, "xABC-12").is_err());
+        assert!(parse(&t, "ABC-123456").is_err());
+        assert!(
+            check_field_type(&FieldType {
+                pattern: Some("(".to_string()),
+                ..ty(FieldKind::Text)
+            })
+            .is_err()
+        );
+
+        let e = FieldType {
+            options: vec!["open".to_string(), "closed".to_string()],
+            ..ty(FieldKind::Enum)
+        };
+        assert!(parse(&e, "open").is_ok());
+        assert!(parse(&e, "Open").is_err());
+        assert!(check_field_type(&ty(FieldKind::Enum)).is_err());
+
+        let b = ty(FieldKind::Boolean);
+        assert_eq!(parse(&b, "true"), Ok(TypedInput::Boolean(true)));
+        assert!(parse(&b, "yes").is_err());
+
+        assert!(matches!(
+            typed_value(&ty(FieldKind::Text), Value::Number(123.0)),
+            Value::Str(s) if s == "123"
+        ));
+    }
+}
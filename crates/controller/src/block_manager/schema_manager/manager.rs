@@ -1,6 +1,8 @@
 use imbl::HashMap;
 use logisheets_base::{BlockCellId, BlockFieldId, BlockId, SheetId};
+use regex::Regex;
 
+use crate::edit_action::FieldType;
 use crate::navigator::BlockPlace;
 
 use super::schema::{BlockCellRole, Field, RenderId, Schema, SchemaTrait};
@@ -194,6 +196,37 @@ impl SchemaManager {
         }
     }
 
+    /// Look up the type of the field `field` of the block bound as
+    /// `ref_name`, if it has one.
+    pub fn get_field_type(&self, ref_name: &str, field: &str) -> Option<FieldType> {
+        let (sheet_id, block_id) = self.refs.get(ref_name)?;
+        let fields = match self.schemas.get(&(*sheet_id, *block_id))? {
+            Schema::RowSchema(s) => s.fields.iter().map(|(n, e)| (n, &e.field_type)).collect(),
+            Schema::ColSchema(s) => s.fields.iter().map(|(n, e)| (n, &e.field_type)).collect(),
+            Schema::RandomSchema(_) => Vec::new(),
+        };
+        fields
+            .into_iter()
+            .find(|(n, _)| *n == field)
+            .and_then(|(_, t)| t.clone())
+    }
+
+    /// Look up the type of a cell's field and its compiled pattern, if it
+    /// has one.
+    pub fn field_type_for_block_cell(
+        &self,
+        sheet_id: SheetId,
+        cell: &BlockCellId,
+    ) -> Option<(FieldType, Option<Regex>)> {
+        let schema = self.schemas.get(&(sheet_id, cell.block_id))?;
+        let (ty, pattern) = match schema {
+            Schema::RowSchema(s) => s.field_type_for_field_axis(cell.col)?,
+            Schema::ColSchema(s) => s.field_type_for_field_axis(cell.row)?,
+            Schema::RandomSchema(_) => return None,
+        };
+        Some((ty.clone(), pattern.cloned()))
+    }
+
     /// For a templated block cell, return the per-field sibling
     /// `BlockCellId`s in the same row, keyed by field name. Used to
     /// build the `#FIELD("name") → Reference` substitution map at
@@ -259,4 +292,30 @@ impl SchemaManager {
             Schema::RandomSchema(_) => return None,
         })
     }
+
+    /// Copy the schemas of sheet `from` to sheet `to`. Ref names are
+    /// workbook-unique, so each copy is bound as `<name>_2`, `<name>_3`, ...
+    /// whichever is free first.
+    pub fn duplicate_sheet(&mut self, from: SheetId, to: SheetId) {
+        let copies: Vec<(BlockId, Schema)> = self
+            .schemas
+            .iter()
+            .filter(|((s, _), _)| *s == from)
+            .map(|((_, b), schema)| (*b, schema.clone()))
+            .collect();
+        for (block_id, mut schema) in copies {
+            let base = schema.get_ref_name();
+            let name = (2..)
+                .map(|n| format!("{}_{}", base, n))
+                .find(|n| !self.refs.contains_key(n))
+                .unwrap();
+            match &mut schema {
+                Schema::RowSchema(s) => s.name = name.clone(),
+                Schema::ColSchema(s) => s.name = name.clone(),
+                Schema::RandomSchema(s) => s.name = name.clone(),
+            }
+            self.schemas.insert((to, block_id), schema);
+            self.refs.insert(name, (to, block_id));
+        }
+    }
 }
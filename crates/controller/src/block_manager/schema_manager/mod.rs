@@ -1,7 +1,9 @@
 pub mod ctx;
 pub mod executor;
+pub mod field_type;
 mod manager;
 pub mod persistence;
+pub mod records;
 pub mod schema;
 
 pub use manager::SchemaManager;
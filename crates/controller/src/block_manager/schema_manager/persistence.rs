@@ -11,11 +11,15 @@
 
 use logisheets_base::{ColId, RowId, SheetId};
 use logisheets_workbook::logisheets::{
-    ColSchemaXml, RandomKeyFieldXml, RandomSchemaXml, RowSchemaXml, SchemaFieldXml,
+    ColSchemaXml, FieldOptionXml, FieldTypeXml, RandomKeyFieldXml, RandomSchemaXml, RowSchemaXml,
+    SchemaFieldXml,
 };
+use regex::Regex;
 
 use super::SchemaManager;
+use super::field_type::check_field_type;
 use super::schema::{ColSchema, FieldEntry, RandomSchema, RowSchema, Schema};
+use crate::edit_action::{FieldKind, FieldType};
 
 /// Pull every schema bound to `sheet_id` out of `manager` and project it
 /// into the three xmlserde-friendly vecs that the workbook's `Sheet` carries.
@@ -48,6 +52,7 @@ pub fn schemas_to_xml(
                         value_formula: entry.value_formula.clone(),
                         validation_formula: entry.validation_formula.clone(),
                         editability_formula: entry.editability_formula.clone(),
+                        field_type: entry.field_type.as_ref().map(field_type_to_xml),
                     })
                     .collect(),
             }),
@@ -65,6 +70,7 @@ pub fn schemas_to_xml(
                         value_formula: entry.value_formula.clone(),
                         validation_formula: entry.validation_formula.clone(),
                         editability_formula: entry.editability_formula.clone(),
+                        field_type: entry.field_type.as_ref().map(field_type_to_xml),
                     })
                     .collect(),
             }),
@@ -106,12 +112,16 @@ pub fn load_schemas_for_sheet(
                 .fields
                 .into_iter()
                 .map(|f| {
+                    let field_type = f.field_type.and_then(field_type_from_xml);
+                    let pattern = field_type.as_ref().and_then(compile_pattern);
                     (
                         f.name,
                         FieldEntry::new(f.axis_id as ColId, f.render_id)
                             .with_value_formula(f.value_formula)
                             .with_validation_formula(f.validation_formula)
-                            .with_editability_formula(f.editability_formula),
+                            .with_editability_formula(f.editability_formula)
+                            .with_field_type(field_type)
+                            .with_pattern(pattern),
                     )
                 })
                 .collect(),
@@ -132,12 +142,16 @@ pub fn load_schemas_for_sheet(
                 .fields
                 .into_iter()
                 .map(|f| {
+                    let field_type = f.field_type.and_then(field_type_from_xml);
+                    let pattern = field_type.as_ref().and_then(compile_pattern);
                     (
                         f.name,
                         FieldEntry::new(f.axis_id as RowId, f.render_id)
                             .with_value_formula(f.value_formula)
                             .with_validation_formula(f.validation_formula)
-                            .with_editability_formula(f.editability_formula),
+                            .with_editability_formula(f.editability_formula)
+                            .with_field_type(field_type)
+                            .with_pattern(pattern),
                     )
                 })
                 .collect(),
@@ -168,6 +182,64 @@ pub fn load_schemas_for_sheet(
     }
 }
 
+fn field_type_to_xml(t: &FieldType) -> FieldTypeXml {
+    let kind = match t.kind {
+        FieldKind::Text => "text",
+        FieldKind::Number => "number",
+        FieldKind::Integer => "integer",
+        FieldKind::Date => "date",
+        FieldKind::Boolean => "boolean",
+        FieldKind::Enum => "enum",
+        FieldKind::Reference => "reference",
+    };
+    FieldTypeXml {
+        kind: kind.to_string(),
+        required: t.required.then_some(true),
+        min: t.min,
+        max: t.max,
+        pattern: t.pattern.clone(),
+        max_length: t.max_length,
+        ref_name: t.ref_name.clone(),
+        options: t
+            .options
+            .iter()
+            .map(|o| FieldOptionXml { val: o.clone() })
+            .collect(),
+    }
+}
+
+/// Inverse of [`field_type_to_xml`]. A kind this version does not know
+/// leaves the field free-form rather than failing the load.
+fn field_type_from_xml(x: FieldTypeXml) -> Option<FieldType> {
+    let kind = match x.kind.as_str() {
+        "text" => FieldKind::Text,
+        "number" => FieldKind::Number,
+        "integer" => FieldKind::Integer,
+        "date" => FieldKind::Date,
+        "boolean" => FieldKind::Boolean,
+        "enum" => FieldKind::Enum,
+        "reference" => FieldKind::Reference,
+        _ => return None,
+    };
+    Some(FieldType {
+        kind,
+        required: x.required.unwrap_or(false),
+        min: x.min,
+        max: x.max,
+        options: x.options.into_iter().map(|o| o.val).collect(),
+        pattern: x.pattern,
+        max_length: x.max_length,
+        ref_name: x.ref_name,
+    })
+}
+
+/// Compile a loaded field's pattern once, as binding does. A pattern that
+/// no longer compiles (a hand-edited file) is dropped rather than failing
+/// the load.
+fn compile_pattern(ty: &FieldType) -> Option<Regex> {
+    check_field_type(ty).ok().flatten()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -184,7 +256,17 @@ mod tests {
                     "name".to_string(),
                     FieldEntry::new(4, "render-name".to_string())
                         .with_validation_formula(Some("LEN(#PLACEHOLDER)>0".to_string()))
-                        .with_editability_formula(Some("TRUE".to_string())),
+                        .with_editability_formula(Some("TRUE".to_string()))
+                        .with_field_type(Some(FieldType {
+                            kind: FieldKind::Enum,
+                            required: true,
+                            min: None,
+                            max: None,
+                            options: vec!["steel".to_string(), "wood".to_string()],
+                            pattern: None,
+                            max_length: None,
+                            ref_name: None,
+                        })),
                 ),
             ],
             name: "materials".to_string(),
@@ -196,7 +278,16 @@ mod tests {
         ColSchema {
             fields: vec![(
                 "alpha".to_string(),
-                FieldEntry::new(7, "render-alpha".to_string()),
+                FieldEntry::new(7, "render-alpha".to_string()).with_field_type(Some(FieldType {
+                    kind: FieldKind::Text,
+                    required: false,
+                    min: None,
+                    max: None,
+                    options: vec![],
+                    pattern: Some("[a-z]+".to_string()),
+                    max_length: None,
+                    ref_name: None,
+                })),
             )],
             name: "transposed".to_string(),
             key: 5,
@@ -250,6 +341,11 @@ mod tests {
                     s.fields[1].1.validation_formula.as_deref(),
                     Some("LEN(#PLACEHOLDER)>0")
                 );
+                assert!(s.fields[0].1.field_type.is_none());
+                let ty = s.fields[1].1.field_type.as_ref().unwrap();
+                assert_eq!(ty.kind, FieldKind::Enum);
+                assert!(ty.required);
+                assert_eq!(ty.options, vec!["steel", "wood"]);
             }
             _ => panic!("expected RowSchema at (1, 10)"),
         }
@@ -259,6 +355,10 @@ mod tests {
                 assert_eq!(s.name, "transposed");
                 assert_eq!(s.key, 5);
                 assert_eq!(s.fields[0].1.field_axis_id, 7);
+                // The pattern is compiled again on load.
+                let pattern = s.fields[0].1.pattern.as_ref().unwrap();
+                assert!(pattern.is_match("abc"));
+                assert!(!pattern.is_match("abc1"));
             }
             _ => panic!("expected ColSchema at (1, 11)"),
         }
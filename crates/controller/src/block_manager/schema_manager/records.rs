@@ -0,0 +1,120 @@
+//! Records for `ImportBlockRecords`, read from JSON or CSV.
+//!
+//! A record is a list of `(field, input)` pairs, the input as it would be
+//! typed into the cell. JSON numbers and booleans are written the way the
+//! engine reads them back (`1.5`, `TRUE`), and `null` is a blank. A CSV
+//! cell is its text; an empty one is a blank too.
+
+use crate::api::interchange::split_delimited;
+use crate::edit_action::RecordFormat;
+
+pub type Record = Vec<(String, String)>;
+
+pub fn parse_records(format: RecordFormat, data: &str) -> Result<Vec<Record>, String> {
+    match format {
+        RecordFormat::Json => parse_json(data),
+        RecordFormat::Csv => parse_csv(data),
+    }
+}
+
+fn parse_json(data: &str) -> Result<Vec<Record>, String> {
+    let value: serde_json::Value =
+        serde_json::from_str(data).map_err(|e| format!("invalid JSON: {}", e))?;
+    let serde_json::Value::Array(items) = value else {
+        return Err("expected a JSON array of records".to_string());
+    };
+    items
+        .into_iter()
+        .enumerate()
+        .map(|(i, item)| {
+            let serde_json::Value::Object(map) = item else {
+                return Err(format!("record {} is not an object", i + 1));
+            };
+            map.into_iter()
+                .map(|(field, v)| {
+                    let input = match v {
+                        serde_json::Value::Null => String::new(),
+                        serde_json::Value::Bool(b) => if b { "TRUE" } else { "FALSE" }.to_string(),
+                        serde_json::Value::Number(n) => n.to_string(),
+                        serde_json::Value::String(s) => s,
+                        _ => {
+                            return Err(format!(
+                                "record {}: field {:?} is not a scalar",
+                                i + 1,
+                                field
+                            ));
+                        }
+                    };
+                    Ok((field, input))
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// The first line names the fields. Blank lines are skipped, and a line
+/// shorter than the header leaves its last fields out.
+fn parse_csv(data: &str) -> Result<Vec<Record>, String> {
+    let data = data.strip_prefix('\u{feff}').unwrap_or(data);
+    let mut lines = split_delimited(data, ',').into_iter();
+    let header = lines.next().ok_or("missing CSV header")?;
+    let header = header
+        .into_iter()
+        .map(|f| f.trim().to_string())
+        .collect::<Vec<_>>();
+    let mut records = vec![];
+    for (i, line) in lines.enumerate() {
+        if line.iter().all(|c| c.is_empty()) {
+            continue;
+        }
+        if line.len() > header.len() {
+            return Err(format!(
+                "line {} has {} cells but the header names {} fields",
+                i + 2,
+                line.len(),
+                header.len()
+            ));
+        }
+        records.push(header.iter().cloned().zip(line).collect());
+    }
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pairs(r: &Record) -> Vec<(&str, &str)> {
+        r.iter().map(|(f, v)| (f.as_str(), v.as_str())).collect()
+    }
+
+    #[test]
+    fn json_records() {
+        let records = parse_records(
+            RecordFormat::Json,
+            r#"[{"id": "a", "qty": 3, "ok": true, "note": null}]"#,
+        )
+        .unwrap();
+        let mut r = pairs(&records[0]);
+        r.sort();
+        assert_eq!(
+            r,
+            vec![("id", "a"), ("note", ""), ("ok", "TRUE"), ("qty", "3")]
+        );
+        assert!(parse_records(RecordFormat::Json, r#"{"id": "a"}"#).is_err());
+        assert!(parse_records(RecordFormat::Json, r#"[{"id": [1]}]"#).is_err());
+    }
+
+    #[test]
+    fn csv_records() {
+        let records = parse_records(
+            RecordFormat::Csv,
+            "\u{feff}id, name\r\na,\"Smith, J\"\n\nb\n",
+        )
+        .unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(pairs(&records[0]), vec![("id", "a"), ("name", "Smith, J")]);
+        assert_eq!(pairs(&records[1]), vec![("id", "b")]);
+        assert!(parse_records(RecordFormat::Csv, "id\na,b\n").is_err());
+    }
+}
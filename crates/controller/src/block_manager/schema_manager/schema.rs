@@ -1,5 +1,7 @@
 use logisheets_base::{BlockCellId, BlockFieldId, BlockId, ColId, RowId};
+use regex::Regex;
 
+use crate::edit_action::FieldType;
 use crate::navigator::BlockPlace;
 
 /// Position of a single block-cell within a schema. Used by the dependency
@@ -36,6 +38,10 @@ pub type RenderId = String;
 ///                              shadow on the cell (advisory red marker)
 ///   - `editability_formula`  → installed as a `ShadowKind::UserEditable`
 ///                              shadow on the cell (host edit gate)
+///
+/// `field_type`, when set, is enforced on `BlockInput` (see
+/// [`super::field_type`]); `pattern` is its text pattern, compiled once when
+/// the type is bound.
 #[derive(Debug, Clone)]
 pub struct FieldEntry<F> {
     pub field_axis_id: F,
@@ -43,6 +49,8 @@ pub struct FieldEntry<F> {
     pub value_formula: Option<String>,
     pub validation_formula: Option<String>,
     pub editability_formula: Option<String>,
+    pub field_type: Option<FieldType>,
+    pub pattern: Option<Regex>,
 }
 
 impl<F> FieldEntry<F> {
@@ -53,6 +61,8 @@ impl<F> FieldEntry<F> {
             value_formula: None,
             validation_formula: None,
             editability_formula: None,
+            field_type: None,
+            pattern: None,
         }
     }
 
@@ -70,6 +80,16 @@ impl<F> FieldEntry<F> {
         self.editability_formula = f;
         self
     }
+
+    pub fn with_field_type(mut self, t: Option<FieldType>) -> Self {
+        self.field_type = t;
+        self
+    }
+
+    pub fn with_pattern(mut self, p: Option<Regex>) -> Self {
+        self.pattern = p;
+        self
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -110,6 +130,15 @@ impl<F: Copy + PartialEq, K, const IS_ROW: bool> FormSchema<F, K, IS_ROW> {
             .and_then(|(_, e)| e.editability_formula.as_deref())
     }
 
+    /// Lookup the type of the field whose field-axis id is `id`, with its
+    /// compiled pattern. Returns `None` for free-form fields.
+    pub fn field_type_for_field_axis(&self, id: F) -> Option<(&FieldType, Option<&Regex>)> {
+        self.fields
+            .iter()
+            .find(|(_, e)| e.field_axis_id == id)
+            .and_then(|(_, e)| Some((e.field_type.as_ref()?, e.pattern.as_ref())))
+    }
+
     /// Resolve a `#FIELD("name")` reference back to the field-axis id
     /// of the referenced sibling field. Returns `None` if the name
     /// isn't a field in this schema.
@@ -0,0 +1,514 @@
+//! Named what-if branches.
+//!
+//! The temp status is one unnamed fork that is either committed wholesale
+//! or thrown away. Branches are named forks of main that live side by
+//! side: the host switches between them, compares any two cell by cell
+//! and merges one back into main. Forking clones main's `Status`, which
+//! `imbl` keeps cheap.
+//!
+//! The branch being shown lives in the controller the way main does: its
+//! state is `Controller::status` and its undo history
+//! `Controller::version_manager`, so every edit path works on it
+//! unchanged. Switching parks those two in here and takes out the other
+//! branch's.
+//!
+//! Merging replays the branch's undoable transactions onto main as one
+//! undoable transaction. Each payload is recorded with the sheets it works
+//! on, so it goes to the same sheets on main even when main has added,
+//! removed or reordered sheets since the fork. Rows and columns are
+//! replayed at their positions, so a merge is refused when main has
+//! inserted or deleted rows or columns in a sheet the branch edits. A
+//! branch changed by a transaction that was not recorded (one that is not
+//! undoable, or a checkpoint restore) is refused too, rather than merged
+//! without it.
+//!
+//! A cell the branch changed that main has also changed since the fork, to
+//! something else, is a conflict; a merge with conflicts only goes through
+//! when forced, and the branch's edits then win. Cells are matched by sheet
+//! and cell id, so a cell that moved (rows inserted above it, say) is still
+//! the same cell. Comparing two branches matches cells by sheet name and
+//! position, as they show.
+//!
+//! Branches last for the session and are not written into the file.
+//! Saving writes the branch being shown.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+use logisheets_base::errors::BasicError;
+use logisheets_base::{CellId, SheetId};
+
+use crate::api::read_display_value;
+use crate::controller::display::{BranchInfo, CellChange, MergeConflict, Value};
+use crate::controller::status::Status;
+use crate::edit_action::EditPayload;
+use crate::version_manager::VersionManager;
+
+/// The name main goes by.
+pub const MAIN: &str = "main";
+
+/// A state with its undo history, parked while another one is shown.
+#[derive(Default)]
+struct Line {
+    status: Status,
+    version_manager: VersionManager,
+}
+
+/// A sheet as a merge finds it: by id for the sheets of main, or by the
+/// order branches created them in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum SheetKey {
+    Id(SheetId),
+    New(usize),
+}
+
+/// A payload with the sheets it works on.
+#[derive(Clone)]
+struct Step {
+    payload: EditPayload,
+    /// The sheets `sheet_idxs_mut` points at, in its order.
+    sheets: Vec<SheetKey>,
+    /// For a payload that puts a sheet at an index, that sheet and the one
+    /// it went before, `None` at the end.
+    placed: Option<(SheetKey, Option<SheetKey>)>,
+}
+
+struct Branch {
+    /// Main as it was when the branch was forked.
+    base: Status,
+    /// `None` while the branch is shown.
+    line: Option<Line>,
+    /// The steps of each undoable transaction made on the branch.
+    edits: Vec<Vec<Step>>,
+    /// Transactions undone on the branch, the latest last.
+    undone: Vec<Vec<Step>>,
+    /// The sheets created on the branch, by their `SheetKey::New` number.
+    created: HashMap<SheetId, usize>,
+    /// Whether a transaction that was not recorded changed the branch.
+    unrecorded: bool,
+}
+
+impl Branch {
+    fn key_of(&self, id: SheetId) -> SheetKey {
+        self.created
+            .get(&id)
+            .map_or(SheetKey::Id(id), |n| SheetKey::New(*n))
+    }
+}
+
+#[derive(Default)]
+pub struct BranchManager {
+    /// The branch shown, `None` for main.
+    current: Option<String>,
+    /// Main, while a branch is shown.
+    main: Option<Line>,
+    branches: BTreeMap<String, Branch>,
+    /// How many sheets have been created on branches.
+    sheets_created: usize,
+}
+
+/// What a cell shows and the formula it holds, keyed by sheet name and
+/// position. Cells with neither are left out.
+type Cells = BTreeMap<(String, usize, usize), (Value, String)>;
+
+/// The same, keyed by sheet and cell id and with where the cell is.
+type CellsById = HashMap<(SheetKey, CellId), ((String, usize, usize), (Value, String))>;
+
+impl BranchManager {
+    pub fn current(&self) -> &str {
+        self.current.as_deref().unwrap_or(MAIN)
+    }
+
+    pub fn is_on_branch(&self) -> bool {
+        self.current.is_some()
+    }
+
+    /// Main first, then the branches by name.
+    pub fn list(&self) -> Vec<BranchInfo> {
+        let main = BranchInfo {
+            name: MAIN.to_string(),
+            current: self.current.is_none(),
+            edits: 0,
+        };
+        let branches = self.branches.iter().map(|(name, b)| BranchInfo {
+            name: name.clone(),
+            current: self.current.as_ref() == Some(name),
+            edits: b.edits.len(),
+        });
+        std::iter::once(main).chain(branches).collect()
+    }
+
+    /// Fork main into a new branch. `shown` is the controller's status.
+    pub fn fork(&mut self, name: String, shown: &Status) -> Result<(), BasicError> {
+        if name == MAIN || self.branches.contains_key(&name) {
+            return Err(BasicError::BranchExists(name));
+        }
+        let base = self.main.as_ref().map_or(shown, |m| &m.status).clone();
+        let mut version_manager = VersionManager::default();
+        version_manager.set_init_status(base.clone());
+        let line = Line {
+            status: base.clone(),
+            version_manager,
+        };
+        self.branches.insert(
+            name,
+            Branch {
+                base,
+                line: Some(line),
+                edits: vec![],
+                undone: vec![],
+                created: HashMap::new(),
+                unrecorded: false,
+            },
+        );
+        Ok(())
+    }
+
+    /// Show branch `name`: park the controller's `status` and
+    /// `version_manager` and put the branch's in their place. The revision
+    /// carries on from the one parked, so readers see the switch as a
+    /// write.
+    pub fn switch(
+        &mut self,
+        name: &str,
+        status: &mut Status,
+        version_manager: &mut VersionManager,
+    ) -> Result<(), BasicError> {
+        if name == self.current() {
+            return Ok(());
+        }
+        if name != MAIN && !self.branches.contains_key(name) {
+            return Err(BasicError::BranchNotFound(name.to_string()));
+        }
+        let revision = version_manager.revision();
+        let shown = Line {
+            status: std::mem::take(status),
+            version_manager: std::mem::take(version_manager),
+        };
+        match &self.current {
+            None => self.main = Some(shown),
+            Some(c) => self.branches.get_mut(c).expect("current branch").line = Some(shown),
+        }
+        let next = match name {
+            MAIN => self.main.take(),
+            _ => self.branches.get_mut(name).and_then(|b| b.line.take()),
+        }
+        .expect("a branch not shown is parked");
+        *status = next.status;
+        *version_manager = next.version_manager;
+        version_manager.revision = revision;
+        version_manager.bump_revision();
+        self.current = (name != MAIN).then(|| name.to_string());
+        Ok(())
+    }
+
+    /// Drop a branch that is not shown. Returns `true` if it existed.
+    pub fn remove(&mut self, name: &str) -> bool {
+        if self.current.as_deref() == Some(name) {
+            return false;
+        }
+        self.branches.remove(name).is_some()
+    }
+
+    /// Log an undoable transaction made on the shown branch, which took it
+    /// from `before` to `after`, with the sheets each payload works on.
+    pub fn record(&mut self, payloads: Vec<EditPayload>, before: &Status, after: &Status) {
+        let mut created = self.sheets_created;
+        let Some(b) = self.current_branch_mut() else {
+            return;
+        };
+        let mut order = before
+            .sheet_info_manager
+            .pos
+            .iter()
+            .map(|id| b.key_of(*id))
+            .collect::<Vec<_>>();
+        let mut steps = vec![];
+        for mut payload in payloads {
+            if matches!(payload, EditPayload::RestoreCheckpoint(_)) {
+                b.unrecorded = true;
+            }
+            let sheets = payload
+                .sheet_idxs_mut()
+                .into_iter()
+                .map(|idx| order.get(*idx).copied())
+                .collect::<Option<Vec<_>>>();
+            let Some(sheets) = sheets else {
+                b.unrecorded = true;
+                continue;
+            };
+            let moved = match &payload {
+                EditPayload::DeleteSheet(p) => {
+                    order.remove(p.idx);
+                    None
+                }
+                EditPayload::MoveSheet(p) => Some(order.remove(p.from)),
+                _ => None,
+            };
+            let placed = payload.new_sheet_idx_mut().map(|idx| {
+                let key = moved.unwrap_or_else(|| {
+                    created += 1;
+                    SheetKey::New(created - 1)
+                });
+                let before = order.get(*idx).copied();
+                order.insert((*idx).min(order.len()), key);
+                (key, before)
+            });
+            steps.push(Step {
+                payload,
+                sheets,
+                placed,
+            });
+        }
+        let ids = &after.sheet_info_manager.pos;
+        if ids.len() == order.len() {
+            for (key, id) in order.iter().zip(ids.iter()) {
+                if let SheetKey::New(n) = key {
+                    b.created.insert(*id, *n);
+                }
+            }
+        } else {
+            b.unrecorded = true;
+        }
+        b.edits.push(steps);
+        b.undone.clear();
+        self.sheets_created = created;
+    }
+
+    /// Note a transaction that changed the shown branch without being
+    /// recorded, so the branch is not merged without it.
+    pub fn mark_unrecorded(&mut self) {
+        if let Some(b) = self.current_branch_mut() {
+            b.unrecorded = true;
+        }
+    }
+
+    /// Follow an undo on the shown branch.
+    pub fn undo(&mut self) {
+        if let Some(b) = self.current_branch_mut()
+            && let Some(edit) = b.edits.pop()
+        {
+            b.undone.push(edit);
+        }
+    }
+
+    /// Follow a redo on the shown branch.
+    pub fn redo(&mut self) {
+        if let Some(b) = self.current_branch_mut()
+            && let Some(edit) = b.undone.pop()
+        {
+            b.edits.push(edit);
+        }
+    }
+
+    /// The payloads a merge of `name` replays onto main, in order, with
+    /// their sheet indexes pointing at the same sheets on main.
+    pub fn rebase(&self, name: &str, shown: &Status) -> Result<Vec<EditPayload>, BasicError> {
+        let b = self.branch(name)?;
+        let refuse = |why: String| BasicError::BranchUnmergeable(name.to_string(), why);
+        if b.unrecorded {
+            return Err(refuse("it has edits that cannot be replayed".to_string()));
+        }
+        let main = self.status_of(MAIN, shown)?;
+        let mut order = main
+            .sheet_info_manager
+            .pos
+            .iter()
+            .map(|id| SheetKey::Id(*id))
+            .collect::<Vec<_>>();
+        let mut checked = HashSet::new();
+        let mut payloads = vec![];
+        for step in b.edits.iter().flatten() {
+            let mut payload = step.payload.clone();
+            for (idx, key) in payload.sheet_idxs_mut().into_iter().zip(&step.sheets) {
+                if let SheetKey::Id(id) = key
+                    && checked.insert(*id)
+                {
+                    let sheet = b.base.sheet_id_manager.get_string(id).unwrap_or_default();
+                    if !order.contains(key) {
+                        return Err(refuse(format!("main no longer has sheet {}", sheet)));
+                    }
+                    if !same_lines(&b.base, main, id) {
+                        return Err(refuse(format!(
+                            "main inserted or deleted rows or columns in sheet {}",
+                            sheet
+                        )));
+                    }
+                }
+                *idx = order
+                    .iter()
+                    .position(|k| k == key)
+                    .ok_or_else(|| refuse("a sheet it edits is gone".to_string()))?;
+            }
+            match &payload {
+                EditPayload::DeleteSheet(p) => {
+                    order.remove(p.idx);
+                }
+                EditPayload::MoveSheet(p) => {
+                    order.remove(p.from);
+                }
+                _ => {}
+            }
+            if let (Some(idx), Some((key, before))) = (payload.new_sheet_idx_mut(), step.placed) {
+                *idx = before
+                    .and_then(|b| order.iter().position(|k| *k == b))
+                    .unwrap_or(order.len());
+                order.insert(*idx, key);
+            }
+            payloads.push(payload);
+        }
+        Ok(payloads)
+    }
+
+    /// The cells that differ between `from` and `to`, old being `from`.
+    pub fn compare(
+        &self,
+        from: &str,
+        to: &str,
+        shown: &Status,
+    ) -> Result<Vec<CellChange>, BasicError> {
+        let old = cells_of(self.status_of(from, shown)?);
+        let new = cells_of(self.status_of(to, shown)?);
+        let empty = (Value::Empty, String::new());
+        let keys = old.keys().chain(new.keys()).collect::<BTreeSet<_>>();
+        let changes = keys
+            .into_iter()
+            .filter_map(|key| {
+                let (old_value, old_formula) = old.get(key).unwrap_or(&empty);
+                let (new_value, new_formula) = new.get(key).unwrap_or(&empty);
+                if old_value == new_value && old_formula == new_formula {
+                    return None;
+                }
+                let (sheet_name, row, col) = key.clone();
+                Some(CellChange {
+                    sheet_name,
+                    row,
+                    col,
+                    old_value: old_value.clone(),
+                    new_value: new_value.clone(),
+                    old_formula: old_formula.clone(),
+                    new_formula: new_formula.clone(),
+                    recalculated: !new_formula.is_empty() && old_formula == new_formula,
+                })
+            })
+            .collect();
+        Ok(changes)
+    }
+
+    /// The cells branch `name` and main both changed since the fork, to
+    /// different content. A formula cell whose formula is the same on all
+    /// three is not one: its value only follows the cells it reads.
+    pub fn conflicts(&self, name: &str, shown: &Status) -> Result<Vec<MergeConflict>, BasicError> {
+        let forked = self.branch(name)?;
+        let base = cells_by_id(&forked.base, SheetKey::Id);
+        let main = cells_by_id(self.status_of(MAIN, shown)?, SheetKey::Id);
+        let branch = cells_by_id(self.status_of(name, shown)?, |id| forked.key_of(id));
+        let empty = (Value::Empty, String::new());
+        let keys = base
+            .keys()
+            .chain(main.keys())
+            .chain(branch.keys())
+            .collect::<HashSet<_>>();
+        let mut conflicts = keys
+            .into_iter()
+            .filter_map(|key| {
+                let (b, m, br) = (base.get(key), main.get(key), branch.get(key));
+                let b_cell = b.map_or(&empty, |c| &c.1);
+                let m_cell = m.map_or(&empty, |c| &c.1);
+                let br_cell = br.map_or(&empty, |c| &c.1);
+                let (cb, cm, cbr) = (content(b_cell), content(m_cell), content(br_cell));
+                if cb == cm || cb == cbr || cm == cbr {
+                    return None;
+                }
+                let (sheet_name, row, col) = m.or(br).or(b)?.0.clone();
+                Some(MergeConflict {
+                    sheet_name,
+                    row,
+                    col,
+                    base_value: b_cell.0.clone(),
+                    main_value: m_cell.0.clone(),
+                    branch_value: br_cell.0.clone(),
+                    base_formula: b_cell.1.clone(),
+                    main_formula: m_cell.1.clone(),
+                    branch_formula: br_cell.1.clone(),
+                })
+            })
+            .collect::<Vec<_>>();
+        conflicts.sort_by(|x, y| (&x.sheet_name, x.row, x.col).cmp(&(&y.sheet_name, y.row, y.col)));
+        Ok(conflicts)
+    }
+
+    fn branch(&self, name: &str) -> Result<&Branch, BasicError> {
+        self.branches
+            .get(name)
+            .ok_or_else(|| BasicError::BranchNotFound(name.to_string()))
+    }
+
+    fn current_branch_mut(&mut self) -> Option<&mut Branch> {
+        let name = self.current.as_ref()?;
+        self.branches.get_mut(name)
+    }
+
+    /// The state of `name`, which is `shown` when it is the branch shown.
+    fn status_of<'a>(&'a self, name: &str, shown: &'a Status) -> Result<&'a Status, BasicError> {
+        if name == self.current() {
+            return Ok(shown);
+        }
+        let line = match name {
+            MAIN => self.main.as_ref(),
+            _ => self.branch(name)?.line.as_ref(),
+        };
+        Ok(&line.expect("a branch not shown is parked").status)
+    }
+}
+
+/// What the cell holds: its formula, or its value when it has none.
+fn content((value, formula): &(Value, String)) -> (Option<&Value>, &str) {
+    (formula.is_empty().then_some(value), formula)
+}
+
+/// Whether a sheet has the same rows and columns, in the same order, in
+/// both states.
+fn same_lines(a: &Status, b: &Status, sheet_id: &SheetId) -> bool {
+    match (
+        a.navigator.sheet_navs.get(sheet_id),
+        b.navigator.sheet_navs.get(sheet_id),
+    ) {
+        (Some(a), Some(b)) => a.data.rows == b.data.rows && a.data.cols == b.data.cols,
+        _ => false,
+    }
+}
+
+fn cells_of(status: &Status) -> Cells {
+    cells_by_id(status, SheetKey::Id).into_values().collect()
+}
+
+fn cells_by_id(status: &Status, key_of: impl Fn(SheetId) -> SheetKey) -> CellsById {
+    let mut cells = CellsById::new();
+    for sheet_id in status.sheet_info_manager.pos.iter() {
+        let Some(container) = status.container.get_sheet_container(*sheet_id) else {
+            continue;
+        };
+        let name = status
+            .sheet_id_manager
+            .get_string(sheet_id)
+            .unwrap_or_default();
+        for (cell_id, _) in container.cells.iter() {
+            if matches!(cell_id, CellId::EphemeralCell(_)) {
+                continue;
+            }
+            let Ok((row, col)) = status.navigator.fetch_cell_idx(sheet_id, cell_id) else {
+                continue;
+            };
+            let value = read_display_value(status, *sheet_id, cell_id);
+            let formula = status.formula_text(*sheet_id, cell_id);
+            if value == Value::Empty && formula.is_empty() {
+                continue;
+            }
+            cells.insert(
+                (key_of(*sheet_id), *cell_id),
+                ((name.clone(), row, col), (value, formula)),
+            );
+        }
+    }
+    cells
+}
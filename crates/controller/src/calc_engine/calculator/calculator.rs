@@ -55,6 +55,10 @@ where
             let rhs = iter.next().unwrap();
             infix::calc_infix(lhs, op, rhs, fetcher)
         }
+        ast::Operator::Prefix(ast::PrefixOperator::ImplicitIntersection) => {
+            let arg = args.into_iter().next().unwrap();
+            infix::implicit_intersect(arg, fetcher.get_curr_addr())
+        }
         ast::Operator::Prefix(p) => {
             let lhs = CalcVertex::Value(CalcValue::Scalar(Value::Number(0_f64)));
             let mut iter = args.into_iter();
@@ -62,6 +66,7 @@ where
             let op = match p {
                 ast::PrefixOperator::Minus => ast::InfixOperator::Minus,
                 ast::PrefixOperator::Plus => ast::InfixOperator::Plus,
+                ast::PrefixOperator::ImplicitIntersection => unreachable!(),
             };
             infix::calc_infix(lhs, &op, rhs, fetcher)
         }
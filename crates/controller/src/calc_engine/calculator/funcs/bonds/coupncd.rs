@@ -1,4 +1,4 @@
-use crate::calc_engine::calculator::math::bond::couppcd;
+use crate::calc_engine::calculator::math::bond::coupncd;
 use logisheets_parser::ast;
 
 use super::super::{CalcValue, CalcVertex, Value};
@@ -25,16 +25,19 @@ where
         ast::Error::Num
     );
 
-    let fourth = fetcher.get_calc_value(args_iter.next().unwrap());
-    assert_f64_from_calc_value!(base, fourth);
-    assert_or_return!(base >= 0. && base <= 4., ast::Error::Num);
+    // The day count basis does not move coupon dates, so it is only
+    // validated.
+    if let Some(arg) = args_iter.next() {
+        assert_f64_from_calc_value!(base, fetcher.get_calc_value(arg));
+        assert_or_return!(base >= 0. && base <= 4., ast::Error::Num);
+    }
 
     assert_or_return!(settle_num < maturity_num, ast::Error::Num);
 
-    let result = couppcd(
+    let result = coupncd(
         settle_num.floor() as u32,
         maturity_num.floor() as u32,
-        base.floor() as u8,
+        freq_num as u8,
     ) as f64;
     CalcVertex::from_number(result)
 }
@@ -2,7 +2,7 @@ use crate::calc_engine::calculator::calc_vertex::{CalcValue, CalcVertex, Value};
 use crate::calc_engine::connector::Connector;
 use logisheets_base::matrix_value::MatrixValue;
 use logisheets_parser::ast;
-use regex::RegexBuilder;
+use regex::{Captures, Regex, RegexBuilder};
 
 // REGEXTEST(text, pattern, [case_sensitivity])
 // Returns TRUE iff `pattern` matches anywhere in `text`.
@@ -29,10 +29,7 @@ where
         },
         None => false,
     };
-    let re = match RegexBuilder::new(&pattern)
-        .case_insensitive(case_insensitive)
-        .build()
-    {
+    let re = match build_regex(&pattern, case_insensitive) {
         Ok(r) => r,
         Err(_) => return CalcVertex::from_error(ast::Error::Value),
     };
@@ -72,10 +69,7 @@ where
         },
         None => false,
     };
-    let re = match RegexBuilder::new(&pattern)
-        .case_insensitive(case_insensitive)
-        .build()
-    {
+    let re = match build_regex(&pattern, case_insensitive) {
         Ok(r) => r,
         Err(_) => return CalcVertex::from_error(ast::Error::Value),
     };
@@ -148,34 +142,53 @@ where
         },
         None => false,
     };
-    let re = match RegexBuilder::new(&pattern)
-        .case_insensitive(case_insensitive)
-        .build()
-    {
+    let re = match build_regex(&pattern, case_insensitive) {
         Ok(r) => r,
         Err(_) => return CalcVertex::from_error(ast::Error::Value),
     };
-    let result = if occurrence == 0 {
-        re.replace_all(&text, replacement.as_str()).into_owned()
+    match replace_matches(&re, &text, &replacement, occurrence) {
+        Some(result) => CalcVertex::from_string(result),
+        None => CalcVertex::from_error(ast::Error::Value),
+    }
+}
+
+/// The regex the REGEX functions match with. Find and replace compiles its
+/// queries here too, so that both read a pattern the same way.
+pub fn build_regex(pattern: &str, case_insensitive: bool) -> Result<Regex, regex::Error> {
+    RegexBuilder::new(pattern)
+        .case_insensitive(case_insensitive)
+        .build()
+}
+
+/// `text` with every match of `re` replaced for `occurrence` 0, or only the
+/// Nth one for N >= 1. `replacement` expands `$0`, `$1`, ... to the groups;
+/// `$$` is a literal `$`. `None` for a negative occurrence.
+pub fn replace_matches(
+    re: &Regex,
+    text: &str,
+    replacement: &str,
+    occurrence: i64,
+) -> Option<String> {
+    if occurrence == 0 {
+        Some(re.replace_all(text, replacement).into_owned())
     } else if occurrence > 0 {
         let mut hit = 0i64;
-        re.replace_all(&text, |caps: &regex::Captures| {
+        let replaced = re.replace_all(text, |caps: &Captures| {
             hit += 1;
             if hit == occurrence {
                 let mut buf = String::new();
-                caps.expand(replacement.as_str(), &mut buf);
+                caps.expand(replacement, &mut buf);
                 buf
             } else {
                 caps.get(0)
                     .map(|m| m.as_str().to_string())
                     .unwrap_or_default()
             }
-        })
-        .into_owned()
+        });
+        Some(replaced.into_owned())
     } else {
-        return CalcVertex::from_error(ast::Error::Value);
-    };
-    CalcVertex::from_string(result)
+        None
+    }
 }
 
 // Excel's case_sensitivity flag: 0/FALSE → case-sensitive (default),
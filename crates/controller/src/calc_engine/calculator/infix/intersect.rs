@@ -1,9 +1,34 @@
-use super::super::calc_vertex::{CalcReference, CalcVertex, ColRange, Reference, RowRange};
+use super::super::calc_vertex::{
+    CalcReference, CalcValue, CalcVertex, ColRange, Reference, RowRange, Value,
+};
 use logisheets_base::Addr as Address;
 use logisheets_parser::ast;
 
 pub fn intersect(lhs: CalcVertex, rhs: CalcVertex) -> CalcVertex {
     match (lhs, rhs) {
+        // An error operand is the result, as for any other operator.
+        (CalcVertex::Value(CalcValue::Scalar(Value::Error(e))), _)
+        | (_, CalcVertex::Value(CalcValue::Scalar(Value::Error(e)))) => CalcVertex::from_error(e),
+        // `(A1:A5,C1:C5) A3:C3`: intersection distributes over a union. Members
+        // that miss the other side drop out; only when all of them miss is the
+        // result `#NULL!`. A member that is an error makes the whole result
+        // that error.
+        (CalcVertex::Union(members), other) | (other, CalcVertex::Union(members)) => {
+            let mut hits = vec![];
+            for m in members {
+                match intersect(*m, other.clone()) {
+                    CalcVertex::Value(CalcValue::Scalar(Value::Error(ast::Error::Null))) => {}
+                    e @ CalcVertex::Value(CalcValue::Scalar(Value::Error(_))) => return e,
+                    CalcVertex::Value(_) => {}
+                    hit => hits.push(Box::new(hit)),
+                }
+            }
+            match hits.len() {
+                0 => CalcVertex::from_error(ast::Error::Null),
+                1 => *hits.pop().unwrap(),
+                _ => CalcVertex::Union(hits),
+            }
+        }
         (CalcVertex::Reference(lhs_ref), CalcVertex::Reference(rhs_ref)) => {
             let lhs_prefix = lhs_ref.sheet;
             let rhs_prefix = rhs_ref.sheet;
@@ -24,6 +49,82 @@ pub fn intersect(lhs: CalcVertex, rhs: CalcVertex) -> CalcVertex {
     }
 }
 
+/// The `@` operator. A reference collapses to the one cell it shares with
+/// `curr` — the formula cell — by row for a column-shaped reference and by
+/// column for a row-shaped one. A reference that does not line up with the
+/// formula is `#VALUE!`, as in Excel. An in-memory array (a literal or a
+/// function result) collapses to its top-left element, and anything already
+/// scalar passes through unchanged.
+pub fn implicit_intersect(v: CalcVertex, curr: Address) -> CalcVertex {
+    match v {
+        CalcVertex::Reference(r) => match implicit_intersect_reference(r.reference, curr) {
+            Some(addr) => CalcVertex::Reference(CalcReference {
+                from_sheet: r.from_sheet,
+                sheet: r.sheet,
+                reference: Reference::Addr(addr),
+            }),
+            None => CalcVertex::from_error(ast::Error::Value),
+        },
+        CalcVertex::Value(CalcValue::Range(m)) => {
+            let first = m.visit(0, 0).map_or_else(|e| e, |v| v.clone());
+            CalcVertex::Value(CalcValue::Scalar(first))
+        }
+        CalcVertex::Union(_) => CalcVertex::from_error(ast::Error::Value),
+        other => other,
+    }
+}
+
+fn implicit_intersect_reference(r: Reference, curr: Address) -> Option<Address> {
+    match r {
+        Reference::Addr(addr) => Some(addr),
+        Reference::ColumnRange(cr) => {
+            let (start, end) = order((cr.start, cr.end));
+            if start == end {
+                Some(Address {
+                    row: curr.row,
+                    col: start,
+                })
+            } else if point_in_interval(curr.col, (start, end)) {
+                Some(curr)
+            } else {
+                None
+            }
+        }
+        Reference::RowRange(rr) => {
+            let (start, end) = order((rr.start, rr.end));
+            if start == end {
+                Some(Address {
+                    row: start,
+                    col: curr.col,
+                })
+            } else if point_in_interval(curr.row, (start, end)) {
+                Some(curr)
+            } else {
+                None
+            }
+        }
+        Reference::Range(start, end) => {
+            let rows = order((start.row, end.row));
+            let cols = order((start.col, end.col));
+            let row = if rows.0 == rows.1 {
+                rows.0
+            } else if point_in_interval(curr.row, rows) {
+                curr.row
+            } else {
+                return None;
+            };
+            let col = if cols.0 == cols.1 {
+                cols.0
+            } else if point_in_interval(curr.col, cols) {
+                curr.col
+            } else {
+                return None;
+            };
+            Some(Address { row, col })
+        }
+    }
+}
+
 fn intersect_without_prefix(l_ref: Reference, r_ref: Reference) -> Option<Reference> {
     match (l_ref, r_ref) {
         (Reference::Addr(la), Reference::Addr(ra)) => intersect_addresses(la, ra),
@@ -227,7 +328,9 @@ mod tests {
     use super::super::super::calc_vertex::{
         CalcReference, CalcValue, CalcVertex, ColRange, Reference, RowRange, Value,
     };
-    use super::{intersect, intersect_without_prefix};
+    use super::{
+        implicit_intersect, implicit_intersect_reference, intersect, intersect_without_prefix,
+    };
     use logisheets_base::Addr as Address;
     use logisheets_parser::ast;
 
@@ -446,4 +549,63 @@ mod tests {
             CalcVertex::Value(CalcValue::Scalar(Value::Error(ast::Error::Null))),
         ))
     }
+
+    #[test]
+    fn union_member_error_propagates() {
+        let cell = |row| {
+            Box::new(CalcVertex::Reference(CalcReference {
+                from_sheet: None,
+                sheet: 1,
+                reference: Reference::Addr(Address { row, col: 1 }),
+            }))
+        };
+        let union = CalcVertex::Union(vec![
+            cell(1),
+            Box::new(CalcVertex::from_error(ast::Error::Ref)),
+        ]);
+        let r = intersect(union, *cell(1));
+        assert!(matches!(
+            r,
+            CalcVertex::Value(CalcValue::Scalar(Value::Error(ast::Error::Ref))),
+        ));
+        let union = CalcVertex::Union(vec![cell(1), cell(2)]);
+        let r = intersect(union, CalcVertex::from_error(ast::Error::Div0));
+        assert!(matches!(
+            r,
+            CalcVertex::Value(CalcValue::Scalar(Value::Error(ast::Error::Div0))),
+        ));
+    }
+
+    #[test]
+    fn implicit_intersect_test() {
+        let curr = Address { row: 3, col: 4 };
+        // A column slice takes the formula's row, a row slice its column.
+        let r = implicit_intersect_reference(
+            Reference::Range(Address { row: 0, col: 0 }, Address { row: 9, col: 0 }),
+            curr,
+        );
+        assert!(matches!(r, Some(Address { row: 3, col: 0 })));
+        let r = implicit_intersect_reference(
+            Reference::Range(Address { row: 7, col: 0 }, Address { row: 7, col: 9 }),
+            curr,
+        );
+        assert!(matches!(r, Some(Address { row: 7, col: 4 })));
+        let r = implicit_intersect_reference(
+            Reference::ColumnRange(ColRange { start: 2, end: 2 }),
+            curr,
+        );
+        assert!(matches!(r, Some(Address { row: 3, col: 2 })));
+        // Out of line with the formula cell.
+        let r = implicit_intersect_reference(
+            Reference::Range(Address { row: 5, col: 0 }, Address { row: 9, col: 0 }),
+            curr,
+        );
+        assert!(r.is_none());
+
+        let cv = CalcVertex::Union(vec![]);
+        assert!(matches!(
+            implicit_intersect(cv, curr),
+            CalcVertex::Value(CalcValue::Scalar(Value::Error(ast::Error::Value))),
+        ));
+    }
 }
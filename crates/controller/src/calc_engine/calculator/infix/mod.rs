@@ -4,6 +4,7 @@ pub mod range;
 use super::super::connector::Connector;
 use super::calc_vertex::{CalcValue, CalcVertex, Value};
 use super::compare::{CompareResult, compare};
+pub use intersect::implicit_intersect;
 use intersect::intersect;
 use logisheets_parser::ast;
 use range::get_range;
@@ -20,3 +20,42 @@ where
     }
     None
 }
+
+const BISECTION_MAX_ITERATION: u32 = 1000;
+
+/// Use bisection to find a root of f between lo and hi. f(lo) and f(hi)
+/// must have opposite signs.
+pub fn bisection<F>(lo: f64, hi: f64, f: F) -> Option<f64>
+where
+    F: Fn(f64) -> f64,
+{
+    let (mut lo, mut hi) = (lo, hi);
+    let mut f_lo = f(lo);
+    let f_hi = f(hi);
+    if f_lo.is_nan() || f_hi.is_nan() {
+        return None;
+    }
+    if f_lo.abs() <= PRECISION {
+        return Some(lo);
+    }
+    if f_hi.abs() <= PRECISION {
+        return Some(hi);
+    }
+    if f_lo.signum() == f_hi.signum() {
+        return None;
+    }
+    for _ in 0..BISECTION_MAX_ITERATION {
+        let mid = (lo + hi) / 2.;
+        let f_mid = f(mid);
+        if f_mid.abs() <= PRECISION || (hi - lo).abs() <= PRECISION {
+            return Some(mid);
+        }
+        if f_mid.signum() == f_lo.signum() {
+            lo = mid;
+            f_lo = f_mid;
+        } else {
+            hi = mid;
+        }
+    }
+    None
+}
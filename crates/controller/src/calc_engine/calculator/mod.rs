@@ -3,4 +3,7 @@ pub mod calculator;
 mod compare;
 mod funcs;
 pub mod infix;
-mod math;
+pub(crate) mod math;
+
+pub use funcs::is_builtin_function;
+pub(crate) use funcs::regex_funcs;
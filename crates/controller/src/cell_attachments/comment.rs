@@ -1,6 +1,8 @@
 use imbl::{HashMap, Vector};
 use logisheets_base::{CellId, PersonId, SheetId};
 
+use crate::cell_shift::{CellMoves, normal_cell, take_moving};
+
 /// A person that can author or be mentioned in a comment.
 ///
 /// This mirrors OOXML `CT_Person` (`xl/persons/personN.xml`). The core is
@@ -188,6 +190,50 @@ impl Comments {
         self.persons.get(person_id)
     }
 
+    /// Copy the threads of sheet `from` to sheet `to`. Notes and mentions
+    /// get new GUIDs, replies follow their root's.
+    pub fn duplicate_sheet(&mut self, from: SheetId, to: SheetId) {
+        let Some(sheet) = self.data.get(&from) else {
+            return;
+        };
+        let mut copy = SheetComments::new();
+        for (cell_id, thread) in sheet.threads.iter() {
+            let new_ids: HashMap<String, String> = thread
+                .iter()
+                .map(|note| (note.id.clone(), format!("{{{}}}", uuid::Uuid::new_v4())))
+                .collect();
+            let notes = thread
+                .iter()
+                .map(|note| {
+                    let mut note = note.clone();
+                    note.id = new_ids[&note.id].clone();
+                    note.parent = note.parent.map(|p| new_ids.get(&p).cloned().unwrap_or(p));
+                    for mention in note.mentions.iter_mut() {
+                        mention.mention_id = format!("{{{}}}", uuid::Uuid::new_v4());
+                    }
+                    note
+                })
+                .collect();
+            copy.threads.insert(*cell_id, notes);
+        }
+        self.data.insert(to, copy);
+    }
+
+    /// Move the threads of sheet `from` along with their cells to sheet `to`,
+    /// which may be the same sheet; those of deleted cells go with them.
+    pub fn move_cells(&mut self, from: SheetId, to: SheetId, moves: &CellMoves) {
+        let Some(sheet) = self.data.get_mut(&from) else {
+            return;
+        };
+        let taken = take_moving(&mut sheet.threads, moves, normal_cell);
+        let sheet = self.data.entry(to).or_default();
+        for (_, new, thread) in taken {
+            if let Some(new) = new {
+                sheet.threads.insert(CellId::NormalCell(new), thread);
+            }
+        }
+    }
+
     /// Append a note to a cell's thread. A root note (`parent == None`) starts a
     /// new thread; a reply is appended after the existing notes.
     pub fn add_note(&mut self, sheet_id: SheetId, cell_id: CellId, note: CommentNote) {
@@ -1,6 +1,8 @@
 use imbl::HashMap;
 use logisheets_base::{NormalCellId, SheetId};
 
+use crate::cell_shift::{CellMoves, take_moving};
+
 #[derive(Debug, Clone, Default)]
 pub struct MergeCells {
     // The 1st CellId is the id of the start cell.
@@ -39,6 +41,21 @@ impl MergeCells {
         }
     }
 
+    /// Move the merges of sheet `from` along with their cells to sheet `to`,
+    /// which may be the same sheet. Moves never tear a merge, so both corners
+    /// move together or are deleted together.
+    pub fn move_cells(&mut self, from: SheetId, to: SheetId, moves: &CellMoves) {
+        let Some(sheet) = self.data.get_mut(&from) else {
+            return;
+        };
+        let taken = take_moving(sheet, moves, |start| Some(*start));
+        for (_, start, end) in taken {
+            if let (Some(start), Some(Some(end))) = (start, moves.get(&end)) {
+                self.add_merge_cell(to, start, *end);
+            }
+        }
+    }
+
     pub fn get_merge_cell(
         &self,
         sheet_id: &SheetId,
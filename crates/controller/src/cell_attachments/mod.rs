@@ -4,10 +4,30 @@ pub mod executor;
 pub mod merge_cell;
 
 use comment::Comments;
+use logisheets_base::SheetId;
 use merge_cell::MergeCells;
 
+use crate::cell_shift::CellMoves;
+
 #[derive(Debug, Clone, Default)]
 pub struct CellAttachmentsManager {
     pub comments: Comments,
     pub merge_cells: MergeCells,
 }
+
+impl CellAttachmentsManager {
+    /// Copy the merges and comment threads of sheet `from` to sheet `to`.
+    pub fn duplicate_sheet(&mut self, from: SheetId, to: SheetId) {
+        if let Some(merges) = self.merge_cells.data.get(&from).cloned() {
+            self.merge_cells.data.insert(to, merges);
+        }
+        self.comments.duplicate_sheet(from, to);
+    }
+
+    /// Move the merges and comment threads of sheet `from` along with their
+    /// cells to sheet `to`.
+    pub fn move_cells(&mut self, from: SheetId, to: SheetId, moves: &CellMoves) {
+        self.merge_cells.move_cells(from, to, moves);
+        self.comments.move_cells(from, to, moves);
+    }
+}
@@ -0,0 +1,339 @@
+//! Geometry of a range-scoped insert or delete (`InsertCells` / `DeleteCells`).
+//!
+//! A whole-line insert adds row/col ids and every position follows on its
+//! own. Shifting part of a sheet cannot work that way: the navigator stays as
+//! it is and the cells themselves change places. Each manager asks a
+//! [`CellShift`] where a position or a rectangle ends up and re-anchors its
+//! ids on the new position.
+//!
+//! All positions are 0-based and inclusive.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use logisheets_base::{CellId, NormalCellId};
+
+use crate::edit_action::{DeleteCells, InsertCells};
+use crate::navigator::sheet_nav::{MAX_COL_CNT, MAX_ROW_CNT};
+
+/// Where the moving cells end up, by id: `None` for a deleted cell. Cells
+/// that stay are not listed.
+pub type CellMoves = HashMap<NormalCellId, Option<NormalCellId>>;
+
+/// The rectangles (by corner ids) that follow their cells, as [`CellMoves`].
+pub type RectMoves = HashMap<(NormalCellId, NormalCellId), Option<(NormalCellId, NormalCellId)>>;
+
+#[derive(Debug, Clone, Copy)]
+pub struct CellShift {
+    pub start_row: usize,
+    pub start_col: usize,
+    pub end_row: usize,
+    pub end_col: usize,
+    /// Cells move along their row (right/left) instead of their column.
+    pub horizontal: bool,
+    pub insert: bool,
+}
+
+/// Where a cell ends up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CellMove {
+    Stay,
+    To(usize, usize),
+    Deleted,
+}
+
+/// Where a rectangle ends up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RectMove {
+    Stay,
+    /// Its corners stay put but some of the cells inside it change.
+    Dirty,
+    To(usize, usize, usize, usize),
+    Deleted,
+}
+
+impl From<&InsertCells> for CellShift {
+    fn from(p: &InsertCells) -> Self {
+        CellShift {
+            start_row: p.start_row,
+            start_col: p.start_col,
+            end_row: p.end_row,
+            end_col: p.end_col,
+            horizontal: p.shift_right,
+            insert: true,
+        }
+    }
+}
+
+impl From<&DeleteCells> for CellShift {
+    fn from(p: &DeleteCells) -> Self {
+        CellShift {
+            start_row: p.start_row,
+            start_col: p.start_col,
+            end_row: p.end_row,
+            end_col: p.end_col,
+            horizontal: p.shift_left,
+            insert: false,
+        }
+    }
+}
+
+impl CellShift {
+    pub fn is_valid(&self) -> bool {
+        self.start_row <= self.end_row
+            && self.start_col <= self.end_col
+            && self.end_row < MAX_ROW_CNT as usize
+            && self.end_col < MAX_COL_CNT as usize
+    }
+
+    /// The position along the shift axis and the one across it.
+    fn split(&self, row: usize, col: usize) -> (usize, usize) {
+        if self.horizontal {
+            (col, row)
+        } else {
+            (row, col)
+        }
+    }
+
+    fn join(&self, along: usize, across: usize) -> (usize, usize) {
+        if self.horizontal {
+            (across, along)
+        } else {
+            (along, across)
+        }
+    }
+
+    /// The rows (columns) whose cells move.
+    fn lanes(&self) -> (usize, usize) {
+        if self.horizontal {
+            (self.start_row, self.end_row)
+        } else {
+            (self.start_col, self.end_col)
+        }
+    }
+
+    fn first(&self) -> usize {
+        if self.horizontal {
+            self.start_col
+        } else {
+            self.start_row
+        }
+    }
+
+    fn count(&self) -> usize {
+        if self.horizontal {
+            self.end_col - self.start_col + 1
+        } else {
+            self.end_row - self.start_row + 1
+        }
+    }
+
+    fn max(&self) -> usize {
+        if self.horizontal {
+            MAX_COL_CNT as usize
+        } else {
+            MAX_ROW_CNT as usize
+        }
+    }
+
+    /// Where position `along` of a moving lane goes: `None` when it is
+    /// deleted or pushed off the sheet.
+    fn move_along(&self, along: usize) -> Option<usize> {
+        let (first, count) = (self.first(), self.count());
+        if along < first {
+            Some(along)
+        } else if self.insert {
+            Some(along + count).filter(|a| *a < self.max())
+        } else if along < first + count {
+            None
+        } else {
+            Some(along - count)
+        }
+    }
+
+    pub fn move_cell(&self, row: usize, col: usize) -> CellMove {
+        let (along, across) = self.split(row, col);
+        let (lo, hi) = self.lanes();
+        if across < lo || across > hi || along < self.first() {
+            return CellMove::Stay;
+        }
+        match self.move_along(along) {
+            Some(along) => {
+                let (row, col) = self.join(along, across);
+                CellMove::To(row, col)
+            }
+            None => CellMove::Deleted,
+        }
+    }
+
+    /// Whether any cell of the rectangle moves or is deleted.
+    pub fn touches(
+        &self,
+        start_row: usize,
+        start_col: usize,
+        end_row: usize,
+        end_col: usize,
+    ) -> bool {
+        let (_, x1) = self.split(start_row, start_col);
+        let (a2, x2) = self.split(end_row, end_col);
+        let (lo, hi) = self.lanes();
+        a2 >= self.first() && x2 >= lo && x1 <= hi
+    }
+
+    /// How a reference to a rectangle is rewritten. It follows the cells only
+    /// when it spans nothing but moving lanes, as Excel does: inserting grows
+    /// it when the insertion falls inside, deleting shrinks it, and it is
+    /// deleted once none of its cells are left.
+    pub fn move_rect(
+        &self,
+        start_row: usize,
+        start_col: usize,
+        end_row: usize,
+        end_col: usize,
+    ) -> RectMove {
+        let (a1, x1) = self.split(start_row, start_col);
+        let (a2, x2) = self.split(end_row, end_col);
+        if !self.touches(start_row, start_col, end_row, end_col) {
+            return RectMove::Stay;
+        }
+        let (lo, hi) = self.lanes();
+        let first = self.first();
+        if x1 < lo || x2 > hi {
+            return RectMove::Dirty;
+        }
+        let count = self.count();
+        let (na1, na2) = if self.insert {
+            let na1 = if a1 < first { a1 } else { a1 + count };
+            if na1 >= self.max() {
+                return RectMove::Deleted;
+            }
+            (na1, (a2 + count).min(self.max() - 1))
+        } else {
+            if a1 >= first && a2 < first + count {
+                return RectMove::Deleted;
+            }
+            let na1 = match self.move_along(a1) {
+                Some(a) => a,
+                None => first,
+            };
+            let na2 = match self.move_along(a2) {
+                Some(a) => a,
+                None => first - 1,
+            };
+            (na1, na2)
+        };
+        let (r1, c1) = self.join(na1, x1);
+        let (r2, c2) = self.join(na2, x2);
+        RectMove::To(r1, c1, r2, c2)
+    }
+
+    /// Whether the shift would tear a unit such as a merge or a block apart:
+    /// it reaches into the moving area without lying wholly in it, or the
+    /// inserted or deleted cells cut through it. A unit that is deleted as a
+    /// whole is not torn.
+    pub fn tears(
+        &self,
+        start_row: usize,
+        start_col: usize,
+        end_row: usize,
+        end_col: usize,
+    ) -> bool {
+        let (a1, x1) = self.split(start_row, start_col);
+        let (a2, x2) = self.split(end_row, end_col);
+        if !self.touches(start_row, start_col, end_row, end_col) {
+            return false;
+        }
+        let (lo, hi) = self.lanes();
+        let (first, count) = (self.first(), self.count());
+        if x1 < lo || x2 > hi || a1 < first {
+            return true;
+        }
+        let deleted = first + count;
+        !self.insert && a1 < deleted && a2 >= deleted
+    }
+}
+
+/// The id of a cell on the grid, which is all a move or shift can move.
+pub fn normal_cell(id: &CellId) -> Option<NormalCellId> {
+    match id {
+        CellId::NormalCell(c) => Some(*c),
+        _ => None,
+    }
+}
+
+/// Take the entries of `map` whose key names a moving cell out of it, each
+/// with where its cell goes. The caller puts them back under their new keys
+/// (on whichever sheet they go to) once every moving entry is out: a cell
+/// often moves onto one that moves on in turn.
+pub fn take_moving<K, V>(
+    map: &mut imbl::HashMap<K, V>,
+    moves: &CellMoves,
+    cell: impl Fn(&K) -> Option<NormalCellId>,
+) -> Vec<(K, Option<NormalCellId>, V)>
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+{
+    let moving = map
+        .keys()
+        .filter_map(|k| Some((k.clone(), *moves.get(&cell(k)?)?)))
+        .collect::<Vec<_>>();
+    moving
+        .into_iter()
+        .filter_map(|(k, to)| {
+            let v = map.remove(&k)?;
+            Some((k, to, v))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shift(insert: bool, horizontal: bool) -> CellShift {
+        // B3:C4
+        CellShift {
+            start_row: 2,
+            start_col: 1,
+            end_row: 3,
+            end_col: 2,
+            horizontal,
+            insert,
+        }
+    }
+
+    #[test]
+    fn insert_down_moves_cells_in_its_columns() {
+        let s = shift(true, false);
+        assert_eq!(s.move_cell(2, 1), CellMove::To(4, 1));
+        assert_eq!(s.move_cell(10, 2), CellMove::To(12, 2));
+        assert_eq!(s.move_cell(1, 1), CellMove::Stay);
+        assert_eq!(s.move_cell(2, 3), CellMove::Stay);
+        assert_eq!(s.move_rect(0, 1, 5, 2), RectMove::To(0, 1, 7, 2));
+        assert_eq!(s.move_rect(0, 0, 5, 2), RectMove::Dirty);
+        assert_eq!(s.move_rect(0, 0, 1, 5), RectMove::Stay);
+    }
+
+    #[test]
+    fn delete_left_closes_the_gap() {
+        let s = shift(false, true);
+        assert_eq!(s.move_cell(2, 1), CellMove::Deleted);
+        assert_eq!(s.move_cell(3, 5), CellMove::To(3, 3));
+        assert_eq!(s.move_cell(4, 5), CellMove::Stay);
+        assert_eq!(s.move_rect(2, 0, 3, 4), RectMove::To(2, 0, 3, 2));
+        assert_eq!(s.move_rect(2, 2, 3, 5), RectMove::To(2, 1, 3, 3));
+        assert_eq!(s.move_rect(2, 1, 2, 2), RectMove::Deleted);
+    }
+
+    #[test]
+    fn tearing_units() {
+        let s = shift(false, false);
+        assert!(!s.tears(4, 1, 5, 2));
+        assert!(!s.tears(2, 1, 3, 1));
+        assert!(s.tears(3, 1, 4, 1));
+        assert!(s.tears(4, 2, 4, 3));
+        assert!(s.tears(1, 1, 2, 1));
+        assert!(!s.tears(0, 0, 1, 5));
+    }
+}
@@ -136,4 +136,43 @@ impl ChartManager {
         self.charts.insert(sheet_id, v);
         true
     }
+
+    /// Copy the charts of sheet `from` to sheet `to`. Each copy gets a fresh
+    /// `chartN` id and part of its own; like a newly created chart it keeps
+    /// only the chart XML, leaving the style/color satellites behind.
+    pub fn duplicate_sheet(&mut self, from: SheetId, to: SheetId) {
+        let mut next = self
+            .charts
+            .values()
+            .flat_map(|v| v.iter())
+            .filter_map(|c| c.id.strip_prefix("chart")?.parse::<u32>().ok())
+            .max()
+            .unwrap_or(0);
+        for chart in self.charts_of_sheet(from) {
+            next += 1;
+            let id = format!("chart{}", next);
+            let part_path = format!("xl/charts/{}.xml", id);
+            let raw = chart
+                .raw
+                .iter()
+                .find(|p| p.path == chart.part_path)
+                .map(|p| PassthroughPart {
+                    path: part_path.clone(),
+                    data: p.data.clone(),
+                    rtype: p.rtype,
+                    rels: vec![],
+                })
+                .into_iter()
+                .collect();
+            self.add(
+                to,
+                Chart {
+                    id,
+                    part_path,
+                    raw: Arc::new(raw),
+                    ..chart
+                },
+            );
+        }
+    }
 }
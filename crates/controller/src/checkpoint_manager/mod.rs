@@ -1,10 +1,10 @@
-//! Named in-memory checkpoints.
+//! Named checkpoints.
 //!
 //! Independent of `VersionManager`:
-//!   - VersionManager   — linear undo/redo stack, LRU bounded, automatic
-//!                        (every undoable tx records). Drives Ctrl-Z/Y.
+//!   - VersionManager — linear undo/redo stack, LRU bounded, automatic
+//!     (every undoable tx records). Drives Ctrl-Z/Y.
 //!   - CheckpointManager — random-access by `label`, AI-driven, persistent
-//!                        within a session unless explicitly deleted.
+//!     within a session unless explicitly deleted.
 //!
 //! They cooperate via `RestoreCheckpoint`: that payload reads a snapshot
 //! out of CheckpointManager AND records the state-swap as a normal
@@ -15,13 +15,22 @@
 //! multiple snapshots share most of their substructure with the live
 //! Status, so each entry's marginal cost is on the order of a few Arc
 //! refcount bumps.
+//!
+//! Checkpoints saved with `persist` set are also written into the file
+//! (see `persistence`) and come back on load with their metadata. Their
+//! state is only rebuilt when one is restored.
 
 use std::collections::VecDeque;
 
 use logisheets_base::errors::BasicError;
+use logisheets_workbook::logisheets::CheckpointXml;
 
 use crate::controller::status::Status;
 
+use self::persistence::{CheckpointDiff, apply, content_of, diff, diff_from_xml, diff_to_xml};
+
+pub mod persistence;
+
 /// Hard ceiling on number of checkpoints kept simultaneously. When at
 /// capacity, the oldest entry is evicted (FIFO by last save/restore).
 ///
@@ -38,26 +47,37 @@ pub struct CheckpointEntry {
     /// Caller-supplied human-readable description. Optional — purely
     /// for round-trip display, not used for routing.
     pub description: Option<String>,
-    /// Snapshot of the workbook state at save time. Restoring replaces
-    /// the controller's live `Status` with a clone of this.
-    pub status: Status,
+    /// Caller-supplied timestamp, stored as given.
+    pub created_at: Option<String>,
+    pub author: Option<String>,
+    /// Whether the checkpoint is written into the file on save. Only the
+    /// cell content and the sheet list are written: styles, merges, names
+    /// and the like are not, and a restore after a reload keeps them as
+    /// the workbook has them.
+    pub persist: bool,
+    pub snapshot: Snapshot,
+}
+
+#[derive(Debug, Clone)]
+pub enum Snapshot {
+    /// The workbook state at save time. Restoring replaces the
+    /// controller's live `Status` with a clone of this.
+    Status(Box<Status>),
+    /// A checkpoint loaded from the file and not restored yet: how it
+    /// differs from the workbook as loaded.
+    Saved(CheckpointDiff),
 }
 
 /// Session-scoped checkpoint store.
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct CheckpointManager {
     /// Entries keyed by label. Using a Vec with linear scan keeps the
     /// data layout simple at our scale (≤20 entries) and lets us
     /// preserve insertion order for FIFO eviction.
     entries: VecDeque<CheckpointEntry>,
-}
-
-impl Default for CheckpointManager {
-    fn default() -> Self {
-        Self {
-            entries: VecDeque::new(),
-        }
-    }
+    /// The workbook as loaded, which `Snapshot::Saved` entries are
+    /// relative to.
+    base: Option<Status>,
 }
 
 impl CheckpointManager {
@@ -71,6 +91,27 @@ impl CheckpointManager {
         description: Option<String>,
         status: Status,
     ) -> &CheckpointEntry {
+        self.save_with_meta(
+            CheckpointMeta {
+                label,
+                description,
+                created_at: None,
+                author: None,
+                persist: false,
+            },
+            status,
+        )
+    }
+
+    /// Like `save`, with the full metadata.
+    pub fn save_with_meta(&mut self, meta: CheckpointMeta, status: Status) -> &CheckpointEntry {
+        let CheckpointMeta {
+            label,
+            description,
+            created_at,
+            author,
+            persist,
+        } = meta;
         // Remove existing entry with this label (so save overrides).
         self.entries.retain(|e| e.label != label);
 
@@ -82,22 +123,47 @@ impl CheckpointManager {
         self.entries.push_front(CheckpointEntry {
             label,
             description,
-            status,
+            created_at,
+            author,
+            persist,
+            snapshot: Snapshot::Status(Box::new(status)),
         });
         self.entries.front().expect("just pushed")
     }
 
     /// Look up a snapshot's Status without consuming it. Returns
     /// `Err(BasicError::CheckpointNotFound)` if the label is unknown
-    /// (fail-loud per design).
+    /// (fail-loud per design). A checkpoint loaded from the file has no
+    /// `Status` until `materialize` gives it one, which the controller
+    /// does before running a restore.
     pub fn get(&self, label: &str) -> Result<&Status, BasicError> {
         self.entries
             .iter()
             .find(|e| e.label == label)
-            .map(|e| &e.status)
+            .and_then(|e| match &e.snapshot {
+                Snapshot::Status(status) => Some(status.as_ref()),
+                Snapshot::Saved(_) => None,
+            })
             .ok_or_else(|| BasicError::CheckpointNotFound(label.to_string()))
     }
 
+    /// For a checkpoint loaded from the file and not rebuilt yet, the
+    /// workbook as loaded and the diff to replay onto it.
+    pub fn pending(&self, label: &str) -> Option<(&Status, &CheckpointDiff)> {
+        let entry = self.entries.iter().find(|e| e.label == label)?;
+        match (&entry.snapshot, &self.base) {
+            (Snapshot::Saved(diff), Some(base)) => Some((base, diff)),
+            _ => None,
+        }
+    }
+
+    /// Store the rebuilt state of a checkpoint loaded from the file.
+    pub fn materialize(&mut self, label: &str, status: Status) {
+        if let Some(entry) = self.entries.iter_mut().find(|e| e.label == label) {
+            entry.snapshot = Snapshot::Status(Box::new(status));
+        }
+    }
+
     /// Remove a checkpoint by label. Returns `true` if it existed.
     pub fn delete(&mut self, label: &str) -> bool {
         let before = self.entries.len();
@@ -113,6 +179,9 @@ impl CheckpointManager {
             .map(|e| CheckpointMeta {
                 label: e.label.clone(),
                 description: e.description.clone(),
+                created_at: e.created_at.clone(),
+                author: e.author.clone(),
+                persist: e.persist,
             })
             .collect()
     }
@@ -127,10 +196,58 @@ impl CheckpointManager {
         self.entries.is_empty()
     }
 
-    /// Drop every checkpoint. Used on file save/load — checkpoints are
-    /// session state, not file state.
+    /// Drop every checkpoint.
     pub fn clear(&mut self) {
         self.entries.clear();
+        self.base = None;
+    }
+
+    /// The persisted checkpoints, each as its difference from `current`,
+    /// the state being saved.
+    pub fn to_xml(&self, current: &Status) -> Vec<CheckpointXml> {
+        let persisted = self.entries.iter().filter(|e| e.persist);
+        if persisted.clone().next().is_none() {
+            return vec![];
+        }
+        let current = content_of(current);
+        let base = self.base.as_ref().map(content_of).unwrap_or_default();
+        persisted
+            .map(|e| {
+                let content = match &e.snapshot {
+                    Snapshot::Status(status) => content_of(status),
+                    Snapshot::Saved(d) => apply(&base, d),
+                };
+                CheckpointXml {
+                    label: e.label.clone(),
+                    description: e.description.clone(),
+                    created_at: e.created_at.clone(),
+                    author: e.author.clone(),
+                    sheets: diff_to_xml(&diff(&current, &content)),
+                }
+            })
+            .collect()
+    }
+
+    /// Replace the checkpoints with those read from a file. `base` is the
+    /// workbook loaded from that file.
+    pub fn load(&mut self, checkpoints: Vec<CheckpointXml>, base: Status) {
+        self.entries = checkpoints
+            .into_iter()
+            .take(MAX_CHECKPOINTS)
+            .map(|c| CheckpointEntry {
+                label: c.label,
+                description: c.description,
+                created_at: c.created_at,
+                author: c.author,
+                persist: true,
+                snapshot: Snapshot::Saved(diff_from_xml(c.sheets)),
+            })
+            .collect();
+        self.base = if self.entries.is_empty() {
+            None
+        } else {
+            Some(base)
+        };
     }
 }
 
@@ -140,6 +257,10 @@ impl CheckpointManager {
 pub struct CheckpointMeta {
     pub label: String,
     pub description: Option<String>,
+    pub created_at: Option<String>,
+    pub author: Option<String>,
+    /// Keep the checkpoint in the file when the workbook is saved.
+    pub persist: bool,
 }
 
 #[cfg(test)]
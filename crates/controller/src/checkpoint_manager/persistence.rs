@@ -0,0 +1,426 @@
+//! Checkpoint persistence: keep chosen checkpoints inside the saved file.
+//!
+//! An in-memory checkpoint is a whole `Status`. Writing that out would
+//! store the workbook once more per checkpoint, so the file instead carries
+//! the cell content (values and formulas) in which each persisted
+//! checkpoint differs from the workbook being saved. On load such an entry
+//! stays a diff until it is restored; restoring replays the diff as plain
+//! edits onto the workbook as it was loaded.
+//!
+//! Only cell content and the sheet list are compared. Sheets are matched
+//! by identity, not by name, and the file records each one by its position
+//! in the saved workbook. A restored checkpoint gets back its values,
+//! formulas, sheets and sheet names, while styles, blocks and other sheet
+//! state are those of the saved workbook.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use logisheets_base::{CellId, CellValue, SheetId};
+use logisheets_parser::unparse;
+use logisheets_workbook::logisheets::{CheckpointCellXml, CheckpointSheetXml};
+
+use crate::controller::status::Status;
+use crate::edit_action::{
+    CellClear, CellInput, CreateSheet, DeleteSheet, EditPayload, MoveSheet, SheetRename,
+};
+
+/// What a cell holds, as far as a checkpoint is concerned.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CellContent {
+    /// Formula text without the leading `=`.
+    Formula(String),
+    Number(f64),
+    Str(String),
+    Bool(bool),
+    Error(String),
+}
+
+type Cells = Vec<((usize, usize), CellContent)>;
+
+/// The non-blank cells of one sheet, by `(row, col)`. `id` is `None` for a
+/// sheet that only a checkpoint read from the file has.
+#[derive(Debug, Clone)]
+pub struct SheetContent {
+    pub id: Option<SheetId>,
+    pub name: String,
+    pub cells: BTreeMap<(usize, usize), CellContent>,
+}
+
+/// A checkpoint relative to some workbook: every sheet of the checkpoint in
+/// order, each with the cells that differ. `None` marks a cell that was
+/// blank at the checkpoint.
+#[derive(Debug, Clone, Default)]
+pub struct CheckpointDiff {
+    pub sheets: Vec<SheetDiff>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SheetDiff {
+    /// The position of the same sheet in that workbook, or `None` if the
+    /// workbook no longer has it.
+    pub saved_idx: Option<usize>,
+    pub name: String,
+    pub cells: BTreeMap<(usize, usize), Option<CellContent>>,
+}
+
+/// The cell content of every sheet, in sheet order. Formulas are unparsed
+/// and win over their cached values.
+pub fn content_of(status: &Status) -> Vec<SheetContent> {
+    let mut name_fetcher = status.name_fetcher();
+    let mut formulas: HashMap<SheetId, Cells> = HashMap::new();
+    for ((sheet_id, cell_id), node) in status.formula_manager.formulas.iter() {
+        if matches!(cell_id, CellId::EphemeralCell(_)) {
+            continue;
+        }
+        let Ok(pos) = status.navigator.fetch_cell_idx(sheet_id, cell_id) else {
+            continue;
+        };
+        let Ok(text) = unparse::unparse(node, &mut name_fetcher, *sheet_id) else {
+            continue;
+        };
+        formulas
+            .entry(*sheet_id)
+            .or_default()
+            .push((pos, CellContent::Formula(text)));
+    }
+
+    status
+        .sheet_info_manager
+        .pos
+        .iter()
+        .map(|sheet_id| {
+            let mut cells = BTreeMap::new();
+            if let Some(container) = status.container.get_sheet_container(*sheet_id) {
+                for (cell_id, cell) in container.cells.iter() {
+                    if matches!(cell_id, CellId::EphemeralCell(_)) {
+                        continue;
+                    }
+                    let content = match &cell.value {
+                        CellValue::Boolean(b) => CellContent::Bool(*b),
+                        CellValue::Error(e) => CellContent::Error(e.to_string()),
+                        CellValue::String(id) => CellContent::Str(
+                            status.text_id_manager.get_string(id).unwrap_or_default(),
+                        ),
+                        CellValue::Number(n) => CellContent::Number(*n),
+                        _ => continue,
+                    };
+                    if let Ok(pos) = status.navigator.fetch_cell_idx(sheet_id, cell_id) {
+                        cells.insert(pos, content);
+                    }
+                }
+            }
+            if let Some(fs) = formulas.remove(sheet_id) {
+                cells.extend(fs);
+            }
+            SheetContent {
+                id: Some(*sheet_id),
+                name: status
+                    .sheet_id_manager
+                    .get_string(sheet_id)
+                    .unwrap_or_default(),
+                cells,
+            }
+        })
+        .collect()
+}
+
+/// What turns `saved` into `checkpoint`. Sheets are matched by id, so a
+/// sheet renamed since the checkpoint is still the same sheet.
+pub fn diff(saved: &[SheetContent], checkpoint: &[SheetContent]) -> CheckpointDiff {
+    let empty = BTreeMap::new();
+    let sheets = checkpoint
+        .iter()
+        .map(|sheet| {
+            let saved_idx = saved
+                .iter()
+                .position(|s| s.id.is_some() && s.id == sheet.id);
+            let base = saved_idx.map_or(&empty, |i| &saved[i].cells);
+            let mut cells = BTreeMap::new();
+            for (pos, content) in sheet.cells.iter() {
+                if base.get(pos) != Some(content) {
+                    cells.insert(*pos, Some(content.clone()));
+                }
+            }
+            for pos in base.keys() {
+                if !sheet.cells.contains_key(pos) {
+                    cells.insert(*pos, None);
+                }
+            }
+            SheetDiff {
+                saved_idx,
+                name: sheet.name.clone(),
+                cells,
+            }
+        })
+        .collect();
+    CheckpointDiff { sheets }
+}
+
+/// The inverse of [`diff`]: the checkpoint's content, given the content the
+/// diff was taken against.
+pub fn apply(saved: &[SheetContent], diff: &CheckpointDiff) -> Vec<SheetContent> {
+    diff.sheets
+        .iter()
+        .map(|sheet| {
+            let base = sheet.saved_idx.and_then(|i| saved.get(i));
+            let mut cells = base.map(|s| s.cells.clone()).unwrap_or_default();
+            for (pos, content) in sheet.cells.iter() {
+                match content {
+                    Some(c) => cells.insert(*pos, c.clone()),
+                    None => cells.remove(pos),
+                };
+            }
+            SheetContent {
+                id: base.and_then(|s| s.id),
+                name: sheet.name.clone(),
+                cells,
+            }
+        })
+        .collect()
+}
+
+/// The edits that turn a workbook whose sheets are `saved_names` into the
+/// checkpoint: give its sheets their names at the checkpoint, create the
+/// missing ones, write its cells, delete the sheets it did not have and put
+/// the rest in its order. A sheet whose name another sheet takes back is
+/// first moved to a free name, so no two sheets share a name in between.
+///
+/// Inputs skip recognition: strings are written quoted and numbers in
+/// their shortest form, so each cell reads back as it was. An error value
+/// is written as its text, which a cell input reads as that error.
+pub fn restore_payloads(saved_names: &[String], diff: &CheckpointDiff) -> Vec<EditPayload> {
+    let mut names = saved_names.to_vec();
+    // For each sheet of the workbook, the checkpoint sheet it becomes.
+    let mut targets: Vec<Option<usize>> = (0..names.len())
+        .map(|idx| diff.sheets.iter().position(|s| s.saved_idx == Some(idx)))
+        .collect();
+    let mut payloads = vec![];
+    let rename = |idx: usize, new_name: String| {
+        EditPayload::SheetRename(SheetRename {
+            old_name: None,
+            idx: Some(idx),
+            new_name,
+        })
+    };
+
+    let wanted: HashSet<&String> = diff.sheets.iter().map(|s| &s.name).collect();
+    let mut taken: HashSet<String> = names
+        .iter()
+        .chain(wanted.iter().copied())
+        .cloned()
+        .collect();
+    for idx in 0..names.len() {
+        let keeps_name = targets[idx].is_some_and(|t| diff.sheets[t].name == names[idx]);
+        if wanted.contains(&names[idx]) && !keeps_name {
+            let free = (0..)
+                .map(|n| format!("Restore{}", n))
+                .find(|n| !taken.contains(n))
+                .unwrap();
+            taken.insert(free.clone());
+            payloads.push(rename(idx, free.clone()));
+            names[idx] = free;
+        }
+    }
+    for idx in 0..names.len() {
+        if let Some(t) = targets[idx]
+            && diff.sheets[t].name != names[idx]
+        {
+            payloads.push(rename(idx, diff.sheets[t].name.clone()));
+            names[idx] = diff.sheets[t].name.clone();
+        }
+    }
+    for (t, sheet) in diff.sheets.iter().enumerate() {
+        if !targets.contains(&Some(t)) {
+            payloads.push(EditPayload::CreateSheet(CreateSheet {
+                idx: names.len(),
+                new_name: sheet.name.clone(),
+            }));
+            names.push(sheet.name.clone());
+            targets.push(Some(t));
+        }
+    }
+
+    for (t, sheet) in diff.sheets.iter().enumerate() {
+        let sheet_idx = targets.iter().position(|x| *x == Some(t)).unwrap();
+        for ((row, col), content) in sheet.cells.iter() {
+            let (row, col) = (*row, *col);
+            payloads.push(match content {
+                None => EditPayload::CellClear(CellClear {
+                    sheet_idx,
+                    row,
+                    col,
+                }),
+                Some(c) => EditPayload::CellInput(CellInput {
+                    sheet_idx,
+                    row,
+                    col,
+                    content: input_of(c),
+                    skip_recognition: Some(true),
+                }),
+            });
+        }
+    }
+    for idx in (0..targets.len()).rev() {
+        if targets[idx].is_none() {
+            payloads.push(EditPayload::DeleteSheet(DeleteSheet { idx }));
+            targets.remove(idx);
+        }
+    }
+    for to in 0..diff.sheets.len() {
+        let from = targets.iter().position(|x| *x == Some(to)).unwrap();
+        if from != to {
+            payloads.push(EditPayload::MoveSheet(MoveSheet { from, to }));
+            let t = targets.remove(from);
+            targets.insert(to, t);
+        }
+    }
+    payloads
+}
+
+fn input_of(content: &CellContent) -> String {
+    match content {
+        CellContent::Formula(f) => format!("={}", f),
+        CellContent::Number(n) => n.to_string(),
+        CellContent::Str(s) => format!("'{}", s),
+        CellContent::Bool(b) => if *b { "TRUE" } else { "FALSE" }.to_string(),
+        CellContent::Error(e) => e.clone(),
+    }
+}
+
+pub fn diff_to_xml(diff: &CheckpointDiff) -> Vec<CheckpointSheetXml> {
+    diff.sheets
+        .iter()
+        .map(|sheet| CheckpointSheetXml {
+            saved_idx: sheet.saved_idx,
+            name: sheet.name.clone(),
+            cells: sheet
+                .cells
+                .iter()
+                .map(|((row, col), content)| {
+                    let (t, v) = match content {
+                        None => (None, None),
+                        Some(CellContent::Formula(f)) => (Some("f"), Some(f.clone())),
+                        Some(CellContent::Number(n)) => (Some("n"), Some(n.to_string())),
+                        Some(CellContent::Str(s)) => (Some("s"), Some(s.clone())),
+                        Some(CellContent::Bool(b)) => {
+                            (Some("b"), Some(if *b { "1" } else { "0" }.to_string()))
+                        }
+                        Some(CellContent::Error(e)) => (Some("e"), Some(e.clone())),
+                    };
+                    CheckpointCellXml {
+                        row: *row,
+                        col: *col,
+                        t: t.map(String::from),
+                        v,
+                    }
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+/// Cells with an unknown type or an unreadable number are dropped.
+pub fn diff_from_xml(sheets: Vec<CheckpointSheetXml>) -> CheckpointDiff {
+    let sheets = sheets
+        .into_iter()
+        .map(|sheet| SheetDiff {
+            saved_idx: sheet.saved_idx,
+            name: sheet.name,
+            cells: sheet
+                .cells
+                .into_iter()
+                .filter_map(|c| {
+                    let v = c.v.unwrap_or_default();
+                    let content = match c.t.as_deref() {
+                        None => None,
+                        Some("f") => Some(CellContent::Formula(v)),
+                        Some("n") => Some(CellContent::Number(v.parse().ok()?)),
+                        Some("s") => Some(CellContent::Str(v)),
+                        Some("b") => Some(CellContent::Bool(v == "1")),
+                        Some("e") => Some(CellContent::Error(v)),
+                        Some(_) => return None,
+                    };
+                    Some(((c.row, c.col), content))
+                })
+                .collect(),
+        })
+        .collect();
+    CheckpointDiff { sheets }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sheet(id: SheetId, name: &str, cells: Cells) -> SheetContent {
+        SheetContent {
+            id: Some(id),
+            name: name.to_string(),
+            cells: cells.into_iter().collect(),
+        }
+    }
+
+    fn renamed_to(p: &EditPayload) -> Option<(usize, &str)> {
+        match p {
+            EditPayload::SheetRename(r) => Some((r.idx?, r.new_name.as_str())),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn diff_then_apply_gives_back_the_checkpoint() {
+        let saved = vec![
+            sheet(1, "A", vec![((0, 0), CellContent::Number(1.0))]),
+            sheet(2, "Gone", vec![]),
+        ];
+        // Sheet 1 was called "First" at the checkpoint.
+        let checkpoint = vec![
+            sheet(3, "New", vec![((1, 1), CellContent::Str("x".into()))]),
+            sheet(
+                1,
+                "First",
+                vec![((0, 1), CellContent::Formula("A1+1".into()))],
+            ),
+        ];
+        let d = diff(&saved, &checkpoint);
+        let names: Vec<_> = d.sheets.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["New", "First"]);
+        assert_eq!(d.sheets[0].saved_idx, None);
+        assert_eq!(d.sheets[1].saved_idx, Some(0));
+        assert_eq!(d.sheets[1].cells.get(&(0, 0)), Some(&None));
+
+        let d = diff_from_xml(diff_to_xml(&d));
+        let back = apply(&saved, &d);
+        assert_eq!(back.len(), 2);
+        assert_eq!(back[0].cells, checkpoint[0].cells);
+        assert_eq!(back[1].cells, checkpoint[1].cells);
+        assert_eq!(back[1].id, Some(1));
+
+        let payloads = restore_payloads(&["A".to_string(), "Gone".to_string()], &d);
+        assert_eq!(renamed_to(&payloads[0]), Some((0, "First")));
+        assert!(matches!(&payloads[1], EditPayload::CreateSheet(c) if c.idx == 2));
+        assert!(
+            payloads
+                .iter()
+                .any(|p| matches!(p, EditPayload::DeleteSheet(d) if d.idx == 1))
+        );
+        assert!(matches!(
+            payloads.last(),
+            Some(EditPayload::MoveSheet(m)) if m.from == 1 && m.to == 0
+        ));
+    }
+
+    #[test]
+    fn swapped_names_go_through_free_names() {
+        let saved = vec![sheet(1, "X", vec![]), sheet(2, "Y", vec![])];
+        let checkpoint = vec![sheet(1, "Y", vec![]), sheet(2, "X", vec![])];
+        let d = diff(&saved, &checkpoint);
+        let payloads = restore_payloads(&["X".to_string(), "Y".to_string()], &d);
+        let renames: Vec<_> = payloads.iter().filter_map(renamed_to).collect();
+        assert_eq!(
+            renames,
+            vec![(0, "Restore0"), (1, "Restore1"), (0, "Y"), (1, "X")]
+        );
+        assert_eq!(payloads.len(), 4);
+    }
+}
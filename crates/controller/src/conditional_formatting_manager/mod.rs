@@ -27,6 +27,8 @@ use imbl::{HashMap, Vector};
 use logisheets_base::{CellId, ColId, RowId, SheetId};
 use logisheets_workbook::prelude::CtCfRule;
 
+use crate::cell_shift::RectMoves;
+
 /// One `sqref` rectangle, anchored on stable ids so it tracks row/column
 /// insertion and deletion the way Excel does.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -218,4 +220,61 @@ impl ConditionalFormattingManager {
         }
         false
     }
+
+    /// Re-anchor the rectangles of a cell shift. A rule whose ranges are all
+    /// deleted goes with them.
+    pub fn shift_cells(&mut self, sheet_id: SheetId, rects: &RectMoves) {
+        let Some(blocks) = self.data.get(&sheet_id) else {
+            return;
+        };
+        let blocks: Vector<CfBlock> = blocks
+            .iter()
+            .cloned()
+            .filter_map(|mut b| {
+                b.ranges = b
+                    .ranges
+                    .into_iter()
+                    .filter_map(|r| match r {
+                        CfRange::Rect(CellId::NormalCell(s), CellId::NormalCell(e)) => {
+                            match rects.get(&(s, e)) {
+                                None => Some(r),
+                                Some(None) => None,
+                                Some(Some((s, e))) => Some(CfRange::Rect(
+                                    CellId::NormalCell(*s),
+                                    CellId::NormalCell(*e),
+                                )),
+                            }
+                        }
+                        r => Some(r),
+                    })
+                    .collect();
+                (!b.ranges.is_empty()).then_some(b)
+            })
+            .collect();
+        self.set_sheet(sheet_id, blocks);
+    }
+
+    /// Copy the rules of sheet `from` to sheet `to`, minting new rule ids.
+    /// Ranges are id-based and row/col ids are per-sheet, so they carry over
+    /// as-is to a copied navigator.
+    pub fn duplicate_sheet(&mut self, from: SheetId, to: SheetId) {
+        let Some(blocks) = self.data.get(&from).cloned() else {
+            return;
+        };
+        let blocks = blocks
+            .into_iter()
+            .map(|mut b| {
+                b.rules = b
+                    .rules
+                    .into_iter()
+                    .map(|r| CfRule {
+                        id: self.mint_rule_id(),
+                        rule: r.rule,
+                    })
+                    .collect();
+                b
+            })
+            .collect();
+        self.set_sheet(to, blocks);
+    }
 }
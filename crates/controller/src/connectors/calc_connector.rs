@@ -7,6 +7,7 @@ use logisheets_base::get_curr_addr::GetCurrAddrTrait;
 use logisheets_base::set_curr_cell::SetCurrCellTrait;
 use logisheets_base::{
     Addr, CellId, CellValue, Error, FuncId, NameId, SheetId, TextId,
+    cube_value::CubeValue,
     matrix_value::{MatrixValue, cross_product_usize},
 };
 use logisheets_base::{BlockCellId, BlockId, BlockRange, CubeCross, NormalRange, Range};
@@ -360,15 +361,9 @@ impl<'a> Connector for CalcConnector<'a> {
                 let sheet_id = r.sheet;
                 match r.reference {
                     Reference::Addr(addr) => match r.from_sheet {
-                        Some(from_sheet) => {
-                            let sheets = self.get_sheet_ids(from_sheet, sheet_id);
-                            sheets.into_iter().for_each(|s| {
-                                let _value = self.get_sheet_calc_range_value(
-                                    s, addr.row, addr.col, addr.row, addr.col,
-                                );
-                            });
-                            todo!()
-                        }
+                        Some(from_sheet) => self.get_cube_calc_value(
+                            from_sheet, sheet_id, addr.row, addr.col, addr.row, addr.col,
+                        ),
                         None => self.get_sheet_calc_range_value(
                             sheet_id, addr.row, addr.col, addr.row, addr.col,
                         ),
@@ -386,7 +381,9 @@ impl<'a> Connector for CalcConnector<'a> {
                         CalcValue::Range(v)
                     }
                     Reference::Range(start, end) => match r.from_sheet {
-                        Some(_) => todo!(),
+                        Some(from_sheet) => self.get_cube_calc_value(
+                            from_sheet, sheet_id, start.row, start.col, end.row, end.col,
+                        ),
                         None => self.get_sheet_calc_range_value(
                             sheet_id, start.row, start.col, end.row, end.col,
                         ),
@@ -533,7 +530,8 @@ impl<'a> CalcConnector<'a> {
         let mut result: Vec<SheetId> = Vec::new();
         match (start_idx, end_idx) {
             (Some(s_idx), Some(e_idx)) => {
-                (s_idx..e_idx + 1).into_iter().for_each(|i| {
+                let (lo, hi) = (s_idx.min(e_idx), s_idx.max(e_idx));
+                (lo..hi + 1).for_each(|i| {
                     if let Some(id) = self.sheet_pos_manager.get_sheet_id(i) {
                         result.push(id)
                     }
@@ -544,6 +542,29 @@ impl<'a> CalcConnector<'a> {
         }
     }
 
+    /// The same block of cells on every sheet from `start` to `end`, by the
+    /// sheets' current tab order — so a 3D reference follows sheets moved into
+    /// or out of its span.
+    fn get_cube_calc_value(
+        &mut self,
+        start: SheetId,
+        end: SheetId,
+        start_row: usize,
+        start_col: usize,
+        end_row: usize,
+        end_col: usize,
+    ) -> CalcValue {
+        let mut data = Vec::new();
+        for s in self.get_sheet_ids(start, end) {
+            match self.get_sheet_calc_range_value(s, start_row, start_col, end_row, end_col) {
+                CalcValue::Range(m) => data.push(m),
+                CalcValue::Scalar(v) => data.push(MatrixValue::from(vec![vec![v]])),
+                _ => return CalcValue::Scalar(Value::Error(ast::Error::Ref)),
+            }
+        }
+        CalcValue::Cube(CubeValue::new(data))
+    }
+
     fn get_matrix_between_rows(
         &mut self,
         sheet_id: SheetId,
@@ -279,6 +279,10 @@ impl<'a> ContainerExecCtx for ContainerConnector<'a> {
         self.style_manager.insert_style(style)
     }
 
+    fn named_style_id(&mut self, name: &str) -> Result<StyleId, Error> {
+        self.style_manager.named_style_id(name)
+    }
+
     fn is_block_cell_templated(
         &self,
         sheet_id: SheetId,
@@ -288,4 +292,20 @@ impl<'a> ContainerExecCtx for ContainerConnector<'a> {
             .formula_for_block_cell(sheet_id, cell)
             .is_some()
     }
+
+    fn block_cell_field_type(
+        &self,
+        sheet_id: SheetId,
+        cell: &BlockCellId,
+    ) -> Option<(crate::edit_action::FieldType, Option<regex::Regex>)> {
+        self.block_schema_manager
+            .field_type_for_block_cell(sheet_id, cell)
+    }
+
+    fn block_key_cells(&self, ref_name: &str) -> Option<(SheetId, Vec<BlockCellId>)> {
+        let bp_fetcher =
+            |sid: &SheetId, bid: &BlockId| self.navigator.get_block_place(sid, bid).ok();
+        self.block_schema_manager
+            .get_all_key_cell_ids(ref_name, &bp_fetcher)
+    }
 }
@@ -5,8 +5,13 @@ use logisheets_base::{
     index_fetcher::IndexFetcherTrait,
 };
 
+use regex::Regex;
+
 use crate::style_manager::RawStyle;
-use crate::{Error, edit_action::StyleUpdateType};
+use crate::{
+    Error,
+    edit_action::{FieldType, StyleUpdateType},
+};
 
 pub trait ContainerExecCtx:
     IdFetcherTrait + IndexFetcherTrait + BlockAffectTrait + SheetIdFetcherByIdxTrait
@@ -21,10 +26,25 @@ pub trait ContainerExecCtx:
 
     fn insert_style(&mut self, style: RawStyle) -> Result<StyleId, Error>;
 
+    /// The style id of a cell formatted with, and linked to, a named style.
+    fn named_style_id(&mut self, name: &str) -> Result<StyleId, Error>;
+
     /// True if the given block cell sits in a field carrying a
     /// value-formula template. The container executor uses this to
     /// skip writing the user's raw `BlockInput.input` content into the
     /// cell — the formula manager will populate the cell from the
     /// schema's template instead.
     fn is_block_cell_templated(&self, sheet_id: SheetId, cell: &BlockCellId) -> bool;
+
+    /// The type of the field the block cell sits in, if it is typed, with
+    /// the pattern compiled when it was bound.
+    fn block_cell_field_type(
+        &self,
+        sheet_id: SheetId,
+        cell: &BlockCellId,
+    ) -> Option<(FieldType, Option<Regex>)>;
+
+    /// The key cells of the block bound as `ref_name`, used to check that a
+    /// reference field names one of them.
+    fn block_key_cells(&self, ref_name: &str) -> Option<(SheetId, Vec<BlockCellId>)>;
 }
@@ -5,8 +5,9 @@ use logisheets_base::{
 
 use crate::{
     Error,
+    block_manager::schema_manager::field_type::{TypedInput, parse_input},
     cell::Cell,
-    edit_action::{CellStyleUpdate, EditPayload},
+    edit_action::{CellStyleUpdate, EditPayload, FieldKind},
     utils::resize_rect_diff,
 };
 
@@ -27,6 +28,34 @@ impl ContainerExecutor {
         }
     }
 
+    /// Whether `key` is a key of the block bound as `ref_name`.
+    fn check_block_key<C: ContainerExecCtx>(
+        &self,
+        ctx: &mut C,
+        ref_name: &str,
+        key: &str,
+    ) -> Result<(), String> {
+        let (sheet_id, key_cells) = ctx
+            .block_key_cells(ref_name)
+            .ok_or_else(|| format!("unknown block ref {:?}", ref_name))?;
+        let key_id = ctx.fetch_text_id(key);
+        let found = key_cells.into_iter().any(|k| {
+            match self.container.get_cell(sheet_id, &CellId::BlockCell(k)) {
+                Some(cell) => match &cell.value {
+                    CellValue::String(t) => *t == key_id,
+                    CellValue::Number(n) => key.parse::<f64>() == Ok(*n),
+                    _ => false,
+                },
+                None => false,
+            }
+        });
+        if found {
+            Ok(())
+        } else {
+            Err(format!("{:?} is not a key of {:?}", key, ref_name))
+        }
+    }
+
     pub fn execute<C: ContainerExecCtx>(
         mut self,
         ctx: &mut C,
@@ -49,8 +78,36 @@ impl ContainerExecutor {
                 if ctx.is_block_cell_templated(sheet_id, &cell_id) {
                     return Ok((self, false));
                 }
-                let cell_value =
-                    CellValue::from_string(p.input, &mut |t| -> TextId { ctx.fetch_text_id(t) });
+                let field_type = ctx
+                    .block_cell_field_type(sheet_id, &cell_id)
+                    .filter(|_| !p.input.starts_with('='));
+                let cell_value = match field_type {
+                    Some((ty, pattern)) => {
+                        let typed =
+                            parse_input(&ty, pattern.as_ref(), &p.input).and_then(|typed| {
+                                match (&ty.ref_name, &typed) {
+                                    (Some(ref_name), TypedInput::Text(key))
+                                        if ty.kind == FieldKind::Reference =>
+                                    {
+                                        self.check_block_key(ctx, ref_name, key)?;
+                                        Ok(typed)
+                                    }
+                                    _ => Ok(typed),
+                                }
+                            });
+                        typed
+                            .map_err(|e| {
+                                Error::PayloadError(format!(
+                                    "block {} cell ({}, {}): {}",
+                                    p.block_id, p.row, p.col, e
+                                ))
+                            })?
+                            .into_cell_value(&mut |t| -> TextId { ctx.fetch_text_id(t) })
+                    }
+                    None => {
+                        CellValue::from_string(p.input, &mut |t| -> TextId { ctx.fetch_text_id(t) })
+                    }
+                };
                 self.container
                     .update_value(sheet_id, CellId::BlockCell(cell_id), cell_value);
                 self.value_changed
@@ -233,8 +290,12 @@ impl ContainerExecutor {
                 let sheet_id = ctx
                     .fetch_sheet_id_by_index(p.sheet_idx)
                     .map_err(|l| BasicError::SheetIdxExceed(l))?;
-                let cell_value =
-                    CellValue::from_string(p.content, &mut |t| -> TextId { ctx.fetch_text_id(t) });
+                let cell_value = match logisheets_base::Error::parse(&p.content) {
+                    Some(e) => CellValue::Error(e),
+                    None => CellValue::from_string(p.content, &mut |t| -> TextId {
+                        ctx.fetch_text_id(t)
+                    }),
+                };
                 let cell_id = ctx.fetch_cell_id(&sheet_id, p.row, p.col)?;
                 self.container.update_value(sheet_id, cell_id, cell_value);
                 self.value_changed.push((sheet_id, cell_id));
@@ -293,6 +354,21 @@ impl ContainerExecutor {
                 info.ht = Some(p.height);
                 Ok((self, true))
             }
+            EditPayload::SetVisible(p) => {
+                let sheet_id = ctx
+                    .fetch_sheet_id_by_index(p.sheet_idx)
+                    .map_err(BasicError::SheetIdxExceed)?;
+                if p.is_row {
+                    let row_id = ctx.fetch_row_id(&sheet_id, p.start)?;
+                    let info = self.container.get_row_info_mut(sheet_id, row_id);
+                    info.hidden = !p.visible;
+                } else {
+                    let col_id = ctx.fetch_col_id(&sheet_id, p.start)?;
+                    let info = self.container.get_col_info_mut(sheet_id, col_id);
+                    info.hidden = !p.visible;
+                }
+                Ok((self, true))
+            }
             EditPayload::DeleteSheet(p) => {
                 let sheet_id = ctx
                     .fetch_sheet_id_by_index(p.idx)
@@ -718,6 +794,58 @@ impl ContainerExecutor {
                 }
                 Ok((self, true))
             }
+            EditPayload::SetCellStyles(p) => {
+                let sheet_id = ctx
+                    .fetch_sheet_id_by_index(p.sheet_idx)
+                    .map_err(BasicError::SheetIdxExceed)?;
+                let width = p.col_count.max(1);
+                for (i, style) in p.styles.into_iter().enumerate() {
+                    let Some(style) = style else {
+                        continue;
+                    };
+                    let row = p.start_row + i / width;
+                    let col = p.start_col + i % width;
+                    let cell_id = ctx.fetch_cell_id(&sheet_id, row, col)?;
+                    let id = ctx.insert_style(style)?;
+                    if let Some(c) = self.container.get_cell_mut(sheet_id, &cell_id) {
+                        c.style = id;
+                    } else {
+                        self.container.add_cell(
+                            sheet_id,
+                            cell_id,
+                            Cell {
+                                value: CellValue::Blank,
+                                style: id,
+                            },
+                        );
+                    }
+                }
+                Ok((self, true))
+            }
+            EditPayload::ApplyCellStyle(p) => {
+                let sheet_id = ctx
+                    .fetch_sheet_id_by_index(p.sheet_idx)
+                    .map_err(BasicError::SheetIdxExceed)?;
+                let id = ctx.named_style_id(&p.name)?;
+                for row in p.start_row..=p.end_row {
+                    for col in p.start_col..=p.end_col {
+                        let cell_id = ctx.fetch_cell_id(&sheet_id, row, col)?;
+                        if let Some(c) = self.container.get_cell_mut(sheet_id, &cell_id) {
+                            c.style = id;
+                        } else {
+                            self.container.add_cell(
+                                sheet_id,
+                                cell_id,
+                                Cell {
+                                    value: CellValue::Blank,
+                                    style: id,
+                                },
+                            );
+                        }
+                    }
+                }
+                Ok((self, true))
+            }
             EditPayload::LineFormatBrush(p) => {
                 let sheet_id = ctx
                     .fetch_sheet_id_by_index(p.src_sheet_idx)
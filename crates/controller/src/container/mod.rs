@@ -1,4 +1,5 @@
 use crate::cell::Cell;
+use crate::cell_shift::{CellMoves, normal_cell, take_moving};
 use crate::controller::display::BlockField;
 use block_line_info_manager::{BlockLineInfo, BlockLineInfoManager};
 use imbl::hashmap::HashMap;
@@ -41,6 +42,44 @@ impl DataContainer {
         res
     }
 
+    /// Copy the cells and line infos of sheet `from` to sheet `to`. Ephemeral
+    /// cells are not positioned on the sheet and stay behind.
+    pub fn duplicate_sheet(&mut self, from: SheetId, to: SheetId) {
+        let Some(mut container) = self.data.get(&from).cloned() else {
+            return;
+        };
+        container
+            .cells
+            .retain(|id, _| !matches!(id, CellId::EphemeralCell(_)));
+        self.data.insert(to, container);
+    }
+
+    /// Move the cells of sheet `from` to their new ids on sheet `to`, which
+    /// may be the same sheet. Returns each moved cell's old id with its new
+    /// one (`None` when it was deleted).
+    pub fn move_cells(
+        &mut self,
+        from: SheetId,
+        to: SheetId,
+        moves: &CellMoves,
+    ) -> Vec<(CellId, Option<CellId>)> {
+        let Some(container) = self.data.get_mut(&from) else {
+            return vec![];
+        };
+        let taken = take_moving(&mut container.cells, moves, normal_cell);
+        let container = self.get_sheet_container_mut(to);
+        taken
+            .into_iter()
+            .map(|(old, new, cell)| {
+                let new = new.map(CellId::NormalCell);
+                if let Some(new) = new {
+                    container.cells.insert(new, cell);
+                }
+                (old, new)
+            })
+            .collect()
+    }
+
     pub fn get_cell(&self, sheet_id: SheetId, cell_id: &CellId) -> Option<&Cell> {
         let container = self.get_sheet_container(sheet_id)?;
         container.cells.get(cell_id)
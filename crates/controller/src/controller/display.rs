@@ -168,6 +168,8 @@ pub struct BlockSchemaFieldEntry {
     /// `ShadowKind::UserEditable` shadow per row at bind / insert time;
     /// host permission layer reads the shadow to gate writes.
     pub editability_formula: Option<String>,
+    /// The field's type, or `None` for free-form fields.
+    pub field_type: Option<crate::edit_action::FieldType>,
 }
 
 #[derive(Debug, Clone, Default, TS)]
@@ -280,9 +282,10 @@ pub struct ChartInfo {
     pub val_axis_title: Option<String>,
 }
 
-/// A person referenced by a comment (author or mention). Enterprise builds
-/// populate `user_id` + `provider_id` from their directory; the `src` app
-/// leaves them `None` and only sets `display_name`.
+/// A person referenced by a comment (author or mention), or the author of
+/// an audited change. Enterprise builds populate `user_id` + `provider_id`
+/// from their directory; the `src` app leaves them `None` and only sets
+/// `display_name`.
 #[derive(Debug, Clone, TS)]
 #[ts(file_name = "comment_person.ts", rename_all = "camelCase")]
 pub struct CommentPerson {
@@ -383,7 +386,7 @@ pub fn get_default_col_width() -> f64 {
     8.43
 }
 
-#[derive(Debug, Clone, Default, TS)]
+#[derive(Debug, Clone, Default, PartialEq, TS)]
 #[ts(file_name = "value.ts", tag = "type")]
 pub enum Value {
     Str(String),
@@ -450,6 +453,73 @@ pub struct TempStatusDiff {
     pub cells: Vec<TempCellChange>,
 }
 
+/// A cell changed by a committed transaction, as the audit log recorded it.
+/// Formulas are without the leading `=`, empty for a plain value.
+/// `recalculated` marks a formula cell whose value changed only because
+/// something it depends on did.
+#[derive(Debug, Clone, TS)]
+#[ts(file_name = "cell_change.ts", rename_all = "camelCase")]
+pub struct CellChange {
+    pub sheet_name: String,
+    pub row: usize,
+    pub col: usize,
+    pub old_value: Value,
+    pub new_value: Value,
+    pub old_formula: String,
+    pub new_formula: String,
+    pub recalculated: bool,
+}
+
+/// One committed transaction in the audit log. `seq` is its position in
+/// the workbook's change feed; `payloads` names the payloads it ran
+/// (`CellInput`, `Undo`, ...). The author and timestamp are whatever the
+/// host last stamped the log with.
+#[derive(Debug, Clone, TS)]
+#[ts(file_name = "change_entry.ts", rename_all = "camelCase")]
+pub struct ChangeEntry {
+    pub seq: usize,
+    pub author: Option<CommentPerson>,
+    pub timestamp: Option<String>,
+    pub payloads: Vec<String>,
+    pub changes: Vec<CellChange>,
+}
+
+/// A what-if branch, or `main`. `edits` counts the transactions a merge
+/// would replay; it is 0 for `main`.
+#[derive(Debug, Clone, TS)]
+#[ts(file_name = "branch_info.ts", rename_all = "camelCase")]
+pub struct BranchInfo {
+    pub name: String,
+    pub current: bool,
+    pub edits: usize,
+}
+
+/// A cell that both main and the branch changed since the fork, each to
+/// something else. Formulas are without the leading `=`.
+#[derive(Debug, Clone, TS)]
+#[ts(file_name = "merge_conflict.ts", rename_all = "camelCase")]
+pub struct MergeConflict {
+    pub sheet_name: String,
+    pub row: usize,
+    pub col: usize,
+    pub base_value: Value,
+    pub main_value: Value,
+    pub branch_value: Value,
+    pub base_formula: String,
+    pub main_formula: String,
+    pub branch_formula: String,
+}
+
+/// The outcome of merging a branch into main. A merge with conflicts is
+/// only carried out when forced, and then reports the conflicts it
+/// overrode.
+#[derive(Debug, Clone, TS)]
+#[ts(file_name = "merge_result.ts", rename_all = "camelCase")]
+pub struct MergeResult {
+    pub merged: bool,
+    pub conflicts: Vec<MergeConflict>,
+}
+
 #[derive(Debug, Clone, TS)]
 #[ts(file_name = "block_field.ts", rename_all = "camelCase")]
 pub struct BlockField {
@@ -1,20 +1,27 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, HashSet, VecDeque};
 
 use logisheets_base::{
-    Addr, BlockId, BlockRange, CellId, ColId, CubeId, NormalRange, Range, RangeId, RowId, SheetId,
-    errors::BasicError,
+    Addr, BlockCellId, BlockId, BlockRange, CellId, CellValue, ColId, CubeId, NormalCellId,
+    NormalRange, Range, RangeId, RowId, SheetId, errors::BasicError,
 };
+use logisheets_parser::unparse;
+use regex::Regex;
 
 use crate::{
     Error,
+    api::read_display_value,
     async_func_manager::AsyncFuncManager,
     block_manager::field_manager::executor::FieldRenderExecutor,
     block_manager::schema_manager::executor::BlockSchemaExecutor,
+    block_manager::schema_manager::field_type::parse_input,
+    block_manager::schema_manager::records::parse_records,
+    block_manager::schema_manager::schema::{FieldEntry, Schema},
     calc_engine::CalcEngine,
     cell_attachments::executor::CellAttachmentsExecutor,
+    cell_shift::{CellMove, CellMoves, CellShift, RectMove, RectMoves, normal_cell},
     chart_manager::ChartExecutor,
     checkpoint_manager::CheckpointManager,
-    conditional_formatting_manager::executor::ConditionalFormattingExecutor,
+    conditional_formatting_manager::{CfRange, executor::ConditionalFormattingExecutor},
     connectors::{
         BlockSchemaConnector, CalcConnector, CellAttachmentsConnector, ContainerConnector,
         CubeConnector, ExclusiveConnector, FormulaConnector, NavigatorConnector, RangeConnector,
@@ -22,18 +29,28 @@ use crate::{
     },
     container::ContainerExecutor,
     cube_manager::executors::CubeExecutor,
-    edit_action::{EditPayload, PayloadsAction, SheetRename},
+    data_table_manager::{executor::DataTableExecutor, resolve},
+    edit_action::{
+        BlockInput, CellInput, CellStyleUpdate, CreateScenarioSummary, CreateSheet, DuplicateSheet,
+        EditPayload, FieldType, ImportBlockRecords, InsertColsInBlock, InsertRowsInBlock,
+        MoveRange, PayloadsAction, SheetRename, ShowScenario, StyleUpdateType,
+    },
     exclusive::executor::ExclusiveManagerExecutor,
     formula_manager::{FormulaExecutor, Vertex},
     image_manager::ImageExecutor,
+    input_recognition::{Recognized, recognize},
     navigator::{NavExecutor, Navigator},
     range_manager::RangeExecutor,
-    settings::CalcConfig,
+    range_move::RangeMove,
+    scenario_manager::{Scenario, executor::ScenarioExecutor},
+    settings::{CalcConfig, InputLocale},
     sid_assigner::ShadowIdAssigner,
+    sqref::col_to_letters,
     version_manager::VersionManager,
     workbook::sheet_info_manager::SheetInfoManager,
 };
 
+use super::display::Value;
 use super::status::Status;
 
 pub struct Executor<'a> {
@@ -49,6 +66,7 @@ pub struct Executor<'a> {
     pub book_name: &'a str,
     pub calc_config: CalcConfig,
     pub async_funcs: &'a HashSet<String>,
+    pub input_locale: &'a InputLocale,
     pub updated_cells: HashSet<(SheetId, CellId)>,
     pub cells_removed: HashSet<(SheetId, CellId)>,
     pub style_updated: HashSet<(SheetId, CellId)>,
@@ -69,12 +87,14 @@ pub struct Executor<'a> {
 
 impl<'a> Executor<'a> {
     pub fn execute_and_calc(self, payload_action: PayloadsAction) -> Result<Self, Error> {
+        let tables_before = data_table_layouts(&self.status);
         let mut result = self;
         for payload in payload_action.clone().payloads.into_iter() {
+            result.refuse_data_table_edit(&payload)?;
             result = result.execute_payload(payload)?;
         }
 
-        let result = result.calc()?;
+        let result = result.calc()?.refresh_data_tables(&tables_before)?;
 
         if payload_action.init {
             result
@@ -93,6 +113,16 @@ impl<'a> Executor<'a> {
     fn execute_payload(self, payload: EditPayload) -> Result<Self, Error> {
         let mut result = self;
 
+        let recognized = match &payload {
+            EditPayload::CellInput(input) => result
+                .recognize_input(input)
+                .map(|recognized| (input.clone(), recognized)),
+            _ => None,
+        };
+        if let Some((input, recognized)) = recognized {
+            return result.execute_recognized_input(input, recognized);
+        }
+
         // RestoreCheckpoint replaces the entire Status with a previously-
         // saved snapshot. Bypasses the per-manager pipeline because
         // there's nothing to incrementally update — the snapshot was
@@ -114,6 +144,49 @@ impl<'a> Executor<'a> {
             return Ok(result);
         }
 
+        // Named cell styles live only in the style manager. Modifying or
+        // deleting one restyles its linked cells in place, on any sheet,
+        // so the whole tx counts as a cell update. Theme edits likewise
+        // change how every theme colour and font resolves.
+        match payload {
+            EditPayload::CreateCellStyle(p) => {
+                result
+                    .status
+                    .style_manager
+                    .create_named_style(p.name, p.ty)?;
+                return Ok(result);
+            }
+            EditPayload::ModifyCellStyle(p) => {
+                result
+                    .status
+                    .style_manager
+                    .modify_named_style(&p.name, p.ty)?;
+                result.cell_updated = true;
+                return Ok(result);
+            }
+            EditPayload::DeleteCellStyle(p) => {
+                result.status.style_manager.delete_named_style(&p.name)?;
+                result.cell_updated = true;
+                return Ok(result);
+            }
+            EditPayload::SetTheme(_)
+            | EditPayload::ApplyBuiltinTheme(_)
+            | EditPayload::SetThemeColor(_)
+            | EditPayload::SetThemeFonts(_) => {
+                let status = &mut result.status;
+                let renamed = status.theme_manager.execute_payload(payload)?;
+                for (scheme, old, new) in renamed {
+                    status
+                        .style_manager
+                        .font_manager
+                        .rename_scheme_fonts(&scheme, &old, &new);
+                }
+                result.cell_updated = true;
+                return Ok(result);
+            }
+            _ => {}
+        }
+
         if let EditPayload::SheetRename(rename) = payload {
             let manager = &mut result.status.sheet_id_manager;
             let SheetRename {
@@ -142,6 +215,22 @@ impl<'a> Executor<'a> {
             return Ok(result);
         }
 
+        if let EditPayload::DuplicateSheet(p) = payload {
+            return result.execute_duplicate_sheet(p);
+        }
+
+        if let EditPayload::ImportBlockRecords(p) = payload {
+            return result.execute_import_block_records(p);
+        }
+
+        if let EditPayload::ShowScenario(p) = payload {
+            return result.execute_show_scenario(p);
+        }
+
+        if let EditPayload::CreateScenarioSummary(p) = payload {
+            return result.execute_create_scenario_summary(p);
+        }
+
         // Deleting a sheet must also release its name from the sheet-id
         // manager so a later CreateSheet can reuse that name. The per-manager
         // delete executors below drop the sheet's data, position and nav
@@ -161,6 +250,17 @@ impl<'a> Executor<'a> {
             }
         }
 
+        // A range-scoped insert or delete, or a range move, keeps the
+        // navigator as it is and moves the cells instead. The ranges, cubes
+        // and blocks follow in the pipeline below; everything else keyed on
+        // cell ids moves here.
+        match &payload {
+            EditPayload::InsertCells(p) => result.shift_cells(p.sheet_idx, CellShift::from(p))?,
+            EditPayload::DeleteCells(p) => result.shift_cells(p.sheet_idx, CellShift::from(p))?,
+            EditPayload::MoveRange(p) => result.move_range(p)?,
+            _ => {}
+        }
+
         let old_navigator = result.status.navigator.clone();
         let (nav_executor, nav_updated) = result.execute_navigator(payload.clone())?;
 
@@ -193,6 +293,13 @@ impl<'a> Executor<'a> {
         let (cf_executor, cf_updated) = result.execute_conditional_formatting(payload.clone())?;
         result.status.conditional_formatting_manager = cf_executor.manager;
 
+        let (data_table_executor, data_table_updated) =
+            result.execute_data_table(payload.clone())?;
+        result.status.data_table_manager = data_table_executor.manager;
+
+        let (scenario_executor, scenario_updated) = result.execute_scenario(payload.clone())?;
+        result.status.scenario_manager = scenario_executor.manager;
+
         let mut dirty_ranges = range_executor.dirty_ranges;
         range_executor.removed_ranges.into_iter().for_each(|e| {
             dirty_ranges.insert(e);
@@ -257,6 +364,8 @@ impl<'a> Executor<'a> {
             || image_updated
             || chart_updated
             || cf_updated
+            || data_table_updated
+            || scenario_updated
             || result.updated_cells.len() > 0
             || result.cells_removed.len() > 0;
 
@@ -301,6 +410,7 @@ impl<'a> Executor<'a> {
                 external_links_manager: result.status.external_links_manager,
                 sheet_info_manager: result.status.sheet_info_manager,
                 style_manager: result.status.style_manager,
+                theme_manager: result.status.theme_manager,
                 cell_attachment_manager: result.status.cell_attachment_manager,
                 dirty_cells_next_round: result.status.dirty_cells_next_round,
                 exclusive_manager: result.status.exclusive_manager,
@@ -310,12 +420,15 @@ impl<'a> Executor<'a> {
                 chart_manager: result.status.chart_manager,
                 data_validation_manager: result.status.data_validation_manager,
                 conditional_formatting_manager: result.status.conditional_formatting_manager,
+                data_table_manager: result.status.data_table_manager,
+                scenario_manager: result.status.scenario_manager,
             },
             version_manager: result.version_manager,
             async_func_manager: result.async_func_manager,
             book_name: result.book_name,
             calc_config: result.calc_config,
             async_funcs: result.async_funcs,
+            input_locale: result.input_locale,
             dirty_vertices: dirty_vertices,
             updated_cells: result.updated_cells,
             cells_removed: result.cells_removed,
@@ -332,6 +445,714 @@ impl<'a> Executor<'a> {
         })
     }
 
+    fn recognize_input(&self, input: &CellInput) -> Option<Recognized> {
+        if input.skip_recognition == Some(true) || input.content.starts_with(['=', '\'']) {
+            return None;
+        }
+        recognize(&input.content, self.input_locale)
+    }
+
+    /// Store recognized input as its number and, if the cell still has the
+    /// General format, give it the number format the input was typed in.
+    /// Both happen in this transaction so a single undo reverts them.
+    fn execute_recognized_input(
+        self,
+        input: CellInput,
+        recognized: Recognized,
+    ) -> Result<Self, Error> {
+        let CellInput {
+            sheet_idx,
+            row,
+            col,
+            ..
+        } = input;
+        let general = self.has_general_format(sheet_idx, row, col);
+        let result = self.execute_payload(EditPayload::CellInput(CellInput {
+            sheet_idx,
+            row,
+            col,
+            content: recognized.value.to_string(),
+            skip_recognition: Some(true),
+        }))?;
+        match recognized.format {
+            Some(format) if general => {
+                result.execute_payload(EditPayload::CellStyleUpdate(CellStyleUpdate {
+                    sheet_idx,
+                    row,
+                    col,
+                    ty: StyleUpdateType {
+                        set_num_fmt: Some(format),
+                        ..Default::default()
+                    },
+                }))
+            }
+            _ => Ok(result),
+        }
+    }
+
+    /// Duplicate a sheet as a composite edit: create the new sheet, copy every
+    /// per-sheet manager's state across, then re-input the source's formulas
+    /// on the copy. Formulas go through their text so that the references the
+    /// source makes to itself come back pointing at the copy, and so that the
+    /// copies get their own vertices in the dependency graph. The text leaves
+    /// out the sheet name of those references, `Sheet1!A1` included, so they
+    /// are read against the copy.
+    fn execute_duplicate_sheet(self, p: DuplicateSheet) -> Result<Self, Error> {
+        let status = &self.status;
+        let src = status
+            .sheet_info_manager
+            .get_sheet_id(p.idx)
+            .ok_or(BasicError::SheetIdxExceed(p.idx))?;
+        let mut name_fetcher = status.name_fetcher();
+        let mut formulas = vec![];
+        for ((sheet_id, cell_id), node) in status.formula_manager.formulas.iter() {
+            if *sheet_id != src || matches!(cell_id, CellId::EphemeralCell(_)) {
+                continue;
+            }
+            let Ok((row, col)) = status.navigator.fetch_cell_idx(&src, cell_id) else {
+                continue;
+            };
+            let text = unparse::unparse(node, &mut name_fetcher, src)?;
+            formulas.push((row, col, format!("={}", text)));
+        }
+
+        let mut result = self.execute_payload(EditPayload::CreateSheet(CreateSheet {
+            idx: p.new_idx,
+            new_name: p.new_name,
+        }))?;
+        let status = &mut result.status;
+        let dst = status
+            .sheet_info_manager
+            .get_sheet_id(p.new_idx)
+            .ok_or(BasicError::SheetIdxExceed(p.new_idx))?;
+        status.navigator.duplicate_sheet(&src, dst)?;
+        status.container.duplicate_sheet(src, dst);
+        status.cell_attachment_manager.duplicate_sheet(src, dst);
+        status.image_manager.duplicate_sheet(src, dst);
+        status.chart_manager.duplicate_sheet(src, dst);
+        status.data_validation_manager.duplicate_sheet(src, dst);
+        status
+            .conditional_formatting_manager
+            .duplicate_sheet(src, dst);
+        status.data_table_manager.duplicate_sheet(src, dst);
+        status.scenario_manager.duplicate_sheet(src, dst);
+        status.block_schema_manager.duplicate_sheet(src, dst);
+        status.exclusive_manager.duplicate_sheet(src, dst);
+        if let Some(color) = status.sheet_info_manager.get_color(&src) {
+            status.sheet_info_manager.colors.insert(dst, color);
+        }
+
+        for (row, col, content) in formulas {
+            result = result.execute_payload(EditPayload::CellInput(CellInput {
+                sheet_idx: p.new_idx,
+                row,
+                col,
+                content,
+                skip_recognition: Some(true),
+            }))?;
+        }
+        result.cell_updated = true;
+        result.sheet_updated = true;
+        Ok(result)
+    }
+
+    /// Import records into a block as a composite edit: grow the block at its
+    /// end once for the records that find no line, then write each record
+    /// field as a `BlockInput`, so typed fields check the input as usual.
+    /// Everything is planned before the first write, and a record that does
+    /// not fit fails the whole import.
+    fn execute_import_block_records(self, p: ImportBlockRecords) -> Result<Self, Error> {
+        let refuse = |msg: String| Error::PayloadError(format!("ImportBlockRecords: {}", msg));
+        let status = &self.status;
+        let sheet_id = status
+            .sheet_info_manager
+            .get_sheet_id(p.sheet_idx)
+            .ok_or(BasicError::SheetIdxExceed(p.sheet_idx))?;
+        let bp = status.navigator.get_block_place(&sheet_id, &p.block_id)?;
+        let (is_row, key, fields) = match status
+            .block_schema_manager
+            .schemas
+            .get(&(sheet_id, p.block_id))
+        {
+            Some(Schema::RowSchema(s)) => (true, s.key, import_fields(&s.fields)),
+            Some(Schema::ColSchema(s)) => (false, s.key, import_fields(&s.fields)),
+            _ => {
+                return Err(refuse(format!("block {} has no form schema", p.block_id)));
+            }
+        };
+        // Records run along `lines`, their fields along `places`.
+        let (lines, places) = if is_row {
+            (&bp.rows, &bp.cols)
+        } else {
+            (&bp.cols, &bp.rows)
+        };
+        let place_of = |axis_id: u32| places.iter().position(|id| *id == axis_id);
+        let key_place = place_of(key).ok_or_else(|| refuse("key is outside the block".into()))?;
+        let key_field = p.key_field.clone().or_else(|| {
+            fields
+                .iter()
+                .find(|f| f.axis_id == key)
+                .map(|f| f.name.clone())
+        });
+        if p.upsert && key_field.is_none() {
+            return Err(refuse("upsert needs a key field".into()));
+        }
+        let places_of = |name: &str| {
+            let mut out = vec![];
+            if key_field.as_deref() == Some(name) {
+                out.push(key_place);
+            }
+            if let Some(place) = fields
+                .iter()
+                .find(|f| f.name == name)
+                .and_then(|f| place_of(f.axis_id))
+                && !out.contains(&place)
+            {
+                out.push(place);
+            }
+            out
+        };
+
+        let text_fetcher = |id| status.text_id_manager.get_string(&id).unwrap_or_default();
+        let text = |line: u32, place: u32| {
+            let (row, col) = if is_row { (line, place) } else { (place, line) };
+            let cell = CellId::BlockCell(BlockCellId {
+                block_id: p.block_id,
+                row,
+                col,
+            });
+            status
+                .container
+                .get_cell(sheet_id, &cell)
+                .map(|c| c.value.to_string(&text_fetcher).trim().to_string())
+                .unwrap_or_default()
+        };
+        let mut keys: HashMap<String, usize> = HashMap::new();
+        let mut free = VecDeque::new();
+        for (i, line) in lines.iter().enumerate() {
+            let key_text = text(*line, key);
+            if !key_text.is_empty() {
+                keys.entry(key_text).or_insert(i);
+            } else if fields
+                .iter()
+                .filter(|f| !f.templated)
+                .all(|f| text(*line, f.axis_id).is_empty())
+            {
+                free.push_back(i);
+            }
+        }
+
+        let records = parse_records(p.format, &p.data).map_err(refuse)?;
+        let line_cnt = lines.len();
+        let mut grown = 0;
+        let mut inputs = vec![];
+        for (i, record) in records.into_iter().enumerate() {
+            let record_key = key_field
+                .as_ref()
+                .and_then(|k| record.iter().find(|(f, _)| f == k))
+                .map(|(_, v)| v.trim().to_string())
+                .filter(|k| !k.is_empty());
+            let existing = record_key
+                .as_ref()
+                .filter(|_| p.upsert)
+                .and_then(|k| keys.get(k).copied());
+            let line = match existing {
+                Some(line) => line,
+                None => {
+                    // The fields a new record leaves out are still read as
+                    // blank input, so that a required one refuses it.
+                    for f in fields.iter().filter(|f| !f.templated) {
+                        let Some((ty, pattern)) = &f.field_type else {
+                            continue;
+                        };
+                        if record.iter().any(|(n, _)| *n == f.name) {
+                            continue;
+                        }
+                        parse_input(ty, pattern.as_ref(), "").map_err(|e| {
+                            refuse(format!("record {}: field {:?}: {}", i + 1, f.name, e))
+                        })?;
+                    }
+                    let line = free.pop_front().unwrap_or_else(|| {
+                        grown += 1;
+                        line_cnt + grown - 1
+                    });
+                    if let Some(k) = record_key {
+                        keys.entry(k).or_insert(line);
+                    }
+                    line
+                }
+            };
+            for (name, input) in record {
+                let record_places = places_of(&name);
+                if record_places.is_empty() {
+                    return Err(refuse(format!(
+                        "record {}: {:?} is not a field of the block",
+                        i + 1,
+                        name
+                    )));
+                }
+                // An update keeps what a blank or null field would clear.
+                if existing.is_some() && input.trim().is_empty() {
+                    continue;
+                }
+                for place in record_places {
+                    let (row, col) = if is_row { (line, place) } else { (place, line) };
+                    let input = BlockInput {
+                        sheet_idx: p.sheet_idx,
+                        block_id: p.block_id,
+                        row,
+                        col,
+                        input: input.clone(),
+                    };
+                    inputs.push((i, input));
+                }
+            }
+        }
+
+        let mut result = self;
+        if grown > 0 {
+            let grow = if is_row {
+                EditPayload::InsertRowsInBlock(InsertRowsInBlock {
+                    sheet_idx: p.sheet_idx,
+                    block_id: p.block_id,
+                    start: line_cnt,
+                    cnt: grown,
+                })
+            } else {
+                EditPayload::InsertColsInBlock(InsertColsInBlock {
+                    sheet_idx: p.sheet_idx,
+                    block_id: p.block_id,
+                    start: line_cnt,
+                    cnt: grown,
+                })
+            };
+            result = result.execute_payload(grow)?;
+        }
+        for (i, input) in inputs {
+            result = result
+                .execute_payload(EditPayload::BlockInput(input))
+                .map_err(|e| match e {
+                    Error::PayloadError(msg) => refuse(format!("record {}: {}", i + 1, msg)),
+                    e => e,
+                })?;
+        }
+        result.cell_updated = true;
+        Ok(result)
+    }
+
+    /// Show a scenario as a composite edit: type each of its values into its
+    /// changing cell, then record it as the scenario shown.
+    fn execute_show_scenario(self, p: ShowScenario) -> Result<Self, Error> {
+        let status = &self.status;
+        let sheet_id = status
+            .sheet_info_manager
+            .get_sheet_id(p.sheet_idx)
+            .ok_or(BasicError::SheetIdxExceed(p.sheet_idx))?;
+        let Some(scenario) = status.scenario_manager.find(sheet_id, &p.name) else {
+            return Err(Error::PayloadError(format!(
+                "ShowScenario: no scenario called {:?}",
+                p.name
+            )));
+        };
+        let inputs = scenario_inputs(status, sheet_id, p.sheet_idx, scenario);
+        let mut result = self;
+        for input in inputs {
+            result.refuse_data_table_edit(&input)?;
+            result = result.execute_payload(input)?;
+        }
+        result.status.scenario_manager.set_shown(sheet_id, &p.name);
+        result.cell_updated = true;
+        Ok(result)
+    }
+
+    /// Write a scenario summary as a composite edit. Each scenario is shown
+    /// on a scratch copy of the status, and what the changing cells and the
+    /// result cells hold there goes on a new sheet, after what they hold now:
+    ///
+    /// ```text
+    /// Scenario Summary
+    ///                   Current Values:  Best  Worst
+    /// Changing Cells:
+    ///          $B$1     10               12    8
+    /// Result Cells:
+    ///          $B$3     100              144   64
+    /// ```
+    fn execute_create_scenario_summary(mut self, p: CreateScenarioSummary) -> Result<Self, Error> {
+        let status = &self.status;
+        let sheet_id = status
+            .sheet_info_manager
+            .get_sheet_id(p.sheet_idx)
+            .ok_or(BasicError::SheetIdxExceed(p.sheet_idx))?;
+        let scenarios = status
+            .scenario_manager
+            .scenarios_of_sheet(sheet_id)
+            .cloned()
+            .collect::<Vec<_>>();
+        if scenarios.is_empty() {
+            return Err(Error::PayloadError(
+                "CreateScenarioSummary: the sheet has no scenarios".to_string(),
+            ));
+        }
+        let nav = &status.navigator;
+        let mut cells = vec![];
+        for c in scenarios.iter().flat_map(|s| s.cells.iter()) {
+            if let Ok(pos) = nav.fetch_cell_idx(&sheet_id, &c.cell_id)
+                && !cells.contains(&pos)
+            {
+                cells.push(pos);
+            }
+        }
+        let changing_cnt = cells.len();
+        cells.extend(p.result_cells.iter().map(|c| (c.row, c.col)));
+        let result_cells = p
+            .result_cells
+            .iter()
+            .map(|c| nav.fetch_cell_id(&sheet_id, c.row, c.col))
+            .collect::<Result<imbl::Vector<_>, _>>()?;
+
+        let read = |status: &Status| {
+            cells
+                .iter()
+                .map(|&pos| value_at(status, sheet_id, pos))
+                .collect::<Vec<_>>()
+        };
+        let mut columns = vec![(String::from("Current Values:"), read(&self.status))];
+        for scenario in scenarios {
+            let inputs = scenario_inputs(&self.status, sheet_id, p.sheet_idx, &scenario);
+            let scratch = self.on_scratch(|mut scratch| {
+                for input in inputs {
+                    scratch = scratch.execute_payload(input)?;
+                }
+                Ok(scratch)
+            })?;
+            columns.push((scenario.name, read(&scratch)));
+        }
+
+        let text = |s: &str| Value::Str(s.to_string());
+        let mut report = vec![(0, 0, text("Scenario Summary"))];
+        for (i, (title, _)) in columns.iter().enumerate() {
+            report.push((1, 2 + i, text(title)));
+        }
+        let sections = [
+            ("Changing Cells:", 0..changing_cnt),
+            ("Result Cells:", changing_cnt..cells.len()),
+        ];
+        let mut row = 2;
+        for (label, section) in sections {
+            if section.is_empty() {
+                continue;
+            }
+            report.push((row, 0, text(label)));
+            for k in section {
+                row += 1;
+                let (r, c) = cells[k];
+                report.push((row, 1, text(&format!("${}${}", col_to_letters(c), r + 1))));
+                for (i, (_, values)) in columns.iter().enumerate() {
+                    report.push((row, 2 + i, values[k].clone()));
+                }
+            }
+            row += 1;
+        }
+
+        let mut result = self.execute_payload(EditPayload::CreateSheet(CreateSheet {
+            idx: p.new_idx,
+            new_name: p.new_name,
+        }))?;
+        for (row, col, value) in report {
+            if value != Value::Empty {
+                result = result.write_value(p.new_idx, row, col, value)?;
+            }
+        }
+        result
+            .status
+            .scenario_manager
+            .set_result_cells(sheet_id, result_cells);
+        result.cell_updated = true;
+        result.sheet_updated = true;
+        Ok(result)
+    }
+
+    /// Check a cell shift and move the cells' content, formulas, merges,
+    /// comments, images and conditional formats to their new ids. A shift
+    /// that would split a merge or a block, delete part of a block, or push
+    /// content off the sheet is refused, as in Excel.
+    fn shift_cells(&mut self, sheet_idx: usize, shift: CellShift) -> Result<(), Error> {
+        let sheet_id = self
+            .status
+            .sheet_info_manager
+            .get_sheet_id(sheet_idx)
+            .ok_or(BasicError::SheetIdxExceed(sheet_idx))?;
+        if !shift.is_valid() {
+            return Err(Error::PayloadError(
+                "the cells to shift are out of the sheet".to_string(),
+            ));
+        }
+        let status = &mut self.status;
+        let nav = &status.navigator;
+        let idx = |id: &NormalCellId| nav.fetch_normal_cell_idx(&sheet_id, id).ok();
+
+        for (start, end) in status
+            .cell_attachment_manager
+            .merge_cells
+            .get_all_merged_cells(&sheet_id)
+        {
+            let (Some((r1, c1)), Some((r2, c2))) = (idx(&start), idx(&end)) else {
+                continue;
+            };
+            if shift.tears(r1, c1, r2, c2) {
+                return Err(Error::PayloadError(
+                    "cannot shift cells: this would split a merged cell".to_string(),
+                ));
+            }
+        }
+        for (r1, c1, r2, c2) in nav.get_block_rects(&sheet_id) {
+            let kept = match shift.move_rect(r1, c1, r2, c2) {
+                RectMove::Stay => true,
+                RectMove::To(nr1, nc1, nr2, nc2) => nr2 - nr1 == r2 - r1 && nc2 - nc1 == c2 - c1,
+                RectMove::Dirty | RectMove::Deleted => false,
+            };
+            if !kept || shift.tears(r1, c1, r2, c2) {
+                return Err(Error::PayloadError(
+                    "cannot shift cells: this would split or delete a block".to_string(),
+                ));
+            }
+        }
+
+        let mut cells: Vec<NormalCellId> = vec![];
+        if let Some(container) = status.container.get_sheet_container(sheet_id) {
+            cells.extend(container.cells.keys().filter_map(|id| match id {
+                CellId::NormalCell(c) => Some(*c),
+                _ => None,
+            }));
+        }
+        if shift.insert
+            && cells
+                .iter()
+                .any(|c| idx(c).map(|(r, c)| shift.move_cell(r, c)) == Some(CellMove::Deleted))
+        {
+            return Err(Error::PayloadError(
+                "cannot insert cells: this would push cells off the sheet".to_string(),
+            ));
+        }
+        cells.extend(attached_cells(status, sheet_id));
+
+        let mut moves = CellMoves::new();
+        for cell in cells {
+            let Some((row, col)) = idx(&cell) else {
+                continue;
+            };
+            match shift.move_cell(row, col) {
+                CellMove::Stay => {}
+                CellMove::Deleted => {
+                    moves.insert(cell, None);
+                }
+                CellMove::To(row, col) => {
+                    let to = nav.fetch_norm_cell_id(&sheet_id, row, col)?;
+                    moves.insert(cell, Some(to));
+                }
+            }
+        }
+        let mut rects = RectMoves::new();
+        let cf_rects = status
+            .conditional_formatting_manager
+            .get_sheet(sheet_id)
+            .into_iter()
+            .flat_map(|blocks| blocks.iter().flat_map(|b| b.ranges.iter()));
+        for range in cf_rects {
+            let CfRange::Rect(CellId::NormalCell(s), CellId::NormalCell(e)) = range else {
+                continue;
+            };
+            let (Some((r1, c1)), Some((r2, c2))) = (idx(s), idx(e)) else {
+                continue;
+            };
+            match shift.move_rect(r1, c1, r2, c2) {
+                RectMove::Stay | RectMove::Dirty => {}
+                RectMove::Deleted => {
+                    rects.insert((*s, *e), None);
+                }
+                RectMove::To(r1, c1, r2, c2) => {
+                    let s_to = nav.fetch_norm_cell_id(&sheet_id, r1, c1)?;
+                    let e_to = nav.fetch_norm_cell_id(&sheet_id, r2, c2)?;
+                    rects.insert((*s, *e), Some((s_to, e_to)));
+                }
+            }
+        }
+
+        let moved = status.container.move_cells(sheet_id, sheet_id, &moves);
+        let filled = moved
+            .iter()
+            .filter_map(|(_, new)| *new)
+            .collect::<HashSet<_>>();
+        for (old, new) in moved {
+            if !filled.contains(&old) {
+                self.cells_removed.insert((sheet_id, old));
+            }
+            if let Some(new) = new {
+                self.updated_cells.insert((sheet_id, new));
+            }
+        }
+        let range_manager = &status.range_manager;
+        status.formula_manager.shift_cells(sheet_id, &moves, |c| {
+            range_manager.get_range_id_assert(&sheet_id, &Range::Normal(NormalRange::Single(*c)))
+        });
+        status
+            .cell_attachment_manager
+            .move_cells(sheet_id, sheet_id, &moves);
+        status.image_manager.move_cells(sheet_id, sheet_id, &moves);
+        status
+            .conditional_formatting_manager
+            .shift_cells(sheet_id, &rects);
+        self.cell_updated = true;
+        Ok(())
+    }
+
+    /// Check a range move and carry the cells' content, merges, comments and
+    /// images to the destination, dropping whatever they overwrite. The
+    /// formulas follow in the formula executor. A move that would split a
+    /// merge or reach into a block is refused.
+    fn move_range(&mut self, p: &MoveRange) -> Result<(), Error> {
+        let sheet_info = &self.status.sheet_info_manager;
+        let from = sheet_info
+            .get_sheet_id(p.sheet_idx)
+            .ok_or(BasicError::SheetIdxExceed(p.sheet_idx))?;
+        let to = sheet_info
+            .get_sheet_id(p.dest_sheet_idx)
+            .ok_or(BasicError::SheetIdxExceed(p.dest_sheet_idx))?;
+        let mv = RangeMove::new(from, to, p);
+        if !mv.is_valid() {
+            return Err(Error::PayloadError(
+                "the cells to move are out of the sheet".to_string(),
+            ));
+        }
+        let status = &mut self.status;
+        let nav = &status.navigator;
+        let sheets = if from == to {
+            vec![from]
+        } else {
+            vec![from, to]
+        };
+
+        for &sheet in sheets.iter() {
+            let idx = |id: &NormalCellId| nav.fetch_normal_cell_idx(&sheet, id).ok();
+            for (start, end) in status
+                .cell_attachment_manager
+                .merge_cells
+                .get_all_merged_cells(&sheet)
+            {
+                let (Some((r1, c1)), Some((r2, c2))) = (idx(&start), idx(&end)) else {
+                    continue;
+                };
+                if mv.tears(sheet, r1, c1, r2, c2) {
+                    return Err(Error::PayloadError(
+                        "cannot move cells: this would split a merged cell".to_string(),
+                    ));
+                }
+            }
+            for (r1, c1, r2, c2) in nav.get_block_rects(&sheet) {
+                if mv.touches(sheet, r1, c1, r2, c2) {
+                    return Err(Error::PayloadError(
+                        "cannot move cells into or out of a block".to_string(),
+                    ));
+                }
+            }
+        }
+
+        let mut moved = CellMoves::new();
+        let mut cut = CellMoves::new();
+        for &sheet in sheets.iter() {
+            let mut cells = attached_cells(status, sheet);
+            if let Some(container) = status.container.get_sheet_container(sheet) {
+                cells.extend(container.cells.keys().filter_map(normal_cell));
+            }
+            for cell in cells {
+                let Ok((row, col)) = nav.fetch_normal_cell_idx(&sheet, &cell) else {
+                    continue;
+                };
+                match mv.move_cell(sheet, row, col) {
+                    CellMove::Stay => {}
+                    CellMove::Deleted => {
+                        cut.insert(cell, None);
+                    }
+                    CellMove::To(row, col) => {
+                        let dest = nav.fetch_norm_cell_id(&to, row, col)?;
+                        moved.insert(cell, Some(dest));
+                    }
+                }
+            }
+        }
+        let mut rects = RectMoves::new();
+        if from == to {
+            let cf_rects = status
+                .conditional_formatting_manager
+                .get_sheet(from)
+                .into_iter()
+                .flat_map(|blocks| blocks.iter().flat_map(|b| b.ranges.iter()));
+            for range in cf_rects {
+                let CfRange::Rect(CellId::NormalCell(s), CellId::NormalCell(e)) = range else {
+                    continue;
+                };
+                let (Ok((r1, c1)), Ok((r2, c2))) = (
+                    nav.fetch_normal_cell_idx(&from, s),
+                    nav.fetch_normal_cell_idx(&from, e),
+                ) else {
+                    continue;
+                };
+                if let RectMove::To(r1, c1, r2, c2) = mv.move_rect(from, r1, c1, r2, c2) {
+                    let s_to = nav.fetch_norm_cell_id(&from, r1, c1)?;
+                    let e_to = nav.fetch_norm_cell_id(&from, r2, c2)?;
+                    rects.insert((*s, *e), Some((s_to, e_to)));
+                }
+            }
+        }
+
+        // On one sheet the moved cells win over the overwritten ones they
+        // land on. Across sheets the destination is cleared first.
+        let mut passes = vec![];
+        if from == to {
+            cut.extend(moved);
+            passes.push((from, cut));
+        } else {
+            passes.push((to, cut));
+            passes.push((from, moved));
+        }
+        for (sheet, moves) in passes {
+            let result = status.container.move_cells(sheet, to, &moves);
+            let filled = result
+                .iter()
+                .filter_map(|(_, new)| *new)
+                .collect::<HashSet<_>>();
+            for (old, new) in result {
+                if sheet != to || !filled.contains(&old) {
+                    self.cells_removed.insert((sheet, old));
+                }
+                if let Some(new) = new {
+                    self.cells_removed.remove(&(to, new));
+                    self.updated_cells.insert((to, new));
+                }
+            }
+            status.cell_attachment_manager.move_cells(sheet, to, &moves);
+            status.image_manager.move_cells(sheet, to, &moves);
+        }
+        status
+            .conditional_formatting_manager
+            .shift_cells(from, &rects);
+        self.cell_updated = true;
+        Ok(())
+    }
+
+    fn has_general_format(&self, sheet_idx: usize, row: usize, col: usize) -> bool {
+        let status = &self.status;
+        let style = status
+            .sheet_info_manager
+            .get_sheet_id(sheet_idx)
+            .and_then(|sheet_id| {
+                let cell_id = status.navigator.fetch_cell_id(&sheet_id, row, col).ok()?;
+                status.container.get_cell(sheet_id, &cell_id)
+            })
+            .map_or(0, |cell| cell.style);
+        status.style_manager.get_style(style).formatter == "General"
+    }
+
     pub fn calc(self) -> Result<Self, Error> {
         let mut dirty_cells_in_next_run = imbl::HashSet::new();
         let mut calc_cells: HashSet<(SheetId, CellId)> = HashSet::new();
@@ -344,6 +1165,7 @@ impl<'a> Executor<'a> {
             book_name,
             calc_config,
             async_funcs,
+            input_locale,
             mut updated_cells,
             cells_removed,
             style_updated,
@@ -419,6 +1241,7 @@ impl<'a> Executor<'a> {
             book_name,
             calc_config,
             async_funcs,
+            input_locale,
             updated_cells,
             cells_removed,
             style_updated,
@@ -433,6 +1256,172 @@ impl<'a> Executor<'a> {
         })
     }
 
+    /// A data table's results are written only by `refresh_data_tables`.
+    /// Typing into one or clearing it is refused, as Excel refuses it.
+    fn refuse_data_table_edit(&self, payload: &EditPayload) -> Result<(), Error> {
+        let (sheet_idx, row, col) = match payload {
+            EditPayload::CellInput(p) => (p.sheet_idx, p.row, p.col),
+            EditPayload::CellClear(p) => (p.sheet_idx, p.row, p.col),
+            _ => return Ok(()),
+        };
+        let status = &self.status;
+        let Some(sheet_id) = status.sheet_info_manager.get_sheet_id(sheet_idx) else {
+            return Ok(());
+        };
+        let manager = &status.data_table_manager;
+        if resolve::table_at(&status.navigator, manager, sheet_id, row, col).is_some() {
+            return Err(Error::PayloadError(
+                "cannot change part of a data table".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Fill in the results of the data tables this transaction may have
+    /// changed: those created, moved or resized since `before`, and those
+    /// computed from a cell that was written or recalculated. A formula
+    /// recalculates when any of its precedents changes, so that covers them
+    /// too. Each substitution is evaluated on a scratch copy of the status. A
+    /// result that changed is then written like any other value, so whatever
+    /// reads it recalculates.
+    fn refresh_data_tables(mut self, before: &[(u32, resolve::Layout)]) -> Result<Self, Error> {
+        if self.status.data_table_manager.is_empty() {
+            return Ok(self);
+        }
+        let changed = |l: &resolve::Layout| {
+            l.sources().into_iter().any(|(row, col)| {
+                self.status
+                    .navigator
+                    .fetch_cell_id(&l.sheet_id, row, col)
+                    .is_ok_and(|id| self.updated_cells.contains(&(l.sheet_id, id)))
+            })
+        };
+        let layouts = data_table_layouts(&self.status)
+            .into_iter()
+            .filter(|t| !before.contains(t) || changed(&t.1))
+            .map(|(_, l)| l)
+            .collect::<Vec<_>>();
+        let mut results = Vec::new();
+        for l in layouts {
+            let Some(sheet_idx) = self.status.sheet_info_manager.get_sheet_idx(&l.sheet_id) else {
+                continue;
+            };
+            if l.broken {
+                let error = Value::Error(logisheets_base::Error::Ref.to_string());
+                results.extend(
+                    l.cells()
+                        .map(|(r, c)| (sheet_idx, l.sheet_id, r, c, error.clone())),
+                );
+                continue;
+            }
+            for trial in l.trials() {
+                let inputs = trial
+                    .inputs
+                    .iter()
+                    .map(|&((r, c), source)| {
+                        (sheet_idx, r, c, value_at(&self.status, l.sheet_id, source))
+                    })
+                    .collect();
+                let scratch = self.evaluate(inputs)?;
+                for &((r, c), read) in trial.reads.iter() {
+                    let value = value_at(&scratch, l.sheet_id, read);
+                    results.push((sheet_idx, l.sheet_id, r, c, value));
+                }
+            }
+        }
+        let mut written = false;
+        for (sheet_idx, sheet_id, row, col, value) in results {
+            if value_at(&self.status, sheet_id, (row, col)) == value {
+                continue;
+            }
+            self = self.write_value(sheet_idx, row, col, value)?;
+            written = true;
+        }
+        if written {
+            self = self.calc()?;
+        }
+        Ok(self)
+    }
+
+    /// The status after putting each `(sheet_idx, row, col, value)` into its
+    /// cell and recalculating, on a scratch copy.
+    pub fn evaluate(&mut self, inputs: Vec<(usize, usize, usize, Value)>) -> Result<Status, Error> {
+        self.on_scratch(|mut scratch| {
+            for (sheet_idx, row, col, value) in inputs {
+                scratch = scratch.write_value(sheet_idx, row, col, value)?;
+            }
+            Ok(scratch)
+        })
+    }
+
+    /// The status after running `edit` on a scratch copy of this one and
+    /// recalculating. Nothing is recorded and the executor's own status is
+    /// left as it is.
+    fn on_scratch<F>(&mut self, edit: F) -> Result<Status, Error>
+    where
+        F: for<'b> FnOnce(Executor<'b>) -> Result<Executor<'b>, Error>,
+    {
+        let mut version_manager = VersionManager::default();
+        let mut async_func_manager = AsyncFuncManager::default();
+        let scratch = Executor {
+            status: self.status.clone(),
+            sid_assigner: &mut *self.sid_assigner,
+            version_manager: &mut version_manager,
+            checkpoint_manager: self.checkpoint_manager,
+            async_func_manager: &mut async_func_manager,
+            book_name: self.book_name,
+            calc_config: self.calc_config,
+            async_funcs: self.async_funcs,
+            input_locale: self.input_locale,
+            updated_cells: HashSet::new(),
+            cells_removed: HashSet::new(),
+            style_updated: HashSet::new(),
+            dirty_vertices: HashSet::new(),
+            row_inserted: vec![],
+            row_removed: vec![],
+            col_inserted: vec![],
+            col_removed: vec![],
+            sheet_updated: false,
+            cell_updated: false,
+            header_updated: HashSet::new(),
+        };
+        Ok(edit(scratch)?.calc()?.status)
+    }
+
+    /// Put `value` into a cell the way input does, so that everything reading
+    /// the cell is marked dirty, then set the value exactly: typed input
+    /// can't produce an error, and a result or a substituted value can be one.
+    fn write_value(
+        self,
+        sheet_idx: usize,
+        row: usize,
+        col: usize,
+        value: Value,
+    ) -> Result<Self, Error> {
+        let mut result = self.execute_payload(EditPayload::CellInput(CellInput {
+            sheet_idx,
+            row,
+            col,
+            content: String::from("0"),
+            skip_recognition: Some(true),
+        }))?;
+        let status = &mut result.status;
+        let sheet_id = status
+            .sheet_info_manager
+            .get_sheet_id(sheet_idx)
+            .ok_or(BasicError::SheetIdxExceed(sheet_idx))?;
+        let cell_id = status.navigator.fetch_cell_id(&sheet_id, row, col)?;
+        let value = match value {
+            Value::Empty => CellValue::Blank,
+            Value::Bool(b) => CellValue::Boolean(b),
+            Value::Number(n) => CellValue::Number(n),
+            Value::Str(s) => CellValue::String(status.text_id_manager.get_or_register_id(&s)),
+            Value::Error(e) => CellValue::Error(logisheets_base::Error::from_string(e)),
+        };
+        status.container.update_value(sheet_id, cell_id, value);
+        Ok(result)
+    }
+
     fn execute_range(&mut self, payload: EditPayload) -> Result<RangeExecutor, Error> {
         let ctx = RangeConnector {
             sheet_id_manager: &mut self.status.sheet_id_manager,
@@ -543,6 +1532,30 @@ impl<'a> Executor<'a> {
         )
     }
 
+    fn execute_data_table(
+        &mut self,
+        payload: EditPayload,
+    ) -> Result<(DataTableExecutor, bool), Error> {
+        let executor = DataTableExecutor::new(self.status.data_table_manager.clone());
+        executor.execute(
+            &self.status.navigator,
+            &self.status.sheet_info_manager,
+            payload,
+        )
+    }
+
+    fn execute_scenario(
+        &mut self,
+        payload: EditPayload,
+    ) -> Result<(ScenarioExecutor, bool), Error> {
+        let executor = ScenarioExecutor::new(self.status.scenario_manager.clone());
+        executor.execute(
+            &self.status.navigator,
+            &self.status.sheet_info_manager,
+            payload,
+        )
+    }
+
     fn execute_chart(&mut self, payload: EditPayload) -> Result<(ChartExecutor, bool), Error> {
         let mut ctx = CellAttachmentsConnector {
             sheet_pos_manager: &self.status.sheet_info_manager,
@@ -654,3 +1667,100 @@ impl<'a> Executor<'a> {
         executor.execute(payload, &mut ctx)
     }
 }
+
+/// A form-schema field as `execute_import_block_records` sees it.
+struct ImportField {
+    name: String,
+    axis_id: u32,
+    /// Its value comes from a formula template; input to it is dropped.
+    templated: bool,
+    field_type: Option<(FieldType, Option<Regex>)>,
+}
+
+fn import_fields(fields: &[(String, FieldEntry<u32>)]) -> Vec<ImportField> {
+    fields
+        .iter()
+        .map(|(name, e)| ImportField {
+            name: name.clone(),
+            axis_id: e.field_axis_id,
+            templated: e.value_formula.is_some(),
+            field_type: e.field_type.clone().map(|t| (t, e.pattern.clone())),
+        })
+        .collect()
+}
+
+/// The normal cells of a sheet that carry something besides their content:
+/// merge corners, formulas, images and comments.
+fn attached_cells(status: &Status, sheet_id: SheetId) -> Vec<NormalCellId> {
+    let mut cells: Vec<NormalCellId> = status
+        .cell_attachment_manager
+        .merge_cells
+        .get_all_merged_cells(&sheet_id)
+        .into_iter()
+        .flat_map(|(s, e)| [s, e])
+        .collect();
+    let attached = status
+        .formula_manager
+        .formulas
+        .keys()
+        .chain(status.image_manager.images.keys())
+        .filter(|(s, _)| *s == sheet_id)
+        .map(|(_, c)| c)
+        .chain(
+            status
+                .cell_attachment_manager
+                .comments
+                .data
+                .get(&sheet_id)
+                .into_iter()
+                .flat_map(|c| c.threads.keys()),
+        );
+    cells.extend(attached.filter_map(|id| match id {
+        CellId::NormalCell(c) => Some(*c),
+        _ => None,
+    }));
+    cells
+}
+
+/// The `CellInput`s that show `scenario`, typing each value as the user
+/// would. A changing cell whose row or column was deleted is left out.
+fn scenario_inputs(
+    status: &Status,
+    sheet_id: SheetId,
+    sheet_idx: usize,
+    scenario: &Scenario,
+) -> Vec<EditPayload> {
+    scenario
+        .cells
+        .iter()
+        .filter_map(|c| {
+            let (row, col) = status
+                .navigator
+                .fetch_cell_idx(&sheet_id, &c.cell_id)
+                .ok()?;
+            Some(EditPayload::CellInput(CellInput {
+                sheet_idx,
+                row,
+                col,
+                content: c.value.clone(),
+                ..Default::default()
+            }))
+        })
+        .collect()
+}
+
+/// What the cell at `(row, col)` shows.
+fn data_table_layouts(status: &Status) -> Vec<(u32, resolve::Layout)> {
+    status
+        .data_table_manager
+        .iter()
+        .filter_map(|t| Some((t.id, resolve::layout(&status.navigator, t)?)))
+        .collect()
+}
+
+fn value_at(status: &Status, sheet_id: SheetId, (row, col): (usize, usize)) -> Value {
+    status
+        .navigator
+        .fetch_cell_id(&sheet_id, row, col)
+        .map_or(Value::Empty, |id| read_display_value(status, sheet_id, &id))
+}
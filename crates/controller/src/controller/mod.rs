@@ -1,3 +1,4 @@
+use std::cell::RefCell;
 use std::collections::HashSet;
 use std::sync::Mutex;
 
@@ -18,6 +19,7 @@ fn record_last_error(e: &dyn std::fmt::Display) {
 }
 
 use logisheets_base::async_func::{AsyncCalcResult, Task};
+use logisheets_base::errors::BasicError;
 use logisheets_base::{BlockRange, CellId, NormalRange, Range, SheetId};
 
 use logisheets_workbook::logisheets::AppData;
@@ -26,10 +28,14 @@ pub mod display;
 mod executor;
 pub mod status;
 pub mod style;
-use crate::checkpoint_manager::CheckpointManager;
+use crate::api::{GoalSeekResult, read_display_value};
+use crate::audit_manager::{AuditManager, payload_names};
+use crate::branch_manager::{BranchManager, MAIN};
+use crate::calc_engine::calculator::math::newton_iter::{bisection, newton};
+use crate::checkpoint_manager::{CheckpointManager, persistence::restore_payloads};
 use crate::edit_action::{
-    ActionEffect, CreateSheet, EditAction, PayloadsAction, RecalcCell, SheetCellId, SheetColId,
-    SheetRowId, StatusCode, WorkbookUpdateType,
+    ActionEffect, CellInput, CreateSheet, EditAction, EditPayload, PayloadsAction, RecalcCell,
+    SheetCellId, SheetColId, SheetRowId, StatusCode, WorkbookUpdateType,
 };
 use crate::errors::{Error, Result};
 use crate::file_loader::load_file;
@@ -41,7 +47,7 @@ use crate::version_manager::VersionManager;
 use executor::Executor;
 use status::Status;
 
-use self::display::SheetInfo;
+use self::display::{MergeResult, SheetInfo, Value};
 use crate::async_func_manager::AsyncFuncManager;
 
 pub struct TempStatus {
@@ -62,8 +68,13 @@ pub struct Controller {
     /// Session-scoped named checkpoints. Independent of the linear
     /// undo/redo stack (see `checkpoint_manager` module docs).
     /// Populated by AI workflows via `Workbook::save_checkpoint`;
-    /// cleared on file save/load.
+    /// only those saved with `persist` outlive the session.
     pub checkpoint_manager: CheckpointManager,
+    /// The change log, when enabled (see `audit_manager` module docs).
+    pub audit_manager: AuditManager,
+    /// Named what-if branches (see `branch_manager` module docs). `status`
+    /// and `version_manager` are those of the branch shown.
+    pub branch_manager: BranchManager,
     pub sid_assigner: ShadowIdAssigner,
 
     pub app_data: Vec<AppData>,
@@ -77,6 +88,8 @@ impl Default for Controller {
             settings: Settings::default(),
             version_manager: VersionManager::default(),
             checkpoint_manager: CheckpointManager::default(),
+            audit_manager: AuditManager::default(),
+            branch_manager: BranchManager::default(),
             async_func_manager: AsyncFuncManager::default(),
             sid_assigner: ShadowIdAssigner::new(),
             app_data: vec![],
@@ -153,6 +166,19 @@ impl Controller {
 
     pub fn commit_temp_status(&mut self) {
         if let Some(temp) = self.temp_status.take() {
+            if self.audit_manager.is_enabled() && !temp.accumulated_payloads.is_empty() {
+                self.audit_manager.record(
+                    payload_names(&temp.accumulated_payloads),
+                    &temp.fork_status,
+                    &self.status,
+                    &temp.accumulated_updated_cells,
+                );
+            }
+            self.branch_manager.record(
+                temp.accumulated_payloads.clone(),
+                &temp.fork_status,
+                &self.status,
+            );
             let merged_payloads = PayloadsAction {
                 payloads: temp.accumulated_payloads,
                 undoable: true,
@@ -178,6 +204,8 @@ impl Controller {
             status,
             version_manager: VersionManager::default(),
             checkpoint_manager: CheckpointManager::default(),
+            audit_manager: AuditManager::default(),
+            branch_manager: BranchManager::default(),
             async_func_manager: AsyncFuncManager::default(),
             sid_assigner: ShadowIdAssigner::new(),
             app_data,
@@ -219,6 +247,78 @@ impl Controller {
         Ok(controller)
     }
 
+    /// Recalculate every formula in the workbook, discarding the values it
+    /// was loaded with. Like the settling run in `from_file`, this is not
+    /// undoable.
+    pub fn recalc_all(&mut self) {
+        let cells = self.status.formula_manager.formulas.keys().copied();
+        self.status.dirty_cells_next_round.extend(cells);
+        self.handle_action(EditAction::Payloads(PayloadsAction {
+            payloads: vec![],
+            undoable: false,
+            init: true,
+        }));
+    }
+
+    /// Checkpoints loaded from the file are kept as diffs. Rebuild those
+    /// the payloads are about to restore by replaying their diff onto the
+    /// workbook as it was loaded. A checkpoint that cannot be rebuilt stays
+    /// as it is, and restoring it fails as for an unknown label.
+    fn materialize_checkpoints(&mut self, payloads: &[EditPayload]) {
+        for payload in payloads {
+            let EditPayload::RestoreCheckpoint(rc) = payload else {
+                continue;
+            };
+            let Some((base, diff)) = self.checkpoint_manager.pending(&rc.label) else {
+                continue;
+            };
+            let names = base
+                .sheet_info_manager
+                .pos
+                .iter()
+                .map(|id| base.sheet_id_manager.get_string(id).unwrap_or_default())
+                .collect::<Vec<_>>();
+            let action = PayloadsAction {
+                payloads: restore_payloads(&names, diff),
+                undoable: false,
+                init: false,
+            };
+            let status = base.clone();
+            let mut version_manager = VersionManager::default();
+            let mut async_func_manager = AsyncFuncManager::default();
+            let checkpoint_manager = CheckpointManager::default();
+            let executor = Executor {
+                status,
+                version_manager: &mut version_manager,
+                async_func_manager: &mut async_func_manager,
+                book_name: &self.curr_book_name,
+                calc_config: self.settings.calc_config,
+                async_funcs: &self.settings.async_funcs,
+                input_locale: &self.settings.input_locale,
+                updated_cells: HashSet::new(),
+                dirty_vertices: HashSet::new(),
+                sheet_updated: false,
+                cell_updated: false,
+                cells_removed: HashSet::new(),
+                sid_assigner: &mut self.sid_assigner,
+                checkpoint_manager: &checkpoint_manager,
+                style_updated: HashSet::new(),
+                row_inserted: vec![],
+                row_removed: vec![],
+                col_inserted: vec![],
+                col_removed: vec![],
+                header_updated: HashSet::new(),
+            };
+            match executor.execute_and_calc(action) {
+                Ok(result) => {
+                    let status = result.status;
+                    self.checkpoint_manager.materialize(&rc.label, status);
+                }
+                Err(e) => record_last_error(&e),
+            }
+        }
+    }
+
     pub fn get_sheet_id_by_idx(&self, idx: usize) -> Option<SheetId> {
         self.status.sheet_info_manager.get_sheet_id(idx)
     }
@@ -253,6 +353,7 @@ impl Controller {
             });
         }
 
+        self.materialize_checkpoints(&action.payloads);
         let executor = Executor {
             status: self.status.clone(),
             version_manager: &mut self.version_manager,
@@ -260,6 +361,7 @@ impl Controller {
             book_name: &self.curr_book_name,
             calc_config: self.settings.calc_config,
             async_funcs: &self.settings.async_funcs,
+            input_locale: &self.settings.input_locale,
             updated_cells: HashSet::new(),
             dirty_vertices: HashSet::new(),
             sheet_updated: false,
@@ -388,6 +490,15 @@ impl Controller {
                 ActionEffect::from(0, vec![], c)
             }
             EditAction::Payloads(payloads_action) => {
+                self.materialize_checkpoints(&payloads_action.payloads);
+                let audited = self.audit_manager.is_enabled()
+                    && !self.branch_manager.is_on_branch()
+                    && !payloads_action.init
+                    && !payloads_action.payloads.is_empty();
+                let audited_payloads = audited.then(|| payload_names(&payloads_action.payloads));
+                let on_branch = self.branch_manager.is_on_branch() && !payloads_action.init;
+                let branch_payloads = (on_branch && payloads_action.undoable)
+                    .then(|| payloads_action.payloads.clone());
                 let executor = Executor {
                     status: self.status.clone(),
                     version_manager: &mut self.version_manager,
@@ -395,6 +506,7 @@ impl Controller {
                     book_name: &self.curr_book_name,
                     calc_config: self.settings.calc_config,
                     async_funcs: &self.settings.async_funcs,
+                    input_locale: &self.settings.input_locale,
                     updated_cells: HashSet::new(),
                     dirty_vertices: HashSet::new(),
                     sheet_updated: false,
@@ -431,6 +543,20 @@ impl Controller {
                             .filter_map(|id| result.status.sheet_info_manager.get_sheet_idx(id))
                             .map(|i| i as u32)
                             .collect();
+                        if let Some(payloads) = audited_payloads {
+                            self.audit_manager.record(
+                                payloads,
+                                &self.status,
+                                &result.status,
+                                result.updated_cells.iter().chain(&result.cells_removed),
+                            );
+                        }
+                        if let Some(payloads) = branch_payloads {
+                            self.branch_manager
+                                .record(payloads, &self.status, &result.status);
+                        } else if on_branch && !matches!(c, WorkbookUpdateType::DoNothing) {
+                            self.branch_manager.mark_unrecorded();
+                        }
                         self.status = result.status;
                         // Committed write — bump the revision unless nothing
                         // actually changed, so readers can detect concurrent
@@ -517,6 +643,7 @@ impl Controller {
                     book_name: &self.curr_book_name,
                     calc_config: self.settings.calc_config,
                     async_funcs: &self.settings.async_funcs,
+                    input_locale: &self.settings.input_locale,
                     updated_cells: HashSet::new(),
                     dirty_vertices,
                     sheet_updated: false,
@@ -602,6 +729,10 @@ impl Controller {
             match self.version_manager.undo() {
                 Some(status) => {
                     self.status = status;
+                    self.branch_manager.undo();
+                    if self.audit_manager.is_enabled() && !self.branch_manager.is_on_branch() {
+                        self.audit_manager.record_undo_redo("Undo");
+                    }
                     true
                 }
                 None => false,
@@ -629,6 +760,10 @@ impl Controller {
             match self.version_manager.redo() {
                 Some(status) => {
                     self.status = status;
+                    self.branch_manager.redo();
+                    if self.audit_manager.is_enabled() && !self.branch_manager.is_on_branch() {
+                        self.audit_manager.record_undo_redo("Redo");
+                    }
                     true
                 }
                 None => false,
@@ -640,6 +775,192 @@ impl Controller {
         changed
     }
 
+    /// Fork main into a new what-if branch. The branch shown stays shown.
+    pub fn fork_branch(&mut self, name: String) -> Result<()> {
+        let shown = self
+            .temp_status
+            .as_ref()
+            .map_or(&self.status, |t| &t.fork_status);
+        self.branch_manager.fork(name, shown)?;
+        Ok(())
+    }
+
+    /// The status of the branch shown, without the edits of an active temp
+    /// branch.
+    pub fn committed_status(&self) -> &Status {
+        self.temp_status
+            .as_ref()
+            .map_or(&self.status, |t| &t.fork_status)
+    }
+
+    /// Show branch `name` (`main` for main). An active temp branch is
+    /// discarded first.
+    pub fn switch_branch(&mut self, name: &str) -> Result<()> {
+        self.clean_temp_status();
+        self.branch_manager
+            .switch(name, &mut self.status, &mut self.version_manager)?;
+        Ok(())
+    }
+
+    /// Drop a branch, switching to main first if it is shown. Returns
+    /// `true` if it existed.
+    pub fn delete_branch(&mut self, name: &str) -> Result<bool> {
+        if name == MAIN {
+            return Ok(false);
+        }
+        if self.branch_manager.current() == name {
+            self.switch_branch(MAIN)?;
+        }
+        Ok(self.branch_manager.remove(name))
+    }
+
+    /// Merge branch `name` into main by replaying its edits there as one
+    /// undoable transaction, then drop it. With conflicts nothing happens
+    /// unless `force` is set. Main is shown afterwards if the merge went
+    /// through.
+    pub fn merge_branch(&mut self, name: &str, force: bool) -> Result<MergeResult> {
+        let payloads = self.branch_manager.rebase(name, self.committed_status())?;
+        let conflicts = self
+            .branch_manager
+            .conflicts(name, self.committed_status())?;
+        if !conflicts.is_empty() && !force {
+            return Ok(MergeResult {
+                merged: false,
+                conflicts,
+            });
+        }
+        self.switch_branch(MAIN)?;
+        if !payloads.is_empty() {
+            let effect = self.handle_action(EditAction::Payloads(PayloadsAction {
+                payloads,
+                undoable: true,
+                init: false,
+            }));
+            if let StatusCode::Err(_) = effect.status {
+                let msg = effect.error_message.unwrap_or_default();
+                return Err(Error::PayloadError(format!(
+                    "merging branch {} failed: {}",
+                    name, msg
+                )));
+            }
+        }
+        self.branch_manager.remove(name);
+        Ok(MergeResult {
+            merged: true,
+            conflicts,
+        })
+    }
+
+    /// Goal Seek: find the value of the changing cell that makes the target
+    /// cell, which must hold a formula, show `goal`. Each trial value is
+    /// evaluated on a scratch copy of the status. The value found is put
+    /// into the changing cell in the temp status, so nothing is committed
+    /// until `commit_temp_status`, and `clean_temp_status` takes it back.
+    pub fn goal_seek(
+        &mut self,
+        sheet_idx: usize,
+        target_row: usize,
+        target_col: usize,
+        goal: f64,
+        changing_row: usize,
+        changing_col: usize,
+    ) -> Result<GoalSeekResult> {
+        let refuse = |msg: &str| Error::PayloadError(format!("Goal Seek: {}", msg));
+        let sheet_id = self
+            .status
+            .sheet_info_manager
+            .get_sheet_id(sheet_idx)
+            .ok_or(BasicError::SheetIdxExceed(sheet_idx))?;
+        let nav = &self.status.navigator;
+        let target = nav.fetch_cell_id(&sheet_id, target_row, target_col)?;
+        let changing = nav.fetch_cell_id(&sheet_id, changing_row, changing_col)?;
+        let formulas = &self.status.formula_manager.formulas;
+        if !formulas.contains_key(&(sheet_id, target)) {
+            return Err(refuse("the target cell must contain a formula"));
+        }
+        if formulas.contains_key(&(sheet_id, changing)) {
+            return Err(refuse("the changing cell must contain a value"));
+        }
+        let start = match read_display_value(&self.status, sheet_id, &changing) {
+            Value::Number(n) => n,
+            Value::Empty => 0.,
+            _ => return Err(refuse("the changing cell must contain a number")),
+        };
+
+        let mut version_manager = VersionManager::default();
+        let mut async_func_manager = AsyncFuncManager::default();
+        let executor = RefCell::new(Executor {
+            status: self.status.clone(),
+            version_manager: &mut version_manager,
+            async_func_manager: &mut async_func_manager,
+            book_name: &self.curr_book_name,
+            calc_config: self.settings.calc_config,
+            async_funcs: &self.settings.async_funcs,
+            input_locale: &self.settings.input_locale,
+            updated_cells: HashSet::new(),
+            dirty_vertices: HashSet::new(),
+            sheet_updated: false,
+            cell_updated: false,
+            cells_removed: HashSet::new(),
+            sid_assigner: &mut self.sid_assigner,
+            checkpoint_manager: &self.checkpoint_manager,
+            style_updated: HashSet::new(),
+            row_inserted: vec![],
+            row_removed: vec![],
+            col_inserted: vec![],
+            col_removed: vec![],
+            header_updated: HashSet::new(),
+        });
+        // How far the target is from the goal with `x` in the changing
+        // cell; NaN when it doesn't show a number.
+        let miss = |x: f64| {
+            let input = vec![(sheet_idx, changing_row, changing_col, Value::Number(x))];
+            match executor.borrow_mut().evaluate(input) {
+                Ok(status) => match read_display_value(&status, sheet_id, &target) {
+                    Value::Number(n) => n - goal,
+                    _ => f64::NAN,
+                },
+                Err(_) => f64::NAN,
+            }
+        };
+        let found = if miss(start).is_nan() {
+            None
+        } else {
+            newton(start, miss)
+                .filter(|x| miss(*x).abs() <= GOAL_SEEK_TOLERANCE)
+                .or_else(|| bracket(start, &miss).and_then(|(lo, hi)| bisection(lo, hi, miss)))
+        };
+        drop(executor);
+
+        let Some(x) = found else {
+            return Ok(GoalSeekResult {
+                found: false,
+                input_value: start,
+                target_value: read_display_value(&self.status, sheet_id, &target),
+            });
+        };
+        let effect = self.handle_action_in_temp_status(PayloadsAction {
+            payloads: vec![EditPayload::CellInput(CellInput {
+                sheet_idx,
+                row: changing_row,
+                col: changing_col,
+                content: x.to_string(),
+                skip_recognition: Some(true),
+            })],
+            undoable: true,
+            init: false,
+        });
+        if let StatusCode::Err(_) = effect.status {
+            let msg = effect.error_message.unwrap_or_default();
+            return Err(refuse(&msg));
+        }
+        Ok(GoalSeekResult {
+            found: true,
+            input_value: x,
+            target_value: read_display_value(&self.status, sheet_id, &target),
+        })
+    }
+
     /// Drop the undo/redo history, keeping the current workbook state as the
     /// new baseline. Nothing is reverted — only the history is cleared. Used
     /// by headless/server callers that treat each operation as atomic and
@@ -653,6 +974,31 @@ impl Controller {
     }
 }
 
+/// How close to the goal Goal Seek must bring the target.
+const GOAL_SEEK_TOLERANCE: f64 = 1e-6;
+
+/// Widen a window around `start` until `f` changes sign across one of its
+/// halves, for bisection to search.
+fn bracket(start: f64, f: &impl Fn(f64) -> f64) -> Option<(f64, f64)> {
+    let sign = f(start).signum();
+    let crosses = |x: f64| {
+        let v = f(x);
+        !v.is_nan() && v.signum() != sign
+    };
+    let mut step = start.abs().max(1.);
+    for _ in 0..64 {
+        let (lo, hi) = (start - step, start + step);
+        if crosses(lo) {
+            return Some((lo, start));
+        }
+        if crosses(hi) {
+            return Some((start, hi));
+        }
+        step *= 2.;
+    }
+    None
+}
+
 #[cfg(test)]
 mod tests {
     use logisheets_base::{CellId, CellValue};
@@ -681,6 +1027,7 @@ mod tests {
                 row: 0,
                 col: 0,
                 content: String::from("=ABS(1)"),
+                ..Default::default()
             })],
             undoable: true,
             init: false,
@@ -699,6 +1046,7 @@ mod tests {
                 row: 0,
                 col: 0,
                 content: String::from("abcdefghijklmnopqrstuvwx"),
+                ..Default::default()
             })],
             undoable: true,
             init: false,
@@ -780,6 +1128,7 @@ mod tests {
                 row: 0,
                 col: 0,
                 content: String::from("=ABS(100)"),
+                ..Default::default()
             })],
             undoable: true,
             init: false,
@@ -807,6 +1156,7 @@ mod tests {
                 row: 0,
                 col: 0,
                 content: String::from("=B2"),
+                ..Default::default()
             })],
             undoable: true,
             init: false,
@@ -819,6 +1169,7 @@ mod tests {
                 row: 0,
                 col: 0,
                 content: String::from("=B2+1"),
+                ..Default::default()
             })],
             undoable: true,
             init: false,
@@ -1143,6 +1494,7 @@ mod tests {
                 row: 0,
                 col: 0,
                 content: String::from("1"),
+                ..Default::default()
             })],
             undoable: true,
             init: false,
@@ -1201,6 +1553,7 @@ mod tests {
                 row: 0,
                 col: 0,
                 content: String::from("1"),
+                ..Default::default()
             })],
             undoable: true,
             init: false,
@@ -1215,6 +1568,7 @@ mod tests {
                 row: 1,
                 col: 0,
                 content: String::from("2"),
+                ..Default::default()
             })],
             undoable: true,
             init: false,
@@ -1266,6 +1620,7 @@ mod tests {
                 row: 0,
                 col: 0,
                 content: String::from("temp"),
+                ..Default::default()
             })],
             undoable: true,
             init: false,
@@ -1280,6 +1635,7 @@ mod tests {
                 row: 0,
                 col: 0,
                 content: String::from("real"),
+                ..Default::default()
             })],
             undoable: true,
             init: false,
@@ -1460,6 +1816,7 @@ mod tests {
                 field_formulas: vec![],
                 validation_formulas: vec![],
                 editability_formulas: vec![],
+                field_types: None,
             })],
             undoable: true,
             init: false,
@@ -1562,6 +1919,7 @@ mod tests {
                 field_formulas: vec![],
                 validation_formulas: vec![],
                 editability_formulas: vec![],
+                field_types: None,
             })],
             undoable: true,
             init: false,
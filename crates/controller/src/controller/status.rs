@@ -2,12 +2,15 @@ use imbl::HashMap;
 use imbl::HashSet;
 use logisheets_base::CellId;
 use logisheets_base::SheetId;
+use logisheets_parser::unparse;
 
 use crate::cell_attachments::CellAttachmentsManager;
 use crate::chart_manager::ChartManager;
 use crate::conditional_formatting_manager::ConditionalFormattingManager;
+use crate::connectors::NameFetcher;
 use crate::container::DataContainer;
 use crate::cube_manager::CubeManager;
+use crate::data_table_manager::DataTableManager;
 use crate::data_validation_manager::DataValidationManager;
 use crate::exclusive::ExclusiveManager;
 use crate::ext_book_manager::ExtBooksManager;
@@ -23,7 +26,9 @@ use crate::navigator::Navigator;
 use crate::block_manager::field_manager::FieldRenderManager;
 use crate::block_manager::schema_manager::SchemaManager;
 use crate::range_manager::RangeManager;
+use crate::scenario_manager::ScenarioManager;
 use crate::style_manager::StyleManager;
+use crate::theme_manager::ThemeManager;
 use crate::workbook::sheet_info_manager::SheetInfoManager;
 
 #[derive(Debug, Clone)]
@@ -41,6 +46,7 @@ pub struct Status {
     pub external_links_manager: ExtBooksManager,
     pub sheet_info_manager: SheetInfoManager,
     pub style_manager: StyleManager,
+    pub theme_manager: ThemeManager,
     pub cell_attachment_manager: CellAttachmentsManager,
     pub exclusive_manager: ExclusiveManager,
     pub block_schema_manager: SchemaManager,
@@ -49,6 +55,8 @@ pub struct Status {
     pub chart_manager: ChartManager,
     pub data_validation_manager: DataValidationManager,
     pub conditional_formatting_manager: ConditionalFormattingManager,
+    pub data_table_manager: DataTableManager,
+    pub scenario_manager: ScenarioManager,
 
     pub dirty_cells_next_round: HashSet<(SheetId, CellId)>,
 }
@@ -71,6 +79,7 @@ impl Default for Status {
             external_links_manager: ExtBooksManager::new(),
             sheet_info_manager: SheetInfoManager::default(),
             style_manager: StyleManager::default(),
+            theme_manager: ThemeManager::default(),
             cell_attachment_manager: CellAttachmentsManager::default(),
             exclusive_manager: ExclusiveManager::default(),
             dirty_cells_next_round: HashSet::new(),
@@ -80,6 +89,36 @@ impl Default for Status {
             chart_manager: ChartManager::new(),
             data_validation_manager: DataValidationManager::new(),
             conditional_formatting_manager: ConditionalFormattingManager::new(),
+            data_table_manager: DataTableManager::new(),
+            scenario_manager: ScenarioManager::new(),
         }
     }
 }
+
+impl Status {
+    /// Looks up the names behind the ids of this status, as formulas are
+    /// unparsed against it.
+    pub fn name_fetcher(&self) -> NameFetcher<'_> {
+        NameFetcher {
+            func_manager: &self.func_id_manager,
+            sheet_id_manager: &self.sheet_id_manager,
+            external_links_manager: &self.external_links_manager,
+            text_id_manager: &self.text_id_manager,
+            name_id_manager: &self.name_id_manager,
+            navigator: &self.navigator,
+            range_manager: &self.range_manager,
+            cube_manager: &self.cube_manager,
+            ext_ref_manager: &self.ext_ref_manager,
+            block_schema_manager: &self.block_schema_manager,
+        }
+    }
+
+    /// The formula of a cell without its leading `=`, or an empty string if
+    /// the cell has none.
+    pub fn formula_text(&self, sheet_id: SheetId, cell_id: &CellId) -> String {
+        let Some(node) = self.formula_manager.formulas.get(&(sheet_id, *cell_id)) else {
+            return String::new();
+        };
+        unparse::unparse(node, &mut self.name_fetcher(), sheet_id).unwrap_or_default()
+    }
+}
@@ -0,0 +1,22 @@
+use logisheets_base::{Cube, CubeId};
+
+use crate::cube_manager::ctx::CubeExecCtx;
+
+use super::{CubeExecutor, CubeUpdateType};
+
+/// A sheet created at `idx` joins every cube it lands inside.
+pub fn create_sheet<C>(exec_ctx: CubeExecutor, idx: usize, ctx: &C) -> CubeExecutor
+where
+    C: CubeExecCtx,
+{
+    let mut func = |cube: &Cube, _: &CubeId| -> CubeUpdateType {
+        let from_idx = ctx.fetch_sheet_index(&cube.from_sheet).unwrap();
+        let to_idx = ctx.fetch_sheet_index(&cube.to_sheet).unwrap();
+        let (start, end) = (from_idx.min(to_idx), from_idx.max(to_idx));
+        if idx <= start || idx > end {
+            return CubeUpdateType::None;
+        }
+        CubeUpdateType::Dirty
+    };
+    exec_ctx.cube_update(&mut func)
+}
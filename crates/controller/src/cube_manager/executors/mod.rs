@@ -1,17 +1,25 @@
+mod create_sheet;
 mod delete_line;
 mod delete_sheet;
 mod input;
 mod insert_line;
+mod move_range;
+mod move_sheet;
+mod shift_cells;
 mod utils;
 use std::collections::HashSet;
 
+use create_sheet::create_sheet;
 use delete_line::delete_line;
 use delete_sheet::delete_sheet;
 use input::input;
 use insert_line::insert_line;
 use logisheets_base::{Cube, CubeId, errors::BasicError};
+use move_range::move_range;
+use move_sheet::move_sheet;
+use shift_cells::shift_cells;
 
-use crate::{Error, edit_action::EditPayload};
+use crate::{Error, cell_shift::CellShift, edit_action::EditPayload, range_move::RangeMove};
 
 use super::{ctx::CubeExecCtx, manager::CubeManager};
 
@@ -129,6 +137,27 @@ impl CubeExecutor {
                     ctx,
                 ))
             }
+            EditPayload::InsertCells(p) => {
+                let sheet_id = ctx
+                    .fetch_sheet_id_by_index(p.sheet_idx)
+                    .map_err(BasicError::SheetIdxExceed)?;
+                Ok(shift_cells(self, sheet_id, &CellShift::from(&p), ctx))
+            }
+            EditPayload::DeleteCells(p) => {
+                let sheet_id = ctx
+                    .fetch_sheet_id_by_index(p.sheet_idx)
+                    .map_err(BasicError::SheetIdxExceed)?;
+                Ok(shift_cells(self, sheet_id, &CellShift::from(&p), ctx))
+            }
+            EditPayload::MoveRange(p) => {
+                let from = ctx
+                    .fetch_sheet_id_by_index(p.sheet_idx)
+                    .map_err(BasicError::SheetIdxExceed)?;
+                let to = ctx
+                    .fetch_sheet_id_by_index(p.dest_sheet_idx)
+                    .map_err(BasicError::SheetIdxExceed)?;
+                Ok(move_range(self, &RangeMove::new(from, to, &p), ctx))
+            }
             EditPayload::DeleteSheet(p) => {
                 let sheet_id = ctx
                     .fetch_sheet_id_by_index(p.idx)
@@ -136,6 +165,8 @@ impl CubeExecutor {
                 let res = delete_sheet(self, sheet_id, ctx);
                 Ok(res)
             }
+            EditPayload::CreateSheet(p) => Ok(create_sheet(self, p.idx, ctx)),
+            EditPayload::MoveSheet(p) => Ok(move_sheet(self, p.from, p.to, ctx)),
             _ => Ok(self),
         }
     }
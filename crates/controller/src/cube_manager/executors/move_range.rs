@@ -0,0 +1,36 @@
+use logisheets_base::{Cube, CubeCross, CubeId};
+
+use crate::{cube_manager::ctx::CubeExecCtx, range_move::RangeMove};
+
+use super::{CubeExecutor, CubeUpdateType};
+
+// As with a cell shift, a cube keeps its positions; it is re-evaluated when
+// the moved or the overwritten cells lie on a sheet it spans and reach into
+// its cross.
+pub fn move_range<C>(exec_ctx: CubeExecutor, mv: &RangeMove, old_ctx: &C) -> CubeExecutor
+where
+    C: CubeExecCtx,
+{
+    let mut func = |cube: &Cube, _: &CubeId| -> CubeUpdateType {
+        let from_idx = old_ctx.fetch_sheet_index(&cube.from_sheet).unwrap();
+        let to_idx = old_ctx.fetch_sheet_index(&cube.to_sheet).unwrap();
+        let (start_row, start_col, end_row, end_col) = match cube.cross {
+            CubeCross::Single(row, col) => (row, col, row, col),
+            CubeCross::RowRange(start, end) => (start, 0, end, usize::MAX),
+            CubeCross::ColRange(start, end) => (0, start, usize::MAX, end),
+            CubeCross::AddrRange(start, end) => (start.row, start.col, end.row, end.col),
+        };
+        let touched = [mv.from, mv.to].into_iter().any(|sheet| {
+            let idx = old_ctx.fetch_sheet_index(&sheet).unwrap();
+            idx >= from_idx
+                && idx <= to_idx
+                && mv.touches(sheet, start_row, start_col, end_row, end_col)
+        });
+        if touched {
+            CubeUpdateType::Dirty
+        } else {
+            CubeUpdateType::None
+        }
+    };
+    exec_ctx.cube_update(&mut func)
+}
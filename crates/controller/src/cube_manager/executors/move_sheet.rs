@@ -0,0 +1,25 @@
+use logisheets_base::{Cube, CubeId};
+
+use crate::cube_manager::ctx::CubeExecCtx;
+
+use super::{CubeExecutor, CubeUpdateType};
+
+/// Moving a sheet from `from` to `to` only shifts the sheets between the two
+/// positions, so a cube whose span does not reach that interval keeps its
+/// members.
+pub fn move_sheet<C>(exec_ctx: CubeExecutor, from: usize, to: usize, ctx: &C) -> CubeExecutor
+where
+    C: CubeExecCtx,
+{
+    let (lo, hi) = (from.min(to), from.max(to));
+    let mut func = |cube: &Cube, _: &CubeId| -> CubeUpdateType {
+        let from_idx = ctx.fetch_sheet_index(&cube.from_sheet).unwrap();
+        let to_idx = ctx.fetch_sheet_index(&cube.to_sheet).unwrap();
+        let (start, end) = (from_idx.min(to_idx), from_idx.max(to_idx));
+        if end < lo || start > hi {
+            return CubeUpdateType::None;
+        }
+        CubeUpdateType::Dirty
+    };
+    exec_ctx.cube_update(&mut func)
+}
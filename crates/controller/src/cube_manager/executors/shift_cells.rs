@@ -0,0 +1,42 @@
+use logisheets_base::{Cube, CubeCross, CubeId};
+
+use crate::{SheetId, cell_shift::CellShift, cube_manager::ctx::CubeExecCtx};
+
+use super::{CubeExecutor, CubeUpdateType};
+
+// A cube names positions on every sheet it spans, so shifting cells on one of
+// them never re-anchors it; it only needs re-evaluating when the shifted
+// area reaches into its cross.
+pub fn shift_cells<C>(
+    exec_ctx: CubeExecutor,
+    sheet: SheetId,
+    shift: &CellShift,
+    old_ctx: &C,
+) -> CubeExecutor
+where
+    C: CubeExecCtx,
+{
+    let mut func = |cube: &Cube, _: &CubeId| -> CubeUpdateType {
+        let from_idx = old_ctx.fetch_sheet_index(&cube.from_sheet).unwrap();
+        let to_idx = old_ctx.fetch_sheet_index(&cube.to_sheet).unwrap();
+        let curr_idx = old_ctx.fetch_sheet_index(&sheet).unwrap();
+        if curr_idx < from_idx || curr_idx > to_idx {
+            return CubeUpdateType::None;
+        }
+
+        let touched = match cube.cross {
+            CubeCross::Single(row, col) => shift.touches(row, col, row, col),
+            CubeCross::RowRange(start, end) => shift.touches(start, 0, end, usize::MAX),
+            CubeCross::ColRange(start, end) => shift.touches(0, start, usize::MAX, end),
+            CubeCross::AddrRange(start, end) => {
+                shift.touches(start.row, start.col, end.row, end.col)
+            }
+        };
+        if touched {
+            CubeUpdateType::Dirty
+        } else {
+            CubeUpdateType::None
+        }
+    };
+    exec_ctx.cube_update(&mut func)
+}
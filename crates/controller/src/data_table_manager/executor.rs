@@ -0,0 +1,85 @@
+//! Applies the data table edit payloads. Filling in the results is the
+//! controller's `Executor::refresh_data_tables`, which runs after the
+//! calculation.
+
+use logisheets_base::errors::BasicError;
+
+use super::DataTableManager;
+use super::resolve::layout;
+use crate::Error;
+use crate::edit_action::{EditPayload, InputCell};
+use crate::navigator::Navigator;
+use crate::workbook::sheet_info_manager::SheetInfoManager;
+
+pub struct DataTableExecutor {
+    pub manager: DataTableManager,
+}
+
+impl DataTableExecutor {
+    pub fn new(manager: DataTableManager) -> Self {
+        Self { manager }
+    }
+
+    /// Returns `(self, changed)`; `changed` is `false` for payloads this
+    /// executor does not handle.
+    pub fn execute(
+        mut self,
+        nav: &Navigator,
+        sheet_info: &SheetInfoManager,
+        payload: EditPayload,
+    ) -> Result<(Self, bool), Error> {
+        match payload {
+            EditPayload::CreateDataTable(p) => {
+                let refuse = |msg: &str| Error::PayloadError(format!("CreateDataTable: {}", msg));
+                let sheet_id = sheet_info
+                    .get_sheet_id(p.sheet_idx)
+                    .ok_or(BasicError::SheetIdxExceed(p.sheet_idx))?;
+                let (r0, r1) = (p.start_row.min(p.end_row), p.start_row.max(p.end_row));
+                let (c0, c1) = (p.start_col.min(p.end_col), p.start_col.max(p.end_col));
+                if r0 == 0 || c0 == 0 {
+                    return Err(refuse(
+                        "the results need a row above and a column to the left",
+                    ));
+                }
+                if p.row_input.is_none() && p.col_input.is_none() {
+                    return Err(refuse("give a row input cell, a column input cell or both"));
+                }
+                let inside = |c: &InputCell| {
+                    (r0 - 1..=r1).contains(&c.row) && (c0 - 1..=c1).contains(&c.col)
+                };
+                if p.row_input.iter().chain(p.col_input.iter()).any(inside) {
+                    return Err(refuse("an input cell must be outside the table"));
+                }
+                let overlaps = self.manager.tables_of_sheet(sheet_id).any(|t| {
+                    layout(nav, t).is_some_and(|l| {
+                        l.start.0 <= r1 && r0 <= l.end.0 && l.start.1 <= c1 && c0 <= l.end.1
+                    })
+                });
+                if overlaps {
+                    return Err(refuse("the results overlap another data table"));
+                }
+                let input = |c: Option<InputCell>| -> Result<_, Error> {
+                    c.map(|c| nav.fetch_cell_id(&sheet_id, c.row, c.col))
+                        .transpose()
+                        .map_err(Error::from)
+                };
+                let start = nav.fetch_cell_id(&sheet_id, r0, c0)?;
+                let end = nav.fetch_cell_id(&sheet_id, r1, c1)?;
+                let row_input = input(p.row_input)?;
+                let col_input = input(p.col_input)?;
+                self.manager.add(sheet_id, start, end, row_input, col_input);
+                Ok((self, true))
+            }
+            EditPayload::RemoveDataTable(p) => {
+                if self.manager.remove(p.table_id).is_none() {
+                    return Err(Error::PayloadError(format!(
+                        "no data table with id {}",
+                        p.table_id
+                    )));
+                }
+                Ok((self, true))
+            }
+            _ => Ok((self, false)),
+        }
+    }
+}
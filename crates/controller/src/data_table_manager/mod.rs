@@ -0,0 +1,99 @@
+//! Models Excel data tables (`{=TABLE(row_input, col_input)}`).
+//!
+//! A data table fills a rectangle of result cells by recalculating formulas
+//! with an input cell swapped for each of the values laid out along the
+//! rectangle's edges:
+//!
+//! - Both inputs: the formula sits in the cell above and left of the
+//!   results. The row input takes each value in the row above the results,
+//!   the column input each value in the column to their left.
+//! - Row input only: the values run along the row above the results and
+//!   the formulas down the column to their left, one per result row.
+//! - Column input only: the values run down the column to the left of the
+//!   results and the formulas along the row above, one per result column.
+//!
+//! A table is anchored on cell ids, so it follows row and column edits. Its
+//! results are plain values in the container. `Executor::refresh_data_tables`
+//! recomputes those of the tables a transaction may have changed after its
+//! calculation, evaluating each substitution on a scratch copy of the
+//! status, and nothing else may write to them.
+
+pub(crate) mod executor;
+// Navigator-dependent conversions; crate-internal (Navigator is not public).
+pub(crate) mod resolve;
+
+use imbl::Vector;
+use logisheets_base::{CellId, SheetId};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DataTable {
+    /// Stable within a session: minted on load and on create. Not persisted.
+    pub id: u32,
+    pub sheet_id: SheetId,
+    /// The top-left result cell.
+    pub start: CellId,
+    /// The bottom-right result cell.
+    pub end: CellId,
+    pub row_input: Option<CellId>,
+    pub col_input: Option<CellId>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DataTableManager {
+    tables: Vector<DataTable>,
+    next_id: u32,
+}
+
+impl DataTableManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(
+        &mut self,
+        sheet_id: SheetId,
+        start: CellId,
+        end: CellId,
+        row_input: Option<CellId>,
+        col_input: Option<CellId>,
+    ) -> u32 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.tables.push_back(DataTable {
+            id,
+            sheet_id,
+            start,
+            end,
+            row_input,
+            col_input,
+        });
+        id
+    }
+
+    /// Returns the table removed, if there was one with this id.
+    pub fn remove(&mut self, id: u32) -> Option<DataTable> {
+        let idx = self.tables.iter().position(|t| t.id == id)?;
+        Some(self.tables.remove(idx))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tables.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &DataTable> {
+        self.tables.iter()
+    }
+
+    pub fn tables_of_sheet(&self, sheet_id: SheetId) -> impl Iterator<Item = &DataTable> {
+        self.tables.iter().filter(move |t| t.sheet_id == sheet_id)
+    }
+
+    /// Copy `from`'s tables onto `to`. A duplicated sheet keeps its cell
+    /// ids, so the anchors carry over as they are.
+    pub fn duplicate_sheet(&mut self, from: SheetId, to: SheetId) {
+        let copies = self.tables_of_sheet(from).cloned().collect::<Vec<_>>();
+        for t in copies {
+            self.add(to, t.start, t.end, t.row_input, t.col_input);
+        }
+    }
+}
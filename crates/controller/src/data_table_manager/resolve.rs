@@ -0,0 +1,132 @@
+//! Positions of a [`DataTable`], read back from the navigator.
+//!
+//! Everything that evaluates or writes a table works on positions: which
+//! value goes into which input cell, and which cell's result lands where.
+
+use logisheets_base::SheetId;
+
+use super::{DataTable, DataTableManager};
+use crate::navigator::Navigator;
+use crate::sqref::format_rect;
+
+/// A table at its current position, 0-based and inclusive.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Layout {
+    pub sheet_id: SheetId,
+    pub start: (usize, usize),
+    pub end: (usize, usize),
+    /// `None` when the table has no such input, or it was deleted.
+    pub row_input: Option<(usize, usize)>,
+    pub col_input: Option<(usize, usize)>,
+    /// An input cell the table had was deleted. Excel shows `#REF!` in every
+    /// result cell then.
+    pub broken: bool,
+}
+
+/// One recalculation a table needs.
+pub(crate) struct Trial {
+    /// Each input cell with the cell holding the value it takes.
+    pub inputs: Vec<((usize, usize), (usize, usize))>,
+    /// Each result cell this fills with the cell whose value it takes.
+    pub reads: Vec<((usize, usize), (usize, usize))>,
+}
+
+/// `None` when a corner of the table was deleted.
+pub(crate) fn layout(nav: &Navigator, table: &DataTable) -> Option<Layout> {
+    let sheet_id = table.sheet_id;
+    let start = nav.fetch_cell_idx(&sheet_id, &table.start).ok()?;
+    let end = nav.fetch_cell_idx(&sheet_id, &table.end).ok()?;
+    if start.0 == 0 || start.1 == 0 {
+        return None;
+    }
+    let position = |input: Option<_>| input.map(|id| nav.fetch_cell_idx(&sheet_id, &id).ok());
+    let row_input = position(table.row_input);
+    let col_input = position(table.col_input);
+    let broken = matches!(row_input, Some(None)) || matches!(col_input, Some(None));
+    Some(Layout {
+        sheet_id,
+        start,
+        end,
+        row_input: row_input.flatten(),
+        col_input: col_input.flatten(),
+        broken,
+    })
+}
+
+/// The table on `sheet_id` whose results include `(row, col)`.
+pub(crate) fn table_at(
+    nav: &Navigator,
+    manager: &DataTableManager,
+    sheet_id: SheetId,
+    row: usize,
+    col: usize,
+) -> Option<(u32, Layout)> {
+    manager.tables_of_sheet(sheet_id).find_map(|t| {
+        let l = layout(nav, t)?;
+        l.contains(row, col).then_some((t.id, l))
+    })
+}
+
+impl Layout {
+    pub fn contains(&self, row: usize, col: usize) -> bool {
+        (self.start.0..=self.end.0).contains(&row) && (self.start.1..=self.end.1).contains(&col)
+    }
+
+    /// The `ref` of the table's results, e.g. `B2:D5`.
+    pub fn reference(&self) -> String {
+        format_rect(self.start.0, self.start.1, self.end.0, self.end.1)
+    }
+
+    /// What the result cells show as their formula, e.g. `TABLE(B1,A2)`. A
+    /// missing input is left empty, as in Excel.
+    pub fn formula(&self) -> String {
+        let cell =
+            |p: Option<(usize, usize)>| p.map_or(String::new(), |(r, c)| format_rect(r, c, r, c));
+        format!("TABLE({},{})", cell(self.row_input), cell(self.col_input))
+    }
+
+    pub fn trials(&self) -> Vec<Trial> {
+        let (r0, c0) = self.start;
+        let (r1, c1) = self.end;
+        match (self.row_input, self.col_input) {
+            (Some(row_input), Some(col_input)) => (r0..=r1)
+                .flat_map(|r| {
+                    (c0..=c1).map(move |c| Trial {
+                        inputs: vec![(row_input, (r0 - 1, c)), (col_input, (r, c0 - 1))],
+                        reads: vec![((r, c), (r0 - 1, c0 - 1))],
+                    })
+                })
+                .collect(),
+            (Some(row_input), None) => (c0..=c1)
+                .map(|c| Trial {
+                    inputs: vec![(row_input, (r0 - 1, c))],
+                    reads: (r0..=r1).map(|r| ((r, c), (r, c0 - 1))).collect(),
+                })
+                .collect(),
+            (None, Some(col_input)) => (r0..=r1)
+                .map(|r| Trial {
+                    inputs: vec![(col_input, (r, c0 - 1))],
+                    reads: (c0..=c1).map(|c| ((r, c), (r0 - 1, c))).collect(),
+                })
+                .collect(),
+            (None, None) => vec![],
+        }
+    }
+
+    /// Every cell the results are computed from: the input cells, the
+    /// values put into them and the formulas read back.
+    pub fn sources(&self) -> Vec<(usize, usize)> {
+        let mut sources: Vec<_> = self.row_input.into_iter().chain(self.col_input).collect();
+        for trial in self.trials() {
+            sources.extend(trial.inputs.iter().map(|&(_, value)| value));
+            sources.extend(trial.reads.iter().map(|&(_, formula)| formula));
+        }
+        sources
+    }
+
+    /// Every result cell, row by row.
+    pub fn cells(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        (self.start.0..=self.end.0)
+            .flat_map(move |r| (self.start.1..=self.end.1).map(move |c| (r, c)))
+    }
+}
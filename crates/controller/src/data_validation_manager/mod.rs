@@ -73,6 +73,12 @@ impl DataValidationManager {
     pub fn is_empty(&self) -> bool {
         self.validations.is_empty()
     }
+
+    pub fn duplicate_sheet(&mut self, from: SheetId, to: SheetId) {
+        if let Some(v) = self.validations.get(&from).cloned() {
+            self.validations.insert(to, v);
+        }
+    }
 }
 
 // `sqref` parsing is shared with conditional formatting; re-exported here so
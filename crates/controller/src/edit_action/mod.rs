@@ -1,4 +1,4 @@
-use crate::conditional_formatting_manager::spec::CfRuleSpec;
+pub use crate::conditional_formatting_manager::spec::{CfFormatSpec, CfRuleSpec};
 use gents_derives::TS;
 use logisheets_base::{BlockId, CellId, ColId, EphemeralId, RowId, SheetId, async_func::Task};
 
@@ -100,6 +100,7 @@ pub enum EditPayload {
     UpsertFieldRenderInfo(UpsertFieldRenderInfo),
     MoveBlockLine(MoveBlockLine),
     ReorderBlockLines(ReorderBlockLines),
+    ImportBlockRecords(ImportBlockRecords),
 
     // DiyCell
     CreateDiyCell(CreateDiyCell),
@@ -121,6 +122,19 @@ pub enum EditPayload {
 
     CellFormatBrush(CellFormatBrush),
     LineFormatBrush(LineFormatBrush),
+    SetCellStyles(SetCellStyles),
+
+    // Named cell styles
+    CreateCellStyle(CreateCellStyle),
+    ModifyCellStyle(ModifyCellStyle),
+    DeleteCellStyle(DeleteCellStyle),
+    ApplyCellStyle(ApplyCellStyle),
+
+    // Theme
+    SetTheme(SetTheme),
+    ApplyBuiltinTheme(ApplyBuiltinTheme),
+    SetThemeColor(SetThemeColor),
+    SetThemeFonts(SetThemeFonts),
 
     CellInput(CellInput),
     EphemeralCellInput(EphemeralCellInput),
@@ -135,6 +149,13 @@ pub enum EditPayload {
     UpdateConditionalFormattingRule(UpdateConditionalFormattingRule),
     MoveConditionalFormattingRule(MoveConditionalFormattingRule),
     DeleteConditionalFormattingRule(DeleteConditionalFormattingRule),
+    CreateDataTable(CreateDataTable),
+    RemoveDataTable(RemoveDataTable),
+    AddScenario(AddScenario),
+    EditScenario(EditScenario),
+    DeleteScenario(DeleteScenario),
+    ShowScenario(ShowScenario),
+    CreateScenarioSummary(CreateScenarioSummary),
     DeleteChart(DeleteChart),
     CreateChart(CreateChart),
     UpdateChart(UpdateChart),
@@ -158,11 +179,16 @@ pub enum EditPayload {
     DeleteSheet(DeleteSheet),
     SetSheetColor(SetSheetColor),
     SetSheetVisible(SetSheetVisible),
+    MoveSheet(MoveSheet),
+    DuplicateSheet(DuplicateSheet),
     // Shifting
     InsertCols(InsertCols),
     DeleteCols(DeleteCols),
     InsertRows(InsertRows),
     DeleteRows(DeleteRows),
+    InsertCells(InsertCells),
+    DeleteCells(DeleteCells),
+    MoveRange(MoveRange),
     InsertColsInBlock(InsertColsInBlock),
     DeleteColsInBlock(DeleteColsInBlock),
     InsertRowsInBlock(InsertRowsInBlock),
@@ -179,6 +205,211 @@ pub enum EditPayload {
     RestoreCheckpoint(RestoreCheckpoint),
 }
 
+impl EditPayload {
+    /// The name of the variant, `CellInput` for a cell input.
+    pub fn name(&self) -> &'static str {
+        match self {
+            EditPayload::BlockInput(_) => "BlockInput",
+            EditPayload::MoveBlock(_) => "MoveBlock",
+            EditPayload::RemoveBlock(_) => "RemoveBlock",
+            EditPayload::CreateBlock(_) => "CreateBlock",
+            EditPayload::ResizeBlock(_) => "ResizeBlock",
+            EditPayload::ConvertBlock(_) => "ConvertBlock",
+            EditPayload::CreateLink(_) => "CreateLink",
+            EditPayload::BindFormSchema(_) => "BindFormSchema",
+            EditPayload::UpsertFieldFormulas(_) => "UpsertFieldFormulas",
+            EditPayload::BindRandomSchema(_) => "BindRandomSchema",
+            EditPayload::UpsertFieldRenderInfo(_) => "UpsertFieldRenderInfo",
+            EditPayload::MoveBlockLine(_) => "MoveBlockLine",
+            EditPayload::ReorderBlockLines(_) => "ReorderBlockLines",
+            EditPayload::ImportBlockRecords(_) => "ImportBlockRecords",
+            EditPayload::CreateDiyCell(_) => "CreateDiyCell",
+            EditPayload::CreateDiyCellById(_) => "CreateDiyCellById",
+            EditPayload::RemoveDiyCell(_) => "RemoveDiyCell",
+            EditPayload::RemoveDiyCellById(_) => "RemoveDiyCellById",
+            EditPayload::CreateAppendix(_) => "CreateAppendix",
+            EditPayload::RemoveAppendix(_) => "RemoveAppendix",
+            EditPayload::CellStyleUpdate(_) => "CellStyleUpdate",
+            EditPayload::EphemeralCellStyleUpdate(_) => "EphemeralCellStyleUpdate",
+            EditPayload::LineStyleUpdate(_) => "LineStyleUpdate",
+            EditPayload::BlockStyleUpdate(_) => "BlockStyleUpdate",
+            EditPayload::BlockLineStyleUpdate(_) => "BlockLineStyleUpdate",
+            EditPayload::BlockLineNameFieldUpdate(_) => "BlockLineNameFieldUpdate",
+            EditPayload::CellFormatBrush(_) => "CellFormatBrush",
+            EditPayload::LineFormatBrush(_) => "LineFormatBrush",
+            EditPayload::SetCellStyles(_) => "SetCellStyles",
+            EditPayload::CreateCellStyle(_) => "CreateCellStyle",
+            EditPayload::ModifyCellStyle(_) => "ModifyCellStyle",
+            EditPayload::DeleteCellStyle(_) => "DeleteCellStyle",
+            EditPayload::ApplyCellStyle(_) => "ApplyCellStyle",
+            EditPayload::SetTheme(_) => "SetTheme",
+            EditPayload::ApplyBuiltinTheme(_) => "ApplyBuiltinTheme",
+            EditPayload::SetThemeColor(_) => "SetThemeColor",
+            EditPayload::SetThemeFonts(_) => "SetThemeFonts",
+            EditPayload::CellInput(_) => "CellInput",
+            EditPayload::EphemeralCellInput(_) => "EphemeralCellInput",
+            EditPayload::EphemeralCellRemove(_) => "EphemeralCellRemove",
+            EditPayload::CellClear(_) => "CellClear",
+            EditPayload::SetCellImage(_) => "SetCellImage",
+            EditPayload::DeleteCellImage(_) => "DeleteCellImage",
+            EditPayload::MoveChart(_) => "MoveChart",
+            EditPayload::CreateConditionalFormattingRule(_) => "CreateConditionalFormattingRule",
+            EditPayload::UpdateConditionalFormattingRule(_) => "UpdateConditionalFormattingRule",
+            EditPayload::MoveConditionalFormattingRule(_) => "MoveConditionalFormattingRule",
+            EditPayload::DeleteConditionalFormattingRule(_) => "DeleteConditionalFormattingRule",
+            EditPayload::CreateDataTable(_) => "CreateDataTable",
+            EditPayload::RemoveDataTable(_) => "RemoveDataTable",
+            EditPayload::AddScenario(_) => "AddScenario",
+            EditPayload::EditScenario(_) => "EditScenario",
+            EditPayload::DeleteScenario(_) => "DeleteScenario",
+            EditPayload::ShowScenario(_) => "ShowScenario",
+            EditPayload::CreateScenarioSummary(_) => "CreateScenarioSummary",
+            EditPayload::DeleteChart(_) => "DeleteChart",
+            EditPayload::CreateChart(_) => "CreateChart",
+            EditPayload::UpdateChart(_) => "UpdateChart",
+            EditPayload::SetColWidth(_) => "SetColWidth",
+            EditPayload::SetRowHeight(_) => "SetRowHeight",
+            EditPayload::SetVisible(_) => "SetVisible",
+            EditPayload::MergeCells(_) => "MergeCells",
+            EditPayload::SplitMergedCells(_) => "SplitMergedCells",
+            EditPayload::AddComment(_) => "AddComment",
+            EditPayload::EditComment(_) => "EditComment",
+            EditPayload::DeleteComment(_) => "DeleteComment",
+            EditPayload::ResolveComment(_) => "ResolveComment",
+            EditPayload::UpsertPerson(_) => "UpsertPerson",
+            EditPayload::SheetRename(_) => "SheetRename",
+            EditPayload::CreateSheet(_) => "CreateSheet",
+            EditPayload::DeleteSheet(_) => "DeleteSheet",
+            EditPayload::SetSheetColor(_) => "SetSheetColor",
+            EditPayload::SetSheetVisible(_) => "SetSheetVisible",
+            EditPayload::MoveSheet(_) => "MoveSheet",
+            EditPayload::DuplicateSheet(_) => "DuplicateSheet",
+            EditPayload::InsertCols(_) => "InsertCols",
+            EditPayload::DeleteCols(_) => "DeleteCols",
+            EditPayload::InsertRows(_) => "InsertRows",
+            EditPayload::DeleteRows(_) => "DeleteRows",
+            EditPayload::InsertCells(_) => "InsertCells",
+            EditPayload::DeleteCells(_) => "DeleteCells",
+            EditPayload::MoveRange(_) => "MoveRange",
+            EditPayload::InsertColsInBlock(_) => "InsertColsInBlock",
+            EditPayload::DeleteColsInBlock(_) => "DeleteColsInBlock",
+            EditPayload::InsertRowsInBlock(_) => "InsertRowsInBlock",
+            EditPayload::DeleteRowsInBlock(_) => "DeleteRowsInBlock",
+            EditPayload::ReproduceCells(_) => "ReproduceCells",
+            EditPayload::RestoreCheckpoint(_) => "RestoreCheckpoint",
+        }
+    }
+
+    /// The indexes of the sheets the payload works on or reads from, in a
+    /// fixed order. The index a sheet is created or moved at is not one of
+    /// them, see `new_sheet_idx_mut`.
+    pub fn sheet_idxs_mut(&mut self) -> Vec<&mut usize> {
+        match self {
+            EditPayload::BlockInput(p) => vec![&mut p.sheet_idx],
+            EditPayload::MoveBlock(p) => vec![&mut p.sheet_idx],
+            EditPayload::RemoveBlock(p) => vec![&mut p.sheet_idx],
+            EditPayload::CreateBlock(p) => vec![&mut p.sheet_idx],
+            EditPayload::ResizeBlock(p) => vec![&mut p.sheet_idx],
+            EditPayload::ConvertBlock(p) => vec![&mut p.sheet_idx],
+            EditPayload::CreateLink(p) => std::iter::once(&mut p.sheet_idx)
+                .chain(p.block_sheet_idx.as_mut())
+                .collect(),
+            EditPayload::BindFormSchema(p) => vec![&mut p.sheet_idx],
+            EditPayload::UpsertFieldFormulas(p) => vec![&mut p.sheet_idx],
+            EditPayload::BindRandomSchema(p) => vec![&mut p.sheet_idx],
+            EditPayload::UpsertFieldRenderInfo(_) => vec![],
+            EditPayload::MoveBlockLine(p) => vec![&mut p.sheet_idx],
+            EditPayload::ReorderBlockLines(p) => vec![&mut p.sheet_idx],
+            EditPayload::ImportBlockRecords(p) => vec![&mut p.sheet_idx],
+            EditPayload::CreateDiyCell(p) => vec![&mut p.sheet_idx],
+            EditPayload::CreateDiyCellById(_) => vec![],
+            EditPayload::RemoveDiyCell(p) => vec![&mut p.sheet_idx],
+            EditPayload::RemoveDiyCellById(_) => vec![],
+            EditPayload::CreateAppendix(p) => p.sheet_idx.iter_mut().collect(),
+            EditPayload::RemoveAppendix(p) => p.sheet_idx.iter_mut().collect(),
+            EditPayload::CellStyleUpdate(p) => vec![&mut p.sheet_idx],
+            EditPayload::EphemeralCellStyleUpdate(p) => vec![&mut p.sheet_idx],
+            EditPayload::LineStyleUpdate(p) => vec![&mut p.sheet_idx],
+            EditPayload::BlockStyleUpdate(p) => vec![&mut p.sheet_idx],
+            EditPayload::BlockLineStyleUpdate(p) => vec![&mut p.sheet_idx],
+            EditPayload::BlockLineNameFieldUpdate(p) => vec![&mut p.sheet_idx],
+            EditPayload::CellFormatBrush(p) => vec![&mut p.src_sheet_idx, &mut p.dst_sheet_idx],
+            EditPayload::LineFormatBrush(p) => vec![&mut p.src_sheet_idx, &mut p.dst_sheet_idx],
+            EditPayload::SetCellStyles(p) => vec![&mut p.sheet_idx],
+            EditPayload::CreateCellStyle(_) => vec![],
+            EditPayload::ModifyCellStyle(_) => vec![],
+            EditPayload::DeleteCellStyle(_) => vec![],
+            EditPayload::ApplyCellStyle(p) => vec![&mut p.sheet_idx],
+            EditPayload::SetTheme(_) => vec![],
+            EditPayload::ApplyBuiltinTheme(_) => vec![],
+            EditPayload::SetThemeColor(_) => vec![],
+            EditPayload::SetThemeFonts(_) => vec![],
+            EditPayload::CellInput(p) => vec![&mut p.sheet_idx],
+            EditPayload::EphemeralCellInput(p) => vec![&mut p.sheet_idx],
+            EditPayload::EphemeralCellRemove(p) => vec![&mut p.sheet_idx],
+            EditPayload::CellClear(p) => vec![&mut p.sheet_idx],
+            EditPayload::SetCellImage(p) => vec![&mut p.sheet_idx],
+            EditPayload::DeleteCellImage(p) => vec![&mut p.sheet_idx],
+            EditPayload::MoveChart(p) => vec![&mut p.sheet_idx],
+            EditPayload::CreateConditionalFormattingRule(p) => vec![&mut p.sheet_idx],
+            EditPayload::UpdateConditionalFormattingRule(p) => vec![&mut p.sheet_idx],
+            EditPayload::MoveConditionalFormattingRule(p) => vec![&mut p.sheet_idx],
+            EditPayload::DeleteConditionalFormattingRule(p) => vec![&mut p.sheet_idx],
+            EditPayload::CreateDataTable(p) => vec![&mut p.sheet_idx],
+            EditPayload::RemoveDataTable(p) => vec![&mut p.sheet_idx],
+            EditPayload::AddScenario(p) => vec![&mut p.sheet_idx],
+            EditPayload::EditScenario(p) => vec![&mut p.sheet_idx],
+            EditPayload::DeleteScenario(p) => vec![&mut p.sheet_idx],
+            EditPayload::ShowScenario(p) => vec![&mut p.sheet_idx],
+            EditPayload::CreateScenarioSummary(p) => vec![&mut p.sheet_idx],
+            EditPayload::DeleteChart(p) => vec![&mut p.sheet_idx],
+            EditPayload::CreateChart(p) => vec![&mut p.sheet_idx],
+            EditPayload::UpdateChart(p) => vec![&mut p.sheet_idx],
+            EditPayload::SetColWidth(p) => vec![&mut p.sheet_idx],
+            EditPayload::SetRowHeight(p) => vec![&mut p.sheet_idx],
+            EditPayload::SetVisible(p) => vec![&mut p.sheet_idx],
+            EditPayload::MergeCells(p) => vec![&mut p.sheet_idx],
+            EditPayload::SplitMergedCells(p) => vec![&mut p.sheet_idx],
+            EditPayload::AddComment(p) => vec![&mut p.sheet_idx],
+            EditPayload::EditComment(p) => vec![&mut p.sheet_idx],
+            EditPayload::DeleteComment(p) => vec![&mut p.sheet_idx],
+            EditPayload::ResolveComment(p) => vec![&mut p.sheet_idx],
+            EditPayload::UpsertPerson(_) => vec![],
+            EditPayload::SheetRename(p) => p.idx.iter_mut().collect(),
+            EditPayload::CreateSheet(_) => vec![],
+            EditPayload::DeleteSheet(p) => vec![&mut p.idx],
+            EditPayload::SetSheetColor(p) => vec![&mut p.idx],
+            EditPayload::SetSheetVisible(p) => vec![&mut p.idx],
+            EditPayload::MoveSheet(p) => vec![&mut p.from],
+            EditPayload::DuplicateSheet(p) => vec![&mut p.idx],
+            EditPayload::InsertCols(p) => vec![&mut p.sheet_idx],
+            EditPayload::DeleteCols(p) => vec![&mut p.sheet_idx],
+            EditPayload::InsertRows(p) => vec![&mut p.sheet_idx],
+            EditPayload::DeleteRows(p) => vec![&mut p.sheet_idx],
+            EditPayload::InsertCells(p) => vec![&mut p.sheet_idx],
+            EditPayload::DeleteCells(p) => vec![&mut p.sheet_idx],
+            EditPayload::MoveRange(p) => vec![&mut p.sheet_idx, &mut p.dest_sheet_idx],
+            EditPayload::InsertColsInBlock(p) => vec![&mut p.sheet_idx],
+            EditPayload::DeleteColsInBlock(p) => vec![&mut p.sheet_idx],
+            EditPayload::InsertRowsInBlock(p) => vec![&mut p.sheet_idx],
+            EditPayload::DeleteRowsInBlock(p) => vec![&mut p.sheet_idx],
+            EditPayload::ReproduceCells(p) => vec![&mut p.sheet_idx],
+            EditPayload::RestoreCheckpoint(_) => vec![],
+        }
+    }
+
+    /// The index the payload puts a new or moved sheet at.
+    pub fn new_sheet_idx_mut(&mut self) -> Option<&mut usize> {
+        match self {
+            EditPayload::CreateSheet(p) => Some(&mut p.idx),
+            EditPayload::MoveSheet(p) => Some(&mut p.to),
+            EditPayload::DuplicateSheet(p) => Some(&mut p.new_idx),
+            EditPayload::CreateScenarioSummary(p) => Some(&mut p.new_idx),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, TS)]
 #[ts(
     file_name = "upsert_field_render_info.ts",
@@ -240,6 +471,25 @@ pub struct SetSheetVisible {
     pub visible: bool,
 }
 
+/// Move the sheet at `from` so that it ends up at index `to`.
+#[derive(Debug, Clone, TS)]
+#[ts(file_name = "move_sheet.ts", builder, rename_all = "camelCase")]
+pub struct MoveSheet {
+    pub from: usize,
+    pub to: usize,
+}
+
+/// Copy the sheet at `idx` into a new sheet named `new_name`, placed at
+/// `new_idx`. Everything on the sheet is copied; references in the copied
+/// formulas to the source sheet itself point at the copy.
+#[derive(Debug, Clone, TS)]
+#[ts(file_name = "duplicate_sheet.ts", builder, rename_all = "camelCase")]
+pub struct DuplicateSheet {
+    pub idx: usize,
+    pub new_idx: usize,
+    pub new_name: String,
+}
+
 /// Find a sheet by its name and rename it. If no sheet is found, do nothing.
 #[derive(Debug, Clone, TS)]
 #[ts(file_name = "sheet_rename.ts", builder, rename_all = "camelCase")]
@@ -262,6 +512,113 @@ pub struct CellFormatBrush {
     pub dst_col_end: usize,
 }
 
+/// Replace the whole style of the cells in a block. `styles` is row-major,
+/// `col_count` wide, starting at `(start_row, start_col)`; a `None` leaves
+/// that cell alone. Unlike `CellFormatBrush` the styles travel with the
+/// payload, so they can come from a clipboard rather than live cells.
+#[derive(Debug, Clone, TS)]
+#[ts(file_name = "set_cell_styles.ts", builder, rename_all = "camelCase")]
+pub struct SetCellStyles {
+    pub sheet_idx: usize,
+    pub start_row: usize,
+    pub start_col: usize,
+    pub col_count: usize,
+    pub styles: Vec<Option<RawStyle>>,
+}
+
+/// Add a named cell style: the "Normal" style with `ty` applied.
+#[derive(Debug, Clone, TS)]
+#[ts(file_name = "create_cell_style.ts", builder, rename_all = "camelCase")]
+pub struct CreateCellStyle {
+    pub name: String,
+    pub ty: StyleUpdateType,
+}
+
+/// Change a named cell style. Every cell linked to it follows, except in
+/// what the cell formatted for itself.
+#[derive(Debug, Clone, TS)]
+#[ts(file_name = "modify_cell_style.ts", builder, rename_all = "camelCase")]
+pub struct ModifyCellStyle {
+    pub name: String,
+    pub ty: StyleUpdateType,
+}
+
+/// Remove a named cell style. Its cells keep their format and are linked
+/// to "Normal". "Normal" itself can't be deleted.
+#[derive(Debug, Clone, TS)]
+#[ts(file_name = "delete_cell_style.ts", builder, rename_all = "camelCase")]
+pub struct DeleteCellStyle {
+    pub name: String,
+}
+
+/// Format the cells of a block with a named cell style, replacing their
+/// own format, and link them to it.
+#[derive(Debug, Clone, TS)]
+#[ts(file_name = "apply_cell_style.ts", builder, rename_all = "camelCase")]
+pub struct ApplyCellStyle {
+    pub sheet_idx: usize,
+    pub start_row: usize,
+    pub start_col: usize,
+    pub end_row: usize,
+    pub end_col: usize,
+    pub name: String,
+}
+
+/// Replace the workbook theme with a `theme1.xml` document, such as the one
+/// inside a `.thmx` file.
+#[derive(Debug, Clone, TS)]
+#[ts(file_name = "set_theme.ts", builder, rename_all = "camelCase")]
+pub struct SetTheme {
+    pub xml: String,
+}
+
+/// Replace the workbook theme with one of the built-in Office themes.
+#[derive(Debug, Clone, TS)]
+#[ts(
+    file_name = "apply_builtin_theme.ts",
+    builder,
+    rename_all = "camelCase"
+)]
+pub struct ApplyBuiltinTheme {
+    pub name: String,
+}
+
+/// Set one colour of the theme. Every colour that refers to it follows.
+#[derive(Debug, Clone, TS)]
+#[ts(file_name = "set_theme_color.ts", builder, rename_all = "camelCase")]
+pub struct SetThemeColor {
+    pub slot: ThemeColorSlot,
+    /// `RRGGBB` hex.
+    pub color: String,
+}
+
+/// Set the theme's heading (major) and body (minor) fonts. `None` leaves
+/// one as it is.
+#[derive(Debug, Clone, TS)]
+#[ts(file_name = "set_theme_fonts.ts", builder, rename_all = "camelCase")]
+pub struct SetThemeFonts {
+    pub major: Option<String>,
+    pub minor: Option<String>,
+}
+
+/// A colour of the theme's colour scheme, in `clrScheme` order.
+#[derive(Debug, Clone, Copy, TS)]
+#[ts(file_name = "theme_color_slot.ts", rename_all = "camelCase")]
+pub enum ThemeColorSlot {
+    Dk1,
+    Lt1,
+    Dk2,
+    Lt2,
+    Accent1,
+    Accent2,
+    Accent3,
+    Accent4,
+    Accent5,
+    Accent6,
+    Hlink,
+    FolHlink,
+}
+
 #[derive(Debug, Clone, TS)]
 #[ts(file_name = "line_format_brush.ts", builder, rename_all = "camelCase")]
 pub struct LineFormatBrush {
@@ -298,6 +655,55 @@ pub struct DeleteCols {
     pub count: usize,
 }
 
+/// Insert blank cells over `start_row..=end_row` x `start_col..=end_col`.
+/// The cells at and below the range, within its columns, move down by its
+/// height; with `shift_right`, the cells at and right of it, within its
+/// rows, move right by its width. Nothing outside those columns (rows)
+/// moves.
+#[derive(Debug, Clone, TS)]
+#[ts(file_name = "insert_cells.ts", builder, rename_all = "camelCase")]
+pub struct InsertCells {
+    pub sheet_idx: usize,
+    pub start_row: usize,
+    pub start_col: usize,
+    pub end_row: usize,
+    pub end_col: usize,
+    pub shift_right: bool,
+}
+
+/// Delete the cells of `start_row..=end_row` x `start_col..=end_col`. The
+/// cells below the range, within its columns, move up to close the gap;
+/// with `shift_left`, the cells right of it, within its rows, move left.
+/// References to the deleted cells become `#REF!`.
+#[derive(Debug, Clone, TS)]
+#[ts(file_name = "delete_cells.ts", builder, rename_all = "camelCase")]
+pub struct DeleteCells {
+    pub sheet_idx: usize,
+    pub start_row: usize,
+    pub start_col: usize,
+    pub end_row: usize,
+    pub end_col: usize,
+    pub shift_left: bool,
+}
+
+/// Move the cells of `start_row..=end_row` x `start_col..=end_col`, as when
+/// dragging a selection, so that its top-left cell lands on
+/// (`dest_row`, `dest_col`) of the sheet at `dest_sheet_idx`. The destination
+/// is overwritten. Formulas anywhere that refer to the moved cells follow
+/// them, and those that refer to overwritten cells become `#REF!`.
+#[derive(Debug, Clone, TS)]
+#[ts(file_name = "move_range.ts", builder, rename_all = "camelCase")]
+pub struct MoveRange {
+    pub sheet_idx: usize,
+    pub start_row: usize,
+    pub start_col: usize,
+    pub end_row: usize,
+    pub end_col: usize,
+    pub dest_sheet_idx: usize,
+    pub dest_row: usize,
+    pub dest_col: usize,
+}
+
 #[derive(Debug, Clone, TS)]
 #[ts(file_name = "insert_cols.ts", builder, rename_all = "camelCase")]
 pub struct InsertCols {
@@ -455,6 +861,109 @@ pub struct DeleteConditionalFormattingRule {
     pub rule_id: u32,
 }
 
+/// Make the cells from `start` to `end` a data table, Excel's
+/// `{=TABLE(row_input, col_input)}`. The input values and formulas go in the
+/// row above and the column to the left of these cells; see
+/// `data_table_manager` for how they are laid out. The results are filled
+/// in on every calculation and can't be edited.
+#[derive(Debug, Clone, TS)]
+#[ts(file_name = "create_data_table.ts", builder, rename_all = "camelCase")]
+pub struct CreateDataTable {
+    pub sheet_idx: usize,
+    pub start_row: usize,
+    pub start_col: usize,
+    pub end_row: usize,
+    pub end_col: usize,
+    /// Takes each value in the row above the results.
+    pub row_input: Option<InputCell>,
+    /// Takes each value in the column to the left of the results.
+    pub col_input: Option<InputCell>,
+}
+
+/// A cell on the sheet the payload names.
+#[derive(Debug, Clone, Copy, TS)]
+#[ts(file_name = "input_cell.ts", rename_all = "camelCase")]
+pub struct InputCell {
+    pub row: usize,
+    pub col: usize,
+}
+
+/// Stop a data table from filling in its results. The results stay as
+/// plain values.
+#[derive(Debug, Clone, TS)]
+#[ts(file_name = "remove_data_table.ts", builder, rename_all = "camelCase")]
+pub struct RemoveDataTable {
+    pub sheet_idx: usize,
+    /// From `Worksheet::get_data_tables`. Session-scoped, like rule ids.
+    pub table_id: u32,
+}
+
+/// Add a scenario to a sheet: a name for a set of changing cells and the
+/// values showing it puts in them. See `scenario_manager`.
+#[derive(Debug, Clone, TS)]
+#[ts(file_name = "add_scenario.ts", builder, rename_all = "camelCase")]
+pub struct AddScenario {
+    pub sheet_idx: usize,
+    /// Unique on the sheet.
+    pub name: String,
+    /// At most 32, as in Excel.
+    pub cells: Vec<ScenarioValue>,
+    pub comment: Option<String>,
+}
+
+/// A changing cell and the value a scenario puts in it, as it would be typed.
+#[derive(Debug, Clone, TS)]
+#[ts(file_name = "scenario_value.ts", rename_all = "camelCase")]
+pub struct ScenarioValue {
+    pub row: usize,
+    pub col: usize,
+    pub value: String,
+}
+
+/// Change the scenario called `name`. A field left out keeps what the
+/// scenario has; an empty `comment` removes the comment.
+#[derive(Debug, Clone, TS)]
+#[ts(file_name = "edit_scenario.ts", builder, rename_all = "camelCase")]
+pub struct EditScenario {
+    pub sheet_idx: usize,
+    pub name: String,
+    pub new_name: Option<String>,
+    pub cells: Option<Vec<ScenarioValue>>,
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, Clone, TS)]
+#[ts(file_name = "delete_scenario.ts", builder, rename_all = "camelCase")]
+pub struct DeleteScenario {
+    pub sheet_idx: usize,
+    pub name: String,
+}
+
+/// Type each of the scenario's values into its changing cell. Undoing it
+/// puts back what the cells had.
+#[derive(Debug, Clone, TS)]
+#[ts(file_name = "show_scenario.ts", builder, rename_all = "camelCase")]
+pub struct ShowScenario {
+    pub sheet_idx: usize,
+    pub name: String,
+}
+
+/// Write a scenario summary on a new sheet named `new_name`, placed at
+/// `new_idx`: the changing cells and `result_cells` of the sheet at
+/// `sheet_idx`, with what they hold now and under each of its scenarios.
+#[derive(Debug, Clone, TS)]
+#[ts(
+    file_name = "create_scenario_summary.ts",
+    builder,
+    rename_all = "camelCase"
+)]
+pub struct CreateScenarioSummary {
+    pub sheet_idx: usize,
+    pub result_cells: Vec<InputCell>,
+    pub new_idx: usize,
+    pub new_name: String,
+}
+
 #[derive(Debug, Clone, TS)]
 #[ts(file_name = "move_chart.ts", builder, rename_all = "camelCase")]
 pub struct MoveChart {
@@ -471,13 +980,20 @@ pub struct MoveChart {
 }
 
 /// Take the `content` as input to the cell. The type of the `content` can be referred automatically.
-#[derive(Debug, Clone, TS)]
+///
+/// Input like `50%`, `$1,234.50`, `3/1/2024` or `14:30` is stored as a
+/// number and, if the cell has the General format, the cell gets a number
+/// format that shows it as typed. Dates follow the workbook's input locale.
+#[derive(Debug, Clone, Default, TS)]
 #[ts(file_name = "cell_input.ts", builder, rename_all = "camelCase")]
 pub struct CellInput {
     pub sheet_idx: usize,
     pub row: usize,
     pub col: usize,
     pub content: String,
+    /// Store `content` without recognizing percentages, currency, dates
+    /// and times.
+    pub skip_recognition: Option<bool>,
 }
 
 #[derive(Debug, Clone, TS)]
@@ -782,6 +1298,45 @@ impl From<ReorderBlockLines> for EditPayload {
     }
 }
 
+/// Write a batch of records into a block bound with a form schema, as one
+/// transaction. Each record maps field names to input, read as a
+/// `blockInput` of that field would be. Records go to the block's empty
+/// lines first, and the block grows at its end for the rest.
+///
+/// With `upsert`, a record whose key is already in the block updates that
+/// line instead. Fields a record leaves out, or leaves blank or null, are
+/// left as they are.
+#[derive(Debug, Clone, TS)]
+#[ts(
+    file_name = "import_block_records.ts",
+    builder,
+    rename_all = "camelCase"
+)]
+pub struct ImportBlockRecords {
+    pub sheet_idx: usize,
+    pub block_id: usize,
+    pub format: RecordFormat,
+    /// A JSON array of objects, or CSV whose first line names the fields.
+    pub data: String,
+    /// The record field that holds the key, written to the key cell.
+    /// Defaults to the schema field on the key line, if there is one.
+    pub key_field: Option<String>,
+    pub upsert: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, TS)]
+#[ts(file_name = "record_format.ts", rename_all = "camelCase")]
+pub enum RecordFormat {
+    Json,
+    Csv,
+}
+
+impl From<ImportBlockRecords> for EditPayload {
+    fn from(value: ImportBlockRecords) -> Self {
+        EditPayload::ImportBlockRecords(value)
+    }
+}
+
 #[derive(Debug, Clone, TS)]
 #[ts(file_name = "bind_form_schema.ts", builder, rename_all = "camelCase")]
 pub struct BindFormSchema {
@@ -826,6 +1381,46 @@ pub struct BindFormSchema {
     /// refuses writes to that cell. Empty vec = all None. Same
     /// placeholder support as `validation_formulas`.
     pub editability_formulas: Vec<Option<String>>,
+    /// Per-field types. Same indexing as `fields`; `None` (or an empty vec)
+    /// leaves a field free-form. A `blockInput` that does not fit its
+    /// field's type is refused.
+    pub field_types: Option<Vec<Option<FieldType>>>,
+}
+
+/// The type of a form-schema field and the constraints on its values.
+/// Only the constraints of `kind` are read:
+///   - `min` / `max` for numbers, integers and dates (dates as serial
+///     numbers), both inclusive
+///   - `options` for enums
+///   - `pattern` (a regex the whole text must match) and `max_length`
+///     for text
+///   - `ref_name` for references, whose values must be a key of that block
+#[derive(Debug, Clone, TS)]
+#[ts(file_name = "field_type.ts", builder, rename_all = "camelCase")]
+pub struct FieldType {
+    pub kind: FieldKind,
+    /// Refuse blank input.
+    pub required: bool,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub options: Vec<String>,
+    pub pattern: Option<String>,
+    pub max_length: Option<usize>,
+    pub ref_name: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, TS)]
+#[ts(file_name = "field_kind.ts", rename_all = "camelCase")]
+pub enum FieldKind {
+    Text,
+    Number,
+    Integer,
+    /// Entered as `yyyy-mm-dd` or as a serial number, stored as a serial.
+    Date,
+    Boolean,
+    Enum,
+    /// A key of another block.
+    Reference,
 }
 
 impl From<BindFormSchema> for EditPayload {
@@ -1114,7 +1709,7 @@ impl Default for StatusCode {
     }
 }
 
-use crate::{ReproducibleCell, controller::style::PatternFill};
+use crate::{ReproducibleCell, controller::style::PatternFill, style_manager::RawStyle};
 use logisheets_workbook::prelude::*;
 
 #[derive(Debug, Clone, TS)]
@@ -1431,6 +2026,62 @@ impl From<DeleteConditionalFormattingRule> for EditPayload {
     }
 }
 impl Payload for DeleteConditionalFormattingRule {}
+
+impl From<CreateDataTable> for EditPayload {
+    fn from(value: CreateDataTable) -> Self {
+        EditPayload::CreateDataTable(value)
+    }
+}
+
+impl Payload for CreateDataTable {}
+
+impl From<RemoveDataTable> for EditPayload {
+    fn from(value: RemoveDataTable) -> Self {
+        EditPayload::RemoveDataTable(value)
+    }
+}
+
+impl Payload for RemoveDataTable {}
+
+impl From<AddScenario> for EditPayload {
+    fn from(value: AddScenario) -> Self {
+        EditPayload::AddScenario(value)
+    }
+}
+
+impl Payload for AddScenario {}
+
+impl From<EditScenario> for EditPayload {
+    fn from(value: EditScenario) -> Self {
+        EditPayload::EditScenario(value)
+    }
+}
+
+impl Payload for EditScenario {}
+
+impl From<DeleteScenario> for EditPayload {
+    fn from(value: DeleteScenario) -> Self {
+        EditPayload::DeleteScenario(value)
+    }
+}
+
+impl Payload for DeleteScenario {}
+
+impl From<ShowScenario> for EditPayload {
+    fn from(value: ShowScenario) -> Self {
+        EditPayload::ShowScenario(value)
+    }
+}
+
+impl Payload for ShowScenario {}
+
+impl From<CreateScenarioSummary> for EditPayload {
+    fn from(value: CreateScenarioSummary) -> Self {
+        EditPayload::CreateScenarioSummary(value)
+    }
+}
+
+impl Payload for CreateScenarioSummary {}
 impl From<DeleteChart> for EditPayload {
     fn from(value: DeleteChart) -> Self {
         EditPayload::DeleteChart(value)
@@ -1505,6 +2156,22 @@ impl From<InsertRows> for EditPayload {
         EditPayload::InsertRows(value)
     }
 }
+impl From<InsertCells> for EditPayload {
+    fn from(value: InsertCells) -> Self {
+        EditPayload::InsertCells(value)
+    }
+}
+impl From<DeleteCells> for EditPayload {
+    fn from(value: DeleteCells) -> Self {
+        EditPayload::DeleteCells(value)
+    }
+}
+
+impl From<MoveRange> for EditPayload {
+    fn from(value: MoveRange) -> Self {
+        EditPayload::MoveRange(value)
+    }
+}
 impl From<DeleteRows> for EditPayload {
     fn from(value: DeleteRows) -> Self {
         EditPayload::DeleteRows(value)
@@ -1551,12 +2218,78 @@ impl From<DeleteSheet> for EditPayload {
     }
 }
 
+impl From<MoveSheet> for EditPayload {
+    fn from(value: MoveSheet) -> Self {
+        EditPayload::MoveSheet(value)
+    }
+}
+
+impl From<DuplicateSheet> for EditPayload {
+    fn from(value: DuplicateSheet) -> Self {
+        EditPayload::DuplicateSheet(value)
+    }
+}
+
 impl From<CellFormatBrush> for EditPayload {
     fn from(value: CellFormatBrush) -> Self {
         EditPayload::CellFormatBrush(value)
     }
 }
 
+impl From<SetCellStyles> for EditPayload {
+    fn from(value: SetCellStyles) -> Self {
+        EditPayload::SetCellStyles(value)
+    }
+}
+
+impl From<CreateCellStyle> for EditPayload {
+    fn from(value: CreateCellStyle) -> Self {
+        EditPayload::CreateCellStyle(value)
+    }
+}
+
+impl From<ModifyCellStyle> for EditPayload {
+    fn from(value: ModifyCellStyle) -> Self {
+        EditPayload::ModifyCellStyle(value)
+    }
+}
+
+impl From<DeleteCellStyle> for EditPayload {
+    fn from(value: DeleteCellStyle) -> Self {
+        EditPayload::DeleteCellStyle(value)
+    }
+}
+
+impl From<ApplyCellStyle> for EditPayload {
+    fn from(value: ApplyCellStyle) -> Self {
+        EditPayload::ApplyCellStyle(value)
+    }
+}
+
+impl From<SetTheme> for EditPayload {
+    fn from(value: SetTheme) -> Self {
+        EditPayload::SetTheme(value)
+    }
+}
+
+impl From<ApplyBuiltinTheme> for EditPayload {
+    fn from(value: ApplyBuiltinTheme) -> Self {
+        EditPayload::ApplyBuiltinTheme(value)
+    }
+}
+
+impl From<SetThemeColor> for EditPayload {
+    fn from(value: SetThemeColor) -> Self {
+        EditPayload::SetThemeColor(value)
+    }
+}
+
+impl From<SetThemeFonts> for EditPayload {
+    fn from(value: SetThemeFonts) -> Self {
+        EditPayload::SetThemeFonts(value)
+    }
+}
+
 impl From<LineFormatBrush> for EditPayload {
     fn from(value: LineFormatBrush) -> Self {
         EditPayload::LineFormatBrush(value)
@@ -1586,6 +2319,7 @@ impl From<CreateLink> for EditPayload {
 
 impl Payload for CreateLink {}
 impl Payload for BlockInput {}
+impl Payload for ImportBlockRecords {}
 impl Payload for BlockStyleUpdate {}
 impl Payload for CellInput {}
 impl Payload for CreateBlock {}
@@ -1597,12 +2331,17 @@ impl Payload for SetVisible {}
 impl Payload for SheetRename {}
 impl Payload for CreateSheet {}
 impl Payload for DeleteSheet {}
+impl Payload for MoveSheet {}
+impl Payload for DuplicateSheet {}
 impl Payload for CellStyleUpdate {}
 impl Payload for LineStyleUpdate {}
 impl Payload for InsertCols {}
 impl Payload for InsertRows {}
 impl Payload for DeleteCols {}
 impl Payload for DeleteRows {}
+impl Payload for InsertCells {}
+impl Payload for DeleteCells {}
+impl Payload for MoveRange {}
 impl Payload for InsertColsInBlock {}
 impl Payload for InsertRowsInBlock {}
 impl Payload for DeleteColsInBlock {}
@@ -50,4 +50,14 @@ impl AppendixManager {
     pub fn remove(&mut self, sheet_id: SheetId, block_cell_id: BlockCellId) {
         self.data.remove(&(sheet_id, block_cell_id));
     }
+
+    pub fn duplicate_sheet(&mut self, from: SheetId, to: SheetId) {
+        let copies: Vec<_> = self
+            .data
+            .iter()
+            .filter(|((s, _), _)| *s == from)
+            .map(|((_, c), v)| ((to, *c), v.clone()))
+            .collect();
+        self.data.extend(copies);
+    }
 }
@@ -42,4 +42,18 @@ impl DiyCellManager {
     ) -> Option<DiyCellId> {
         self.data.get(&(sheet_id, *block_cell_id)).cloned()
     }
+
+    /// Give every diy cell of sheet `from` a counterpart in sheet `to`, under
+    /// a new id.
+    pub fn duplicate_sheet(&mut self, from: SheetId, to: SheetId) {
+        let cells: Vec<BlockCellId> = self
+            .data
+            .keys()
+            .filter(|(s, _)| *s == from)
+            .map(|(_, c)| *c)
+            .collect();
+        for c in cells {
+            self.create_new_diy_cell(to, c);
+        }
+    }
 }
@@ -1,5 +1,6 @@
 use appendix::AppendixManager;
 use diy_cell::DiyCellManager;
+use logisheets_base::SheetId;
 
 mod appendix;
 pub mod ctx;
@@ -13,3 +14,10 @@ pub struct ExclusiveManager {
     pub diy_cell_manager: DiyCellManager,
     pub appendix_manager: AppendixManager,
 }
+
+impl ExclusiveManager {
+    pub fn duplicate_sheet(&mut self, from: SheetId, to: SheetId) {
+        self.diy_cell_manager.duplicate_sheet(from, to);
+        self.appendix_manager.duplicate_sheet(from, to);
+    }
+}
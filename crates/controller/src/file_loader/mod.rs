@@ -21,7 +21,7 @@ use crate::{
     id_manager::SheetIdManager,
     image_manager::{CellImage, ImageManager},
     navigator::{BlockPlace, Navigator},
-    settings::Settings,
+    settings::{InputLocale, Settings},
     sid_assigner::ShadowIdAssigner,
     theme_manager::ThemeManager,
     utils::turn_indexed_color_to_rgb,
@@ -52,6 +52,7 @@ pub fn load_file(wb: Wb, book_name: String) -> Controller {
         mut external_links_manager,
         mut sheet_info_manager,
         mut style_manager,
+        mut theme_manager,
         mut cell_attachment_manager,
         mut formula_manager,
         mut range_manager,
@@ -64,6 +65,8 @@ pub fn load_file(wb: Wb, book_name: String) -> Controller {
         mut image_manager,
         mut chart_manager,
         mut data_validation_manager,
+        data_table_manager,
+        scenario_manager,
     } = Status::default();
     let mut sheet_id_fetcher = SheetIdFetcher {
         sheet_id_manager: &mut sheet_id_manager,
@@ -99,9 +102,16 @@ pub fn load_file(wb: Wb, book_name: String) -> Controller {
         }
     });
     let mut app_data = vec![];
+    let mut checkpoints = vec![];
+    let mut audit = None;
 
     if let Some(logisheets) = logisheets {
         app_data = logisheets.apps;
+        checkpoints = logisheets.checkpoints;
+        audit = logisheets.audit;
+        if let Some(locale) = logisheets.input_locale {
+            settings.input_locale = InputLocale::from_xml(locale);
+        }
         // Restore the workbook-wide FieldRenderManager (per-renderId
         // style + diy_render flags) before walking sheets, so cell load
         // and any downstream display calls see the populated formatters.
@@ -229,6 +239,9 @@ pub fn load_file(wb: Wb, book_name: String) -> Controller {
     // styles reference, not something the xf walk can reach.
     style_manager.dxf_manager =
         crate::style_manager::dxf_manager::DxfManager::from_ct_dxfs(xl.styles.1.dxfs.as_ref());
+    if let Some(theme) = &xl.theme {
+        theme_manager = ThemeManager::from(theme.1.clone());
+    }
     let mut style_loader = StyleLoader::new(&mut style_manager, &xl.styles.1);
     // Persons are workbook-scoped and referenced by threaded comments, so they
     // must be registered before any sheet's comments are loaded.
@@ -240,6 +253,9 @@ pub fn load_file(wb: Wb, book_name: String) -> Controller {
     // Collect the conversion specs during the sheet walk, where the OOXML table
     // metadata is in scope.
     let mut pending_tables: Vec<TableConvertSpec> = Vec::new();
+    // Data tables are anchored on cell ids once the load has settled, like
+    // conditional formatting.
+    let mut pending_data_tables: Vec<(SheetId, CtFormula)> = Vec::new();
     // TODO: Here we should we `.into_iter()` to take the ownership logically
     // rather than call `.clone()` below.
     xl.workbook_part
@@ -316,6 +332,16 @@ pub fn load_file(wb: Wb, book_name: String) -> Controller {
                     &xl,
                     &mut dirty_cells,
                 );
+                pending_data_tables.extend(
+                    ws.worksheet_part
+                        .sheet_data
+                        .rows
+                        .iter()
+                        .flat_map(|row| row.cells.iter())
+                        .filter_map(|c| c.f.as_ref())
+                        .filter(|f| matches!(f.t, StCellFormulaType::DataTable))
+                        .map(|f| (sheet_id, f.clone())),
+                );
                 if let Some(drawing) = &ws.drawing {
                     load_cell_images(
                         sheet_id,
@@ -384,6 +410,7 @@ pub fn load_file(wb: Wb, book_name: String) -> Controller {
         external_links_manager,
         sheet_info_manager,
         style_manager,
+        theme_manager,
         cell_attachment_manager,
         dirty_cells_next_round: dirty_cells,
         range_manager,
@@ -396,10 +423,9 @@ pub fn load_file(wb: Wb, book_name: String) -> Controller {
         chart_manager,
         data_validation_manager,
         conditional_formatting_manager,
+        data_table_manager,
+        scenario_manager,
     };
-    if let Some(theme) = xl.theme {
-        settings.theme = ThemeManager::from(theme.1);
-    }
     let mut controller = Controller::from(status, book_name, settings, app_data);
     convert_tables_to_blocks(&mut controller, pending_tables);
     // Must run last: `sqref` is resolved against the navigator, and blocks only
@@ -407,6 +433,15 @@ pub fn load_file(wb: Wb, book_name: String) -> Controller {
     // converted table has to anchor on block cell ids, not the normal cell ids
     // those coordinates had mid-load.
     model_conditional_formatting(&mut controller);
+    model_data_tables(&mut controller, pending_data_tables);
+    model_scenarios(&mut controller);
+    // Persisted checkpoints are diffs against the workbook as saved, which
+    // is what has just been loaded.
+    let base = controller.status.clone();
+    controller.checkpoint_manager.load(checkpoints, base);
+    if let Some(audit) = audit {
+        controller.audit_manager.load(audit, &controller.status);
+    }
     controller
 }
 
@@ -467,6 +502,140 @@ fn model_conditional_formatting(controller: &mut Controller) {
     }
 }
 
+/// Anchor each `<f t="dataTable">` on the cell ids of its results and input
+/// cells. The results keep the values the file cached for them until the
+/// next calculation refreshes them.
+///
+/// A table whose input cell was deleted (`del1`/`del2`) has nothing to
+/// anchor that input on, so it is not modeled: its results load as plain
+/// values, which is what they are until the table is rebuilt.
+fn model_data_tables(controller: &mut Controller, pending: Vec<(SheetId, CtFormula)>) {
+    use crate::sqref::parse_sqref;
+
+    let status = &mut controller.status;
+    for (sheet_id, f) in pending {
+        if f.del1 || f.del2 {
+            continue;
+        }
+        let nav = &status.navigator;
+        let cell = |r: &Option<String>| {
+            let p = parse_sqref(r.as_deref()?).into_iter().next()?;
+            nav.fetch_cell_id(&sheet_id, p.r0, p.c0).ok()
+        };
+        // With both inputs, `dtr` says whether `r1` is the row input.
+        let (row_input, col_input) = match (f.dt_2d, f.dtr) {
+            (true, true) => (cell(&f.r1), cell(&f.r2)),
+            (true, false) => (cell(&f.r2), cell(&f.r1)),
+            (false, true) => (cell(&f.r1), None),
+            (false, false) => (None, cell(&f.r1)),
+        };
+        let Some(range) = f
+            .reference
+            .as_deref()
+            .and_then(|r| parse_sqref(r).into_iter().next())
+        else {
+            continue;
+        };
+        let (Ok(start), Ok(end)) = (
+            nav.fetch_cell_id(&sheet_id, range.r0, range.c0),
+            nav.fetch_cell_id(&sheet_id, range.r1, range.c1),
+        ) else {
+            continue;
+        };
+        if row_input.is_none() && col_input.is_none() {
+            continue;
+        }
+        status
+            .data_table_manager
+            .add(sheet_id, start, end, row_input, col_input);
+    }
+}
+
+/// Move each sheet's `<scenarios>` out of the verbatim passthrough and into
+/// the manager, anchoring every changing cell and result cell on its cell
+/// id. Changing cells Excel flagged as deleted are dropped.
+///
+/// If a cell reference doesn't resolve, the sheet's `<scenarios>` is left in
+/// `preserved_parts` and round-trips as it was.
+fn model_scenarios(controller: &mut Controller) {
+    let sheet_ids: Vec<_> = controller
+        .settings
+        .preserved_parts
+        .keys()
+        .cloned()
+        .collect();
+    for sheet_id in sheet_ids {
+        let Some(modeled) = controller
+            .settings
+            .preserved_parts
+            .get(&sheet_id)
+            .and_then(|p| p.scenarios.as_ref())
+            .and_then(|raw| model_sheet_scenarios(&controller.status.navigator, sheet_id, raw))
+        else {
+            continue;
+        };
+        controller
+            .status
+            .scenario_manager
+            .set_sheet(sheet_id, modeled);
+        if let Some(p) = controller.settings.preserved_parts.get_mut(&sheet_id) {
+            p.scenarios = None;
+        }
+    }
+}
+
+fn model_sheet_scenarios(
+    nav: &crate::navigator::Navigator,
+    sheet_id: SheetId,
+    raw: &CtScenarios,
+) -> Option<crate::scenario_manager::SheetScenarios> {
+    use crate::scenario_manager::{ChangingCell, Scenario, SheetScenarios};
+    use crate::sqref::{UNBOUNDED, parse_sqref};
+
+    let cell_id = |r: usize, c: usize| nav.fetch_cell_id(&sheet_id, r, c).ok();
+    let mut scenarios = imbl::Vector::new();
+    for s in raw.scenarios.iter() {
+        let mut cells = imbl::Vector::new();
+        for input in s.input_cells.iter().filter(|c| !c.deleted) {
+            let p = parse_sqref(&input.r).into_iter().next()?;
+            cells.push_back(ChangingCell {
+                cell_id: cell_id(p.r0, p.c0)?,
+                value: input.val.clone(),
+                num_fmt_id: input.num_fmt_id,
+            });
+        }
+        scenarios.push_back(Scenario {
+            name: s.name.clone(),
+            cells,
+            comment: s.comment.clone(),
+            user: s.user.clone(),
+            locked: s.locked,
+            hidden: s.hidden,
+        });
+    }
+    let mut result_cells = imbl::Vector::new();
+    for p in parse_sqref(raw.sqref.as_deref().unwrap_or_default()) {
+        if p.r1 == UNBOUNDED || p.c1 == UNBOUNDED {
+            return None;
+        }
+        for r in p.r0..=p.r1 {
+            for c in p.c0..=p.c1 {
+                result_cells.push_back(cell_id(r, c)?);
+            }
+        }
+    }
+    let name_at = |idx: Option<u32>| {
+        let s: &Scenario = scenarios.get(idx? as usize)?;
+        Some(s.name.clone())
+    };
+    Some(SheetScenarios {
+        shown: name_at(raw.show),
+        current: name_at(raw.current),
+        scenarios,
+        result_cells,
+    })
+}
+
 /// A structured OOXML table queued for conversion into a form block. Positions
 /// are 0-based; the region already EXCLUDES the header row(s) (which supply the
 /// field names) and any totals row(s).
@@ -584,6 +753,7 @@ fn convert_tables_to_blocks(controller: &mut Controller, specs: Vec<TableConvert
                 field_formulas: vec![],
                 validation_formulas: vec![],
                 editability_formulas: vec![],
+                field_types: None,
             }),
         ];
         controller.handle_action(EditAction::Payloads(PayloadsAction {
@@ -1,4 +1,8 @@
-use crate::style_manager::{StyleManager, xf_manager::CtrlXf};
+use crate::style_manager::{
+    StyleManager,
+    named_style_manager::NamedStyleManager,
+    xf_manager::{CtrlXf, XfManager},
+};
 use logisheets_base::StyleId;
 use logisheets_workbook::prelude::*;
 use std::collections::HashMap;
@@ -13,12 +17,36 @@ pub struct StyleLoader<'a> {
 
 impl<'a> StyleLoader<'a> {
     pub fn new(manager: &'a mut StyleManager, part: &'a StylesheetPart) -> Self {
-        StyleLoader {
+        let mut loader = StyleLoader {
             manager,
             part,
             xf_cache: HashMap::new(),
             cell_xf_cache: HashMap::new(),
+        };
+        loader.load_named_styles();
+        loader
+    }
+
+    /// Load `<cellStyleXfs>` one xf per index, so a cell's `xfId` is the id
+    /// of its named style's xf, and then `<cellStyles>`.
+    fn load_named_styles(&mut self) {
+        let Some(style_xfs) = &self.part.cell_style_xfs else {
+            return;
+        };
+        if style_xfs.xfs.is_empty() {
+            return;
         }
+        let mut xf_manager = XfManager::new(0);
+        for xf in &style_xfs.xfs {
+            let ctrl_xf = self.ctrl_xf(xf);
+            xf_manager.push(ctrl_xf);
+        }
+        self.manager.cell_style_xfs_manager = xf_manager;
+        let count = style_xfs.xfs.len() as u32;
+        self.manager.named_styles = match &self.part.cell_styles {
+            Some(cell_styles) => NamedStyleManager::from_ct_cell_styles(cell_styles, count),
+            None => NamedStyleManager::default(),
+        };
     }
 
     pub fn load_xf(&mut self, idx: u32) -> StyleId {
@@ -34,77 +62,90 @@ impl<'a> StyleLoader<'a> {
                 return 0;
             }
             let xf = xf.unwrap();
-            let font_id = if let Some(i) = xf.font_id {
-                if let Some(fonts) = &self.part.fonts {
-                    if let Some(f) = fonts.fonts.get(i as usize) {
-                        self.manager.font_manager.get_id(f)
-                    } else {
-                        0
-                    }
+            let style_xf_count = self
+                .part
+                .cell_style_xfs
+                .as_ref()
+                .map_or(0, |x| x.xfs.len() as u32);
+            let ctrl_xf = CtrlXf {
+                xf_id: xf.xf_id.filter(|id| *id < style_xf_count),
+                ..self.ctrl_xf(xf)
+            };
+            self.manager.cell_xfs_manager.get_id(&ctrl_xf)
+        };
+        self.cell_xf_cache.insert(idx, style_id);
+        style_id
+    }
+
+    fn ctrl_xf(&mut self, xf: &CtXf) -> CtrlXf {
+        let font_id = if let Some(i) = xf.font_id {
+            if let Some(fonts) = &self.part.fonts {
+                if let Some(f) = fonts.fonts.get(i as usize) {
+                    self.manager.font_manager.get_id(f)
                 } else {
                     0
                 }
             } else {
                 0
-            };
-            let fill_id = if let Some(i) = xf.fill_id {
-                if let Some(fills) = &self.part.fills {
-                    if let Some(f) = fills.fills.get(i as usize) {
-                        self.manager.fill_manager.get_id(f)
-                    } else {
-                        0
-                    }
+            }
+        } else {
+            0
+        };
+        let fill_id = if let Some(i) = xf.fill_id {
+            if let Some(fills) = &self.part.fills {
+                if let Some(f) = fills.fills.get(i as usize) {
+                    self.manager.fill_manager.get_id(f)
                 } else {
                     0
                 }
             } else {
                 0
-            };
-            let num_fmt_id = if let Some(i) = xf.num_fmt_id {
-                if let Some(num_fmts) = &self.part.num_fmts {
-                    let pos = num_fmts.num_fmts.iter().position(|fmt| fmt.num_fmt_id == i);
-                    if let Some(p) = pos {
-                        let f = num_fmts.num_fmts.get(p).unwrap();
-                        self.manager.num_fmt_manager.get_id(&f.format_code)
-                    } else {
-                        i
-                    }
+            }
+        } else {
+            0
+        };
+        let num_fmt_id = if let Some(i) = xf.num_fmt_id {
+            if let Some(num_fmts) = &self.part.num_fmts {
+                let pos = num_fmts.num_fmts.iter().position(|fmt| fmt.num_fmt_id == i);
+                if let Some(p) = pos {
+                    let f = num_fmts.num_fmts.get(p).unwrap();
+                    self.manager.num_fmt_manager.get_id(&f.format_code)
                 } else {
                     i
                 }
             } else {
-                0
-            };
-            let border_id = if let Some(i) = xf.border_id {
-                if let Some(borders) = &self.part.borders {
-                    if let Some(f) = borders.borders.get(i as usize) {
-                        self.manager.border_manager.get_id(f)
-                    } else {
-                        0
-                    }
+                i
+            }
+        } else {
+            0
+        };
+        let border_id = if let Some(i) = xf.border_id {
+            if let Some(borders) = &self.part.borders {
+                if let Some(f) = borders.borders.get(i as usize) {
+                    self.manager.border_manager.get_id(f)
                 } else {
                     0
                 }
             } else {
                 0
-            };
-            let ctrl_xf = CtrlXf {
-                alignment: xf.alignment.clone(),
-                protection: xf.protction.clone(),
-                font_id: Some(font_id),
-                border_id: Some(border_id),
-                fill_id: Some(fill_id),
-                num_fmt_id: Some(num_fmt_id),
-                apply_number_format: xf.apply_number_format,
-                apply_font: xf.apply_font,
-                apply_fill: xf.apply_fill,
-                apply_border: xf.apply_border,
-                apply_alignment: xf.apply_alignment,
-                apply_protection: xf.apply_protection,
-            };
-            self.manager.cell_xfs_manager.get_id(&ctrl_xf)
+            }
+        } else {
+            0
         };
-        self.cell_xf_cache.insert(idx, style_id);
-        style_id
+        CtrlXf {
+            alignment: xf.alignment.clone(),
+            protection: xf.protction.clone(),
+            font_id: Some(font_id),
+            border_id: Some(border_id),
+            fill_id: Some(fill_id),
+            num_fmt_id: Some(num_fmt_id),
+            apply_number_format: xf.apply_number_format,
+            apply_font: xf.apply_font,
+            apply_fill: xf.apply_fill,
+            apply_border: xf.apply_border,
+            apply_alignment: xf.apply_alignment,
+            apply_protection: xf.apply_protection,
+            xf_id: None,
+        }
     }
 }
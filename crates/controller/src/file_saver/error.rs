@@ -9,4 +9,6 @@ pub enum SaveError {
     SheetNameError(SheetId),
     #[error("Zip error")]
     ZipError,
+    #[error("Data table result cell not found: {1}")]
+    DataTableCellMissing(SheetId, String),
 }
@@ -48,18 +48,13 @@ pub fn save_file(
     let ext_ref_manager = &controller.status.ext_ref_manager;
 
     let data_container = &controller.status.container;
-    let attachment_manager = &controller.status.cell_attachment_manager;
     let sheet_pos_manager = &controller.status.sheet_info_manager;
     let sheet_id_manager = &controller.status.sheet_id_manager;
-    let style_manager = &controller.status.style_manager;
-    let ext_book_manager = &controller.status.external_links_manager;
-    let theme_manager = &controller.settings.theme;
 
     let settings = &controller.settings;
 
     let mut navigator_replication = controller.status.navigator.clone();
     let block_schema_manager = &controller.status.block_schema_manager;
-    let field_render_manager = &controller.status.field_render_manager;
     let mut saver = Saver {
         part_count: 0,
         _external_count: 0,
@@ -80,25 +75,11 @@ pub fn save_file(
     };
 
     save_workbook(
-        data_container,
-        formula_manager,
-        attachment_manager,
-        sheet_pos_manager,
-        sheet_id_manager,
-        style_manager,
-        ext_book_manager,
-        theme_manager,
-        text_id_manager,
-        &controller.status.navigator,
+        &controller.status,
         settings,
         controller.app_data.clone(),
-        block_schema_manager,
-        field_render_manager,
-        &controller.status.image_manager,
-        &controller.status.chart_manager,
-        &controller.status.data_validation_manager,
-        &controller.status.conditional_formatting_manager,
-        &controller.status.range_manager,
+        controller.checkpoint_manager.to_xml(&controller.status),
+        controller.audit_manager.to_xml(&controller.status),
         &mut saver,
     )
 }
@@ -261,6 +242,10 @@ impl<'a> NameFetcherTrait for Saver<'a> {
             .fetch_field_name(sheet_id, block_id, field_id)
     }
 
+    fn implicit_intersection_as_function(&self) -> bool {
+        true
+    }
+
     fn resolve_block_ref_cell(
         &self,
         sheet_id: SheetId,
@@ -22,7 +22,7 @@ pub fn save_sheet_style<S: SaverTrait>(manager: &StyleManager, _: &mut S) -> Sty
         borders,
         cell_style_xfs,
         cell_xfs,
-        cell_styles: None,
+        cell_styles: manager.named_styles.to_ct_cell_styles(),
         // Preserved in load order so every retained `dxfId` (conditional
         // formatting, table styles) still resolves.
         dxfs: manager.dxf_manager.to_ct_dxfs(),
@@ -1,11 +1,12 @@
 use itertools::Itertools;
-use logisheets_base::NormalRange;
+use logisheets_base::{CellId, NormalRange, SheetId};
 use logisheets_workbook::{
-    logisheets::{AppData, LinkRangeXml, LogiSheetsData, Sheet},
+    logisheets::{AppData, AuditXml, CheckpointXml, LinkRangeXml, LogiSheetsData, Sheet},
     prelude::{ChartAnchor, PassthroughPart},
     prelude::{
-        CtConditionalFormatting, CtExternalReference, CtExternalReferences, CtPerson, CtSheet,
-        CtSheets, Persons, WorkbookPart,
+        CtConditionalFormatting, CtExternalReference, CtExternalReferences, CtFormula,
+        CtInputCells, CtPerson, CtScenario, CtScenarios, CtSheet, CtSheetData, CtSheets, Persons,
+        StCellFormulaType, WorkbookPart,
     },
     workbook::{DocProps, Media, Wb, Worksheet, WorksheetDrawing, Xl},
 };
@@ -13,50 +14,51 @@ use std::collections::HashMap;
 
 use crate::{
     block_manager::{
-        field_manager::{FieldRenderManager, persistence::field_renders_to_xml},
-        schema_manager::{SchemaManager, persistence::schemas_to_xml},
+        field_manager::persistence::field_renders_to_xml,
+        schema_manager::persistence::schemas_to_xml,
     },
     cell_attachments::CellAttachmentsManager,
-    container::DataContainer,
-    data_validation_manager::DataValidationManager,
-    ext_book_manager::ExtBooksManager,
+    controller::status::Status,
     file_saver::{
         external_links::save_external_link, styles::save_sheet_style, worksheet::save_sheets,
     },
-    formula_manager::FormulaManager,
-    id_manager::{SheetIdManager, TextIdManager},
-    image_manager::ImageManager,
     navigator::Navigator,
     settings::Settings,
-    style_manager::StyleManager,
-    theme_manager::ThemeManager,
-    workbook::sheet_info_manager::SheetInfoManager,
+    sqref::format_rect,
 };
 
 use super::{SaverTrait, error::SaveError, sst::save_sst};
 
+/// Write the workbook from `status`, the managers of the sheet state, and
+/// what lives beside it: the settings, app data, checkpoints and audit log.
 pub fn save_workbook<S: SaverTrait>(
-    data_container: &DataContainer,
-    formula_manager: &FormulaManager,
-    attachment_manager: &CellAttachmentsManager,
-    sheet_pos_manager: &SheetInfoManager,
-    sheet_id_manager: &SheetIdManager,
-    style_manager: &StyleManager,
-    ext_book_manager: &ExtBooksManager,
-    theme_manager: &ThemeManager,
-    text_id_manager: &TextIdManager,
-    navigator: &Navigator,
+    status: &Status,
     settings: &Settings,
     app_data: Vec<AppData>,
-    block_schema_manager: &SchemaManager,
-    field_render_manager: &FieldRenderManager,
-    image_manager: &ImageManager,
-    chart_manager: &crate::chart_manager::ChartManager,
-    data_validation_manager: &DataValidationManager,
-    conditional_formatting_manager: &crate::conditional_formatting_manager::ConditionalFormattingManager,
-    range_manager: &crate::range_manager::RangeManager,
+    checkpoints: Vec<CheckpointXml>,
+    audit: Option<AuditXml>,
     saver: &mut S,
 ) -> Result<Wb, SaveError> {
+    let data_container = &status.container;
+    let formula_manager = &status.formula_manager;
+    let attachment_manager = &status.cell_attachment_manager;
+    let sheet_pos_manager = &status.sheet_info_manager;
+    let sheet_id_manager = &status.sheet_id_manager;
+    let style_manager = &status.style_manager;
+    let ext_book_manager = &status.external_links_manager;
+    let theme_manager = &status.theme_manager;
+    let text_id_manager = &status.text_id_manager;
+    let navigator = &status.navigator;
+    let block_schema_manager = &status.block_schema_manager;
+    let field_render_manager = &status.field_render_manager;
+    let image_manager = &status.image_manager;
+    let chart_manager = &status.chart_manager;
+    let data_validation_manager = &status.data_validation_manager;
+    let conditional_formatting_manager = &status.conditional_formatting_manager;
+    let data_table_manager = &status.data_table_manager;
+    let scenario_manager = &status.scenario_manager;
+    let range_manager = &status.range_manager;
+
     let mut worksheets: HashMap<String, Worksheet> = HashMap::new();
     let mut ct_sheets: Vec<CtSheet> = vec![];
     let mut sheets: Vec<Sheet> = vec![];
@@ -90,7 +92,7 @@ pub fn save_workbook<S: SaverTrait>(
             )
         })
         .sorted_by_key(|a| a.0)
-        .for_each(|(sheet_pos, ct_sheet, mut worksheet, block_ranges)| {
+        .try_for_each(|(sheet_pos, ct_sheet, mut worksheet, block_ranges)| {
             // The tuple's first element is the sheet *position* (usize),
             // not the `SheetId`. Resolve the id from the position manager
             // so `schemas_to_xml` can filter by stable id.
@@ -173,6 +175,23 @@ pub fn save_workbook<S: SaverTrait>(
             );
             worksheet.worksheet_part.conditional_formatting = modeled;
 
+            // Data tables: the `<f t="dataTable">` goes on the first result
+            // cell and its `ref` spans them all. The other result cells carry
+            // only their values, which the container already has.
+            data_tables_to_xml(
+                data_table_manager,
+                navigator,
+                sheet_id,
+                &mut worksheet.worksheet_part.sheet_data,
+            )?;
+
+            // Scenarios: a sheet's `<scenarios>` is still in `preserved_parts`
+            // only if it could not be modeled at load, so the modeled ones
+            // take its place whenever there are any.
+            if let Some(scenarios) = scenarios_to_xml(scenario_manager, navigator, sheet_id) {
+                worksheet.worksheet_part.scenarios = Some(scenarios);
+            }
+
             worksheets.insert(ct_sheet.id.clone(), worksheet);
             ct_sheets.push(ct_sheet);
             let (row_schemas, col_schemas, random_schemas) =
@@ -217,7 +236,8 @@ pub fn save_workbook<S: SaverTrait>(
                 link_ranges,
             };
             sheets.push(sheet);
-        });
+            Ok::<(), SaveError>(())
+        })?;
     let ct_sheets = CtSheets { sheets: ct_sheets };
     let styles = save_sheet_style(style_manager, saver);
     let style_id = saver.fetch_part_id();
@@ -266,6 +286,9 @@ pub fn save_workbook<S: SaverTrait>(
             sheets,
             apps: app_data,
             field_renders: field_renders_to_xml(field_render_manager, style_manager),
+            checkpoints,
+            audit,
+            input_locale: settings.input_locale.to_xml(),
         }),
     };
     Ok(workbook)
@@ -323,6 +346,121 @@ fn get_workbook(ct_sheets: CtSheets, ext_references: Vec<CtExternalReference>) -
     }
 }
 
+/// Write each data table on `sheet_id` as the `<f>` of its first result
+/// cell.
+fn data_tables_to_xml(
+    manager: &crate::data_table_manager::DataTableManager,
+    navigator: &Navigator,
+    sheet_id: SheetId,
+    sheet_data: &mut CtSheetData,
+) -> Result<(), SaveError> {
+    for table in manager.tables_of_sheet(sheet_id) {
+        let Some(l) = crate::data_table_manager::resolve::layout(navigator, table) else {
+            continue;
+        };
+        let cell_ref = |p: Option<(usize, usize)>| p.map(|(r, c)| format_rect(r, c, r, c));
+        // One input is `r1`; `dtr` says whether it is the row input. With
+        // both, `r1` is the row input, so `dtr` is set, and `r2` the column
+        // input.
+        let dt_2d = table.row_input.is_some() && table.col_input.is_some();
+        let (r1, r2, dtr) = if dt_2d {
+            (cell_ref(l.row_input), cell_ref(l.col_input), true)
+        } else if table.row_input.is_some() {
+            (cell_ref(l.row_input), None, true)
+        } else {
+            (cell_ref(l.col_input), None, false)
+        };
+        let first = format_rect(l.start.0, l.start.1, l.start.0, l.start.1);
+        let cell = sheet_data
+            .rows
+            .iter_mut()
+            .flat_map(|row| row.cells.iter_mut())
+            .find(|c| c.r.as_deref() == Some(first.as_str()))
+            .ok_or(SaveError::DataTableCellMissing(sheet_id, first))?;
+        cell.f = Some(CtFormula {
+            formula: None,
+            t: StCellFormulaType::DataTable,
+            aca: false,
+            reference: Some(l.reference()),
+            dt_2d,
+            dtr,
+            // A deleted input cell is flagged and its reference dropped.
+            del1: l.broken && r1.is_none(),
+            del2: l.broken && dt_2d && r2.is_none(),
+            r1,
+            r2,
+            ca: false,
+            si: None,
+            bx: false,
+        });
+    }
+    Ok(())
+}
+
+/// Render a sheet's scenarios at the current positions of their cells. A
+/// scenario whose changing cells were all deleted is left out.
+fn scenarios_to_xml(
+    manager: &crate::scenario_manager::ScenarioManager,
+    navigator: &Navigator,
+    sheet_id: SheetId,
+) -> Option<CtScenarios> {
+    let sheet = manager.get_sheet(sheet_id)?;
+    let cell_ref = |id: &CellId| {
+        let (r, c) = navigator.fetch_cell_idx(&sheet_id, id).ok()?;
+        Some(format_rect(r, c, r, c))
+    };
+    let scenarios = sheet
+        .scenarios
+        .iter()
+        .filter_map(|s| {
+            let input_cells = s
+                .cells
+                .iter()
+                .filter_map(|c| {
+                    Some(CtInputCells {
+                        r: cell_ref(&c.cell_id)?,
+                        deleted: false,
+                        undone: false,
+                        val: c.value.clone(),
+                        num_fmt_id: c.num_fmt_id,
+                    })
+                })
+                .collect::<Vec<_>>();
+            if input_cells.is_empty() {
+                return None;
+            }
+            Some(CtScenario {
+                count: input_cells.len() as u32,
+                input_cells,
+                name: s.name.clone(),
+                locked: s.locked,
+                hidden: s.hidden,
+                user: s.user.clone(),
+                comment: s.comment.clone(),
+            })
+        })
+        .collect::<Vec<_>>();
+    if scenarios.is_empty() {
+        return None;
+    }
+    let idx_of = |name: &Option<String>| {
+        let name = name.as_ref()?;
+        let idx = scenarios.iter().position(|s| s.name == *name)?;
+        Some(idx as u32)
+    };
+    let result_cells = sheet
+        .result_cells
+        .iter()
+        .filter_map(cell_ref)
+        .collect::<Vec<_>>();
+    Some(CtScenarios {
+        current: idx_of(&sheet.current),
+        show: idx_of(&sheet.shown),
+        sqref: (!result_cells.is_empty()).then(|| result_cells.join(" ")),
+        scenarios,
+    })
+}
+
 /// Render a sheet's modeled conditional formatting back to OOXML. A block whose
 /// every range lost its anchors (the rows/columns were deleted) yields no
 /// element — matching Excel, where deleting the covered rows removes the rule.
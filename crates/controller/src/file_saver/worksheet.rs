@@ -462,6 +462,7 @@ fn save_sheet_data<S: SaverTrait>(
                         aca: false,
                         reference: None,
                         dt_2d: false,
+                        dtr: false,
                         del1: false,
                         del2: false,
                         r1: None,
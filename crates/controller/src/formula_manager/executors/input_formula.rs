@@ -8,7 +8,9 @@ use logisheets_parser::Parser;
 use logisheets_parser::ast::{self, RangeDisplay};
 use std::collections::HashSet;
 
+use crate::cell_shift::{CellMove, RectMove};
 use crate::formula_manager::{FormulaManager, Vertex, ctx::FormulaExecCtx};
+use crate::range_move::RangeMove;
 
 pub fn input_formula<C: FormulaExecCtx>(
     executor: FormulaExecutor,
@@ -927,6 +929,195 @@ fn get_all_vertices_from_ast(ast: &ast::Node, vertices: &mut HashSet<Vertex>) {
     }
 }
 
+/// Carry the formulas along a `MoveRange`. Formulas in the moved cells are
+/// re-registered at their destination and those in overwritten cells are
+/// dropped. Every reference wholly inside the moved cells follows them, a
+/// reference wholly inside the overwritten cells becomes `#REF!`, and a
+/// formula whose reference only overlaps the move is recalculated.
+pub fn move_range<C: FormulaExecCtx>(
+    executor: FormulaExecutor,
+    mv: &RangeMove,
+    ctx: &mut C,
+) -> Result<FormulaExecutor, BasicError> {
+    let mut executor = executor;
+    let mut moved = vec![];
+    for ((sheet, cell_id), ast) in executor.manager.formulas.clone() {
+        let CellId::NormalCell(normal) = cell_id else {
+            continue;
+        };
+        let Ok((row, col)) = ctx.fetch_normal_cell_index(&sheet, &normal) else {
+            continue;
+        };
+        match mv.move_cell(sheet, row, col) {
+            CellMove::Stay => {}
+            CellMove::Deleted => executor = remove(executor, sheet, cell_id, ctx)?,
+            CellMove::To(row, col) => {
+                executor = remove(executor, sheet, cell_id, ctx)?;
+                moved.push((row, col, ast));
+            }
+        }
+    }
+
+    for ((sheet, cell_id), ast) in executor.manager.formulas.clone() {
+        let mut touched = false;
+        match move_refs(&ast, mv, ctx, &mut touched) {
+            Some(ast) => executor = register_parsed_ast(executor, sheet, cell_id, ast, ctx)?,
+            None if touched => {
+                let range = match cell_id {
+                    CellId::NormalCell(normal) => Range::Normal(NormalRange::Single(normal)),
+                    CellId::BlockCell(block) => Range::Block(BlockRange::Single(block)),
+                    CellId::EphemeralCell(v) => Range::Ephemeral(v),
+                };
+                let range_id = ctx.fetch_range_id(&sheet, &range);
+                executor
+                    .dirty_vertices
+                    .insert(Vertex::Range(sheet, range_id));
+            }
+            None => {}
+        }
+    }
+    for (row, col, ast) in moved {
+        let mut touched = false;
+        let ast = move_refs(&ast, mv, ctx, &mut touched).unwrap_or(ast);
+        let cell_id = ctx.fetch_norm_cell_id(&mv.to, row, col)?;
+        executor = register_parsed_ast(executor, mv.to, CellId::NormalCell(cell_id), ast, ctx)?;
+    }
+    Ok(executor)
+}
+
+/// Rewrite the references of `ast` along the move. Returns `None` when no
+/// reference changes; `touched` is set when one overlaps the move in place.
+fn move_refs<C: FormulaExecCtx>(
+    ast: &ast::Node,
+    mv: &RangeMove,
+    ctx: &mut C,
+    touched: &mut bool,
+) -> Option<ast::Node> {
+    let pure = match &ast.pure {
+        ast::PureNode::Func(func) => {
+            let args = func
+                .args
+                .iter()
+                .map(|n| move_refs(n, mv, ctx, touched))
+                .collect::<Vec<_>>();
+            if args.iter().all(Option::is_none) {
+                return None;
+            }
+            let args = args
+                .into_iter()
+                .zip(func.args.iter())
+                .map(|(moved, old)| moved.unwrap_or_else(|| old.clone()))
+                .collect();
+            ast::PureNode::Func(ast::Func {
+                op: func.op.clone(),
+                args,
+            })
+        }
+        ast::PureNode::Value(_) | ast::PureNode::ArrayConstant(_) => return None,
+        ast::PureNode::Reference(ast::CellReference::Mut(r)) => {
+            let reference = move_ref(r, mv, ctx, touched)?;
+            ast::PureNode::Reference(reference)
+        }
+        ast::PureNode::Reference(_) => return None,
+        ast::PureNode::BlockRef(node) => match node {
+            ast::BlockRefNode::Single {
+                sheet_id,
+                block_id,
+                field_id,
+                by_block,
+                key,
+            } => ast::PureNode::BlockRef(ast::BlockRefNode::Single {
+                sheet_id: *sheet_id,
+                block_id: *block_id,
+                field_id: *field_id,
+                by_block: *by_block,
+                key: Box::new(move_refs(key, mv, ctx, touched)?),
+            }),
+            ast::BlockRefNode::Multi {
+                sheet_id,
+                block_id,
+                by_block,
+                key_condition,
+                field_condition,
+            } => {
+                let key = move_refs(key_condition, mv, ctx, touched);
+                let field = move_refs(field_condition, mv, ctx, touched);
+                if key.is_none() && field.is_none() {
+                    return None;
+                }
+                ast::PureNode::BlockRef(ast::BlockRefNode::Multi {
+                    sheet_id: *sheet_id,
+                    block_id: *block_id,
+                    by_block: *by_block,
+                    key_condition: Box::new(key.unwrap_or_else(|| *key_condition.clone())),
+                    field_condition: Box::new(field.unwrap_or_else(|| *field_condition.clone())),
+                })
+            }
+        },
+    };
+    Some(ast::Node {
+        pure,
+        bracket: ast.bracket,
+    })
+}
+
+fn move_ref<C: FormulaExecCtx>(
+    r: &RangeDisplay,
+    mv: &RangeMove,
+    ctx: &mut C,
+    touched: &mut bool,
+) -> Option<ast::CellReference> {
+    let Some(Range::Normal(range)) = ctx.lookup_range(r.sheet_id, r.range_id) else {
+        return None;
+    };
+    let sheet = r.sheet_id;
+    let (r1, c1, r2, c2) = match &range {
+        NormalRange::Single(c) => {
+            let (row, col) = ctx.fetch_normal_cell_index(&sheet, c).ok()?;
+            (row, col, row, col)
+        }
+        NormalRange::AddrRange(s, e) => {
+            let (r1, c1) = ctx.fetch_normal_cell_index(&sheet, s).ok()?;
+            let (r2, c2) = ctx.fetch_normal_cell_index(&sheet, e).ok()?;
+            (r1, c1, r2, c2)
+        }
+        NormalRange::RowRange(s, e) => {
+            let r1 = ctx.fetch_row_index(&sheet, s).ok()?;
+            let r2 = ctx.fetch_row_index(&sheet, e).ok()?;
+            (r1, 0, r2, usize::MAX)
+        }
+        NormalRange::ColRange(s, e) => {
+            let c1 = ctx.fetch_col_index(&sheet, s).ok()?;
+            let c2 = ctx.fetch_col_index(&sheet, e).ok()?;
+            (0, c1, usize::MAX, c2)
+        }
+    };
+    match mv.move_rect(sheet, r1, c1, r2, c2) {
+        RectMove::Stay => None,
+        RectMove::Dirty => {
+            *touched = true;
+            None
+        }
+        RectMove::Deleted => Some(ast::CellReference::RefErr),
+        RectMove::To(r1, c1, r2, c2) => {
+            let start = ctx.fetch_norm_cell_id(&mv.to, r1, c1).ok()?;
+            let range = match range {
+                NormalRange::Single(_) => NormalRange::Single(start),
+                _ => {
+                    let end = ctx.fetch_norm_cell_id(&mv.to, r2, c2).ok()?;
+                    NormalRange::AddrRange(start, end)
+                }
+            };
+            let range_id = ctx.fetch_range_id(&mv.to, &Range::Normal(range));
+            Some(ast::CellReference::Mut(RangeDisplay {
+                sheet_id: mv.to,
+                range_id,
+                ref_abs: r.ref_abs,
+            }))
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -4,7 +4,7 @@ use std::collections::HashSet;
 
 pub use input_formula::{convert_cells_to_block,
     add_ast_node, input_block_cell_shadow_template, input_block_cell_template, input_block_formula,
-    input_ephemeral_formula, input_formula, rebuild_range_deps, remove_ephemeral_formula,
+    input_ephemeral_formula, input_formula, move_range, rebuild_range_deps, remove_ephemeral_formula,
     remove_formula,
 };
 use logisheets_base::{
@@ -12,6 +12,7 @@ use logisheets_base::{
 };
 
 use crate::block_manager::schema_manager::schema::BlockCellRole;
+use crate::range_move::RangeMove;
 use crate::{Error, edit_action::EditPayload};
 
 use super::{FormulaManager, Vertex, ctx::FormulaExecCtx};
@@ -268,6 +269,22 @@ impl FormulaExecutor {
                 rebuild_range_deps(&mut exec.manager, ctx);
                 Ok(exec)
             }
+            EditPayload::InsertCells(_) | EditPayload::DeleteCells(_) => {
+                // Shifting re-anchors ranges on other cells, so a range now
+                // covers members it has no edges to yet.
+                let mut exec = self;
+                rebuild_range_deps(&mut exec.manager, ctx);
+                Ok(exec)
+            }
+            EditPayload::MoveRange(p) => {
+                let from = ctx
+                    .fetch_sheet_id_by_index(p.sheet_idx)
+                    .map_err(BasicError::SheetIdxExceed)?;
+                let to = ctx
+                    .fetch_sheet_id_by_index(p.dest_sheet_idx)
+                    .map_err(BasicError::SheetIdxExceed)?;
+                move_range(self, &RangeMove::new(from, to, &p), ctx)
+            }
             _ => Ok(self),
         }?;
         let FormulaExecutor {
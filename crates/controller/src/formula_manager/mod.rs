@@ -4,10 +4,13 @@ pub mod graph;
 
 use graph::Graph;
 use imbl::HashMap;
-use logisheets_base::{BlockFieldId, BlockId, CubeId, ExtRefId, NameId, RangeId, SheetId};
+use logisheets_base::{
+    BlockFieldId, BlockId, CubeId, ExtRefId, NameId, NormalCellId, RangeId, SheetId,
+};
 use logisheets_parser::ast;
 
 use crate::CellId;
+use crate::cell_shift::{CellMoves, normal_cell, take_moving};
 
 use self::ctx::FormulaExecCtx;
 use self::executors::{add_ast_node, rebuild_range_deps};
@@ -47,6 +50,36 @@ impl FormulaManager {
     pub fn rebuild_range_deps<C: FormulaExecCtx>(&mut self, ctx: &C) {
         rebuild_range_deps(self, ctx)
     }
+
+    /// Move the formulas of a cell shift along with their cells. A moved
+    /// formula keeps its vertex, since the range of its cell is re-anchored
+    /// rather than replaced. A deleted one is detached from the graph like a
+    /// cleared cell; `range_of` gives the range id of a cell's vertex.
+    pub fn shift_cells(
+        &mut self,
+        sheet_id: SheetId,
+        moves: &CellMoves,
+        range_of: impl Fn(&NormalCellId) -> Option<RangeId>,
+    ) {
+        let taken = take_moving(&mut self.formulas, moves, |(s, id)| {
+            normal_cell(id).filter(|_| *s == sheet_id)
+        });
+        for ((_, old), new, ast) in taken {
+            if let Some(new) = new {
+                self.formulas
+                    .insert((sheet_id, CellId::NormalCell(new)), ast);
+                continue;
+            }
+            let Some(range_id) = normal_cell(&old).and_then(|c| range_of(&c)) else {
+                continue;
+            };
+            let vertex = Vertex::Range(sheet_id, range_id);
+            if let Some(deps) = self.graph.get_deps(&vertex).cloned() {
+                deps.iter()
+                    .for_each(|dep| self.graph.remove_dep(&vertex, dep));
+            }
+        }
+    }
 }
 
 /// Vertex of the formula dependency graph.
@@ -17,6 +17,8 @@ use std::sync::Arc;
 use imbl::HashMap;
 use logisheets_base::{CellId, SheetId};
 
+use crate::cell_shift::{CellMoves, normal_cell, take_moving};
+
 /// An image filling a single cell.
 #[derive(Debug, Clone)]
 pub struct CellImage {
@@ -57,4 +59,29 @@ impl ImageManager {
             .map(|((_, c), img)| (*c, img.clone()))
             .collect()
     }
+
+    /// Move the images of sheet `from` along with their cells to sheet `to`,
+    /// which may be the same sheet; those of deleted cells go with them.
+    pub fn move_cells(&mut self, from: SheetId, to: SheetId, moves: &CellMoves) {
+        let taken = take_moving(&mut self.images, moves, |(s, id)| {
+            normal_cell(id).filter(|_| *s == from)
+        });
+        for (_, new, image) in taken {
+            if let Some(new) = new {
+                self.images.insert((to, CellId::NormalCell(new)), image);
+            }
+        }
+    }
+
+    /// Copy the images of sheet `from` onto the same cells of sheet `to`,
+    /// each under a new id.
+    pub fn duplicate_sheet(&mut self, from: SheetId, to: SheetId) {
+        for (cell_id, image) in self.images_of_sheet(from) {
+            let image = CellImage {
+                id: uuid::Uuid::new_v4().to_string(),
+                ..image
+            };
+            self.images.insert((to, cell_id), image);
+        }
+    }
 }
@@ -0,0 +1,331 @@
+//! Excel-style recognition of typed cell input.
+//!
+//! Typing `50%`, `$1,234.50`, `3/1/2024` or `14:30` into a cell stores a
+//! number, and the cell picks up a number format that shows it the way it
+//! was typed. `recognize` does the reading; the executor rewrites the
+//! `CellInput` to the number and applies the format.
+
+use chrono::{Datelike, NaiveDate};
+use logisheets_base::datetime::get_serial_num_by_date_1900;
+
+use crate::settings::{DateOrder, InputLocale};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Recognized {
+    pub value: f64,
+    /// The number format that displays `value` the way it was typed. `None`
+    /// for input that reads as a plain number.
+    pub format: Option<String>,
+}
+
+/// Read `text` as a number, percentage, currency amount, date or time.
+/// Returns `None` for anything else, which is stored as typed.
+pub fn recognize(text: &str, locale: &InputLocale) -> Option<Recognized> {
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+    recognize_date_time(text, locale).or_else(|| recognize_number(text, locale))
+}
+
+fn recognize_number(text: &str, locale: &InputLocale) -> Option<Recognized> {
+    let mut s = text;
+    let mut negative = false;
+    // Accounting style: `(200)` is -200.
+    if let Some(inner) = s.strip_prefix('(').and_then(|s| s.strip_suffix(')')) {
+        negative = true;
+        s = inner.trim();
+    }
+    if let Some(rest) = s.strip_prefix('-') {
+        if negative {
+            return None;
+        }
+        negative = true;
+        s = rest.trim_start();
+    } else if let Some(rest) = s.strip_prefix('+') {
+        s = rest.trim_start();
+    }
+
+    let symbol = locale.currency_symbol.as_str();
+    // `Some(true)` when the symbol leads the amount.
+    let mut currency = None;
+    if !symbol.is_empty() {
+        if let Some(rest) = s.strip_prefix(symbol) {
+            currency = Some(true);
+            s = rest.trim_start();
+            if let Some(rest) = s.strip_prefix('-').filter(|_| !negative) {
+                negative = true;
+                s = rest;
+            }
+        } else if let Some(rest) = s.strip_suffix(symbol) {
+            currency = Some(false);
+            s = rest.trim_end();
+        }
+    }
+    let percent = match s.strip_suffix('%') {
+        Some(rest) => {
+            s = rest.trim_end();
+            true
+        }
+        None => false,
+    };
+    if percent && currency.is_some() {
+        return None;
+    }
+
+    let number = parse_number(s, locale)?;
+    if number.exponent && (percent || currency.is_some() || number.grouped) {
+        return None;
+    }
+    let mut value = if negative {
+        -number.value
+    } else {
+        number.value
+    };
+    let decimals = if number.decimals > 0 { ".00" } else { "" };
+    let format = if let Some(leading) = currency {
+        Some(if leading {
+            format!("\"{symbol}\"#,##0{decimals}")
+        } else {
+            format!("#,##0{decimals} \"{symbol}\"")
+        })
+    } else if percent {
+        value /= 100.;
+        Some(format!("0{decimals}%"))
+    } else if number.exponent {
+        Some(String::from("0.00E+00"))
+    } else if number.grouped {
+        Some(format!("#,##0{decimals}"))
+    } else {
+        None
+    };
+    Some(Recognized { value, format })
+}
+
+struct Number {
+    value: f64,
+    decimals: usize,
+    grouped: bool,
+    exponent: bool,
+}
+
+/// An unsigned number written with the locale's separators: thousands
+/// groups of three, an optional fraction and an optional exponent.
+fn parse_number(s: &str, locale: &InputLocale) -> Option<Number> {
+    let (mantissa, exponent) = match s.find(['e', 'E']) {
+        Some(i) => (&s[..i], Some(&s[i + 1..])),
+        None => (s, None),
+    };
+    let (int, frac) = mantissa
+        .split_once(locale.decimal_separator)
+        .unwrap_or((mantissa, ""));
+    let groups = int.split(locale.thousands_separator).collect::<Vec<_>>();
+    let grouped = groups.len() > 1;
+    if grouped
+        && (groups[0].is_empty() || groups[0].len() > 3 || groups[1..].iter().any(|g| g.len() != 3))
+    {
+        return None;
+    }
+    let int = groups.concat();
+    if int.is_empty() && frac.is_empty() {
+        return None;
+    }
+    if !int.bytes().chain(frac.bytes()).all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let mut canonical = format!("{int}.{frac}");
+    if let Some(exp) = exponent {
+        let digits = exp.strip_prefix(['+', '-']).unwrap_or(exp);
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        canonical.push('e');
+        canonical.push_str(exp);
+    }
+    let value = canonical.parse::<f64>().ok().filter(|v| v.is_finite())?;
+    Some(Number {
+        value,
+        decimals: frac.len(),
+        grouped,
+        exponent: exponent.is_some(),
+    })
+}
+
+fn recognize_date_time(text: &str, locale: &InputLocale) -> Option<Recognized> {
+    let (date, time) = match text.split_once(char::is_whitespace) {
+        Some((first, rest)) if !first.contains(':') => (Some(first), Some(rest.trim())),
+        _ if text.contains(':') => (None, Some(text)),
+        _ => (Some(text), None),
+    };
+    let mut value = 0.;
+    let mut formats = Vec::new();
+    if let Some(date) = date {
+        let (serial, format) = parse_date(date, locale)?;
+        value += serial as f64;
+        formats.push(format);
+    }
+    if let Some(time) = time {
+        let (fraction, format) = parse_time(time)?;
+        value += fraction;
+        formats.push(format);
+    }
+    Some(Recognized {
+        value,
+        format: Some(formats.join(" ")),
+    })
+}
+
+/// A date of three numeric parts. A leading four-digit year reads as
+/// year-month-day whatever the locale says.
+fn parse_date(s: &str, locale: &InputLocale) -> Option<(u32, String)> {
+    let sep = s
+        .chars()
+        .find(|c| *c == '/' || *c == '-' || *c == locale.date_separator)?;
+    let parts = s.split(sep).collect::<Vec<_>>();
+    if parts.len() != 3
+        || parts
+            .iter()
+            .any(|p| p.is_empty() || p.len() > 4 || !p.bytes().all(|b| b.is_ascii_digit()))
+    {
+        return None;
+    }
+    let order = if parts[0].len() == 4 {
+        DateOrder::Ymd
+    } else {
+        locale.date_order
+    };
+    let (y, m, d) = match order {
+        DateOrder::Ymd => (0, 1, 2),
+        DateOrder::Mdy => (2, 0, 1),
+        DateOrder::Dmy => (2, 1, 0),
+    };
+    if parts[m].len() > 2 || parts[d].len() > 2 || parts[y].len() == 3 {
+        return None;
+    }
+    let year = parts[y].parse::<i32>().ok()?;
+    // Two-digit years follow Excel: 00-29 are 20xx, 30-99 are 19xx.
+    let year = match (parts[y].len(), year) {
+        (4, _) => year,
+        (_, 0..=29) => 2000 + year,
+        _ => 1900 + year,
+    };
+    let date = NaiveDate::from_ymd_opt(year, parts[m].parse().ok()?, parts[d].parse().ok()?)?;
+    if year < 1900 {
+        return None;
+    }
+    let serial = get_serial_num_by_date_1900(year as u32, date.month(), date.day())?;
+
+    let sep = match sep {
+        '/' | '-' => sep.to_string(),
+        _ => format!("\\{sep}"),
+    };
+    let format = match order {
+        DateOrder::Ymd => ["yyyy", "mm", "dd"].join(&sep),
+        DateOrder::Mdy => ["m", "d", "yyyy"].join(&sep),
+        DateOrder::Dmy => ["d", "m", "yyyy"].join(&sep),
+    };
+    Some((serial, format))
+}
+
+/// `h:mm` or `h:mm:ss`, on a 24-hour clock or followed by AM/PM.
+fn parse_time(s: &str) -> Option<(f64, String)> {
+    let upper = s.to_ascii_uppercase();
+    let (clock, pm) = if let Some(clock) = upper.strip_suffix("AM") {
+        (clock.trim_end(), Some(false))
+    } else if let Some(clock) = upper.strip_suffix("PM") {
+        (clock.trim_end(), Some(true))
+    } else {
+        (upper.as_str(), None)
+    };
+    let parts = clock.split(':').collect::<Vec<_>>();
+    if !(2..=3).contains(&parts.len())
+        || parts
+            .iter()
+            .any(|p| p.is_empty() || p.len() > 2 || !p.bytes().all(|b| b.is_ascii_digit()))
+    {
+        return None;
+    }
+    let mut hour = parts[0].parse::<u32>().ok()?;
+    let minute = parts[1].parse::<u32>().ok()?;
+    let second = match parts.get(2) {
+        Some(p) => p.parse::<u32>().ok()?,
+        None => 0,
+    };
+    if minute > 59 || second > 59 {
+        return None;
+    }
+    match pm {
+        Some(_) if hour == 0 || hour > 12 => return None,
+        Some(pm) => hour = hour % 12 + if pm { 12 } else { 0 },
+        None if hour > 23 => return None,
+        None => {}
+    }
+    let value = (hour * 3600 + minute * 60 + second) as f64 / 86400.;
+    let mut format = String::from(if parts.len() == 3 { "h:mm:ss" } else { "h:mm" });
+    if pm.is_some() {
+        format.push_str(" AM/PM");
+    }
+    Some((value, format))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn us(text: &str) -> Option<(f64, Option<String>)> {
+        recognize(text, &InputLocale::default()).map(|r| (r.value, r.format))
+    }
+
+    fn fmt(s: &str) -> Option<String> {
+        Some(s.to_string())
+    }
+
+    #[test]
+    fn numbers() {
+        assert_eq!(us("50%"), Some((0.5, fmt("0%"))));
+        assert_eq!(us("12.5%"), Some((0.125, fmt("0.00%"))));
+        assert_eq!(us("$1,234.50"), Some((1234.5, fmt("\"$\"#,##0.00"))));
+        assert_eq!(us("-$5"), Some((-5., fmt("\"$\"#,##0"))));
+        assert_eq!(us("1,234"), Some((1234., fmt("#,##0"))));
+        assert_eq!(us("1e3"), Some((1000., fmt("0.00E+00"))));
+        assert_eq!(us("(200)"), Some((-200., None)));
+        assert_eq!(us("42"), Some((42., None)));
+        assert_eq!(us("1,23"), None);
+        assert_eq!(us("(-5)"), None);
+        assert_eq!(us("abc"), None);
+        assert_eq!(us("1e"), None);
+    }
+
+    #[test]
+    fn dates_and_times() {
+        assert_eq!(us("2024-03-01"), Some((45352., fmt("yyyy-mm-dd"))));
+        assert_eq!(us("3/1/2024"), Some((45352., fmt("m/d/yyyy"))));
+        assert_eq!(us("1900-01-01"), Some((1., fmt("yyyy-mm-dd"))));
+        assert_eq!(us("1900-03-01"), Some((61., fmt("yyyy-mm-dd"))));
+        assert_eq!(us("14:30"), Some((14.5 / 24., fmt("h:mm"))));
+        assert_eq!(us("2:30 PM"), Some((14.5 / 24., fmt("h:mm AM/PM"))));
+        assert_eq!(
+            us("2024-03-01 12:00"),
+            Some((45352.5, fmt("yyyy-mm-dd h:mm")))
+        );
+        assert_eq!(us("2/30/2024"), None);
+        assert_eq!(us("24:00"), None);
+        assert_eq!(us("555-123-4567"), None);
+    }
+
+    #[test]
+    fn follows_the_locale() {
+        let de = InputLocale {
+            date_order: DateOrder::Dmy,
+            date_separator: '.',
+            decimal_separator: ',',
+            thousands_separator: '.',
+            currency_symbol: String::from("€"),
+        };
+        let de = |text| recognize(text, &de).map(|r| (r.value, r.format));
+        assert_eq!(de("1.3.2024"), Some((45352., fmt("d\\.m\\.yyyy"))));
+        assert_eq!(de("1,5"), Some((1.5, None)));
+        assert_eq!(de("1.234.567"), Some((1234567., fmt("#,##0"))));
+        assert_eq!(de("12,50 €"), Some((12.5, fmt("#,##0.00 \"€\""))));
+    }
+}
@@ -3,10 +3,13 @@ extern crate lazy_static;
 
 pub mod api;
 mod async_func_manager;
+pub mod audit_manager;
 mod block_manager;
+pub mod branch_manager;
 mod calc_engine;
 mod cell;
 mod cell_attachments;
+mod cell_shift;
 pub mod chart_manager;
 pub mod checkpoint_manager;
 pub mod conditional_formatting_manager;
@@ -14,6 +17,7 @@ mod connectors;
 mod container;
 pub mod controller;
 mod cube_manager;
+pub mod data_table_manager;
 pub mod data_validation_manager;
 pub mod edit_action;
 mod errors;
@@ -25,9 +29,12 @@ mod file_saver;
 mod formula_manager;
 mod id_manager;
 pub mod image_manager;
+mod input_recognition;
 mod lock;
 mod navigator;
 mod range_manager;
+mod range_move;
+pub mod scenario_manager;
 mod settings;
 pub mod sid_assigner;
 mod sqref;
@@ -47,6 +54,7 @@ pub use controller::{
     take_last_error,
 };
 pub use exclusive::{Appendix, AppendixWithCell};
+pub use settings::{DateOrder, InputLocale, InputLocaleInfo};
 pub use logisheets_workbook::prelude::SerdeErr;
 
 pub use logisheets_base::BlockId;
@@ -54,6 +62,11 @@ pub use logisheets_base::async_func::AsyncCalcResult;
 pub use logisheets_base::async_func::AsyncErr;
 pub use logisheets_base::async_func::Task;
 
+pub use logisheets_astchecker::{
+    ArgCount, ArgType, FormulaCheckResult, FormulaDiagnostic, FormulaDiagnosticKind, FunctionArg,
+    FunctionCategory, FunctionSignature,
+};
+pub use logisheets_lexer::{FormulaToken, FormulaTokenKind, TokenReference, tokenize};
 pub use logisheets_lexer4fmt::{CellRef, FormulaDisplayInfo, TokenType, TokenUnit};
 
 // Has SKIPPED the '='
@@ -69,4 +82,10 @@ pub fn lex_and_fmt(s: &str) -> Option<FormulaDisplayInfo> {
     logisheets_lexer4fmt::lex_and_fmt(s)
 }
 
+/// Metadata of a built-in function: parameters, expected types, description,
+/// category and volatility. The lookup is case-insensitive.
+pub fn get_function_signature(name: &str) -> Option<FunctionSignature> {
+    logisheets_astchecker::get_function_signature(name).cloned()
+}
+
 pub use api::*;
@@ -2,7 +2,11 @@ use logisheets_base::{CellId, ColId, RowId, SheetId, errors::BasicError};
 
 use super::sheet_nav::{MAX_COL_CNT, MAX_ROW_CNT};
 use super::{BlockPlace, Navigator, SheetNav, ctx::NavExecCtx};
-use crate::{Error, edit_action::EditPayload};
+use crate::{
+    Error,
+    cell_shift::{CellMove, CellShift},
+    edit_action::EditPayload,
+};
 
 /// Cells a single block may cover.
 ///
@@ -458,11 +462,55 @@ impl NavExecutor {
                 };
                 Ok((result, true))
             }
+            EditPayload::InsertCells(p) => {
+                let sheet_id = ctx
+                    .fetch_sheet_id_by_index(p.sheet_idx)
+                    .map_err(BasicError::SheetIdxExceed)?;
+                shift_blocks(&mut self.nav, &sheet_id, &CellShift::from(&p));
+                Ok((self, true))
+            }
+            EditPayload::DeleteCells(p) => {
+                let sheet_id = ctx
+                    .fetch_sheet_id_by_index(p.sheet_idx)
+                    .map_err(BasicError::SheetIdxExceed)?;
+                shift_blocks(&mut self.nav, &sheet_id, &CellShift::from(&p));
+                Ok((self, true))
+            }
             _ => Ok((self, false)),
         }
     }
 }
 
+/// Move the blocks lying in the lanes of a cell shift along with the cells
+/// around them. The controller has already refused shifts that would tear
+/// or delete a block, so each one either stays or moves whole.
+fn shift_blocks(nav: &mut Navigator, sheet_id: &SheetId, shift: &CellShift) {
+    let Some(sheet_nav) = nav.sheet_navs.get(sheet_id) else {
+        return;
+    };
+    let mut moves = sheet_nav
+        .data
+        .blocks
+        .iter()
+        .filter_map(|(block_id, bp)| {
+            let (row, col) = nav.fetch_normal_cell_idx(sheet_id, &bp.master).ok()?;
+            match shift.move_cell(row, col) {
+                CellMove::To(new_row, new_col) => Some((*block_id, row + col, new_row, new_col)),
+                _ => None,
+            }
+        })
+        .collect::<Vec<_>>();
+    // Move the block farthest along the shift first when inserting, and the
+    // nearest first when deleting, so no block lands on one yet to move.
+    moves.sort_by_key(|(_, pos, _, _)| *pos);
+    if shift.insert {
+        moves.reverse();
+    }
+    for (block_id, _, row, col) in moves {
+        nav.move_block(sheet_id, &block_id, row, col);
+    }
+}
+
 fn insert_new_rows(sheet_nav: SheetNav, idx: usize, cnt: u32) -> (SheetNav, Vec<RowId>) {
     let mut new_id_manager = sheet_nav.id_manager.clone();
     let new_ids = new_id_manager.get_row_ids(cnt);
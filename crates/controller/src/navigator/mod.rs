@@ -201,6 +201,17 @@ impl Navigator {
         self.sheet_navs.insert(sheet_id, SheetNav::default());
     }
 
+    /// Give sheet `to` the rows, columns and blocks of sheet `from`. Their
+    /// ids are only unique within a sheet, so the copy keeps them and every
+    /// cell id of `from` names the same position in `to`.
+    pub fn duplicate_sheet(&mut self, from: &SheetId, to: SheetId) -> Result<(), BasicError> {
+        let mut sheet_nav = self.get_sheet_nav(from)?.clone();
+        sheet_nav.sheet_id = to;
+        sheet_nav.cache = Default::default();
+        self.sheet_navs.insert(to, sheet_nav);
+        Ok(())
+    }
+
     pub fn get_affected_blockplace(
         &self,
         sheet_id: &SheetId,
@@ -8,6 +8,7 @@ mod insert_line;
 mod occupy_addr_range;
 mod remove_block;
 mod resize_block;
+mod shift_cells;
 mod utils;
 use std::collections::HashSet;
 
@@ -21,9 +22,11 @@ use insert_line::insert_line;
 use logisheets_base::{BlockRange, NormalRange, Range, RangeId, SheetId, errors::BasicError};
 use remove_block::remove_block;
 use resize_block::resize_block;
+use shift_cells::shift_cells;
 
 use crate::{
-    Error, edit_action::EditPayload, range_manager::executors::occupy_addr_range::occupy_addr_range,
+    Error, cell_shift::CellShift, edit_action::EditPayload,
+    range_manager::executors::occupy_addr_range::occupy_addr_range,
 };
 
 use super::{ctx::RangeExecCtx, manager::RangeManager};
@@ -311,6 +314,18 @@ impl RangeExecutor {
                 );
                 Ok(result)
             }
+            EditPayload::InsertCells(p) => {
+                let sheet_id = ctx
+                    .fetch_sheet_id_by_index(p.sheet_idx)
+                    .map_err(BasicError::SheetIdxExceed)?;
+                Ok(shift_cells(self, sheet_id, &CellShift::from(&p), ctx))
+            }
+            EditPayload::DeleteCells(p) => {
+                let sheet_id = ctx
+                    .fetch_sheet_id_by_index(p.sheet_idx)
+                    .map_err(BasicError::SheetIdxExceed)?;
+                Ok(shift_cells(self, sheet_id, &CellShift::from(&p), ctx))
+            }
             EditPayload::InsertColsInBlock(p) => {
                 let sheet_id = ctx
                     .fetch_sheet_id_by_index(p.sheet_idx)
@@ -406,12 +421,18 @@ impl RangeExecutor {
                     to_convert.insert((normal_range, block_range));
                 }
             });
+        // Unhook every old definition before adding any new one: an update
+        // may move a range onto the old definition of another one that moves
+        // on too, as when a column of cells shifts down.
+        to_update.iter().for_each(|new_range| {
+            if let Some(old_range) = manager.id_to_normal_range.get(&new_range.id)
+                && manager.normal_range_to_id.get(old_range) == Some(&new_range.id)
+            {
+                manager.normal_range_to_id.remove(old_range);
+            }
+        });
         to_update.into_iter().for_each(|new_range| {
             if let Range::Normal(range) = new_range.range {
-                let old_range = manager.id_to_normal_range.get(&new_range.id);
-                if old_range.is_some() {
-                    manager.normal_range_to_id.remove(old_range.unwrap());
-                }
                 manager
                     .id_to_normal_range
                     .insert(new_range.id, range.clone());
@@ -0,0 +1,90 @@
+use logisheets_base::{NormalRange, Range, RangeId, SheetId};
+
+use super::{NewRange, RangeExecCtx, RangeExecutor, RangeUpdateType};
+use crate::cell_shift::{CellMove, CellShift, RectMove};
+
+// Cells change places while every row and column keeps its id, so the ranges
+// are re-anchored on the cells they end up naming. A formula's own cell is a
+// single-cell range too: re-anchoring it is what moves the formula's vertex.
+pub fn shift_cells<C>(
+    exec_ctx: RangeExecutor,
+    sheet: SheetId,
+    shift: &CellShift,
+    ctx: &C,
+) -> RangeExecutor
+where
+    C: RangeExecCtx,
+{
+    let mut func = |range: &NormalRange, range_id: &RangeId| -> RangeUpdateType {
+        let update_to = |range: NormalRange| {
+            RangeUpdateType::UpdateTo(NewRange {
+                id: *range_id,
+                range: Range::Normal(range),
+            })
+        };
+        match range {
+            NormalRange::Single(cell) => {
+                let Ok((row, col)) = ctx.fetch_normal_cell_index(&sheet, cell) else {
+                    return RangeUpdateType::None;
+                };
+                match shift.move_cell(row, col) {
+                    CellMove::Stay => RangeUpdateType::None,
+                    CellMove::Deleted => RangeUpdateType::Removed,
+                    CellMove::To(row, col) => match ctx.fetch_norm_cell_id(&sheet, row, col) {
+                        Ok(cell) => update_to(NormalRange::Single(cell)),
+                        Err(_) => RangeUpdateType::Removed,
+                    },
+                }
+            }
+            NormalRange::AddrRange(start, end) => {
+                let Ok((start_row, start_col)) = ctx.fetch_normal_cell_index(&sheet, start) else {
+                    return RangeUpdateType::None;
+                };
+                let Ok((end_row, end_col)) = ctx.fetch_normal_cell_index(&sheet, end) else {
+                    return RangeUpdateType::None;
+                };
+                match shift.move_rect(start_row, start_col, end_row, end_col) {
+                    RectMove::Stay => RangeUpdateType::None,
+                    RectMove::Dirty => RangeUpdateType::Dirty,
+                    RectMove::Deleted => RangeUpdateType::Removed,
+                    RectMove::To(r1, c1, r2, c2) => match (
+                        ctx.fetch_norm_cell_id(&sheet, r1, c1),
+                        ctx.fetch_norm_cell_id(&sheet, r2, c2),
+                    ) {
+                        (Ok(s), Ok(e)) => update_to(NormalRange::AddrRange(s, e)),
+                        _ => RangeUpdateType::Removed,
+                    },
+                }
+            }
+            // Whole rows and columns keep their extent; only what is in them
+            // changes.
+            NormalRange::RowRange(start, end) => {
+                let (Ok(start), Ok(end)) = (
+                    ctx.fetch_row_index(&sheet, start),
+                    ctx.fetch_row_index(&sheet, end),
+                ) else {
+                    return RangeUpdateType::None;
+                };
+                if shift.touches(start, 0, end, usize::MAX) {
+                    RangeUpdateType::Dirty
+                } else {
+                    RangeUpdateType::None
+                }
+            }
+            NormalRange::ColRange(start, end) => {
+                let (Ok(start), Ok(end)) = (
+                    ctx.fetch_col_index(&sheet, start),
+                    ctx.fetch_col_index(&sheet, end),
+                ) else {
+                    return RangeUpdateType::None;
+                };
+                if shift.touches(0, start, usize::MAX, end) {
+                    RangeUpdateType::Dirty
+                } else {
+                    RangeUpdateType::None
+                }
+            }
+        }
+    };
+    exec_ctx.normal_range_update(&sheet, &mut func)
+}
@@ -0,0 +1,213 @@
+//! Geometry of moving a range of cells (`MoveRange`).
+//!
+//! Like a cell shift, a move keeps the navigator as it is: the moved cells
+//! take the ids of the cells at their destination, which may be on another
+//! sheet. Whatever sat at the destination is overwritten, so it is reported
+//! as deleted. The moves are expressed with the [`CellMove`] and [`RectMove`]
+//! of [`crate::cell_shift`].
+//!
+//! All positions are 0-based and inclusive.
+
+use logisheets_base::SheetId;
+
+use crate::cell_shift::{CellMove, RectMove};
+use crate::edit_action::MoveRange;
+use crate::navigator::sheet_nav::{MAX_COL_CNT, MAX_ROW_CNT};
+
+#[derive(Debug, Clone, Copy)]
+pub struct RangeMove {
+    pub from: SheetId,
+    pub to: SheetId,
+    pub start_row: usize,
+    pub start_col: usize,
+    pub end_row: usize,
+    pub end_col: usize,
+    pub dest_row: usize,
+    pub dest_col: usize,
+}
+
+impl RangeMove {
+    pub fn new(from: SheetId, to: SheetId, p: &MoveRange) -> Self {
+        RangeMove {
+            from,
+            to,
+            start_row: p.start_row,
+            start_col: p.start_col,
+            end_row: p.end_row,
+            end_col: p.end_col,
+            dest_row: p.dest_row,
+            dest_col: p.dest_col,
+        }
+    }
+
+    pub fn is_valid(&self) -> bool {
+        if self.start_row > self.end_row || self.start_col > self.end_col {
+            return false;
+        }
+        let dest_end_row = self.dest_row.checked_add(self.end_row - self.start_row);
+        let dest_end_col = self.dest_col.checked_add(self.end_col - self.start_col);
+        matches!(dest_end_row, Some(r) if r < MAX_ROW_CNT as usize)
+            && matches!(dest_end_col, Some(c) if c < MAX_COL_CNT as usize)
+    }
+
+    fn dest_end(&self) -> (usize, usize) {
+        (
+            self.dest_row + self.end_row - self.start_row,
+            self.dest_col + self.end_col - self.start_col,
+        )
+    }
+
+    fn src(&self) -> (usize, usize, usize, usize) {
+        (self.start_row, self.start_col, self.end_row, self.end_col)
+    }
+
+    fn dest(&self) -> (usize, usize, usize, usize) {
+        let (end_row, end_col) = self.dest_end();
+        (self.dest_row, self.dest_col, end_row, end_col)
+    }
+
+    /// Where the cell at `row`, `col` of `sheet` goes: a moved cell lands on
+    /// the destination sheet, an overwritten one is deleted.
+    pub fn move_cell(&self, sheet: SheetId, row: usize, col: usize) -> CellMove {
+        if sheet == self.from && within((row, col, row, col), self.src()) {
+            CellMove::To(
+                self.dest_row + row - self.start_row,
+                self.dest_col + col - self.start_col,
+            )
+        } else if sheet == self.to && within((row, col, row, col), self.dest()) {
+            CellMove::Deleted
+        } else {
+            CellMove::Stay
+        }
+    }
+
+    /// Whether the rectangle reaches into the moved or the overwritten cells.
+    pub fn touches(
+        &self,
+        sheet: SheetId,
+        start_row: usize,
+        start_col: usize,
+        end_row: usize,
+        end_col: usize,
+    ) -> bool {
+        let rect = (start_row, start_col, end_row, end_col);
+        (sheet == self.from && meets(rect, self.src()))
+            || (sheet == self.to && meets(rect, self.dest()))
+    }
+
+    /// How a reference to a rectangle is rewritten. It follows the cells when
+    /// it lies wholly in the moved range and is deleted when it lies wholly in
+    /// the overwritten cells, as in Excel. Any other rectangle touched by the
+    /// move keeps its place.
+    pub fn move_rect(
+        &self,
+        sheet: SheetId,
+        start_row: usize,
+        start_col: usize,
+        end_row: usize,
+        end_col: usize,
+    ) -> RectMove {
+        let rect = (start_row, start_col, end_row, end_col);
+        let in_src = sheet == self.from && within(rect, self.src());
+        if in_src {
+            let (r1, c1) = (
+                self.dest_row + start_row - self.start_row,
+                self.dest_col + start_col - self.start_col,
+            );
+            let (r2, c2) = (
+                self.dest_row + end_row - self.start_row,
+                self.dest_col + end_col - self.start_col,
+            );
+            return RectMove::To(r1, c1, r2, c2);
+        }
+        let meets_src = sheet == self.from && meets(rect, self.src());
+        if sheet == self.to && within(rect, self.dest()) && !meets_src {
+            return RectMove::Deleted;
+        }
+        if self.touches(sheet, start_row, start_col, end_row, end_col) {
+            RectMove::Dirty
+        } else {
+            RectMove::Stay
+        }
+    }
+
+    /// Whether the move would tear a merge apart: it is partly moved, or
+    /// partly overwritten without moving itself.
+    pub fn tears(
+        &self,
+        sheet: SheetId,
+        start_row: usize,
+        start_col: usize,
+        end_row: usize,
+        end_col: usize,
+    ) -> bool {
+        match self.move_rect(sheet, start_row, start_col, end_row, end_col) {
+            RectMove::Stay | RectMove::To(..) | RectMove::Deleted => false,
+            RectMove::Dirty => true,
+        }
+    }
+}
+
+fn within(inner: (usize, usize, usize, usize), outer: (usize, usize, usize, usize)) -> bool {
+    inner.0 >= outer.0 && inner.1 >= outer.1 && inner.2 <= outer.2 && inner.3 <= outer.3
+}
+
+fn meets(a: (usize, usize, usize, usize), b: (usize, usize, usize, usize)) -> bool {
+    a.0 <= b.2 && b.0 <= a.2 && a.1 <= b.3 && b.1 <= a.3
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // B2:C3 to D3 on the same sheet, so C3 is both moved and overwritten.
+    fn mv() -> RangeMove {
+        RangeMove {
+            from: 0,
+            to: 0,
+            start_row: 1,
+            start_col: 1,
+            end_row: 2,
+            end_col: 2,
+            dest_row: 2,
+            dest_col: 3,
+        }
+    }
+
+    #[test]
+    fn cells_move_or_are_overwritten() {
+        let m = mv();
+        assert_eq!(m.move_cell(0, 1, 1), CellMove::To(2, 3));
+        assert_eq!(m.move_cell(0, 2, 2), CellMove::To(3, 4));
+        assert_eq!(m.move_cell(0, 3, 3), CellMove::Deleted);
+        assert_eq!(m.move_cell(0, 0, 0), CellMove::Stay);
+        assert_eq!(m.move_cell(1, 1, 1), CellMove::Stay);
+    }
+
+    #[test]
+    fn references_follow_or_are_cut() {
+        let m = mv();
+        assert_eq!(m.move_rect(0, 1, 1, 2, 1), RectMove::To(2, 3, 3, 3));
+        assert_eq!(m.move_rect(0, 3, 4, 3, 4), RectMove::Deleted);
+        assert_eq!(m.move_rect(0, 2, 2, 3, 3), RectMove::Dirty);
+        assert_eq!(m.move_rect(0, 0, 0, usize::MAX, 0), RectMove::Stay);
+        assert_eq!(m.move_rect(0, 0, 0, usize::MAX, 1), RectMove::Dirty);
+        assert!(m.tears(0, 0, 1, 1, 1));
+        assert!(!m.tears(0, 3, 3, 3, 4));
+    }
+
+    #[test]
+    fn reversed_or_oversized_moves_are_invalid() {
+        assert!(mv().is_valid());
+        let reversed = RangeMove {
+            start_row: 3,
+            ..mv()
+        };
+        assert!(!reversed.is_valid());
+        let far = RangeMove {
+            dest_col: usize::MAX,
+            ..mv()
+        };
+        assert!(!far.is_valid());
+    }
+}
@@ -0,0 +1,127 @@
+//! Applies the payloads that add, edit and delete scenarios. Showing one and
+//! the summary report write cells, so the controller's executor runs those.
+
+use imbl::Vector;
+use logisheets_base::SheetId;
+use logisheets_base::errors::BasicError;
+
+use super::{ChangingCell, MAX_CHANGING_CELLS, Scenario, ScenarioManager};
+use crate::Error;
+use crate::edit_action::{EditPayload, ScenarioValue};
+use crate::navigator::Navigator;
+use crate::workbook::sheet_info_manager::SheetInfoManager;
+
+pub struct ScenarioExecutor {
+    pub manager: ScenarioManager,
+}
+
+impl ScenarioExecutor {
+    pub fn new(manager: ScenarioManager) -> Self {
+        Self { manager }
+    }
+
+    /// Returns `(self, changed)`; `changed` is `false` for payloads this
+    /// executor does not handle.
+    pub fn execute(
+        mut self,
+        nav: &Navigator,
+        sheet_info: &SheetInfoManager,
+        payload: EditPayload,
+    ) -> Result<(Self, bool), Error> {
+        let sheet_id = |idx: usize| {
+            sheet_info
+                .get_sheet_id(idx)
+                .ok_or(Error::Basic(BasicError::SheetIdxExceed(idx)))
+        };
+        match payload {
+            EditPayload::AddScenario(p) => {
+                let refuse = |msg: String| Error::PayloadError(format!("AddScenario: {}", msg));
+                let sheet_id = sheet_id(p.sheet_idx)?;
+                let name = self.free_name(sheet_id, &p.name).map_err(refuse)?;
+                let cells = changing_cells(nav, sheet_id, p.cells).map_err(refuse)?;
+                let scenario = Scenario {
+                    name,
+                    cells,
+                    comment: p.comment.filter(|c| !c.is_empty()),
+                    user: None,
+                    locked: false,
+                    hidden: false,
+                };
+                self.manager.add(sheet_id, scenario);
+                Ok((self, true))
+            }
+            EditPayload::EditScenario(p) => {
+                let refuse = |msg: String| Error::PayloadError(format!("EditScenario: {}", msg));
+                let sheet_id = sheet_id(p.sheet_idx)?;
+                let Some(mut scenario) = self.manager.find(sheet_id, &p.name).cloned() else {
+                    return Err(refuse(format!("no scenario called {:?}", p.name)));
+                };
+                if let Some(new_name) = p.new_name.filter(|n| n.trim() != p.name) {
+                    scenario.name = self.free_name(sheet_id, &new_name).map_err(refuse)?;
+                }
+                if let Some(cells) = p.cells {
+                    scenario.cells = changing_cells(nav, sheet_id, cells).map_err(refuse)?;
+                }
+                if let Some(comment) = p.comment {
+                    scenario.comment = Some(comment).filter(|c| !c.is_empty());
+                }
+                self.manager.replace(sheet_id, &p.name, scenario);
+                Ok((self, true))
+            }
+            EditPayload::DeleteScenario(p) => {
+                let sheet_id = sheet_id(p.sheet_idx)?;
+                if self.manager.remove(sheet_id, &p.name).is_none() {
+                    return Err(Error::PayloadError(format!(
+                        "DeleteScenario: no scenario called {:?}",
+                        p.name
+                    )));
+                }
+                Ok((self, true))
+            }
+            _ => Ok((self, false)),
+        }
+    }
+
+    /// `name` trimmed, if no other scenario on the sheet has it.
+    fn free_name(&self, sheet_id: SheetId, name: &str) -> Result<String, String> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err("a scenario needs a name".to_string());
+        }
+        if self.manager.find(sheet_id, name).is_some() {
+            return Err(format!("{:?} is already a scenario on this sheet", name));
+        }
+        Ok(name.to_string())
+    }
+}
+
+fn changing_cells(
+    nav: &Navigator,
+    sheet_id: SheetId,
+    values: Vec<ScenarioValue>,
+) -> Result<Vector<ChangingCell>, String> {
+    if values.is_empty() {
+        return Err("a scenario needs at least one changing cell".to_string());
+    }
+    if values.len() > MAX_CHANGING_CELLS {
+        return Err(format!(
+            "a scenario can change at most {} cells",
+            MAX_CHANGING_CELLS
+        ));
+    }
+    let mut cells = Vector::new();
+    for v in values {
+        let cell_id = nav
+            .fetch_cell_id(&sheet_id, v.row, v.col)
+            .map_err(|e| e.to_string())?;
+        if cells.iter().any(|c: &ChangingCell| c.cell_id == cell_id) {
+            return Err("a changing cell is given twice".to_string());
+        }
+        cells.push_back(ChangingCell {
+            cell_id,
+            value: v.value,
+            num_fmt_id: None,
+        });
+    }
+    Ok(cells)
+}
@@ -0,0 +1,142 @@
+//! Models Excel scenarios (`<scenarios>`, the What-If Scenario Manager).
+//!
+//! A scenario is a named set of changing cells, each with the value it puts
+//! there. Showing one is typing those values into the cells, which is the
+//! controller's `Executor::execute_show_scenario`. A summary report shows
+//! each scenario on a scratch copy of the status and writes what the result
+//! cells come to on a new sheet.
+//!
+//! Changing cells are anchored on cell ids, so a scenario follows row and
+//! column edits. A changing cell whose row or column was deleted drops out
+//! of the scenario wherever it is read.
+
+pub(crate) mod executor;
+
+use imbl::{HashMap, Vector};
+use logisheets_base::{CellId, SheetId};
+
+/// Excel refuses a scenario with more changing cells than this.
+pub const MAX_CHANGING_CELLS: usize = 32;
+
+#[derive(Debug, Clone)]
+pub struct Scenario {
+    /// Unique on its sheet.
+    pub name: String,
+    pub cells: Vector<ChangingCell>,
+    pub comment: Option<String>,
+    /// Who added the scenario. Kept from the file; a new one has none.
+    pub user: Option<String>,
+    /// Excel's "Prevent changes" and "Hide", which apply only while the sheet
+    /// is protected. Kept from the file.
+    pub locked: bool,
+    pub hidden: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct ChangingCell {
+    pub cell_id: CellId,
+    /// As it is typed when the scenario is shown, e.g. `12` or `TRUE`.
+    pub value: String,
+    /// The number format Excel recorded with the value.
+    pub num_fmt_id: Option<u32>,
+}
+
+/// A sheet's scenarios, in the order they were added.
+#[derive(Debug, Clone, Default)]
+pub struct SheetScenarios {
+    pub scenarios: Vector<Scenario>,
+    /// The scenario last shown, by name.
+    pub shown: Option<String>,
+    /// The scenario selected in Excel's dialog, by name.
+    pub current: Option<String>,
+    /// The result cells of the last summary report.
+    pub result_cells: Vector<CellId>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ScenarioManager {
+    data: HashMap<SheetId, SheetScenarios>,
+}
+
+impl ScenarioManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get_sheet(&self, sheet_id: SheetId) -> Option<&SheetScenarios> {
+        self.data.get(&sheet_id)
+    }
+
+    pub fn set_sheet(&mut self, sheet_id: SheetId, scenarios: SheetScenarios) {
+        self.data.insert(sheet_id, scenarios);
+    }
+
+    pub fn scenarios_of_sheet(&self, sheet_id: SheetId) -> impl Iterator<Item = &Scenario> {
+        self.data
+            .get(&sheet_id)
+            .into_iter()
+            .flat_map(|s| s.scenarios.iter())
+    }
+
+    pub fn find(&self, sheet_id: SheetId, name: &str) -> Option<&Scenario> {
+        self.scenarios_of_sheet(sheet_id).find(|s| s.name == name)
+    }
+
+    pub fn add(&mut self, sheet_id: SheetId, scenario: Scenario) {
+        self.data
+            .entry(sheet_id)
+            .or_default()
+            .scenarios
+            .push_back(scenario);
+    }
+
+    /// Put `scenario` in the place of the one called `name`, which it may
+    /// rename. Returns `false` if there is no such scenario.
+    pub fn replace(&mut self, sheet_id: SheetId, name: &str, scenario: Scenario) -> bool {
+        let Some(sheet) = self.data.get_mut(&sheet_id) else {
+            return false;
+        };
+        let Some(idx) = sheet.scenarios.iter().position(|s| s.name == name) else {
+            return false;
+        };
+        for selected in [&mut sheet.shown, &mut sheet.current] {
+            if selected.as_deref() == Some(name) {
+                *selected = Some(scenario.name.clone());
+            }
+        }
+        sheet.scenarios.set(idx, scenario);
+        true
+    }
+
+    /// Returns the scenario removed, if there was one called `name`.
+    pub fn remove(&mut self, sheet_id: SheetId, name: &str) -> Option<Scenario> {
+        let sheet = self.data.get_mut(&sheet_id)?;
+        let idx = sheet.scenarios.iter().position(|s| s.name == name)?;
+        for selected in [&mut sheet.shown, &mut sheet.current] {
+            if selected.as_deref() == Some(name) {
+                *selected = None;
+            }
+        }
+        Some(sheet.scenarios.remove(idx))
+    }
+
+    /// Record that the scenario called `name` was shown. Showing one also
+    /// selects it, as in Excel.
+    pub fn set_shown(&mut self, sheet_id: SheetId, name: &str) {
+        let sheet = self.data.entry(sheet_id).or_default();
+        sheet.shown = Some(name.to_string());
+        sheet.current = Some(name.to_string());
+    }
+
+    pub fn set_result_cells(&mut self, sheet_id: SheetId, cells: Vector<CellId>) {
+        self.data.entry(sheet_id).or_default().result_cells = cells;
+    }
+
+    /// Copy `from`'s scenarios onto `to`. A duplicated sheet keeps its cell
+    /// ids, so the changing cells carry over as they are.
+    pub fn duplicate_sheet(&mut self, from: SheetId, to: SheetId) {
+        if let Some(sheet) = self.data.get(&from).cloned() {
+            self.data.insert(to, sheet);
+        }
+    }
+}
@@ -1,6 +1,8 @@
 use std::collections::{HashMap, HashSet};
 
+use gents_derives::TS;
 use logisheets_base::SheetId;
+use logisheets_workbook::logisheets::InputLocaleXml;
 use logisheets_workbook::prelude::{
     CtAutoFilter, CtCellWatches, CtConditionalFormatting, CtControls, CtCustomProperties,
     CtCustomSheetViews, CtDataConsolidate, CtHeaderFooter, CtHyperlinks, CtIgnoredErrors,
@@ -9,8 +11,6 @@ use logisheets_workbook::prelude::{
     CtSortState, CtTableParts, CtWebPublishItems,
 };
 
-use crate::theme_manager::ThemeManager;
-
 /// Worksheet-level OOXML features the controller does not model but preserves
 /// verbatim across open→save, so they aren't silently dropped (previously the
 /// save path hardcoded these to `None`/empty). Captured from the parsed
@@ -51,7 +51,9 @@ pub struct Settings {
     pub preserved_parts: HashMap<SheetId, PreservedWorksheetParts>,
     pub calc_config: CalcConfig,
     pub async_funcs: HashSet<String>, // function names in upper case.
-    pub theme: ThemeManager,
+    pub input_locale: InputLocale,
+    /// Fill lists of the workbook's own, such as `North, East, South, West`.
+    pub custom_lists: Vec<Vec<String>>,
 }
 
 impl Default for Settings {
@@ -66,7 +68,8 @@ impl Default for Settings {
             sheet_views,
             preserved_parts: HashMap::new(),
             async_funcs: afuncs.into_iter().collect(),
-            theme: ThemeManager::default(),
+            input_locale: InputLocale::default(),
+            custom_lists: Vec::new(),
         }
     }
 }
@@ -85,3 +88,124 @@ impl Default for CalcConfig {
         }
     }
 }
+
+/// How typed cell input is read: the order of the parts of a date and the
+/// separators and currency symbol the user types. Defaults to en-US.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InputLocale {
+    pub date_order: DateOrder,
+    pub date_separator: char,
+    pub decimal_separator: char,
+    pub thousands_separator: char,
+    pub currency_symbol: String,
+}
+
+impl Default for InputLocale {
+    fn default() -> Self {
+        InputLocale {
+            date_order: DateOrder::Mdy,
+            date_separator: '/',
+            decimal_separator: '.',
+            thousands_separator: ',',
+            currency_symbol: String::from("$"),
+        }
+    }
+}
+
+impl InputLocale {
+    /// `None` for the default locale, which the file leaves out.
+    pub(crate) fn to_xml(&self) -> Option<InputLocaleXml> {
+        if *self == InputLocale::default() {
+            return None;
+        }
+        let date_order = match self.date_order {
+            DateOrder::Mdy => "mdy",
+            DateOrder::Dmy => "dmy",
+            DateOrder::Ymd => "ymd",
+        };
+        Some(InputLocaleXml {
+            date_order: date_order.to_string(),
+            date_separator: self.date_separator.to_string(),
+            decimal_separator: self.decimal_separator.to_string(),
+            thousands_separator: self.thousands_separator.to_string(),
+            currency_symbol: self.currency_symbol.clone(),
+        })
+    }
+
+    /// A locale the file got wrong loads as the default.
+    pub(crate) fn from_xml(xml: InputLocaleXml) -> Self {
+        let date_order = match xml.date_order.as_str() {
+            "dmy" => DateOrder::Dmy,
+            "ymd" => DateOrder::Ymd,
+            _ => DateOrder::Mdy,
+        };
+        let info = InputLocaleInfo {
+            date_order,
+            date_separator: xml.date_separator,
+            decimal_separator: xml.decimal_separator,
+            thousands_separator: xml.thousands_separator,
+            currency_symbol: xml.currency_symbol,
+        };
+        InputLocale::try_from(info).unwrap_or_default()
+    }
+}
+
+/// `InputLocale` as the host reads and sets it, with the separators as text.
+#[derive(Debug, Clone, TS)]
+#[ts(file_name = "input_locale.ts", rename_all = "camelCase")]
+pub struct InputLocaleInfo {
+    pub date_order: DateOrder,
+    pub date_separator: String,
+    pub decimal_separator: String,
+    pub thousands_separator: String,
+    pub currency_symbol: String,
+}
+
+impl From<&InputLocale> for InputLocaleInfo {
+    fn from(locale: &InputLocale) -> Self {
+        InputLocaleInfo {
+            date_order: locale.date_order,
+            date_separator: locale.date_separator.to_string(),
+            decimal_separator: locale.decimal_separator.to_string(),
+            thousands_separator: locale.thousands_separator.to_string(),
+            currency_symbol: locale.currency_symbol.clone(),
+        }
+    }
+}
+
+impl TryFrom<InputLocaleInfo> for InputLocale {
+    type Error = String;
+
+    /// Each separator must be one character, and a number could not be read
+    /// if the decimal and thousands separators were the same.
+    fn try_from(info: InputLocaleInfo) -> Result<Self, String> {
+        let single = |what: &str, s: &str| {
+            let mut chars = s.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => Ok(c),
+                _ => Err(format!("the {} must be one character, not {:?}", what, s)),
+            }
+        };
+        let locale = InputLocale {
+            date_order: info.date_order,
+            date_separator: single("date separator", &info.date_separator)?,
+            decimal_separator: single("decimal separator", &info.decimal_separator)?,
+            thousands_separator: single("thousands separator", &info.thousands_separator)?,
+            currency_symbol: info.currency_symbol,
+        };
+        if locale.decimal_separator == locale.thousands_separator {
+            return Err("the decimal and thousands separators must differ".to_string());
+        }
+        Ok(locale)
+    }
+}
+
+/// The order of day, month and year in a date that does not start with a
+/// four-digit year.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, TS)]
+#[ts(file_name = "date_order.ts", rename_all = "camelCase")]
+pub enum DateOrder {
+    Mdy,
+    Dmy,
+    Ymd,
+}
@@ -5,4 +5,10 @@ use thiserror::Error;
 pub enum StyleError {
     #[error("cannot find the style id: {0}")]
     StyleIdNotFound(StyleId),
+    #[error("cannot find the cell style: {0}")]
+    NamedStyleNotFound(String),
+    #[error("a cell style named {0} already exists")]
+    NamedStyleExists(String),
+    #[error("the Normal cell style cannot be deleted")]
+    DeleteNormalStyle,
 }
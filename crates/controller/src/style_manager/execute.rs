@@ -11,15 +11,23 @@ pub fn execute_style_update(
     update_type: StyleUpdateType,
     id: StyleId,
 ) -> Result<StyleId, Error> {
+    let xf = sm
+        .cell_xfs_manager
+        .get_item(id)
+        .ok_or(StyleError::StyleIdNotFound(id))?
+        .clone();
+    let xf = update_xf(sm, xf, update_type);
+    let new_id = sm.cell_xfs_manager.get_id(&xf);
+    Ok(new_id)
+}
+
+/// `xf` with `update_type` applied, registering the fonts, borders, fills
+/// and number formats it needs.
+pub fn update_xf(sm: &mut StyleManager, mut xf: CtrlXf, update_type: StyleUpdateType) -> CtrlXf {
     let font_manager = &mut sm.font_manager;
     let border_manager = &mut sm.border_manager;
     let fill_manager = &mut sm.fill_manager;
-    let cell_xfs_manager = &mut sm.cell_xfs_manager;
     let num_fmt_manager = &mut sm.num_fmt_manager;
-    let mut xf = cell_xfs_manager
-        .get_item(id)
-        .ok_or(StyleError::StyleIdNotFound(id))?
-        .clone();
     if let Some(new_font_id) = font_manager.execute(xf.font_id.unwrap_or(0), &update_type) {
         xf.font_id = Some(new_font_id);
         xf.apply_font = Some(true);
@@ -79,8 +87,7 @@ pub fn execute_style_update(
         }
         xf.alignment = Some(result);
     }
-    let new_id = cell_xfs_manager.get_id(&xf);
-    Ok(new_id)
+    xf
 }
 
 pub fn insert_style(sm: &mut StyleManager, style: RawStyle) -> Result<StyleId, Error> {
@@ -106,6 +113,7 @@ pub fn insert_style(sm: &mut StyleManager, style: RawStyle) -> Result<StyleId, E
         apply_border: Some(true),
         apply_alignment: Some(true),
         apply_protection: None,
+        xf_id: None,
     };
     let new_id = sm.cell_xfs_manager.get_id(&xf);
     Ok(new_id)
@@ -18,6 +18,29 @@ impl Default for FontManager {
 }
 
 impl FontManager {
+    /// Rename the fonts of a theme font scheme still named `old`, after the
+    /// theme's font for that scheme changes to `new`. Fonts renamed by the
+    /// user, or named for another script, keep their name.
+    pub fn rename_scheme_fonts(&mut self, scheme: &StFontScheme, old: &str, new: &str) {
+        let renamed: Vec<(FontId, CtFont)> = self
+            .iter()
+            .filter(|(_, font)| {
+                font.scheme.as_ref().is_some_and(|s| &s.val == scheme)
+                    && font.name.as_ref().is_some_and(|n| n.val == old)
+            })
+            .map(|(id, font)| {
+                let mut font = font.clone();
+                font.name = Some(CtFontName {
+                    val: new.to_string(),
+                });
+                (*id, font)
+            })
+            .collect();
+        for (id, font) in renamed {
+            self.replace(id, font);
+        }
+    }
+
     pub fn execute(&mut self, id: FontId, update_type: &StyleUpdateType) -> Option<FontId> {
         let mut font = if let Some(font) = self.get_item(id) {
             font.clone()
@@ -39,6 +62,8 @@ impl FontManager {
         }
         if let Some(n) = &update_type.set_font_name {
             font.name = Some(CtFontName { val: n.clone() });
+            // A font picked by name no longer follows the theme's fonts.
+            font.scheme = None;
         }
         if let Some(o) = update_type.set_font_outline {
             font.outline = o;
@@ -77,6 +77,36 @@ where
             .collect()
     }
 
+    /// Register `data` under a new id even if it is already known. Lookups
+    /// by value keep finding the earlier id.
+    pub fn push(&mut self, data: T) -> I {
+        let r = self.next_available;
+        if !self.data_to_id.contains_key(&data) {
+            self.data_to_id.insert(data.clone(), r);
+        }
+        self.id_to_data.insert(r, data);
+        let one: I = NumCast::from(1usize).unwrap();
+        self.next_available += one;
+        r
+    }
+
+    /// Change the data behind `id` in place, so everything holding `id`
+    /// sees the new value.
+    pub fn replace(&mut self, id: I, data: T) {
+        if let Some(old) = self.id_to_data.insert(id, data.clone())
+            && self.data_to_id.get(&old) == Some(&id)
+        {
+            self.data_to_id.remove(&old);
+        }
+        if !self.data_to_id.contains_key(&data) {
+            self.data_to_id.insert(data, id);
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&I, &T)> {
+        self.id_to_data.iter()
+    }
+
     fn registry(&mut self, data: T) -> I {
         let r = self.next_available;
         self.data_to_id.insert(data.clone(), self.next_available);
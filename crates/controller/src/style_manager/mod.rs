@@ -6,10 +6,12 @@ mod execute;
 pub mod fill_manager;
 pub mod font_manager;
 mod manager;
+pub mod named_style_manager;
 pub mod num_fmt_manager;
 pub mod xf_manager;
 
-use execute::insert_style;
+use errors::StyleError;
+use execute::{insert_style, update_xf};
 use gents_derives::TS;
 
 use border_manager::BorderManager;
@@ -17,8 +19,9 @@ use dxf_manager::DxfManager;
 use fill_manager::FillManager;
 use font_manager::FontManager;
 use logisheets_base::StyleId;
+use named_style_manager::{NamedStyle, NamedStyleManager};
 use num_fmt_manager::NumFmtManager;
-use xf_manager::XfManager;
+use xf_manager::{CtrlXf, XfId, XfManager};
 
 use logisheets_workbook::prelude::{CtBorder, CtCellAlignment, CtCellProtection, CtFill, CtFont};
 
@@ -45,7 +48,9 @@ pub struct StyleManager {
     pub border_manager: BorderManager,
     pub fill_manager: FillManager,
     pub cell_xfs_manager: XfManager,
+    /// The formats of the named styles, one xf per style.
     pub cell_style_xfs_manager: XfManager,
+    pub named_styles: NamedStyleManager,
     pub num_fmt_manager: NumFmtManager,
     /// Differential formats (`<dxfs>`). Referenced by dxfId from conditional
     /// formatting and table styles; kept in file order (see `DxfManager`).
@@ -65,11 +70,131 @@ impl StyleManager {
         insert_style(self, style)
     }
 
+    /// Add a named style: "Normal" with `update` applied.
+    pub fn create_named_style(
+        &mut self,
+        name: String,
+        update: StyleUpdateType,
+    ) -> Result<(), Error> {
+        if self.named_styles.get(&name).is_some() {
+            return Err(StyleError::NamedStyleExists(name).into());
+        }
+        let base = self.normal_xf();
+        let xf = update_xf(self, base, update);
+        let xf_id = self.cell_style_xfs_manager.push(xf);
+        self.named_styles.add(NamedStyle {
+            name,
+            xf_id,
+            builtin_id: None,
+            i_level: None,
+            custom_builtin: None,
+        });
+        Ok(())
+    }
+
+    /// Apply `update` to a named style. Cells linked to the style follow
+    /// it, except in what they set for themselves.
+    pub fn modify_named_style(&mut self, name: &str, update: StyleUpdateType) -> Result<(), Error> {
+        let xf_id = self.named_style(name)?.xf_id;
+        let old = self.style_xf(xf_id);
+        let new = update_xf(self, old.clone(), update);
+        self.cell_style_xfs_manager.replace(xf_id, new.clone());
+        let linked: Vec<(StyleId, CtrlXf)> = self
+            .cell_xfs_manager
+            .iter()
+            .filter(|(_, xf)| self.linked_style_xf(xf) == Some(xf_id))
+            .map(|(id, xf)| (*id, xf.clone()))
+            .collect();
+        for (id, mut xf) in linked {
+            follow_style(&mut xf, &old, &new);
+            self.cell_xfs_manager.replace(id, xf);
+        }
+        Ok(())
+    }
+
+    /// Remove a named style. Its cells keep their format and are linked to
+    /// "Normal" instead.
+    pub fn delete_named_style(&mut self, name: &str) -> Result<(), Error> {
+        let style = self.named_style(name)?;
+        if style.builtin_id == Some(0) {
+            return Err(StyleError::DeleteNormalStyle.into());
+        }
+        let xf_id = style.xf_id;
+        self.named_styles.remove(name);
+        let normal = self.named_styles.normal().map(|s| s.xf_id);
+        let linked: Vec<(StyleId, CtrlXf)> = self
+            .cell_xfs_manager
+            .iter()
+            .filter(|(_, xf)| self.linked_style_xf(xf) == Some(xf_id))
+            .map(|(id, xf)| (*id, xf.clone()))
+            .collect();
+        for (id, mut xf) in linked {
+            xf.xf_id = normal;
+            self.cell_xfs_manager.replace(id, xf);
+        }
+        Ok(())
+    }
+
+    /// The cell style id of a cell formatted with the named style, and
+    /// linked to it.
+    pub fn named_style_id(&mut self, name: &str) -> Result<StyleId, Error> {
+        let xf_id = self.named_style(name)?.xf_id;
+        let xf = CtrlXf {
+            xf_id: Some(xf_id),
+            ..self.style_xf(xf_id)
+        };
+        Ok(self.cell_xfs_manager.get_id(&xf))
+    }
+
+    /// The name of the named style the cell style `id` is linked to.
+    pub fn get_named_style_name(&self, id: StyleId) -> Option<&str> {
+        let xf_id = self.linked_style_xf(self.cell_xfs_manager.get_item(id)?)?;
+        self.named_styles
+            .get_by_xf_id(xf_id)
+            .map(|s| s.name.as_str())
+    }
+
+    /// The format of the named style as a [`RawStyle`].
+    pub fn get_named_style(&self, name: &str) -> Option<RawStyle> {
+        let xf = self.style_xf(self.named_styles.get(name)?.xf_id);
+        Some(self.get_raw_style_by_xf(&xf))
+    }
+
+    fn named_style(&self, name: &str) -> Result<&NamedStyle, Error> {
+        self.named_styles
+            .get(name)
+            .ok_or_else(|| StyleError::NamedStyleNotFound(name.to_string()).into())
+    }
+
+    /// A cell xf without `xfId` is linked to "Normal", as in Excel.
+    fn linked_style_xf(&self, xf: &CtrlXf) -> Option<XfId> {
+        xf.xf_id
+            .or_else(|| self.named_styles.normal().map(|s| s.xf_id))
+    }
+
+    fn style_xf(&self, xf_id: XfId) -> CtrlXf {
+        self.cell_style_xfs_manager
+            .get_item(xf_id)
+            .cloned()
+            .unwrap_or_else(CtrlXf::init)
+    }
+
+    fn normal_xf(&self) -> CtrlXf {
+        match self.named_styles.normal() {
+            Some(s) => self.style_xf(s.xf_id),
+            None => CtrlXf::init(),
+        }
+    }
+
     pub fn get_style(&self, id: StyleId) -> RawStyle {
         let xf = self
             .cell_xfs_manager
             .get_item(id)
             .unwrap_or(self.cell_xfs_manager.get_item(0).unwrap());
+        self.get_raw_style_by_xf(xf)
+    }
+
+    fn get_raw_style_by_xf(&self, xf: &CtrlXf) -> RawStyle {
         let font_id = xf.font_id.unwrap_or(0);
         let font = self
             .font_manager
@@ -102,3 +227,26 @@ impl StyleManager {
         }
     }
 }
+
+/// Take over the parts of a linked cell's format that still match the old
+/// style; the parts the cell changed for itself stay.
+fn follow_style(xf: &mut CtrlXf, old: &CtrlXf, new: &CtrlXf) {
+    if xf.font_id == old.font_id {
+        xf.font_id = new.font_id;
+    }
+    if xf.fill_id == old.fill_id {
+        xf.fill_id = new.fill_id;
+    }
+    if xf.border_id == old.border_id {
+        xf.border_id = new.border_id;
+    }
+    if xf.num_fmt_id == old.num_fmt_id {
+        xf.num_fmt_id = new.num_fmt_id;
+    }
+    if xf.alignment == old.alignment {
+        xf.alignment = new.alignment.clone();
+    }
+    if xf.protection == old.protection {
+        xf.protection = new.protection.clone();
+    }
+}
@@ -0,0 +1,117 @@
+use logisheets_workbook::prelude::{CtCellStyle, CtCellStyles};
+
+use super::xf_manager::XfId;
+
+/// A named cell style (`<cellStyle>`) such as "Normal", "Good" or
+/// "Heading 1". Its format is the cell-style xf `xf_id`, which no other
+/// named style shares, so the style can be modified in place.
+#[derive(Debug, Clone)]
+pub struct NamedStyle {
+    pub name: String,
+    pub xf_id: XfId,
+    /// Set for Excel's built-in styles; 0 is "Normal".
+    pub builtin_id: Option<u32>,
+    pub i_level: Option<u32>,
+    pub custom_builtin: Option<bool>,
+}
+
+/// The workbook's named cell styles, in file order. Names are unique,
+/// ignoring case, as in Excel.
+#[derive(Debug, Clone)]
+pub struct NamedStyleManager {
+    styles: Vec<NamedStyle>,
+}
+
+impl Default for NamedStyleManager {
+    fn default() -> Self {
+        NamedStyleManager {
+            styles: vec![NamedStyle {
+                name: String::from("Normal"),
+                xf_id: 0,
+                builtin_id: Some(0),
+                i_level: None,
+                custom_builtin: None,
+            }],
+        }
+    }
+}
+
+impl NamedStyleManager {
+    pub fn get(&self, name: &str) -> Option<&NamedStyle> {
+        self.styles
+            .iter()
+            .find(|s| s.name.eq_ignore_ascii_case(name))
+    }
+
+    pub fn get_by_xf_id(&self, xf_id: XfId) -> Option<&NamedStyle> {
+        self.styles.iter().find(|s| s.xf_id == xf_id)
+    }
+
+    /// The "Normal" style every other style and unstyled cell falls back
+    /// to.
+    pub fn normal(&self) -> Option<&NamedStyle> {
+        self.styles.iter().find(|s| s.builtin_id == Some(0))
+    }
+
+    pub fn styles(&self) -> &[NamedStyle] {
+        &self.styles
+    }
+
+    pub fn add(&mut self, style: NamedStyle) {
+        self.styles.push(style);
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<NamedStyle> {
+        let idx = self
+            .styles
+            .iter()
+            .position(|s| s.name.eq_ignore_ascii_case(name))?;
+        Some(self.styles.remove(idx))
+    }
+
+    /// Load `<cellStyles>`. `xf_count` is the number of cell-style xfs
+    /// loaded; styles pointing past them are dropped.
+    pub fn from_ct_cell_styles(ct: &CtCellStyles, xf_count: u32) -> Self {
+        let styles: Vec<NamedStyle> = ct
+            .cell_styles
+            .iter()
+            .filter(|s| s.xf_id < xf_count)
+            .enumerate()
+            .map(|(i, s)| NamedStyle {
+                // A nameless style can't be looked up, so give it one.
+                name: s.name.clone().unwrap_or_else(|| format!("Style {}", i + 1)),
+                xf_id: s.xf_id,
+                builtin_id: s.builtin_id,
+                i_level: s.i_level,
+                custom_builtin: s.custom_builtin,
+            })
+            .collect();
+        let mut manager = NamedStyleManager { styles };
+        if manager.normal().is_none() && xf_count > 0 {
+            manager
+                .styles
+                .insert(0, NamedStyleManager::default().styles[0].clone());
+        }
+        manager
+    }
+
+    pub fn to_ct_cell_styles(&self) -> Option<CtCellStyles> {
+        let cell_styles: Vec<CtCellStyle> = self
+            .styles
+            .iter()
+            .map(|s| CtCellStyle {
+                name: Some(s.name.clone()),
+                xf_id: s.xf_id,
+                builtin_id: s.builtin_id,
+                i_level: s.i_level,
+                custom_builtin: s.custom_builtin,
+            })
+            .collect();
+        let count = cell_styles.len() as u32;
+        if count > 0 {
+            Some(CtCellStyles { count, cell_styles })
+        } else {
+            None
+        }
+    }
+}
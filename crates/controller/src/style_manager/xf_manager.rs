@@ -20,6 +20,9 @@ pub struct CtrlXf {
     pub apply_border: Option<bool>,
     pub apply_alignment: Option<bool>,
     pub apply_protection: Option<bool>,
+    /// The cell-style xf of the named style a cell xf is linked to. Always
+    /// `None` for the cell-style xfs themselves.
+    pub xf_id: Option<XfId>,
 }
 
 impl CtrlXf {
@@ -37,6 +40,7 @@ impl CtrlXf {
             apply_border: None,
             apply_alignment: None,
             apply_protection: None,
+            xf_id: None,
         }
     }
 
@@ -50,7 +54,7 @@ impl CtrlXf {
             font_id: self.font_id,
             fill_id: self.fill_id,
             border_id: self.border_id,
-            xf_id: None,
+            xf_id: self.xf_id,
             quote_prefix: false,
             pivot_button: false,
             apply_number_format: self.apply_number_format,
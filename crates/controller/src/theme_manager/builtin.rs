@@ -0,0 +1,100 @@
+use logisheets_workbook::prelude::{CtSrgbColor, CtSystemColor, EgColorChoice, ThemePart};
+
+/// The theme every built-in one is made from: its format scheme (fills,
+/// lines, effects) and its fonts for other scripts are kept, its colours and
+/// latin fonts are replaced.
+const TEMPLATE: &str = include_str!("office.xml");
+
+/// An Office theme shipped with the engine.
+pub struct BuiltinTheme {
+    pub name: &'static str,
+    /// `RRGGBB`, in `clrScheme` order: dk1, lt1, dk2, lt2, accent1..6,
+    /// hlink, folHlink.
+    pub colors: [&'static str; 12],
+    pub major_font: &'static str,
+    pub minor_font: &'static str,
+}
+
+pub const BUILTIN_THEMES: &[BuiltinTheme] = &[
+    BuiltinTheme {
+        name: "Office",
+        colors: [
+            "000000", "FFFFFF", "44546A", "E7E6E6", "4472C4", "ED7D31", "A5A5A5", "FFC000",
+            "5B9BD5", "70AD47", "0563C1", "954F72",
+        ],
+        major_font: "Calibri Light",
+        minor_font: "Calibri",
+    },
+    BuiltinTheme {
+        name: "Office 2007 - 2010",
+        colors: [
+            "000000", "FFFFFF", "1F497D", "EEECE1", "4F81BD", "C0504D", "9BBB59", "8064A2",
+            "4BACC6", "F79646", "0000FF", "800080",
+        ],
+        major_font: "Cambria",
+        minor_font: "Calibri",
+    },
+    BuiltinTheme {
+        name: "Office 2023",
+        colors: [
+            "000000", "FFFFFF", "0E2841", "E8E8E8", "156082", "E97132", "196B24", "0F9ED5",
+            "A02B93", "4EA72E", "467886", "96607D",
+        ],
+        major_font: "Aptos Display",
+        minor_font: "Aptos Narrow",
+    },
+    BuiltinTheme {
+        name: "Grayscale",
+        colors: [
+            "000000", "FFFFFF", "000000", "F8F8F8", "DDDDDD", "B2B2B2", "969696", "808080",
+            "5F5F5F", "4D4D4D", "5F5F5F", "919191",
+        ],
+        major_font: "Calibri Light",
+        minor_font: "Calibri",
+    },
+];
+
+/// Build the built-in theme called `name`, ignoring case.
+pub fn builtin_theme(name: &str) -> Option<ThemePart> {
+    let theme = BUILTIN_THEMES
+        .iter()
+        .find(|t| t.name.eq_ignore_ascii_case(name))?;
+    let mut part = xmlserde::xml_deserialize_from_str::<ThemePart>(TEMPLATE).ok()?;
+    part.name = theme.name.to_string();
+    let elements = &mut part.theme_elements;
+    let scheme = &mut elements.clr_scheme;
+    scheme.name = theme.name.to_string();
+    // Excel writes the first two as system colours, with the colour they
+    // had when saved.
+    scheme.dk1 = system_color("windowText", theme.colors[0]);
+    scheme.lt1 = system_color("window", theme.colors[1]);
+    let others = [
+        &mut scheme.dk2,
+        &mut scheme.lt2,
+        &mut scheme.accent1,
+        &mut scheme.accent2,
+        &mut scheme.accent3,
+        &mut scheme.accent4,
+        &mut scheme.accent5,
+        &mut scheme.accent6,
+        &mut scheme.hlink,
+        &mut scheme.fol_hlink,
+    ];
+    for (color, rgb) in others.into_iter().zip(&theme.colors[2..]) {
+        *color = EgColorChoice::SrgbClr(CtSrgbColor {
+            val: rgb.to_string(),
+        });
+    }
+    let fonts = &mut elements.font_scheme;
+    fonts.name = theme.name.to_string();
+    fonts.major_font.latin.typeface = theme.major_font.to_string();
+    fonts.minor_font.latin.typeface = theme.minor_font.to_string();
+    Some(part)
+}
+
+fn system_color(val: &str, rgb: &str) -> EgColorChoice {
+    EgColorChoice::SysClr(CtSystemColor {
+        val: val.to_string(),
+        last_clr: Some(rgb.to_string()),
+    })
+}
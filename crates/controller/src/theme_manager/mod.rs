@@ -1,6 +1,17 @@
-use logisheets_workbook::prelude::{CtColorScheme, ThemePart};
+mod builtin;
 
-#[derive(Default, Clone)]
+use logisheets_workbook::prelude::{
+    CtColorScheme, CtSrgbColor, EgColorChoice, StFontScheme, ThemePart,
+};
+
+pub use builtin::{BUILTIN_THEMES, builtin_theme};
+
+use crate::{
+    edit_action::{EditPayload, ThemeColorSlot},
+    errors::Error,
+};
+
+#[derive(Debug, Default, Clone)]
 pub struct ThemeManager {
     pub theme: Option<ThemePart>,
 }
@@ -51,6 +62,123 @@ impl ThemeManager {
         }
     }
 
+    /// The typeface of the theme's major (headings) or minor (body) font,
+    /// which fonts with `<scheme>` use in place of their own name.
+    pub fn get_font(&self, scheme: &StFontScheme) -> Option<&str> {
+        let fonts = &self.theme.as_ref()?.theme_elements.font_scheme;
+        let typeface = match scheme {
+            StFontScheme::Major => &fonts.major_font.latin.typeface,
+            StFontScheme::Minor => &fonts.minor_font.latin.typeface,
+            StFontScheme::None => return None,
+        };
+        if typeface.is_empty() {
+            None
+        } else {
+            Some(typeface)
+        }
+    }
+
+    pub fn get_name(&self) -> Option<&str> {
+        self.theme.as_ref().map(|t| t.name.as_str())
+    }
+
+    /// Execute a theme payload. Returns the scheme fonts whose typeface
+    /// changed, as `(scheme, old, new)`, for the fonts using them to follow.
+    pub fn execute_payload(
+        &mut self,
+        payload: EditPayload,
+    ) -> Result<Vec<(StFontScheme, String, String)>, Error> {
+        let old = self.scheme_fonts();
+        match payload {
+            EditPayload::SetTheme(p) => {
+                let part = xmlserde::xml_deserialize_from_str::<ThemePart>(&p.xml)
+                    .map_err(|e| Error::PayloadError(format!("invalid theme: {}", e)))?;
+                self.set_theme(part);
+            }
+            EditPayload::ApplyBuiltinTheme(p) => {
+                let part = builtin_theme(&p.name)
+                    .ok_or_else(|| Error::PayloadError(format!("unknown theme: {}", p.name)))?;
+                self.set_theme(part);
+            }
+            EditPayload::SetThemeColor(p) => self.set_color(p.slot, &p.color)?,
+            EditPayload::SetThemeFonts(p) => self.set_fonts(p.major, p.minor),
+            _ => {}
+        }
+        let renamed = old
+            .into_iter()
+            .zip(self.scheme_fonts())
+            .filter(|((_, old), (_, new))| old != new)
+            .map(|((scheme, old), (_, new))| (scheme, old, new))
+            .collect();
+        Ok(renamed)
+    }
+
+    pub fn set_theme(&mut self, part: ThemePart) {
+        self.theme = Some(part);
+    }
+
+    /// Set one colour of the colour scheme to an `RRGGBB` hex.
+    pub fn set_color(&mut self, slot: ThemeColorSlot, rgb: &str) -> Result<(), Error> {
+        let rgb = rgb.trim_start_matches('#');
+        if rgb.len() != 6 || !rgb.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(Error::PayloadError(format!("invalid theme color: {}", rgb)));
+        }
+        let scheme = &mut self.theme_mut().theme_elements.clr_scheme;
+        let color = match slot {
+            ThemeColorSlot::Dk1 => &mut scheme.dk1,
+            ThemeColorSlot::Lt1 => &mut scheme.lt1,
+            ThemeColorSlot::Dk2 => &mut scheme.dk2,
+            ThemeColorSlot::Lt2 => &mut scheme.lt2,
+            ThemeColorSlot::Accent1 => &mut scheme.accent1,
+            ThemeColorSlot::Accent2 => &mut scheme.accent2,
+            ThemeColorSlot::Accent3 => &mut scheme.accent3,
+            ThemeColorSlot::Accent4 => &mut scheme.accent4,
+            ThemeColorSlot::Accent5 => &mut scheme.accent5,
+            ThemeColorSlot::Accent6 => &mut scheme.accent6,
+            ThemeColorSlot::Hlink => &mut scheme.hlink,
+            ThemeColorSlot::FolHlink => &mut scheme.fol_hlink,
+        };
+        *color = EgColorChoice::SrgbClr(CtSrgbColor {
+            val: rgb.to_ascii_uppercase(),
+        });
+        Ok(())
+    }
+
+    /// Set the latin typeface of the major and minor fonts. `None` leaves
+    /// one as it is.
+    pub fn set_fonts(&mut self, major: Option<String>, minor: Option<String>) {
+        let fonts = &mut self.theme_mut().theme_elements.font_scheme;
+        if let Some(major) = major {
+            fonts.major_font.latin.typeface = major;
+        }
+        if let Some(minor) = minor {
+            fonts.minor_font.latin.typeface = minor;
+        }
+    }
+
+    fn scheme_fonts(&self) -> [(StFontScheme, String); 2] {
+        [StFontScheme::Major, StFontScheme::Minor].map(|scheme| {
+            let typeface = match self.theme {
+                Some(_) => self.get_font(&scheme).unwrap_or_default().to_string(),
+                None => {
+                    let office = &BUILTIN_THEMES[0];
+                    match scheme {
+                        StFontScheme::Major => office.major_font.to_string(),
+                        _ => office.minor_font.to_string(),
+                    }
+                }
+            };
+            (scheme, typeface)
+        })
+    }
+
+    /// A workbook without a theme starts editing from the Office theme,
+    /// which is what Excel shows for it.
+    fn theme_mut(&mut self) -> &mut ThemePart {
+        self.theme
+            .get_or_insert_with(|| builtin_theme("Office").expect("the Office theme is built in"))
+    }
+
     fn get_color_scheme(&self) -> &CtColorScheme {
         let clr_scheme = &self.theme.as_ref().unwrap().theme_elements.clr_scheme;
         clr_scheme
@@ -59,8 +187,9 @@ impl ThemeManager {
 
 #[cfg(test)]
 mod tests {
-    use super::ThemeManager;
-    use logisheets_workbook::prelude::ThemePart;
+    use super::{BUILTIN_THEMES, ThemeManager, builtin_theme};
+    use crate::edit_action::{EditPayload, SetThemeColor, SetThemeFonts, ThemeColorSlot};
+    use logisheets_workbook::prelude::{StFontScheme, ThemePart};
 
     // The `theme` attribute (as written in cell colors) maps text1 -> dk1 and
     // background1 -> lt1, i.e. indices 0/1 are swapped vs the clrScheme order.
@@ -83,4 +212,54 @@ mod tests {
         // text1 is dark (default black-ish), not the light background.
         assert_ne!(tm.get_theme_color(1), tm.get_theme_color(0));
     }
+
+    #[test]
+    fn builtin_themes_build() {
+        for theme in BUILTIN_THEMES {
+            let tm = ThemeManager::from(builtin_theme(theme.name).unwrap());
+            assert_eq!(tm.get_name(), Some(theme.name));
+            for (idx, rgb) in theme.colors.iter().enumerate() {
+                assert_eq!(tm.get_color(idx as u32), format!("FF{}", rgb));
+            }
+            assert_eq!(tm.get_font(&StFontScheme::Minor), Some(theme.minor_font));
+        }
+        assert!(builtin_theme("no such theme").is_none());
+    }
+
+    #[test]
+    fn edits_start_from_office_and_report_font_changes() {
+        let mut tm = ThemeManager::default();
+        let renamed = tm
+            .execute_payload(EditPayload::SetThemeColor(SetThemeColor {
+                slot: ThemeColorSlot::Accent1,
+                color: "#c00000".to_string(),
+            }))
+            .unwrap();
+        assert!(renamed.is_empty());
+        assert_eq!(tm.get_name(), Some("Office"));
+        // theme="4" is accent1; theme="5" is still Office's accent2.
+        assert_eq!(tm.get_theme_color(4), "FFC00000");
+        assert_eq!(tm.get_theme_color(5), "FFED7D31");
+
+        let renamed = tm
+            .execute_payload(EditPayload::SetThemeFonts(SetThemeFonts {
+                major: None,
+                minor: Some("Arial".to_string()),
+            }))
+            .unwrap();
+        assert_eq!(
+            renamed,
+            vec![(
+                StFontScheme::Minor,
+                "Calibri".to_string(),
+                "Arial".to_string()
+            )]
+        );
+
+        let bad = tm.execute_payload(EditPayload::SetThemeColor(SetThemeColor {
+            slot: ThemeColorSlot::Dk1,
+            color: "red".to_string(),
+        }));
+        assert!(bad.is_err());
+    }
 }
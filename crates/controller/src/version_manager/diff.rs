@@ -99,6 +99,47 @@ fn convert_diff<C: VersionExecCtx>(
                 .map_err(|l| BasicError::SheetIdxExceed(l))?;
             Ok(Some((Diff::Unavailable, sheet_id)))
         }
+        // Creating or removing a data table changes what its result cells
+        // show as their formula, so the sheet is marked wholly stale.
+        EditPayload::CreateDataTable(p) => {
+            let sheet_id = ctx
+                .fetch_sheet_id_by_index(p.sheet_idx)
+                .map_err(BasicError::SheetIdxExceed)?;
+            Ok(Some((Diff::Unavailable, sheet_id)))
+        }
+        EditPayload::RemoveDataTable(p) => {
+            let sheet_id = ctx
+                .fetch_sheet_id_by_index(p.sheet_idx)
+                .map_err(BasicError::SheetIdxExceed)?;
+            Ok(Some((Diff::Unavailable, sheet_id)))
+        }
+        EditPayload::AddScenario(p) => {
+            let sheet_id = ctx
+                .fetch_sheet_id_by_index(p.sheet_idx)
+                .map_err(BasicError::SheetIdxExceed)?;
+            Ok(Some((Diff::Unavailable, sheet_id)))
+        }
+        EditPayload::EditScenario(p) => {
+            let sheet_id = ctx
+                .fetch_sheet_id_by_index(p.sheet_idx)
+                .map_err(BasicError::SheetIdxExceed)?;
+            Ok(Some((Diff::Unavailable, sheet_id)))
+        }
+        EditPayload::DeleteScenario(p) => {
+            let sheet_id = ctx
+                .fetch_sheet_id_by_index(p.sheet_idx)
+                .map_err(BasicError::SheetIdxExceed)?;
+            Ok(Some((Diff::Unavailable, sheet_id)))
+        }
+        // Showing a scenario types into each of its changing cells.
+        EditPayload::ShowScenario(p) => {
+            let sheet_id = ctx
+                .fetch_sheet_id_by_index(p.sheet_idx)
+                .map_err(BasicError::SheetIdxExceed)?;
+            Ok(Some((Diff::Unavailable, sheet_id)))
+        }
+        // The summary is written on a sheet of its own, like a duplicate.
+        EditPayload::CreateScenarioSummary(_) => Ok(Some((Diff::SheetProperty, 0))),
         EditPayload::BlockInput(bi) => {
             let sheet_id = ctx
                 .fetch_sheet_id_by_index(bi.sheet_idx)
@@ -247,6 +288,25 @@ fn convert_diff<C: VersionExecCtx>(
                 .map_err(|l| BasicError::SheetIdxExceed(l))?;
             Ok(Some((Diff::Unavailable, sheet_id)))
         }
+        EditPayload::InsertCells(p) => {
+            let sheet_id = ctx
+                .fetch_sheet_id_by_index(p.sheet_idx)
+                .map_err(BasicError::SheetIdxExceed)?;
+            Ok(Some((Diff::Unavailable, sheet_id)))
+        }
+        EditPayload::DeleteCells(p) => {
+            let sheet_id = ctx
+                .fetch_sheet_id_by_index(p.sheet_idx)
+                .map_err(BasicError::SheetIdxExceed)?;
+            Ok(Some((Diff::Unavailable, sheet_id)))
+        }
+        // The destination sheet is covered by the cells the move updates.
+        EditPayload::MoveRange(p) => {
+            let sheet_id = ctx
+                .fetch_sheet_id_by_index(p.sheet_idx)
+                .map_err(BasicError::SheetIdxExceed)?;
+            Ok(Some((Diff::Unavailable, sheet_id)))
+        }
         EditPayload::InsertColsInBlock(p) => {
             let sheet_id = ctx
                 .fetch_sheet_id_by_index(p.sheet_idx)
@@ -271,6 +331,12 @@ fn convert_diff<C: VersionExecCtx>(
                 .map_err(|l| BasicError::SheetIdxExceed(l))?;
             Ok(Some((Diff::Unavailable, sheet_id)))
         }
+        EditPayload::ImportBlockRecords(p) => {
+            let sheet_id = ctx
+                .fetch_sheet_id_by_index(p.sheet_idx)
+                .map_err(BasicError::SheetIdxExceed)?;
+            Ok(Some((Diff::Unavailable, sheet_id)))
+        }
         EditPayload::CellClear(cr) => {
             let sheet_id = ctx
                 .fetch_sheet_id_by_index(cr.sheet_idx)
@@ -303,6 +369,30 @@ fn convert_diff<C: VersionExecCtx>(
                 .map_err(|l| BasicError::SheetIdxExceed(l))?;
             Ok(Some((Diff::Unavailable, sheet_id)))
         }
+        EditPayload::SetCellStyles(p) => {
+            let sheet_id = ctx
+                .fetch_sheet_id_by_index(p.sheet_idx)
+                .map_err(BasicError::SheetIdxExceed)?;
+            Ok(Some((Diff::Unavailable, sheet_id)))
+        }
+        // Named styles are workbook-scoped. Modifying one restyles its cells
+        // on every sheet; the executor flags the whole transaction as
+        // updated instead, as for `RestoreCheckpoint`.
+        EditPayload::CreateCellStyle(_) => Ok(None),
+        EditPayload::ModifyCellStyle(_) => Ok(None),
+        EditPayload::DeleteCellStyle(_) => Ok(None),
+        // The theme is workbook-scoped, too: its colours and fonts are
+        // resolved when cells are read, so every sheet is affected.
+        EditPayload::SetTheme(_) => Ok(None),
+        EditPayload::ApplyBuiltinTheme(_) => Ok(None),
+        EditPayload::SetThemeColor(_) => Ok(None),
+        EditPayload::SetThemeFonts(_) => Ok(None),
+        EditPayload::ApplyCellStyle(p) => {
+            let sheet_id = ctx
+                .fetch_sheet_id_by_index(p.sheet_idx)
+                .map_err(BasicError::SheetIdxExceed)?;
+            Ok(Some((Diff::Unavailable, sheet_id)))
+        }
         EditPayload::LineFormatBrush(p) => {
             let sheet_id = ctx
                 .fetch_sheet_id_by_index(p.dst_sheet_idx)
@@ -400,6 +490,8 @@ fn convert_diff<C: VersionExecCtx>(
         }
         EditPayload::SetSheetColor(_) => Ok(Some((Diff::SheetProperty, 0))),
         EditPayload::SetSheetVisible(_) => Ok(Some((Diff::SheetProperty, 0))),
+        EditPayload::MoveSheet(_) => Ok(Some((Diff::SheetProperty, 0))),
+        EditPayload::DuplicateSheet(_) => Ok(Some((Diff::SheetProperty, 0))),
         EditPayload::BlockLineStyleUpdate(p) => {
             let sheet_id = ctx
                 .fetch_sheet_id_by_index(p.sheet_idx)
@@ -46,6 +46,16 @@ impl SheetInfoManager {
                 self.hiddens.remove(&id);
                 Ok(self)
             }
+            EditPayload::MoveSheet(p) => {
+                let len = self.pos.len();
+                if p.from >= len || p.to >= len {
+                    return Err(BasicError::SheetIdxExceed(len));
+                }
+                let id = self.pos.remove(p.from);
+                self.pos.insert(p.to, id);
+                ctx.has_updated();
+                Ok(self)
+            }
             EditPayload::SetSheetColor(p) => {
                 let id = self
                     .get_sheet_id(p.idx)
@@ -58,7 +68,11 @@ impl SheetInfoManager {
                 let id = self
                     .get_sheet_id(p.idx)
                     .ok_or(BasicError::SheetIdxExceed(self.pos.len()))?;
-                self.hiddens.insert(id);
+                if p.visible {
+                    self.hiddens.remove(&id);
+                } else {
+                    self.hiddens.insert(id);
+                }
                 ctx.has_updated();
                 Ok(self)
             }
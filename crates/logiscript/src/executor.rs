@@ -1,13 +1,17 @@
 use logisheets_controller::edit_action::{
-    CellInput, ConvertBlock, CreateBlock, CreateSheet, DeleteCols, DeleteColsInBlock, DeleteRows,
-    DeleteRowsInBlock, EditAction, EditPayload, InsertCols, InsertColsInBlock, InsertRows,
-    InsertRowsInBlock, MoveBlock, PayloadsAction, RemoveBlock, ResizeBlock,
+    AddComment, AuthorInput, CellClear, CellInput, CellStyleUpdate, ConvertBlock, CreateBlock,
+    CreateConditionalFormattingRule, CreateSheet, DeleteCols, DeleteColsInBlock, DeleteComment,
+    DeleteConditionalFormattingRule, DeleteRows, DeleteRowsInBlock, DeleteSheet, EditAction,
+    EditPayload, InsertCols, InsertColsInBlock, InsertRows, InsertRowsInBlock, MoveBlock,
+    PayloadsAction, RemoveBlock, ResizeBlock, SetSheetVisible, SetVisible, SheetRename, StatusCode,
+    WorkbookUpdateType,
 };
-use logisheets_controller::{Value, Workbook};
+use logisheets_controller::{Value, Workbook, take_last_error};
 
 use crate::operator::{
-    BlockShiftData, CheckEmpty, CheckError, CheckFormula, CheckNum, CheckString, Input, Operator,
-    ShiftData, Statement, Switch,
+    Area, BlockShiftData, CfRule, CheckComment, CheckConditionalFormat, CheckEmpty, CheckError,
+    CheckFormula, CheckNum, CheckSize, CheckString, CheckStyle, Comment, Input, Operator, Position,
+    ShiftData, Statement, StyleCells, Switch, Target, Visibility,
 };
 use crate::parser::{ParseError, parse};
 
@@ -71,6 +75,61 @@ fn _exec(ctx: &mut ExecContext, statements: Vec<Statement>) -> Option<ExecError>
             Operator::BlockDeleteRow(data) => exec_block(ctx, true, false, data, line),
             Operator::BlockDeleteCol(data) => exec_block(ctx, false, false, data, line),
             Operator::ConvertBlock(convert_block) => exec_convert_block(ctx, convert_block, line),
+            Operator::Clear(area) => exec_clear(ctx, area, line),
+            Operator::Style(style) => exec_style(ctx, style, line),
+            Operator::Merge(mut p) => exec_on_sheet(ctx, line, |sheet_idx| {
+                p.sheet_idx = sheet_idx;
+                vec![EditPayload::MergeCells(p)]
+            }),
+            Operator::Split(mut p) => exec_on_sheet(ctx, line, |sheet_idx| {
+                p.sheet_idx = sheet_idx;
+                vec![EditPayload::SplitMergedCells(p)]
+            }),
+            Operator::Comment(c) => exec_comment(ctx, c, line),
+            Operator::DeleteComment(p) => exec_delete_comment(ctx, p, line),
+            Operator::CreateSheet(name) => {
+                let idx = ctx.workbook.get_sheet_count();
+                exec_payloads(
+                    ctx,
+                    vec![EditPayload::CreateSheet(CreateSheet {
+                        idx,
+                        new_name: name,
+                    })],
+                    line,
+                )
+            }
+            Operator::RenameSheet(p) => exec_rename_sheet(ctx, p, line),
+            Operator::DeleteSheet(name) => match ctx.workbook.get_sheet_idx_by_name(&name) {
+                Ok(idx) => exec_payloads(
+                    ctx,
+                    vec![EditPayload::DeleteSheet(DeleteSheet { idx })],
+                    line,
+                ),
+                Err(_) => Some(sheet_not_found(&name, line)),
+            },
+            Operator::SetVisible(v) => exec_set_visible(ctx, v, line),
+            Operator::SetRowHeight(mut p) => exec_on_sheet(ctx, line, |sheet_idx| {
+                p.sheet_idx = sheet_idx;
+                vec![EditPayload::SetRowHeight(p)]
+            }),
+            Operator::SetColWidth(mut p) => exec_on_sheet(ctx, line, |sheet_idx| {
+                p.sheet_idx = sheet_idx;
+                vec![EditPayload::SetColWidth(p)]
+            }),
+            Operator::CreateCfRule(rule) => exec_create_cf_rule(ctx, rule, line),
+            Operator::ClearCfRules => exec_clear_cf_rules(ctx, line),
+            Operator::Undo => exec_undo_redo(ctx, true, line),
+            Operator::Redo => exec_undo_redo(ctx, false, line),
+            Operator::Roundtrip => exec_roundtrip(ctx, line),
+            Operator::CheckStyle(c) => exec_check_style(ctx, c, line),
+            Operator::CheckConditionalFormat(c) => exec_check_cf(ctx, c, line),
+            Operator::CheckMerged(area) => exec_check_merged(ctx, area, line),
+            Operator::CheckUnmerged(p) => exec_check_unmerged(ctx, p, line),
+            Operator::CheckVisible(v) => exec_check_visible(ctx, v, line),
+            Operator::CheckComment(c) => exec_check_comment(ctx, c, line),
+            Operator::CheckSheets(names) => exec_check_sheets(ctx, names, line),
+            Operator::CheckRowHeight(c) => exec_check_size(ctx, c, true, line),
+            Operator::CheckColWidth(c) => exec_check_size(ctx, c, false, line),
         };
         if let Some(err) = res {
             return Some(err);
@@ -114,10 +173,10 @@ fn exec_shift_row(
                 start: data.from as usize,
                 count: data.cnt as usize,
             })
-            .set_undoable(false)
+            .set_undoable(true)
     } else {
         PayloadsAction::new()
-            .set_undoable(false)
+            .set_undoable(true)
             .add_payload(DeleteRows {
                 sheet_idx,
                 start: data.from as usize,
@@ -144,7 +203,7 @@ fn exec_shift_col(
     let sheet_idx = sheet.unwrap();
     let action = if insert {
         PayloadsAction::new()
-            .set_undoable(false)
+            .set_undoable(true)
             .add_payload(InsertCols {
                 sheet_idx,
                 start: data.from as usize,
@@ -152,7 +211,7 @@ fn exec_shift_col(
             })
     } else {
         PayloadsAction::new()
-            .set_undoable(false)
+            .set_undoable(true)
             .add_payload(DeleteCols {
                 sheet_idx,
                 start: data.from as usize,
@@ -169,7 +228,7 @@ fn exec_switch(ctx: &mut ExecContext, switch: Switch, _line: usize) -> Option<Ex
     if workbook.get_sheet_by_name(&switch.sheet).is_err() {
         workbook.handle_action(EditAction::Payloads(
             PayloadsAction::new()
-                .set_undoable(false)
+                .set_undoable(true)
                 .add_payload(CreateSheet {
                     idx: 0,
                     new_name: switch.sheet.clone(),
@@ -194,12 +253,13 @@ fn exec_input(ctx: &mut ExecContext, input: Input, line: usize) -> Option<ExecEr
     ctx.workbook
         .handle_action(EditAction::Payloads(PayloadsAction {
             init: false,
-            undoable: false,
+            undoable: true,
             payloads: vec![EditPayload::CellInput(CellInput {
                 sheet_idx,
                 row: input.row as usize,
                 col: input.col as usize,
                 content: input.content,
+                ..Default::default()
             })],
         }));
     None
@@ -222,7 +282,7 @@ fn exec_create_block(
     ctx.workbook
         .handle_action(EditAction::Payloads(PayloadsAction {
             payloads: vec![EditPayload::CreateBlock(payload)],
-            undoable: false,
+            undoable: true,
             init: false,
         }));
     None
@@ -245,7 +305,7 @@ fn exec_move_block(
     ctx.workbook
         .handle_action(EditAction::Payloads(PayloadsAction {
             payloads: vec![EditPayload::MoveBlock(payload)],
-            undoable: false,
+            undoable: true,
             init: false,
         }));
     None
@@ -268,7 +328,7 @@ fn exec_resize_block(
     ctx.workbook
         .handle_action(EditAction::Payloads(PayloadsAction {
             payloads: vec![EditPayload::ResizeBlock(payload)],
-            undoable: false,
+            undoable: true,
             init: false,
         }));
     None
@@ -291,7 +351,7 @@ fn exec_remove_block(
     ctx.workbook
         .handle_action(EditAction::Payloads(PayloadsAction {
             payloads: vec![EditPayload::RemoveBlock(payload)],
-            undoable: false,
+            undoable: true,
             init: false,
         }));
     None
@@ -386,7 +446,9 @@ fn exec_convert_block(
     }
     let sheet_idx = sheet.unwrap();
     convert_block.sheet_idx = sheet_idx;
-    let action = PayloadsAction::new().add_payload(convert_block);
+    let action = PayloadsAction::new()
+        .set_undoable(true)
+        .add_payload(convert_block);
     ctx.workbook.handle_action(EditAction::Payloads(action));
     None
 }
@@ -408,7 +470,7 @@ fn exec_block(
     let sheet_idx = sheet.unwrap();
     let action = if is_row && insert {
         PayloadsAction::new()
-            .set_undoable(false)
+            .set_undoable(true)
             .add_payload(InsertRowsInBlock {
                 sheet_idx,
                 block_id: data.block_id,
@@ -417,7 +479,7 @@ fn exec_block(
             })
     } else if !is_row && insert {
         PayloadsAction::new()
-            .set_undoable(false)
+            .set_undoable(true)
             .add_payload(InsertColsInBlock {
                 sheet_idx,
                 block_id: data.block_id,
@@ -426,7 +488,7 @@ fn exec_block(
             })
     } else if is_row && !insert {
         PayloadsAction::new()
-            .set_undoable(false)
+            .set_undoable(true)
             .add_payload(DeleteRowsInBlock {
                 sheet_idx,
                 block_id: data.block_id,
@@ -435,7 +497,7 @@ fn exec_block(
             })
     } else {
         PayloadsAction::new()
-            .set_undoable(false)
+            .set_undoable(true)
             .add_payload(DeleteColsInBlock {
                 sheet_idx,
                 block_id: data.block_id,
@@ -560,6 +622,440 @@ fn exec_check_error(
     }
 }
 
+fn sheet_not_found(name: &str, line: usize) -> ExecError {
+    ExecError {
+        line,
+        msg: format!("Sheet {} is not found", name),
+    }
+}
+
+/// Apply the payloads as one undoable step, reporting an edit the engine
+/// rejects as a failure of this line.
+fn exec_payloads(
+    ctx: &mut ExecContext,
+    payloads: Vec<EditPayload>,
+    line: usize,
+) -> Option<ExecError> {
+    let effect = ctx
+        .workbook
+        .handle_action(EditAction::Payloads(PayloadsAction {
+            payloads,
+            undoable: true,
+            init: false,
+        }));
+    match effect.status {
+        StatusCode::Ok(_) => None,
+        StatusCode::Err(code) => Some(ExecError {
+            line,
+            msg: take_last_error().unwrap_or(format!("the edit failed with code {}", code)),
+        }),
+    }
+}
+
+/// Like `exec_payloads`, for payloads that target the current sheet.
+fn exec_on_sheet<F>(ctx: &mut ExecContext, line: usize, build: F) -> Option<ExecError>
+where
+    F: FnOnce(usize) -> Vec<EditPayload>,
+{
+    match ctx.workbook.get_sheet_idx_by_name(&ctx.sheet_name) {
+        Ok(sheet_idx) => exec_payloads(ctx, build(sheet_idx), line),
+        Err(_) => Some(sheet_not_found(&ctx.sheet_name, line)),
+    }
+}
+
+fn cells(area: &Area) -> impl Iterator<Item = (usize, usize)> + '_ {
+    (area.start_row..=area.end_row)
+        .flat_map(move |r| (area.start_col..=area.end_col).map(move |c| (r as usize, c as usize)))
+}
+
+fn exec_clear(ctx: &mut ExecContext, area: Area, line: usize) -> Option<ExecError> {
+    exec_on_sheet(ctx, line, |sheet_idx| {
+        cells(&area)
+            .map(|(row, col)| {
+                EditPayload::CellClear(CellClear {
+                    sheet_idx,
+                    row,
+                    col,
+                })
+            })
+            .collect()
+    })
+}
+
+fn exec_style(ctx: &mut ExecContext, style: StyleCells, line: usize) -> Option<ExecError> {
+    let ty = style.style.to_update();
+    exec_on_sheet(ctx, line, |sheet_idx| {
+        cells(&style.area)
+            .map(|(row, col)| {
+                EditPayload::CellStyleUpdate(CellStyleUpdate {
+                    sheet_idx,
+                    row,
+                    col,
+                    ty: ty.clone(),
+                })
+            })
+            .collect()
+    })
+}
+
+fn exec_comment(ctx: &mut ExecContext, c: Comment, line: usize) -> Option<ExecError> {
+    exec_on_sheet(ctx, line, |sheet_idx| {
+        vec![EditPayload::AddComment(AddComment {
+            sheet_idx,
+            row: c.row as usize,
+            col: c.col as usize,
+            comment_id: format!("logiscript-{}", line),
+            parent_id: None,
+            author: AuthorInput {
+                display_name: String::from("logiscript"),
+                user_id: None,
+                provider_id: None,
+            },
+            dt: String::from("2024-01-01T00:00:00Z"),
+            content: c.content,
+            mentions: vec![],
+        })]
+    })
+}
+
+fn exec_delete_comment(ctx: &mut ExecContext, p: Position, line: usize) -> Option<ExecError> {
+    let comment = match ctx.workbook.get_sheet_by_name(&ctx.sheet_name) {
+        Ok(ws) => ws.get_comment(p.row as usize, p.col as usize),
+        Err(_) => return Some(sheet_not_found(&ctx.sheet_name, line)),
+    };
+    let Some(root) = comment.and_then(|c| c.notes.into_iter().next()) else {
+        return Some(ExecError {
+            line,
+            msg: String::from("no comment is found"),
+        });
+    };
+    exec_on_sheet(ctx, line, |sheet_idx| {
+        vec![EditPayload::DeleteComment(DeleteComment {
+            sheet_idx,
+            comment_id: root.id,
+        })]
+    })
+}
+
+fn exec_rename_sheet(ctx: &mut ExecContext, p: SheetRename, line: usize) -> Option<ExecError> {
+    let old_name = p.old_name.clone().unwrap_or_default();
+    if ctx.workbook.get_sheet_idx_by_name(&old_name).is_err() {
+        return Some(sheet_not_found(&old_name, line));
+    }
+    let new_name = p.new_name.clone();
+    if let Some(e) = exec_payloads(ctx, vec![EditPayload::SheetRename(p)], line) {
+        return Some(e);
+    }
+    if ctx.sheet_name == old_name {
+        ctx.sheet_name = new_name;
+    }
+    None
+}
+
+fn exec_set_visible(ctx: &mut ExecContext, v: Visibility, line: usize) -> Option<ExecError> {
+    match v.target {
+        Target::Sheet(name) => match ctx.workbook.get_sheet_idx_by_name(&name) {
+            Ok(idx) => exec_payloads(
+                ctx,
+                vec![EditPayload::SetSheetVisible(SetSheetVisible {
+                    idx,
+                    visible: v.visible,
+                })],
+                line,
+            ),
+            Err(_) => Some(sheet_not_found(&name, line)),
+        },
+        Target::Row(start) | Target::Col(start) => {
+            let is_row = matches!(v.target, Target::Row(_));
+            exec_on_sheet(ctx, line, |sheet_idx| {
+                vec![EditPayload::SetVisible(SetVisible {
+                    is_row,
+                    sheet_idx,
+                    start: start as usize,
+                    visible: v.visible,
+                })]
+            })
+        }
+    }
+}
+
+fn exec_create_cf_rule(ctx: &mut ExecContext, rule: CfRule, line: usize) -> Option<ExecError> {
+    exec_on_sheet(ctx, line, |sheet_idx| {
+        vec![EditPayload::CreateConditionalFormattingRule(
+            CreateConditionalFormattingRule {
+                sheet_idx,
+                start_row: rule.area.start_row as usize,
+                start_col: rule.area.start_col as usize,
+                end_row: rule.area.end_row as usize,
+                end_col: rule.area.end_col as usize,
+                rule: rule.rule,
+            },
+        )]
+    })
+}
+
+fn exec_clear_cf_rules(ctx: &mut ExecContext, line: usize) -> Option<ExecError> {
+    let rules = match ctx.workbook.get_sheet_by_name(&ctx.sheet_name) {
+        Ok(ws) => ws.get_conditional_formatting_rules(),
+        Err(_) => return Some(sheet_not_found(&ctx.sheet_name, line)),
+    };
+    if rules.is_empty() {
+        return None;
+    }
+    exec_on_sheet(ctx, line, |sheet_idx| {
+        rules
+            .into_iter()
+            .map(|r| {
+                EditPayload::DeleteConditionalFormattingRule(DeleteConditionalFormattingRule {
+                    sheet_idx,
+                    rule_id: r.rule_id,
+                })
+            })
+            .collect()
+    })
+}
+
+fn exec_undo_redo(ctx: &mut ExecContext, undo: bool, line: usize) -> Option<ExecError> {
+    let action = if undo {
+        EditAction::Undo
+    } else {
+        EditAction::Redo
+    };
+    let effect = ctx.workbook.handle_action(action);
+    let done = matches!(
+        effect.status,
+        StatusCode::Ok(WorkbookUpdateType::Undo) | StatusCode::Ok(WorkbookUpdateType::Redo)
+    );
+    if done {
+        None
+    } else {
+        Some(ExecError {
+            line,
+            msg: format!("nothing to {}", if undo { "undo" } else { "redo" }),
+        })
+    }
+}
+
+/// Save to xlsx and continue on the reloaded workbook, so that everything
+/// checked afterwards has survived the file format.
+fn exec_roundtrip(ctx: &mut ExecContext, line: usize) -> Option<ExecError> {
+    let reloaded = ctx
+        .workbook
+        .save()
+        .and_then(|buf| Workbook::from_file(&buf, String::from("logiscript")));
+    match reloaded {
+        Ok(wb) => {
+            ctx.workbook = wb;
+            None
+        }
+        Err(e) => Some(ExecError {
+            line,
+            msg: format!("roundtrip failed: {}", e),
+        }),
+    }
+}
+
+fn exec_check_style(ctx: &mut ExecContext, c: CheckStyle, line: usize) -> Option<ExecError> {
+    let ws = match ctx.workbook.get_sheet_by_name(&ctx.sheet_name) {
+        Ok(ws) => ws,
+        Err(_) => return Some(sheet_not_found(&ctx.sheet_name, line)),
+    };
+    let style = match ws.get_style(c.row as usize, c.col as usize) {
+        Ok(style) => style,
+        Err(e) => {
+            return Some(ExecError {
+                line,
+                msg: e.to_string(),
+            });
+        }
+    };
+    c.expect
+        .check(&style)
+        .err()
+        .map(|msg| ExecError { line, msg })
+}
+
+fn exec_check_cf(
+    ctx: &mut ExecContext,
+    c: CheckConditionalFormat,
+    line: usize,
+) -> Option<ExecError> {
+    let ws = match ctx.workbook.get_sheet_by_name(&ctx.sheet_name) {
+        Ok(ws) => ws,
+        Err(_) => return Some(sheet_not_found(&ctx.sheet_name, line)),
+    };
+    let info = match ws.get_cell_info(c.row as usize, c.col as usize) {
+        Ok(info) => info,
+        Err(e) => {
+            return Some(ExecError {
+                line,
+                msg: e.to_string(),
+            });
+        }
+    };
+    match (c.expect, info.conditional_format) {
+        (None, None) => None,
+        (None, Some(_)) => Some(ExecError {
+            line,
+            msg: String::from("expect no conditional format, found one"),
+        }),
+        (Some(_), None) => Some(ExecError {
+            line,
+            msg: String::from("expect a conditional format, found none"),
+        }),
+        (Some(expect), Some(cf)) => expect
+            .check(&cf.style)
+            .err()
+            .map(|msg| ExecError { line, msg }),
+    }
+}
+
+fn exec_check_merged(ctx: &mut ExecContext, area: Area, line: usize) -> Option<ExecError> {
+    let ws = match ctx.workbook.get_sheet_by_name(&ctx.sheet_name) {
+        Ok(ws) => ws,
+        Err(_) => return Some(sheet_not_found(&ctx.sheet_name, line)),
+    };
+    let found = ws.get_all_merged_cells().iter().any(|m| {
+        (m.start_row, m.start_col, m.end_row, m.end_col)
+            == (
+                area.start_row as usize,
+                area.start_col as usize,
+                area.end_row as usize,
+                area.end_col as usize,
+            )
+    });
+    if found {
+        None
+    } else {
+        Some(ExecError {
+            line,
+            msg: String::from("the range is not merged"),
+        })
+    }
+}
+
+fn exec_check_unmerged(ctx: &mut ExecContext, p: Position, line: usize) -> Option<ExecError> {
+    let ws = match ctx.workbook.get_sheet_by_name(&ctx.sheet_name) {
+        Ok(ws) => ws,
+        Err(_) => return Some(sheet_not_found(&ctx.sheet_name, line)),
+    };
+    let (row, col) = (p.row as usize, p.col as usize);
+    let merged = ws
+        .get_all_merged_cells()
+        .into_iter()
+        .find(|m| m.start_row <= row && row <= m.end_row && m.start_col <= col && col <= m.end_col);
+    merged.map(|m| ExecError {
+        line,
+        msg: format!(
+            "the cell is in a merged range: ({}, {}) to ({}, {})",
+            m.start_row, m.start_col, m.end_row, m.end_col
+        ),
+    })
+}
+
+fn exec_check_visible(ctx: &mut ExecContext, v: Visibility, line: usize) -> Option<ExecError> {
+    let hidden = match &v.target {
+        Target::Sheet(name) => {
+            let info = ctx
+                .workbook
+                .get_all_sheet_info()
+                .into_iter()
+                .find(|s| &s.name == name);
+            match info {
+                Some(info) => info.hidden,
+                None => return Some(sheet_not_found(name, line)),
+            }
+        }
+        Target::Row(idx) | Target::Col(idx) => {
+            let ws = match ctx.workbook.get_sheet_by_name(&ctx.sheet_name) {
+                Ok(ws) => ws,
+                Err(_) => return Some(sheet_not_found(&ctx.sheet_name, line)),
+            };
+            if matches!(v.target, Target::Row(_)) {
+                ws.is_row_hidden(*idx as usize)
+            } else {
+                ws.is_col_hidden(*idx as usize)
+            }
+        }
+    };
+    if hidden != v.visible {
+        None
+    } else {
+        Some(ExecError {
+            line,
+            msg: format!(
+                "expect {}, found {}",
+                if v.visible { "visible" } else { "hidden" },
+                if hidden { "hidden" } else { "visible" }
+            ),
+        })
+    }
+}
+
+fn exec_check_comment(ctx: &mut ExecContext, c: CheckComment, line: usize) -> Option<ExecError> {
+    let ws = match ctx.workbook.get_sheet_by_name(&ctx.sheet_name) {
+        Ok(ws) => ws,
+        Err(_) => return Some(sheet_not_found(&ctx.sheet_name, line)),
+    };
+    let found = ws
+        .get_comment(c.row as usize, c.col as usize)
+        .and_then(|c| c.notes.into_iter().next())
+        .map(|n| n.content);
+    if found == c.expect {
+        None
+    } else {
+        Some(ExecError {
+            line,
+            msg: format!("expect comment {:?}, found {:?}", c.expect, found),
+        })
+    }
+}
+
+fn exec_check_sheets(ctx: &mut ExecContext, names: Vec<String>, line: usize) -> Option<ExecError> {
+    let found: Vec<String> = ctx
+        .workbook
+        .get_all_sheet_info()
+        .into_iter()
+        .map(|s| s.name)
+        .collect();
+    if found == names {
+        None
+    } else {
+        Some(ExecError {
+            line,
+            msg: format!("expect sheets {:?}, found {:?}", names, found),
+        })
+    }
+}
+
+fn exec_check_size(
+    ctx: &mut ExecContext,
+    c: CheckSize,
+    is_row: bool,
+    line: usize,
+) -> Option<ExecError> {
+    let ws = match ctx.workbook.get_sheet_by_name(&ctx.sheet_name) {
+        Ok(ws) => ws,
+        Err(_) => return Some(sheet_not_found(&ctx.sheet_name, line)),
+    };
+    let size = if is_row {
+        ws.get_row_height(c.idx as usize)
+    } else {
+        ws.get_col_width(c.idx as usize)
+    };
+    match size {
+        Ok(size) if (size - c.expect).abs() < 10e-4 => None,
+        Ok(size) => Some(ExecError {
+            line,
+            msg: format!("found: {}, expect: {}", size, c.expect),
+        }),
+        Err(e) => Some(ExecError {
+            line,
+            msg: e.to_string(),
+        }),
+    }
+}
+
 pub fn execute_script(script: &str) -> Option<Error> {
     let statements = parse(script);
     if let Err(e) = statements {
@@ -643,4 +1139,20 @@ CHECKERR A1 #DIV/0!
             panic!("{}", err.to_string())
         }
     }
+
+    #[test]
+    fn scenario_failures_name_the_line() {
+        let script = r#"
+STYLE A1 bold=true
+CHECKSTYLE A1 bold=false
+"#;
+        let err = execute_script(script).unwrap();
+        assert_eq!(err.to_string(), "line 3: expect bold false, found true");
+
+        let err = execute_script("UNDO").unwrap();
+        assert_eq!(err.to_string(), "line 1: nothing to undo");
+
+        let err = execute_script("STYLE A1 shadow=true").unwrap();
+        assert_eq!(err.to_string(), "line 1: unknown style attribute: shadow");
+    }
 }
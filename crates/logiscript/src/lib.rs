@@ -1,6 +1,7 @@
 mod executor;
 mod operator;
 mod parser;
+mod style;
 
 pub use executor::execute_script;
 pub use executor::load_from_script;
@@ -1,8 +1,13 @@
 use logisheets_controller::{
     BlockId,
-    edit_action::{ConvertBlock, CreateBlock, MoveBlock, RemoveBlock, ResizeBlock},
+    edit_action::{
+        CfRuleSpec, ConvertBlock, CreateBlock, MergeCells, MoveBlock, RemoveBlock, ResizeBlock,
+        SetColWidth, SetRowHeight, SheetRename, SplitMergedCells,
+    },
 };
 
+use crate::style::StyleSpec;
+
 #[derive(Debug)]
 pub struct Statement {
     pub op: Operator,
@@ -31,6 +36,32 @@ pub enum Operator {
     BlockInsertCol(BlockShiftData),
     BlockDeleteRow(BlockShiftData),
     BlockDeleteCol(BlockShiftData),
+    Clear(Area),
+    Style(StyleCells),
+    Merge(MergeCells), // ignore the sheet_idx
+    Split(SplitMergedCells),
+    Comment(Comment),
+    DeleteComment(Position),
+    CreateSheet(String),
+    RenameSheet(SheetRename),
+    DeleteSheet(String),
+    SetVisible(Visibility),
+    SetRowHeight(SetRowHeight),
+    SetColWidth(SetColWidth),
+    CreateCfRule(CfRule),
+    ClearCfRules,
+    Undo,
+    Redo,
+    Roundtrip,
+    CheckStyle(CheckStyle),
+    CheckConditionalFormat(CheckConditionalFormat),
+    CheckMerged(Area),
+    CheckUnmerged(Position),
+    CheckVisible(Visibility),
+    CheckComment(CheckComment),
+    CheckSheets(Vec<String>),
+    CheckRowHeight(CheckSize),
+    CheckColWidth(CheckSize),
 }
 
 #[derive(Debug)]
@@ -91,3 +122,78 @@ pub struct BlockShiftData {
     pub from: u32,
     pub cnt: u32,
 }
+
+#[derive(Debug)]
+pub struct Position {
+    pub row: u32,
+    pub col: u32,
+}
+
+#[derive(Debug)]
+pub struct Area {
+    pub start_row: u32,
+    pub start_col: u32,
+    pub end_row: u32,
+    pub end_col: u32,
+}
+
+#[derive(Debug)]
+pub struct StyleCells {
+    pub area: Area,
+    pub style: StyleSpec,
+}
+
+#[derive(Debug)]
+pub struct Comment {
+    pub row: u32,
+    pub col: u32,
+    pub content: String,
+}
+
+#[derive(Debug)]
+pub enum Target {
+    Row(u32),
+    Col(u32),
+    Sheet(String),
+}
+
+#[derive(Debug)]
+pub struct Visibility {
+    pub target: Target,
+    pub visible: bool,
+}
+
+#[derive(Debug)]
+pub struct CfRule {
+    pub area: Area,
+    pub rule: CfRuleSpec,
+}
+
+#[derive(Debug)]
+pub struct CheckStyle {
+    pub row: u32,
+    pub col: u32,
+    pub expect: StyleSpec,
+}
+
+/// `expect` is `None` when no rule should apply to the cell.
+#[derive(Debug)]
+pub struct CheckConditionalFormat {
+    pub row: u32,
+    pub col: u32,
+    pub expect: Option<StyleSpec>,
+}
+
+/// `expect` is `None` when the cell should have no comment.
+#[derive(Debug)]
+pub struct CheckComment {
+    pub row: u32,
+    pub col: u32,
+    pub expect: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct CheckSize {
+    pub idx: u32,
+    pub expect: f64,
+}
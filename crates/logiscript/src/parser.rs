@@ -1,14 +1,17 @@
 use logisheets_controller::edit_action::{
-    ConvertBlock, CreateBlock, MoveBlock, RemoveBlock, ResizeBlock,
+    CfFormatSpec, CfRuleSpec, ConvertBlock, CreateBlock, MergeCells, MoveBlock, RemoveBlock,
+    ResizeBlock, SetColWidth, SetRowHeight, SheetRename, SplitMergedCells,
 };
 use pest::Parser;
 use pest::iterators::Pair;
 use pest_derive::Parser;
 
 use crate::operator::{
-    BlockShiftData, CheckEmpty, CheckError, CheckFormula, CheckNum, CheckString, Input, Operator,
-    ShiftData, Statement, Switch,
+    Area, BlockShiftData, CfRule, CheckComment, CheckConditionalFormat, CheckEmpty, CheckError,
+    CheckFormula, CheckNum, CheckSize, CheckString, CheckStyle, Comment, Input, Operator, Position,
+    ShiftData, Statement, StyleCells, Switch, Target, Visibility,
 };
+use crate::style::{StyleSpec, parse_bool, parse_color};
 
 #[derive(Parser)]
 #[grammar = "grammar.pest"]
@@ -224,10 +227,238 @@ fn parse_op(s: Pair<Rule>) -> Result<Operator, ParseError> {
                 cnt,
             }))
         }
+        Rule::clear_op => {
+            let area = parse_area(s.into_inner().next().unwrap());
+            Ok(Operator::Clear(area))
+        }
+        Rule::style_op => {
+            let mut iter = s.into_inner();
+            let area = parse_area(iter.next().unwrap());
+            let style = StyleSpec::parse(parse_attrs(iter)).map_err(parse_error)?;
+            Ok(Operator::Style(StyleCells { area, style }))
+        }
+        Rule::merge_op => {
+            let range_pair = s.into_inner().next().unwrap();
+            let ((start_row, start_col), (end_row, end_col)) = parse_range(range_pair).unwrap();
+            Ok(Operator::Merge(MergeCells {
+                sheet_idx: 1, // dummy sheet_idx
+                start_row: start_row as usize,
+                start_col: start_col as usize,
+                end_row: end_row as usize,
+                end_col: end_col as usize,
+            }))
+        }
+        Rule::split_op => {
+            let (row, col) = parse_position(s.into_inner().next().unwrap()).unwrap();
+            Ok(Operator::Split(SplitMergedCells {
+                sheet_idx: 1, // dummy sheet_idx
+                row: row as usize,
+                col: col as usize,
+            }))
+        }
+        Rule::comment_op => {
+            let mut iter = s.into_inner();
+            let (row, col) = parse_position(iter.next().unwrap()).unwrap();
+            let content = iter.next().unwrap().as_str().to_string();
+            Ok(Operator::Comment(Comment { row, col, content }))
+        }
+        Rule::delete_comment_op => {
+            let (row, col) = parse_position(s.into_inner().next().unwrap()).unwrap();
+            Ok(Operator::DeleteComment(Position { row, col }))
+        }
+        Rule::create_sheet => {
+            let name = s.into_inner().next().unwrap().as_str().to_string();
+            Ok(Operator::CreateSheet(name))
+        }
+        Rule::rename_sheet => {
+            let mut iter = s.into_inner();
+            let old_name = iter.next().unwrap().as_str().to_string();
+            let new_name = iter.next().unwrap().as_str().to_string();
+            Ok(Operator::RenameSheet(SheetRename {
+                old_name: Some(old_name),
+                idx: None,
+                new_name,
+            }))
+        }
+        Rule::delete_sheet => {
+            let name = s.into_inner().next().unwrap().as_str().to_string();
+            Ok(Operator::DeleteSheet(name))
+        }
+        Rule::hide_op | Rule::show_op => {
+            let visible = s.as_rule() == Rule::show_op;
+            let target = parse_target(s.into_inner().next().unwrap());
+            Ok(Operator::SetVisible(Visibility { target, visible }))
+        }
+        Rule::row_height => {
+            let mut iter = s.into_inner();
+            let row = iter.next().unwrap().as_str().parse::<usize>().unwrap() - 1;
+            let height = iter.next().unwrap().as_str().parse::<f64>().unwrap();
+            Ok(Operator::SetRowHeight(SetRowHeight {
+                sheet_idx: 1, // dummy sheet_idx
+                row,
+                height,
+            }))
+        }
+        Rule::col_width => {
+            let mut iter = s.into_inner();
+            let col = column_label_to_index(iter.next().unwrap().as_str()) as usize;
+            let width = iter.next().unwrap().as_str().parse::<f64>().unwrap();
+            Ok(Operator::SetColWidth(SetColWidth {
+                sheet_idx: 1, // dummy sheet_idx
+                col,
+                width,
+            }))
+        }
+        Rule::cf_rule => {
+            let mut iter = s.into_inner();
+            let area = parse_area(iter.next().unwrap());
+            let ty = iter.next().unwrap().as_str().to_string();
+            let rule = parse_cf_rule(ty, parse_attrs(iter)).map_err(parse_error)?;
+            Ok(Operator::CreateCfRule(CfRule { area, rule }))
+        }
+        Rule::cf_clear => Ok(Operator::ClearCfRules),
+        Rule::undo_op => Ok(Operator::Undo),
+        Rule::redo_op => Ok(Operator::Redo),
+        Rule::roundtrip_op => Ok(Operator::Roundtrip),
+        Rule::checkstyle_op => {
+            let mut iter = s.into_inner();
+            let (row, col) = parse_position(iter.next().unwrap()).unwrap();
+            let expect = StyleSpec::parse(parse_attrs(iter)).map_err(parse_error)?;
+            Ok(Operator::CheckStyle(CheckStyle { row, col, expect }))
+        }
+        Rule::checkcf_op => {
+            let mut iter = s.into_inner().peekable();
+            let (row, col) = parse_position(iter.next().unwrap()).unwrap();
+            let expect = if iter.peek().unwrap().as_rule() == Rule::none {
+                None
+            } else {
+                Some(StyleSpec::parse(parse_attrs(iter)).map_err(parse_error)?)
+            };
+            Ok(Operator::CheckConditionalFormat(CheckConditionalFormat {
+                row,
+                col,
+                expect,
+            }))
+        }
+        Rule::checkmerged_op => {
+            let range_pair = s.into_inner().next().unwrap();
+            let ((start_row, start_col), (end_row, end_col)) = parse_range(range_pair).unwrap();
+            Ok(Operator::CheckMerged(Area {
+                start_row,
+                start_col,
+                end_row,
+                end_col,
+            }))
+        }
+        Rule::checkunmerged_op => {
+            let (row, col) = parse_position(s.into_inner().next().unwrap()).unwrap();
+            Ok(Operator::CheckUnmerged(Position { row, col }))
+        }
+        Rule::checkhidden_op | Rule::checkvisible_op => {
+            let visible = s.as_rule() == Rule::checkvisible_op;
+            let target = parse_target(s.into_inner().next().unwrap());
+            Ok(Operator::CheckVisible(Visibility { target, visible }))
+        }
+        Rule::checkcomment_op => {
+            let mut iter = s.into_inner();
+            let (row, col) = parse_position(iter.next().unwrap()).unwrap();
+            let expect = iter.next().unwrap();
+            let expect = match expect.as_rule() {
+                Rule::none => None,
+                _ => Some(expect.as_str().to_string()),
+            };
+            Ok(Operator::CheckComment(CheckComment { row, col, expect }))
+        }
+        Rule::checksheets_op => {
+            let names = s.into_inner().map(|p| p.as_str().to_string()).collect();
+            Ok(Operator::CheckSheets(names))
+        }
+        Rule::checkheight_op => {
+            let mut iter = s.into_inner();
+            let idx = iter.next().unwrap().as_str().parse::<u32>().unwrap() - 1;
+            let expect = iter.next().unwrap().as_str().parse::<f64>().unwrap();
+            Ok(Operator::CheckRowHeight(CheckSize { idx, expect }))
+        }
+        Rule::checkwidth_op => {
+            let mut iter = s.into_inner();
+            let idx = column_label_to_index(iter.next().unwrap().as_str());
+            let expect = iter.next().unwrap().as_str().parse::<f64>().unwrap();
+            Ok(Operator::CheckColWidth(CheckSize { idx, expect }))
+        }
         _ => unreachable!(),
     }
 }
 
+fn parse_error(msg: String) -> ParseError {
+    // The line is filled in by `parse`.
+    ParseError { line: 0, msg }
+}
+
+fn parse_area(p: Pair<Rule>) -> Area {
+    let inner = p.into_inner().next().unwrap();
+    let ((start_row, start_col), (end_row, end_col)) = match inner.as_rule() {
+        Rule::range => parse_range(inner).unwrap(),
+        _ => {
+            let pos = parse_position(inner).unwrap();
+            (pos, pos)
+        }
+    };
+    Area {
+        start_row,
+        start_col,
+        end_row,
+        end_col,
+    }
+}
+
+fn parse_target(p: Pair<Rule>) -> Target {
+    match p.as_rule() {
+        Rule::row => Target::Row(p.as_str().parse::<u32>().unwrap() - 1),
+        Rule::col => Target::Col(column_label_to_index(p.as_str())),
+        _ => Target::Sheet(p.into_inner().next().unwrap().as_str().to_string()),
+    }
+}
+
+fn parse_attrs<'a>(pairs: impl Iterator<Item = Pair<'a, Rule>>) -> Vec<(String, String)> {
+    pairs
+        .map(|attr| {
+            let mut iter = attr.into_inner();
+            let key = iter.next().unwrap().as_str().to_string();
+            let value = iter.next().unwrap().as_str();
+            let value = value
+                .strip_prefix('"')
+                .and_then(|v| v.strip_suffix('"'))
+                .unwrap_or(value);
+            (key, value.to_string())
+        })
+        .collect()
+}
+
+fn parse_cf_rule(ty: String, attrs: Vec<(String, String)>) -> Result<CfRuleSpec, String> {
+    let mut rule = CfRuleSpec {
+        ty,
+        above_average: true,
+        ..Default::default()
+    };
+    let mut format = CfFormatSpec::default();
+    for (key, value) in attrs {
+        match key.to_ascii_lowercase().as_str() {
+            "operator" => rule.operator = Some(value),
+            "operand" => rule.operands.push(value),
+            "text" => rule.text = Some(value),
+            "stop" => rule.stop_if_true = parse_bool(&value)?,
+            "fill" => format.fill_color = Some(format!("FF{}", parse_color(&value)?)),
+            "color" => format.font_color = Some(format!("FF{}", parse_color(&value)?)),
+            "bold" => format.bold = Some(parse_bool(&value)?),
+            "italic" => format.italic = Some(parse_bool(&value)?),
+            "strike" => format.strike = Some(parse_bool(&value)?),
+            _ => return Err(format!("unknown conditional formatting attribute: {}", key)),
+        }
+    }
+    rule.format = Some(format);
+    Ok(rule)
+}
+
 fn column_label_to_index(label: &str) -> u32 {
     let mut result: u32 = 0;
     for (i, c) in label.chars().rev().enumerate() {
@@ -261,7 +492,10 @@ pub fn parse(s: &str) -> Result<Vec<Statement>, ParseError> {
             continue;
         }
         if let Some(token) = lex(line) {
-            let op = parse_op(token)?;
+            let op = parse_op(token).map_err(|e| ParseError {
+                line: idx + 1,
+                msg: e.msg,
+            })?;
             result.push(Statement { op, line: idx + 1 });
         } else {
             return Err(ParseError {
@@ -0,0 +1,280 @@
+use logisheets_controller::controller::style::{Color, PatternFill, from_hex_str};
+use logisheets_controller::edit_action::{
+    Alignment, HorizontalAlignment, StyleUpdateType, VerticalAlignment,
+};
+use logisheets_controller::{BorderPr, Fill, Style};
+use logisheets_workbook::prelude::{StBorderStyle, StPatternType, StUnderlineValues};
+
+/// The style attributes a script can set with `STYLE` and assert with
+/// `CHECKSTYLE`. Colors are kept as `RRGGBB`.
+#[derive(Debug, Default)]
+pub struct StyleSpec {
+    pub bold: Option<bool>,
+    pub italic: Option<bool>,
+    pub strike: Option<bool>,
+    pub underline: Option<String>,
+    pub size: Option<f64>,
+    pub font: Option<String>,
+    pub color: Option<String>,
+    pub fill: Option<String>,
+    pub num_fmt: Option<String>,
+    pub halign: Option<String>,
+    pub valign: Option<String>,
+    pub wrap: Option<bool>,
+    pub border: Option<String>,
+}
+
+const UNDERLINES: &[StUnderlineValues] = &[
+    StUnderlineValues::Single,
+    StUnderlineValues::Double,
+    StUnderlineValues::SingleAccounting,
+    StUnderlineValues::DoubleAccounting,
+    StUnderlineValues::None,
+];
+
+const BORDERS: &[StBorderStyle] = &[
+    StBorderStyle::None,
+    StBorderStyle::Thin,
+    StBorderStyle::Medium,
+    StBorderStyle::Dashed,
+    StBorderStyle::Dotted,
+    StBorderStyle::Thick,
+    StBorderStyle::Double,
+    StBorderStyle::Hair,
+    StBorderStyle::MediumDashed,
+    StBorderStyle::DashDot,
+    StBorderStyle::MediumDashDot,
+    StBorderStyle::DashDotDot,
+    StBorderStyle::MediumDashDotDot,
+    StBorderStyle::SlantDashDot,
+];
+
+const HALIGNS: &[HorizontalAlignment] = &[
+    HorizontalAlignment::General,
+    HorizontalAlignment::Left,
+    HorizontalAlignment::Center,
+    HorizontalAlignment::Right,
+    HorizontalAlignment::Fill,
+    HorizontalAlignment::Justify,
+    HorizontalAlignment::CenterContinuous,
+    HorizontalAlignment::Distributed,
+];
+
+const VALIGNS: &[VerticalAlignment] = &[
+    VerticalAlignment::Center,
+    VerticalAlignment::Top,
+    VerticalAlignment::Bottom,
+    VerticalAlignment::Justify,
+    VerticalAlignment::Distributed,
+];
+
+/// Find the variant whose name is `name`, ignoring case.
+fn find_variant<T: std::fmt::Debug + Clone>(variants: &[T], name: &str) -> Option<T> {
+    variants
+        .iter()
+        .find(|v| format!("{:?}", v).eq_ignore_ascii_case(name))
+        .cloned()
+}
+
+fn same_name<T: std::fmt::Debug>(v: Option<&T>, name: &str) -> bool {
+    match v {
+        Some(v) => format!("{:?}", v).eq_ignore_ascii_case(name),
+        None => name.eq_ignore_ascii_case("none") || name.eq_ignore_ascii_case("general"),
+    }
+}
+
+pub fn parse_bool(v: &str) -> Result<bool, String> {
+    match v.to_ascii_lowercase().as_str() {
+        "true" => Ok(true),
+        "false" => Ok(false),
+        _ => Err(format!("expect true or false, found {}", v)),
+    }
+}
+
+/// Accept `RRGGBB` or `AARRGGBB` and return `RRGGBB` in upper case.
+pub fn parse_color(v: &str) -> Result<String, String> {
+    let v = v.trim_start_matches('#');
+    let valid = (v.len() == 6 || v.len() == 8) && v.chars().all(|c| c.is_ascii_hexdigit());
+    if !valid {
+        return Err(format!("expect a hex color like FF0000, found {}", v));
+    }
+    Ok(v[v.len() - 6..].to_ascii_uppercase())
+}
+
+fn color_to_hex(c: &Color) -> String {
+    let channel = |v: Option<f64>| v.unwrap_or(0.).round().clamp(0., 255.) as u8;
+    format!(
+        "{:02X}{:02X}{:02X}",
+        channel(c.red),
+        channel(c.green),
+        channel(c.blue)
+    )
+}
+
+impl StyleSpec {
+    pub fn parse(attrs: Vec<(String, String)>) -> Result<StyleSpec, String> {
+        let mut spec = StyleSpec::default();
+        for (key, value) in attrs {
+            match key.to_ascii_lowercase().as_str() {
+                "bold" => spec.bold = Some(parse_bool(&value)?),
+                "italic" => spec.italic = Some(parse_bool(&value)?),
+                "strike" => spec.strike = Some(parse_bool(&value)?),
+                "wrap" => spec.wrap = Some(parse_bool(&value)?),
+                "size" => {
+                    let size = value
+                        .parse::<f64>()
+                        .map_err(|_| format!("expect a font size, found {}", value))?;
+                    spec.size = Some(size)
+                }
+                "font" => spec.font = Some(value),
+                "color" => spec.color = Some(parse_color(&value)?),
+                "fill" => spec.fill = Some(parse_color(&value)?),
+                "numfmt" => spec.num_fmt = Some(value),
+                "underline" => {
+                    find_variant(UNDERLINES, &value)
+                        .ok_or(format!("unknown underline: {}", value))?;
+                    spec.underline = Some(value)
+                }
+                "border" => {
+                    find_variant(BORDERS, &value).ok_or(format!("unknown border: {}", value))?;
+                    spec.border = Some(value)
+                }
+                "halign" => {
+                    find_variant(HALIGNS, &value)
+                        .ok_or(format!("unknown horizontal alignment: {}", value))?;
+                    spec.halign = Some(value)
+                }
+                "valign" => {
+                    find_variant(VALIGNS, &value)
+                        .ok_or(format!("unknown vertical alignment: {}", value))?;
+                    spec.valign = Some(value)
+                }
+                _ => return Err(format!("unknown style attribute: {}", key)),
+            }
+        }
+        Ok(spec)
+    }
+
+    pub fn to_update(&self) -> StyleUpdateType {
+        let border = self.border.as_ref().and_then(|b| find_variant(BORDERS, b));
+        let alignment = if self.halign.is_some() || self.valign.is_some() || self.wrap.is_some() {
+            Some(Alignment {
+                horizontal: self.halign.as_ref().and_then(|h| find_variant(HALIGNS, h)),
+                vertical: self.valign.as_ref().and_then(|v| find_variant(VALIGNS, v)),
+                wrap_text: self.wrap,
+            })
+        } else {
+            None
+        };
+        StyleUpdateType {
+            set_font_bold: self.bold,
+            set_font_italic: self.italic,
+            set_font_strike: self.strike,
+            set_font_underline: self
+                .underline
+                .as_ref()
+                .and_then(|u| find_variant(UNDERLINES, u)),
+            set_font_size: self.size,
+            set_font_name: self.font.clone(),
+            set_font_color: self.color.as_ref().map(|c| format!("FF{}", c)),
+            set_pattern_fill: self.fill.as_ref().map(|c| PatternFill {
+                fg_color: Some(from_hex_str(format!("FF{}", c), 0.)),
+                bg_color: None,
+                pattern_type: Some(StPatternType::Solid),
+            }),
+            set_num_fmt: self.num_fmt.clone(),
+            set_alignment: alignment,
+            set_left_border_style: border.clone(),
+            set_right_border_style: border.clone(),
+            set_top_border_style: border.clone(),
+            set_bottom_border_style: border,
+            ..Default::default()
+        }
+    }
+
+    /// Compare the attributes this spec sets against `style`, describing
+    /// the first mismatch.
+    pub fn check(&self, style: &Style) -> Result<(), String> {
+        fn expect<T: std::fmt::Debug>(
+            name: &str,
+            ok: bool,
+            expect: T,
+            found: String,
+        ) -> Result<(), String> {
+            if ok {
+                Ok(())
+            } else {
+                Err(format!("expect {} {:?}, found {}", name, expect, found))
+            }
+        }
+        let font = &style.font;
+        if let Some(b) = self.bold {
+            expect("bold", font.bold == b, b, font.bold.to_string())?;
+        }
+        if let Some(b) = self.italic {
+            expect("italic", font.italic == b, b, font.italic.to_string())?;
+        }
+        if let Some(b) = self.strike {
+            expect("strike", font.strike == b, b, font.strike.to_string())?;
+        }
+        if let Some(u) = &self.underline {
+            let found = font.underline.as_ref().map(|u| &u.val);
+            expect("underline", same_name(found, u), u, format!("{:?}", found))?;
+        }
+        if let Some(sz) = self.size {
+            let found = font.sz.unwrap_or_default();
+            expect("size", (found - sz).abs() < 1e-6, sz, found.to_string())?;
+        }
+        if let Some(name) = &self.font {
+            let found = font
+                .name
+                .as_ref()
+                .map(|n| n.val.clone())
+                .unwrap_or_default();
+            expect("font", &found == name, name, found)?;
+        }
+        if let Some(c) = &self.color {
+            let found = font.color.as_ref().map(color_to_hex).unwrap_or_default();
+            expect("color", &found == c, c, found)?;
+        }
+        if let Some(c) = &self.fill {
+            let found = match &style.fill {
+                Fill::PatternFill(p) => p.fg_color.as_ref().map(color_to_hex),
+                Fill::GradientFill(_) => None,
+            }
+            .unwrap_or_default();
+            expect("fill", &found == c, c, found)?;
+        }
+        if let Some(f) = &self.num_fmt {
+            expect("numfmt", &style.formatter == f, f, style.formatter.clone())?;
+        }
+        let alignment = style.alignment.as_ref();
+        if let Some(h) = &self.halign {
+            let found = alignment.and_then(|a| a.horizontal.as_ref());
+            expect("halign", same_name(found, h), h, format!("{:?}", found))?;
+        }
+        if let Some(v) = &self.valign {
+            let found = alignment.and_then(|a| a.vertical.as_ref());
+            expect("valign", same_name(found, v), v, format!("{:?}", found))?;
+        }
+        if let Some(w) = self.wrap {
+            let found = alignment.and_then(|a| a.wrap_text).unwrap_or(false);
+            expect("wrap", found == w, w, found.to_string())?;
+        }
+        if let Some(b) = &self.border {
+            let border = &style.border;
+            let sides: [(&str, &Option<BorderPr>); 4] = [
+                ("left", &border.left),
+                ("right", &border.right),
+                ("top", &border.top),
+                ("bottom", &border.bottom),
+            ];
+            for (side, pr) in sides {
+                let found = pr.as_ref().map(|p| &p.style);
+                let name = format!("{} border", side);
+                expect(&name, same_name(found, b), b, format!("{:?}", found))?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,139 @@
+//! What a grid cell shows: the formatted text fitted to the cell's width and
+//! the colour of the format section that rendered it.
+//!
+//! Not part of upstream `ssf`, which formats for `TEXT()` and has no notion
+//! of a cell. The width rules follow Excel: a number that does not fit shows
+//! as `#` across the cell, except in the General format, which first gives up
+//! decimals and then switches to scientific notation. Text is never cut; it
+//! spills into the neighbouring cells instead.
+
+use crate::evalfmt::Value;
+use crate::format::{choose_fmt, format};
+use crate::helpers::{fill, isgeneral};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CellText {
+    pub text: String,
+    /// The section's `[Red]` or `[Color10]`, as a number in Excel's 56-colour
+    /// palette (`[Black]` is 1, `[White]` 2, `[Red]` 3, ... `[Cyan]` 8).
+    pub color: Option<u8>,
+}
+
+/// Format `v` for a cell `width` characters wide. `None` leaves the text as
+/// long as it is.
+pub fn format_cell(
+    fmt: &str,
+    v: &Value,
+    width: Option<usize>,
+    date1904: bool,
+) -> Result<CellText, String> {
+    let text = format(fmt, v, date1904)?;
+    let color = section_color(fmt, v)?;
+    let text = match (v, width) {
+        (Value::Num(n), Some(width)) if text.chars().count() > width => {
+            if isgeneral(fmt, 0) || isgeneral(&choose_fmt(fmt, v)?.1, 0) {
+                general_fit(*n, width)
+            } else {
+                fill('#', width)
+            }
+        }
+        _ => text,
+    };
+    Ok(CellText { text, color })
+}
+
+/// The colour of the section of `fmt` that formats `v`, picked the way
+/// `format` picks the section: by sign, or by the `[<0]`-style conditions.
+pub fn section_color(fmt: &str, v: &Value) -> Result<Option<u8>, String> {
+    if isgeneral(fmt, 0) {
+        return Ok(None);
+    }
+    let (_, section) = choose_fmt(fmt, v)?;
+    Ok(find_color(&section))
+}
+
+fn find_color(section: &str) -> Option<u8> {
+    let mut chars = section.chars();
+    let mut in_str = false;
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => in_str = !in_str,
+            '\\' | '_' | '*' if !in_str => {
+                chars.next();
+            }
+            '[' if !in_str => {
+                let token = chars.by_ref().take_while(|c| *c != ']').collect::<String>();
+                if let Some(n) = color_number(&token) {
+                    return Some(n);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn color_number(token: &str) -> Option<u8> {
+    let token = token.to_ascii_lowercase();
+    let n = match token.as_str() {
+        "black" => 1,
+        "white" => 2,
+        "red" => 3,
+        "green" => 4,
+        "blue" => 5,
+        "yellow" => 6,
+        "magenta" => 7,
+        "cyan" => 8,
+        _ => token.strip_prefix("color")?.parse::<u8>().ok()?,
+    };
+    (1..=56).contains(&n).then_some(n)
+}
+
+/// General in a narrow cell: round away decimals until the number fits, then
+/// fall back to scientific notation with as many digits as fit.
+fn general_fit(v: f64, width: usize) -> String {
+    fixed_fit(v, width)
+        .or_else(|| scientific_fit(v, width))
+        .unwrap_or_else(|| fill('#', width))
+}
+
+fn fixed_fit(v: f64, width: usize) -> Option<String> {
+    let int_len = format!("{:.0}", v.trunc()).len();
+    if int_len > width {
+        return None;
+    }
+    let max_decimals = width.saturating_sub(int_len + 1);
+    for decimals in (0..=max_decimals).rev() {
+        let s = strip_zeros(&format!("{:.*}", decimals, v));
+        if s.len() > width {
+            continue;
+        }
+        // Rounding a small number down to zero loses it: use scientific.
+        if v != 0. && !s.bytes().any(|b| matches!(b, b'1'..=b'9')) {
+            return None;
+        }
+        return Some(s);
+    }
+    None
+}
+
+fn scientific_fit(v: f64, width: usize) -> Option<String> {
+    for digits in (0..=width.min(14)).rev() {
+        let s = format!("{:.*e}", digits, v);
+        let (mantissa, exp) = s.split_once('e')?;
+        let exp = exp.parse::<i32>().ok()?;
+        let sign = if exp < 0 { '-' } else { '+' };
+        let s = format!("{}E{}{:02}", strip_zeros(mantissa), sign, exp.abs());
+        if s.len() <= width {
+            return Some(s);
+        }
+    }
+    None
+}
+
+fn strip_zeros(s: &str) -> String {
+    if !s.contains('.') {
+        return s.to_string();
+    }
+    s.trim_end_matches('0').trim_end_matches('.').to_string()
+}
@@ -7,6 +7,7 @@ use std::sync::OnceLock;
 use crate::datecode::{parse_date_code, write_date, DateCode};
 use crate::general;
 use crate::jsnum;
+use crate::locale::SectionLocale;
 use crate::writenum::write_num;
 
 /// A value to be formatted: a number or text.
@@ -277,6 +278,17 @@ const ALLOWED: &str = ",$-+/():!^&'~{}<>=€acfijklopqrtuvwxzP";
 
 /// `ssf.eval_fmt(fmt, v, opts, flen)`.
 pub fn eval_fmt(fmt: &str, v: &Value, date1904: bool, flen: usize) -> Result<String, String> {
+    eval_fmt_with(fmt, v, date1904, flen, None)
+}
+
+/// `eval_fmt`, rendering names, separators and numerals for `loc` when given.
+pub(crate) fn eval_fmt_with(
+    fmt: &str,
+    v: &Value,
+    date1904: bool,
+    flen: usize,
+    loc: Option<&SectionLocale>,
+) -> Result<String, String> {
     let vnum = v.as_num();
     let mut ev = Ev {
         out: Vec::new(),
@@ -367,7 +379,12 @@ pub fn eval_fmt(fmt: &str, v: &Value, date1904: bool, flen: usize) -> Result<Str
                     ev.i += 3;
                 } else if chars_substr_upper(&ev.f, ev.i, 5) == "AM/PM" {
                     if let Some(dt) = &ev.dt {
-                        q.v = if dt.h >= 12 { "PM" } else { "AM" }.into();
+                        q.v = match loc {
+                            Some(loc) => loc.am_pm(dt.h >= 12),
+                            None if dt.h >= 12 => "PM",
+                            None => "AM",
+                        }
+                        .into();
                     }
                     q.t = 'T';
                     ev.i += 5;
@@ -419,14 +436,24 @@ pub fn eval_fmt(fmt: &str, v: &Value, date1904: bool, flen: usize) -> Result<Str
                         .captures(&o)
                         .and_then(|c| c.get(1))
                         .map(|m| m.as_str())
-                        .filter(|s| !s.is_empty())
-                        .unwrap_or("$")
-                        .to_string();
-                    if !fmt_is_date(fmt) {
+                        .filter(|s| !s.is_empty());
+                    // A bare locale token like `[$-407]` shows no symbol when
+                    // rendering for a locale.
+                    let sym = match (sym, loc) {
+                        (None, Some(_)) => None,
+                        (sym, _) => Some(sym.unwrap_or("$").to_string()),
+                    };
+                    if let Some(sym) = sym.filter(|_| !fmt_is_date(fmt)) {
                         ev.out.push(Tok { t: 't', v: sym });
                     }
                 }
             }
+            // Rendering for a locale, a `.` between date fields is a literal
+            // (the Japanese `ge.m.d`); upstream only takes it as subseconds.
+            '.' if loc.is_some() && ev.dt.is_some() && ev.f.get(ev.i + 1) != Some(&'0') => {
+                ev.out.push(Tok { t: 't', v: ".".into() });
+                ev.i += 1;
+            }
             '.' => {
                 if ev.dt.is_some() {
                     let mut o = String::from('.');
@@ -574,6 +601,17 @@ pub fn eval_fmt(fmt: &str, v: &Value, date1904: bool, flen: usize) -> Result<Str
         }
     }
 
+    // Remember where each token came from: the locale's separators and
+    // numerals apply to rendered numbers and dates, not to literal text.
+    let origins: Vec<char> = ev
+        .out
+        .iter()
+        .map(|tok| match (tok.t, v) {
+            ('G', Value::Text(_)) => 't',
+            (t, _) => t,
+        })
+        .collect();
+
     // --- replace fields ---
     let out = &mut ev.out;
     let mut nstr = String::new();
@@ -587,9 +625,20 @@ pub fn eval_fmt(fmt: &str, v: &Value, date1904: bool, flen: usize) -> Result<Str
             }
             'd' | 'm' | 'y' | 'h' | 'H' | 'M' | 's' | 'e' | 'b' | 'Z' => {
                 let dt = ev.dt.as_ref().unwrap();
-                out[i].v = write_date(out[i].t, &out[i].v, dt, ss0)?;
+                out[i].v = match loc.and_then(|loc| loc.write_date(out[i].t, &out[i].v, dt)) {
+                    Some(s) => s,
+                    None => write_date(out[i].t, &out[i].v, dt, ss0)?,
+                };
                 out[i].t = 't';
             }
+            // Upstream leaves the era name unrendered.
+            'g' => {
+                if let Some(loc) = loc {
+                    let dt = ev.dt.as_ref().unwrap();
+                    out[i].v = loc.write_date('g', &out[i].v, dt).unwrap_or_default();
+                    out[i].t = 't';
+                }
+            }
             'n' | '?' => {
                 let mut jj = i + 1;
                 loop {
@@ -756,6 +805,13 @@ pub fn eval_fmt(fmt: &str, v: &Value, date1904: bool, flen: usize) -> Result<Str
         }
     }
 
+    if let Some(loc) = loc {
+        let parts: Vec<(char, String)> = origins
+            .into_iter()
+            .zip(ev.out.into_iter().map(|tok| tok.v))
+            .collect();
+        return Ok(loc.finish(&parts));
+    }
     let mut retval = String::new();
     for tok in &ev.out {
         retval.push_str(&tok.v);
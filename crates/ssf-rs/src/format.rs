@@ -4,9 +4,10 @@
 use regex::Regex;
 use std::sync::OnceLock;
 
-use crate::evalfmt::{eval_fmt, fmt_is_date, split_fmt, Value};
+use crate::evalfmt::{eval_fmt_with, fmt_is_date, split_fmt, Value};
 use crate::general::general_fmt_num_value;
 use crate::helpers::isgeneral;
+use crate::locale::{locale, section_locale};
 use crate::tables::resolve_id;
 
 fn cfregex() -> &'static Regex {
@@ -43,7 +44,7 @@ fn chkcond(v: f64, rr: &Option<(String, f64)>) -> bool {
 }
 
 /// `ssf.choose_fmt(f, v)` -> `(flen, chosen_format)`.
-fn choose_fmt(f: &str, v: &Value) -> Result<(usize, String), String> {
+pub(crate) fn choose_fmt(f: &str, v: &Value) -> Result<(usize, String), String> {
     let mut fmt = split_fmt(f)?;
     let mut l = fmt.len();
     let lat = fmt[l - 1].contains('@');
@@ -119,32 +120,67 @@ fn choose_fmt(f: &str, v: &Value) -> Result<(usize, String), String> {
 
 /// `ssf.format(fmt, v, opts)` for a string format code.
 pub fn format(fmt: &str, v: &Value, date1904: bool) -> Result<String, String> {
+    render(fmt, v, date1904, None)
+}
+
+/// `ssf.format` for a numeric format id.
+pub fn format_id(id: u16, v: &Value, date1904: bool) -> Result<String, String> {
+    let sfmt = resolve_id(id);
+    format(&sfmt, v, date1904)
+}
+
+/// `format` for the locale with the Windows LCID `lcid` (`0x407` is de-DE):
+/// its decimal and thousands separators, month and day names, AM/PM and
+/// calendars. A `[$-...]` token in the format overrides `lcid`. Not in
+/// upstream `ssf`; see the `locale` module.
+pub fn format_with_locale(
+    fmt: &str,
+    v: &Value,
+    lcid: u32,
+    date1904: bool,
+) -> Result<String, String> {
+    render(fmt, v, date1904, Some(lcid))
+}
+
+/// `format_id` for a locale. The currency formats 5-8 and the short dates 14
+/// and 22 follow the locale, the way Excel localizes its built-in formats.
+pub fn format_id_with_locale(
+    id: u16,
+    v: &Value,
+    lcid: u32,
+    date1904: bool,
+) -> Result<String, String> {
+    let sfmt = locale(lcid)
+        .and_then(|l| l.builtin(id))
+        .unwrap_or_else(|| resolve_id(id));
+    format_with_locale(&sfmt, v, lcid, date1904)
+}
+
+fn render(fmt: &str, v: &Value, date1904: bool, lcid: Option<u32>) -> Result<String, String> {
     let sfmt = fmt;
     if isgeneral(sfmt, 0) {
-        return Ok(general_of(v));
+        return Ok(general_of(v, sfmt, sfmt, lcid));
     }
     let f = choose_fmt(sfmt, v)?;
     if isgeneral(&f.1, 0) {
-        return Ok(general_of(v));
+        return Ok(general_of(v, sfmt, &f.1, lcid));
     }
     if let Value::Text(t) = v {
         if t.is_empty() {
             return Ok(String::new());
         }
     }
-    eval_fmt(&f.1, v, date1904, f.0)
+    let loc = lcid.map(|lcid| section_locale(sfmt, &f.1, lcid));
+    eval_fmt_with(&f.1, v, date1904, f.0, loc.as_ref())
 }
 
-/// `ssf.format` for a numeric format id.
-pub fn format_id(id: u16, v: &Value, date1904: bool) -> Result<String, String> {
-    let sfmt = resolve_id(id);
-    format(&sfmt, v, date1904)
-}
-
-fn general_of(v: &Value) -> String {
-    match v {
-        Value::Num(n) => general_fmt_num_value(*n),
-        Value::Text(t) => t.clone(),
+fn general_of(v: &Value, fmt: &str, section: &str, lcid: Option<u32>) -> String {
+    match (v, lcid) {
+        (Value::Num(n), Some(lcid)) => {
+            section_locale(fmt, section, lcid).finish(&[('G', general_fmt_num_value(*n))])
+        }
+        (Value::Num(n), None) => general_fmt_num_value(*n),
+        (Value::Text(t), _) => t.clone(),
     }
 }
 
@@ -16,16 +16,20 @@
 //! ```
 
 pub mod datecode;
+pub mod display;
 pub mod evalfmt;
 pub mod format;
 pub mod general;
 pub mod helpers;
 pub mod jsnum;
+pub mod locale;
 pub mod tables;
 pub mod writenum;
 
+pub use display::{format_cell, section_color, CellText};
 pub use evalfmt::{fmt_is_date as is_date, Value};
-pub use format::{format, format_id};
+pub use format::{format, format_id, format_id_with_locale, format_with_locale};
+pub use locale::{locale, Calendar, Locale};
 
 /// Convenience: format a number `val` with a string format code.
 pub fn format_str(fmt: &str, val: f64) -> Result<String, String> {
@@ -0,0 +1,915 @@
+//! Locale-aware rendering for `format_with_locale`: separators, month and day
+//! names, AM/PM designators and currency formats keyed by Windows LCID, plus
+//! the East Asian era calendars and the `[DBNum1]`-`[DBNum3]` numerals.
+//!
+//! Not part of upstream `ssf`, which renders en-US only. Format codes stay
+//! canonical (`.` is the decimal point, `,` the thousands separator); the
+//! locale only changes what gets rendered. A section can name its own locale
+//! with an LCID token, `[$-407]` or `[$€-407]`, or a language tag,
+//! `[$-ja-JP]`. The third byte of a hex LCID picks the calendar: `[$-30411]`
+//! is the Japanese era calendar, `[$-40404]` the Republic of China one.
+//! Names and separators follow CLDR; `tests/locale_diff.rs` checks them
+//! against Node's `Intl`.
+
+use crate::datecode::DateCode;
+use crate::helpers::pad0;
+
+/// The writing system a locale uses for `[DBNum]` numerals.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Script {
+    Latin,
+    Japanese,
+    SimplifiedChinese,
+    TraditionalChinese,
+    Korean,
+}
+
+/// The calendar behind the `e` (era year) and `g` (era name) codes. `y`
+/// always counts Gregorian years.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Calendar {
+    Gregorian,
+    JapaneseEra,
+    /// Republic of China (民國) years, counted from 1912.
+    TaiwanRoc,
+    /// Dangi years, counted from 2333 BC.
+    KoreanTangun,
+    /// Buddhist years, 543 ahead of the Gregorian year.
+    ThaiBuddhist,
+}
+
+#[derive(Debug)]
+pub struct Locale {
+    pub lcid: u32,
+    pub tag: &'static str,
+    pub decimal: char,
+    pub group: char,
+    pub months: [&'static str; 12],
+    pub months_short: [&'static str; 12],
+    /// Sunday first.
+    pub days: [&'static str; 7],
+    pub days_short: [&'static str; 7],
+    pub am_pm: [&'static str; 2],
+    /// The positive section of built-in format 5, which places the symbol.
+    /// `None` keeps the built-in table's `"$"` formats.
+    pub currency_format: Option<&'static str>,
+    /// Built-in format 14, the short date.
+    pub short_date: &'static str,
+    /// The calendar `e` and `g` use when the LCID token does not pick one.
+    pub era_calendar: Calendar,
+    pub script: Script,
+}
+
+const EN_MONTHS: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+const EN_DAYS: [&str; 7] = [
+    "Sunday",
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+];
+const EN_DAYS_SHORT: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+const AM_PM: [&str; 2] = ["AM", "PM"];
+const NUMBERED_MONTHS: [&str; 12] = [
+    "1月", "2月", "3月", "4月", "5月", "6月", "7月", "8月", "9月", "10月", "11月", "12月",
+];
+const KO_MONTHS: [&str; 12] = [
+    "1월", "2월", "3월", "4월", "5월", "6월", "7월", "8월", "9월", "10월", "11월", "12월",
+];
+const ZH_DAYS: [&str; 7] = [
+    "星期日",
+    "星期一",
+    "星期二",
+    "星期三",
+    "星期四",
+    "星期五",
+    "星期六",
+];
+
+static LOCALES: [Locale; 12] = [
+    Locale {
+        lcid: 0x409,
+        tag: "en-US",
+        decimal: '.',
+        group: ',',
+        months: EN_MONTHS,
+        months_short: [
+            "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+        ],
+        days: EN_DAYS,
+        days_short: EN_DAYS_SHORT,
+        am_pm: AM_PM,
+        currency_format: None,
+        short_date: "m/d/yy",
+        era_calendar: Calendar::Gregorian,
+        script: Script::Latin,
+    },
+    Locale {
+        lcid: 0x809,
+        tag: "en-GB",
+        decimal: '.',
+        group: ',',
+        months: EN_MONTHS,
+        months_short: [
+            "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sept", "Oct", "Nov", "Dec",
+        ],
+        days: EN_DAYS,
+        days_short: EN_DAYS_SHORT,
+        am_pm: AM_PM,
+        currency_format: Some("\"£\"#,##0"),
+        short_date: "dd/mm/yyyy",
+        era_calendar: Calendar::Gregorian,
+        script: Script::Latin,
+    },
+    Locale {
+        lcid: 0x407,
+        tag: "de-DE",
+        decimal: ',',
+        group: '.',
+        months: [
+            "Januar",
+            "Februar",
+            "März",
+            "April",
+            "Mai",
+            "Juni",
+            "Juli",
+            "August",
+            "September",
+            "Oktober",
+            "November",
+            "Dezember",
+        ],
+        months_short: [
+            "Jan", "Feb", "Mär", "Apr", "Mai", "Jun", "Jul", "Aug", "Sep", "Okt", "Nov", "Dez",
+        ],
+        days: [
+            "Sonntag",
+            "Montag",
+            "Dienstag",
+            "Mittwoch",
+            "Donnerstag",
+            "Freitag",
+            "Samstag",
+        ],
+        days_short: ["So", "Mo", "Di", "Mi", "Do", "Fr", "Sa"],
+        am_pm: AM_PM,
+        currency_format: Some("#,##0 \"€\""),
+        short_date: "dd\\.mm\\.yyyy",
+        era_calendar: Calendar::Gregorian,
+        script: Script::Latin,
+    },
+    Locale {
+        lcid: 0x40C,
+        tag: "fr-FR",
+        decimal: ',',
+        group: '\u{A0}',
+        months: [
+            "janvier",
+            "février",
+            "mars",
+            "avril",
+            "mai",
+            "juin",
+            "juillet",
+            "août",
+            "septembre",
+            "octobre",
+            "novembre",
+            "décembre",
+        ],
+        months_short: [
+            "janv.", "févr.", "mars", "avr.", "mai", "juin", "juil.", "août", "sept.", "oct.",
+            "nov.", "déc.",
+        ],
+        days: [
+            "dimanche", "lundi", "mardi", "mercredi", "jeudi", "vendredi", "samedi",
+        ],
+        days_short: ["dim.", "lun.", "mar.", "mer.", "jeu.", "ven.", "sam."],
+        am_pm: AM_PM,
+        currency_format: Some("#,##0 \"€\""),
+        short_date: "dd/mm/yyyy",
+        era_calendar: Calendar::Gregorian,
+        script: Script::Latin,
+    },
+    Locale {
+        lcid: 0xC0A,
+        tag: "es-ES",
+        decimal: ',',
+        group: '.',
+        months: [
+            "enero",
+            "febrero",
+            "marzo",
+            "abril",
+            "mayo",
+            "junio",
+            "julio",
+            "agosto",
+            "septiembre",
+            "octubre",
+            "noviembre",
+            "diciembre",
+        ],
+        months_short: [
+            "ene", "feb", "mar", "abr", "may", "jun", "jul", "ago", "sept", "oct", "nov", "dic",
+        ],
+        days: [
+            "domingo",
+            "lunes",
+            "martes",
+            "miércoles",
+            "jueves",
+            "viernes",
+            "sábado",
+        ],
+        days_short: ["dom", "lun", "mar", "mié", "jue", "vie", "sáb"],
+        am_pm: AM_PM,
+        currency_format: Some("#,##0 \"€\""),
+        short_date: "dd/mm/yyyy",
+        era_calendar: Calendar::Gregorian,
+        script: Script::Latin,
+    },
+    Locale {
+        lcid: 0x410,
+        tag: "it-IT",
+        decimal: ',',
+        group: '.',
+        months: [
+            "gennaio",
+            "febbraio",
+            "marzo",
+            "aprile",
+            "maggio",
+            "giugno",
+            "luglio",
+            "agosto",
+            "settembre",
+            "ottobre",
+            "novembre",
+            "dicembre",
+        ],
+        months_short: [
+            "gen", "feb", "mar", "apr", "mag", "giu", "lug", "ago", "set", "ott", "nov", "dic",
+        ],
+        days: [
+            "domenica",
+            "lunedì",
+            "martedì",
+            "mercoledì",
+            "giovedì",
+            "venerdì",
+            "sabato",
+        ],
+        days_short: ["dom", "lun", "mar", "mer", "gio", "ven", "sab"],
+        am_pm: AM_PM,
+        currency_format: Some("#,##0 \"€\""),
+        short_date: "dd/mm/yyyy",
+        era_calendar: Calendar::Gregorian,
+        script: Script::Latin,
+    },
+    Locale {
+        lcid: 0x413,
+        tag: "nl-NL",
+        decimal: ',',
+        group: '.',
+        months: [
+            "januari",
+            "februari",
+            "maart",
+            "april",
+            "mei",
+            "juni",
+            "juli",
+            "augustus",
+            "september",
+            "oktober",
+            "november",
+            "december",
+        ],
+        months_short: [
+            "jan", "feb", "mrt", "apr", "mei", "jun", "jul", "aug", "sep", "okt", "nov", "dec",
+        ],
+        days: [
+            "zondag",
+            "maandag",
+            "dinsdag",
+            "woensdag",
+            "donderdag",
+            "vrijdag",
+            "zaterdag",
+        ],
+        days_short: ["zo", "ma", "di", "wo", "do", "vr", "za"],
+        am_pm: AM_PM,
+        currency_format: Some("\"€\" #,##0"),
+        short_date: "d-m-yyyy",
+        era_calendar: Calendar::Gregorian,
+        script: Script::Latin,
+    },
+    Locale {
+        lcid: 0x416,
+        tag: "pt-BR",
+        decimal: ',',
+        group: '.',
+        months: [
+            "janeiro",
+            "fevereiro",
+            "março",
+            "abril",
+            "maio",
+            "junho",
+            "julho",
+            "agosto",
+            "setembro",
+            "outubro",
+            "novembro",
+            "dezembro",
+        ],
+        months_short: [
+            "jan.", "fev.", "mar.", "abr.", "mai.", "jun.", "jul.", "ago.", "set.", "out.", "nov.",
+            "dez.",
+        ],
+        days: [
+            "domingo",
+            "segunda-feira",
+            "terça-feira",
+            "quarta-feira",
+            "quinta-feira",
+            "sexta-feira",
+            "sábado",
+        ],
+        days_short: ["dom.", "seg.", "ter.", "qua.", "qui.", "sex.", "sáb."],
+        am_pm: AM_PM,
+        currency_format: Some("\"R$\" #,##0"),
+        short_date: "dd/mm/yyyy",
+        era_calendar: Calendar::Gregorian,
+        script: Script::Latin,
+    },
+    Locale {
+        lcid: 0x411,
+        tag: "ja-JP",
+        decimal: '.',
+        group: ',',
+        months: NUMBERED_MONTHS,
+        months_short: NUMBERED_MONTHS,
+        days: [
+            "日曜日",
+            "月曜日",
+            "火曜日",
+            "水曜日",
+            "木曜日",
+            "金曜日",
+            "土曜日",
+        ],
+        days_short: ["日", "月", "火", "水", "木", "金", "土"],
+        am_pm: ["午前", "午後"],
+        currency_format: Some("\"¥\"#,##0"),
+        short_date: "yyyy/m/d",
+        era_calendar: Calendar::JapaneseEra,
+        script: Script::Japanese,
+    },
+    Locale {
+        lcid: 0x804,
+        tag: "zh-CN",
+        decimal: '.',
+        group: ',',
+        months: [
+            "一月",
+            "二月",
+            "三月",
+            "四月",
+            "五月",
+            "六月",
+            "七月",
+            "八月",
+            "九月",
+            "十月",
+            "十一月",
+            "十二月",
+        ],
+        months_short: NUMBERED_MONTHS,
+        days: ZH_DAYS,
+        days_short: ["周日", "周一", "周二", "周三", "周四", "周五", "周六"],
+        am_pm: ["上午", "下午"],
+        currency_format: Some("\"¥\"#,##0"),
+        short_date: "yyyy/m/d",
+        era_calendar: Calendar::Gregorian,
+        script: Script::SimplifiedChinese,
+    },
+    Locale {
+        lcid: 0x404,
+        tag: "zh-TW",
+        decimal: '.',
+        group: ',',
+        months: NUMBERED_MONTHS,
+        months_short: NUMBERED_MONTHS,
+        days: ZH_DAYS,
+        days_short: ["週日", "週一", "週二", "週三", "週四", "週五", "週六"],
+        am_pm: ["上午", "下午"],
+        currency_format: Some("\"NT$\"#,##0"),
+        short_date: "yyyy/m/d",
+        era_calendar: Calendar::TaiwanRoc,
+        script: Script::TraditionalChinese,
+    },
+    Locale {
+        lcid: 0x412,
+        tag: "ko-KR",
+        decimal: '.',
+        group: ',',
+        months: KO_MONTHS,
+        months_short: KO_MONTHS,
+        days: [
+            "일요일",
+            "월요일",
+            "화요일",
+            "수요일",
+            "목요일",
+            "금요일",
+            "토요일",
+        ],
+        days_short: ["일", "월", "화", "수", "목", "금", "토"],
+        am_pm: ["오전", "오후"],
+        currency_format: Some("\"₩\"#,##0"),
+        short_date: "yyyy-mm-dd",
+        era_calendar: Calendar::Gregorian,
+        script: Script::Korean,
+    },
+];
+
+/// The locale data for a Windows LCID. Only the language part (the low 16
+/// bits) is looked at.
+pub fn locale(lcid: u32) -> Option<&'static Locale> {
+    LOCALES.iter().find(|l| l.lcid == lcid & 0xFFFF)
+}
+
+/// The locale data for a language tag like `de-DE`, matched case-insensitively.
+pub fn locale_by_tag(tag: &str) -> Option<&'static Locale> {
+    LOCALES.iter().find(|l| l.tag.eq_ignore_ascii_case(tag))
+}
+
+impl Locale {
+    /// The locale's version of a built-in format id: the currency formats
+    /// 5-8 and the dates 14 and 22. `None` for ids the locale leaves alone.
+    pub fn builtin(&self, id: u16) -> Option<String> {
+        match id {
+            5..=8 | 63..=66 => {
+                let positive = self.currency_format?;
+                let positive = if matches!(id, 7 | 8 | 65 | 66) {
+                    positive.replace("#,##0", "#,##0.00")
+                } else {
+                    positive.to_string()
+                };
+                let red = if matches!(id, 6 | 8 | 64 | 66) {
+                    "[Red]"
+                } else {
+                    ""
+                };
+                Some(format!("{positive};{red}-{positive}"))
+            }
+            14 => Some(self.short_date.to_string()),
+            22 => Some(format!("{} h:mm", self.short_date)),
+            _ => None,
+        }
+    }
+}
+
+/// How one format section renders: the locale plus what the section's own
+/// `[$-...]` and `[DBNum]` tokens ask for.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct SectionLocale {
+    pub locale: &'static Locale,
+    pub calendar: Calendar,
+    /// Write the first year of an era as 元 (`[$-ja-JP-x-gannen]`).
+    pub gannen: bool,
+    pub dbnum: u8,
+}
+
+/// Resolve the locale for `section` of `fmt`, rendered for `lcid`. An LCID
+/// token in the section wins; otherwise the first one anywhere in `fmt`
+/// applies, so `[$-407]0.00;-0.00` renders both sections in German. An LCID
+/// without locale data renders as en-US.
+pub(crate) fn section_locale(fmt: &str, section: &str, lcid: u32) -> SectionLocale {
+    let fallback = locale(lcid).unwrap_or(&LOCALES[0]);
+    let mut resolved = SectionLocale {
+        locale: fallback,
+        calendar: fallback.era_calendar,
+        gannen: false,
+        dbnum: 0,
+    };
+    let own = bracket_tokens(section);
+    let lcid_token = own
+        .iter()
+        .chain(bracket_tokens(fmt).iter())
+        .find_map(|t| t.strip_prefix('$').and_then(|t| t.split_once('-')))
+        .map(|(_, code)| code.to_string());
+    if let Some(code) = lcid_token {
+        apply_lcid_token(&mut resolved, &code);
+    }
+    for token in &own {
+        let lower = token.to_ascii_lowercase();
+        if let Some(n) = lower
+            .strip_prefix("dbnum")
+            .and_then(|n| n.parse::<u8>().ok())
+        {
+            if (1..=3).contains(&n) {
+                resolved.dbnum = n;
+            }
+        }
+    }
+    resolved
+}
+
+fn apply_lcid_token(resolved: &mut SectionLocale, code: &str) {
+    if !code.is_empty() && code.chars().all(|c| c.is_ascii_hexdigit()) {
+        let Ok(v) = u32::from_str_radix(code, 16) else {
+            return;
+        };
+        // 0xF800 and 0xF400 are the system long date and time: no locale.
+        if let Some(l) = locale(v) {
+            resolved.locale = l;
+            resolved.calendar = l.era_calendar;
+        }
+        match (v >> 16) & 0xFF {
+            1 | 2 => resolved.calendar = Calendar::Gregorian,
+            3 => resolved.calendar = Calendar::JapaneseEra,
+            4 => resolved.calendar = Calendar::TaiwanRoc,
+            5 => resolved.calendar = Calendar::KoreanTangun,
+            7 => resolved.calendar = Calendar::ThaiBuddhist,
+            _ => {}
+        }
+        return;
+    }
+    let lower = code.to_ascii_lowercase();
+    let tag = match lower.strip_suffix("-x-gannen") {
+        Some(tag) => {
+            resolved.gannen = true;
+            tag
+        }
+        None => lower.as_str(),
+    };
+    if let Some(l) = locale_by_tag(tag) {
+        resolved.locale = l;
+        resolved.calendar = l.era_calendar;
+    }
+}
+
+/// The contents of the `[...]` blocks of a format, outside quotes and escapes.
+fn bracket_tokens(fmt: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut chars = fmt.chars();
+    let mut in_str = false;
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => in_str = !in_str,
+            '\\' | '_' | '*' if !in_str => {
+                chars.next();
+            }
+            '[' if !in_str => tokens.push(chars.by_ref().take_while(|c| *c != ']').collect()),
+            _ => {}
+        }
+    }
+    tokens
+}
+
+struct Era {
+    /// The first day, as (year, month, day).
+    start: (i64, i64, i64),
+    narrow: &'static str,
+    long: &'static str,
+}
+
+const JAPANESE_ERAS: [Era; 5] = [
+    Era {
+        start: (1868, 1, 1),
+        narrow: "M",
+        long: "明治",
+    },
+    Era {
+        start: (1912, 7, 30),
+        narrow: "T",
+        long: "大正",
+    },
+    Era {
+        start: (1926, 12, 25),
+        narrow: "S",
+        long: "昭和",
+    },
+    Era {
+        start: (1989, 1, 8),
+        narrow: "H",
+        long: "平成",
+    },
+    Era {
+        start: (2019, 5, 1),
+        narrow: "R",
+        long: "令和",
+    },
+];
+
+impl SectionLocale {
+    /// A date field in this locale, or `None` where `write_date`'s en-US
+    /// rendering applies unchanged.
+    pub(crate) fn write_date(&self, t: char, fmt: &str, dt: &DateCode) -> Option<String> {
+        let flen = fmt.chars().count();
+        let l = self.locale;
+        match t {
+            'm' if flen >= 3 => {
+                let m = (dt.m - 1) as usize;
+                Some(match flen {
+                    3 => l.months_short[m].to_string(),
+                    5 => l.months[m].chars().next().unwrap_or_default().to_string(),
+                    _ => l.months[m].to_string(),
+                })
+            }
+            'd' if flen >= 3 => {
+                let q = dt.q as usize;
+                Some(
+                    if flen == 3 {
+                        l.days_short[q]
+                    } else {
+                        l.days[q]
+                    }
+                    .to_string(),
+                )
+            }
+            'e' => {
+                let year = match self.calendar {
+                    Calendar::Gregorian => return None,
+                    Calendar::JapaneseEra => match japanese_era(dt) {
+                        Some(era) => dt.y - era.start.0 + 1,
+                        None => return None,
+                    },
+                    Calendar::TaiwanRoc if dt.y > 1911 => dt.y - 1911,
+                    Calendar::TaiwanRoc => return None,
+                    Calendar::KoreanTangun => dt.y + 2333,
+                    Calendar::ThaiBuddhist => dt.y + 543,
+                };
+                if self.gannen && year == 1 {
+                    return Some(String::from("元"));
+                }
+                Some(pad0(&year.to_string(), flen.min(2)))
+            }
+            'g' => Some(match self.calendar {
+                Calendar::JapaneseEra => match japanese_era(dt) {
+                    Some(era) if flen == 1 => era.narrow.to_string(),
+                    Some(era) if flen == 2 => era.long.chars().take(1).collect(),
+                    Some(era) => era.long.to_string(),
+                    None => String::new(),
+                },
+                Calendar::TaiwanRoc if dt.y > 1911 => String::from("民國"),
+                _ => String::new(),
+            }),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn am_pm(&self, pm: bool) -> &'static str {
+        self.locale.am_pm[pm as usize]
+    }
+
+    /// Join the rendered tokens of a section. `parts` pairs each token's text
+    /// with the kind of format token it came from, so that only the output of
+    /// number placeholders and date fields picks up the locale's separators
+    /// and `[DBNum]` numerals; quoted text is left alone.
+    pub(crate) fn finish(&self, parts: &[(char, String)]) -> String {
+        let numerals = numerals(self.locale.script, self.dbnum);
+        let mut out = String::new();
+        for (k, (origin, text)) in parts.iter().enumerate() {
+            let kind = match origin {
+                'n' | '?' | 'D' | 'G' | 's' => Kind::Number,
+                'y' | 'b' => Kind::Year,
+                'e' if self.calendar == Calendar::Gregorian => Kind::Year,
+                'd' | 'm' | 'h' | 'H' | 'M' | 'e' | 'Z' => Kind::Date,
+                _ => Kind::Literal,
+            };
+            if kind == Kind::Literal {
+                out.push_str(text);
+                continue;
+            }
+            // The fraction of a number can be split over several tokens.
+            let mut fraction = k > 0
+                && kind == Kind::Number
+                && parts[k - 1].0 == parts[k].0
+                && out.ends_with(self.locale.decimal)
+                && self.locale.decimal != self.locale.group;
+            let mut run = String::new();
+            let flush = |run: &mut String, fraction: bool, out: &mut String| {
+                if run.is_empty() {
+                    return;
+                }
+                match numerals {
+                    Some(n) if kind == Kind::Year || fraction => out.push_str(&n.digit_wise(run)),
+                    Some(n) => out.push_str(&n.positional(run)),
+                    None => out.push_str(run),
+                }
+                run.clear();
+            };
+            let chars = text.chars().collect::<Vec<_>>();
+            for (i, &c) in chars.iter().enumerate() {
+                match c {
+                    '0'..='9' => run.push(c),
+                    // A thousands separator between digits belongs to the
+                    // run: positional numerals drop it.
+                    ',' if kind == Kind::Number
+                        && !run.is_empty()
+                        && chars.get(i + 1).is_some_and(|c| c.is_ascii_digit()) =>
+                    {
+                        if numerals.is_none_or(|n| !n.positional) {
+                            run.push(self.locale.group);
+                        }
+                    }
+                    '.' if kind == Kind::Number => {
+                        flush(&mut run, fraction, &mut out);
+                        out.push(self.locale.decimal);
+                        fraction = true;
+                    }
+                    ',' if kind == Kind::Number => {
+                        flush(&mut run, fraction, &mut out);
+                        out.push(self.locale.group);
+                    }
+                    _ => {
+                        flush(&mut run, fraction, &mut out);
+                        out.push(c);
+                        fraction = false;
+                    }
+                }
+            }
+            flush(&mut run, fraction, &mut out);
+        }
+        out
+    }
+}
+
+fn japanese_era(dt: &DateCode) -> Option<&'static Era> {
+    JAPANESE_ERAS
+        .iter()
+        .rev()
+        .find(|era| (dt.y, dt.m, dt.d) >= era.start)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Literal,
+    /// A number: takes the locale's separators, and digits before the
+    /// decimal point read as a whole.
+    Number,
+    /// A day, month or time field. Names like `janv.` keep their dots.
+    Date,
+    /// A year: read digit by digit.
+    Year,
+}
+
+/// A `[DBNum]` numeral system.
+#[derive(Debug, Clone, Copy)]
+struct Numerals {
+    digits: [char; 10],
+    /// Whether whole numbers are written with 十, 百, 千 and 万 rather than
+    /// one character per digit.
+    positional: bool,
+    /// 十, 百, 千.
+    units: [char; 3],
+    /// 万, 億, 兆: every fourth power of ten.
+    myriads: [char; 3],
+    /// Chinese writes 零 for a gap of zeros (一万零五); Japanese skips it.
+    zero: Option<char>,
+    /// When a 1 before 十, 百 or 千 is dropped.
+    omit_one: OmitOne,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OmitOne {
+    Never,
+    Always,
+    LeadingTen,
+}
+
+const KANJI_DIGITS: [char; 10] = ['〇', '一', '二', '三', '四', '五', '六', '七', '八', '九'];
+const FULLWIDTH_DIGITS: [char; 10] = ['０', '１', '２', '３', '４', '５', '６', '７', '８', '９'];
+
+fn numerals(script: Script, dbnum: u8) -> Option<Numerals> {
+    let kanji = |myriads, zero, omit_one| Numerals {
+        digits: KANJI_DIGITS,
+        positional: true,
+        units: ['十', '百', '千'],
+        myriads,
+        zero,
+        omit_one,
+    };
+    Some(match (script, dbnum) {
+        (Script::Latin, _) | (_, 0) => return None,
+        (_, 3) => Numerals {
+            digits: FULLWIDTH_DIGITS,
+            positional: false,
+            ..kanji(['万', '億', '兆'], None, OmitOne::Never)
+        },
+        (Script::Japanese, 1) => kanji(['万', '億', '兆'], None, OmitOne::Always),
+        (Script::Korean, 1) => kanji(['萬', '億', '兆'], None, OmitOne::Always),
+        (Script::SimplifiedChinese, 1) => {
+            kanji(['万', '亿', '兆'], Some('零'), OmitOne::LeadingTen)
+        }
+        (Script::TraditionalChinese, 1) => {
+            kanji(['萬', '億', '兆'], Some('零'), OmitOne::LeadingTen)
+        }
+        (Script::Japanese, _) => Numerals {
+            digits: ['〇', '壱', '弐', '参', '四', '伍', '六', '七', '八', '九'],
+            units: ['拾', '百', '阡'],
+            ..kanji(['萬', '億', '兆'], None, OmitOne::Never)
+        },
+        (Script::Korean, _) => Numerals {
+            digits: ['零', '壹', '貳', '參', '四', '五', '六', '七', '八', '九'],
+            units: ['拾', '百', '千'],
+            ..kanji(['萬', '億', '兆'], None, OmitOne::Never)
+        },
+        (Script::SimplifiedChinese, _) => Numerals {
+            digits: ['零', '壹', '贰', '叁', '肆', '伍', '陆', '柒', '捌', '玖'],
+            units: ['拾', '佰', '仟'],
+            ..kanji(['万', '亿', '兆'], Some('零'), OmitOne::Never)
+        },
+        (Script::TraditionalChinese, _) => Numerals {
+            digits: ['零', '壹', '貳', '參', '肆', '伍', '陸', '柒', '捌', '玖'],
+            units: ['拾', '佰', '仟'],
+            ..kanji(['萬', '億', '兆'], Some('零'), OmitOne::Never)
+        },
+    })
+}
+
+impl Numerals {
+    fn digit_wise(&self, run: &str) -> String {
+        run.chars()
+            .map(|c| match c.to_digit(10) {
+                Some(d) => self.digits[d as usize],
+                None => c,
+            })
+            .collect()
+    }
+
+    /// A whole number the way it is read: 1234 is 千二百三十四 in Japanese.
+    fn positional(&self, run: &str) -> String {
+        if !self.positional {
+            return self.digit_wise(run);
+        }
+        let digits = run.trim_start_matches('0').as_bytes();
+        if digits.is_empty() {
+            return self.digits[0].to_string();
+        }
+        // Past 兆 the units run out: fall back to one character per digit.
+        if digits.len() > 16 {
+            return self.digit_wise(run);
+        }
+        let n = digits.len();
+        let mut out = String::new();
+        let mut gap = false;
+        let mut myriad_used = false;
+        for (i, b) in digits.iter().enumerate() {
+            let d = (b - b'0') as usize;
+            let pos = n - 1 - i;
+            let unit = pos % 4;
+            if d == 0 {
+                gap = true;
+            } else {
+                if gap {
+                    if let Some(zero) = self.zero {
+                        out.push(zero);
+                    }
+                }
+                gap = false;
+                let omit = d == 1
+                    && unit > 0
+                    && match self.omit_one {
+                        OmitOne::Never => false,
+                        OmitOne::Always => true,
+                        OmitOne::LeadingTen => unit == 1 && out.is_empty(),
+                    };
+                if !omit {
+                    out.push(self.digits[d]);
+                }
+                if unit > 0 {
+                    out.push(self.units[unit - 1]);
+                }
+                myriad_used = true;
+            }
+            if unit == 0 && pos > 0 {
+                if myriad_used {
+                    out.push(self.myriads[pos / 4 - 1]);
+                }
+                myriad_used = false;
+            }
+        }
+        out
+    }
+}
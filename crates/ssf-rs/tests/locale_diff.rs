@@ -0,0 +1,258 @@
+//! Differential test: `ssf_rs::format_with_locale` vs. the CLDR data behind
+//! Node's `Intl`. Separators are checked with `Intl.NumberFormat`; month, day,
+//! AM/PM and era names with `Intl.DateTimeFormat`. Cases pass as hex so
+//! nothing is lost to escaping.
+//!
+//! Ignored by default (needs `node` built with full ICU). Run with:
+//!   cargo test -p ssf-rs --test locale_diff -- --ignored --nocapture
+
+use std::io::Write;
+use std::process::Command;
+
+use ssf_rs::{format_with_locale, Value};
+
+struct Lcg(u64);
+impl Lcg {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        self.0
+    }
+}
+
+const LOCALES: &[(&str, u32)] = &[
+    ("en-US", 0x409),
+    ("en-GB", 0x809),
+    ("de-DE", 0x407),
+    ("fr-FR", 0x40C),
+    ("es-ES", 0xC0A),
+    ("it-IT", 0x410),
+    ("nl-NL", 0x413),
+    ("pt-BR", 0x416),
+    ("ja-JP", 0x411),
+    ("zh-CN", 0x804),
+    ("zh-TW", 0x404),
+    ("ko-KR", 0x412),
+];
+
+/// Number formats and the `Intl.NumberFormat` options that render the same.
+const NUMBER_FORMATS: &[(&str, &str)] = &[
+    (
+        "#,##0.00",
+        r#"{"minimumFractionDigits":2,"maximumFractionDigits":2}"#,
+    ),
+    (
+        "0.00",
+        r#"{"minimumFractionDigits":2,"maximumFractionDigits":2,"useGrouping":false}"#,
+    ),
+    ("#,##0", r#"{"maximumFractionDigits":0}"#),
+];
+
+/// Date formats and the `Intl.DateTimeFormat` options plus the part of
+/// `formatToParts` that renders the same.
+const DATE_FORMATS: &[(&str, &str)] = &[
+    ("mmmm", r#"{"month":"long","part":"month"}"#),
+    ("mmm", r#"{"month":"short","part":"month"}"#),
+    ("dddd", r#"{"weekday":"long","part":"weekday"}"#),
+    ("ddd", r#"{"weekday":"short","part":"weekday"}"#),
+    (
+        "AM/PM",
+        r#"{"hour":"numeric","hour12":true,"part":"dayPeriod"}"#,
+    ),
+];
+
+/// Japanese era formats, checked against the `ja-JP-u-ca-japanese` calendar.
+const ERA_FORMATS: &[(&str, &str)] = &[
+    (
+        "[$-411]ggg",
+        r#"{"era":"long","year":"numeric","part":"era"}"#,
+    ),
+    (
+        "[$-411]g",
+        r#"{"era":"narrow","year":"numeric","part":"era"}"#,
+    ),
+    (
+        "[$-411]e",
+        r#"{"era":"long","year":"numeric","part":"year"}"#,
+    ),
+];
+
+fn hex(bytes: &[u8]) -> String {
+    let mut s = String::with_capacity(bytes.len() * 2);
+    for b in bytes {
+        s.push_str(&format!("{:02x}", b));
+    }
+    s
+}
+
+fn unhex(s: &str) -> Vec<u8> {
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+const NODE_SCRIPT: &str = r#"
+const fs = require('fs');
+const lines = fs.readFileSync(process.argv[2], 'utf8').split('\n');
+function fromBits(h){
+  const b = BigInt('0x'+h);
+  const buf = new ArrayBuffer(8); const dv = new DataView(buf);
+  dv.setBigUint64(0, b); return dv.getFloat64(0);
+}
+function fromHex(h){ return Buffer.from(h,'hex').toString('utf8'); }
+function toHex(s){ return Buffer.from(s,'utf8').toString('hex'); }
+const out = [];
+for (const line of lines) {
+  if (!line) continue;
+  const [tag, optsHex, bits] = line.split('\t');
+  const opts = JSON.parse(fromHex(optsHex));
+  const val = fromBits(bits);
+  let r;
+  if (opts.part === undefined) {
+    r = new Intl.NumberFormat(tag, opts).format(val);
+  } else {
+    const part = opts.part; delete opts.part;
+    opts.timeZone = 'UTC';
+    const date = new Date(Date.UTC(1899, 11, 30) + Math.round(val * 86400000));
+    const dtf = new Intl.DateTimeFormat(tag, opts);
+    // A month or weekday on its own formats to just the name (`5月`).
+    if (part === 'month' || part === 'weekday') r = dtf.format(date);
+    else r = (dtf.formatToParts(date).find(p => p.type === part) || {value: ''}).value;
+    // Intl writes the first year of an era as 元; Excel only with gannen.
+    if (part === 'year' && r === '元') r = '1';
+  }
+  out.push(toHex(r.replace(/\u202f/g, '\u00a0')));
+}
+process.stdout.write(out.join('\n'));
+"#;
+
+/// A number with three decimals that never sits on a rounding tie, so
+/// both sides round it the same way.
+fn number(lcg: &mut Lcg) -> f64 {
+    let digits = 1 + lcg.next_u64() % 12;
+    let mut k = (lcg.next_u64() % 10u64.pow(digits as u32)) as i64;
+    if k % 10 == 5 || k % 1000 == 500 {
+        k += 1;
+    }
+    let v = k as f64 / 1000.;
+    if lcg.next_u64().is_multiple_of(4) {
+        -v
+    } else {
+        v
+    }
+}
+
+#[test]
+#[ignore = "requires node"]
+fn differential_against_intl() {
+    let mut lcg = Lcg(0x0123456789ABCDEF);
+    let numbers: Vec<f64> = (0..200).map(|_| number(&mut lcg)).collect();
+    // Serials from 1901 to 2099, with a time of day.
+    let dates: Vec<f64> = (0..200)
+        .map(|_| {
+            400. + (lcg.next_u64() % 72_000) as f64 + (lcg.next_u64() % 86_400) as f64 / 86_400.
+        })
+        .collect();
+
+    let mut input = String::new();
+    let mut rust_out: Vec<String> = Vec::new();
+    let mut case = |tag: &str, opts: &str, v: f64, fmt: &str, lcid: u32| {
+        input.push_str(&format!(
+            "{}\t{}\t{:016x}\n",
+            tag,
+            hex(opts.as_bytes()),
+            v.to_bits()
+        ));
+        let r = format_with_locale(fmt, &Value::Num(v), lcid, false)
+            .unwrap_or_else(|_| "\u{1}ERR".to_string());
+        rust_out.push(r);
+    };
+    for &(tag, lcid) in LOCALES {
+        for &(fmt, opts) in NUMBER_FORMATS {
+            for &v in &numbers {
+                // CLDR leaves four-digit numbers ungrouped in Spanish and
+                // Italian; Excel groups them.
+                if (tag == "es-ES" || tag == "it-IT") && (1000. ..10000.).contains(&v.abs().round())
+                {
+                    continue;
+                }
+                case(tag, opts, v, fmt, lcid);
+            }
+        }
+        for &(fmt, opts) in DATE_FORMATS {
+            // Excel keeps AM/PM in Europe, where CLDR has `am`, `a. m.` and
+            // the like, and writes 오전/오후 where CLDR has moved to AM/PM.
+            if fmt == "AM/PM" && !matches!(tag, "en-US" | "ja-JP" | "zh-CN" | "zh-TW") {
+                continue;
+            }
+            for &v in &dates {
+                case(tag, opts, v, fmt, lcid);
+            }
+        }
+    }
+    for &(fmt, opts) in ERA_FORMATS {
+        for &v in &dates {
+            case("ja-JP-u-ca-japanese", opts, v, fmt, 0x409);
+        }
+    }
+
+    let dir = std::env::temp_dir();
+    let infile = dir.join("ssf_rs_locale_diff_in.txt");
+    std::fs::File::create(&infile)
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+    let scriptfile = dir.join("ssf_rs_locale_diff.js");
+    std::fs::File::create(&scriptfile)
+        .unwrap()
+        .write_all(NODE_SCRIPT.as_bytes())
+        .unwrap();
+
+    let output = Command::new("node")
+        .arg(&scriptfile)
+        .arg(&infile)
+        .output()
+        .expect("failed to run node");
+    assert!(
+        output.status.success(),
+        "node failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let js_out = String::from_utf8(output.stdout).unwrap();
+    let js_lines: Vec<String> = js_out
+        .split('\n')
+        .map(|h| String::from_utf8_lossy(&unhex(h)).into_owned())
+        .collect();
+
+    assert_eq!(js_lines.len(), rust_out.len(), "line count mismatch");
+
+    let input_lines: Vec<&str> = input.lines().collect();
+    let mut mismatches = 0usize;
+    let mut samples = Vec::new();
+    for (i, (r, j)) in rust_out.iter().zip(js_lines.iter()).enumerate() {
+        if r != j {
+            mismatches += 1;
+            if samples.len() < 60 {
+                let parts: Vec<&str> = input_lines[i].split('\t').collect();
+                let val = f64::from_bits(u64::from_str_radix(parts[2], 16).unwrap());
+                let opts = String::from_utf8(unhex(parts[1])).unwrap();
+                samples.push(format!(
+                    "  locale={} opts={} val={:?}  rust={:?} intl={:?}",
+                    parts[0], opts, val, r, j
+                ));
+            }
+        }
+    }
+    if mismatches > 0 {
+        panic!(
+            "{}/{} mismatches:\n{}",
+            mismatches,
+            rust_out.len(),
+            samples.join("\n")
+        );
+    }
+    eprintln!("locale differential OK: {} cases matched", rust_out.len());
+}
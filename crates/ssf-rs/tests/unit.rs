@@ -1,7 +1,9 @@
 //! Node-free unit tests with expectations captured from the reference `ssf`.
 //! These run under a plain `cargo test -p ssf-rs` (no `--ignored`, no `node`).
 
-use ssf_rs::{format_str, format_text};
+use ssf_rs::{
+    format_cell, format_id_with_locale, format_str, format_text, format_with_locale, Value,
+};
 
 #[test]
 fn common_formats() {
@@ -51,3 +53,134 @@ fn readme_examples() {
     assert_eq!(format_str("#,##0.00", 1234.5).unwrap(), "1,234.50");
     assert_eq!(format_str("yyyy-mm-dd", 45000.0).unwrap(), "2023-03-15");
 }
+
+/// `format_cell` is this crate's own addition, so these expectations come from
+/// Excel rather than the reference `ssf`.
+#[test]
+fn cell_colors_and_conditions() {
+    let cell = |fmt: &str, v: f64| format_cell(fmt, &Value::Num(v), None, false).unwrap();
+    let red = "#,##0.00;[Red]-#,##0.00";
+    assert_eq!(cell(red, -5.0).text, "-5.00");
+    assert_eq!(cell(red, -5.0).color, Some(3));
+    assert_eq!(cell(red, 5.0).color, None);
+    let cond = "[Blue][<0]\"low\";[Color10][>100]\"high\";0";
+    assert_eq!(cell(cond, -1.0).text, "low");
+    assert_eq!(cell(cond, -1.0).color, Some(5));
+    assert_eq!(cell(cond, 200.0).text, "high");
+    assert_eq!(cell(cond, 200.0).color, Some(10));
+    assert_eq!(cell(cond, 50.0).text, "50");
+    assert_eq!(cell(cond, 50.0).color, None);
+    assert_eq!(cell("General", -5.0).color, None);
+}
+
+#[test]
+fn cell_width() {
+    let cell = |fmt: &str, v: f64, width: usize| {
+        format_cell(fmt, &Value::Num(v), Some(width), false)
+            .unwrap()
+            .text
+    };
+    assert_eq!(cell("#,##0.00", 1234567.0, 8), "########");
+    assert_eq!(cell("#,##0.00", 1234.0, 8), "1,234.00");
+    assert_eq!(cell("yyyy-mm-dd", 45000.0, 5), "#####");
+    assert_eq!(cell("General", 1.23456789, 8), "1.234568");
+    assert_eq!(cell("General", 1234567.891, 8), "1234568");
+    assert_eq!(cell("General", 123456789012.0, 8), "1.23E+11");
+    assert_eq!(cell("General", -0.000001234, 8), "-1.2E-06");
+    assert_eq!(cell("General", 123456789012.0, 3), "###");
+    let text = format_cell(
+        "@",
+        &Value::Text("a long piece of text".into()),
+        Some(4),
+        false,
+    );
+    assert_eq!(text.unwrap().text, "a long piece of text");
+}
+
+/// Locale rendering is this crate's own addition too: names and separators
+/// come from CLDR (see `locale_diff.rs`), the rest from Excel.
+#[test]
+fn locale_separators_and_names() {
+    let loc = |fmt: &str, v: f64, lcid: u32| {
+        format_with_locale(fmt, &Value::Num(v), lcid, false).unwrap()
+    };
+    assert_eq!(loc("#,##0.00", 1234567.891, 0x407), "1.234.567,89");
+    assert_eq!(
+        loc("#,##0.00", 1234567.891, 0x40C),
+        "1\u{a0}234\u{a0}567,89"
+    );
+    assert_eq!(loc("#,##0.00", 1234567.891, 0x409), "1,234,567.89");
+    assert_eq!(loc("General", 3.5, 0x407), "3,5");
+    assert_eq!(loc("0.00E+00", 12345.678, 0x407), "1,23E+04");
+    assert_eq!(loc("mm:ss.00", 0.5001, 0x407), "00:08,64");
+    assert_eq!(loc("\"a.b\" 0.0", 1.5, 0x407), "a.b 1,5");
+    assert_eq!(
+        loc("dddd d mmmm yyyy", 45000.0, 0x407),
+        "Mittwoch 15 März 2023"
+    );
+    assert_eq!(loc("ddd d mmm", 45000.0, 0x40C), "mer. 15 mars");
+    assert_eq!(loc("mmmmm", 45000.0, 0x410), "m");
+    assert_eq!(loc("h:mm AM/PM", 0.75, 0x411), "6:00 午後");
+    // `[$€-2]` carries a symbol but no locale of its own.
+    assert_eq!(loc("[$€-2] #,##0.00", 1234.5, 0x407), "€ 1.234,50");
+    // Unknown LCIDs render as en-US.
+    assert_eq!(loc("#,##0.0 mmm", 45000.0, 0x7F), "45,000.0 Mar");
+}
+
+#[test]
+fn locale_tokens_and_calendars() {
+    let loc = |fmt: &str, v: f64| format_with_locale(fmt, &Value::Num(v), 0x409, false).unwrap();
+    assert_eq!(loc("[$-407]mmmm", 45000.0), "März");
+    assert_eq!(loc("[$-407]#,##0.00", 1234.5), "1.234,50");
+    assert_eq!(loc("[$€-407]#,##0.00", 1234.5), "€1.234,50");
+    assert_eq!(loc("[$-de-DE]#,##0.00", 1234.5), "1.234,50");
+    assert_eq!(
+        loc("[$-411]ggge\"年\"m\"月\"d\"日\"", 45000.0),
+        "令和5年3月15日"
+    );
+    assert_eq!(loc("[$-411]ge.m.d", 32516.0), "H1.1.8");
+    assert_eq!(loc("[$-411]ge.m.d", 32515.0), "S64.1.7");
+    assert_eq!(loc("[$-30411]gge", 43586.0), "令1");
+    assert_eq!(loc("[$-ja-JP-x-gannen]ggge\"年\"", 43586.0), "令和元年");
+    assert_eq!(loc("[$-404]gge/m/d", 45000.0), "民國112/3/15");
+    assert_eq!(loc("[$-50412]e", 45000.0), "4356");
+    assert_eq!(loc("[$-7041E]e", 45000.0), "2566");
+    // `e` without an era calendar is the Gregorian year.
+    assert_eq!(loc("e", 45000.0), "2023");
+}
+
+#[test]
+fn dbnum_numerals() {
+    let loc = |fmt: &str, v: f64| format_with_locale(fmt, &Value::Num(v), 0x409, false).unwrap();
+    assert_eq!(loc("[DBNum1][$-411]General", 1234.0), "千二百三十四");
+    assert_eq!(loc("[DBNum1][$-411]General", 10010.0), "一万十");
+    assert_eq!(loc("[DBNum1][$-804]General", 10010.0), "一万零一十");
+    assert_eq!(loc("[DBNum1][$-804]General", 12.0), "十二");
+    assert_eq!(loc("[DBNum1][$-804]General", 0.0), "〇");
+    assert_eq!(loc("[DBNum2][$-804]General", 1234.5), "壹仟贰佰叁拾肆.伍");
+    assert_eq!(loc("[DBNum2][$-411]General", 30.0), "参拾");
+    assert_eq!(loc("[DBNum3][$-411]#,##0", 1234.0), "１,２３４");
+    assert_eq!(
+        loc("[DBNum1][$-411]yyyy\"年\"m\"月\"d\"日\"", 45000.0),
+        "二〇二三年三月十五日"
+    );
+    // Latin-script locales have no DBNum numerals.
+    assert_eq!(loc("[DBNum1]General", 12.0), "12");
+}
+
+#[test]
+fn localized_builtin_formats() {
+    let id = |id: u16, v: f64, lcid: u32| {
+        format_id_with_locale(id, &Value::Num(v), lcid, false).unwrap()
+    };
+    assert_eq!(id(14, 45500.0, 0x407), "27.07.2024");
+    assert_eq!(id(14, 45500.0, 0x809), "27/07/2024");
+    assert_eq!(id(14, 45500.0, 0x411), "2024/7/27");
+    assert_eq!(id(22, 45500.75, 0x413), "27-7-2024 18:00");
+    assert_eq!(id(7, -1234.5, 0x407), "-1.234,50 €");
+    assert_eq!(id(8, -1234.5, 0x809), "-£1,234.50");
+    assert_eq!(id(5, 1234.5, 0x416), "R$ 1.235");
+    // en-US keeps the built-in table.
+    assert_eq!(id(7, -1234.5, 0x409), "(1,234.50)");
+    assert_eq!(id(14, 45500.0, 0x409), "7/27/24");
+}
@@ -126,6 +126,13 @@ pub fn handle(msg: JsValue, book_id: Option<usize>) -> JsValue {
             params.row,
             params.col,
         )),
+        Message::GetCellStyleName(params) => res_to_js(ws::get_cell_style_name(
+            &mgr,
+            id,
+            params.sheet_idx,
+            params.row,
+            params.col,
+        )),
         Message::GetCells(params) => res_to_js(ws::get_cell_infos(
             &mgr,
             id,
@@ -148,6 +155,96 @@ pub fn handle(msg: JsValue, book_id: Option<usize>) -> JsValue {
             params.dst_end_row,
             params.dst_end_col,
         )),
+        Message::CopyRange(params) => res_to_js(ws::copy_range(
+            &mgr,
+            id,
+            params.sheet_idx,
+            params.start_row,
+            params.start_col,
+            params.end_row,
+            params.end_col,
+            params.cut,
+        )),
+        Message::ReadClipboard(params) => res_to_js(ws::read_clipboard(
+            &mgr,
+            id,
+            params.sheet_idx,
+            params.row,
+            params.col,
+            params.tsv.as_deref(),
+            params.html.as_deref(),
+        )),
+        Message::PastePayloads(params) => res_to_js(ws::paste_payloads(
+            &mgr,
+            id,
+            &params.data,
+            params.sheet_idx,
+            params.row,
+            params.col,
+            &params.options,
+        )),
+        Message::PredictFillPayloads(params) => res_to_js(ws::predict_fill_payloads(
+            &mgr,
+            id,
+            params.sheet_idx,
+            params.src_start_row,
+            params.src_start_col,
+            params.src_end_row,
+            params.src_end_col,
+            params.dst_start_row,
+            params.dst_start_col,
+            params.dst_end_row,
+            params.dst_end_col,
+            params.fill_type,
+        )),
+        Message::FillSeries(params) => res_to_js(ws::fill_series(
+            &mgr,
+            id,
+            params.sheet_idx,
+            params.start_row,
+            params.start_col,
+            params.end_row,
+            params.end_col,
+            &params.options,
+        )),
+        Message::GetCustomLists => ok_to_js(&controller::get_custom_lists(&mgr, id)),
+        Message::GetInputLocale => ok_to_js(&controller::get_input_locale(&mgr, id)),
+        Message::GetCellStyles => ok_to_js(&controller::get_cell_styles(&mgr, id)),
+        Message::GetTheme => ok_to_js(&controller::get_theme(&mgr, id)),
+        Message::GetBuiltinThemes => ok_to_js(&controller::get_builtin_themes(&mgr, id)),
+        Message::FindAll(params) => res_to_js(controller::find_all(&mgr, id, &params.options)),
+        Message::FindNext(params) => res_to_js(controller::find_next(
+            &mgr,
+            id,
+            &params.options,
+            params.sheet_idx,
+            params.row,
+            params.col,
+        )),
+        Message::ReplacePayloads(params) => res_to_js(controller::replace_payloads(
+            &mgr,
+            id,
+            &params.options,
+            &params.replacement,
+            params.cell.as_ref(),
+        )),
+        Message::SetCustomLists(params) => {
+            controller::set_custom_lists(&mut mgr, id, params.lists);
+            JsValue::NULL
+        }
+        Message::SetInputLocale(params) => {
+            res_to_js(controller::set_input_locale(&mut mgr, id, params.locale))
+        }
+        Message::PredictFlashFill(params) => res_to_js(ws::predict_flash_fill(
+            &mgr,
+            id,
+            params.sheet_idx,
+            params.start_row,
+            params.start_col,
+            params.end_row,
+            params.end_col,
+            params.target_col,
+        )),
         Message::GetCellsExceptWindow(params) => res_to_js(ws::get_cell_infos_except_window(
             &mgr,
             id,
@@ -373,6 +470,9 @@ pub fn handle(msg: JsValue, book_id: Option<usize>) -> JsValue {
         Message::GetDisplayUnitsOfFormula(params) => {
             res_to_js(controller::get_display_units_of_formula(&params.formula))
         }
+        Message::GetFormulaTokens(params) => {
+            ok_to_js(&controller::get_formula_tokens(&params.formula))
+        }
         Message::GetRowInfo(params) => {
             res_to_js(controller::get_row_info(&mgr, id, params.sheet_idx, params.row_idx))
         }
@@ -382,6 +482,9 @@ pub fn handle(msg: JsValue, book_id: Option<usize>) -> JsValue {
         Message::CheckFormula(params) => {
             ok_to_js(&controller::check_formula(&mgr, id, params.formula))
         }
+        Message::GetFunctionSignature(params) => {
+            res_to_js(controller::get_function_signature_by_name(&params.name))
+        }
         Message::GetBlockInfo(params) => res_to_js(ws::get_block_info(
             &mgr,
             id,
@@ -469,15 +572,39 @@ pub fn handle(msg: JsValue, book_id: Option<usize>) -> JsValue {
             res_to_js(ws::get_linkable_blocks(&mgr, id, params.sheet_idx, params.col_cnt))
         }
         Message::GetLinks(params) => res_to_js(ws::get_links(&mgr, id, params.sheet_idx)),
-        Message::SaveCheckpoint(params) => ok_to_js(&ws::save_checkpoint(
-            &mut mgr,
-            id,
-            params.label,
-            params.description,
-        )),
+        Message::SaveCheckpoint(params) => ok_to_js(&ws::save_checkpoint(&mut mgr, id, params)),
         Message::DeleteCheckpoint(params) => {
             ok_to_js(&ws::delete_checkpoint(&mut mgr, id, params.label))
         }
         Message::ListCheckpoints => ok_to_js(&ws::list_checkpoints(&mgr, id)),
+        Message::SetAudit(params) => ok_to_js(&ws::set_audit(&mut mgr, id, params)),
+        Message::SetAuditAuthor(params) => ok_to_js(&ws::set_audit_author(&mut mgr, id, params)),
+        Message::GetCellHistory(params) => res_to_js(ws::get_cell_history(
+            &mgr,
+            id,
+            params.sheet_idx,
+            params.row,
+            params.col,
+        )),
+        Message::GetChangeFeed(params) => ok_to_js(&ws::get_change_feed(&mgr, id, params.since)),
+        Message::ClearAuditLog => ok_to_js(&ws::clear_audit_log(&mut mgr, id)),
+        Message::ForkBranch(params) => res_to_js(ws::fork_branch(&mut mgr, id, params.name)),
+        Message::SwitchBranch(params) => res_to_js(ws::switch_branch(&mut mgr, id, &params.name)),
+        Message::DeleteBranch(params) => res_to_js(ws::delete_branch(&mut mgr, id, &params.name)),
+        Message::ListBranches => ok_to_js(&ws::list_branches(&mgr, id)),
+        Message::CompareBranches(params) => {
+            res_to_js(ws::compare_branches(&mgr, id, &params.from, &params.to))
+        }
+        Message::MergeBranch(params) => res_to_js(ws::merge_branch(
+            &mut mgr,
+            id,
+            &params.name,
+            params.force.unwrap_or(false),
+        )),
+        Message::GetDataTables(params) => {
+            ok_to_js(&ws::get_data_tables(&mgr, id, params.sheet_idx))
+        }
+        Message::GoalSeek(params) => res_to_js(ws::goal_seek(&mut mgr, id, params)),
+        Message::GetScenarios(params) => ok_to_js(&ws::get_scenarios(&mgr, id, params.sheet_idx)),
     }
 }
@@ -25,6 +25,8 @@ pub mod prelude {
     // builds; an explicit re-export beats a glob, so this also settles the
     // ambiguity rather than silencing it.
     pub use super::ooxml::drawing_part::CtMarker;
+    // The theme's colour choices, for building and editing a `ThemePart`.
+    pub use super::ooxml::enum_groups::{CtSrgbColor, CtSystemColor, EgColorChoice};
     pub use super::ooxml::external_links::*;
     pub use super::ooxml::persons::*;
     pub use super::ooxml::pivot_cache_definition::*;
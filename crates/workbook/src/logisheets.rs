@@ -37,6 +37,141 @@ pub struct LogiSheetsData {
     /// keyed by `RenderId` alone, which is sheet-agnostic.
     #[xmlserde(name = b"fieldRender", ty = "child")]
     pub field_renders: Vec<FieldRenderXml>,
+    /// Named checkpoints the user chose to keep with the file. Each one is
+    /// stored as the cell content that differs from the saved workbook, so
+    /// a checkpoint costs only as much as it diverges.
+    #[xmlserde(name = b"checkpoint", ty = "child")]
+    pub checkpoints: Vec<CheckpointXml>,
+    /// The change log, when the workbook keeps one in the file.
+    #[xmlserde(name = b"audit", ty = "child")]
+    pub audit: Option<AuditXml>,
+    /// How typed cell input is read. Absent for the default, en-US.
+    #[xmlserde(name = b"inputLocale", ty = "child")]
+    pub input_locale: Option<InputLocaleXml>,
+}
+
+/// `dateOrder` is `mdy`, `dmy` or `ymd`; each separator is one character.
+#[derive(Debug, XmlSerialize, XmlDeserialize)]
+pub struct InputLocaleXml {
+    #[xmlserde(name = b"dateOrder", ty = "attr")]
+    pub date_order: String,
+    #[xmlserde(name = b"dateSeparator", ty = "attr")]
+    pub date_separator: String,
+    #[xmlserde(name = b"decimalSeparator", ty = "attr")]
+    pub decimal_separator: String,
+    #[xmlserde(name = b"thousandsSeparator", ty = "attr")]
+    pub thousands_separator: String,
+    #[xmlserde(name = b"currencySymbol", ty = "attr")]
+    pub currency_symbol: String,
+}
+
+#[derive(Debug, XmlSerialize, XmlDeserialize)]
+pub struct AuditXml {
+    #[xmlserde(name = b"person", ty = "child")]
+    pub persons: Vec<AuditPersonXml>,
+    #[xmlserde(name = b"entry", ty = "child")]
+    pub entries: Vec<AuditEntryXml>,
+}
+
+#[derive(Debug, XmlSerialize, XmlDeserialize)]
+pub struct AuditPersonXml {
+    #[xmlserde(name = b"guid", ty = "attr")]
+    pub guid: String,
+    #[xmlserde(name = b"displayName", ty = "attr")]
+    pub display_name: String,
+    #[xmlserde(name = b"userId", ty = "attr")]
+    pub user_id: Option<String>,
+    #[xmlserde(name = b"providerId", ty = "attr")]
+    pub provider_id: Option<String>,
+}
+
+/// One committed transaction.
+#[derive(Debug, XmlSerialize, XmlDeserialize)]
+pub struct AuditEntryXml {
+    /// The guid of a `person` of this log.
+    #[xmlserde(name = b"author", ty = "attr")]
+    pub author: Option<String>,
+    #[xmlserde(name = b"timestamp", ty = "attr")]
+    pub timestamp: Option<String>,
+    /// Space-separated names of the payloads the transaction ran.
+    #[xmlserde(name = b"payloads", ty = "attr")]
+    pub payloads: Option<String>,
+    #[xmlserde(name = b"c", ty = "child")]
+    pub changes: Vec<AuditChangeXml>,
+}
+
+/// A cell change. Values are typed by `oldType` / `newType` (`s`, `n`, `b`
+/// or `e`; absent means blank). The position is the cell's position when
+/// the file was saved, or when it changed if it has been deleted since
+/// (`live="0"`).
+#[derive(Debug, XmlSerialize, XmlDeserialize)]
+pub struct AuditChangeXml {
+    #[xmlserde(name = b"sheet", ty = "attr")]
+    pub sheet: String,
+    #[xmlserde(name = b"row", ty = "attr")]
+    pub row: usize,
+    #[xmlserde(name = b"col", ty = "attr")]
+    pub col: usize,
+    #[xmlserde(name = b"live", ty = "attr")]
+    pub live: Option<bool>,
+    #[xmlserde(name = b"oldType", ty = "attr")]
+    pub old_type: Option<String>,
+    #[xmlserde(name = b"oldValue", ty = "attr")]
+    pub old_value: Option<String>,
+    #[xmlserde(name = b"newType", ty = "attr")]
+    pub new_type: Option<String>,
+    #[xmlserde(name = b"newValue", ty = "attr")]
+    pub new_value: Option<String>,
+    #[xmlserde(name = b"oldFormula", ty = "attr")]
+    pub old_formula: Option<String>,
+    #[xmlserde(name = b"newFormula", ty = "attr")]
+    pub new_formula: Option<String>,
+    #[xmlserde(name = b"recalculated", ty = "attr")]
+    pub recalculated: Option<bool>,
+}
+
+#[derive(Debug, XmlSerialize, XmlDeserialize)]
+pub struct CheckpointXml {
+    #[xmlserde(name = b"label", ty = "attr")]
+    pub label: String,
+    #[xmlserde(name = b"description", ty = "attr")]
+    pub description: Option<String>,
+    /// Host-supplied timestamp, kept as given.
+    #[xmlserde(name = b"createdAt", ty = "attr")]
+    pub created_at: Option<String>,
+    #[xmlserde(name = b"author", ty = "attr")]
+    pub author: Option<String>,
+    /// Every sheet of the checkpoint, in order. Sheets of the saved
+    /// workbook that are not listed did not exist at the checkpoint.
+    #[xmlserde(name = b"sheet", ty = "child")]
+    pub sheets: Vec<CheckpointSheetXml>,
+}
+
+#[derive(Debug, XmlSerialize, XmlDeserialize)]
+pub struct CheckpointSheetXml {
+    /// The position of the same sheet in the saved workbook. Absent for a
+    /// sheet the saved workbook does not have.
+    #[xmlserde(name = b"savedIdx", ty = "attr")]
+    pub saved_idx: Option<usize>,
+    #[xmlserde(name = b"name", ty = "attr")]
+    pub name: String,
+    #[xmlserde(name = b"c", ty = "child")]
+    pub cells: Vec<CheckpointCellXml>,
+}
+
+/// A cell whose content at the checkpoint differs from the saved workbook.
+/// `t` is `f` (formula, without the leading `=`), `n`, `s`, `b` or `e`; a
+/// cell without `t` was blank.
+#[derive(Debug, XmlSerialize, XmlDeserialize)]
+pub struct CheckpointCellXml {
+    #[xmlserde(name = b"row", ty = "attr")]
+    pub row: usize,
+    #[xmlserde(name = b"col", ty = "attr")]
+    pub col: usize,
+    #[xmlserde(name = b"t", ty = "attr")]
+    pub t: Option<String>,
+    #[xmlserde(name = b"v", ty = "attr")]
+    pub v: Option<String>,
 }
 
 #[derive(Debug, XmlSerialize, XmlDeserialize)]
@@ -203,6 +338,38 @@ pub struct SchemaFieldXml {
     pub validation_formula: Option<String>,
     #[xmlserde(name = b"editabilityFormula", ty = "attr")]
     pub editability_formula: Option<String>,
+    /// Absent for free-form fields.
+    #[xmlserde(name = b"fieldType", ty = "child")]
+    pub field_type: Option<FieldTypeXml>,
+}
+
+/// The type of a schema field. `kind` is one of `text`, `number`,
+/// `integer`, `date`, `boolean`, `enum` and `reference`; the other
+/// attributes are the constraints that apply to it.
+#[derive(Debug, XmlSerialize, XmlDeserialize)]
+pub struct FieldTypeXml {
+    #[xmlserde(name = b"kind", ty = "attr")]
+    pub kind: String,
+    #[xmlserde(name = b"required", ty = "attr")]
+    pub required: Option<bool>,
+    #[xmlserde(name = b"min", ty = "attr")]
+    pub min: Option<f64>,
+    #[xmlserde(name = b"max", ty = "attr")]
+    pub max: Option<f64>,
+    #[xmlserde(name = b"pattern", ty = "attr")]
+    pub pattern: Option<String>,
+    #[xmlserde(name = b"maxLength", ty = "attr")]
+    pub max_length: Option<usize>,
+    #[xmlserde(name = b"refName", ty = "attr")]
+    pub ref_name: Option<String>,
+    #[xmlserde(name = b"option", ty = "child")]
+    pub options: Vec<FieldOptionXml>,
+}
+
+#[derive(Debug, XmlSerialize, XmlDeserialize)]
+pub struct FieldOptionXml {
+    #[xmlserde(name = b"val", ty = "attr")]
+    pub val: String,
 }
 
 /// A free-form schema: explicit `(key, row, col, renderId)` tuples with no
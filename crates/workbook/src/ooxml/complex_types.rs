@@ -2117,7 +2117,7 @@ pub struct CtDataRef {
 #[derive(Debug, Clone, XmlSerialize, XmlDeserialize)]
 pub struct CtScenarios {
     #[xmlserde(name = b"scenario", ty = "child")]
-    pub scearios: Vec<CtScenario>,
+    pub scenarios: Vec<CtScenario>,
     #[xmlserde(name = b"current", ty = "attr")]
     pub current: Option<u32>,
     #[xmlserde(name = b"show", ty = "attr")]
@@ -2128,7 +2128,7 @@ pub struct CtScenarios {
 
 #[derive(Debug, Clone, XmlSerialize, XmlDeserialize)]
 pub struct CtScenario {
-    #[xmlserde(name = b"CtInputCells", ty = "child")]
+    #[xmlserde(name = b"inputCells", ty = "child")]
     pub input_cells: Vec<CtInputCells>,
     #[xmlserde(name = b"name", ty = "attr")]
     pub name: String,
@@ -2494,6 +2494,8 @@ pub struct CtFormula {
     pub reference: Option<StRef>,
     #[xmlserde(name = b"dt2D", ty = "attr", default = "default_false")]
     pub dt_2d: bool,
+    #[xmlserde(name = b"dtr", ty = "attr", default = "default_false")]
+    pub dtr: bool,
     #[xmlserde(name = b"del1", ty = "attr", default = "default_false")]
     pub del1: bool,
     #[xmlserde(name = b"del2", ty = "attr", default = "default_false")]
@@ -367,10 +367,10 @@ export class DataService {
 
   public async checkFormula(formula: string): Promise<boolean> {
     const result = await this._workbook.checkFormula({ formula });
-    if (typeof result === "boolean") {
-      return result;
+    if (isErrorMessage(result)) {
+      return false;
     }
-    return false;
+    return result.valid;
   }
 
   // ========================================================================
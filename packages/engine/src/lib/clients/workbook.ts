@@ -13,6 +13,9 @@ import type {
     BlockInfo,
     Comment,
     FormulaDisplayInfo,
+    FormulaCheckResult,
+    FormulaToken,
+    FunctionSignature,
     CellCoordinate,
     SheetCellId,
     Callback,
@@ -513,8 +516,25 @@ export class WorkbookClient implements Client {
         ) as Resp<FormulaDisplayInfo>
     }
 
-    checkFormula(params: {formula: string}): Resp<boolean> {
-        return this._call(MethodName.CheckFormula, params) as Resp<boolean>
+    getFormulaTokens(params: {formula: string}): Resp<readonly FormulaToken[]> {
+        return this._call(
+            MethodName.GetFormulaTokens,
+            params
+        ) as Resp<readonly FormulaToken[]>
+    }
+
+    checkFormula(params: {formula: string}): Resp<FormulaCheckResult> {
+        return this._call(
+            MethodName.CheckFormula,
+            params
+        ) as Resp<FormulaCheckResult>
+    }
+
+    getFunctionSignature(params: {name: string}): Resp<FunctionSignature> {
+        return this._call(
+            MethodName.GetFunctionSignature,
+            params
+        ) as Resp<FunctionSignature>
     }
 
     // ========================================================================
@@ -79,6 +79,7 @@ export enum MethodName {
     ExportBlockData = 'exportBlockData',
     GetTempStatusChanges = 'getTempStatusChanges',
     CheckFormula = 'checkFormula',
+    GetFunctionSignature = 'getFunctionSignature',
     Save = 'save',
 
     CleanupTempStatus = 'cleanupTempStatus',
@@ -111,6 +112,7 @@ export enum MethodName {
     GetCellId = 'getCellId',
 
     GetDisplayUnitsOfFormula = 'getDisplayUnitsOfFormula',
+    GetFormulaTokens = 'getFormulaTokens',
 
     GetNextVisibleCell = 'getNextVisibleCell',
 
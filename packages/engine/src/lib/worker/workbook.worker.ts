@@ -20,6 +20,9 @@ import type {
     SheetInfo,
     CellCoordinate,
     FormulaDisplayInfo,
+    FormulaCheckResult,
+    FormulaToken,
+    FunctionSignature,
     ActionEffect,
     Value,
     ReproducibleCell,
@@ -553,6 +556,10 @@ export class WorkbookWorkerService implements IWorkbookWorker {
         return this.workbook.getDisplayUnitsOfFormula(f)
     }
 
+    public getFormulaTokens(params: {formula: string}): readonly FormulaToken[] {
+        return this.workbook.getFormulaTokens(params.formula)
+    }
+
     public calcCondition(params: {
         sheetIdx: number
         condition: any
@@ -588,10 +595,16 @@ export class WorkbookWorkerService implements IWorkbookWorker {
         return this.workbook.getTempStatusChanges()
     }
 
-    public checkFormula(params: {formula: string}): boolean {
+    public checkFormula(params: {formula: string}): FormulaCheckResult {
         return this.workbook.checkFormula(params.formula)
     }
 
+    public getFunctionSignature(params: {
+        name: string
+    }): Result<FunctionSignature> {
+        return this.workbook.getFunctionSignature(params.name)
+    }
+
     // ========================================================================
     // App Data Operations
     // ========================================================================
@@ -778,6 +791,9 @@ export class WorkbookWorkerService implements IWorkbookWorker {
                 case MethodName.CheckFormula:
                     result = this.checkFormula(args)
                     break
+                case MethodName.GetFunctionSignature:
+                    result = this.getFunctionSignature(args)
+                    break
                 case MethodName.GetBlockValues:
                     result = this.getBlockValues(args)
                     break
@@ -823,6 +839,9 @@ export class WorkbookWorkerService implements IWorkbookWorker {
                 case MethodName.GetDisplayUnitsOfFormula:
                     result = this.getDisplayUnitsOfFormula(args)
                     break
+                case MethodName.GetFormulaTokens:
+                    result = this.getFormulaTokens(args)
+                    break
                 case MethodName.GetNextVisibleCell:
                     result = this.getNextVisibleCell(args)
                     break
@@ -4,6 +4,9 @@
  * with i18n description KEYS resolved to English text (so consumers who don't
  * pass their own localized list get readable descriptions out-of-the-box).
  *
+ * The same file is copied into crates/controller/ast_checker, whose function
+ * registry embeds it to serve `getFunctionSignature` and `checkFormula`.
+ *
  * Monorepo dev tool: reads from ../../../resources. Run after the function set
  * or its English strings change:
  *
@@ -42,7 +45,9 @@ const out = funcs.map((f) => ({
 }))
 
 const dest = resolve(here, '../src/lib/builtin-functions.json')
-writeFileSync(dest, JSON.stringify(out, null, 2) + '\n')
+const json = JSON.stringify(out, null, 2) + '\n'
+writeFileSync(dest, json)
+writeFileSync(resolve(repoRoot, 'crates/controller/ast_checker/funcs.json'), json)
 
 console.log(`Wrote ${out.length} functions to ${dest}`)
 const stillKeys = out.filter((f) => /^functions\./.test(f.description)).length
@@ -70,6 +70,10 @@ export interface FormulaFunction {
     name: string
     /** Description of what the function does */
     description: string
+    /** Function category, e.g. "math" or "lookup" */
+    category?: string
+    /** If true, the function recalculates on every edit (RAND, TODAY, ...) */
+    volatile?: boolean
     /** Function arguments */
     args: FormulaArg[]
     /** Argument count constraints */
@@ -86,6 +90,10 @@ export interface FormulaFunction {
 export interface FormulaArg {
     argName: string
     description: string
+    /** Expected value type; omitted means any value is accepted */
+    argType?: 'number' | 'text' | 'logical' | 'reference' | 'array'
+    /** If true, only a reference like A1 or A1:B2 is accepted */
+    refOnly?: boolean
     /** If true, this argument can repeat */
     startRepeated?: boolean
 }
@@ -611,8 +611,40 @@ describe('RPC', () => {
                 'checkFormula',
                 {formula: '=1+1'},
                 bookId
-            ) as boolean
-            expect(result).toBe(true)
+            ) as {valid: boolean}
+            expect(result.valid).toBe(true)
+        })
+
+        it('should report argument count errors with positions', () => {
+            const result = rpc(
+                'checkFormula',
+                {formula: '=ABS(1, 2)'},
+                bookId
+            ) as {valid: boolean; diagnostics: {start: number; end: number}[]}
+            expect(result.valid).toBe(false)
+            expect(result.diagnostics[0].start).toBe(1)
+            expect(result.diagnostics[0].end).toBe(10)
+        })
+
+        it('should get a function signature', () => {
+            const result = rpc(
+                'getFunctionSignature',
+                {name: 'sumifs'},
+                bookId
+            ) as {name: string; args: {repeated: boolean}[]}
+            expect(result.name).toBe('SUMIFS')
+            expect(result.args[1].repeated).toBe(true)
+        })
+
+        it('should tokenize an unfinished formula', () => {
+            const result = rpc(
+                'getFormulaTokens',
+                {formula: 'SUM(A1, Sheet1!A1'},
+                bookId
+            ) as {kind: string; start: number; reference?: {color: number}}[]
+            expect(result[0].kind).toBe('function')
+            expect(result[2].kind).toBe('reference')
+            expect(result[5].reference?.color).toBe(1)
         })
 
         it('should get display units of formula', () => {
@@ -7,6 +7,9 @@ import {
     BlockSortOrder,
     GetBlockSortOrderParams,
     FormulaDisplayInfo,
+    FormulaCheckResult,
+    FormulaToken,
+    FunctionSignature,
     ShadowCellInfo,
     SheetCellId,
     SheetInfo,
@@ -23,6 +26,30 @@ import {
     TempStatusDiff,
     BlockDataRow,
     CommentMention,
+    ClipboardContents,
+    ClipboardData,
+    CopyRangeParams,
+    ReadClipboardParams,
+    PastePayloadsParams,
+    FillType,
+    FillSeriesParams,
+    CellInput,
+    PredictFlashFillParams,
+    CellStyleInfo,
+    StyleUpdateType,
+    ApplyCellStyle,
+    ThemeInfo,
+    ThemeColorSlot,
+    FindOptions,
+    FoundCell,
+    RecordFormat,
+    AuthorInput,
+    ChangeEntry,
+    BranchInfo,
+    CellChange,
+    MergeResult,
+    GoalSeekResult,
+    InputLocaleInfo,
 } from '../bindings'
 import {ColId, RowId} from '../types'
 import {Worksheet} from './worksheet'
@@ -52,6 +79,12 @@ export type ReturnCode = number
 export type Callback = () => void
 export type CellIdCallback = (cellId: SheetCellId) => void
 
+/** What a replacement did, and the matches it left alone. */
+export interface ReplaceResult {
+    effect: ActionEffect
+    skipped: readonly FoundCell[]
+}
+
 export class Workbook {
     public constructor() {
         this._id = rpc('newWorkbook') as number
@@ -325,10 +358,22 @@ export class Workbook {
         return rpc('getFormulaFunctionNames', undefined, this._id)
     }
 
-    public checkFormula(f: string): boolean {
+    /**
+     * Syntax check plus argument count / literal type checks against the
+     * built-in function registry. Diagnostic offsets index into `f`.
+     */
+    public checkFormula(f: string): FormulaCheckResult {
         return rpc('checkFormula', {formula: f}, this._id)
     }
 
+    /**
+     * Parameters, expected types, description, category and volatility of a
+     * built-in function, for autocomplete and argument tooltips.
+     */
+    public getFunctionSignature(name: string): Result<FunctionSignature> {
+        return rpc('getFunctionSignature', {name}, this._id)
+    }
+
     public calcCondition(sheetIdx: number, f: string): Result<boolean> {
         return rpc('calcCondition', {sheetIdx, condition: f}, this._id)
     }
@@ -400,6 +445,14 @@ export class Workbook {
         return rpc('getDisplayUnitsOfFormula', {formula: f}, this._id)
     }
 
+    /**
+     * Classified tokens with byte spans, for syntax highlighting. Works on
+     * unfinished formulas too. References spelled alike share a color index.
+     */
+    public getFormulaTokens(f: string): readonly FormulaToken[] {
+        return rpc('getFormulaTokens', {formula: f}, this._id)
+    }
+
     public onCellValueChanged(
         sheetIdx: number,
         rowIdx: number,
@@ -603,12 +656,14 @@ export class Workbook {
     }
 
     /**
-     * Fill-handle drag: predict the contents for `dst` from the source
-     * block `src` and commit them as a single (undoable) transaction.
+     * Fill-handle drag: predict the contents and formats for `dst` from the
+     * source block `src` and commit them as a single (undoable) transaction.
      *
-     * The semantics (formula reference shift, arithmetic series, value
-     * copy) live in the Rust engine; this just predicts then dispatches.
-     * Returns the prediction error if the ranges don't align on one axis.
+     * The semantics (formula reference shift, number, date, list and
+     * text+number series, value copy) live in the Rust engine; this just
+     * predicts then dispatches. `fillType` is the AutoFill option the user
+     * picked. Returns the prediction error if the ranges don't align on one
+     * axis.
      */
     public fill(
         sheetIdx: number,
@@ -623,17 +678,264 @@ export class Workbook {
             startCol: number
             endRow: number
             endCol: number
-        }
+        },
+        fillType: FillType = 'default'
     ): Result<ActionEffect> {
-        const inputs = this.getWorksheet(sheetIdx).predictFill(src, dst)
+        const payloads = rpc(
+            'predictFillPayloads',
+            {
+                sheetIdx,
+                srcStartRow: src.startRow,
+                srcStartCol: src.startCol,
+                srcEndRow: src.endRow,
+                srcEndCol: src.endCol,
+                dstStartRow: dst.startRow,
+                dstStartCol: dst.startCol,
+                dstEndRow: dst.endRow,
+                dstEndCol: dst.endCol,
+                fillType,
+            },
+            this._id
+        )
+        if (isErrorMessage(payloads)) return payloads
+        return this.execTransaction({
+            payloads,
+            undoable: true,
+            temp: false,
+        })
+    }
+
+    /**
+     * The Series dialog: fill the range from the first cell of each line
+     * with an explicit step and optional stop value, as one undoable
+     * transaction.
+     */
+    public fillSeries(params: FillSeriesParams): Result<ActionEffect> {
+        const inputs = rpc(
+            'fillSeries',
+            params as unknown as Record<string, unknown>,
+            this._id
+        )
         if (isErrorMessage(inputs)) return inputs
         return this.execTransaction({
-            payloads: inputs.map((value) => ({type: 'cellInput', value})),
+            payloads: inputs.map((value: CellInput) => ({
+                type: 'cellInput',
+                value,
+            })),
+            undoable: true,
+            temp: false,
+        })
+    }
+
+    /**
+     * The workbook's own fill lists, tried before the built-in weekdays,
+     * months and quarters.
+     */
+    public getCustomLists(): readonly (readonly string[])[] {
+        return rpc('getCustomLists', undefined, this._id)
+    }
+
+    public setCustomLists(lists: readonly (readonly string[])[]) {
+        rpc('setCustomLists', {lists}, this._id)
+    }
+
+    /**
+     * How typed cell input is read: the order of the parts of a date and
+     * the separators and currency symbol. Saved with the workbook.
+     */
+    public getInputLocale(): InputLocaleInfo {
+        return rpc('getInputLocale', undefined, this._id)
+    }
+
+    /** Fails if a separator is not one character or the decimal and
+     *  thousands separators are the same. */
+    public setInputLocale(locale: InputLocaleInfo): Result<void> {
+        return rpc('setInputLocale', {locale}, this._id)
+    }
+
+    /** The named cell styles ("Normal", "Good", ...), in file order. */
+    public getCellStyles(): readonly CellStyleInfo[] {
+        return rpc('getCellStyles', undefined, this._id)
+    }
+
+    /** Add a named cell style: "Normal" with `ty` applied. */
+    public createCellStyle(name: string, ty: StyleUpdateType): ActionEffect {
+        return this.execTransaction({
+            payloads: [{type: 'createCellStyle', value: {name, ty}}],
             undoable: true,
             temp: false,
         })
     }
 
+    /**
+     * Change a named cell style. Cells linked to it follow, except in what
+     * they formatted for themselves.
+     */
+    public modifyCellStyle(name: string, ty: StyleUpdateType): ActionEffect {
+        return this.execTransaction({
+            payloads: [{type: 'modifyCellStyle', value: {name, ty}}],
+            undoable: true,
+            temp: false,
+        })
+    }
+
+    /** Remove a named cell style; its cells are linked to "Normal". */
+    public deleteCellStyle(name: string): ActionEffect {
+        return this.execTransaction({
+            payloads: [{type: 'deleteCellStyle', value: {name}}],
+            undoable: true,
+            temp: false,
+        })
+    }
+
+    /** Format a block with a named cell style and link it to the style. */
+    public applyCellStyle(params: ApplyCellStyle): ActionEffect {
+        return this.execTransaction({
+            payloads: [{type: 'applyCellStyle', value: params}],
+            undoable: true,
+            temp: false,
+        })
+    }
+
+    /**
+     * The workbook theme, or null for a workbook without one, which renders
+     * with the Office theme.
+     */
+    public getTheme(): ThemeInfo | null {
+        return rpc('getTheme', undefined, this._id)
+    }
+
+    /** The built-in Office themes `applyBuiltinTheme` accepts. */
+    public getBuiltinThemes(): readonly ThemeInfo[] {
+        return rpc('getBuiltinThemes', undefined, this._id)
+    }
+
+    /** Replace the theme with a `theme1.xml` document, e.g. from a .thmx. */
+    public setTheme(xml: string): ActionEffect {
+        return this.execTransaction({
+            payloads: [{type: 'setTheme', value: {xml}}],
+            undoable: true,
+            temp: false,
+        })
+    }
+
+    public applyBuiltinTheme(name: string): ActionEffect {
+        return this.execTransaction({
+            payloads: [{type: 'applyBuiltinTheme', value: {name}}],
+            undoable: true,
+            temp: false,
+        })
+    }
+
+    /** Set one theme colour, as `RRGGBB`. */
+    public setThemeColor(slot: ThemeColorSlot, color: string): ActionEffect {
+        return this.execTransaction({
+            payloads: [{type: 'setThemeColor', value: {slot, color}}],
+            undoable: true,
+            temp: false,
+        })
+    }
+
+    /** Set the theme's heading and body fonts; omit one to keep it. */
+    public setThemeFonts(fonts: {
+        major?: string
+        minor?: string
+    }): ActionEffect {
+        return this.execTransaction({
+            payloads: [{type: 'setThemeFonts', value: fonts}],
+            undoable: true,
+            temp: false,
+        })
+    }
+
+    /**
+     * Flash Fill: infer the pattern of the example rows typed into
+     * `targetCol` and fill its blank rows in the block, as one undoable
+     * transaction. Returns an error when no pattern matches the examples.
+     */
+    public flashFill(params: PredictFlashFillParams): Result<ActionEffect> {
+        const inputs = rpc(
+            'predictFlashFill',
+            params as unknown as Record<string, unknown>,
+            this._id
+        )
+        if (isErrorMessage(inputs)) return inputs
+        return this.execTransaction({
+            payloads: inputs.map((value: CellInput) => ({
+                type: 'cellInput',
+                value,
+            })),
+            undoable: true,
+            temp: false,
+        })
+    }
+
+    /**
+     * Every cell that matches `options`, sheet by sheet in row or column
+     * order, as Excel's Find All lists them.
+     */
+    public findAll(options: FindOptions): Result<readonly FoundCell[]> {
+        return rpc('findAll', {options}, this._id)
+    }
+
+    /**
+     * The next match after `(row, col)` of sheet `sheetIdx`, wrapping around
+     * to the start of the scope; `null` when nothing matches.
+     */
+    public findNext(
+        options: FindOptions,
+        sheetIdx: number,
+        row: number,
+        col: number
+    ): Result<FoundCell | null> {
+        return rpc('findNext', {options, sheetIdx, row, col}, this._id)
+    }
+
+    /**
+     * Replace the match in `cell` as one (undoable) transaction. Fails,
+     * changing nothing, when the replacement would break a formula.
+     * `skipped` lists the matches, looking in values, that a formula
+     * computes and so are left alone.
+     */
+    public replace(
+        options: FindOptions,
+        replacement: string,
+        cell: FoundCell
+    ): Result<ReplaceResult> {
+        return this._replace(options, replacement, cell)
+    }
+
+    /**
+     * Replace every match as one (undoable) transaction. Fails, changing
+     * nothing, when the replacement would break a formula. `skipped` is as
+     * for `replace`.
+     */
+    public replaceAll(
+        options: FindOptions,
+        replacement: string
+    ): Result<ReplaceResult> {
+        return this._replace(options, replacement)
+    }
+
+    private _replace(
+        options: FindOptions,
+        replacement: string,
+        cell?: FoundCell
+    ): Result<ReplaceResult> {
+        const plan = rpc(
+            'replacePayloads',
+            {options, replacement, cell: cell ?? null},
+            this._id
+        )
+        if (isErrorMessage(plan)) return plan
+        const effect = this.execTransaction({
+            payloads: plan.payloads,
+            undoable: true,
+            temp: false,
+        })
+        return {effect, skipped: plan.skipped}
+    }
+
     /**
      * Read-only: compute the row/column order that sorts a block by one of
      * its fields. The engine compares typed cell values (numbers numerically,
@@ -680,6 +982,87 @@ export class Workbook {
         })
     }
 
+    /**
+     * Write records into a block bound with a form schema as one (undoable)
+     * transaction with a single recalculation. `data` is a JSON array of
+     * objects or CSV with a header line, keyed by field name. With `upsert`,
+     * records whose key is already in the block update that line, keeping
+     * what their blank or null fields would clear; the rest fill empty lines
+     * and then grow the block. Fails, changing nothing, when
+     * any record does not fit its fields.
+     */
+    public importBlockRecords(p: {
+        sheetIdx: number
+        blockId: number
+        format: RecordFormat
+        data: string
+        keyField?: string
+        upsert?: boolean
+    }): Result<ActionEffect> {
+        return this.execTransaction({
+            payloads: [
+                {
+                    type: 'importBlockRecords',
+                    value: {
+                        sheetIdx: p.sheetIdx,
+                        blockId: p.blockId,
+                        format: p.format,
+                        data: p.data,
+                        keyField: p.keyField,
+                        upsert: p.upsert ?? false,
+                    },
+                },
+            ],
+            undoable: true,
+            temp: false,
+        })
+    }
+
+    /**
+     * Copy (or cut) a block. Returns the clipboard payload along with its
+     * `text/plain` (TSV) and `text/html` forms: write those two to the system
+     * clipboard and keep the payload for pastes inside the workbook.
+     */
+    public copyRange(params: CopyRangeParams): Result<ClipboardContents> {
+        return rpc(
+            'copyRange',
+            params as unknown as Record<string, unknown>,
+            this._id
+        )
+    }
+
+    /**
+     * Read what another application (Excel, Google Sheets, a text editor) put
+     * on the system clipboard. The HTML is preferred, the TSV is the
+     * fallback. Formulas are anchored at the paste cell `(row, col)`.
+     */
+    public readClipboard(params: ReadClipboardParams): Result<ClipboardData> {
+        return rpc(
+            'readClipboard',
+            params as unknown as Record<string, unknown>,
+            this._id
+        )
+    }
+
+    /**
+     * Paste a clipboard payload and commit it as a single (undoable)
+     * transaction. Formula shifting, paste-special and cut-moves live in the
+     * Rust engine; this just predicts then dispatches.
+     */
+    public paste(params: PastePayloadsParams): Result<ActionEffect> {
+        const payloads = rpc(
+            'pastePayloads',
+            params as unknown as Record<string, unknown>,
+            this._id
+        )
+        if (isErrorMessage(payloads)) return payloads
+        return this.execTransaction({
+            payloads,
+            undoable: true,
+            temp: false,
+        })
+    }
+
     public getWorksheetById(id: number): Worksheet {
         return new Worksheet(this._id, id, false)
     }
@@ -754,18 +1137,30 @@ export class Workbook {
     // ---- Named checkpoints --------------------------------------------
     //
     // Save / delete / list go through dedicated RPCs (not the
-    // transaction pipeline) — they manage session-only checkpoint
-    // storage and don't touch sheet state. To restore, send a normal
-    // `restoreCheckpoint` payload via handleTransaction; that lands on
-    // the undo stack so users can Ctrl-Z to reverse the restore.
+    // transaction pipeline) — they manage checkpoint storage and don't
+    // touch sheet state. Checkpoints saved with `persist` are written
+    // into the file on save and listed again after load; restoring one
+    // read from the file brings back its cell content, sheets and sheet
+    // names, and keeps the styles of the workbook as loaded. To restore,
+    // send a normal `restoreCheckpoint` payload via handleTransaction;
+    // that lands on the undo stack so users can Ctrl-Z to reverse the
+    // restore.
 
     /** Snapshot the current workbook state under `label`. Overwrites
      *  any existing checkpoint with the same label. Returns the number
-     *  of checkpoints currently stored after this save. */
-    public saveCheckpoint(label: string, description?: string): Result<number> {
+     *  of checkpoints currently stored after this save. `createdAt` is
+     *  stored as given. With `persist`, only cell values and formulas and
+     *  the sheet list (names and order) are written into the file; styles,
+     *  merges, defined names and the rest are not, so restoring after a
+     *  reload leaves them as the workbook has them. */
+    public saveCheckpoint(
+        label: string,
+        description?: string,
+        meta?: {createdAt?: string; author?: string; persist?: boolean}
+    ): Result<number> {
         return rpc(
             'saveCheckpoint',
-            {label, description} as unknown as Record<string, unknown>,
+            {label, description, ...meta} as unknown as Record<string, unknown>,
             this._id
         )
     }
@@ -781,11 +1176,158 @@ export class Workbook {
 
     /** List all checkpoints (newest first). */
     public listCheckpoints(): Result<
-        ReadonlyArray<{label: string; description?: string}>
+        ReadonlyArray<{
+            label: string
+            description?: string
+            createdAt?: string
+            author?: string
+            persist: boolean
+        }>
     > {
         return rpc('listCheckpoints', undefined, this._id)
     }
 
+    // ---- Audit log ----------------------------------------------------
+    //
+    // Off until `setAudit(true)`. Each committed transaction is then
+    // logged with the author and timestamp last given to
+    // `setAuditAuthor`, and the old and new value and formula of every
+    // cell it changed. With `persist` the log is written into the file.
+
+    public setAudit(enabled: boolean, persist?: boolean): Result<void> {
+        return rpc(
+            'setAudit',
+            {enabled, persist} as unknown as Record<string, unknown>,
+            this._id
+        )
+    }
+
+    /** The author and timestamp the following commits are logged with.
+     *  `timestamp` is stored as given. */
+    public setAuditAuthor(author: AuthorInput, timestamp: string): Result<void> {
+        return rpc(
+            'setAuditAuthor',
+            {author, timestamp} as unknown as Record<string, unknown>,
+            this._id
+        )
+    }
+
+    /** The logged changes of one cell, oldest first. The history follows
+     *  the cell when rows or columns move it. */
+    public getCellHistory(
+        sheetIdx: number,
+        row: number,
+        col: number
+    ): Result<readonly ChangeEntry[]> {
+        return rpc(
+            'getCellHistory',
+            {sheetIdx, row, col} as unknown as Record<string, unknown>,
+            this._id
+        )
+    }
+
+    /** Every logged entry from sequence number `since` on. */
+    public getChangeFeed(since?: number): Result<readonly ChangeEntry[]> {
+        return rpc(
+            'getChangeFeed',
+            {since} as unknown as Record<string, unknown>,
+            this._id
+        )
+    }
+
+    public clearAuditLog(): Result<void> {
+        return rpc('clearAuditLog', undefined, this._id)
+    }
+
+    // ---- What-if branches ---------------------------------------------
+    //
+    // Named forks of main, `main` being main itself. Edits, undo and redo
+    // apply to the branch shown. Branches last for the session; saving
+    // writes the branch shown.
+
+    /** Fork main into a new branch. The branch shown stays shown. */
+    public forkBranch(name: string): Result<void> {
+        return rpc(
+            'forkBranch',
+            {name} as unknown as Record<string, unknown>,
+            this._id
+        )
+    }
+
+    public switchBranch(name: string): Result<void> {
+        return rpc(
+            'switchBranch',
+            {name} as unknown as Record<string, unknown>,
+            this._id
+        )
+    }
+
+    /** Drop a branch, showing main if it was shown. Returns `true` if it
+     *  existed. */
+    public deleteBranch(name: string): Result<boolean> {
+        return rpc(
+            'deleteBranch',
+            {name} as unknown as Record<string, unknown>,
+            this._id
+        )
+    }
+
+    /** `main` first, then the branches by name. */
+    public listBranches(): Result<readonly BranchInfo[]> {
+        return rpc('listBranches', undefined, this._id)
+    }
+
+    /** The cells whose value or formula differ, old being `from`. */
+    public compareBranches(
+        from: string,
+        to: string
+    ): Result<readonly CellChange[]> {
+        return rpc(
+            'compareBranches',
+            {from, to} as unknown as Record<string, unknown>,
+            this._id
+        )
+    }
+
+    /** Replay the branch's edits onto main as one undoable transaction
+     *  and drop the branch. When main changed the same cells, nothing
+     *  happens unless `force` is set; the conflicts are listed either
+     *  way. Fails, even when forced, if main has since inserted or
+     *  deleted rows or columns in a sheet the branch edits, or the branch
+     *  was changed by an edit that is not undoable. */
+    public mergeBranch(name: string, force?: boolean): Result<MergeResult> {
+        return rpc(
+            'mergeBranch',
+            {name, force} as unknown as Record<string, unknown>,
+            this._id
+        )
+    }
+
+    /** Find the value of the changing cell that makes the target formula
+     *  cell show `goal`. The answer is left in the temp status:
+     *  `commitTempStatus` keeps it and `cleanupTempStatus` takes it back. */
+    public goalSeek(
+        sheetIdx: number,
+        targetRow: number,
+        targetCol: number,
+        goal: number,
+        changingRow: number,
+        changingCol: number
+    ): Result<GoalSeekResult> {
+        return rpc(
+            'goalSeek',
+            {
+                sheetIdx,
+                targetRow,
+                targetCol,
+                goal,
+                changingRow,
+                changingCol,
+            } as unknown as Record<string, unknown>,
+            this._id
+        )
+    }
+
     private _inputAsyncResult(r: AsyncFuncResult): ActionEffect {
         return input_async_result(this._id, r) as ActionEffect
     }
@@ -19,7 +19,9 @@ import {
     CellImageInfo,
     ChartInfo,
     CfRuleInfo,
+    DataTableInfo,
     DependentCell,
+    ScenarioInfo,
     CellRefRange,
     LinkInfo,
 } from '../bindings'
@@ -453,6 +455,18 @@ export class Worksheet {
         )
     }
 
+    /** The named cell style the cell is linked to, or null. */
+    public getCellStyleName(
+        rowIdx: number,
+        colIdx: number
+    ): Result<string | null> {
+        return rpc(
+            'getCellStyleName',
+            {sheetIdx: this._sheetIdx, row: rowIdx, col: colIdx},
+            this._id
+        )
+    }
+
     public getValue(rowIdx: number, colIdx: number): Result<Value> {
         return rpc(
             'getValue',
@@ -545,6 +559,24 @@ export class Worksheet {
         )
     }
 
+    /**
+     * The data tables on this sheet. They are created and removed with the
+     * `createDataTable` and `removeDataTable` payloads; `tableId` is
+     * session-scoped, like a rule id.
+     */
+    public getDataTables(): Result<DataTableInfo[]> {
+        return rpc('getDataTables', {sheetIdx: this._sheetIdx}, this._id)
+    }
+
+    /**
+     * The scenarios on this sheet. They are edited, shown and summarized
+     * with the `addScenario`, `editScenario`, `deleteScenario`,
+     * `showScenario` and `createScenarioSummary` payloads.
+     */
+    public getScenarios(): Result<ScenarioInfo[]> {
+        return rpc('getScenarios', {sheetIdx: this._sheetIdx}, this._id)
+    }
+
     public getFullyCoveredBlocks(
         rowIdx: number,
         colIdx: number,
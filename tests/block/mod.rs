@@ -50,6 +50,7 @@ fn test_bind_block_schema() {
             field_formulas: vec![],
             validation_formulas: vec![],
             editability_formulas: vec![],
+            field_types: None,
             row: true,
         })],
         undoable: true,
@@ -95,6 +96,7 @@ fn test_form_block_rowcnt1_two_fields() {
                 field_formulas: vec![],
                 validation_formulas: vec![],
                 editability_formulas: vec![],
+                field_types: None,
                 row: true,
             }),
         ],
@@ -935,6 +937,7 @@ fn test_blockref_readers_recompute_after_reload() {
                 field_formulas: vec![None, None, Some("=#FIELD(\"a\")*2".to_string())],
                 validation_formulas: vec![],
                 editability_formulas: vec![],
+                field_types: None,
                 row: true,
             }),
             // Two readers outside the block: the single form and the aggregate.
@@ -943,12 +946,14 @@ fn test_blockref_readers_recompute_after_reload() {
                 row: 5,
                 col: 0,
                 content: "=BLOCKREF(\"t\",\"k1\",\"b\")".to_string(),
+                ..Default::default()
             }),
             EditPayload::CellInput(CellInput {
                 sheet_idx: 0,
                 row: 5,
                 col: 1,
                 content: "=SUM(BLOCKREFS(\"t\",\"*\",\"b\"))".to_string(),
+                ..Default::default()
             }),
         ],
         undoable: true,
@@ -1057,6 +1062,7 @@ fn test_save_can_resolve_block_refs_to_coordinates() {
                 field_formulas: vec![],
                 validation_formulas: vec![],
                 editability_formulas: vec![],
+                field_types: None,
                 row: true,
             }),
             EditPayload::CellInput(CellInput {
@@ -1064,12 +1070,14 @@ fn test_save_can_resolve_block_refs_to_coordinates() {
                 row: 5,
                 col: 0,
                 content: "=BLOCKREF(\"t\",\"r2\",\"v\")".to_string(),
+                ..Default::default()
             }),
             EditPayload::CellInput(CellInput {
                 sheet_idx: 0,
                 row: 5,
                 col: 1,
                 content: "=SUM(BLOCKREFS(\"t\",\"*\",\"v\"))".to_string(),
+                ..Default::default()
             }),
         ],
         undoable: true,
@@ -1163,6 +1171,7 @@ fn test_block_row_removal_dirties_readers() {
         field_formulas: vec![],
         validation_formulas: vec![],
         editability_formulas: vec![],
+        field_types: None,
         row: true,
     }));
     // Readers outside the block: one aggregate over the field, one count.
@@ -1171,12 +1180,14 @@ fn test_block_row_removal_dirties_readers() {
         row: 10,
         col: 0,
         content: "=SUM(BLOCKREFS(\"t\",\"*\",\"v\"))".to_string(),
+        ..Default::default()
     }));
     payloads.push(EditPayload::CellInput(CellInput {
         sheet_idx: 0,
         row: 10,
         col: 1,
         content: "=COUNT(BLOCKREFS(\"t\",\"*\",\"v\"))".to_string(),
+        ..Default::default()
     }));
     workbook.handle_action(logisheets::EditAction::Payloads(PayloadsAction {
         payloads,
@@ -1280,6 +1291,7 @@ fn test_save_resolves_a_block_join_to_index_match() {
                 field_formulas: vec![],
                 validation_formulas: vec![],
                 editability_formulas: vec![],
+                field_types: None,
                 row: true,
             }),
             // An ordinary cell naming the product to look up, and the join.
@@ -1288,12 +1300,14 @@ fn test_save_resolves_a_block_join_to_index_match() {
                 row: 5,
                 col: 0,
                 content: "gizmo".to_string(),
+                ..Default::default()
             }),
             EditPayload::CellInput(CellInput {
                 sheet_idx: 0,
                 row: 5,
                 col: 1,
                 content: "=BLOCKREF(\"products\",A6,\"line\")".to_string(),
+                ..Default::default()
             }),
         ],
         undoable: true,
@@ -1370,6 +1384,7 @@ fn build_pct_block(pct_rule: &str, rows: &[(&str, f64)]) -> (Workbook, StatusCod
         field_formulas: vec![None, None, Some(pct_rule.to_string())],
         validation_formulas: vec![],
         editability_formulas: vec![],
+        field_types: None,
         row: true,
     }));
     let effect = workbook.handle_action(logisheets::EditAction::Payloads(PayloadsAction {
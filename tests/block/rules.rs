@@ -121,6 +121,7 @@ fn fresh_block_with_data(
                 field_formulas: vec![None, None],
                 validation_formulas,
                 editability_formulas,
+                field_types: None,
                 row: true,
             }),
             // Value cells last — so #PLACEHOLDER references the cell
@@ -536,6 +537,7 @@ fn test_validation_field_ref_unknown_field_errors() {
                     Some(r#"#FIELD("nope")>0"#.into()),
                 ],
                 editability_formulas: vec![],
+                field_types: None,
                 row: true,
             }),
         ],
@@ -582,6 +584,7 @@ fn test_field_rule_coordinate_into_own_block_errors() {
                 field_formulas: vec![None, None, Some(r#"=C1+#FIELD("amt")"#.into())],
                 validation_formulas: vec![],
                 editability_formulas: vec![],
+                field_types: None,
                 row: true,
             }),
         ],
@@ -609,6 +612,7 @@ fn test_field_rule_coordinate_outside_block_is_fine() {
                 row: 20,
                 col: 0,
                 content: "0.25".into(),
+                ..Default::default()
             }),
             EditPayload::CreateBlock(CreateBlock {
                 sheet_idx: 0,
@@ -635,6 +639,7 @@ fn test_field_rule_coordinate_outside_block_is_fine() {
                 ],
                 validation_formulas: vec![],
                 editability_formulas: vec![],
+                field_types: None,
                 row: true,
             }),
             EditPayload::BlockInput(BlockInput {
@@ -688,30 +693,35 @@ fn test_convert_block_keeps_the_formulas_live() {
                 row: 0,
                 col: 1,
                 content: "2".into(),
+                ..Default::default()
             }),
             EditPayload::CellInput(CellInput {
                 sheet_idx: 0,
                 row: 1,
                 col: 0,
                 content: "10".into(),
+                ..Default::default()
             }),
             EditPayload::CellInput(CellInput {
                 sheet_idx: 0,
                 row: 1,
                 col: 2,
                 content: "=A2*$B$1".into(),
+                ..Default::default()
             }),
             EditPayload::CellInput(CellInput {
                 sheet_idx: 0,
                 row: 2,
                 col: 0,
                 content: "20".into(),
+                ..Default::default()
             }),
             EditPayload::CellInput(CellInput {
                 sheet_idx: 0,
                 row: 2,
                 col: 2,
                 content: "=A3*$B$1".into(),
+                ..Default::default()
             }),
         ],
         undoable: true,
@@ -759,6 +769,7 @@ fn test_convert_block_keeps_the_formulas_live() {
             row: 0,
             col: 1,
             content: "3".into(),
+            ..Default::default()
         })],
         undoable: true,
         init: false,
@@ -45,12 +45,14 @@ fn test_transaction() {
                 row: 0,
                 col: 0,
                 content: "10".to_string(),
+                ..Default::default()
             })
             .add_payload(CellInput {
                 sheet_idx: 0,
                 row: 0,
                 col: 1,
                 content: "20".to_string(),
+                ..Default::default()
             }),
     ));
     assert_eq!(result.value_changed.len(), 5);
@@ -0,0 +1,99 @@
+//! Excel-compatibility ratchet.
+//!
+//! Every `.xlsx` under `tests/` is recalculated and compared against the
+//! values Excel cached in it (see `check_excel_compat`). Drop a workbook
+//! saved by Excel anywhere under `tests/` and its formulas are checked.
+//!
+//! Mismatches that predate this guard are grandfathered in
+//! `tests/excel_compat_baseline.txt`, one `file sheet!cell` per line. A new
+//! mismatch fails the test, and so does a baseline entry that now matches
+//! (delete it), so the baseline can only shrink.
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use logisheets::{CompatOptions, check_excel_compat};
+
+const BASELINE: &str = "tests/excel_compat_baseline.txt";
+
+fn repo_root() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+}
+
+fn xlsx_files(dir: &Path, out: &mut Vec<PathBuf>) {
+    for e in fs::read_dir(dir).unwrap() {
+        let p = e.unwrap().path();
+        if p.is_dir() {
+            xlsx_files(&p, out);
+        } else if p.extension().and_then(|x| x.to_str()) == Some("xlsx") {
+            out.push(p);
+        }
+    }
+}
+
+fn col_name(col: usize) -> String {
+    let mut col = col + 1;
+    let mut letters = Vec::new();
+    while col > 0 {
+        letters.push((b'A' + ((col - 1) % 26) as u8) as char);
+        col = (col - 1) / 26;
+    }
+    letters.iter().rev().collect()
+}
+
+#[test]
+fn cached_excel_values_match_recalculation() {
+    let root = repo_root();
+    let mut files = Vec::new();
+    xlsx_files(&root.join("tests"), &mut files);
+    files.sort();
+
+    let mut mismatches = BTreeSet::new();
+    let mut details = Vec::new();
+    for file in files {
+        let name = file.strip_prefix(root.join("tests")).unwrap();
+        let name = name.to_str().unwrap().replace('\\', "/");
+        let buf = fs::read(&file).unwrap();
+        let report = check_excel_compat(&buf, &CompatOptions::default())
+            .unwrap_or_else(|e| panic!("{}: {}", name, e));
+        for m in report.mismatches {
+            let key = format!("{} {}!{}{}", name, m.sheet, col_name(m.col), m.row + 1);
+            details.push(format!(
+                "{}: ={} excel {:?}, logisheets {:?}",
+                key, m.formula, m.excel, m.logisheets
+            ));
+            mismatches.insert(key);
+        }
+    }
+
+    let baseline: BTreeSet<String> = fs::read_to_string(root.join(BASELINE))
+        .unwrap()
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .map(String::from)
+        .collect();
+    let new: Vec<String> = details
+        .into_iter()
+        .filter(|d| !baseline.contains(d.split(':').next().unwrap()))
+        .collect();
+    let fixed: Vec<String> = baseline.difference(&mismatches).cloned().collect();
+
+    let mut msg = String::new();
+    if !new.is_empty() {
+        msg += &format!(
+            "\n{} cell(s) disagree with the value Excel cached:\n  {}\n",
+            new.len(),
+            new.join("\n  ")
+        );
+    }
+    if !fixed.is_empty() {
+        msg += &format!(
+            "\n{} cell(s) now match Excel but are still listed in {BASELINE} — delete these lines so the baseline keeps shrinking:\n  {}\n",
+            fixed.len(),
+            fixed.join("\n  ")
+        );
+    }
+    assert!(msg.is_empty(), "{msg}");
+}
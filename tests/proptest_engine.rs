@@ -143,6 +143,7 @@ fn eval_in_engine(formula: &str) -> Option<f64> {
             row: 0,
             col: 0,
             content: format!("={}", formula),
+            ..Default::default()
         },
     )));
     match wb.get_sheet_by_idx(0).ok()?.get_value(0, 0).ok()? {
@@ -194,6 +195,7 @@ proptest! {
                 row: 0,
                 col: 0,
                 content: format!("={}", s),
+                ..Default::default()
             }),
         ));
         // Reading the result must also not panic.
@@ -44,6 +44,25 @@ mod operators {
     }
 }
 
+// Editing scenarios beyond formulas: styles, merges, comments, sheets,
+// visibility, conditional formatting, undo/redo and the xlsx roundtrip.
+#[cfg(test)]
+mod scenarios {
+    use glob::glob;
+
+    use crate::test_script;
+
+    #[test]
+    fn test_scenarios() {
+        let scripts = glob("tests/scenarios/*.script").expect("");
+        scripts.into_iter().for_each(|p| {
+            let path = p.unwrap();
+            let path = path.to_str().unwrap();
+            test_script(path)
+        });
+    }
+}
+
 #[cfg(test)]
 mod funcs {
 
@@ -83,18 +102,21 @@ mod funcs {
                     row: 0,
                     col: 0,
                     content: "1".into(),
+                    ..Default::default()
                 })
                 .add_payload(CellInput {
                     sheet_idx: 0,
                     row: 1,
                     col: 0,
                     content: "2".into(),
+                    ..Default::default()
                 })
                 .add_payload(CellInput {
                     sheet_idx: 0,
                     row: 0,
                     col: 2,
                     content: "=SUM(A1:A2)".into(),
+                    ..Default::default()
                 }),
         ));
         let v = wb.get_sheet_by_idx(0).unwrap().get_value(0, 2).unwrap();
@@ -113,6 +135,7 @@ mod funcs {
                 row: 0,
                 col: 0,
                 content: "10".into(),
+                ..Default::default()
             },
         )));
         let v = reopened
@@ -163,12 +186,14 @@ mod funcs {
                 row: mr,
                 col: 0,
                 content: "k".to_string(),
+                ..Default::default()
             })
             .add_payload(CellInput {
                 sheet_idx: 1,
                 row: mr,
                 col: 1,
                 content: seed.to_string(),
+                ..Default::default()
             })
             .add_payload(BindFormSchema {
                 ref_name: refname.to_string(),
@@ -181,6 +206,7 @@ mod funcs {
                 field_formulas: vec![],
                 validation_formulas: vec![],
                 editability_formulas: vec![],
+                field_types: None,
                 row: true,
             })
         };
@@ -193,48 +219,56 @@ mod funcs {
                     row: 0,
                     col: 0,
                     content: "100".into(),
+                    ..Default::default()
                 })
                 .add_payload(CellInput {
                     sheet_idx: 0,
                     row: 1,
                     col: 0,
                     content: "4.9".into(),
+                    ..Default::default()
                 })
                 .add_payload(CellInput {
                     sheet_idx: 0,
                     row: 2,
                     col: 0,
                     content: "30".into(),
+                    ..Default::default()
                 })
                 .add_payload(CellInput {
                     sheet_idx: 0,
                     row: 0,
                     col: 4,
                     content: "=A1*10000".into(),
+                    ..Default::default()
                 })
                 .add_payload(CellInput {
                     sheet_idx: 0,
                     row: 1,
                     col: 4,
                     content: "=A2/100/12".into(),
+                    ..Default::default()
                 })
                 .add_payload(CellInput {
                     sheet_idx: 0,
                     row: 2,
                     col: 4,
                     content: "=A3*12".into(),
+                    ..Default::default()
                 })
                 .add_payload(CellInput {
                     sheet_idx: 0,
                     row: 3,
                     col: 4,
                     content: "=(1+E2)^E3".into(),
+                    ..Default::default()
                 })
                 .add_payload(CellInput {
                     sheet_idx: 0,
                     row: 0,
                     col: 2,
                     content: "=ROUND(E1*E2*E4/(E4-1),2)".into(),
+                    ..Default::default()
                 }),
         ));
         // Phase 2 (buildBlockPlan): three input blocks, then rewrite A1/A2/A3 to
@@ -252,18 +286,21 @@ mod funcs {
                 row: 0,
                 col: 0,
                 content: r#"=BLOCKREF("loan", "k", "v")"#.into(),
+                ..Default::default()
             })
             .add_payload(CellInput {
                 sheet_idx: 0,
                 row: 1,
                 col: 0,
                 content: r#"=BLOCKREF("rate", "k", "v")"#.into(),
+                ..Default::default()
             })
             .add_payload(CellInput {
                 sheet_idx: 0,
                 row: 2,
                 col: 0,
                 content: r#"=BLOCKREF("years", "k", "v")"#.into(),
+                ..Default::default()
             })
             // Output MIRROR cells on the hidden sheet (buildBlockPlan layout):
             // reactive `=<sellerSheet>!<outCell>` at cols 100/164/228.
@@ -272,18 +309,21 @@ mod funcs {
                 row: 0,
                 col: 100,
                 content: "=Sheet1!C1".into(),
+                ..Default::default()
             })
             .add_payload(CellInput {
                 sheet_idx: 1,
                 row: 0,
                 col: 164,
                 content: "=Sheet1!C2".into(),
+                ..Default::default()
             })
             .add_payload(CellInput {
                 sheet_idx: 1,
                 row: 0,
                 col: 228,
                 content: "=Sheet1!C3".into(),
+                ..Default::default()
             });
         wb.handle_action(EditAction::Payloads(action));
 
@@ -361,6 +401,7 @@ mod funcs {
                     field_formulas: vec![],
                     validation_formulas: vec![],
                     editability_formulas: vec![],
+                    field_types: None,
                     row: true,
                 })
                 .add_payload(CellInput {
@@ -368,12 +409,14 @@ mod funcs {
                     row: 10,
                     col: 10,
                     content: String::from(r#"=BLOCKREF("test_ref", "key2", "field2")"#),
+                    ..Default::default()
                 })
                 .add_payload(CellInput {
                     sheet_idx: 0,
                     row: 11,
                     col: 11,
                     content: String::from(r#"=SUM(BLOCKREFS("test_ref", "key*", "field2"))"#),
+                    ..Default::default()
                 }),
         ));
         let v = wb.get_sheet_by_idx(0).unwrap().get_value(10, 10).unwrap();
@@ -409,6 +452,7 @@ mod funcs {
                     field_formulas: vec![],
                     validation_formulas: vec![],
                     editability_formulas: vec![],
+                    field_types: None,
                     row: true,
                 })
                 .add_payload(CellInput {
@@ -416,12 +460,14 @@ mod funcs {
                     row: 10,
                     col: 10,
                     content: String::from(r#"=BLOCKREF("test_ref", "key2", "field2")"#),
+                    ..Default::default()
                 })
                 .add_payload(CellInput {
                     sheet_idx: 0,
                     row: 11,
                     col: 11,
                     content: String::from(r#"=SUM(BLOCKREFS("test_ref", "key*", "field2"))"#),
+                    ..Default::default()
                 }),
         ));
 
@@ -438,6 +484,7 @@ mod funcs {
                 row: 1,
                 col: 2,
                 content: String::from("100"),
+                ..Default::default()
             },
         )));
 
@@ -507,6 +554,7 @@ mod funcs {
                     field_formulas: vec![],
                     validation_formulas: vec![],
                     editability_formulas: vec![],
+                    field_types: None,
                     row: true,
                 })
                 .add_payload(CellInput {
@@ -514,36 +562,42 @@ mod funcs {
                     row: 0,
                     col: 0,
                     content: "alice".into(),
+                    ..Default::default()
                 })
                 .add_payload(CellInput {
                     sheet_idx: 0,
                     row: 0,
                     col: 1,
                     content: "30".into(),
+                    ..Default::default()
                 })
                 .add_payload(CellInput {
                     sheet_idx: 0,
                     row: 1,
                     col: 0,
                     content: "bob".into(),
+                    ..Default::default()
                 })
                 .add_payload(CellInput {
                     sheet_idx: 0,
                     row: 1,
                     col: 1,
                     content: "40".into(),
+                    ..Default::default()
                 })
                 .add_payload(CellInput {
                     sheet_idx: 0,
                     row: 10,
                     col: 5,
                     content: r#"=SUM(BLOCKREFS("people", "*", "age"))"#.into(),
+                    ..Default::default()
                 })
                 .add_payload(CellInput {
                     sheet_idx: 0,
                     row: 10,
                     col: 6,
                     content: r#"=BLOCKREF("people", "alice", "age")"#.into(),
+                    ..Default::default()
                 }),
         ));
 
@@ -568,6 +622,7 @@ mod funcs {
                 row: 0,
                 col: 1,
                 content: "31".into(),
+                ..Default::default()
             },
         )));
 
@@ -609,24 +664,28 @@ mod funcs {
                     row: 0,
                     col: 0,
                     content: "alice".to_string(),
+                    ..Default::default()
                 })
                 .add_payload(CellInput {
                     sheet_idx: 0,
                     row: 0,
                     col: 1,
                     content: "30".to_string(),
+                    ..Default::default()
                 })
                 .add_payload(CellInput {
                     sheet_idx: 0,
                     row: 1,
                     col: 0,
                     content: "bob".to_string(),
+                    ..Default::default()
                 })
                 .add_payload(CellInput {
                     sheet_idx: 0,
                     row: 1,
                     col: 1,
                     content: "40".to_string(),
+                    ..Default::default()
                 })
                 .add_payload(BindFormSchema {
                     ref_name: "people".to_string(),
@@ -639,6 +698,7 @@ mod funcs {
                     field_formulas: vec![],
                     validation_formulas: vec![],
                     editability_formulas: vec![],
+                    field_types: None,
                     row: true,
                 })
                 .add_payload(CellInput {
@@ -646,6 +706,7 @@ mod funcs {
                     row: 10,
                     col: 5,
                     content: r#"=BLOCKREF("people", "alice", "age")"#.to_string(),
+                    ..Default::default()
                 }),
         ));
 
@@ -663,6 +724,7 @@ mod funcs {
                 row: 0,
                 col: 1,
                 content: "31".to_string(),
+                ..Default::default()
             },
         )));
 
@@ -700,24 +762,28 @@ mod funcs {
                     row: 0,
                     col: 0,
                     content: "alice".into(),
+                    ..Default::default()
                 })
                 .add_payload(CellInput {
                     sheet_idx: 0,
                     row: 0,
                     col: 1,
                     content: "30".into(),
+                    ..Default::default()
                 })
                 .add_payload(CellInput {
                     sheet_idx: 0,
                     row: 1,
                     col: 0,
                     content: "bob".into(),
+                    ..Default::default()
                 })
                 .add_payload(CellInput {
                     sheet_idx: 0,
                     row: 1,
                     col: 1,
                     content: "40".into(),
+                    ..Default::default()
                 })
                 .add_payload(BindFormSchema {
                     ref_name: "people".into(),
@@ -730,6 +796,7 @@ mod funcs {
                     field_formulas: vec![],
                     validation_formulas: vec![],
                     editability_formulas: vec![],
+                    field_types: None,
                     row: true,
                 })
                 // Two formulas referencing the block BY NAME "people".
@@ -738,12 +805,14 @@ mod funcs {
                     row: 10,
                     col: 5,
                     content: r#"=BLOCKREF("people", "alice", "age")"#.into(),
+                    ..Default::default()
                 })
                 .add_payload(CellInput {
                     sheet_idx: 0,
                     row: 10,
                     col: 6,
                     content: r#"=SUM(BLOCKREFS("people", "*", "age"))"#.into(),
+                    ..Default::default()
                 }),
         ));
 
@@ -773,6 +842,7 @@ mod funcs {
                 field_formulas: vec![],
                 validation_formulas: vec![],
                 editability_formulas: vec![],
+                field_types: None,
                 row: true,
             },
         )));
@@ -795,6 +865,7 @@ mod funcs {
                 row: 0,
                 col: 1,
                 content: "31".into(),
+                ..Default::default()
             },
         )));
         assert!(
@@ -813,6 +884,7 @@ mod funcs {
                 row: 10,
                 col: 7,
                 content: r#"=BLOCKREF("humans", "alice", "age")"#.into(),
+                ..Default::default()
             },
         )));
         assert!(
@@ -847,6 +919,7 @@ mod funcs {
                     row: 0,
                     col: 1,
                     content: "30".to_string(),
+                    ..Default::default()
                 })
                 .add_payload(BindFormSchema {
                     ref_name: "price".to_string(),
@@ -859,6 +932,7 @@ mod funcs {
                     field_formulas: vec![],
                     validation_formulas: vec![],
                     editability_formulas: vec![],
+                    field_types: None,
                     row: true,
                 })
                 .add_payload(CellInput {
@@ -866,6 +940,7 @@ mod funcs {
                     row: 5,
                     col: 5,
                     content: r#"=BLOCKREF("price", "", "v")"#.to_string(),
+                    ..Default::default()
                 }),
         ));
 
@@ -884,6 +959,7 @@ mod funcs {
                 row: 0,
                 col: 1,
                 content: "42".to_string(),
+                ..Default::default()
             },
         )));
         let v = wb.get_sheet_by_idx(0).unwrap().get_value(5, 5).unwrap();
@@ -919,12 +995,14 @@ mod funcs {
                     row: 0,
                     col: 1,
                     content: "10".to_string(),
+                    ..Default::default()
                 })
                 .add_payload(CellInput {
                     sheet_idx: 0,
                     row: 1,
                     col: 1,
                     content: "20".to_string(),
+                    ..Default::default()
                 })
                 .add_payload(BindFormSchema {
                     ref_name: "ambig".to_string(),
@@ -937,6 +1015,7 @@ mod funcs {
                     field_formulas: vec![],
                     validation_formulas: vec![],
                     editability_formulas: vec![],
+                    field_types: None,
                     row: true,
                 })
                 .add_payload(CellInput {
@@ -944,6 +1023,7 @@ mod funcs {
                     row: 5,
                     col: 5,
                     content: r#"=BLOCKREF("ambig", "", "v")"#.to_string(),
+                    ..Default::default()
                 }),
         ));
 
@@ -974,6 +1054,7 @@ mod funcs {
                     field_formulas: vec![],
                     validation_formulas: vec![],
                     editability_formulas: vec![],
+                    field_types: None,
                     row: true,
                 })
                 .add_payload(CellInput {
@@ -981,6 +1062,7 @@ mod funcs {
                     row: 10,
                     col: 10,
                     content: String::from(r#"=BLOCKREF("test_ref", "key2", "field2")"#),
+                    ..Default::default()
                 }),
         ));
 
@@ -998,6 +1080,7 @@ mod funcs {
                 field_formulas: vec![],
                 validation_formulas: vec![],
                 editability_formulas: vec![],
+                field_types: None,
                 row: true,
             },
         )));
@@ -1052,12 +1135,14 @@ mod funcs {
                     row: 0,
                     col: 0,
                     content: "1".into(),
+                    ..Default::default()
                 })
                 .add_payload(CellInput {
                     sheet_idx: 0,
                     row: 0,
                     col: 1,
                     content: "111".into(),
+                    ..Default::default()
                 })
                 .add_payload(BindFormSchema {
                     ref_name: "L1".to_string(),
@@ -1070,6 +1155,7 @@ mod funcs {
                     field_formulas: vec![],
                     validation_formulas: vec![],
                     editability_formulas: vec![],
+                    field_types: None,
                     row: true,
                 })
                 // L2 block at A3:B3 (one row, key "1").
@@ -1088,12 +1174,14 @@ mod funcs {
                     row: 2,
                     col: 0,
                     content: "1".into(),
+                    ..Default::default()
                 })
                 .add_payload(CellInput {
                     sheet_idx: 0,
                     row: 2,
                     col: 1,
                     content: "222".into(),
+                    ..Default::default()
                 })
                 .add_payload(BindFormSchema {
                     ref_name: "L2".to_string(),
@@ -1106,6 +1194,7 @@ mod funcs {
                     field_formulas: vec![],
                     validation_formulas: vec![],
                     editability_formulas: vec![],
+                    field_types: None,
                     row: true,
                 })
                 // PL block at A5:C6 (two rows, keys "一"/"二").
@@ -1125,12 +1214,14 @@ mod funcs {
                     row: 4,
                     col: 0,
                     content: "一".into(),
+                    ..Default::default()
                 })
                 .add_payload(CellInput {
                     sheet_idx: 0,
                     row: 5,
                     col: 0,
                     content: "二".into(),
+                    ..Default::default()
                 })
                 .add_payload(BindFormSchema {
                     ref_name: "PL".to_string(),
@@ -1154,6 +1245,7 @@ mod funcs {
                     ],
                     validation_formulas: vec![],
                     editability_formulas: vec![],
+                    field_types: None,
                     row: true,
                 })
                 // Seed LEVEL = "1" for both PL rows AFTER bind, as the
@@ -1163,12 +1255,14 @@ mod funcs {
                     row: 4,
                     col: 1,
                     content: "1".into(),
+                    ..Default::default()
                 })
                 .add_payload(CellInput {
                     sheet_idx: 0,
                     row: 5,
                     col: 1,
                     content: "1".into(),
+                    ..Default::default()
                 }),
         ));
 
@@ -1229,12 +1323,14 @@ mod funcs {
                     row: 0,
                     col: 0,
                     content: "a1".into(),
+                    ..Default::default()
                 })
                 .add_payload(CellInput {
                     sheet_idx: 0,
                     row: 1,
                     col: 0,
                     content: "a2".into(),
+                    ..Default::default()
                 })
                 .add_payload(CreateBlock {
                     sheet_idx: 0,
@@ -1251,12 +1347,14 @@ mod funcs {
                     row: 3,
                     col: 0,
                     content: "a1".into(),
+                    ..Default::default()
                 })
                 .add_payload(CellInput {
                     sheet_idx: 0,
                     row: 4,
                     col: 0,
                     content: "a2".into(),
+                    ..Default::default()
                 })
                 // PHASE 1: bind both schemas with NO formulas — just
                 // register refNames + field sets so later parses can
@@ -1272,6 +1370,7 @@ mod funcs {
                     field_formulas: vec![None, None],
                     validation_formulas: vec![],
                     editability_formulas: vec![],
+                    field_types: None,
                     row: true,
                 })
                 .add_payload(BindFormSchema {
@@ -1285,6 +1384,7 @@ mod funcs {
                     field_formulas: vec![None, None],
                     validation_formulas: vec![],
                     editability_formulas: vec![],
+                    field_types: None,
                     row: true,
                 })
                 // Seed B's v column with literal numbers so A's SUM
@@ -1294,12 +1394,14 @@ mod funcs {
                     row: 3,
                     col: 1,
                     content: "10".into(),
+                    ..Default::default()
                 })
                 .add_payload(CellInput {
                     sheet_idx: 0,
                     row: 4,
                     col: 1,
                     content: "20".into(),
+                    ..Default::default()
                 })
                 // PHASE 2: install A's forward-reference template. By
                 // now B's refName + field set are registered, so the